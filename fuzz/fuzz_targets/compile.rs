@@ -0,0 +1,18 @@
+#![no_main]
+use rhai::Engine;
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let mut engine = Engine::new();
+    engine.set_max_expr_depths(50, 5);
+    engine.set_max_identifier_len(64);
+    engine.set_max_string_literal_len(1000);
+    engine.set_max_interpolation_depth(8);
+    engine.set_max_tokens(10_000);
+    engine.set_max_line_len(1000);
+    let engine = engine;
+
+    // Tokenizing and parsing arbitrary input must never panic.
+    _ = engine.compile(String::from_utf8_lossy(data));
+});
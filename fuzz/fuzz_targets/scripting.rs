@@ -20,6 +20,11 @@ fuzz_target!(|ctx: Ctx| {
     engine.set_max_modules(1000);
     engine.set_max_call_levels(10);
     engine.set_max_expr_depths(50, 5);
+    engine.set_max_identifier_len(64);
+    engine.set_max_string_literal_len(1000);
+    engine.set_max_interpolation_depth(8);
+    engine.set_max_tokens(10_000);
+    engine.set_max_line_len(1000);
     engine.set_optimization_level(ctx.optimization_level);
     let start = Instant::now();
     engine.on_progress(move |_| {
@@ -0,0 +1,677 @@
+//! Module implementing the analysis pass that finds possible mistakes in a script.
+
+use crate::ast::{ASTFlags, ASTNode, BinaryExpr, Expr, FnCallExpr, Stmt, StmtBlockContainer};
+use crate::engine::{
+    FN_DISPATCH, FN_SCOPE_VAR, FN_SCOPE_VAR_TYPE, KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR,
+    KEYWORD_FN_PTR_CALL, KEYWORD_FN_PTR_CURRY, KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_TYPE_OF,
+};
+use crate::module::FuncInfo;
+use crate::tokenizer::{is_valid_identifier, Token};
+use crate::{
+    Engine, ImmutableString, Module, ParseError, ParseWarning, ParseWarningKind, Position, PERR,
+};
+use std::collections::BTreeMap;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_function"))]
+use crate::{ast::ScriptFnDef, func::StraightHashMap, FnArgsVec, Shared};
+
+/// A variable declared in the current scope.
+#[derive(Debug, Clone)]
+struct Declared {
+    /// Name of the variable.
+    name: ImmutableString,
+    /// Position of the declaration.
+    pos: Position,
+    /// Kind of warning if the variable is not used.
+    kind: ParseWarningKind,
+    /// Is the variable a constant?
+    is_const: bool,
+    /// Has the variable been read?
+    used: bool,
+}
+
+/// State of the analysis of a script or a function body.
+#[derive(Debug)]
+struct Analyzer<'a> {
+    /// Warnings found.
+    warnings: &'a mut Vec<ParseWarning>,
+    /// Stack of variables declared, simulating the runtime scope.
+    stack: Vec<Declared>,
+    /// Name of the function being analyzed, if any.
+    fn_name: Option<ImmutableString>,
+}
+
+impl Analyzer<'_> {
+    /// Declare a variable.
+    fn declare(&mut self, name: &ImmutableString, pos: Position, kind: ParseWarningKind) {
+        self.stack.push(Declared {
+            name: name.clone(),
+            pos,
+            kind,
+            is_const: false,
+            // Variables starting with an underscore are meant to be unused
+            used: name.is_empty() || name.starts_with('_'),
+        });
+    }
+    /// Mark the nearest variable with a particular name as read.
+    fn read(&mut self, name: &str) {
+        if let Some(var) = self.stack.iter_mut().rev().find(|v| v.name == name) {
+            var.used = true;
+        }
+    }
+    /// Mark all variables in scope as read.
+    ///
+    /// This is used when variables may be accessed in ways that cannot be tracked,
+    /// e.g. via `eval` or by a function call that captures the parent scope.
+    fn read_all(&mut self) {
+        self.stack.iter_mut().for_each(|v| v.used = true);
+    }
+    /// Remove all variables declared after a certain point, reporting those that are not used.
+    fn rewind(&mut self, len: usize) {
+        for var in self.stack.drain(len..).filter(|v| !v.used) {
+            let message = match (var.kind, &self.fn_name) {
+                (ParseWarningKind::UnusedParameter, Some(fn_name)) => {
+                    format!(
+                        "Parameter '{}' of function '{fn_name}' is never used",
+                        var.name
+                    )
+                }
+                _ if var.is_const => format!("Constant '{}' is never used", var.name),
+                _ => format!("Variable '{}' is never used", var.name),
+            };
+            self.warnings
+                .push(ParseWarning::new(var.kind, var.pos, message));
+        }
+    }
+
+    /// Analyze a block of statements in a new scope.
+    fn block(&mut self, statements: &[Stmt]) {
+        let orig_len = self.stack.len();
+        self.statements(statements);
+        self.rewind(orig_len);
+    }
+    /// Analyze a list of statements in the current scope.
+    fn statements(&mut self, statements: &[Stmt]) {
+        let mut exit = None;
+        let mut reported = false;
+
+        for stmt in statements {
+            // Only report the first unreachable statement
+            if let Some(keyword) = exit.filter(|_| !reported && !stmt.is_noop()) {
+                let pos = match stmt {
+                    // The position of an assignment is the operator
+                    Stmt::Assignment(x) => x.1.lhs.start_position(),
+                    _ => stmt.position(),
+                };
+                self.warnings.push(ParseWarning::new(
+                    ParseWarningKind::UnreachableCode,
+                    pos,
+                    format!("Unreachable code after '{keyword}'"),
+                ));
+                reported = true;
+            }
+
+            self.stmt(stmt);
+
+            if exit.is_none() {
+                let is_break = stmt.options().contains(ASTFlags::BREAK);
+
+                exit = match stmt {
+                    Stmt::Return(..) if is_break => Some(Token::Throw.literal_syntax()),
+                    Stmt::Return(..) => Some(Token::Return.literal_syntax()),
+                    Stmt::BreakLoop(..) if is_break => Some(Token::Break.literal_syntax()),
+                    Stmt::BreakLoop(..) => Some(Token::Continue.literal_syntax()),
+                    _ => None,
+                };
+            }
+        }
+    }
+    /// Check whether a condition is a constant.
+    fn condition(&mut self, expr: &Expr) {
+        if !is_constant(expr) {
+            return;
+        }
+
+        let message = match expr {
+            Expr::BoolConstant(value, ..) => format!("Condition is always {value}"),
+            _ => "Condition is a constant expression".to_string(),
+        };
+
+        self.warnings.push(ParseWarning::new(
+            ParseWarningKind::ConstantCondition,
+            expr.start_position(),
+            message,
+        ));
+    }
+    /// Analyze a statement.
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Noop(..) => (),
+
+            Stmt::If(x, ..) => {
+                self.condition(&x.expr);
+                self.expr(&x.expr);
+                self.block(x.body.statements());
+                self.block(x.branch.statements());
+            }
+            Stmt::Switch(x, ..) => {
+                self.expr(&x.0);
+                x.1.expressions.iter().for_each(|case| {
+                    self.expr(&case.condition);
+                    self.expr(&case.expr);
+                });
+            }
+            // loop { ... }
+            Stmt::While(x, ..) if x.expr.is_unit() => self.block(x.body.statements()),
+            Stmt::While(x, ..) => {
+                self.condition(&x.expr);
+                self.expr(&x.expr);
+                self.block(x.body.statements());
+            }
+            Stmt::Do(x, ..) => {
+                self.block(x.body.statements());
+                self.condition(&x.expr);
+                self.expr(&x.expr);
+            }
+            Stmt::For(x, ..) => {
+                let (var, var2, var3, x) = &**x;
+
+                self.expr(&x.expr);
+
+                let orig_len = self.stack.len();
+                self.declare(&var.name, var.pos, ParseWarningKind::UnusedVariable);
+                for var in var2.iter().chain(var3) {
+                    self.declare(&var.name, var.pos, ParseWarningKind::UnusedVariable);
+                }
+                self.block(x.body.statements());
+                self.rewind(orig_len);
+            }
+            Stmt::Var(x, options, ..) => {
+                let (var, expr, ..) = &**x;
+
+                self.expr(expr);
+                self.declare(&var.name, var.pos, ParseWarningKind::UnusedVariable);
+
+                let declared = self.stack.last_mut().unwrap();
+                declared.is_const = options.contains(ASTFlags::CONSTANT);
+                // Exported variables are used by the module
+                if options.contains(ASTFlags::EXPORTED) {
+                    declared.used = true;
+                }
+            }
+            Stmt::Assignment(x) => {
+                let (op_info, BinaryExpr { lhs, rhs }) = &**x;
+
+                self.expr(rhs);
+
+                // Simple assignment to a variable does not read it
+                match lhs {
+                    Expr::Variable(..) if !op_info.is_op_assignment() => (),
+                    _ => self.expr(lhs),
+                }
+            }
+            Stmt::FnCall(x, ..) => self.fn_call(x),
+            Stmt::Block(x) => self.block(x.statements()),
+            Stmt::TryCatch(x, ..) => {
+                self.block(x.try_block.statements());
+
+                let orig_len = self.stack.len();
+                if let Expr::Variable(ref v, .., pos) = x.catch_var {
+                    self.declare(&v.3, pos, ParseWarningKind::UnusedVariable);
+                    // The error variable does not need to be used
+                    self.stack.last_mut().unwrap().used = true;
+                }
+                self.expr(&x.guard);
+                self.block(x.catch_block.statements());
+                self.rewind(orig_len);
+            }
+            Stmt::Expr(expr) => self.expr(expr),
+            Stmt::BreakLoop(expr, ..) | Stmt::Return(expr, ..) => {
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, ..) => self.expr(&x.0),
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(x, ..) => self.read(&x.0.name),
+
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(..) => (),
+        }
+    }
+    /// Analyze a function call.
+    fn fn_call(&mut self, x: &FnCallExpr) {
+        x.args.iter().for_each(|arg| self.expr(arg));
+
+        if x.capture_parent_scope || (x.name == KEYWORD_EVAL && !x.is_qualified()) {
+            self.read_all();
+        }
+    }
+    /// Analyze an expression.
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::DynamicConstant(..)
+            | Expr::BoolConstant(..)
+            | Expr::IntegerConstant(..)
+            | Expr::CharConstant(..)
+            | Expr::StringConstant(..)
+            | Expr::Unit(..)
+            | Expr::ThisPtr(..)
+            | Expr::Property(..) => (),
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(..) => (),
+
+            Expr::InterpolatedString(x, ..) | Expr::Array(x, ..) => {
+                x.iter().for_each(|expr| self.expr(expr));
+            }
+            Expr::Map(x, ..) => x.0.iter().for_each(|(.., expr)| self.expr(expr)),
+
+            Expr::Variable(x, ..) if x.1.is_empty() => self.read(&x.3),
+            Expr::Variable(..) => (),
+
+            Expr::Stmt(x) => self.block(x.statements()),
+
+            Expr::FnCall(x, ..) | Expr::MethodCall(x, ..) => self.fn_call(x),
+
+            Expr::Dot(x, ..)
+            | Expr::Index(x, ..)
+            | Expr::And(x, ..)
+            | Expr::Or(x, ..)
+            | Expr::Coalesce(x, ..) => {
+                self.expr(&x.lhs);
+                self.expr(&x.rhs);
+            }
+
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, ..) => {
+                x.inputs.iter().for_each(|expr| self.expr(expr));
+                // Custom syntax may access any variable
+                self.read_all();
+            }
+        }
+    }
+}
+
+/// Is an expression a constant (e.g. a literal, or an operator on literals)?
+#[must_use]
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::And(x, ..) | Expr::Or(x, ..) | Expr::Coalesce(x, ..) => {
+            is_constant(&x.lhs) && is_constant(&x.rhs)
+        }
+        Expr::FnCall(x, ..) => match x.op_token {
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Some(Token::Custom(..)) => false,
+            Some(..) => x.args.iter().all(is_constant),
+            None => false,
+        },
+        expr => expr.is_constant(),
+    }
+}
+
+impl Engine {
+    /// Analyze a parsed script for possible mistakes (before optimization).
+    ///
+    /// Positions of function parameters are keyed by the hash of each function.
+    pub(crate) fn analyze(
+        &self,
+        statements: &StmtBlockContainer,
+        #[cfg(not(feature = "no_function"))] lib: &[Shared<ScriptFnDef>],
+        #[cfg(not(feature = "no_function"))] param_positions: &StraightHashMap<FnArgsVec<Position>>,
+    ) -> Vec<ParseWarning> {
+        let mut warnings = Vec::new();
+
+        #[cfg(not(feature = "no_function"))]
+        for fn_def in lib {
+            let hash = if fn_def.is_variadic {
+                crate::calc_variadic_fn_hash(None, &fn_def.name)
+            } else {
+                crate::calc_fn_hash(None, &fn_def.name, fn_def.params.len())
+            };
+            #[cfg(not(feature = "no_object"))]
+            let hash = fn_def
+                .this_type
+                .as_ref()
+                .map_or(hash, |typ| crate::calc_typed_method_hash(hash, typ));
+
+            let mut analyzer = Analyzer {
+                warnings: &mut warnings,
+                stack: Vec::new(),
+                fn_name: Some(fn_def.name.clone()),
+            };
+
+            let positions = param_positions.get(&hash);
+            let num_required = fn_def.num_required_params();
+            let num_fixed = fn_def.num_fixed_params();
+
+            fn_def.params.iter().enumerate().for_each(|(i, param)| {
+                // Default values can refer to previous parameters
+                if i >= num_required && i < num_fixed {
+                    analyzer.expr(&fn_def.defaults[i - num_required]);
+                }
+
+                let pos = positions
+                    .and_then(|p| p.get(i).copied())
+                    .unwrap_or(Position::NONE);
+                analyzer.declare(param, pos, ParseWarningKind::UnusedParameter);
+
+                // Parameters of anonymous functions (including captured variables) are not checked
+                if positions.is_none() || !self.warn_unused_params() {
+                    analyzer.stack.last_mut().unwrap().used = true;
+                }
+            });
+
+            analyzer.block(fn_def.body.statements());
+            analyzer.rewind(0);
+        }
+
+        let mut analyzer = Analyzer {
+            warnings: &mut warnings,
+            stack: Vec::new(),
+            fn_name: None,
+        };
+
+        analyzer.statements(statements);
+
+        // Global constants may be accessed from functions via the `global` namespace
+        analyzer
+            .stack
+            .iter_mut()
+            .filter(|v| v.is_const)
+            .for_each(|v| v.used = true);
+
+        analyzer.rewind(0);
+
+        warnings.sort_by_key(|w| (w.position.line(), w.position.position()));
+        warnings
+    }
+}
+
+/// Range of the number of arguments accepted by a function.
+///
+/// [`None`] as the maximum means any number of arguments.
+type Arity = (usize, Option<usize>);
+
+/// Functions that are handled specially during function calls, with any number of arguments.
+const SPECIAL_FNS: &[&str] = &[
+    KEYWORD_PRINT,
+    KEYWORD_DEBUG,
+    KEYWORD_TYPE_OF,
+    KEYWORD_EVAL,
+    KEYWORD_FN_PTR,
+    KEYWORD_FN_PTR_CALL,
+    KEYWORD_FN_PTR_CURRY,
+    KEYWORD_IS_DEF_VAR,
+    #[cfg(not(feature = "no_closure"))]
+    crate::engine::KEYWORD_IS_SHARED,
+    #[cfg(not(feature = "no_function"))]
+    crate::engine::KEYWORD_IS_DEF_FN,
+    #[cfg(not(feature = "no_index"))]
+    crate::engine::FN_SCOPE_VAR_NAMES,
+    FN_SCOPE_VAR,
+    FN_SCOPE_VAR_TYPE,
+    FN_DISPATCH,
+];
+
+/// Get the number of arguments accepted by a script-defined function.
+#[cfg(not(feature = "no_function"))]
+#[must_use]
+fn script_fn_arity(fn_def: &ScriptFnDef) -> Arity {
+    let max = if fn_def.is_variadic {
+        None
+    } else {
+        Some(fn_def.num_fixed_params())
+    };
+    (fn_def.num_required_params(), max)
+}
+
+/// Get the number of arguments accepted by a function in a [`Module`].
+#[must_use]
+fn fn_arity(f: &FuncInfo) -> Arity {
+    #[cfg(not(feature = "no_function"))]
+    if let Some(fn_def) = f.func.get_script_fn_def() {
+        return script_fn_arity(fn_def);
+    }
+
+    (f.metadata.num_params, Some(f.metadata.num_params))
+}
+
+/// Add the functions in a [`Module`] to a map of known functions.
+///
+/// If `global_only` is `true`, only functions in the global namespace (including those in
+/// sub-modules) are added.
+fn add_module_fns<'a>(
+    known: &mut BTreeMap<&'a str, Vec<Arity>>,
+    module: &'a Module,
+    global_only: bool,
+) {
+    module
+        .iter_fn()
+        .filter(|f| !global_only || f.metadata.namespace == crate::FnNamespace::Global)
+        .for_each(|f| {
+            known
+                .entry(f.metadata.name.as_str())
+                .or_default()
+                .push(fn_arity(f));
+        });
+
+    if global_only {
+        module
+            .iter_sub_modules()
+            .for_each(|(.., m)| add_module_fns(known, m, true));
+    }
+}
+
+/// Calculate the edit distance (i.e. the Levenshtein distance) between two strings.
+#[must_use]
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ch1) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &ch2) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ch1 == ch2 {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Find the name most similar to a misspelled function name, if any.
+#[must_use]
+fn suggest<'a>(name: &str, names: impl Iterator<Item = &'a str>) -> Option<String> {
+    let max_distance = usize::max(1, name.chars().count() / 3);
+
+    names
+        .filter(|&s| s != name && is_valid_identifier(s))
+        .map(|s| (edit_distance(name, s), s))
+        .filter(|&(d, ..)| d <= max_distance)
+        .min()
+        .map(|(.., s)| s.to_string())
+}
+
+/// Check a function call with a particular number of arguments against the known functions.
+///
+/// Returns the name of a similar function (if any) when the function is not found.
+fn check_fn(
+    known: &BTreeMap<&str, Vec<Arity>>,
+    name: &str,
+    num_args: usize,
+) -> Result<(), Option<String>> {
+    match known.get(name) {
+        Some(arities)
+            if arities
+                .iter()
+                .any(|&(min, max)| num_args >= min && max.map_or(true, |max| num_args <= max)) =>
+        {
+            Ok(())
+        }
+        Some(..) => Err(None),
+        None => Err(suggest(name, known.keys().copied())),
+    }
+}
+
+impl Engine {
+    /// Check that all function calls in a parsed script can be resolved (under strict functions
+    /// mode), returning an error for the first one that cannot.
+    pub(crate) fn check_fn_calls(
+        &self,
+        statements: &[Stmt],
+        #[cfg(not(feature = "no_function"))] lib: &[Shared<ScriptFnDef>],
+    ) -> Result<(), ParseError> {
+        let walk = |on_node: &mut dyn FnMut(&[ASTNode]) -> bool| {
+            let path = &mut Vec::new();
+
+            for stmt in statements {
+                if !stmt.walk(path, on_node) {
+                    return;
+                }
+            }
+            #[cfg(not(feature = "no_function"))]
+            for fn_def in lib {
+                for expr in fn_def.defaults.iter() {
+                    if !expr.walk(path, on_node) {
+                        return;
+                    }
+                }
+                for stmt in fn_def.body.iter() {
+                    if !stmt.walk(path, on_node) {
+                        return;
+                    }
+                }
+            }
+        };
+
+        // Collect the names of all imported modules
+        #[cfg(not(feature = "no_module"))]
+        let mut imports = Vec::<ImmutableString>::new();
+        #[cfg(not(feature = "no_module"))]
+        walk(&mut |path| {
+            if let Some(ASTNode::Stmt(Stmt::Import(x, ..))) = path.last() {
+                imports.push(x.1.name.clone());
+            }
+            true
+        });
+        #[cfg(feature = "no_module")]
+        let imports: [ImmutableString; 0] = [];
+
+        // Collect all functions visible to unqualified function calls
+        let mut known = BTreeMap::<&str, Vec<Arity>>::new();
+
+        SPECIAL_FNS.iter().for_each(|&name| {
+            known.entry(name).or_default().push((0, None));
+        });
+        #[cfg(not(feature = "no_function"))]
+        lib.iter().for_each(|fn_def| {
+            known
+                .entry(fn_def.name.as_str())
+                .or_default()
+                .push(script_fn_arity(fn_def));
+        });
+        self.global_modules
+            .iter()
+            .for_each(|m| add_module_fns(&mut known, m, false));
+        #[cfg(not(feature = "no_module"))]
+        self.global_sub_modules
+            .values()
+            .for_each(|m| add_module_fns(&mut known, m, true));
+
+        let mut error = None;
+
+        walk(&mut |path| {
+            let (x, pos) = match path.last() {
+                Some(ASTNode::Expr(Expr::FnCall(x, pos)) | ASTNode::Stmt(Stmt::FnCall(x, pos))) => {
+                    (x, *pos)
+                }
+                _ => return true,
+            };
+
+            // Operators are not checked
+            if x.op_token.is_some() {
+                return true;
+            }
+
+            let num_args = x.args.len();
+
+            #[cfg(not(feature = "no_module"))]
+            if x.is_qualified() {
+                let root = x.namespace.root();
+                let is_global = false;
+
+                #[cfg(not(feature = "no_function"))]
+                let is_global = is_global || root == crate::engine::KEYWORD_GLOBAL;
+
+                // Modules imported at runtime, the `global` module, and static modules that
+                // are not yet built, cannot be checked
+                if is_global
+                    || imports.iter().any(|name| name == root)
+                    || self.lazy_sub_modules.contains_key(root)
+                {
+                    return true;
+                }
+
+                let module = x.namespace.path[1..]
+                    .iter()
+                    .try_fold(
+                        self.global_sub_modules.get(root).map(|m| &**m),
+                        |m, ident| m.map(|m| m.get_sub_module(&ident.name)),
+                    )
+                    .flatten();
+
+                let result = match module {
+                    Some(m) => {
+                        let mut fns = BTreeMap::<&str, Vec<Arity>>::new();
+                        add_module_fns(&mut fns, m, false);
+                        check_fn(&fns, &x.name, num_args)
+                    }
+                    None => Err(None),
+                };
+
+                return match result {
+                    Ok(()) => true,
+                    Err(hint) => {
+                        let name = format!(
+                            "{}{}{}",
+                            x.namespace,
+                            crate::engine::NAMESPACE_SEPARATOR,
+                            x.name
+                        );
+                        error = Some(ParseError(
+                            PERR::FnUndefined(name, num_args, hint).into(),
+                            pos,
+                        ));
+                        false
+                    }
+                };
+            }
+
+            match check_fn(&known, &x.name, num_args) {
+                Ok(()) => true,
+                // Functions may be defined in the global namespace of imported modules
+                Err(..) if !imports.is_empty() => true,
+                Err(hint) => {
+                    error = Some(ParseError(
+                        PERR::FnUndefined(x.name.to_string(), num_args, hint).into(),
+                        pos,
+                    ));
+                    false
+                }
+            }
+        });
+
+        error.map_or(Ok(()), Err)
+    }
+}
@@ -2,9 +2,9 @@
 use crate::func::SendSync;
 use crate::packages::string_basic::{FUNC_TO_DEBUG, FUNC_TO_STRING};
 use crate::{types::dynamic::Variant, Engine, Identifier, RegisterNativeFunction};
-use std::marker::PhantomData;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
+use std::{any::type_name, marker::PhantomData};
 
 #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
 use crate::func::register::Mut;
@@ -88,6 +88,9 @@ impl Engine {
     }
 }
 
+/// A deferred registration of a constructor function, returning its hash.
+type ConstructorFn = Box<dyn FnOnce(&mut Engine, &str) -> u64>;
+
 /// Builder to build the API of a custom type for use with an [`Engine`].
 ///
 /// The type is automatically registered when this builder is dropped.
@@ -98,9 +101,18 @@ impl Engine {
 ///
 /// To define a pretty-print name, call [`with_name`][`TypeBuilder::with_name`],
 /// to use [`Engine::register_type_with_name`] instead.
+///
+/// ## Metadata
+///
+/// Under the `metadata` feature, all functions registered via the builder are recorded together
+/// with the custom type, so that they are grouped under it in the output of
+/// [`Engine::gen_fn_metadata_to_json`] and `Engine::definitions` (under `internals`).
 pub struct TypeBuilder<'a, T: Variant + Clone> {
     engine: &'a mut Engine,
     name: Option<&'static str>,
+    constructors: Vec<ConstructorFn>,
+    #[cfg(feature = "metadata")]
+    functions: Vec<u64>,
     _marker: PhantomData<T>,
 }
 
@@ -111,16 +123,35 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         Self {
             engine,
             name: None,
+            constructors: Vec::new(),
+            #[cfg(feature = "metadata")]
+            functions: Vec::new(),
             _marker: PhantomData,
         }
     }
+
+    /// Register a function and record it as belonging to the custom type.
+    #[inline]
+    fn add_fn<A: 'static, const N: usize, const C: bool, R: Variant + Clone, const L: bool>(
+        &mut self,
+        name: impl AsRef<str> + Into<Identifier>,
+        func: impl RegisterNativeFunction<A, N, C, R, L> + SendSync + 'static,
+    ) -> &mut Self {
+        let _hash = self.engine.register_fn_with_hash(name, func);
+        #[cfg(feature = "metadata")]
+        self.functions.push(_hash);
+        self
+    }
 }
 
 impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
     /// Set a pretty-print name for the `type_of` function.
+    ///
+    /// Functions registered afterwards show this name in their metadata.
     #[inline(always)]
     pub fn with_name(&mut self, name: &'static str) -> &mut Self {
         self.name = Some(name);
+        self.engine.register_type_with_name::<T>(name);
         self
     }
 
@@ -130,8 +161,7 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         &mut self,
         on_print: impl Fn(&mut T) -> String + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_fn(FUNC_TO_STRING, on_print);
-        self
+        self.add_fn(FUNC_TO_STRING, on_print)
     }
 
     /// Debug-print this custom type.
@@ -140,8 +170,7 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         &mut self,
         on_print: impl Fn(&mut T) -> String + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_fn(FUNC_TO_DEBUG, on_print);
-        self
+        self.add_fn(FUNC_TO_DEBUG, on_print)
     }
 
     /// Register a custom function.
@@ -151,7 +180,58 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         name: impl AsRef<str> + Into<Identifier>,
         method: impl RegisterNativeFunction<A, N, C, R, L> + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_fn(name, method);
+        self.add_fn(name, method)
+    }
+
+    /// Register a constructor function, callable from scripts under the pretty-print name of the
+    /// type (see [`with_name`][`TypeBuilder::with_name`]).
+    ///
+    /// If no pretty-print name is set, the last segment of the Rust type name (without generics)
+    /// is used instead.
+    ///
+    /// Multiple constructors with different parameters can be registered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "no_object"))]
+    /// # {
+    /// use rhai::{CustomType, TypeBuilder, Engine, INT};
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct Foo {
+    ///     x: INT
+    /// }
+    ///
+    /// impl CustomType for Foo {
+    ///     fn build(mut builder: TypeBuilder<Self>) {
+    ///         builder
+    ///             .with_name("Foo")
+    ///             .with_constructor(|x: INT| Self { x })
+    ///             .with_get_set("x", |f: &mut Self| f.x, |f: &mut Self, x: INT| f.x = x);
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.build_type::<Foo>();
+    ///
+    /// assert_eq!(engine.eval::<INT>("let f = Foo(1); f.x += 2; f.x")?, 3);
+    /// # Ok(())
+    /// # }
+    /// # }
+    /// ```
+    #[inline]
+    pub fn with_constructor<A: 'static, const N: usize, const C: bool, const L: bool>(
+        &mut self,
+        func: impl RegisterNativeFunction<A, N, C, T, L> + SendSync + 'static,
+    ) -> &mut Self {
+        // The constructor is named after the type, which may not yet be known,
+        // so it is registered only when the builder is dropped.
+        self.constructors.push(Box::new(move |engine, name| {
+            engine.register_fn_with_hash(name, func)
+        }));
         self
     }
 }
@@ -183,8 +263,7 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         name: impl AsRef<str>,
         get_fn: impl RegisterNativeFunction<(Mut<T>,), 1, C, V, L> + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_get(name, get_fn);
-        self
+        self.add_fn(crate::engine::make_getter(name.as_ref()), get_fn)
     }
 
     /// Register a setter function.
@@ -196,8 +275,7 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         name: impl AsRef<str>,
         set_fn: impl RegisterNativeFunction<(Mut<T>, V), 2, C, (), L> + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_set(name, set_fn);
-        self
+        self.add_fn(crate::engine::make_setter(name.as_ref()), set_fn)
     }
 
     /// Short-hand for registering both getter and setter functions.
@@ -218,8 +296,7 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         get_fn: impl RegisterNativeFunction<(Mut<T>,), 1, C1, V, L1> + SendSync + 'static,
         set_fn: impl RegisterNativeFunction<(Mut<T>, V), 2, C2, (), L2> + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_get_set(name, get_fn, set_fn);
-        self
+        self.with_get(&name, get_fn).with_set(&name, set_fn)
     }
 }
 
@@ -240,8 +317,7 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         &mut self,
         get_fn: impl RegisterNativeFunction<(Mut<T>, X), 2, C, V, L> + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_indexer_get(get_fn);
-        self
+        self.add_fn(crate::engine::FN_IDX_GET, get_fn)
     }
 
     /// Register an index setter.
@@ -257,8 +333,7 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         &mut self,
         set_fn: impl RegisterNativeFunction<(Mut<T>, X, V), 3, C, (), L> + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_indexer_set(set_fn);
-        self
+        self.add_fn(crate::engine::FN_IDX_SET, set_fn)
     }
 
     /// Short-hand for registering both index getter and setter functions.
@@ -277,17 +352,34 @@ impl<'a, T: Variant + Clone> TypeBuilder<'a, T> {
         get_fn: impl RegisterNativeFunction<(Mut<T>, X), 2, C1, V, L1> + SendSync + 'static,
         set_fn: impl RegisterNativeFunction<(Mut<T>, X, V), 3, C2, (), L2> + SendSync + 'static,
     ) -> &mut Self {
-        self.engine.register_indexer_get_set(get_fn, set_fn);
-        self
+        self.with_indexer_get(get_fn).with_indexer_set(set_fn)
     }
 }
 
 impl<'a, T: Variant + Clone> Drop for TypeBuilder<'a, T> {
     #[inline]
     fn drop(&mut self) {
-        match self.name {
-            Some(name) => self.engine.register_type_with_name::<T>(name),
-            None => self.engine.register_type::<T>(),
-        };
+        let name = self.name.unwrap_or_else(type_name::<T>);
+
+        let constructor_name = self.name.unwrap_or_else(|| {
+            let name = name.split('<').next().unwrap();
+            name.rsplit("::").next().unwrap()
+        });
+
+        // Register the type first so that the constructors' metadata shows its name
+        self.engine.register_type_with_name::<T>(name);
+
+        let _constructors = std::mem::take(&mut self.constructors)
+            .into_iter()
+            .map(|constructor| constructor(self.engine, constructor_name))
+            .collect::<Vec<_>>();
+
+        #[cfg(feature = "metadata")]
+        {
+            let functions = _constructors.into_iter().chain(self.functions.drain(..));
+            self.engine
+                .global_namespace_mut()
+                .set_custom_type_with_functions::<T>(name, functions);
+        }
     }
 }
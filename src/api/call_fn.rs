@@ -2,14 +2,15 @@
 #![cfg(not(feature = "no_function"))]
 
 use crate::eval::{Caches, GlobalRuntimeState};
+use crate::func::EncapsulatedEnviron;
 use crate::types::dynamic::Variant;
 use crate::{
-    Dynamic, Engine, FnArgsVec, FuncArgs, Position, RhaiResult, RhaiResultOf, Scope, StaticVec,
-    AST, ERR,
+    Dynamic, Engine, FnArgsVec, FnPtr, FuncArgs, Identifier, Position, RhaiResult, RhaiResultOf,
+    Scope, Shared, SharedModule, StaticVec, AST, ERR,
 };
+use std::mem;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{any::type_name, mem};
 
 /// Options for calling a script-defined function via [`Engine::call_fn_with_options`].
 #[derive(Debug, Hash)]
@@ -74,6 +75,105 @@ impl<'a> CallFnOptions<'a> {
     }
 }
 
+/// A call to a script-defined function, for use with [`Engine::call_fns`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct FnCallSpec {
+    /// Name of the script-defined function to call.
+    pub name: Identifier,
+    /// Arguments to the function call.
+    pub args: Vec<Dynamic>,
+    /// Index of the value (if any) to bind to the `this` pointer, within the values provided to
+    /// [`Engine::call_fns_with_options`]. Default [`None`].
+    pub this_ptr: Option<usize>,
+}
+
+impl FnCallSpec {
+    /// Create a new [`FnCallSpec`].
+    #[inline]
+    #[must_use]
+    pub fn new(name: impl Into<Identifier>, args: impl FuncArgs) -> Self {
+        let mut arg_values = Vec::new();
+        args.parse(&mut arg_values);
+
+        Self {
+            name: name.into(),
+            args: arg_values,
+            this_ptr: None,
+        }
+    }
+    /// Bind the value at a particular index to the `this` pointer.
+    #[inline(always)]
+    #[must_use]
+    pub const fn bind_this_ptr(mut self, index: usize) -> Self {
+        self.this_ptr = Some(index);
+        self
+    }
+}
+
+/// Options for calling multiple script-defined functions via [`Engine::call_fns_with_options`].
+#[derive(Debug, Hash)]
+#[non_exhaustive]
+pub struct CallFnsOptions {
+    /// The custom state of this evaluation run (if any), overrides [`Engine::default_tag`]. Default [`None`].
+    pub tag: Option<Dynamic>,
+    /// Evaluate the [`AST`] (once) to load necessary modules before calling the functions? Default `true`.
+    pub eval_ast: bool,
+    /// Rewind the [`Scope`] after each function call? Default `true`.
+    pub rewind_scope: bool,
+    /// Stop at the first function call that fails? Default `true`.
+    pub stop_on_error: bool,
+}
+
+impl Default for CallFnsOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CallFnsOptions {
+    /// Create a default [`CallFnsOptions`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            tag: None,
+            eval_ast: true,
+            rewind_scope: true,
+            stop_on_error: true,
+        }
+    }
+    /// Set the custom state of this evaluation run (if any).
+    #[inline(always)]
+    #[must_use]
+    pub fn with_tag(mut self, value: impl Variant + Clone) -> Self {
+        self.tag = Some(Dynamic::from(value));
+        self
+    }
+    /// Set whether to evaluate the [`AST`] to load necessary modules before calling the functions.
+    #[inline(always)]
+    #[must_use]
+    pub const fn eval_ast(mut self, value: bool) -> Self {
+        self.eval_ast = value;
+        self
+    }
+    /// Set whether to rewind the [`Scope`] after each function call.
+    #[inline(always)]
+    #[must_use]
+    pub const fn rewind_scope(mut self, value: bool) -> Self {
+        self.rewind_scope = value;
+        self
+    }
+    /// Set whether to stop at the first function call that fails.
+    #[inline(always)]
+    #[must_use]
+    pub const fn stop_on_error(mut self, value: bool) -> Self {
+        self.stop_on_error = value;
+        self
+    }
+}
+
 impl Engine {
     /// Call a script function defined in an [`AST`] with multiple arguments.
     ///
@@ -182,17 +282,358 @@ impl Engine {
             arg_values.as_mut(),
             options,
         )
-        .and_then(|result| {
-            result.try_cast_raw().map_err(|r| {
-                let result_type = self.map_type_name(r.type_name());
-                let cast_type = match type_name::<T>() {
-                    typ if typ.contains("::") => self.map_type_name(typ),
-                    typ => typ,
-                };
-                ERR::ErrorMismatchOutputType(cast_type.into(), result_type.into(), Position::NONE)
-                    .into()
-            })
-        })
+        .and_then(|result| self.cast_dynamic(result))
+    }
+    /// Call a script function in a shared [`Module`][crate::Module] of script-defined functions
+    /// (e.g. obtained from [`AST::split`]) with multiple arguments.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// No statements are evaluated. The [`Scope`] is rewound after the function call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("
+    ///     fn add(x, y) { len(x) + y + foo }
+    ///     print(\"this is not run\");
+    /// ")?;
+    ///
+    /// let (_, lib) = ast.split();
+    ///
+    /// let mut scope = Scope::new();
+    /// scope.push("foo", 42_i64);
+    ///
+    /// let result = engine.call_fn_in_module::<i64>(&mut scope, &lib, "add", ( "abc", 123_i64 ) )?;
+    /// assert_eq!(result, 168);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_fn_in_module<T: Variant + Clone>(
+        &self,
+        scope: &mut Scope,
+        lib: &SharedModule,
+        name: impl AsRef<str>,
+        args: impl FuncArgs,
+    ) -> RhaiResultOf<T> {
+        let mut arg_values = StaticVec::new_const();
+        args.parse(&mut arg_values);
+
+        let global = &mut GlobalRuntimeState::new(self);
+        let caches = &mut Caches::new();
+
+        global.lib.push(lib.clone());
+
+        let result = self._call_script_fn_in_lib(
+            global,
+            caches,
+            scope,
+            None,
+            lib,
+            name.as_ref(),
+            arg_values.as_mut(),
+            true,
+        );
+
+        #[cfg(feature = "debugging")]
+        if self.is_debugger_registered() {
+            global.debugger_mut().status = crate::eval::DebuggerStatus::Terminate;
+            let node = &crate::ast::Stmt::Noop(Position::NONE);
+            self.run_debugger(global, caches, scope, None, node)?;
+        }
+
+        result.and_then(|result| self.cast_dynamic(result))
+    }
+    /// Call multiple script functions defined in an [`AST`], in order, against the same [`Scope`].
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// The [`AST`] is evaluated only once, before calling the first function.
+    /// If not desired, use [`call_fns_with_options`][Engine::call_fns_with_options] instead.
+    ///
+    /// The results are returned in the same order as the calls. Evaluation stops at the first
+    /// function call that fails, so the last result is the error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, FnCallSpec, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("
+    ///     fn pre_update()  { ticks += 1; }
+    ///     fn update(x)     { ticks * x }
+    /// ")?;
+    ///
+    /// let mut scope = Scope::new();
+    /// scope.push("ticks", 0_i64);
+    ///
+    /// let calls = [FnCallSpec::new("pre_update", ()), FnCallSpec::new("update", (21_i64,))];
+    ///
+    /// let results = engine.call_fns(&mut scope, &ast, &calls);
+    ///
+    /// assert_eq!(results.len(), 2);
+    /// assert_eq!(results[1].as_ref().unwrap().as_int().unwrap(), 21);
+    /// assert_eq!(scope.get_value::<i64>("ticks").unwrap(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn call_fns(&self, scope: &mut Scope, ast: &AST, calls: &[FnCallSpec]) -> Vec<RhaiResult> {
+        self.call_fns_with_options(<_>::default(), scope, ast, calls, &mut [])
+    }
+    /// Call multiple script functions defined in an [`AST`], in order, against the same [`Scope`].
+    ///
+    /// Options are provided via the [`CallFnsOptions`] type.
+    /// This is an advanced API.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// Values to bind to the `this` pointer are provided in `this_ptrs`, and each [`FnCallSpec`]
+    /// refers to one of them by index.
+    ///
+    /// The results are returned in the same order as the calls. If the [`AST`] fails to evaluate,
+    /// only that error is returned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{CallFnsOptions, Dynamic, Engine, FnCallSpec, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("
+    ///     fn action(x) { this += x; }
+    ///     fn fail()    { throw 42; }
+    /// ")?;
+    ///
+    /// let mut scope = Scope::new();
+    /// let mut values = [Dynamic::from(1_i64), Dynamic::from(10_i64)];
+    ///
+    /// let calls = [
+    ///     FnCallSpec::new("action", (41_i64,)).bind_this_ptr(0),
+    ///     FnCallSpec::new("fail", ()),
+    ///     FnCallSpec::new("action", (32_i64,)).bind_this_ptr(1),
+    /// ];
+    ///
+    /// // Continue after errors
+    /// let options = CallFnsOptions::new().stop_on_error(false);
+    ///
+    /// let results = engine.call_fns_with_options(options, &mut scope, &ast, &calls, &mut values);
+    ///
+    /// assert_eq!(results.len(), 3);
+    /// assert!(results[1].is_err());
+    /// assert_eq!(values[0].as_int().unwrap(), 42);
+    /// assert_eq!(values[1].as_int().unwrap(), 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn call_fns_with_options(
+        &self,
+        options: CallFnsOptions,
+        scope: &mut Scope,
+        ast: &AST,
+        calls: &[FnCallSpec],
+        this_ptrs: &mut [Dynamic],
+    ) -> Vec<RhaiResult> {
+        let global = &mut GlobalRuntimeState::new(self);
+        let caches = &mut Caches::new();
+
+        if let Some(value) = options.tag {
+            global.tag = value;
+        }
+
+        global.lib.push(ast.shared_lib().clone());
+
+        #[cfg(not(feature = "no_module"))]
+        {
+            global.embedded_module_resolver = ast.resolver.clone();
+        }
+
+        let rewind_scope = options.rewind_scope;
+        let statements = ast.statements();
+
+        if options.eval_ast && !calls.is_empty() && !statements.is_empty() {
+            defer! {
+                scope if rewind_scope => rewind;
+                let orig_scope_len = scope.len();
+            }
+
+            if let Err(err) = self.eval_global_statements(global, caches, scope, statements, true) {
+                return vec![Err(err)];
+            }
+        }
+
+        let mut results = Vec::with_capacity(calls.len());
+
+        for call in calls {
+            let mut arg_values = call.args.clone();
+
+            let result = match call.this_ptr {
+                Some(index) if index >= this_ptrs.len() => {
+                    #[allow(clippy::cast_possible_wrap)]
+                    let index = index as crate::INT;
+                    Err(ERR::ErrorArrayBounds(this_ptrs.len(), index, Position::NONE).into())
+                }
+                Some(index) => self._call_script_fn_in_lib(
+                    global,
+                    caches,
+                    scope,
+                    Some(&mut this_ptrs[index]),
+                    ast.shared_lib(),
+                    &call.name,
+                    &mut arg_values,
+                    rewind_scope,
+                ),
+                None => self._call_script_fn_in_lib(
+                    global,
+                    caches,
+                    scope,
+                    None,
+                    ast.shared_lib(),
+                    &call.name,
+                    &mut arg_values,
+                    rewind_scope,
+                ),
+            };
+
+            let stop = result.is_err() && options.stop_on_error;
+            results.push(result);
+
+            if stop {
+                break;
+            }
+        }
+
+        #[cfg(feature = "debugging")]
+        if self.is_debugger_registered() {
+            global.debugger_mut().status = crate::eval::DebuggerStatus::Terminate;
+            let node = &crate::ast::Stmt::Noop(Position::NONE);
+            if let Err(err) = self.run_debugger(global, caches, scope, None, node) {
+                results.push(Err(err));
+            }
+        }
+
+        results
+    }
+    /// Evaluate an [`AST`] and collect the function pointers that it defines with names starting
+    /// with a prefix, e.g. to register event handlers written in a script.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// The following are collected, in order:
+    ///
+    /// 1. variables left in the [`Scope`] after evaluation that hold function pointers (e.g. closures),
+    ///    in the order they are defined (only the latest if a variable is shadowed),
+    /// 2. script-defined functions in the [`AST`], sorted by name.
+    ///
+    /// Each function pointer is bound to the environment of the [`AST`] (i.e. its functions,
+    /// imported modules and global constants), so that it can be called via
+    /// [`FnPtr::call_with_environ`][crate::FnPtr::call_with_environ] without the [`AST`].
+    ///
+    /// Variables defined by the [`AST`] are left in the [`Scope`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("
+    ///     fn on_init() { scale(1) }
+    ///     fn scale(x)  { x * 10 }
+    ///
+    ///     let on_tick = |n| scale(n) + 1;
+    ///     let other = 42;
+    /// ")?;
+    ///
+    /// let handlers = engine.collect_fn_ptrs(&mut Scope::new(), &ast, "on_")?;
+    ///
+    /// drop(ast);
+    ///
+    /// assert_eq!(handlers.len(), 2);
+    /// assert_eq!(handlers[0].0, "on_tick");
+    /// assert_eq!(handlers[0].1.call_with_environ::<i64>(&engine, (4_i64,))?, 41);
+    /// assert_eq!(handlers[1].0, "on_init");
+    /// assert_eq!(handlers[1].1.call_with_environ::<i64>(&engine, ())?, 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn collect_fn_ptrs(
+        &self,
+        scope: &mut Scope,
+        ast: &AST,
+        prefix: &str,
+    ) -> RhaiResultOf<Vec<(String, FnPtr)>> {
+        let global = &mut GlobalRuntimeState::new(self);
+        let caches = &mut Caches::new();
+        let orig_scope_len = scope.len();
+
+        let _ = self.eval_ast_with_scope_raw(global, caches, scope, ast)?;
+
+        let environ = Shared::new(EncapsulatedEnviron {
+            lib: ast.shared_lib().clone(),
+            #[cfg(not(feature = "no_module"))]
+            imports: global
+                .scan_imports_raw()
+                .map(|(n, m)| (n.clone(), m.clone()))
+                .collect(),
+            #[cfg(not(feature = "no_module"))]
+            constants: global.constants.clone(),
+        });
+
+        let mut fn_ptrs = Vec::new();
+        let mut names = Vec::new();
+
+        // Scan variables from the latest, skipping shadowed ones
+        for (name, _, value) in scope.iter_rev_raw().take(scope.len() - orig_scope_len) {
+            if !name.starts_with(prefix) || names.contains(&name) {
+                continue;
+            }
+
+            names.push(name);
+
+            if let Some(mut fn_ptr) = value.flatten_clone().try_cast::<FnPtr>() {
+                if fn_ptr.environ.is_none() {
+                    fn_ptr.environ = Some(environ.clone());
+                }
+                fn_ptrs.push((name.to_string(), fn_ptr));
+            }
+        }
+
+        fn_ptrs.reverse();
+
+        let mut fn_defs = ast
+            .iter_fn_def()
+            .filter(|f| f.name.starts_with(prefix) && !crate::func::is_anonymous_fn(&f.name))
+            .collect::<Vec<_>>();
+
+        fn_defs.sort_by(|a, b| a.name.cmp(&b.name));
+        fn_defs.dedup_by(|a, b| a.name == b.name);
+
+        fn_ptrs.extend(fn_defs.into_iter().map(|f| {
+            let fn_ptr = FnPtr {
+                name: f.name.clone(),
+                curry: Vec::new(),
+                environ: Some(environ.clone()),
+                native_fn: None,
+                fn_def: Some(f.clone()),
+                binds_this: false,
+            };
+            (f.name.to_string(), fn_ptr)
+        }));
+
+        Ok(fn_ptrs)
     }
     /// Call a script function defined in an [`AST`] with multiple [`Dynamic`] arguments.
     ///
@@ -241,34 +682,16 @@ impl Engine {
         };
 
         let result = global_result.and_then(|_| {
-            let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
-
-            // Check for data race.
-            #[cfg(not(feature = "no_closure"))]
-            crate::func::ensure_no_data_race(name, args, false)?;
-
-            ast.shared_lib()
-                .get_script_fn(name, args.len())
-                .map_or_else(
-                    || Err(ERR::ErrorFunctionNotFound(name.into(), Position::NONE).into()),
-                    |fn_def| {
-                        self.call_script_fn(
-                            global,
-                            caches,
-                            scope,
-                            this_ptr.as_deref_mut(),
-                            None,
-                            fn_def,
-                            args,
-                            rewind_scope,
-                            Position::NONE,
-                        )
-                    },
-                )
-                .or_else(|err| match *err {
-                    ERR::Exit(out, ..) => Ok(out),
-                    _ => Err(err),
-                })
+            self._call_script_fn_in_lib(
+                global,
+                caches,
+                scope,
+                this_ptr.as_deref_mut(),
+                ast.shared_lib(),
+                name,
+                arg_values,
+                rewind_scope,
+            )
         });
 
         #[cfg(feature = "debugging")]
@@ -291,4 +714,48 @@ impl Engine {
 
         result
     }
+    /// Call a script function defined in a [`Module`][crate::Module] of script-defined functions,
+    /// assuming that the functions are already available in the [`GlobalRuntimeState`].
+    ///
+    /// All the arguments are _consumed_, meaning that they're replaced by `()`.
+    #[inline]
+    fn _call_script_fn_in_lib(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        lib: &SharedModule,
+        name: &str,
+        arg_values: &mut [Dynamic],
+        rewind_scope: bool,
+    ) -> RhaiResult {
+        let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
+
+        // Check for data race.
+        #[cfg(not(feature = "no_closure"))]
+        crate::func::ensure_no_data_race(name, args, false)?;
+
+        lib.get_script_fn(name, args.len())
+            .map_or_else(
+                || Err(ERR::ErrorFunctionNotFound(name.into(), Position::NONE).into()),
+                |fn_def| {
+                    self.call_script_fn(
+                        global,
+                        caches,
+                        scope,
+                        this_ptr,
+                        None,
+                        fn_def,
+                        args,
+                        rewind_scope,
+                        Position::NONE,
+                    )
+                },
+            )
+            .or_else(|err| match *err {
+                ERR::Exit(out, ..) => Ok(out),
+                _ => Err(err),
+            })
+    }
 }
@@ -1,13 +1,68 @@
 //! Module that defines the public compilation API of [`Engine`].
 
+use crate::api::options::LangOptions;
 use crate::func::native::locked_write;
 use crate::parser::{ParseResult, ParseState};
 use crate::tokenizer::lex_raw;
 use crate::types::StringsInterner;
-use crate::{Engine, OptimizationLevel, Scope, AST};
+use crate::{Engine, OptimizationLevel, ParseWarning, Scope, AST};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
+/// Options for compiling a script via [`Engine::compile_with_options`].
+///
+/// Options left unset keep the corresponding settings of the [`Engine`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct CompileOptions {
+    /// Optimization level for this compilation, overrides [`Engine::optimization_level`].
+    /// Default [`None`].
+    ///
+    /// Not available under `no_optimize`.
+    #[cfg(not(feature = "no_optimize"))]
+    pub optimization_level: Option<OptimizationLevel>,
+    /// Turn Strict Variables mode on or off for this compilation, overrides
+    /// [`Engine::strict_variables`]. Default [`None`].
+    pub strict_variables: Option<bool>,
+}
+
+impl Default for CompileOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CompileOptions {
+    /// Create a default [`CompileOptions`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(not(feature = "no_optimize"))]
+            optimization_level: None,
+            strict_variables: None,
+        }
+    }
+    /// Set the optimization level for this compilation.
+    ///
+    /// Not available under `no_optimize`.
+    #[cfg(not(feature = "no_optimize"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_optimization_level(mut self, level: OptimizationLevel) -> Self {
+        self.optimization_level = Some(level);
+        self
+    }
+    /// Set whether Strict Variables mode is on for this compilation.
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_strict_variables(mut self, enable: bool) -> Self {
+        self.strict_variables = Some(enable);
+        self
+    }
+}
+
 impl Engine {
     /// Compile a string into an [`AST`], which can be used later for evaluation.
     ///
@@ -82,6 +137,9 @@ impl Engine {
     /// via the current [module resolver][crate::ModuleResolver] and embedded into the resultant
     /// [`AST`]. When it is evaluated later, `import` statement directly recall pre-resolved
     /// [modules][crate::Module] and the resolution process is not performed again.
+    ///
+    /// If [`fold_module_constants`][Engine::fold_module_constants] is turned on, the [`AST`] is
+    /// re-optimized to fold constants of the embedded modules into the script.
     #[cfg(not(feature = "no_module"))]
     pub fn compile_into_self_contained(
         &self,
@@ -145,6 +203,12 @@ impl Engine {
                 resolver.insert(path, module);
             }
             ast.resolver = Some(resolver.into());
+
+            // Re-optimize to fold constants of the embedded modules
+            #[cfg(not(feature = "no_optimize"))]
+            if self.fold_module_constants() && self.optimization_level != OptimizationLevel::None {
+                ast = self.optimize_ast(scope, ast, self.optimization_level);
+            }
         }
 
         Ok(ast)
@@ -232,7 +296,145 @@ impl Engine {
         };
 
         let state = &mut ParseState::new(scope, interned_strings, tc);
-        let mut _ast = self.parse(stream.peekable(), state, optimization_level)?;
+        let mut _ast = self.parse(stream.peekable(), state, |_| {}, optimization_level)?;
+        #[cfg(feature = "metadata")]
+        {
+            let global_comments = &state.tokenizer_control.borrow().global_comments;
+            _ast.doc = global_comments.into();
+        }
+        Ok(_ast)
+    }
+    /// Compile a string into an [`AST`], also returning a list of [warnings][ParseWarning] on
+    /// possible mistakes in the script.
+    ///
+    /// The script is analyzed before it is optimized. Warnings are reported for:
+    ///
+    /// * local variables that are never read (except those starting with an underscore, and
+    ///   global constants, which may be accessed from functions),
+    /// * parameters of script-defined functions that are never read (turn off via
+    ///   [`set_warn_unused_params`][Engine::set_warn_unused_params]),
+    /// * statements that can never be reached because they follow `return`, `throw`, `break` or
+    ///   `continue`,
+    /// * constant conditions in `if`, `while` and `do` statements.
+    ///
+    /// Warnings are sorted by position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, ParseWarningKind};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let (ast, warnings) = engine.compile_with_warnings("let x = 40; let y = 1; x + 2")?;
+    ///
+    /// assert_eq!(engine.eval_ast::<i64>(&ast)?, 42);
+    ///
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].kind, ParseWarningKind::UnusedVariable);
+    /// assert_eq!(warnings[0].message, "Variable 'y' is never used");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compile_with_warnings(
+        &self,
+        script: impl AsRef<str>,
+    ) -> ParseResult<(AST, Vec<ParseWarning>)> {
+        let scripts = [script];
+        let (stream, tc) = lex_raw(self, &scripts, self.token_mapper.as_deref());
+
+        let mut interner;
+        let mut guard;
+        let interned_strings = if let Some(ref interner) = self.interned_strings {
+            guard = locked_write(interner);
+            &mut *guard
+        } else {
+            interner = StringsInterner::new();
+            &mut interner
+        };
+
+        let scope = Scope::new();
+        let state = &mut ParseState::new(Some(&scope), interned_strings, tc);
+        state.warnings = Some(Vec::new());
+
+        let mut _ast = self.parse(stream.peekable(), state, |_| {}, self.optimization_level)?;
+        #[cfg(feature = "metadata")]
+        {
+            let global_comments = &state.tokenizer_control.borrow().global_comments;
+            _ast.doc = global_comments.into();
+        }
+
+        Ok((_ast, state.warnings.take().unwrap_or_default()))
+    }
+    /// Compile a string into an [`AST`] with [options][CompileOptions] that override the
+    /// settings of the [`Engine`] for this compilation only.
+    ///
+    /// Optimization can also be turned off from within the script itself via an
+    /// `//! rhai:optimize=off` marker comment: before any other code it applies to the entire
+    /// script, while before a function definition it excludes only that function.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_optimize"))]
+    /// # {
+    /// use rhai::{CompileOptions, Engine, OptimizationLevel};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let options = CompileOptions::new()
+    ///                 .with_optimization_level(OptimizationLevel::None)
+    ///                 .with_strict_variables(true);
+    ///
+    /// let ast = engine.compile_with_options("let x = 40; x + 2", options)?;
+    ///
+    /// assert_eq!(engine.eval_ast::<i64>(&ast)?, 42);
+    ///
+    /// // Strict Variables mode is on for this compilation only
+    /// assert!(engine.compile_with_options("x + 2", options).is_err());
+    /// assert!(engine.compile("x + 2").is_ok());
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn compile_with_options(
+        &self,
+        script: impl AsRef<str>,
+        options: CompileOptions,
+    ) -> ParseResult<AST> {
+        let scripts = [script];
+        let (stream, tc) = lex_raw(self, &scripts, self.token_mapper.as_deref());
+
+        let mut interner;
+        let mut guard;
+        let interned_strings = if let Some(ref interner) = self.interned_strings {
+            guard = locked_write(interner);
+            &mut *guard
+        } else {
+            interner = StringsInterner::new();
+            &mut interner
+        };
+
+        let scope = Scope::new();
+        let state = &mut ParseState::new(Some(&scope), interned_strings, tc);
+
+        let mut _ast = self.parse(
+            stream.peekable(),
+            state,
+            |s| {
+                if let Some(enable) = options.strict_variables {
+                    s.options.set(LangOptions::STRICT_VAR, enable);
+                }
+            },
+            #[cfg(not(feature = "no_optimize"))]
+            options
+                .optimization_level
+                .unwrap_or(self.optimization_level),
+            #[cfg(feature = "no_optimize")]
+            (),
+        )?;
         #[cfg(feature = "metadata")]
         {
             let global_comments = &state.tokenizer_control.borrow().global_comments;
@@ -32,8 +32,19 @@ pub mod markers {
     pub const CUSTOM_SYNTAX_MARKER_FLOAT: &str = "$float$";
     /// Special marker for matching a boolean value.
     pub const CUSTOM_SYNTAX_MARKER_BOOL: &str = "$bool$";
+    /// Special marker for repeating a group of symbols until a closing symbol.
+    pub const CUSTOM_SYNTAX_MARKER_REPEAT: &str = "$rep$";
     /// Special marker for identifying the custom syntax variant.
     pub const CUSTOM_SYNTAX_MARKER_SYNTAX_VARIANT: &str = "$$";
+    /// Special marker for declaring a new variable (followed by the variable name).
+    pub const CUSTOM_SYNTAX_MARKER_DECLARE_VAR: &str = "$var$";
+}
+
+/// Is a custom syntax symbol a special marker?
+#[inline(always)]
+#[must_use]
+fn is_marker(s: &str) -> bool {
+    s.len() > 2 && s.starts_with('$') && s.ends_with('$')
 }
 
 /// A general expression evaluation trait object.
@@ -173,6 +184,25 @@ impl Deref for Expression<'_> {
     }
 }
 
+/// Where a custom syntax is allowed to appear.
+///
+/// Not available under `no_custom_syntax`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[non_exhaustive]
+pub enum CustomSyntaxPosition {
+    /// The custom syntax can only be used as a statement.
+    ///
+    /// It is an error to use it inside an expression.
+    Statement,
+    /// The custom syntax can only be used inside an expression.
+    ///
+    /// It is an error to use it as a statement (which would otherwise discard its value).
+    Expression,
+    /// The custom syntax can be used anywhere (the default).
+    #[default]
+    Both,
+}
+
 /// Definition of a custom syntax definition.
 pub struct CustomSyntax {
     /// A parsing function to return the next token in a custom syntax based on the
@@ -182,6 +212,8 @@ pub struct CustomSyntax {
     pub func: Box<FnCustomSyntaxEval>,
     /// Any variables added/removed in the scope?
     pub scope_may_be_changed: bool,
+    /// Where the custom syntax is allowed to appear.
+    pub position: CustomSyntaxPosition,
 }
 
 impl Engine {
@@ -213,15 +245,114 @@ impl Engine {
     /// Replacing one variable with another (i.e. adding a new variable and removing one variable at
     /// the same time so that the total _size_ of the [`Scope`][crate::Scope] is unchanged) also
     /// does NOT count, so `false` should be passed.
+    ///
+    /// ## Repetition
+    ///
+    /// The special symbol `$rep$` starts a group of symbols that is matched zero or more times.
+    /// The group extends up to the closing symbol, which must be the last symbol, e.g.
+    /// `["pipeline", "$expr$", "$rep$", "=>", "$expr$", "end"]` matches `pipeline a end`,
+    /// `pipeline a => b end`, `pipeline a => b => c end` etc.
+    ///
+    /// Repetition stops when the closing symbol is encountered. If the group starts with a
+    /// keyword/symbol, repetition also stops when the next symbol is neither that keyword/symbol
+    /// nor the closing symbol, which then fails with a missing closing symbol.
+    ///
+    /// The implementation function receives the inputs of all repetitions in order.
+    ///
+    /// Only one `$rep$` is allowed, and the closing symbol must be a keyword/symbol which is
+    /// different from the first symbol of the group.
+    ///
+    /// ## Conflicts
+    ///
+    /// The first symbol is used to identify the custom syntax.  It is an error to register a custom
+    /// syntax that starts with the same symbol as an existing custom syntax or custom operator.
+    #[inline(always)]
     pub fn register_custom_syntax<S: AsRef<str> + Into<Identifier>>(
         &mut self,
         symbols: impl AsRef<[S]>,
         scope_may_be_changed: bool,
         func: impl Fn(&mut EvalContext, &[Expression]) -> RhaiResult + SendSync + 'static,
+    ) -> ParseResult<&mut Self> {
+        self.register_custom_syntax_with_position(
+            symbols,
+            scope_may_be_changed,
+            CustomSyntaxPosition::Both,
+            func,
+        )
+    }
+    /// Register a custom syntax with the [`Engine`] which can only be used in a particular
+    /// [position][CustomSyntaxPosition].
+    ///
+    /// Not available under `no_custom_syntax`.
+    ///
+    /// * `symbols` holds a slice of strings that define the custom syntax.
+    /// * `scope_may_be_changed` specifies variables _may_ be added/removed by this custom syntax.
+    /// * `position` specifies whether the custom syntax can be used as a statement, inside an
+    ///   expression, or both.
+    /// * `func` is the implementation function.
+    ///
+    /// See [`register_custom_syntax`][Engine::register_custom_syntax] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{CustomSyntaxPosition, Engine, ParseErrorType};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_custom_syntax_with_position(
+    ///     ["retry", "$int$", "$block$"],
+    ///     false,
+    ///     CustomSyntaxPosition::Statement,
+    ///     |context, inputs| context.eval_expression_tree(&inputs[1]),
+    /// )?;
+    ///
+    /// engine.run("retry 3 { print(42); }")?;
+    ///
+    /// let err = engine.compile("let x = retry 3 { 42 };").unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     *err.err_type(),
+    ///     ParseErrorType::CustomSyntaxStatementOnly("retry".into())
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn register_custom_syntax_with_position<S: AsRef<str> + Into<Identifier>>(
+        &mut self,
+        symbols: impl AsRef<[S]>,
+        scope_may_be_changed: bool,
+        position: CustomSyntaxPosition,
+        func: impl Fn(&mut EvalContext, &[Expression]) -> RhaiResult + SendSync + 'static,
     ) -> ParseResult<&mut Self> {
         #[allow(clippy::wildcard_imports)]
         use markers::*;
 
+        // Check for conflicts with existing custom syntax or custom operators
+        if let Some(s) = symbols
+            .as_ref()
+            .iter()
+            .map(|s| s.as_ref().trim())
+            .find(|s| !s.is_empty())
+        {
+            let conflict = if self.custom_syntax.contains_key(s) {
+                Some("an existing custom syntax")
+            } else if matches!(self.custom_keywords.get(s), Some(Some(..))) {
+                Some("a custom operator")
+            } else {
+                None
+            };
+
+            if let Some(conflict) = conflict {
+                return Err(LexError::ImproperSymbol(
+                    s.to_string(),
+                    format!("Custom syntax starting with '{s}' conflicts with {conflict} '{s}'"),
+                )
+                .into_err(Position::NONE));
+            }
+        }
+
         let mut segments = Vec::<ImmutableString>::new();
 
         for s in symbols.as_ref() {
@@ -255,6 +386,13 @@ impl Engine {
                 #[cfg(not(feature = "no_float"))]
                 CUSTOM_SYNTAX_MARKER_FLOAT if !segments.is_empty() => s.into(),
 
+                // Repetition not in first position, only once
+                CUSTOM_SYNTAX_MARKER_REPEAT
+                    if !segments.is_empty() && !segments.iter().any(|seg| seg == s) =>
+                {
+                    s.into()
+                }
+
                 // Identifier not in first position
                 _ if !segments.is_empty() && is_valid_identifier(s) => s.into(),
 
@@ -320,17 +458,75 @@ impl Engine {
         // The first keyword/symbol is the discriminator
         let key = segments[0].clone();
 
+        // Split off the repeated group and its closing symbol, if any
+        let repeat = match segments
+            .iter()
+            .position(|s| s.as_str() == CUSTOM_SYNTAX_MARKER_REPEAT)
+        {
+            Some(index) => {
+                segments.remove(index);
+
+                let group = index..segments.len().saturating_sub(1);
+
+                match segments.get(group.end) {
+                    Some(s) if !group.is_empty() && !is_marker(s) && *s != segments[index] => {
+                        Some((group, s.clone()))
+                    }
+                    _ => {
+                        return Err(LexError::ImproperSymbol(
+                            CUSTOM_SYNTAX_MARKER_REPEAT.to_string(),
+                            format!("Improper repetition for custom syntax: '{CUSTOM_SYNTAX_MARKER_REPEAT}' must be followed by a group of symbols and then a different closing symbol"),
+                        )
+                        .into_err(Position::NONE));
+                    }
+                }
+            }
+            None => None,
+        };
+
+        // Construct the parsing function
+        let parse = move |stream: &[ImmutableString], look_ahead: &str, state: &mut Dynamic| {
+            let len = stream.len();
+
+            let (group, closing) = match repeat {
+                Some((ref group, ref closing)) if len >= group.start => (group, closing),
+                _ => return Ok(segments.get(len).cloned()),
+            };
+
+            // The state is set once the closing symbol is matched
+            if state.as_bool() == Ok(true) {
+                return Ok(None);
+            }
+
+            let offset = (len - group.start) % group.len();
+            let first = &segments[group.start];
+
+            if offset == 0
+                && (look_ahead == closing.as_str()
+                    || (!is_marker(first) && look_ahead != first.as_str()))
+            {
+                *state = true.into();
+                Ok(Some(closing.clone()))
+            } else {
+                Ok(Some(segments[group.start + offset].clone()))
+            }
+        };
+
         self.register_custom_syntax_with_state_raw(
-            key,
-            // Construct the parsing function
-            move |stream, _, _| match stream.len() {
-                len if len >= segments.len() => Ok(None),
-                len => Ok(Some(segments[len].clone())),
-            },
+            key.clone(),
+            parse,
             scope_may_be_changed,
             move |context, expressions, _| func(context, expressions),
         );
 
+        if let Some(syntax) = self
+            .custom_syntax
+            .get_mut(key.as_str())
+            .and_then(crate::Shared::get_mut)
+        {
+            syntax.position = position;
+        }
+
         Ok(self)
     }
     /// Register a custom syntax with the [`Engine`] with custom user-defined state.
@@ -364,7 +560,55 @@ impl Engine {
     ///
     /// * `Ok(None)`: parsing complete and there are no more symbols to match.
     /// * `Ok(Some(symbol))`: the next symbol to match, which can also be `$expr$`, `$ident$` or `$block$`.
+    /// * `Ok(Some("$var$name"))`: declare a new variable `name` (see below); no symbol is matched.
     /// * `Err(ParseError)`: error that is reflected back to the [`Engine`], normally `ParseError(ParseErrorType::BadInput(LexError::ImproperSymbol(message)), Position::NONE)` to indicate a syntax error, but it can be any [`ParseError`][crate::ParseError].
+    ///
+    /// ## Declaring new variables
+    ///
+    /// Returning `$var$` followed by a variable name (e.g. `$var$foo`) declares a new variable
+    /// which is visible to statements following the custom syntax.  Such variables are resolved at
+    /// compile time (e.g. they are accepted under [Strict Variables][Engine::set_strict_variables]
+    /// mode), without searching the [`Scope`][crate::Scope] by name at runtime.
+    ///
+    /// The declaration is added to `symbols` as is, and is visible to the parsing function
+    /// when it is called again.
+    ///
+    /// The implementation function must push exactly the declared variables onto the
+    /// [`Scope`][crate::Scope], in the order of declaration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_strict_variables(true);
+    ///
+    /// // let2 $ident$ = $expr$;
+    /// engine.register_custom_syntax_with_state_raw(
+    ///     "let2",
+    ///     |symbols, _, _| match symbols.len() {
+    ///         1 => Ok(Some("$ident$".into())),
+    ///         2 => Ok(Some(format!("$var${}", symbols[1]).into())),
+    ///         3 => Ok(Some("=".into())),
+    ///         4 => Ok(Some("$expr$".into())),
+    ///         _ => Ok(None),
+    ///     },
+    ///     false,
+    ///     |context, inputs, _| {
+    ///         let name = inputs[0].get_string_value().unwrap().to_string();
+    ///         let value = context.eval_expression_tree(&inputs[1])?.as_int().unwrap();
+    ///         context.scope_mut().push(name, value * 2);
+    ///         Ok(rhai::Dynamic::UNIT)
+    ///     },
+    /// );
+    ///
+    /// assert_eq!(engine.eval::<i64>("let2 x = 21; x")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn register_custom_syntax_with_state_raw(
         &mut self,
         key: impl Into<Identifier>,
@@ -380,6 +624,7 @@ impl Engine {
                 parse: Box::new(parse),
                 func: Box::new(func),
                 scope_may_be_changed,
+                position: CustomSyntaxPosition::Both,
             }
             .into(),
         );
@@ -2,9 +2,11 @@
 #![cfg(feature = "internals")]
 #![cfg(feature = "metadata")]
 
+mod typescript;
+
 use crate::module::{FuncInfo, ModuleFlags};
 use crate::tokenizer::{is_valid_function_name, Token};
-use crate::{Engine, FnAccess, FnPtr, Module, Scope, INT};
+use crate::{Dynamic, Engine, FnAccess, FnPtr, ImmutableString, Module, Scope, INT};
 
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -435,6 +437,57 @@ impl Module {
             write!(writer, "const {name}: {ty};")?;
         }
 
+        // Functions registered together with a custom type are grouped under it
+        let mut custom_types = self
+            .iter_custom_types()
+            .filter(|(_, info)| !info.functions.is_empty())
+            .map(|(_, info)| info)
+            .collect::<Vec<_>>();
+        custom_types.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+
+        let grouped = custom_types
+            .iter()
+            .flat_map(|info| info.functions.iter().copied())
+            .collect::<std::collections::BTreeSet<_>>();
+
+        for f in self.sorted_functions() {
+            if grouped.contains(&f.metadata.hash) {
+                continue;
+            }
+
+            if !first {
+                writer.write_str("\n\n")?;
+            }
+            first = false;
+
+            f.write_module_definition(writer, def)?;
+        }
+
+        for info in custom_types {
+            if !first {
+                writer.write_str("\n\n")?;
+            }
+            first = false;
+
+            for comment in &*info.comments {
+                writeln!(writer, "{comment}")?;
+            }
+            write!(writer, "// type {}", info.display_name)?;
+
+            for &hash in &*info.functions {
+                if let Some(f) = self.iter_fn().find(|f| f.metadata.hash == hash) {
+                    writer.write_str("\n\n")?;
+                    f.write_module_definition(writer, def)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get all functions inside the [`Module`], sorted by name, number of parameters and types.
+    #[must_use]
+    fn sorted_functions(&self) -> Vec<&FuncInfo> {
         let mut func_infos = self.iter_fn().collect::<Vec<_>>();
         func_infos.sort_by(|a, b| match a.metadata.name.cmp(&b.metadata.name) {
             Ordering::Equal => match a.metadata.num_params.cmp(&b.metadata.num_params) {
@@ -445,30 +498,46 @@ impl Module {
             },
             o => o,
         });
+        func_infos
+    }
+}
 
-        for f in func_infos {
-            if !first {
-                writer.write_str("\n\n")?;
-            }
-            first = false;
+impl FuncInfo {
+    /// Get the name and type of a parameter.
+    #[must_use]
+    fn def_param<'a>(&'a self, index: usize, engine: &'a Engine) -> (&'a str, Cow<'a, str>) {
+        self.metadata
+            .params_info
+            .get(index)
+            .map_or(("_", "?".into()), |s| {
+                let mut s = s.splitn(2, ':');
+                (
+                    s.next().unwrap_or("_").split(' ').last().unwrap(),
+                    s.next()
+                        .map_or(Cow::Borrowed("?"), |ty| def_type_name(ty, engine)),
+                )
+            })
+    }
 
-            if f.metadata.access != FnAccess::Private {
-                let operator =
-                    !f.metadata.name.contains('$') && !is_valid_function_name(&f.metadata.name);
+    /// Output definitions for a function inside a [`Module`], unless it is private.
+    fn write_module_definition(
+        &self,
+        writer: &mut dyn fmt::Write,
+        def: &Definitions,
+    ) -> fmt::Result {
+        if self.metadata.access == FnAccess::Private {
+            return Ok(());
+        }
 
-                #[cfg(not(feature = "no_custom_syntax"))]
-                let operator =
-                    operator || def.engine.custom_keywords.contains_key(&f.metadata.name);
+        let operator =
+            !self.metadata.name.contains('$') && !is_valid_function_name(&self.metadata.name);
 
-                f.write_definition(writer, def, operator)?;
-            }
-        }
+        #[cfg(not(feature = "no_custom_syntax"))]
+        let operator = operator || def.engine.custom_keywords.contains_key(&self.metadata.name);
 
-        Ok(())
+        self.write_definition(writer, def, operator)
     }
-}
 
-impl FuncInfo {
     /// Output definitions for a function.
     fn write_definition(
         &self,
@@ -501,18 +570,7 @@ impl FuncInfo {
             }
             first = false;
 
-            let (param_name, param_type) =
-                self.metadata
-                    .params_info
-                    .get(i)
-                    .map_or(("_", "?".into()), |s| {
-                        let mut s = s.splitn(2, ':');
-                        (
-                            s.next().unwrap_or("_").split(' ').last().unwrap(),
-                            s.next()
-                                .map_or(Cow::Borrowed("?"), |ty| def_type_name(ty, def.engine)),
-                        )
-                    });
+            let (param_name, param_type) = self.def_param(i, def.engine);
 
             if operator {
                 write!(writer, "{param_type}")?;
@@ -578,6 +636,36 @@ fn def_type_name<'a>(ty: &'a str, engine: &'a Engine) -> Cow<'a, str> {
     ty.into()
 }
 
+/// Map the type of a value to the type name used in definitions.
+///
+/// Custom types map to the names they are registered with.
+fn def_value_type_name<'a>(value: &Dynamic, engine: &'a Engine) -> Cow<'a, str> {
+    match value.type_name() {
+        "string" => "String".into(),
+        "array" => "Array".into(),
+        "blob" => "Blob".into(),
+        "map" => "Map".into(),
+        "Fn" => "FnPtr".into(),
+        "timestamp" => "Instant".into(),
+        "decimal" => "Decimal".into(),
+        "<shared>" => "?".into(),
+        ty => def_type_name(ty, engine),
+    }
+}
+
+/// Return the value of a constant as a literal, if it is an integer, a string or a boolean.
+fn def_literal(value: &Dynamic) -> Option<String> {
+    if let Ok(n) = value.as_int() {
+        Some(n.to_string())
+    } else if let Ok(b) = value.as_bool() {
+        Some(b.to_string())
+    } else {
+        value
+            .read_lock::<ImmutableString>()
+            .map(|s| format!("{:?}", s.as_str()))
+    }
+}
+
 impl Scope<'_> {
     /// _(metadata, internals)_ Return definitions for all items inside the [`Scope`].
     fn write_definition(&self, writer: &mut dyn fmt::Write, def: &Definitions) -> fmt::Result {
@@ -588,8 +676,14 @@ impl Scope<'_> {
             }
             first = false;
 
+            if constant {
+                if let Some(literal) = def_literal(value) {
+                    writeln!(writer, "/// Value: `{literal}`")?;
+                }
+            }
+
             let kw = if constant { Token::Const } else { Token::Let };
-            let ty = def_type_name(value.type_name(), def.engine);
+            let ty = def_value_type_name(value, def.engine);
 
             write!(writer, "{kw} {name}: {ty};")?;
         }
@@ -0,0 +1,466 @@
+//! Module that defines functions to output TypeScript definition files for [`Engine`].
+
+use super::{def_literal, def_type_name, def_value_type_name, Definitions};
+use crate::module::{FuncInfo, ModuleFlags};
+use crate::tokenizer::is_valid_function_name;
+use crate::{Engine, FnAccess, Module, SmartString};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::{self, Write},
+};
+
+/// Indentation of members inside an `interface` or a `namespace`.
+const INDENT: &str = "    ";
+
+/// Getter type and setter type (if any) of a property.
+type TsProperty<'a> = (Option<Cow<'a, str>>, Option<Cow<'a, str>>);
+
+/// A TypeScript `interface` stub for a custom type.
+#[derive(Debug, Default)]
+struct TsInterface<'a> {
+    /// Doc-comments of the custom type.
+    comments: &'a [SmartString],
+    /// Properties.
+    properties: BTreeMap<&'a str, TsProperty<'a>>,
+    /// Methods and operators.
+    methods: Vec<String>,
+}
+
+impl Definitions<'_> {
+    /// Output all TypeScript definition files returned from
+    /// [`iter_ts_files`][Definitions::iter_ts_files] to a specified directory.
+    ///
+    /// This function creates the directories and overrides any existing files if needed.
+    #[cfg(not(feature = "no_std"))]
+    #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
+    #[inline]
+    pub fn write_to_dir_ts(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        use std::fs;
+
+        let path = path.as_ref();
+
+        fs::create_dir_all(path)?;
+
+        for (file_name, content) in self.iter_ts_files() {
+            fs::write(path.join(file_name), content)?;
+        }
+
+        Ok(())
+    }
+
+    /// Return all definitions merged into a single TypeScript (`.d.ts`) file.
+    ///
+    /// Rhai types are mapped to TypeScript types (e.g. `INT` to `number`, `Array` to `unknown[]`),
+    /// custom types are declared as `interface` stubs (with their getters/setters as properties
+    /// and their methods and operators as methods) and modules are declared as namespaces.
+    /// Doc-comments are written as JSDoc comments.
+    #[must_use]
+    pub fn ts_format(&self) -> String {
+        let mut def_file = self.ts_static_module();
+
+        #[cfg(not(feature = "no_module"))]
+        for (_, module_def) in self.ts_modules() {
+            def_file += "\n";
+            def_file += &module_def;
+        }
+
+        if self.scope.is_some() {
+            def_file += "\n";
+            def_file += &self.ts_scope_items();
+        }
+
+        def_file
+    }
+
+    /// Iterate over generated TypeScript definition files.
+    ///
+    /// The returned iterator yields all definition files as (filename, content) pairs.
+    #[inline]
+    pub fn iter_ts_files(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        std::iter::once(("__static__.d.ts".to_string(), self.ts_static_module()))
+            .chain(
+                self.scope
+                    .iter()
+                    .map(move |_| ("__scope__.d.ts".to_string(), self.ts_scope_items())),
+            )
+            .chain(
+                #[cfg(not(feature = "no_module"))]
+                {
+                    self.ts_modules()
+                        .map(|(name, def)| (format!("{name}.d.ts"), def))
+                },
+                #[cfg(feature = "no_module")]
+                {
+                    std::iter::empty()
+                },
+            )
+    }
+
+    /// Get all global modules to include.
+    fn ts_global_modules(&self) -> impl Iterator<Item = &Module> {
+        let exclude_flags = if self.config.include_standard_packages {
+            ModuleFlags::empty()
+        } else {
+            ModuleFlags::STANDARD_LIB
+        };
+
+        self.engine
+            .global_modules
+            .iter()
+            .filter(move |m| !m.flags.contains(exclude_flags))
+            .map(|m| &**m)
+    }
+
+    /// Return TypeScript definitions for all custom types and all globally available functions
+    /// and constants.
+    #[must_use]
+    fn ts_static_module(&self) -> String {
+        let engine = self.engine;
+
+        // Custom types
+        let mut interfaces = BTreeMap::<&str, TsInterface>::new();
+
+        let custom_types = self.ts_global_modules().flat_map(Module::iter_custom_types);
+        #[cfg(not(feature = "no_module"))]
+        let custom_types = custom_types.chain(
+            engine
+                .global_sub_modules
+                .values()
+                .flat_map(|m| m.iter_custom_types()),
+        );
+
+        for (_, info) in custom_types {
+            interfaces
+                .entry(info.display_name.as_str())
+                .or_default()
+                .comments = &info.comments[..];
+        }
+
+        for m in self.ts_global_modules() {
+            m.collect_ts_members(&mut interfaces, engine, false);
+        }
+        #[cfg(not(feature = "no_module"))]
+        for m in engine.global_sub_modules.values() {
+            m.collect_ts_members(&mut interfaces, engine, true);
+        }
+
+        let mut s = String::new();
+
+        for (name, interface) in interfaces {
+            write_ts_comments(&mut s, interface.comments, "").unwrap();
+            writeln!(s, "declare interface {name} {{").unwrap();
+
+            for (property, (getter, setter)) in interface.properties {
+                let readonly = if setter.is_none() { "readonly " } else { "" };
+                let ty = getter.or(setter).unwrap();
+                writeln!(s, "{INDENT}{readonly}{property}: {ty};").unwrap();
+            }
+            for method in interface.methods {
+                s += &method;
+            }
+
+            s += "}\n\n";
+        }
+
+        // Global constants and functions
+        for m in self.ts_global_modules() {
+            m.write_ts_items(&mut s, engine, "declare ", "").unwrap();
+        }
+
+        s
+    }
+
+    /// Return TypeScript definitions for all items inside the [`Scope`][crate::Scope], if any.
+    #[must_use]
+    fn ts_scope_items(&self) -> String {
+        let mut s = String::new();
+
+        if let Some(scope) = self.scope {
+            for (name, constant, value) in scope.iter_raw() {
+                if constant {
+                    if let Some(literal) = def_literal(value) {
+                        let comment = format!("Value: `{literal}`");
+                        write_ts_comments(&mut s, &[comment.into()], "").unwrap();
+                    }
+                }
+
+                let kw = if constant { "const" } else { "let" };
+                let ty = def_value_type_name(value, self.engine);
+                let ty = ts_type_name(&ty, self.engine);
+
+                writeln!(s, "declare {kw} {name}: {ty};").unwrap();
+            }
+        }
+
+        s
+    }
+
+    /// Return a (module name, TypeScript definitions) pair for each registered static
+    /// [module][Module], declared as a namespace.
+    #[cfg(not(feature = "no_module"))]
+    fn ts_modules(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        let mut m = self
+            .engine
+            .global_sub_modules
+            .iter()
+            .map(|(name, module)| {
+                let mut s = String::new();
+                module
+                    .write_ts_namespace(&mut s, name, self.engine, "declare ", "")
+                    .unwrap();
+                (name.to_string(), s)
+            })
+            .collect::<Vec<_>>();
+
+        m.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+
+        m.into_iter()
+    }
+}
+
+impl Module {
+    /// Collect getters, setters, methods and operators of custom types inside the [`Module`],
+    /// optionally only functions in the global namespace.
+    fn collect_ts_members<'a>(
+        &'a self,
+        interfaces: &mut BTreeMap<&'a str, TsInterface<'a>>,
+        engine: &'a Engine,
+        global_only: bool,
+    ) {
+        for f in self.sorted_functions() {
+            let metadata = &f.metadata;
+
+            if metadata.access == FnAccess::Private
+                || metadata.num_params == 0
+                || (global_only && !metadata.namespace.is_global_namespace())
+            {
+                continue;
+            }
+
+            let (_, this_type) = f.def_param(0, engine);
+
+            let interface = match interfaces.get_mut(&*this_type) {
+                Some(interface) => interface,
+                None => continue,
+            };
+
+            if let Some(name) = metadata.name.strip_prefix("get$") {
+                let ty = ts_type_name(&metadata.return_type, engine);
+                interface.properties.entry(name).or_default().0 = Some(ty);
+            } else if let Some(name) = metadata.name.strip_prefix("set$") {
+                let (_, ty) = f.def_param(1, engine);
+                let ty = ts_type_name(&ty, engine).into_owned().into();
+                interface.properties.entry(name).or_default().1 = Some(ty);
+            } else if !metadata.name.contains('$') {
+                let mut method = String::new();
+                write_ts_comments(&mut method, &metadata.comments, INDENT).unwrap();
+
+                if is_valid_function_name(&metadata.name) {
+                    write!(method, "{INDENT}{}", metadata.name).unwrap();
+                } else {
+                    write!(method, "{INDENT}\"{}\"", metadata.name).unwrap();
+                }
+                f.write_ts_signature(&mut method, engine, 1).unwrap();
+                method += ";\n";
+
+                interface.methods.push(method);
+            }
+        }
+        #[cfg(not(feature = "no_module"))]
+        for (_, m) in self.iter_sub_modules() {
+            m.collect_ts_members(interfaces, engine, true);
+        }
+    }
+
+    /// Output TypeScript definitions for all constants and functions inside the [`Module`].
+    fn write_ts_items(
+        &self,
+        writer: &mut dyn fmt::Write,
+        engine: &Engine,
+        prefix: &str,
+        indent: &str,
+    ) -> fmt::Result {
+        let mut vars = self.iter_var().collect::<Vec<_>>();
+        vars.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (name, value) in vars {
+            let ty = ts_type_name(value.type_name(), engine);
+            writeln!(writer, "{indent}{prefix}const {name}: {ty};")?;
+        }
+
+        for f in self.sorted_functions() {
+            let name = &f.metadata.name;
+
+            // Getters, setters and operators are not functions in TypeScript
+            if f.metadata.access == FnAccess::Private
+                || name.contains('$')
+                || !is_valid_function_name(name)
+            {
+                continue;
+            }
+
+            write_ts_comments(writer, &f.metadata.comments, indent)?;
+            write!(writer, "{indent}{prefix}function {name}")?;
+            f.write_ts_signature(writer, engine, 0)?;
+            writer.write_str(";\n")?;
+        }
+
+        Ok(())
+    }
+
+    /// Output TypeScript definitions for the [`Module`] as a namespace.
+    #[cfg(not(feature = "no_module"))]
+    fn write_ts_namespace(
+        &self,
+        writer: &mut dyn fmt::Write,
+        name: &str,
+        engine: &Engine,
+        prefix: &str,
+        indent: &str,
+    ) -> fmt::Result {
+        writeln!(writer, "{indent}{prefix}namespace {name} {{")?;
+
+        let inner = format!("{indent}{INDENT}");
+
+        let mut submodules = self.iter_sub_modules().collect::<Vec<_>>();
+        submodules.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (submodule_name, submodule) in submodules {
+            submodule.write_ts_namespace(writer, submodule_name, engine, "", &inner)?;
+        }
+
+        self.write_ts_items(writer, engine, "", &inner)?;
+
+        writeln!(writer, "{indent}}}")
+    }
+}
+
+impl FuncInfo {
+    /// Output the TypeScript parameters and return type of a function, skipping a number of
+    /// leading parameters.
+    fn write_ts_signature(
+        &self,
+        writer: &mut dyn fmt::Write,
+        engine: &Engine,
+        skip: usize,
+    ) -> fmt::Result {
+        writer.write_str("(")?;
+
+        for i in skip..self.metadata.num_params {
+            if i > skip {
+                writer.write_str(", ")?;
+            }
+
+            let (name, ty) = self.def_param(i, engine);
+            let ty = ts_type_name(&ty, engine);
+
+            if name == "_" {
+                write!(writer, "arg{i}: {ty}")?;
+            } else {
+                write!(writer, "{name}: {ty}")?;
+            }
+        }
+
+        write!(
+            writer,
+            "): {}",
+            ts_type_name(&self.metadata.return_type, engine)
+        )
+    }
+}
+
+/// Map a Rhai type to a TypeScript type.
+///
+/// Custom types keep their names (they are declared as `interface` stubs) while unknown types
+/// are mapped to `unknown`.
+#[must_use]
+fn ts_type_name<'a>(ty: &'a str, engine: &'a Engine) -> Cow<'a, str> {
+    let ty = def_type_name(ty, engine);
+
+    let ts_type = match &*ty {
+        "" | "()" => "void",
+        "?" => "unknown",
+        "bool" => "boolean",
+        "int" | "float" | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16"
+        | "u32" | "u64" | "u128" | "usize" | "f32" | "f64" => "number",
+        "String" | "char" => "string",
+        "Array" => "unknown[]",
+        "Blob" => "Uint8Array",
+        "Map" => "Record<string, unknown>",
+        "FnPtr" => "Function",
+        name if is_custom_type(name, engine) => return ty,
+        _ => "unknown",
+    };
+
+    ts_type.into()
+}
+
+/// Is a type name the display name of a registered custom type?
+#[must_use]
+fn is_custom_type(name: &str, engine: &Engine) -> bool {
+    let found = engine
+        .global_modules
+        .iter()
+        .flat_map(|m| m.iter_custom_types())
+        .any(|(_, info)| info.display_name == name);
+
+    #[cfg(not(feature = "no_module"))]
+    let found = found
+        || engine
+            .global_sub_modules
+            .values()
+            .flat_map(|m| m.iter_custom_types())
+            .any(|(_, info)| info.display_name == name);
+
+    found
+}
+
+/// Output doc-comments as a JSDoc comment.
+fn write_ts_comments(
+    writer: &mut dyn fmt::Write,
+    comments: &[SmartString],
+    indent: &str,
+) -> fmt::Result {
+    let mut lines = Vec::new();
+
+    for comment in comments {
+        if let Some(block) = comment.strip_prefix("/**") {
+            let block = block.strip_suffix("*/").unwrap_or(block);
+
+            lines.extend(block.lines().map(|line| {
+                let line = line.trim();
+                let line = line.strip_prefix('*').unwrap_or(line);
+                line.strip_prefix(' ').unwrap_or(line)
+            }));
+        } else {
+            lines.extend(comment.lines().map(|line| {
+                let line = line.trim_start().trim_start_matches('/');
+                line.strip_prefix(' ').unwrap_or(line)
+            }));
+        }
+    }
+
+    while lines.first().map_or(false, |line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    while lines.last().map_or(false, |line| line.trim().is_empty()) {
+        lines.pop();
+    }
+
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "{indent}/**")?;
+    for line in lines {
+        if line.trim().is_empty() {
+            writeln!(writer, "{indent} *")?;
+        } else {
+            writeln!(writer, "{indent} * {}", line.trim_end())?;
+        }
+    }
+    writeln!(writer, "{indent} */")
+}
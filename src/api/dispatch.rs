@@ -0,0 +1,109 @@
+//! Module that defines the dynamic dispatch API of [`Engine`].
+
+use crate::{Engine, Identifier};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Does a function name match an allowlist pattern?
+///
+/// The wildcard `*` in a pattern matches any sequence of characters (including none).
+/// A pattern without wildcards must match the function name exactly.
+#[must_use]
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.as_bytes(), name.as_bytes());
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&ch) if ch == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            // Let the last wildcard swallow one more character
+            _ => match backtrack {
+                Some((bp, bn)) => {
+                    backtrack = Some((bp, bn + 1));
+                    p = bp + 1;
+                    n = bn + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&ch| ch == b'*')
+}
+
+impl Engine {
+    /// Set the allowlist of function names that scripts can call via dynamic dispatch.
+    ///
+    /// Each pattern is either an exact function name or a glob pattern where `*` matches any
+    /// sequence of characters (e.g. `handle_*`).
+    ///
+    /// Once an allowlist is set, scripts can call `dispatch(prefix, suffix, args...)`, which calls
+    /// the function named `prefix + suffix` with the arguments `args`.
+    ///
+    /// Calling a function whose name is not in the allowlist raises
+    /// [`ErrorDispatchNotAllowed`][crate::EvalAltResult::ErrorDispatchNotAllowed].
+    /// Calling a function that is in the allowlist but does not exist raises
+    /// [`ErrorFunctionNotFound`][crate::EvalAltResult::ErrorFunctionNotFound] as usual.
+    ///
+    /// The result of checking a function name against the allowlist is cached for the duration
+    /// of each run.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, EvalAltResult};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_fn("handle_click", |x: i64| x * 2);
+    /// engine.register_fn("secret", |x: i64| x);
+    ///
+    /// engine.set_dynamic_dispatch_allowlist(["handle_*"]);
+    ///
+    /// assert_eq!(engine.eval::<i64>(r#"dispatch("handle_", "click", 21)"#)?, 42);
+    ///
+    /// let err = engine.eval::<i64>(r#"dispatch("sec", "ret", 21)"#).unwrap_err();
+    ///
+    /// assert!(matches!(*err, EvalAltResult::ErrorDispatchNotAllowed(..)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn set_dynamic_dispatch_allowlist(
+        &mut self,
+        patterns: impl IntoIterator<Item = impl Into<Identifier>>,
+    ) -> &mut Self {
+        self.dispatch_allowlist = Some(patterns.into_iter().map(Into::into).collect());
+        self
+    }
+    /// Remove the allowlist of function names for dynamic dispatch.
+    ///
+    /// This disables `dispatch`, which then resolves like any other function call.
+    #[inline(always)]
+    pub fn clear_dynamic_dispatch_allowlist(&mut self) -> &mut Self {
+        self.dispatch_allowlist = None;
+        self
+    }
+    /// Is dynamic dispatch enabled (i.e. an allowlist is set)?
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_dynamic_dispatch_enabled(&self) -> bool {
+        self.dispatch_allowlist.is_some()
+    }
+    /// Is a function name allowed to be called via dynamic dispatch?
+    #[must_use]
+    pub(crate) fn is_dispatch_allowed(&self, name: &str) -> bool {
+        self.dispatch_allowlist.as_ref().map_or(false, |list| {
+            list.iter().any(|pattern| matches_pattern(pattern, name))
+        })
+    }
+}
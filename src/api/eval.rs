@@ -6,13 +6,10 @@ use crate::parser::ParseState;
 use crate::tokenizer::lex_raw;
 use crate::types::dynamic::Variant;
 use crate::types::StringsInterner;
-use crate::{Dynamic, Engine, Position, RhaiResult, RhaiResultOf, Scope, AST, ERR};
+use crate::{Dynamic, Engine, RhaiResult, RhaiResultOf, Scope, AST};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{
-    any::{type_name, TypeId},
-    mem,
-};
+use std::{any::TypeId, mem};
 
 impl Engine {
     /// Evaluate a string as a script, returning the result value or an error.
@@ -208,19 +205,7 @@ impl Engine {
             return Ok(reify! { result => T });
         }
 
-        result.try_cast_raw::<T>().map_err(|v| {
-            let typename = match type_name::<T>() {
-                typ if typ.contains("::") => self.map_type_name(typ),
-                typ => typ,
-            };
-
-            ERR::ErrorMismatchOutputType(
-                typename.into(),
-                self.map_type_name(v.type_name()).into(),
-                Position::NONE,
-            )
-            .into()
-        })
+        self.cast_dynamic(result)
     }
     /// Evaluate an [`AST`] with own scope, returning the result value or an error.
     #[inline]
@@ -232,6 +217,12 @@ impl Engine {
         ast: &AST,
     ) -> RhaiResult {
         let orig_source = mem::replace(&mut global.source, ast.source_raw().cloned());
+        let orig_node_ids = mem::replace(
+            &mut global.node_ids,
+            self.stmt_tracer
+                .is_some()
+                .then(|| (ast.source_raw().cloned(), ast.node_ids.clone())),
+        );
 
         #[cfg(not(feature = "no_function"))]
         let orig_lib_len = global.lib.len();
@@ -253,6 +244,7 @@ impl Engine {
             g.lib.truncate(orig_lib_len);
 
             g.source = orig_source;
+            g.node_ids = orig_node_ids;
         }}
 
         let r = self.eval_global_statements(global, caches, scope, ast.statements(), true)?;
@@ -260,7 +252,7 @@ impl Engine {
         #[cfg(feature = "debugging")]
         if self.is_debugger_registered() {
             global.debugger_mut().status = crate::eval::DebuggerStatus::Terminate;
-            let node = &crate::ast::Stmt::Noop(Position::NONE);
+            let node = &crate::ast::Stmt::Noop(crate::Position::NONE);
             self.run_debugger(global, caches, scope, None, node)?;
         }
 
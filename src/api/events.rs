@@ -1,7 +1,10 @@
 //! Module that defines public event handlers for [`Engine`].
 
 use crate::func::SendSync;
-use crate::{Dynamic, Engine, EvalContext, Position, RhaiResultOf, VarDefInfo};
+use crate::{
+    Dynamic, Engine, EvalContext, FnCallInfo, Position, RhaiResultOf, VarChainSegment, VarDefInfo,
+    VarWrite,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -66,6 +69,142 @@ impl Engine {
         self.resolve_var = Some(Box::new(callback));
         self
     }
+    /// Provide a callback that will be invoked before each variable access, with the
+    /// statically-known segments of any property/index access chain on the variable.
+    ///
+    /// This allows a host to serve, say, `user.address.city` directly without constructing the
+    /// intermediate `user` and `address` values.
+    ///
+    /// This callback is invoked before the one registered via [`on_var`][Engine::on_var].
+    ///
+    /// # WARNING - Unstable API
+    ///
+    /// This API is volatile and may change in the future.
+    ///
+    /// # Callback Function Signature
+    ///
+    /// `Fn(name: &str, index: usize, chain: &[VarChainSegment], context: EvalContext) -> Result<Option<(Dynamic, usize)>, Box<EvalAltResult>>`
+    ///
+    /// where:
+    /// * `name`: name of the variable.
+    /// * `index`: an offset from the bottom of the current [`Scope`][crate::Scope] that the
+    ///   variable is supposed to reside (see [`on_var`][Engine::on_var]).
+    /// * `chain`: the leading segments (property names and literal indices) of the property/index
+    ///   access chain when the variable is read as the root of such a chain, stopping at the first
+    ///   segment that is not statically known (e.g. a method call or a computed index).
+    ///   Empty for plain variable access and for assignments.
+    /// * `context`: the current [evaluation context][`EvalContext`].
+    ///
+    /// ## Return value
+    ///
+    /// * `Ok(None)`: continue with normal variable access.
+    /// * `Ok(Some((Dynamic, usize)))`: the value together with the number of leading segments in
+    ///   `chain` that it has consumed. Zero means the value of the variable itself.
+    ///   The remainder of the chain is evaluated normally on the value.
+    ///
+    /// ## Raising errors
+    ///
+    /// Return `Err(...)` if there is an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, VarChainSegment};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Register a variable resolver that serves `user.name` directly.
+    /// engine.on_var_chain(|name, _, chain, _| {
+    ///     match (name, chain) {
+    ///         ("user", [VarChainSegment::Property("name"), ..]) => Ok(Some(("Bob".into(), 1))),
+    ///         _ => Ok(None)
+    ///     }
+    /// });
+    ///
+    /// # #[cfg(not(feature = "no_object"))]
+    /// assert_eq!(engine.eval::<String>("user.name.to_upper()")?, "BOB");
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[deprecated = "This API is NOT deprecated, but it is considered volatile and may change in the future."]
+    #[inline(always)]
+    pub fn on_var_chain(
+        &mut self,
+        callback: impl Fn(
+                &str,
+                usize,
+                &[VarChainSegment],
+                EvalContext,
+            ) -> RhaiResultOf<Option<(Dynamic, usize)>>
+            + SendSync
+            + 'static,
+    ) -> &mut Self {
+        self.resolve_var_chain = Some(Box::new(callback));
+        self
+    }
+    /// Provide a callback that will be invoked before each write to a variable, including
+    /// op-assignments and assignments to properties or indexed elements of the variable.
+    ///
+    /// Writes to `this` are also intercepted, with `this` as the name of the variable.
+    ///
+    /// # WARNING - Unstable API
+    ///
+    /// This API is volatile and may change in the future.
+    ///
+    /// # Callback Function Signature
+    ///
+    /// `Fn(name: &str, write: VarWrite, pos: Position, context: EvalContext) -> Result<(), Box<EvalAltResult>>`
+    ///
+    /// where:
+    /// * `name`: name of the variable.
+    /// * `write`: the kind of write together with the new value or, for op-assignments, the operator
+    ///   and the right-hand-side value.
+    /// * `pos`: position of the variable.
+    /// * `context`: the current [evaluation context][`EvalContext`].
+    ///
+    /// ## Return value
+    ///
+    /// * `Ok(())`: continue with the write.
+    ///
+    /// ## Raising errors
+    ///
+    /// Return `Err(...)` to veto the write. The variable is not modified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, VarWrite};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Only allow adding to `total`.
+    /// engine.on_var_write(|name, write, _, _| match (name, write) {
+    ///     ("total", VarWrite::OpAssign("+=", _)) => Ok(()),
+    ///     ("total", _) => Err(format!("cannot modify total: {name}").into()),
+    ///     _ => Ok(())
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<i64>("let total = 1; total += 41; total")?, 42);
+    ///
+    /// assert!(engine.eval::<i64>("let total = 1; total = 0; total").is_err());
+    ///
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[deprecated = "This API is NOT deprecated, but it is considered volatile and may change in the future."]
+    #[inline(always)]
+    pub fn on_var_write(
+        &mut self,
+        callback: impl Fn(&str, VarWrite, Position, EvalContext) -> RhaiResultOf<()>
+            + SendSync
+            + 'static,
+    ) -> &mut Self {
+        self.var_write_filter = Some(Box::new(callback));
+        self
+    }
     /// Provide a callback that will be invoked before the definition of each variable .
     ///
     /// # WARNING - Unstable API
@@ -246,6 +385,30 @@ impl Engine {
         self.progress = Some(Box::new(callback));
         self
     }
+    /// Set the increment by which the `sleep` function sleeps before calling the progress callback
+    /// registered via [`on_progress`][Engine::on_progress] (default 50 milliseconds), so that a
+    /// sleeping script can be terminated promptly.
+    ///
+    /// A zero duration makes `sleep` block for the full duration without calling the callback.
+    ///
+    /// Not available under `unchecked` or `no_std`.
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(not(feature = "no_std"))]
+    #[inline(always)]
+    pub fn set_sleep_granularity(&mut self, granularity: std::time::Duration) -> &mut Self {
+        self.sleep_granularity = granularity;
+        self
+    }
+    /// The increment by which the `sleep` function sleeps before calling the progress callback.
+    ///
+    /// Not available under `unchecked` or `no_std`.
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(not(feature = "no_std"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn sleep_granularity(&self) -> std::time::Duration {
+        self.sleep_granularity
+    }
     /// Override default action of `print` (print to stdout using [`println!`])
     ///
     /// # Example
@@ -325,6 +488,145 @@ impl Engine {
         self.debug = Some(Box::new(callback));
         self
     }
+    /// Register a callback for tracing function calls.
+    ///
+    /// The callback is called before each call to a script-defined or native Rust function,
+    /// except for built-in operators on standard types, which are evaluated inline.
+    ///
+    /// # Callback Function Signature
+    ///
+    /// `Fn(info: &FnCallInfo)`
+    ///
+    /// where:
+    /// * `info`: a [`FnCallInfo`] containing information on the function call
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::sync::RwLock;
+    /// # use std::sync::Arc;
+    /// use rhai::Engine;
+    ///
+    /// let calls = Arc::new(RwLock::new(Vec::new()));
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// let log = calls.clone();
+    /// engine.on_fn_call(move |info| log.write().unwrap().push(
+    ///                     format!("{}:{}", info.name(), info.level())
+    ///                  ));
+    ///
+    /// engine.run(r#"let x = "hello"; x.to_upper().len();"#)?;
+    ///
+    /// assert_eq!(*calls.read().unwrap(), ["to_upper:1", "len:1"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn on_fn_call(&mut self, callback: impl Fn(&FnCallInfo) + SendSync + 'static) -> &mut Self {
+        self.fn_call_tracer = Some(Box::new(callback));
+        #[cfg(not(feature = "no_time"))]
+        {
+            self.time_fn_calls = false;
+        }
+        self
+    }
+    /// Register a callback for tracing and timing function calls.
+    /// Not available under `no_time`.
+    ///
+    /// This is the same as [`on_fn_call`][Engine::on_fn_call], except that the callback is also
+    /// called after each function returns, with the time taken by the call available via
+    /// [`FnCallInfo::elapsed`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::sync::RwLock;
+    /// # use std::sync::Arc;
+    /// use rhai::Engine;
+    ///
+    /// let timed = Arc::new(RwLock::new(Vec::new()));
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// let log = timed.clone();
+    /// engine.on_fn_call_timed(move |info| {
+    ///     if info.elapsed().is_some() {
+    ///         log.write().unwrap().push(info.name().to_string());
+    ///     }
+    /// });
+    ///
+    /// engine.run(r#"let x = "hello"; x.to_upper().len();"#)?;
+    ///
+    /// assert_eq!(*timed.read().unwrap(), ["to_upper", "len"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_time"))]
+    #[inline(always)]
+    pub fn on_fn_call_timed(
+        &mut self,
+        callback: impl Fn(&FnCallInfo) + SendSync + 'static,
+    ) -> &mut Self {
+        self.fn_call_tracer = Some(Box::new(callback));
+        self.time_fn_calls = true;
+        self
+    }
+    /// Register a callback for tracing the evaluation of statements, e.g. to collect coverage
+    /// (see [`CoverageCollector`][crate::coverage::CoverageCollector]).
+    ///
+    /// The callback is called once before each statement is evaluated.
+    /// There is no overhead other than a single check when no callback is registered.
+    ///
+    /// # Callback Function Signature
+    ///
+    /// `Fn(source: Option<&str>, pos: Position, id: u32)`
+    ///
+    /// where:
+    /// * `source`: the source of the statement, if any
+    /// * `pos`: position of the statement
+    /// * `id`: the node ID of the statement if the [`AST`][crate::AST] being run is compiled with
+    ///   [`set_assign_node_ids`][Engine::set_assign_node_ids] turned on, otherwise zero
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # use std::sync::RwLock;
+    /// # use std::sync::Arc;
+    /// use rhai::Engine;
+    ///
+    /// let lines = Arc::new(RwLock::new(Vec::new()));
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// let log = lines.clone();
+    /// engine.on_statement(move |_, pos, _| log.write().unwrap().push(pos.line().unwrap()));
+    ///
+    /// engine.run(
+    /// "
+    ///     let x = 42;
+    ///     if x > 0 {
+    ///         x += 1;
+    ///     } else {
+    ///         x -= 1;
+    ///     }
+    /// ")?;
+    ///
+    /// assert_eq!(*lines.read().unwrap(), [2, 3, 4]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn on_statement(
+        &mut self,
+        callback: impl Fn(Option<&str>, Position, u32) + SendSync + 'static,
+    ) -> &mut Self {
+        self.stmt_tracer = Some(Box::new(callback));
+        self
+    }
     /// _(debugging)_ Register a callback for debugging.
     /// Exported under the `debugging` feature only.
     ///
@@ -13,25 +13,37 @@ use std::{
 };
 
 impl Engine {
-    /// Read the contents of a file into a string.
-    fn read_file(path: impl AsRef<Path>) -> RhaiResultOf<String> {
-        let path = path.as_ref();
+    /// Read a script from a reader into a string, up to the
+    /// [maximum script file size][Engine::max_script_file_size] (if any).
+    ///
+    /// `name` describes the script in error messages.
+    fn read_script(&self, reader: impl Read, name: &str) -> RhaiResultOf<String> {
+        #[cfg(not(feature = "unchecked"))]
+        let max_size = self
+            .limits
+            .max_script_file_size
+            .map(std::num::NonZeroUsize::get);
+        #[cfg(feature = "unchecked")]
+        let max_size: Option<usize> = None;
 
-        let mut f = File::open(path).map_err(|err| {
-            ERR::ErrorSystem(
-                format!("Cannot open script file '{}'", path.to_string_lossy()),
-                err.into(),
-            )
-        })?;
+        // Read at most one byte beyond the limit, so a script that is too large is detected
+        // without reading all of it
+        let limit = max_size.map_or(u64::MAX, |n| n as u64 + 1);
+        let mut bytes = Vec::new();
 
-        let mut contents = String::new();
+        reader
+            .take(limit)
+            .read_to_end(&mut bytes)
+            .map_err(|err| ERR::ErrorSystem(format!("Cannot read {name}"), err.into()))?;
 
-        f.read_to_string(&mut contents).map_err(|err| {
-            ERR::ErrorSystem(
-                format!("Cannot read script file '{}'", path.to_string_lossy()),
-                err.into(),
-            )
-        })?;
+        if let Some(max) = max_size {
+            if bytes.len() > max {
+                return Err(Self::script_too_large(name, max));
+            }
+        }
+
+        let mut contents = String::from_utf8(bytes)
+            .map_err(|err| ERR::ErrorSystem(format!("Cannot read {name}"), err.into()))?;
 
         if contents.starts_with("#!") {
             // Remove shebang
@@ -45,6 +57,71 @@ impl Engine {
 
         Ok(contents)
     }
+    /// Create the error for a script that is larger than the maximum script file size.
+    fn script_too_large(name: &str, max: usize) -> Box<ERR> {
+        let err = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("larger than the maximum size of {max} bytes"),
+        );
+        ERR::ErrorSystem(format!("Cannot read {name}"), err.into()).into()
+    }
+    /// Read the contents of a file into a string.
+    ///
+    /// Errors contain the canonicalized path of the file, if available.
+    fn read_file(&self, path: impl AsRef<Path>) -> RhaiResultOf<String> {
+        let path = path.as_ref();
+        let name = format!(
+            "script file '{}'",
+            path.canonicalize()
+                .as_deref()
+                .unwrap_or(path)
+                .to_string_lossy()
+        );
+
+        let f = File::open(path)
+            .map_err(|err| ERR::ErrorSystem(format!("Cannot open {name}"), err.into()))?;
+
+        // Check the size of the file before reading it
+        #[cfg(not(feature = "unchecked"))]
+        if let (Some(max), Ok(metadata)) = (self.limits.max_script_file_size, f.metadata()) {
+            if metadata.len() > max.get() as u64 {
+                return Err(Self::script_too_large(&name, max.get()));
+            }
+        }
+
+        self.read_script(f, &name)
+    }
+    /// Compile a script from a reader into an [`AST`], which can be used later for evaluation.
+    ///
+    /// Not available under `no_std` or `WASM`.
+    ///
+    /// `source_name` is set as the source of the [`AST`] and is used in error messages.
+    ///
+    /// The script is read up to the [maximum script file size][Engine::max_script_file_size] (if
+    /// any). A leading shebang line (starting with `#!`) is skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile_reader("40 + 2".as_bytes(), "answer")?;
+    ///
+    /// assert_eq!(ast.source(), Some("answer"));
+    /// assert_eq!(engine.eval_ast::<i64>(&ast)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn compile_reader(&self, reader: impl Read, source_name: &str) -> RhaiResultOf<AST> {
+        let contents = self.read_script(reader, &format!("script '{source_name}'"))?;
+        let mut ast = self.compile(contents)?;
+        ast.set_source(source_name);
+        Ok(ast)
+    }
     /// Compile a script file into an [`AST`], which can be used later for evaluation.
     ///
     /// Not available under `no_std` or `WASM`.
@@ -107,7 +184,7 @@ impl Engine {
     /// ```
     #[inline]
     pub fn compile_file_with_scope(&self, scope: &Scope, path: PathBuf) -> RhaiResultOf<AST> {
-        Self::read_file(&path).and_then(|contents| {
+        self.read_file(&path).and_then(|contents| {
             let mut ast = self.compile_with_scope(scope, contents)?;
             ast.set_source(path.to_string_lossy().as_ref());
             Ok(ast)
@@ -117,6 +194,9 @@ impl Engine {
     ///
     /// Not available under `no_std` or `WASM`.
     ///
+    /// The file path is used as the source of the script, so `import` paths starting with `./` or
+    /// `../` resolve relative to the directory of the script file.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -132,12 +212,15 @@ impl Engine {
     /// ```
     #[inline]
     pub fn eval_file<T: Variant + Clone>(&self, path: PathBuf) -> RhaiResultOf<T> {
-        Self::read_file(path).and_then(|contents| self.eval::<T>(&contents))
+        self.eval_file_with_scope(&mut Scope::new(), path)
     }
     /// Evaluate a script file with own scope, returning the result value or an error.
     ///
     /// Not available under `no_std` or `WASM`.
     ///
+    /// The file path is used as the source of the script, so `import` paths starting with `./` or
+    /// `../` resolve relative to the directory of the script file.
+    ///
     /// ## Constants Propagation
     ///
     /// If not [`OptimizationLevel::None`][crate::OptimizationLevel::None], constants defined within
@@ -168,12 +251,23 @@ impl Engine {
         scope: &mut Scope,
         path: PathBuf,
     ) -> RhaiResultOf<T> {
-        Self::read_file(path).and_then(|contents| self.eval_with_scope(scope, &contents))
+        self.read_file(&path).and_then(|contents| {
+            let mut ast = self.compile_scripts_with_scope_raw(
+                Some(scope),
+                [&contents],
+                self.optimization_level,
+            )?;
+            ast.set_source(path.to_string_lossy().as_ref());
+            self.eval_ast_with_scope(scope, &ast)
+        })
     }
     /// Evaluate a file.
     ///
     /// Not available under `no_std` or `WASM`.
     ///
+    /// The file path is used as the source of the script, so `import` paths starting with `./` or
+    /// `../` resolve relative to the directory of the script file.
+    ///
     /// # Example
     ///
     /// ```no_run
@@ -189,12 +283,15 @@ impl Engine {
     /// ```
     #[inline]
     pub fn run_file(&self, path: PathBuf) -> RhaiResultOf<()> {
-        Self::read_file(path).and_then(|contents| self.run(&contents))
+        self.run_file_with_scope(&mut Scope::new(), path)
     }
     /// Evaluate a file with own scope.
     ///
     /// Not available under `no_std` or `WASM`.
     ///
+    /// The file path is used as the source of the script, so `import` paths starting with `./` or
+    /// `../` resolve relative to the directory of the script file.
+    ///
     /// ## Constants Propagation
     ///
     /// If not [`OptimizationLevel::None`][crate::OptimizationLevel::None], constants defined within
@@ -221,7 +318,15 @@ impl Engine {
     /// ```
     #[inline]
     pub fn run_file_with_scope(&self, scope: &mut Scope, path: PathBuf) -> RhaiResultOf<()> {
-        Self::read_file(path).and_then(|contents| self.run_with_scope(scope, &contents))
+        self.read_file(&path).and_then(|contents| {
+            let mut ast = self.compile_scripts_with_scope_raw(
+                Some(scope),
+                [&contents],
+                self.optimization_level,
+            )?;
+            ast.set_source(path.to_string_lossy().as_ref());
+            self.run_ast_with_scope(scope, &ast)
+        })
     }
 }
 
@@ -239,7 +344,10 @@ impl Engine {
 /// ```
 #[inline]
 pub fn eval_file<T: Variant + Clone>(path: impl AsRef<Path>) -> RhaiResultOf<T> {
-    Engine::read_file(path).and_then(|contents| Engine::new().eval::<T>(&contents))
+    let engine = Engine::new();
+    engine
+        .read_file(path)
+        .and_then(|contents| engine.eval::<T>(&contents))
 }
 
 /// Evaluate a file.
@@ -256,5 +364,8 @@ pub fn eval_file<T: Variant + Clone>(path: impl AsRef<Path>) -> RhaiResultOf<T>
 /// ```
 #[inline]
 pub fn run_file(path: impl AsRef<Path>) -> RhaiResultOf<()> {
-    Engine::read_file(path).and_then(|contents| Engine::new().run(&contents))
+    let engine = Engine::new();
+    engine
+        .read_file(path)
+        .and_then(|contents| engine.run(&contents))
 }
@@ -0,0 +1,738 @@
+//! Module that defines the `format_script` API of [`Engine`].
+
+use crate::parser::{ParseResult, ParseState};
+use crate::tokenizer::{lex_raw, Token};
+use crate::types::StringsInterner;
+use crate::{Engine, SmartString};
+use std::mem;
+use std::ops::Range;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Options for formatting a script via [`Engine::format_script`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct FormatOptions {
+    /// Number of spaces per level of indentation. Default 4.
+    pub indent_width: usize,
+    /// Maximum length of a line before the arguments of a function call (or the items of an array
+    /// or object map literal) are wrapped one per line. Default 100.
+    pub max_line_len: usize,
+    /// Keep comments? Default `true`.
+    ///
+    /// Doc-comments are always kept because they are part of the compiled script.
+    pub preserve_comments: bool,
+}
+
+impl Default for FormatOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatOptions {
+    /// Create a default [`FormatOptions`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            indent_width: 4,
+            max_line_len: 100,
+            preserve_comments: true,
+        }
+    }
+    /// Set the number of spaces per level of indentation.
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_indent_width(mut self, value: usize) -> Self {
+        self.indent_width = value;
+        self
+    }
+    /// Set the maximum length of a line before arguments are wrapped one per line.
+    #[inline(always)]
+    #[must_use]
+    pub const fn with_max_line_len(mut self, value: usize) -> Self {
+        self.max_line_len = value;
+        self
+    }
+    /// Set whether to keep comments.
+    #[inline(always)]
+    #[must_use]
+    pub const fn preserve_comments(mut self, value: bool) -> Self {
+        self.preserve_comments = value;
+        self
+    }
+}
+
+/// A comment in the script, attached to the token following it.
+#[derive(Debug)]
+struct Comment {
+    /// Text of the comment.
+    text: String,
+    /// Is the comment on the same line as the token (or comment) before it?
+    trailing: bool,
+    /// Is there an empty line before the comment?
+    blank_before: bool,
+}
+
+/// A token in the script, together with its original text and the comments before it.
+#[derive(Debug)]
+struct Item {
+    /// The token.
+    token: Token,
+    /// Original text of the token.
+    text: SmartString,
+    /// Comments before the token.
+    comments: Vec<Comment>,
+    /// Is there an empty line before the token (after any comments)?
+    blank_before: bool,
+    /// Index of the matching closing bracket, if this is an opening bracket.
+    close: Option<usize>,
+}
+
+/// Whitespace to write before the next piece of text.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum Gap {
+    /// Nothing.
+    None,
+    /// A single space.
+    Space,
+    /// A new line.
+    Line,
+    /// An empty line.
+    BlankLine,
+}
+
+/// How a token relates to the token after it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Role {
+    /// Any other token.
+    Other,
+    /// End of an operand, so that a following `(` or `[` is a call or an index.
+    Operand,
+    /// A token that is immediately followed by the next token, e.g. `(` or `.`.
+    Open,
+    /// A unary operator.
+    Unary,
+    /// The `|` starting the parameters of a closure.
+    ClosureOpen,
+}
+
+/// Layout of a piece of code.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Mode {
+    /// Lists are wrapped if they do not fit, blocks span multiple lines.
+    Normal,
+    /// Lists are kept on one line, blocks span multiple lines.
+    Flat,
+    /// Everything is kept on one line (e.g. within an interpolated string).
+    Inline,
+}
+
+/// Layout state that is rolled back when a list does not fit on one line.
+#[derive(Debug, Clone, Copy)]
+struct Cursor {
+    /// Current level of indentation.
+    level: usize,
+    /// Whitespace to write before the next piece of text.
+    gap: Gap,
+    /// Index of the last token written.
+    last: Option<usize>,
+    /// Relationship of the last token written to the next token.
+    role: Role,
+    /// Are we within the parameters of a closure?
+    closure: bool,
+    /// Is the next block the body of a `switch` statement?
+    switch: bool,
+    /// Is the next block the body of a `do` loop?
+    do_loop: bool,
+}
+
+/// Formatter of a tokenized script.
+struct Formatter<'a> {
+    /// The [`Engine`], for checking that tokens can be joined without a space.
+    engine: &'a Engine,
+    /// Formatting options.
+    options: FormatOptions,
+    /// Tokens in the script, ending with [`Token::EOF`].
+    items: Vec<Item>,
+    /// Output script.
+    out: String,
+    /// Layout state.
+    cursor: Cursor,
+}
+
+impl Formatter<'_> {
+    /// Request at least a particular gap before the next piece of text.
+    #[inline(always)]
+    fn gap(&mut self, gap: Gap) {
+        self.cursor.gap = self.cursor.gap.max(gap);
+    }
+    /// Request a new line, or a space when keeping everything on one line.
+    #[inline(always)]
+    fn line(&mut self, mode: Mode) {
+        self.gap(if mode == Mode::Inline {
+            Gap::Space
+        } else {
+            Gap::Line
+        });
+    }
+    /// Write a piece of text after any pending whitespace.
+    fn write(&mut self, text: &str) {
+        match mem::replace(&mut self.cursor.gap, Gap::None) {
+            _ if self.out.is_empty() => (),
+            Gap::None => (),
+            Gap::Space => self.out.push(' '),
+            gap => {
+                // No empty lines right after an opening bracket
+                if gap == Gap::BlankLine && !self.out.ends_with(['{', '(', '['].as_ref()) {
+                    self.out.push('\n');
+                }
+                self.out.push('\n');
+                let indent = self.cursor.level * self.options.indent_width;
+                self.out.extend(std::iter::repeat(' ').take(indent));
+            }
+        }
+        self.out.push_str(text);
+    }
+    /// Number of characters on the current line.
+    fn column(&self) -> usize {
+        self.out
+            .rsplit('\n')
+            .next()
+            .map_or(0, |s| s.chars().count())
+    }
+
+    /// Write the comments before a token.
+    fn comments(&mut self, index: usize) {
+        let comments = mem::take(&mut self.items[index].comments);
+
+        for comment in &comments {
+            if comment.trailing && !self.out.is_empty() {
+                // Keep the comment at the end of the current line
+                self.out.push(' ');
+                self.out.push_str(&comment.text);
+                self.gap(if comment.text.starts_with("//") {
+                    Gap::Line
+                } else {
+                    Gap::Space
+                });
+            } else {
+                self.gap(if comment.blank_before {
+                    Gap::BlankLine
+                } else {
+                    Gap::Line
+                });
+                self.write(&comment.text);
+                self.gap(Gap::Line);
+            }
+        }
+
+        self.items[index].comments = comments;
+    }
+    /// Write the text of a token (without any comments before it) and track its role.
+    fn text(&mut self, index: usize) {
+        let text = mem::take(&mut self.items[index].text);
+        self.write(&text);
+        self.items[index].text = text;
+
+        self.cursor.role = match self.items[index].token {
+            Token::UnaryMinus | Token::UnaryPlus => Role::Unary,
+            Token::Bang if self.is_call_bang(index) => Role::Operand,
+            Token::Bang => Role::Unary,
+            Token::Pipe if self.cursor.closure => {
+                self.cursor.closure = false;
+                Role::Other
+            }
+            Token::Pipe if self.is_unary_position() => {
+                self.cursor.closure = true;
+                Role::ClosureOpen
+            }
+            Token::LeftParen
+            | Token::LeftBracket
+            | Token::MapStart
+            | Token::Period
+            | Token::DoubleColon => Role::Open,
+            #[cfg(not(feature = "no_object"))]
+            Token::Elvis => Role::Open,
+            #[cfg(not(feature = "no_index"))]
+            Token::QuestionBracket => Role::Open,
+            Token::Identifier(..)
+            | Token::IntegerConstant(..)
+            | Token::CharConstant(..)
+            | Token::StringConstant(..)
+            | Token::True
+            | Token::False
+            | Token::Unit
+            | Token::Reserved(..)
+            | Token::RightParen
+            | Token::RightBracket
+            | Token::RightBrace => Role::Operand,
+            #[cfg(not(feature = "no_float"))]
+            Token::FloatConstant(..) => Role::Operand,
+            #[cfg(feature = "decimal")]
+            Token::DecimalConstant(..) => Role::Operand,
+            #[cfg(feature = "bigint")]
+            Token::BigIntConstant(..) => Role::Operand,
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Token::Custom(..) => Role::Operand,
+            _ => Role::Other,
+        };
+        self.cursor.last = Some(index);
+
+        match self.items[index].token {
+            Token::Switch => self.cursor.switch = true,
+            Token::Do => self.cursor.do_loop = true,
+            _ => (),
+        }
+    }
+    /// Write a token, together with the comments and whitespace before it.
+    fn token(&mut self, index: usize) {
+        self.comments(index);
+
+        if self.cursor.gap >= Gap::Line && self.items[index].blank_before {
+            self.gap(Gap::BlankLine);
+        }
+        if self.needs_space(index) {
+            self.gap(Gap::Space);
+        }
+
+        self.text(index);
+    }
+
+    /// Is the next token after the last token written an operand (i.e. not a binary operator)?
+    fn is_unary_position(&self) -> bool {
+        self.cursor.last.map_or(true, |last| {
+            let token = &self.items[last].token;
+            token.is_next_unary() || *token == Token::DoubleArrow
+        })
+    }
+    /// Is the `!` token at an index part of a function call that shares the scope, e.g. `foo!()`?
+    fn is_call_bang(&self, index: usize) -> bool {
+        self.cursor.role == Role::Operand
+            && self.cursor.last.map_or(false, |last| {
+                matches!(self.items[last].token, Token::Identifier(..))
+            })
+            && matches!(self.items[index + 1].token, Token::LeftParen | Token::Unit)
+    }
+    /// Is a token written immediately after the token before it?
+    fn is_glued_to_previous(token: &Token) -> bool {
+        match token {
+            Token::Comma
+            | Token::SemiColon
+            | Token::Colon
+            | Token::RightParen
+            | Token::RightBracket
+            | Token::RightBrace
+            | Token::Period
+            | Token::DoubleColon
+            | Token::ExclusiveRange
+            | Token::InclusiveRange => true,
+            #[cfg(not(feature = "no_object"))]
+            Token::Elvis => true,
+            #[cfg(not(feature = "no_index"))]
+            Token::QuestionBracket => true,
+            _ => false,
+        }
+    }
+    /// Is a space needed between the last token written and a token?
+    fn needs_space(&self, index: usize) -> bool {
+        let last = match self.cursor.last {
+            Some(last) if !self.out.is_empty() => last,
+            _ => return false,
+        };
+        let token = &self.items[index].token;
+        let role = self.cursor.role;
+
+        let space = !(Self::is_glued_to_previous(token)
+            || matches!(role, Role::Open | Role::Unary | Role::ClosureOpen)
+            || matches!(
+                self.items[last].token,
+                Token::ExclusiveRange | Token::InclusiveRange
+            )
+            || (*token == Token::Pipe && self.cursor.closure)
+            || (matches!(token, Token::LeftParen | Token::LeftBracket | Token::Unit)
+                && role == Role::Operand)
+            || (*token == Token::Bang && self.is_call_bang(index)));
+
+        space || !self.can_join(last, index)
+    }
+    /// Can two tokens be written without a space between them and still be read back the same?
+    fn can_join(&self, first: usize, second: usize) -> bool {
+        let (first, second) = (&self.items[first], &self.items[second]);
+
+        match (first.text.chars().last(), second.text.chars().next()) {
+            (Some('(' | '[' | '{' | ',' | ';'), _)
+            | (_, Some('(' | ')' | '[' | ']' | '}' | ',' | ';'))
+            | (None, _)
+            | (_, None) => true,
+            _ => {
+                let text = format!("{}{}", first.text, second.text);
+                let mut tokens = self.engine.tokenize(&text).map(|(token, ..)| token);
+
+                tokens.next().as_ref() == Some(&first.token)
+                    && tokens.next().as_ref() == Some(&second.token)
+                    && tokens.next().is_none()
+            }
+        }
+    }
+
+    /// Format the token (or bracketed group of tokens) at an index.
+    ///
+    /// Returns the index of the next token.
+    fn item(&mut self, index: usize, mode: Mode) -> usize {
+        let next_is_brace = self.items[index + 1].token == Token::LeftBrace;
+
+        match (&self.items[index].token, self.items[index].close) {
+            (Token::InterpolatedString(..), _) if next_is_brace => self.interpolated(index),
+            (Token::LeftBrace, Some(close)) => self.block(index, close, mode),
+            (Token::LeftParen | Token::LeftBracket | Token::MapStart, Some(close)) => {
+                self.list(index, close, mode)
+            }
+            #[cfg(not(feature = "no_index"))]
+            (Token::QuestionBracket, Some(close)) => self.list(index, close, mode),
+            _ => {
+                self.token(index);
+                index + 1
+            }
+        }
+    }
+    /// Format a range of tokens as statements.
+    fn statements(&mut self, range: Range<usize>, switch: bool, mode: Mode) {
+        let mut index = range.start;
+
+        while index < range.end {
+            let is_separator = match self.items[index].token {
+                Token::SemiColon => true,
+                Token::Comma => switch,
+                _ => false,
+            };
+
+            index = self.item(index, mode);
+
+            if is_separator {
+                self.line(mode);
+            }
+        }
+    }
+    /// Format a block of statements.
+    fn block(&mut self, index: usize, close: usize, mode: Mode) -> usize {
+        let switch = mem::take(&mut self.cursor.switch);
+        let do_loop = mem::take(&mut self.cursor.do_loop);
+        let mode = if mode == Mode::Inline {
+            Mode::Inline
+        } else {
+            Mode::Normal
+        };
+
+        self.token(index);
+
+        if close > index + 1 || !self.items[close].comments.is_empty() {
+            self.cursor.level += 1;
+            self.line(mode);
+            self.statements(index + 1..close, switch, mode);
+            self.comments(close);
+            self.cursor.level -= 1;
+            self.line(mode);
+        }
+
+        self.text(close);
+        self.cursor.role = Role::Other;
+
+        // Start a new statement unless the code continues after the block
+        let next = &self.items[close + 1].token;
+
+        let continued = match next {
+            Token::Else | Token::Catch | Token::DoubleArrow | Token::Equals => true,
+            Token::While | Token::Until => do_loop,
+            _ if Self::is_glued_to_previous(next) => true,
+            _ => next.precedence().is_some() || next.is_op_assignment(),
+        };
+
+        if !continued {
+            self.line(mode);
+        }
+
+        close + 1
+    }
+    /// Format a list of items within brackets, e.g. function call arguments.
+    fn list(&mut self, index: usize, close: usize, mode: Mode) -> usize {
+        self.token(index);
+
+        let has_comments = (index + 1..=close).any(|n| !self.items[n].comments.is_empty());
+
+        if !has_comments {
+            let (start, cursor, column) = (self.out.len(), self.cursor, self.column());
+
+            let mut n = index + 1;
+            while n < close {
+                n = self.item(n, mode.max_flat());
+            }
+            self.token(close);
+
+            if mode != Mode::Normal || self.fits(start, column) {
+                return close + 1;
+            }
+
+            // Too long, so roll back and wrap the items
+            self.out.truncate(start);
+            self.cursor = cursor;
+        }
+
+        self.cursor.level += 1;
+        self.gap(Gap::Line);
+
+        let mut n = index + 1;
+        while n < close {
+            let is_comma = self.items[n].token == Token::Comma;
+            n = self.item(n, Mode::Normal);
+            if is_comma {
+                self.gap(Gap::Line);
+            }
+        }
+
+        self.comments(close);
+        self.cursor.level -= 1;
+        self.gap(Gap::Line);
+        self.text(close);
+
+        close + 1
+    }
+    /// Do the first and last lines of the output since a position fit within the maximum line length?
+    fn fits(&self, start: usize, column: usize) -> bool {
+        let text = &self.out[start..];
+        let max = self.options.max_line_len;
+
+        match (text.find('\n'), text.rfind('\n')) {
+            (Some(first), Some(last)) => {
+                column + text[..first].chars().count() <= max
+                    && text[last + 1..].chars().count() <= max
+            }
+            _ => column + text.chars().count() <= max,
+        }
+    }
+    /// Format an interpolated string, keeping the interpolated expressions on one line.
+    fn interpolated(&mut self, index: usize) -> usize {
+        self.token(index);
+
+        let mut n = index + 1;
+
+        loop {
+            let close = match (&self.items[n].token, self.items[n].close) {
+                // The text of the string must continue after the closing brace
+                (Token::LeftBrace, Some(close)) if close + 1 < self.items.len() - 1 => close,
+                _ => break,
+            };
+
+            self.text(n);
+            self.cursor.role = Role::Open;
+            self.statements(n + 1..close, false, Mode::Inline);
+            self.comments(close);
+            self.text(close);
+
+            self.text(close + 1);
+            n = close + 2;
+
+            if !matches!(self.items[close + 1].token, Token::InterpolatedString(..)) {
+                break;
+            }
+        }
+
+        n
+    }
+}
+
+impl Mode {
+    /// The mode for the contents of a list that fits on one line.
+    #[inline(always)]
+    #[must_use]
+    fn max_flat(self) -> Self {
+        match self {
+            Self::Normal | Self::Flat => Self::Flat,
+            Self::Inline => Self::Inline,
+        }
+    }
+}
+
+impl Engine {
+    /// Format a script into a canonical layout.
+    ///
+    /// The script is parsed first, and a [`ParseError`][crate::ParseError] is returned if it is
+    /// not valid.
+    ///
+    /// Only whitespace is changed. Tokens are kept exactly as written (e.g. hex literals stay in
+    /// hex), so compiling the formatted script gives the same result as compiling the original.
+    /// Formatting an already-formatted script does not change it.
+    ///
+    /// * Statements are placed one per line, and blocks are indented by
+    ///   [`indent_width`][FormatOptions::indent_width] spaces per level.
+    /// * Empty lines between statements are kept, but multiple empty lines are collapsed into one.
+    /// * Function call arguments, array items and object map properties that do not fit within
+    ///   [`max_line_len`][FormatOptions::max_line_len] are wrapped one per line.
+    /// * Comments (if [kept][FormatOptions::preserve_comments]) stay at the end of the line they
+    ///   are on, or on their own lines before the code following them.
+    ///
+    /// Under `no_position`, comments are always placed on their own lines and empty lines are not
+    /// kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, FormatOptions};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let script = engine.format_script(
+    ///     "let x=0xff;// answer\nif x>42{print(x)}else{x+=1;}",
+    ///     FormatOptions::new(),
+    /// )?;
+    ///
+    /// assert_eq!(
+    ///     script,
+    ///     "let x = 0xff; // answer\nif x > 42 {\n    print(x)\n} else {\n    x += 1;\n}\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn format_script(
+        &self,
+        script: impl AsRef<str>,
+        options: FormatOptions,
+    ) -> ParseResult<String> {
+        let script = script.as_ref();
+
+        // Make sure that the script is valid
+        let scripts = [script];
+        let (stream, tc) = lex_raw(self, &scripts, self.token_mapper.as_deref());
+        let mut interner = StringsInterner::new();
+        let mut state = ParseState::new(None, &mut interner, tc);
+        let _ast = self.parse(
+            stream.peekable(),
+            &mut state,
+            |_| {},
+            #[cfg(not(feature = "no_optimize"))]
+            crate::OptimizationLevel::None,
+            #[cfg(feature = "no_optimize")]
+            (),
+        )?;
+
+        // Collect the tokens, attaching comments to the tokens following them
+        let mut items = Vec::<Item>::new();
+        let mut comments = Vec::new();
+        let mut last_line = None;
+
+        for (token, pos, span, text) in self.tokenize_raw(script, true, true) {
+            let line = pos.line();
+            let blank_before =
+                matches!((last_line, line), (Some(last), Some(line)) if line > last + 1);
+            let trailing = last_line.is_some() && last_line == line;
+            last_line = span.end().line();
+
+            match token {
+                Token::Comment(text) => {
+                    let keep = options.preserve_comments;
+                    // Doc-comments are part of the compiled script
+                    #[cfg(feature = "metadata")]
+                    let keep = keep || text.starts_with("//!");
+                    #[cfg(feature = "metadata")]
+                    #[cfg(not(feature = "no_function"))]
+                    let keep = keep || crate::tokenizer::is_doc_comment(&text);
+
+                    if keep {
+                        let text = if text.starts_with("//") {
+                            text.trim_end().into()
+                        } else {
+                            *text
+                        };
+                        comments.push(Comment {
+                            text,
+                            trailing,
+                            blank_before,
+                        });
+                    }
+                }
+                token => {
+                    let text = if text.is_empty() {
+                        token.to_string().into()
+                    } else {
+                        text
+                    };
+                    items.push(Item {
+                        token,
+                        text,
+                        comments: mem::take(&mut comments),
+                        blank_before,
+                        close: None,
+                    });
+                }
+            }
+        }
+
+        items.push(Item {
+            token: Token::EOF,
+            text: SmartString::new_const(),
+            comments,
+            blank_before: false,
+            close: None,
+        });
+
+        // Match brackets
+        let mut opened = Vec::new();
+
+        for n in 0..items.len() {
+            match items[n].token {
+                Token::LeftParen | Token::LeftBracket | Token::LeftBrace | Token::MapStart => {
+                    opened.push(n)
+                }
+                #[cfg(not(feature = "no_index"))]
+                Token::QuestionBracket => opened.push(n),
+                Token::RightParen | Token::RightBracket | Token::RightBrace => {
+                    if let Some(&open) = opened.last() {
+                        let matched = match (&items[open].token, &items[n].token) {
+                            (Token::LeftParen, Token::RightParen)
+                            | (Token::LeftBracket, Token::RightBracket)
+                            | (Token::LeftBrace | Token::MapStart, Token::RightBrace) => true,
+                            #[cfg(not(feature = "no_index"))]
+                            (Token::QuestionBracket, Token::RightBracket) => true,
+                            _ => false,
+                        };
+                        if matched {
+                            opened.pop();
+                            items[open].close = Some(n);
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let end = items.len() - 1;
+
+        let mut formatter = Formatter {
+            engine: self,
+            options,
+            items,
+            out: String::new(),
+            cursor: Cursor {
+                level: 0,
+                gap: Gap::None,
+                last: None,
+                role: Role::Other,
+                closure: false,
+                switch: false,
+                do_loop: false,
+            },
+        };
+
+        formatter.statements(0..end, false, Mode::Normal);
+        formatter.comments(end);
+
+        let mut script = formatter.out;
+        if !script.is_empty() {
+            script.push('\n');
+        }
+        Ok(script)
+    }
+}
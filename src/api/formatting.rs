@@ -1,11 +1,13 @@
 //! Module that provide formatting services to the [`Engine`].
+use crate::packages::bit_field::BitPositions;
 use crate::packages::iter_basic::{BitRange, CharsStream, StepRange};
 use crate::parser::{ParseResult, ParseState};
 use crate::tokenizer::lex_raw;
+use crate::types::dynamic::Variant;
 use crate::types::StringsInterner;
 use crate::{
-    Engine, ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, Position, RhaiError,
-    SmartString, ERR,
+    Dynamic, Engine, ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, Position, RhaiError,
+    RhaiResultOf, SmartString, ERR,
 };
 use std::any::type_name;
 #[cfg(feature = "no_std")]
@@ -14,7 +16,7 @@ use std::prelude::v1::*;
 /// Map the name of a standard type into a friendly form.
 #[inline]
 #[must_use]
-fn map_std_type_name(name: &str, shorthands: bool) -> &str {
+pub(crate) fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     let name = name.trim();
 
     if name == type_name::<String>() {
@@ -34,6 +36,10 @@ fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     if name == type_name::<rust_decimal::Decimal>() {
         return if shorthands { "decimal" } else { "Decimal" };
     }
+    #[cfg(feature = "bigint")]
+    if name == type_name::<num_bigint::BigInt>() {
+        return if shorthands { "bigint" } else { "BigInt" };
+    }
     if name == type_name::<FnPtr>() || name == "FnPtr" {
         return if shorthands { "Fn" } else { "FnPtr" };
     }
@@ -45,6 +51,10 @@ fn map_std_type_name(name: &str, shorthands: bool) -> &str {
     if name == type_name::<crate::Blob>() || name == "Blob" {
         return if shorthands { "blob" } else { "Blob" };
     }
+    #[cfg(not(feature = "no_index"))]
+    if name == type_name::<crate::BlobView>() || name == "BlobView" {
+        return if shorthands { "blob-view" } else { "BlobView" };
+    }
     #[cfg(not(feature = "no_object"))]
     if name == type_name::<crate::Map>() || name == "Map" {
         return if shorthands { "map" } else { "Map" };
@@ -71,9 +81,32 @@ fn map_std_type_name(name: &str, shorthands: bool) -> &str {
             "RangeInclusive<i64>"
         };
     }
+    #[cfg(not(feature = "no_float"))]
+    if name == type_name::<std::ops::Range<crate::FLOAT>>() {
+        return if shorthands {
+            "range"
+        } else if cfg!(feature = "f32_float") {
+            "Range<f32>"
+        } else {
+            "Range<f64>"
+        };
+    }
+    #[cfg(not(feature = "no_float"))]
+    if name == type_name::<std::ops::RangeInclusive<crate::FLOAT>>() {
+        return if shorthands {
+            "range="
+        } else if cfg!(feature = "f32_float") {
+            "RangeInclusive<f32>"
+        } else {
+            "RangeInclusive<f64>"
+        };
+    }
     if name == type_name::<BitRange>() {
         return if shorthands { "range" } else { "BitRange" };
     }
+    if name == type_name::<BitPositions>() {
+        return if shorthands { "range" } else { "BitPositions" };
+    }
     if name == type_name::<CharsStream>() {
         return if shorthands { "range" } else { "CharStream" };
     }
@@ -144,6 +177,13 @@ pub fn format_type(typ: &str, is_return_type: bool) -> std::borrow::Cow<str> {
         } else {
             format!("&mut {r}").into()
         };
+    } else if let Some(x) = typ.strip_prefix('&') {
+        let r = format_type(x, false);
+        return if r == x {
+            typ.into()
+        } else {
+            format!("&{r}").into()
+        };
     } else if typ.contains(' ') {
         let typ = typ.replace(' ', "");
         let r = format_type(&typ, is_return_type);
@@ -264,6 +304,44 @@ impl Engine {
             .into()
     }
 
+    /// Convert a [`Dynamic`] value into a specific type.
+    ///
+    /// Shared values are flattened first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorMismatchOutputType`][ERR::ErrorMismatchOutputType] if the value is not of
+    /// the requested type, with type names mapped via [`map_type_name`][Engine::map_type_name].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Dynamic, Engine};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// assert_eq!(engine.cast_dynamic::<i64>(Dynamic::from(42_i64)).unwrap(), 42);
+    ///
+    /// let err = engine.cast_dynamic::<bool>(Dynamic::from(42_i64)).unwrap_err();
+    ///
+    /// assert_eq!(err.to_string(), "Output type incorrect: i64 (expecting bool)");
+    /// ```
+    #[inline]
+    pub fn cast_dynamic<T: Variant + Clone>(&self, value: Dynamic) -> RhaiResultOf<T> {
+        value.try_cast_result().map_err(|v| {
+            let cast_type = match type_name::<T>() {
+                typ if typ.contains("::") => self.map_type_name(typ),
+                typ => typ,
+            };
+            ERR::ErrorMismatchOutputType(
+                cast_type.into(),
+                self.map_type_name(v.type_name()).into(),
+                Position::NONE,
+            )
+            .into()
+        })
+    }
+
     /// Compact a script to eliminate insignificant whitespaces and comments.
     ///
     /// This is useful to prepare a script for further compressing.
@@ -283,6 +361,7 @@ impl Engine {
         let mut _ast = self.parse(
             stream.peekable(),
             &mut state,
+            |_| {},
             #[cfg(not(feature = "no_optimize"))]
             crate::OptimizationLevel::None,
             #[cfg(feature = "no_optimize")]
@@ -1,13 +1,20 @@
 //! Module that defines JSON manipulation functions for [`Engine`].
 #![cfg(not(feature = "no_object"))]
 
+use crate::eval::GlobalRuntimeState;
 use crate::func::native::locked_write;
-use crate::parser::{ParseSettingFlags, ParseState};
+use crate::packages::string_basic::{print_with_func, FUNC_TO_STRING};
+use crate::parser::{ParseResult, ParseSettingFlags, ParseState};
 use crate::tokenizer::{lex_raw, Token};
+use crate::types::dynamic::Union;
 use crate::types::StringsInterner;
-use crate::{Engine, LexError, Map, OptimizationLevel, RhaiResultOf};
+use crate::{
+    Dynamic, Engine, LexError, Map, NativeCallContext, OptimizationLevel, ParseErrorType, Position,
+    RhaiResult, RhaiResultOf, INT,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
+use std::{fmt::Write, iter::Peekable, str::Chars};
 
 impl Engine {
     /// Parse a JSON string into an [object map][Map].
@@ -142,6 +149,582 @@ impl Engine {
 
         self.eval_ast(&ast)
     }
+    /// Parse any JSON text into a [`Dynamic`] value.
+    ///
+    /// Not available under `no_object`.
+    ///
+    /// Unlike [`parse_json`][Engine::parse_json], the JSON text can be any value (e.g. an array or
+    /// a number), and it is parsed strictly according to the JSON standard.
+    ///
+    /// * `null` maps to `()`.
+    /// * JSON objects map to [object maps][Map], and JSON arrays map to [arrays][crate::Array]
+    ///   (an error under `no_index`).
+    /// * Numbers map to [`INT`] if they are integers within range, otherwise to
+    ///   [`FLOAT`][crate::FLOAT] (an error under `no_float`).
+    ///
+    /// The maximum sizes of strings, arrays and object maps, as well as the maximum expression
+    /// depth (for nested arrays and object maps), are enforced while parsing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_index"))]
+    /// # #[cfg(not(feature = "no_float"))]
+    /// # {
+    /// use rhai::{Array, Engine, Map};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let value = engine.parse_json_full(r#"[1, 2.5e3, null, {"x": "hello\u0021"}]"#)?;
+    ///
+    /// let array = value.cast::<Array>();
+    /// assert_eq!(array.len(), 4);
+    /// assert_eq!(array[0].as_int().unwrap(), 1);
+    /// assert_eq!(array[1].as_float().unwrap(), 2500.0);
+    /// assert!(array[2].is_unit());
+    ///
+    /// let map = array[3].read_lock::<Map>().unwrap();
+    /// assert_eq!(map["x"].clone().into_string().unwrap(), "hello!");
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn parse_json_full(&self, json: impl AsRef<str>) -> RhaiResult {
+        let mut parser = JsonParser {
+            engine: self,
+            stream: json.as_ref().chars().peekable(),
+            line: 1,
+            col: 0,
+        };
+
+        let value = parser.parse_value(0)?;
+
+        parser.skip_whitespace();
+
+        match parser.stream.peek() {
+            None => Ok(value),
+            Some(&ch) => Err(parser
+                .error(LexError::UnexpectedInput(ch.to_string()))
+                .into()),
+        }
+    }
+    /// Return the JSON representation of any [`Dynamic`] value.
+    ///
+    /// Not available under `no_object`.
+    ///
+    /// * `()` becomes `null`.
+    /// * Characters and strings become JSON strings.
+    /// * Floating-point numbers that are not finite (e.g. NaN) become `null`.
+    /// * [BLOB's][crate::Blob] become arrays of bytes.
+    /// * Values of other types (e.g. custom types) become JSON strings, converted via the
+    ///   `to_string` function registered for the type (if any).
+    ///
+    /// Set `pretty` to `true` to indent the output with two spaces per level.
+    ///
+    /// This function can be used together with [`parse_json_full`][Engine::parse_json_full] to
+    /// work with JSON texts without using the [`serde_json`](https://crates.io/crates/serde_json)
+    /// crate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// # #[cfg(not(feature = "no_index"))]
+    /// # {
+    /// use rhai::{Dynamic, Engine};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let value = engine.eval::<Dynamic>(r#"#{ a: [1, ()], b: "x\ty" }"#)?;
+    ///
+    /// assert_eq!(engine.to_json_string(&value, false), r#"{"a":[1,null],"b":"x\ty"}"#);
+    /// assert_eq!(engine.to_json_string(&value, true), "{\n  \"a\": [\n    1,\n    null\n  ],\n  \"b\": \"x\\ty\"\n}");
+    /// # }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn to_json_string(&self, value: &Dynamic, pretty: bool) -> String {
+        let global = &GlobalRuntimeState::new(self);
+        let ctx = &(self, FUNC_TO_STRING, None, global, Position::NONE).into();
+        let mut json = String::new();
+
+        write_json(ctx, &mut json, value, if pretty { Some(0) } else { None });
+
+        json
+    }
+}
+
+/// Parser for JSON text that enforces the limits of an [`Engine`] while parsing.
+struct JsonParser<'e, 's> {
+    /// The [`Engine`], for its limits.
+    #[cfg_attr(feature = "unchecked", allow(dead_code))]
+    engine: &'e Engine,
+    /// Stream of input characters.
+    stream: Peekable<Chars<'s>>,
+    /// Current line number.
+    line: usize,
+    /// Position of the last character read on the current line.
+    col: usize,
+}
+
+impl JsonParser<'_, '_> {
+    /// Get the position of the next character.
+    #[must_use]
+    fn position(&self) -> Position {
+        #[allow(clippy::cast_possible_truncation)]
+        Position::new(
+            self.line.min(u16::MAX as usize) as u16,
+            (self.col + 1).min(u16::MAX as usize) as u16,
+        )
+    }
+    /// Create a parse error at the position of the next character.
+    #[cold]
+    fn error(&self, err: impl Into<ParseErrorType>) -> crate::ParseError {
+        crate::ParseError(Box::new(err.into()), self.position())
+    }
+    /// Read the next character.
+    fn next_char(&mut self) -> Option<char> {
+        let ch = self.stream.next()?;
+
+        if ch == '\n' {
+            self.line += 1;
+            self.col = 0;
+        } else {
+            self.col += 1;
+        }
+
+        Some(ch)
+    }
+    /// Skip JSON white-space.
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.stream.peek() {
+            self.next_char();
+        }
+    }
+    /// Read the next non-white-space character, which must be the specified character.
+    fn expect_char(&mut self, expected: char, err: &str) -> ParseResult<()> {
+        self.skip_whitespace();
+
+        match self.stream.peek() {
+            Some(&ch) if ch == expected => {
+                self.next_char();
+                Ok(())
+            }
+            Some(&ch) => Err(self.error(LexError::UnexpectedInput(ch.to_string()))),
+            None => Err(self.error(ParseErrorType::MissingToken(
+                expected.to_string(),
+                err.into(),
+            ))),
+        }
+    }
+    /// Parse a JSON value, with `level` being the number of enclosing arrays and object maps.
+    fn parse_value(&mut self, level: usize) -> ParseResult<Dynamic> {
+        self.skip_whitespace();
+
+        match self.stream.peek() {
+            Some('{') => self.parse_object(level + 1),
+            Some('[') => self.parse_array(level + 1),
+            Some('"') => self.parse_string().map(Into::into),
+            Some('-' | '0'..='9') => self.parse_number(),
+            Some(ch) if ch.is_ascii_alphabetic() => {
+                let pos = self.position();
+                let mut word = String::new();
+
+                while let Some(&ch) = self.stream.peek().filter(|ch| ch.is_ascii_alphanumeric()) {
+                    word.push(ch);
+                    self.next_char();
+                }
+
+                match word.as_str() {
+                    "true" => Ok(true.into()),
+                    "false" => Ok(false.into()),
+                    "null" => Ok(Dynamic::UNIT),
+                    _ => Err(LexError::UnexpectedInput(word).into_err(pos)),
+                }
+            }
+            Some(&ch) => Err(self.error(LexError::UnexpectedInput(ch.to_string()))),
+            None => Err(self.error(ParseErrorType::UnexpectedEOF)),
+        }
+    }
+    /// Check the nesting level of arrays and object maps.
+    fn check_level(&self, _level: usize) -> ParseResult<()> {
+        #[cfg(not(feature = "unchecked"))]
+        if self.engine.max_expr_depth() > 0 && _level > self.engine.max_expr_depth() {
+            return Err(self.error(ParseErrorType::ExprTooDeep));
+        }
+
+        Ok(())
+    }
+    /// Parse a JSON object into an [object map][Map].
+    fn parse_object(&mut self, level: usize) -> ParseResult<Dynamic> {
+        const MISSING_RBRACE: &str = "to end this object map";
+
+        self.check_level(level)?;
+        self.next_char();
+        self.skip_whitespace();
+
+        let mut map = Map::new();
+
+        if self.stream.peek() == Some(&'}') {
+            self.next_char();
+            return Ok(map.into());
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            let name = match self.stream.peek() {
+                Some('"') => self.parse_string()?,
+                Some(&ch) => return Err(self.error(LexError::UnexpectedInput(ch.to_string()))),
+                None => {
+                    return Err(self.error(ParseErrorType::MissingToken(
+                        "}".into(),
+                        MISSING_RBRACE.into(),
+                    )))
+                }
+            };
+
+            self.expect_char(':', "to separate the property name from its value")?;
+
+            let value = self.parse_value(level)?;
+            map.insert(name.into(), value);
+
+            #[cfg(not(feature = "unchecked"))]
+            if self.engine.max_map_size() > 0 && map.len() > self.engine.max_map_size() {
+                return Err(self.error(ParseErrorType::LiteralTooLarge(
+                    "Number of properties in object map".into(),
+                    self.engine.max_map_size(),
+                )));
+            }
+
+            self.skip_whitespace();
+            let pos = self.position();
+
+            match self.next_char() {
+                Some(',') => (),
+                Some('}') => return Ok(map.into()),
+                Some(ch) => return Err(LexError::UnexpectedInput(ch.to_string()).into_err(pos)),
+                None => {
+                    return Err(self.error(ParseErrorType::MissingToken(
+                        "}".into(),
+                        MISSING_RBRACE.into(),
+                    )))
+                }
+            }
+        }
+    }
+    /// Parse a JSON array into an [array][crate::Array].
+    fn parse_array(&mut self, _level: usize) -> ParseResult<Dynamic> {
+        #[cfg(feature = "no_index")]
+        return Err(self.error(LexError::UnexpectedInput("[".into())));
+
+        #[cfg(not(feature = "no_index"))]
+        {
+            const MISSING_RBRACKET: &str = "to end this array";
+
+            self.check_level(_level)?;
+            self.next_char();
+            self.skip_whitespace();
+
+            let mut array = crate::Array::new();
+
+            if self.stream.peek() == Some(&']') {
+                self.next_char();
+                return Ok(array.into());
+            }
+
+            loop {
+                array.push(self.parse_value(_level)?);
+
+                #[cfg(not(feature = "unchecked"))]
+                if self.engine.max_array_size() > 0 && array.len() > self.engine.max_array_size() {
+                    return Err(self.error(ParseErrorType::LiteralTooLarge(
+                        "Size of array".into(),
+                        self.engine.max_array_size(),
+                    )));
+                }
+
+                self.skip_whitespace();
+                let pos = self.position();
+
+                match self.next_char() {
+                    Some(',') => (),
+                    Some(']') => return Ok(array.into()),
+                    Some(ch) => return Err(LexError::UnexpectedInput(ch.to_string()).into_err(pos)),
+                    None => {
+                        return Err(self.error(ParseErrorType::MissingToken(
+                            "]".into(),
+                            MISSING_RBRACKET.into(),
+                        )))
+                    }
+                }
+            }
+        }
+    }
+    /// Parse a JSON string.
+    fn parse_string(&mut self) -> ParseResult<String> {
+        self.next_char();
+
+        let mut result = String::new();
+
+        loop {
+            let pos = self.position();
+
+            match self.next_char() {
+                Some('"') => break,
+                Some('\\') => {
+                    let ch = match self.next_char() {
+                        Some(ch @ ('"' | '\\' | '/')) => ch,
+                        Some('b') => '\x08',
+                        Some('f') => '\x0c',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hi = self.parse_hex4(pos)?;
+
+                            let code = if (0xd800..0xdc00).contains(&hi) {
+                                // Surrogate pair
+                                match (self.next_char(), self.next_char()) {
+                                    (Some('\\'), Some('u')) => (),
+                                    _ => {
+                                        return Err(LexError::MalformedEscapeSequence(format!(
+                                            "\\u{hi:04x}"
+                                        ))
+                                        .into_err(pos))
+                                    }
+                                }
+
+                                let lo = self.parse_hex4(pos)?;
+
+                                if !(0xdc00..0xe000).contains(&lo) {
+                                    return Err(LexError::MalformedEscapeSequence(format!(
+                                        "\\u{hi:04x}\\u{lo:04x}"
+                                    ))
+                                    .into_err(pos));
+                                }
+
+                                0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00)
+                            } else {
+                                hi
+                            };
+
+                            char::from_u32(code).ok_or_else(|| {
+                                LexError::MalformedEscapeSequence(format!("\\u{code:04x}"))
+                                    .into_err(pos)
+                            })?
+                        }
+                        Some(ch) => {
+                            return Err(
+                                LexError::MalformedEscapeSequence(format!("\\{ch}")).into_err(pos)
+                            )
+                        }
+                        None => return Err(self.error(LexError::UnterminatedString)),
+                    };
+
+                    result.push(ch);
+                }
+                Some(ch) if ch < ' ' => {
+                    return Err(
+                        LexError::UnexpectedInput(ch.escape_default().to_string()).into_err(pos)
+                    );
+                }
+                Some(ch) => result.push(ch),
+                None => return Err(self.error(LexError::UnterminatedString)),
+            }
+
+            #[cfg(not(feature = "unchecked"))]
+            if self.engine.max_string_size() > 0 && result.len() > self.engine.max_string_size() {
+                return Err(self.error(LexError::StringTooLong(self.engine.max_string_size())));
+            }
+        }
+
+        Ok(result)
+    }
+    /// Parse four hex digits in a `\u` escape sequence.
+    fn parse_hex4(&mut self, pos: Position) -> ParseResult<u32> {
+        let mut code = 0;
+
+        for _ in 0..4 {
+            match self.next_char().and_then(|ch| ch.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(LexError::MalformedEscapeSequence("\\u".into()).into_err(pos)),
+            }
+        }
+
+        Ok(code)
+    }
+    /// Parse a JSON number.
+    fn parse_number(&mut self) -> ParseResult<Dynamic> {
+        let pos = self.position();
+        let mut text = String::new();
+        let mut is_integer = true;
+
+        while let Some(&ch) = self
+            .stream
+            .peek()
+            .filter(|&&ch| matches!(ch, '0'..='9' | '-' | '+' | '.' | 'e' | 'E'))
+        {
+            is_integer &= ch.is_ascii_digit() || (ch == '-' && text.is_empty());
+            text.push(ch);
+            self.next_char();
+        }
+
+        if !is_json_number(&text) {
+            return Err(LexError::MalformedNumber(text).into_err(pos));
+        }
+
+        if is_integer {
+            if let Ok(n) = text.parse::<INT>() {
+                return Ok(n.into());
+            }
+        }
+
+        #[cfg(not(feature = "no_float"))]
+        return text
+            .parse::<crate::FLOAT>()
+            .map(Into::into)
+            .map_err(|_| LexError::MalformedNumber(text).into_err(pos));
+
+        #[cfg(feature = "no_float")]
+        return Err(LexError::MalformedNumber(text).into_err(pos));
+    }
+}
+
+/// Is the text a number according to the JSON grammar?
+#[must_use]
+fn is_json_number(text: &str) -> bool {
+    fn digits(s: &str) -> (&str, &str) {
+        let n = s.bytes().take_while(u8::is_ascii_digit).count();
+        s.split_at(n)
+    }
+
+    let text = text.strip_prefix('-').unwrap_or(text);
+
+    let (int, rest) = digits(text);
+
+    if int.is_empty() || (int.len() > 1 && int.starts_with('0')) {
+        return false;
+    }
+
+    let rest = match rest.strip_prefix('.') {
+        Some(rest) => match digits(rest) {
+            ("", _) => return false,
+            (_, rest) => rest,
+        },
+        None => rest,
+    };
+
+    match rest.strip_prefix(|ch| ch == 'e' || ch == 'E') {
+        Some(exp) => {
+            let exp = exp.strip_prefix(|ch| ch == '+' || ch == '-').unwrap_or(exp);
+            matches!(digits(exp), (d, "") if !d.is_empty())
+        }
+        None => rest.is_empty(),
+    }
+}
+
+/// Write a string as a JSON string literal.
+fn write_json_string(json: &mut String, s: &str) {
+    json.push('"');
+
+    for ch in s.chars() {
+        match ch {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            '\x08' => json.push_str("\\b"),
+            '\x0c' => json.push_str("\\f"),
+            ch if ch < ' ' => write!(json, "\\u{:04x}", ch as u32).unwrap(),
+            ch => json.push(ch),
+        }
+    }
+
+    json.push('"');
+}
+
+/// Write a new line followed by the indentation for a level, if pretty-printing.
+fn write_json_indent(json: &mut String, indent: Option<usize>) {
+    if let Some(level) = indent {
+        json.push('\n');
+        json.extend(std::iter::repeat("  ").take(level));
+    }
+}
+
+/// Write a sequence of JSON items enclosed by delimiters.
+fn write_json_items<T>(
+    json: &mut String,
+    items: impl IntoIterator<Item = T>,
+    delimiters: (char, char),
+    indent: Option<usize>,
+    mut write_item: impl FnMut(&mut String, T, Option<usize>),
+) {
+    let inner = indent.map(|level| level + 1);
+    let mut empty = true;
+
+    json.push(delimiters.0);
+
+    for item in items {
+        if !empty {
+            json.push(',');
+        }
+        empty = false;
+        write_json_indent(json, inner);
+        write_item(json, item, inner);
+    }
+
+    if !empty {
+        write_json_indent(json, indent);
+    }
+
+    json.push(delimiters.1);
+}
+
+/// Write the JSON representation of a [`Dynamic`] value.
+fn write_json(ctx: &NativeCallContext, json: &mut String, value: &Dynamic, indent: Option<usize>) {
+    match value.0 {
+        Union::Unit(..) => json.push_str("null"),
+        Union::Bool(b, ..) => json.push_str(if b { "true" } else { "false" }),
+        Union::Int(n, ..) => write!(json, "{n}").unwrap(),
+        #[cfg(not(feature = "no_float"))]
+        Union::Float(f, ..) if f.is_finite() => write!(json, "{f}").unwrap(),
+        #[cfg(not(feature = "no_float"))]
+        Union::Float(..) => json.push_str("null"),
+        #[cfg(feature = "decimal")]
+        Union::Decimal(ref d, ..) => write!(json, "{d}").unwrap(),
+        Union::Str(ref s, ..) => write_json_string(json, s),
+        Union::Char(ch, ..) => write_json_string(json, ch.encode_utf8(&mut [0; 4])),
+        #[cfg(not(feature = "no_index"))]
+        Union::Array(ref a, ..) => {
+            write_json_items(json, &**a, ('[', ']'), indent, |json, v, i| {
+                write_json(ctx, json, v, i);
+            })
+        }
+        #[cfg(not(feature = "no_index"))]
+        Union::Blob(ref b, ..) => write_json_items(json, &**b, ('[', ']'), indent, |json, v, _| {
+            write!(json, "{v}").unwrap();
+        }),
+        Union::Map(ref m, ..) => {
+            write_json_items(json, &**m, ('{', '}'), indent, |json, (k, v), i| {
+                write_json_string(json, k);
+                json.push_str(if i.is_some() { ": " } else { ":" });
+                write_json(ctx, json, v, i);
+            })
+        }
+        #[cfg(not(feature = "no_closure"))]
+        Union::Shared(ref cell, ..) => {
+            write_json(ctx, json, &crate::func::locked_read(cell), indent)
+        }
+        _ => write_json_string(
+            json,
+            &print_with_func(FUNC_TO_STRING, ctx, &mut value.clone()),
+        ),
+    }
 }
 
 /// Return the JSON representation of an [object map][Map].
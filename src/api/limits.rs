@@ -49,6 +49,14 @@ pub struct Limits {
     /// Not available under `no_function`.
     #[cfg(not(feature = "no_function"))]
     pub max_call_stack_depth: usize,
+    /// Maximum levels of calls to closures and anonymous functions made through function pointers.
+    ///
+    /// Zero means that such calls are counted together with other function calls against
+    /// [`max_call_stack_depth`][Limits::max_call_stack_depth].
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    pub max_closure_call_stack_depth: usize,
     /// Maximum depth of statements/expressions at global level.
     pub max_expr_depth: Option<NonZeroUsize>,
     /// Maximum depth of statements/expressions in functions.
@@ -81,6 +89,18 @@ pub struct Limits {
     /// Not available under `no_object`.
     #[cfg(not(feature = "no_object"))]
     pub max_map_size: Option<NonZeroUsize>,
+    /// Maximum length of an identifier in a script.
+    pub max_identifier_len: Option<NonZeroUsize>,
+    /// Maximum length of a string literal in a script.
+    pub max_string_literal_len: Option<NonZeroUsize>,
+    /// Maximum nesting depth of interpolated strings in a script.
+    pub max_interpolation_depth: Option<NonZeroUsize>,
+    /// Maximum number of tokens in a script.
+    pub max_tokens: Option<NonZeroUsize>,
+    /// Maximum length of a line in a script.
+    pub max_line_len: Option<NonZeroUsize>,
+    /// Maximum size, in bytes, of a script file (or reader).
+    pub max_script_file_size: Option<NonZeroUsize>,
 }
 
 impl Limits {
@@ -92,6 +112,8 @@ impl Limits {
         Self {
             #[cfg(not(feature = "no_function"))]
             max_call_stack_depth: default_limits::MAX_CALL_STACK_DEPTH,
+            #[cfg(not(feature = "no_function"))]
+            max_closure_call_stack_depth: 0,
             max_expr_depth: NonZeroUsize::new(default_limits::MAX_EXPR_DEPTH),
             #[cfg(not(feature = "no_function"))]
             max_function_expr_depth: NonZeroUsize::new(default_limits::MAX_FUNCTION_EXPR_DEPTH),
@@ -104,6 +126,12 @@ impl Limits {
             max_array_size: None,
             #[cfg(not(feature = "no_object"))]
             max_map_size: None,
+            max_identifier_len: None,
+            max_string_literal_len: None,
+            max_interpolation_depth: None,
+            max_tokens: None,
+            max_line_len: None,
+            max_script_file_size: None,
         }
     }
 }
@@ -159,6 +187,32 @@ impl Engine {
         #[cfg(feature = "no_function")]
         return 0;
     }
+    /// Set the maximum levels of calls to closures and anonymous functions made through
+    /// function pointers (0 to count them together with other function calls).
+    ///
+    /// When set, such calls (e.g. `f.call(x)` or closures passed to `map` and `filter`) are counted
+    /// against this limit only, and no longer against [`max_call_levels`][Engine::max_call_levels].
+    /// This allows deeply-nested combinator-style closures without raising the limit on real recursion.
+    ///
+    /// Not available under `unchecked` or `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn set_max_closure_call_levels(&mut self, levels: usize) -> &mut Self {
+        self.limits.max_closure_call_stack_depth = levels;
+        self
+    }
+    /// The maximum levels of calls to closures and anonymous functions made through function
+    /// pointers (0 if they are counted together with other function calls).
+    ///
+    /// Not available under `unchecked` or `no_function`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn max_closure_call_levels(&self) -> usize {
+        #[cfg(not(feature = "no_function"))]
+        return self.limits.max_closure_call_stack_depth;
+        #[cfg(feature = "no_function")]
+        return 0;
+    }
     /// Set the maximum number of operations allowed for a script to run to avoid
     /// consuming too much resources (0 for unlimited).
     ///
@@ -321,4 +375,132 @@ impl Engine {
         #[cfg(feature = "no_object")]
         return 0;
     }
+    /// Set the maximum length, in bytes, of identifiers in scripts (0 for unlimited).
+    ///
+    /// Longer identifiers are rejected when the script is compiled, as soon as the limit is reached.
+    ///
+    /// Not available under `unchecked`.
+    #[inline(always)]
+    pub fn set_max_identifier_len(&mut self, max_len: usize) -> &mut Self {
+        self.limits.max_identifier_len = NonZeroUsize::new(max_len);
+        self
+    }
+    /// The maximum length, in bytes, of identifiers in scripts (0 for unlimited).
+    ///
+    /// Not available under `unchecked`.
+    #[inline]
+    #[must_use]
+    pub const fn max_identifier_len(&self) -> usize {
+        match self.limits.max_identifier_len {
+            Some(n) => n.get(),
+            None => 0,
+        }
+    }
+    /// Set the maximum length, in bytes, of string literals in scripts (0 for unlimited).
+    ///
+    /// Unlike [`set_max_string_size`][Engine::set_max_string_size], which limits strings at
+    /// runtime, this limit applies when the script is compiled, as soon as it is reached.
+    ///
+    /// Not available under `unchecked`.
+    #[inline(always)]
+    pub fn set_max_string_literal_len(&mut self, max_len: usize) -> &mut Self {
+        self.limits.max_string_literal_len = NonZeroUsize::new(max_len);
+        self
+    }
+    /// The maximum length, in bytes, of string literals in scripts (0 for unlimited).
+    ///
+    /// Not available under `unchecked`.
+    #[inline]
+    #[must_use]
+    pub const fn max_string_literal_len(&self) -> usize {
+        match self.limits.max_string_literal_len {
+            Some(n) => n.get(),
+            None => 0,
+        }
+    }
+    /// Set the maximum nesting depth of interpolated strings in scripts (0 for unlimited).
+    ///
+    /// Not available under `unchecked`.
+    #[inline(always)]
+    pub fn set_max_interpolation_depth(&mut self, max_depth: usize) -> &mut Self {
+        self.limits.max_interpolation_depth = NonZeroUsize::new(max_depth);
+        self
+    }
+    /// The maximum nesting depth of interpolated strings in scripts (0 for unlimited).
+    ///
+    /// Not available under `unchecked`.
+    #[inline]
+    #[must_use]
+    pub const fn max_interpolation_depth(&self) -> usize {
+        match self.limits.max_interpolation_depth {
+            Some(n) => n.get(),
+            None => 0,
+        }
+    }
+    /// Set the maximum number of tokens in a script compiled in one go (0 for unlimited).
+    ///
+    /// Not available under `unchecked`.
+    #[inline(always)]
+    pub fn set_max_tokens(&mut self, max_tokens: usize) -> &mut Self {
+        self.limits.max_tokens = NonZeroUsize::new(max_tokens);
+        self
+    }
+    /// The maximum number of tokens in a script compiled in one go (0 for unlimited).
+    ///
+    /// Not available under `unchecked`.
+    #[inline]
+    #[must_use]
+    pub const fn max_tokens(&self) -> usize {
+        match self.limits.max_tokens {
+            Some(n) => n.get(),
+            None => 0,
+        }
+    }
+    /// Set the maximum length, in characters, of lines in scripts (0 for unlimited).
+    ///
+    /// Character positions are tracked up to 65535 within a line, so limits above that have no
+    /// effect.
+    ///
+    /// Not available under `unchecked` or `no_position`.
+    #[inline(always)]
+    pub fn set_max_line_len(&mut self, max_len: usize) -> &mut Self {
+        self.limits.max_line_len = NonZeroUsize::new(max_len);
+        self
+    }
+    /// The maximum length, in characters, of lines in scripts (0 for unlimited).
+    ///
+    /// Not available under `unchecked` or `no_position`.
+    #[inline]
+    #[must_use]
+    pub const fn max_line_len(&self) -> usize {
+        match self.limits.max_line_len {
+            Some(n) => n.get(),
+            None => 0,
+        }
+    }
+    /// Set the maximum size, in bytes, of script files (0 for unlimited).
+    ///
+    /// The limit applies to all script files read by the [`Engine`] (e.g. via
+    /// [`compile_file`][Engine::compile_file], [`eval_file`][Engine::eval_file],
+    /// [`run_file`][Engine::run_file] or a file module resolver) as well as scripts read via
+    /// [`compile_reader`][Engine::compile_reader]. Larger scripts are rejected without being read
+    /// in full.
+    ///
+    /// Not available under `unchecked`.
+    #[inline(always)]
+    pub fn set_max_script_file_size(&mut self, max_size: usize) -> &mut Self {
+        self.limits.max_script_file_size = NonZeroUsize::new(max_size);
+        self
+    }
+    /// The maximum size, in bytes, of script files (0 for unlimited).
+    ///
+    /// Not available under `unchecked`.
+    #[inline]
+    #[must_use]
+    pub const fn max_script_file_size(&self) -> usize {
+        match self.limits.max_script_file_size {
+            Some(n) => n.get(),
+            None => 0,
+        }
+    }
 }
@@ -16,14 +16,24 @@ pub mod call_fn;
 
 pub mod options;
 
+pub mod version;
+
 pub mod optimize;
 
 pub mod limits;
 
+pub mod settings;
+
 pub mod events;
 
+pub mod dispatch;
+
 pub mod formatting;
 
+pub mod format_script;
+
+pub mod tokenize;
+
 pub mod custom_syntax;
 
 pub mod build_type;
@@ -33,7 +43,9 @@ pub mod definitions;
 
 pub mod deprecated;
 
-use crate::{Dynamic, Engine, Identifier};
+use crate::func::{locked_read, locked_write};
+use crate::types::StringsInterner;
+use crate::{Dynamic, Engine, Identifier, Locked, StringsInternerStats};
 
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -75,6 +87,42 @@ impl Engine {
         self
     }
 
+    /// Set the global constants that are seeded into every evaluation, replacing any existing ones.
+    ///
+    /// Global constants are read-only and are accessed via the `global` namespace
+    /// (e.g. `global::ANSWER`), including from within functions.
+    /// Constants defined at the top level of a script shadow global constants of the same name.
+    ///
+    /// Not available under `no_module` or `no_function`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_global_constants([("ANSWER", 42_i64)]);
+    ///
+    /// assert_eq!(engine.eval::<i64>("fn foo() { global::ANSWER } foo()")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    pub fn set_global_constants(
+        &mut self,
+        constants: impl IntoIterator<Item = (impl Into<crate::ImmutableString>, impl Into<Dynamic>)>,
+    ) -> &mut Self {
+        self.global_constants = constants
+            .into_iter()
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+        self
+    }
+
     /// Disable a particular keyword or operator in the language.
     ///
     /// # Examples
@@ -136,6 +184,68 @@ impl Engine {
         self.disabled_symbols.contains(symbol)
     }
 
+    /// Disable a particular function by name.
+    ///
+    /// Calling a disabled function from a script raises
+    /// [`ErrorFunctionNotFound`][crate::EvalAltResult::ErrorFunctionNotFound], regardless of
+    /// whether the function is script-defined, registered via the [`Engine`] or part of a package.
+    /// This also applies to built-in functions such as `eval`, `Fn` or `curry`.
+    ///
+    /// Functions in a namespace are disabled with their qualified name (e.g. `"math::sqrt"`),
+    /// which does not affect calls to the same function without the namespace.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, EvalAltResult};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.disable_function("eval");    // disable the 'eval' function
+    ///
+    /// let err = engine.run(r#"eval("40 + 2")"#).unwrap_err();
+    ///
+    /// assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(..)));
+    ///
+    /// engine.enable_function("eval");     // enable it again
+    ///
+    /// assert_eq!(engine.eval::<i64>(r#"eval("40 + 2")"#)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    pub fn disable_function(&mut self, name: impl Into<Identifier>) -> &mut Self {
+        self.disabled_functions.insert(name.into());
+        self
+    }
+
+    /// Enable a function previously disabled via [`disable_function`][Engine::disable_function].
+    #[inline]
+    pub fn enable_function(&mut self, name: &str) -> &mut Self {
+        self.disabled_functions.remove(name);
+        self
+    }
+
+    /// Is a particular function disabled?
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.disable_function("sleep");   // disable the 'sleep' function
+    ///
+    /// assert!(engine.is_function_disabled("sleep"));
+    /// ```
+    #[inline(always)]
+    #[must_use]
+    pub fn is_function_disabled(&self, name: &str) -> bool {
+        self.disabled_functions.contains(name)
+    }
+
     /// Register a custom operator with a precedence into the language.
     ///
     /// Not available under `no_custom_syntax`.
@@ -231,4 +341,129 @@ impl Engine {
         self.def_tag = value.into();
         self
     }
+
+    /// Get the maximum number of strings interned (0 if string interning is disabled).
+    #[inline]
+    #[must_use]
+    pub fn max_strings_interned(&self) -> usize {
+        self.interned_strings
+            .as_ref()
+            .map_or(0, |interner| locked_read(interner).max())
+    }
+    /// Set the maximum number of strings interned.
+    ///
+    /// Set to zero to disable string interning.
+    ///
+    /// If there are more strings interned than the new maximum, the excess strings are evicted.
+    /// Strings already handed out are not affected.
+    #[inline]
+    pub fn set_max_strings_interned(&mut self, max: usize) -> &mut Self {
+        if max == 0 {
+            self.interned_strings = None;
+        } else if let Some(ref interner) = self.interned_strings {
+            locked_write(interner).set_max(max);
+        } else {
+            self.interned_strings = Some(Locked::new(StringsInterner::new_with_max(max)).into());
+        }
+        self
+    }
+    /// Get statistics of the strings interned by this [`Engine`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_max_strings_interned(100);
+    ///
+    /// let stats = engine.interner_stats();
+    ///
+    /// assert_eq!(stats.max, 100);
+    /// assert!(stats.count <= 100);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn interner_stats(&self) -> StringsInternerStats {
+        self.interned_strings
+            .as_ref()
+            .map_or_else(StringsInternerStats::default, |interner| {
+                locked_read(interner).stats()
+            })
+    }
+    /// Remove all strings interned by this [`Engine`].
+    ///
+    /// Strings already handed out are not affected.
+    #[inline]
+    pub fn clear_string_interner(&mut self) -> &mut Self {
+        if let Some(ref interner) = self.interned_strings {
+            locked_write(interner).clear();
+        }
+        self
+    }
+    /// Set the rounding strategy used when the results of [`Decimal`][rust_decimal::Decimal]
+    /// arithmetic are rounded to the fixed scale set via [`set_decimal_scale`][Engine::set_decimal_scale],
+    /// and by the `round_to`, `with_scale` and `rescale` functions.
+    ///
+    /// The default is [`MidpointNearestEven`][rust_decimal::RoundingStrategy::MidpointNearestEven]
+    /// (i.e. banker's rounding).
+    ///
+    /// Requires the `decimal` feature.
+    #[cfg(feature = "decimal")]
+    #[inline(always)]
+    pub fn set_decimal_rounding(&mut self, strategy: rust_decimal::RoundingStrategy) -> &mut Self {
+        self.decimal_rounding = strategy;
+        self
+    }
+    /// The rounding strategy used for [`Decimal`][rust_decimal::Decimal] arithmetic.
+    ///
+    /// Requires the `decimal` feature.
+    #[cfg(feature = "decimal")]
+    #[inline(always)]
+    #[must_use]
+    pub const fn decimal_rounding(&self) -> rust_decimal::RoundingStrategy {
+        self.decimal_rounding
+    }
+    /// Set a fixed scale (i.e. number of digits after the decimal point) that the results of
+    /// [`Decimal`][rust_decimal::Decimal] arithmetic operators (e.g. `/`) are rounded to,
+    /// using the rounding strategy set via [`set_decimal_rounding`][Engine::set_decimal_rounding].
+    ///
+    /// Set to `None` (the default) to keep the results unchanged.
+    ///
+    /// Requires the `decimal` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    /// use rust_decimal::Decimal;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_decimal_scale(Some(2));
+    ///
+    /// let result = engine.eval::<Decimal>("parse_decimal(\"1.0\") / 8")?;
+    ///
+    /// assert_eq!(result.to_string(), "0.12");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "decimal")]
+    #[inline(always)]
+    pub fn set_decimal_scale(&mut self, scale: Option<u32>) -> &mut Self {
+        self.decimal_scale = scale;
+        self
+    }
+    /// The fixed scale that the results of [`Decimal`][rust_decimal::Decimal] arithmetic
+    /// operators are rounded to, if any.
+    ///
+    /// Requires the `decimal` feature.
+    #[cfg(feature = "decimal")]
+    #[inline(always)]
+    #[must_use]
+    pub const fn decimal_scale(&self) -> Option<u32> {
+        self.decimal_scale
+    }
 }
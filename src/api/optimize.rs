@@ -1,7 +1,10 @@
 //! Module that defines the script optimization API of [`Engine`].
 #![cfg(not(feature = "no_optimize"))]
 
-use crate::{optimizer::optimize_into_ast, Engine, OptimizationLevel, Scope, AST};
+use crate::{
+    optimizer::{optimize_changes_into_ast, optimize_into_ast},
+    Engine, OptimizationLevel, Scope, AST,
+};
 
 impl Engine {
     /// Control whether and how the [`Engine`] will optimize an [`AST`] after compilation.
@@ -23,6 +26,86 @@ impl Engine {
         self.optimization_level
     }
 
+    /// Set the maximum number of iterations of a `for` loop over a constant integer range
+    /// (e.g. `for i in 0..4`) that the optimizer will unroll.
+    /// Use zero to disable loop unrolling.
+    ///
+    /// Not available under `no_optimize`.
+    #[inline(always)]
+    pub fn set_loop_unroll_threshold(&mut self, threshold: usize) -> &mut Self {
+        self.loop_unroll_threshold = threshold;
+        self
+    }
+
+    /// The maximum number of iterations of a `for` loop over a constant integer range
+    /// that the optimizer will unroll (zero if disabled).
+    ///
+    /// Not available under `no_optimize`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn loop_unroll_threshold(&self) -> usize {
+        self.loop_unroll_threshold
+    }
+
+    /// Set whether the optimizer folds constants of statically-known modules into scripts.
+    ///
+    /// Not available under `no_optimize` or `no_module`.
+    ///
+    /// When enabled (and not [`OptimizationLevel::None`]), namespace-qualified variables
+    /// (e.g. `c::MAX`) are replaced with their values if the module is either:
+    ///
+    /// * a static module registered via [`register_static_module`][Engine::register_static_module], or
+    /// * imported via an `import` statement with a constant path that is found in the module
+    ///   resolver embedded in the [`AST`] (see
+    ///   [`compile_into_self_contained`][Engine::compile_into_self_contained]).
+    ///
+    /// This is opt-in because it assumes that these modules do not change after compilation
+    /// and that static modules are not shadowed by modules imported elsewhere (e.g. by the
+    /// caller of a function) under the same name.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Module, INT};
+    ///
+    /// let mut module = Module::new();
+    /// module.set_var("MAX", 100 as INT);
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.register_static_module("c", module.into());
+    /// engine.set_fold_module_constants(true);
+    ///
+    /// let ast = engine.compile("c::MAX + 1")?;
+    ///
+    /// // The constant is folded into the script
+    /// assert!(!format!("{ast:?}").contains("Variable"));
+    /// assert_eq!(engine.eval_ast::<INT>(&ast)?, 101);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub fn set_fold_module_constants(&mut self, enable: bool) -> &mut Self {
+        self.options.set(
+            crate::api::options::LangOptions::FOLD_MODULE_CONSTANTS,
+            enable,
+        );
+        self
+    }
+
+    /// Does the optimizer fold constants of statically-known modules into scripts?
+    /// Default is `false`.
+    ///
+    /// Not available under `no_optimize` or `no_module`.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn fold_module_constants(&self) -> bool {
+        self.options
+            .contains(crate::api::options::LangOptions::FOLD_MODULE_CONSTANTS)
+    }
+
     /// Optimize the [`AST`] with constants defined in an external Scope.
     /// An optimized copy of the [`AST`] is returned while the original [`AST`] is consumed.
     ///
@@ -60,9 +143,14 @@ impl Engine {
                 .iter_fn()
                 .map(|f| f.func.get_script_fn_def().cloned().expect("`ScriptFnDef"))
                 .collect(),
+            #[cfg(not(feature = "no_module"))]
+            ast.resolver.take(),
             optimization_level,
         );
 
+        _new_ast.set_language_version(ast.language_version());
+        _new_ast.node_ids = std::mem::take(&mut ast.node_ids);
+
         #[cfg(feature = "metadata")]
         {
             _new_ast.doc = std::mem::take(&mut ast.doc);
@@ -70,4 +158,61 @@ impl Engine {
 
         _new_ast
     }
+
+    /// Re-optimize an [`AST`] after some constants in an external Scope have changed.
+    /// An optimized copy of the [`AST`] is returned while the original [`AST`] is consumed.
+    ///
+    /// Not available under `no_optimize`.
+    ///
+    /// This is similar to [`optimize_ast`][Engine::optimize_ast], except that only the parts of
+    /// the [`AST`] that use any of the changed constants are re-optimized: if any top-level
+    /// statement uses a changed constant, all top-level statements are re-optimized; functions
+    /// are re-optimized individually and those that do not use any changed constant are kept
+    /// untouched.
+    ///
+    /// Constants are detected via [`AST::collect_free_variables`]. Statements and functions that
+    /// may access variables dynamically (e.g. via `eval`) are always re-optimized.
+    ///
+    /// Constants in `changed_constants` must not have been propagated into the [`AST`] with
+    /// different values before (e.g. they were newly added to the Scope), as they are no longer
+    /// referred to by name afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, OptimizationLevel, Scope, INT};
+    ///
+    /// let engine = Engine::new();
+    /// let mut scope = Scope::new();
+    ///
+    /// let ast = engine.compile("if DEBUG { 1 } else { 2 }")?;
+    ///
+    /// scope.push_constant("DEBUG", true);
+    ///
+    /// let ast = engine.optimize_ast_incremental(&scope, ast, OptimizationLevel::Simple, &["DEBUG"]);
+    ///
+    /// // The constant is propagated into the script
+    /// assert!(ast.collect_free_variables().is_empty());
+    /// assert_eq!(engine.eval_ast::<INT>(&ast)?, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn optimize_ast_incremental(
+        &self,
+        scope: &Scope,
+        ast: AST,
+        optimization_level: OptimizationLevel,
+        changed_constants: &[&str],
+    ) -> AST {
+        optimize_changes_into_ast(
+            self,
+            Some(scope),
+            ast,
+            changed_constants,
+            optimization_level,
+        )
+    }
 }
@@ -8,7 +8,7 @@ use std::prelude::v1::*;
 bitflags! {
     /// Bit-flags containing all language options for the [`Engine`].
     #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
-    pub struct LangOptions: u16 {
+    pub struct LangOptions: u32 {
         /// Is `if`-expression allowed?
         const IF_EXPR = 0b_0000_0000_0001;
         /// Is `switch` expression allowed?
@@ -32,9 +32,66 @@ bitflags! {
         const FAIL_ON_INVALID_MAP_PROPERTY = 0b_0001_0000_0000;
         /// Fast operators mode?
         const FAST_OPS = 0b_0010_0000_0000;
+        /// Are scope introspection functions (e.g. `scope_var_names`) available to scripts?
+        const SCOPE_INTROSPECTION = 0b_0100_0000_0000;
+        /// Record the provenance of values produced by expressions?
+        const TRACK_PROVENANCE = 0b_1000_0000_0000;
+        /// Can `switch` match values of custom types against case literals via `==`?
+        const SWITCH_CUSTOM_TYPES = 0b_0001_0000_0000_0000;
+        /// Are built-in integer operators always checked, even under `unchecked`?
+        const STRICT_ARITHMETIC = 0b_0010_0000_0000_0000;
+        /// Warn about unused parameters of script-defined functions?
+        const WARN_UNUSED_PARAMS = 0b_0100_0000_0000_0000;
+        /// Attach the spans of the expressions where they occur to runtime errors?
+        const ERROR_SPANS = 0b_1000_0000_0000_0000;
+        /// Capture the call stack of script-defined functions into runtime errors?
+        #[cfg(not(feature = "no_function"))]
+        const CAPTURE_STACK_ON_ERROR = 0b_0001_0000_0000_0000_0000;
+        /// Strict functions mode?
+        const STRICT_FN = 0b_0010_0000_0000_0000_0000;
+        /// Warn about variables shadowing (when shadowing is allowed)?
+        const WARN_SHADOWING = 0b_0100_0000_0000_0000_0000;
+        /// Warn about parameters of script-defined functions shadowing global variables?
+        #[cfg(not(feature = "no_function"))]
+        const WARN_PARAM_SHADOWING = 0b_1000_0000_0000_0000_0000;
+        /// Forbid parameters of script-defined functions shadowing global variables?
+        #[cfg(not(feature = "no_function"))]
+        const DENY_PARAM_SHADOWING = 0b_0001_0000_0000_0000_0000_0000;
+        /// Assign ID's to the nodes of compiled [`AST`][crate::AST]'s?
+        const ASSIGN_NODE_IDS = 0b_0010_0000_0000_0000_0000_0000;
+        /// Fold constants of static modules into scripts during optimization?
+        #[cfg(not(feature = "no_module"))]
+        const FOLD_MODULE_CONSTANTS = 0b_0100_0000_0000_0000_0000_0000;
     }
 }
 
+/// Policy on variables _shadowing_ other variables with the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ShadowingPolicy {
+    /// Shadowing is allowed.
+    #[default]
+    Allow,
+    /// Shadowing is allowed, but reported as a [warning][crate::ParseWarning] by
+    /// [`compile_with_warnings`][Engine::compile_with_warnings].
+    Warn,
+    /// Shadowing is not allowed.
+    Deny,
+}
+
+/// Format of the debug output of arrays and object maps (e.g. via `debug` and `to_debug`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DebugFormat {
+    /// All items on a single line, e.g. `[1, #{"a": 2}]`.
+    #[default]
+    Compact,
+    /// Each item on its own line (followed by a comma), with nested items indented by the
+    /// specified number of spaces per level.
+    Pretty {
+        /// Number of spaces per level of indentation.
+        indent: usize,
+    },
+}
+
 impl LangOptions {
     /// Create a new [`LangOptions`] with default values.
     #[inline(always)]
@@ -48,6 +105,7 @@ impl LangOptions {
                 | Self::LOOPING.bits()
                 | Self::SHADOWING.bits()
                 | Self::FAST_OPS.bits()
+                | Self::WARN_UNUSED_PARAMS.bits()
                 | {
                     #[cfg(not(feature = "no_function"))]
                     {
@@ -160,6 +218,98 @@ impl Engine {
         self.options.set(LangOptions::SHADOWING, enable);
         self
     }
+    /// Get the policy on variables shadowing.
+    /// Default is [`ShadowingPolicy::Allow`].
+    #[inline]
+    #[must_use]
+    pub const fn shadowing_policy(&self) -> ShadowingPolicy {
+        if !self.options.contains(LangOptions::SHADOWING) {
+            ShadowingPolicy::Deny
+        } else if self.options.contains(LangOptions::WARN_SHADOWING) {
+            ShadowingPolicy::Warn
+        } else {
+            ShadowingPolicy::Allow
+        }
+    }
+    /// Set the policy on variables shadowing.
+    ///
+    /// A `let` or `const` statement _shadows_ a variable if it declares a name that is already
+    /// visible, either declared earlier in the same block or an enclosing block, or (outside of
+    /// functions) in the [`Scope`][crate::Scope] passed to the [`Engine`].
+    ///
+    /// [`ShadowingPolicy::Deny`] is the same as
+    /// [`set_allow_shadowing(false)`][Engine::set_allow_shadowing].
+    ///
+    /// Whatever the policy, callbacks registered via [`on_def_var`][Engine::on_def_var] can find
+    /// out whether a variable will shadow another via
+    /// [`VarDefInfo::will_shadow_other_variables`][crate::VarDefInfo::will_shadow_other_variables].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, ParseWarningKind, ShadowingPolicy};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_shadowing_policy(ShadowingPolicy::Warn);
+    ///
+    /// let (_, warnings) = engine.compile_with_warnings("let x = 1; { let x = x + 1; x }")?;
+    ///
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].kind, ParseWarningKind::ShadowedVariable);
+    /// assert_eq!(warnings[0].message, "Variable 'x' shadows an existing variable");
+    ///
+    /// engine.set_shadowing_policy(ShadowingPolicy::Deny);
+    ///
+    /// assert!(engine.compile("let x = 1; { let x = x + 1; x }").is_err());
+    /// # Ok::<_, rhai::ParseError>(())
+    /// ```
+    #[inline]
+    pub fn set_shadowing_policy(&mut self, policy: ShadowingPolicy) -> &mut Self {
+        self.options
+            .set(LangOptions::SHADOWING, policy != ShadowingPolicy::Deny);
+        self.options
+            .set(LangOptions::WARN_SHADOWING, policy == ShadowingPolicy::Warn);
+        self
+    }
+    /// Get the policy on parameters of script-defined functions shadowing global variables.
+    /// Default is [`ShadowingPolicy::Allow`].
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub const fn param_shadowing_policy(&self) -> ShadowingPolicy {
+        if self.options.contains(LangOptions::DENY_PARAM_SHADOWING) {
+            ShadowingPolicy::Deny
+        } else if self.options.contains(LangOptions::WARN_PARAM_SHADOWING) {
+            ShadowingPolicy::Warn
+        } else {
+            ShadowingPolicy::Allow
+        }
+    }
+    /// Set the policy on parameters of script-defined functions shadowing global variables.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// A parameter _shadows_ a global variable if it has the same name as a variable or constant
+    /// declared at global level before the function definition, or in the
+    /// [`Scope`][crate::Scope] passed to the [`Engine`].
+    ///
+    /// This policy is independent of [`set_shadowing_policy`][Engine::set_shadowing_policy].
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    pub fn set_param_shadowing_policy(&mut self, policy: ShadowingPolicy) -> &mut Self {
+        self.options.set(
+            LangOptions::DENY_PARAM_SHADOWING,
+            policy == ShadowingPolicy::Deny,
+        );
+        self.options.set(
+            LangOptions::WARN_PARAM_SHADOWING,
+            policy == ShadowingPolicy::Warn,
+        );
+        self
+    }
     /// Is strict variables mode enabled?
     /// Default is `false`.
     #[inline(always)]
@@ -173,6 +323,39 @@ impl Engine {
         self.options.set(LangOptions::STRICT_VAR, enable);
         self
     }
+    /// Is strict functions mode enabled?
+    /// Default is `false`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn strict_functions(&self) -> bool {
+        self.options.contains(LangOptions::STRICT_FN)
+    }
+    /// Set whether strict functions mode is enabled.
+    ///
+    /// Under strict functions mode, all function calls in a script are checked during compilation
+    /// against the functions defined in the script itself, the functions registered with the
+    /// [`Engine`] (including those in packages and global modules), and the functions in static
+    /// modules. A call to an unknown function, or with the wrong number of arguments, is a
+    /// [`ParseErrorType::FnUndefined`][crate::ParseErrorType::FnUndefined] error.
+    ///
+    /// The following calls are not checked:
+    ///
+    /// * method calls (e.g. `x.foo()`), which may call function pointers held in object map
+    ///   properties,
+    /// * calls through function pointers, including `call`, `curry`, `Fn` and `eval`,
+    /// * operators,
+    /// * calls qualified by the name of a module imported via an `import` statement.
+    ///
+    /// In addition, when the script contains `import` statements, calls that cannot be resolved
+    /// are not errors because they may be resolved to global functions in the imported modules.
+    ///
+    /// Functions registered with the [`Engine`] after compilation, or defined in other scripts,
+    /// are not visible during the check.
+    #[inline(always)]
+    pub fn set_strict_functions(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::STRICT_FN, enable);
+        self
+    }
     /// Raise error if an object map property does not exist?
     /// Default is `false`.
     ///
@@ -207,4 +390,208 @@ impl Engine {
         self.options.set(LangOptions::FAST_OPS, enable);
         self
     }
+    /// Are scope introspection functions (`scope_var_names`, `scope_var` and `scope_var_type`)
+    /// available to scripts?
+    /// Default is `false`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn scope_introspection(&self) -> bool {
+        self.options.contains(LangOptions::SCOPE_INTROSPECTION)
+    }
+    /// Set whether scope introspection functions (`scope_var_names`, `scope_var` and
+    /// `scope_var_type`) are available to scripts.
+    ///
+    /// These functions expose the names and values of all variables in the current scope,
+    /// so they are turned off by default.
+    #[inline(always)]
+    pub fn set_scope_introspection(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::SCOPE_INTROSPECTION, enable);
+        self
+    }
+    /// Is provenance tracking of values enabled?
+    /// Default is `false`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn track_provenance(&self) -> bool {
+        self.options.contains(LangOptions::TRACK_PROVENANCE)
+    }
+    /// Set whether provenance tracking of values is enabled.
+    ///
+    /// When enabled, every value produced by an expression records the position of the
+    /// expression that produced it (see [`Dynamic::provenance`][crate::Dynamic::provenance]).
+    ///
+    /// The provenance is stored in the value's _tag_, which is therefore overwritten.
+    /// This mode is intended for debugging and slows down evaluation.
+    #[inline(always)]
+    pub fn set_track_provenance(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::TRACK_PROVENANCE, enable);
+        self
+    }
+    /// Can `switch` match values of custom types?
+    /// Default is `false`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn allow_switch_on_custom_types(&self) -> bool {
+        self.options.contains(LangOptions::SWITCH_CUSTOM_TYPES)
+    }
+    /// Set whether `switch` can match values of custom types.
+    ///
+    /// When enabled, a `switch` value that is not hashable (e.g. a custom type) is compared
+    /// against each case literal in turn via the `==` operator, which must be registered for
+    /// the custom type and the type of the literal.
+    ///
+    /// This only takes effect for scripts compiled while it is enabled.
+    #[inline(always)]
+    pub fn set_allow_switch_on_custom_types(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::SWITCH_CUSTOM_TYPES, enable);
+        self
+    }
+    /// Is strict arithmetic enabled?
+    /// Default is `false`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn strict_arithmetic(&self) -> bool {
+        self.options.contains(LangOptions::STRICT_ARITHMETIC)
+    }
+    /// Set whether strict arithmetic is enabled.
+    ///
+    /// When enabled, the built-in arithmetic operators for integers (`+`, `-`, `*`, `/`, `%`,
+    /// `**` and their assignment forms) always check for overflow and division by zero,
+    /// raising [`ErrorArithmetic`][crate::EvalAltResult::ErrorArithmetic], even when built
+    /// with the `unchecked` feature.
+    ///
+    /// Without `unchecked`, integer arithmetic is always checked and this setting has no effect.
+    #[inline(always)]
+    pub fn set_strict_arithmetic(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::STRICT_ARITHMETIC, enable);
+        self
+    }
+    /// Are unused parameters of script-defined functions reported as warnings?
+    /// Default is `true`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn warn_unused_params(&self) -> bool {
+        self.options.contains(LangOptions::WARN_UNUSED_PARAMS)
+    }
+    /// Set whether unused parameters of script-defined functions are reported as warnings by
+    /// [`compile_with_warnings`][Engine::compile_with_warnings].
+    #[inline(always)]
+    pub fn set_warn_unused_params(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::WARN_UNUSED_PARAMS, enable);
+        self
+    }
+    /// Are runtime errors wrapped with the [spans][crate::Span] of the expressions where they occur?
+    /// Default is `false`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn error_spans(&self) -> bool {
+        self.options.contains(LangOptions::ERROR_SPANS)
+    }
+    /// Set whether runtime errors are wrapped with the [spans][crate::Span] of the expressions
+    /// where they occur.
+    ///
+    /// When enabled, catchable errors raised while evaluating an expression are wrapped in
+    /// [`EvalAltResult::ErrorWithSpan`][crate::EvalAltResult::ErrorWithSpan], which holds the span
+    /// of the innermost expression (see [`EvalAltResult::span`][crate::EvalAltResult::span]).
+    /// Use [`EvalAltResult::unwrap_inner`][crate::EvalAltResult::unwrap_inner] to get the
+    /// original error.
+    ///
+    /// Not available under `no_position`.
+    #[cfg(not(feature = "no_position"))]
+    #[inline(always)]
+    pub fn set_error_spans(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::ERROR_SPANS, enable);
+        self
+    }
+    /// Is the call stack of script-defined functions captured into runtime errors?
+    /// Default is `false`.
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn capture_stack_on_error(&self) -> bool {
+        self.options.contains(LangOptions::CAPTURE_STACK_ON_ERROR)
+    }
+    /// Set whether the call stack of script-defined functions is captured into runtime errors.
+    ///
+    /// When enabled, a catchable error raised inside a script-defined function is wrapped in
+    /// [`EvalAltResult::ErrorWithCallStack`][crate::EvalAltResult::ErrorWithCallStack], which
+    /// holds the names of all the functions being called at the time, together with the positions
+    /// of the calls (see [`EvalAltResult::call_stack`][crate::EvalAltResult::call_stack]).
+    ///
+    /// Unlike the call stack kept by the `debugging` feature, function call arguments are not
+    /// recorded. There is no overhead when this option is turned off.
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    pub fn set_capture_stack_on_error(&mut self, enable: bool) -> &mut Self {
+        self.options
+            .set(LangOptions::CAPTURE_STACK_ON_ERROR, enable);
+        self
+    }
+    /// Are ID's assigned to the nodes of compiled [`AST`][crate::AST]'s?
+    /// Default is `false`.
+    #[inline(always)]
+    #[must_use]
+    pub const fn assign_node_ids(&self) -> bool {
+        self.options.contains(LangOptions::ASSIGN_NODE_IDS)
+    }
+    /// Set whether ID's are assigned to the nodes of compiled [`AST`][crate::AST]'s.
+    ///
+    /// When enabled, each statement and expression in a script is given a numeric ID during
+    /// compilation, in the order they are parsed, starting from 1. Nodes that are not optimized
+    /// away keep their ID's when the [`AST`][crate::AST] is optimized, including via
+    /// [`optimize_ast`][Engine::optimize_ast], so external tools can refer to nodes by ID.
+    ///
+    /// Node ID's are kept in a side-table indexed by position, so nodes have no ID under
+    /// `no_position`, and nested nodes of the same type at the same position share the same ID
+    /// (e.g. the two `+` operator calls in `a + b + c`, which both start at `a`).
+    ///
+    /// ID's are obtained via `ASTNode::id` and nodes are looked up via `AST::node_by_id`
+    /// (both under `internals`).
+    #[inline(always)]
+    pub fn set_assign_node_ids(&mut self, enable: bool) -> &mut Self {
+        self.options.set(LangOptions::ASSIGN_NODE_IDS, enable);
+        self
+    }
+    /// Get the format of the debug output of arrays and object maps.
+    /// Default is [`DebugFormat::Compact`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn debug_format(&self) -> DebugFormat {
+        self.debug_format
+    }
+    /// Set the format of the debug output of arrays and object maps, including nested ones, via
+    /// `debug` and `to_debug`. The output of `print` and `to_string` is not affected.
+    ///
+    /// Under any format, a shared array or object map that has already been visited (e.g. one that
+    /// contains itself) is output as `<shared #n>`, where `n` is the order in which shared arrays
+    /// and object maps are first encountered, starting from 1.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{DebugFormat, Engine};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_debug_format(DebugFormat::Pretty { indent: 2 });
+    ///
+    /// # #[cfg(not(feature = "no_index"))]
+    /// # #[cfg(not(feature = "no_object"))]
+    /// assert_eq!(
+    ///     engine.eval::<String>(r#"to_debug([1, #{a: "x", b: []}])"#)?,
+    ///     "[\n  1,\n  #{\n    \"a\": \"x\",\n    \"b\": [],\n  },\n]"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn set_debug_format(&mut self, format: DebugFormat) -> &mut Self {
+        self.debug_format = format;
+        self
+    }
 }
@@ -1,10 +1,10 @@
 //! Module that defines the public function/module registration API of [`Engine`].
 
-use crate::func::{FnCallArgs, RegisterNativeFunction, SendSync};
+use crate::func::{shared_make_mut, FnCallArgs, RegisterNativeFunction, SendSync};
 use crate::module::ModuleFlags;
 use crate::types::dynamic::Variant;
 use crate::{
-    Engine, FnAccess, FnNamespace, Identifier, Module, NativeCallContext, RhaiResultOf, Shared,
+    Engine, FnAccess, FnNamespace, Identifier, Module, NativeCallContext, RhaiResultOf,
     SharedModule,
 };
 use std::any::{type_name, TypeId};
@@ -17,19 +17,28 @@ use crate::func::register::Mut;
 impl Engine {
     /// Get a mutable reference to the global namespace module
     /// (which is the first module in `global_modules`).
+    ///
+    /// The module is copied first if it is shared with another [`Engine`]
+    /// (see [`clone_registrations`][Engine::clone_registrations]).
     #[inline(always)]
     #[must_use]
-    fn global_namespace_mut(&mut self) -> &mut Module {
+    pub(crate) fn global_namespace_mut(&mut self) -> &mut Module {
         if self.global_modules.is_empty() {
             let mut global_namespace = Module::new();
             global_namespace.flags |= ModuleFlags::INTERNAL;
             self.global_modules.push(global_namespace.into());
         }
 
-        Shared::get_mut(self.global_modules.first_mut().unwrap()).expect("not shared")
+        shared_make_mut(self.global_modules.first_mut().unwrap())
     }
     /// Register a custom function with the [`Engine`].
     ///
+    /// # Operators
+    ///
+    /// Operators are overloaded by registering functions named after the operator symbol.
+    /// Binary operators (e.g. `+`, `*`, `==`) call functions with two parameters, while the
+    /// unary operators `-x`, `+x` and `!x` call functions named `-`, `+` and `!` with one parameter.
+    ///
     /// # Example
     ///
     /// ```
@@ -67,30 +76,157 @@ impl Engine {
         name: impl AsRef<str> + Into<Identifier>,
         func: F,
     ) -> &mut Self {
-        let param_types = F::param_types();
-
+        self.register_fn_with_hash(name, func);
+        self
+    }
+    /// Register a custom function with the [`Engine`], returning its hash.
+    #[inline]
+    pub(crate) fn register_fn_with_hash<
+        A: 'static,
+        const N: usize,
+        const C: bool,
+        R: Variant + Clone,
+        const L: bool,
+        F: RegisterNativeFunction<A, N, C, R, L> + SendSync + 'static,
+    >(
+        &mut self,
+        name: impl AsRef<str> + Into<Identifier>,
+        func: F,
+    ) -> u64 {
         #[cfg(feature = "metadata")]
+        return self.register_fn_with_metadata_and_hash(name, func, &[], "", &[]);
+
+        #[cfg(not(feature = "metadata"))]
+        return self.register_native_fn(name, func, None);
+    }
+    /// _(metadata)_ Register a custom function with the [`Engine`], together with the names of
+    /// its parameters, the name of its return type and its doc-comments.
+    /// Exported under the `metadata` feature only.
+    ///
+    /// The extra information is included in the function metadata, e.g. in
+    /// [`gen_fn_metadata_to_json`][Engine::gen_fn_metadata_to_json] and in the output of
+    /// `Engine::definitions` (under `internals`).
+    ///
+    /// ## Parameter Names
+    ///
+    /// Each parameter name may optionally include a type (i.e. `var_name: type`),
+    /// otherwise the name of the parameter's Rust type is used.
+    ///
+    /// Parameters without names are shown as `_`.
+    ///
+    /// ## Return Type
+    ///
+    /// If `return_type` is empty, the name of the function's Rust return type is used.
+    ///
+    /// ## Comments
+    ///
+    /// Block doc-comments should be kept in a separate string slice.
+    ///
+    /// Line doc-comments should be merged, with line-breaks, into a single string slice without a final termination line-break.
+    ///
+    /// Leading white-spaces should be stripped, and each string slice always starts with the corresponding
+    /// doc-comment leader: `///` or `/**`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_fn_with_metadata(
+    ///     "add",
+    ///     |x: i64, y: i64| x + y,
+    ///     &["x", "y"],
+    ///     "",
+    ///     &["/// Add two numbers together."],
+    /// );
+    ///
+    /// assert_eq!(engine.eval::<i64>("add(40, 2)")?, 42);
+    ///
+    /// let signatures = engine.gen_fn_signatures(false);
+    ///
+    /// assert_eq!(signatures[0], "add(x: i64, y: i64) -> i64");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "metadata")]
+    #[inline]
+    pub fn register_fn_with_metadata<
+        A: 'static,
+        const N: usize,
+        const C: bool,
+        R: Variant + Clone,
+        const L: bool,
+        F: RegisterNativeFunction<A, N, C, R, L> + SendSync + 'static,
+    >(
+        &mut self,
+        name: impl AsRef<str> + Into<Identifier>,
+        func: F,
+        param_names: &[&str],
+        return_type: &str,
+        comments: &[&str],
+    ) -> &mut Self {
+        self.register_fn_with_metadata_and_hash(name, func, param_names, return_type, comments);
+        self
+    }
+    /// Register a custom function with the [`Engine`], together with its metadata, returning its hash.
+    #[cfg(feature = "metadata")]
+    fn register_fn_with_metadata_and_hash<
+        A: 'static,
+        const N: usize,
+        const C: bool,
+        R: Variant + Clone,
+        const L: bool,
+        F: RegisterNativeFunction<A, N, C, R, L> + SendSync + 'static,
+    >(
+        &mut self,
+        name: impl AsRef<str> + Into<Identifier>,
+        func: F,
+        param_names: &[&str],
+        return_type: &str,
+        comments: &[&str],
+    ) -> u64 {
         let mut param_type_names = F::param_names()
             .iter()
-            .map(|ty| format!("_: {}", self.format_type_name(ty)))
+            .enumerate()
+            .map(|(i, ty)| match param_names.get(i) {
+                Some(name) if name.contains(':') => (*name).to_string(),
+                Some(name) if !name.is_empty() => format!("{name}: {}", self.format_type_name(ty)),
+                _ => format!("_: {}", self.format_type_name(ty)),
+            })
             .collect::<crate::FnArgsVec<_>>();
 
-        #[cfg(feature = "metadata")]
-        if F::return_type() != TypeId::of::<()>() {
+        if !return_type.is_empty() {
+            param_type_names.push(return_type.into());
+        } else if F::return_type() != TypeId::of::<()>() {
             param_type_names.push(self.format_type_name(F::return_type_name()).into());
         }
 
-        #[cfg(feature = "metadata")]
         let param_type_names = param_type_names
             .iter()
             .map(String::as_str)
             .collect::<crate::FnArgsVec<_>>();
-        #[cfg(feature = "metadata")]
-        let param_type_names = Some(param_type_names.as_ref());
-
-        #[cfg(not(feature = "metadata"))]
-        let param_type_names: Option<&[&str]> = None;
 
+        self.register_native_fn(name, func, Some(param_type_names.as_ref()), comments)
+    }
+    /// Register a native Rust function into the global namespace, returning its hash.
+    fn register_native_fn<
+        A: 'static,
+        const N: usize,
+        const C: bool,
+        R: Variant + Clone,
+        const L: bool,
+        F: RegisterNativeFunction<A, N, C, R, L> + SendSync + 'static,
+    >(
+        &mut self,
+        name: impl AsRef<str> + Into<Identifier>,
+        func: F,
+        param_type_names: Option<&[&str]>,
+        #[cfg(feature = "metadata")] comments: &[&str],
+    ) -> u64 {
+        let param_types = F::param_types();
         let fn_name = name.as_ref();
         let is_pure = true;
 
@@ -102,7 +238,18 @@ impl Engine {
 
         let func = func.into_callable_function(fn_name.into(), is_pure);
 
-        self.global_namespace_mut().set_fn(
+        #[cfg(feature = "metadata")]
+        return self.global_namespace_mut().set_fn_with_comments(
+            name,
+            FnNamespace::Global,
+            FnAccess::Public,
+            param_type_names,
+            param_types,
+            comments,
+            func,
+        );
+        #[cfg(not(feature = "metadata"))]
+        return self.global_namespace_mut().set_fn(
             name,
             FnNamespace::Global,
             FnAccess::Public,
@@ -110,7 +257,6 @@ impl Engine {
             param_types,
             func,
         );
-        self
     }
     /// Register a function of the [`Engine`].
     ///
@@ -325,6 +471,74 @@ impl Engine {
     ) -> &mut Self {
         self.register_fn(crate::engine::make_getter(name.as_ref()), get_fn)
     }
+    /// Register a getter function for an optional member of a registered type with the [`Engine`].
+    ///
+    /// The function signature must start with `&mut self` and not `&self`.
+    ///
+    /// If the getter returns `None`, the property is treated as missing: `()` is returned, or
+    /// an [`ErrorPropertyNotFound`][crate::EvalAltResult::ErrorPropertyNotFound] error is raised
+    /// if [`fail_on_invalid_map_property`][Engine::fail_on_invalid_map_property] is set.
+    ///
+    /// Not available under `no_object`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// #[derive(Clone)]
+    /// struct TestStruct {
+    ///     fields: HashMap<String, i64>
+    /// }
+    ///
+    /// impl TestStruct {
+    ///     fn new() -> Self {
+    ///         Self { fields: [("foo".to_string(), 1)].into() }
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine
+    ///     .register_type::<TestStruct>()
+    ///     .register_fn("new_ts", TestStruct::new)
+    ///     .register_get_opt("foo", |obj: &mut TestStruct| obj.fields.get("foo").copied())
+    ///     .register_get_opt("bar", |obj: &mut TestStruct| obj.fields.get("bar").copied());
+    ///
+    /// assert_eq!(engine.eval::<i64>("let a = new_ts(); a.foo")?, 1);
+    /// assert_eq!(engine.eval::<()>("let a = new_ts(); a.bar")?, ());
+    ///
+    /// engine.set_fail_on_invalid_map_property(true);
+    ///
+    /// assert!(engine.eval::<()>("let a = new_ts(); a.bar").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    #[inline]
+    pub fn register_get_opt<T: Variant + Clone, V: Variant + Clone>(
+        &mut self,
+        name: impl AsRef<str>,
+        get_fn: impl Fn(&mut T) -> Option<V> + SendSync + 'static,
+    ) -> &mut Self {
+        let prop = name.as_ref().to_string();
+
+        self.register_fn(
+            crate::engine::make_getter(name.as_ref()),
+            move |ctx: NativeCallContext, obj: &mut T| -> RhaiResultOf<_> {
+                match get_fn(obj) {
+                    Some(value) => Ok(crate::Dynamic::from(value)),
+                    None if ctx.engine().fail_on_invalid_map_property() => {
+                        Err(crate::ERR::ErrorPropertyNotFound(prop.clone(), ctx.position()).into())
+                    }
+                    None => Ok(crate::Dynamic::UNIT),
+                }
+            },
+        )
+    }
     /// Register a setter function for a member of a registered type with the [`Engine`].
     ///
     /// Not available under `no_object`.
@@ -774,30 +988,221 @@ impl Engine {
             }
         }
 
+        self.lazy_sub_modules.remove(name.as_ref());
         register_static_module_raw(&mut self.global_sub_modules, name.as_ref(), module);
         self
     }
+    /// Register a static module namespace with the [`Engine`] which is only built upon first
+    /// access.
+    ///
+    /// This is useful for modules that are expensive to build but rarely used by scripts.
+    ///
+    /// The initialization function is called (exactly once) when the module namespace is first
+    /// accessed via a qualified name, e.g. calling a function `name::func(...)` or reading a
+    /// variable `name::VAR`.
+    ///
+    /// Unlike [`register_static_module`][Engine::register_static_module]:
+    ///
+    /// * functions marked [`FnNamespace::Global`] and type iterators are _not_ exposed to scripts
+    ///   without namespace qualifications, because that would require the module to be built;
+    ///
+    /// * the module is not included in metadata and definitions generated for the [`Engine`].
+    ///
+    /// Not available under `no_module`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name contains the namespace separator `::`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Module};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.register_lazy_static_module("tables", || {
+    ///     // Expensive initialization...
+    ///     let mut module = Module::new();
+    ///     module.set_var("SIZE", 42_i64);
+    ///     module.into()
+    /// });
+    ///
+    /// assert_eq!(engine.eval::<i64>("tables::SIZE")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    pub fn register_lazy_static_module(
+        &mut self,
+        name: impl AsRef<str> + Into<Identifier>,
+        init: impl FnOnce() -> SharedModule + SendSync + 'static,
+    ) -> &mut Self {
+        assert!(
+            !name.as_ref().contains(crate::engine::NAMESPACE_SEPARATOR),
+            "lazy static module name cannot contain `{}`",
+            crate::engine::NAMESPACE_SEPARATOR
+        );
+
+        let name = name.into();
+        self.global_sub_modules.remove(&name);
+        self.lazy_sub_modules.insert(
+            name,
+            crate::Locked::new(crate::engine::LazyModule::Lazy(Box::new(init))),
+        );
+        self
+    }
     /// _(metadata)_ Generate a list of all registered functions.
     /// Exported under the `metadata` feature only.
     ///
     /// Functions from the following sources are included, in order:
     /// 1) Functions registered into the global namespace
-    /// 2) Functions in registered sub-modules
+    /// 2) Functions in registered sub-modules, with their namespace (e.g. `foo::bar::func(...)`)
     /// 3) Functions in registered packages
     /// 4) Functions in standard packages (optional)
+    ///
+    /// Functions from each source are sorted, so the output is deterministic.
+    ///
+    /// Signatures are in human-readable form, with type names as registered:
+    ///
+    /// | Kind            | Signature              |
+    /// | --------------- | ---------------------- |
+    /// | function        | `func(x: INT) -> bool` |
+    /// | operator        | `+(x: Foo, y: INT)`    |
+    /// | property getter | `Foo.name: String`     |
+    /// | property setter | `Foo.name = String`    |
+    /// | indexer getter  | `Foo[INT] -> bool`     |
+    /// | indexer setter  | `Foo[INT] = bool`      |
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, Module};
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct Foo(String);
+    ///
+    /// let mut engine = Engine::new_raw();
+    ///
+    /// engine.register_type_with_name::<Foo>("Foo")
+    ///       .register_get("name", |x: &mut Foo| x.0.clone());
+    ///
+    /// let mut module = Module::new();
+    /// let hash = module.set_native_fn("double", |x: i64| Ok(x * 2));
+    /// module.update_fn_metadata(hash, ["x: i64", "i64"]);
+    /// engine.register_static_module("calc", module.into());
+    ///
+    /// let signatures = engine.gen_fn_signatures(false);
+    ///
+    /// assert_eq!(signatures, ["Foo.name: String", "calc::double(x: i64) -> i64"]);
+    /// ```
     #[cfg(feature = "metadata")]
     #[inline]
     #[must_use]
     pub fn gen_fn_signatures(&self, include_packages: bool) -> Vec<String> {
+        self.gen_fn_signatures_grouped(include_packages)
+            .iter()
+            .map(|f| self.format_fn_signature(f))
+            .collect()
+    }
+    /// _(metadata)_ Generate a list of all registered functions, together with all the functions
+    /// defined in an [`AST`][crate::AST].
+    /// Exported under the `metadata` feature only.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// Functions defined in the [`AST`][crate::AST] come first, followed by the functions listed
+    /// by [`gen_fn_signatures`][Engine::gen_fn_signatures].
+    #[cfg(feature = "metadata")]
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub fn gen_fn_signatures_with_ast(
+        &self,
+        ast: &crate::AST,
+        include_packages: bool,
+    ) -> Vec<String> {
+        self.gen_fn_signatures_grouped_with_ast(ast, include_packages)
+            .iter()
+            .map(|f| self.format_fn_signature(f))
+            .collect()
+    }
+    /// _(metadata)_ Generate structured signatures of all registered functions.
+    /// Exported under the `metadata` feature only.
+    ///
+    /// Functions are included from the same sources, and in the same order, as
+    /// [`gen_fn_signatures`][Engine::gen_fn_signatures].  Functions in sub-modules of static
+    /// modules are also included.
+    ///
+    /// Each entry records where it comes from (see [`FnSignatureSource`][crate::FnSignatureSource]).
+    /// The output is deterministic.
+    #[cfg(feature = "metadata")]
+    #[inline(always)]
+    #[must_use]
+    pub fn gen_fn_signatures_grouped(&self, include_packages: bool) -> Vec<crate::FnSignatureInfo> {
+        self.collect_fn_signatures(None, include_packages)
+    }
+    /// _(metadata)_ Generate structured signatures of all registered functions, together with all
+    /// the functions defined in an [`AST`][crate::AST].
+    /// Exported under the `metadata` feature only.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// Functions defined in the [`AST`][crate::AST] come first, with the source
+    /// [`FnSignatureSource::Script`][crate::FnSignatureSource::Script], followed by the functions
+    /// listed by [`gen_fn_signatures_grouped`][Engine::gen_fn_signatures_grouped].
+    #[cfg(feature = "metadata")]
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub fn gen_fn_signatures_grouped_with_ast(
+        &self,
+        ast: &crate::AST,
+        include_packages: bool,
+    ) -> Vec<crate::FnSignatureInfo> {
+        self.collect_fn_signatures(Some(ast), include_packages)
+    }
+    /// Collect structured signatures of all registered functions, and those defined in an
+    /// [`AST`][crate::AST] if any.
+    #[cfg(feature = "metadata")]
+    fn collect_fn_signatures(
+        &self,
+        _ast: Option<&crate::AST>,
+        include_packages: bool,
+    ) -> Vec<crate::FnSignatureInfo> {
+        use crate::{FnSignatureOptions, FnSignatureSource};
+
+        let options = FnSignatureOptions::new();
         let mut signatures = Vec::with_capacity(64);
 
+        #[cfg(not(feature = "no_function"))]
+        if let Some(ast) = _ast {
+            ast.shared_lib().collect_fn_signatures(
+                options,
+                &mut Vec::new(),
+                &FnSignatureSource::Script,
+                &mut signatures,
+            );
+        }
+
         if let Some(global_namespace) = self.global_modules.first() {
-            signatures.extend(global_namespace.gen_fn_signatures());
+            global_namespace.collect_fn_signatures(
+                options,
+                &mut Vec::new(),
+                &FnSignatureSource::Global,
+                &mut signatures,
+            );
         }
 
         #[cfg(not(feature = "no_module"))]
         for (name, m) in &self.global_sub_modules {
-            signatures.extend(m.gen_fn_signatures().map(|f| format!("{name}::{f}")));
+            m.collect_fn_signatures(
+                options,
+                &mut vec![name.clone()],
+                &FnSignatureSource::StaticModule(name.clone()),
+                &mut signatures,
+            );
         }
 
         let exclude_flags = if include_packages {
@@ -806,14 +1211,23 @@ impl Engine {
             crate::module::ModuleFlags::INTERNAL | crate::module::ModuleFlags::STANDARD_LIB
         };
 
-        signatures.extend(
-            self.global_modules
-                .iter()
-                .skip(1)
-                .filter(|m| !m.flags.contains(exclude_flags))
-                .flat_map(|m| m.gen_fn_signatures()),
-        );
+        for m in self
+            .global_modules
+            .iter()
+            .skip(1)
+            .filter(|m| !m.flags.contains(exclude_flags))
+        {
+            let source = FnSignatureSource::Package(m.id().map(Into::into));
+            m.collect_fn_signatures(options, &mut Vec::new(), &source, &mut signatures);
+        }
 
         signatures
     }
+    /// Format a structured function signature in human-readable form, with type names as
+    /// registered in this [`Engine`].
+    #[cfg(feature = "metadata")]
+    #[inline]
+    fn format_fn_signature(&self, signature: &crate::FnSignatureInfo) -> String {
+        signature.format(|typ| self.format_type_name(typ))
+    }
 }
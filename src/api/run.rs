@@ -73,7 +73,7 @@ impl Engine {
             };
 
             let state = &mut ParseState::new(Some(scope), interned_strings, tc);
-            self.parse(stream.peekable(), state, self.optimization_level)?
+            self.parse(stream.peekable(), state, |_| {}, self.optimization_level)?
         };
         self.run_ast_with_scope(scope, &ast)
     }
@@ -130,6 +130,10 @@ impl Engine {
         let global = &mut GlobalRuntimeState::new(self);
         global.source = ast.source_raw().cloned();
 
+        if self.stmt_tracer.is_some() {
+            global.node_ids = Some((global.source.clone(), ast.node_ids.clone()));
+        }
+
         #[cfg(not(feature = "no_function"))]
         global.lib.push(ast.shared_lib().clone());
 
@@ -0,0 +1,497 @@
+//! Module that defines the API to take and restore snapshots of [`Engine`] settings.
+#![cfg(not(feature = "no_object"))]
+#![cfg(not(feature = "no_index"))]
+
+use crate::api::options::LangOptions;
+use crate::{Array, Dynamic, Engine, Identifier, Map, INT};
+#[cfg(feature = "no_std")]
+use core_error::Error;
+#[cfg(not(feature = "no_std"))]
+use std::error::Error;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{convert::TryFrom, fmt};
+
+/// Version of the format of the settings snapshot produced by [`Engine::settings`].
+pub const SETTINGS_VERSION: INT = 1;
+
+/// Names of all language options in the settings snapshot.
+const OPTIONS: &[(&str, LangOptions)] = &[
+    ("allow_if_expression", LangOptions::IF_EXPR),
+    ("allow_switch_expression", LangOptions::SWITCH_EXPR),
+    ("allow_loop_expressions", LangOptions::LOOP_EXPR),
+    ("allow_statement_expression", LangOptions::STMT_EXPR),
+    #[cfg(not(feature = "no_function"))]
+    ("allow_anonymous_fn", LangOptions::ANON_FN),
+    ("allow_looping", LangOptions::LOOPING),
+    ("allow_shadowing", LangOptions::SHADOWING),
+    ("strict_variables", LangOptions::STRICT_VAR),
+    ("strict_functions", LangOptions::STRICT_FN),
+    ("warn_on_shadowing", LangOptions::WARN_SHADOWING),
+    #[cfg(not(feature = "no_function"))]
+    ("warn_on_param_shadowing", LangOptions::WARN_PARAM_SHADOWING),
+    #[cfg(not(feature = "no_function"))]
+    ("deny_param_shadowing", LangOptions::DENY_PARAM_SHADOWING),
+    (
+        "fail_on_invalid_map_property",
+        LangOptions::FAIL_ON_INVALID_MAP_PROPERTY,
+    ),
+    ("fast_operators", LangOptions::FAST_OPS),
+    ("scope_introspection", LangOptions::SCOPE_INTROSPECTION),
+    ("track_provenance", LangOptions::TRACK_PROVENANCE),
+    (
+        "allow_switch_on_custom_types",
+        LangOptions::SWITCH_CUSTOM_TYPES,
+    ),
+    ("strict_arithmetic", LangOptions::STRICT_ARITHMETIC),
+    ("warn_unused_params", LangOptions::WARN_UNUSED_PARAMS),
+    ("error_spans", LangOptions::ERROR_SPANS),
+    #[cfg(not(feature = "no_function"))]
+    (
+        "capture_stack_on_error",
+        LangOptions::CAPTURE_STACK_ON_ERROR,
+    ),
+];
+
+/// Settings keys that are part of the snapshot but cannot be applied.
+const READ_ONLY_KEYS: &[&str] = &[
+    "custom_operators",
+    "custom_syntax",
+    "global_modules",
+    "static_modules",
+    "callbacks",
+    "default_tag",
+];
+
+/// Error when applying settings to an [`Engine`] via [`Engine::apply_settings`].
+///
+/// Not available under `no_object` or `no_index`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+#[must_use]
+pub enum SettingsError {
+    /// The settings key is not recognized.
+    /// Wrapped value is the full path of the key (e.g. `limits.max_operations`).
+    UnknownKey(String),
+    /// The value of a settings key is invalid.
+    /// Wrapped values are the full path of the key and a description of the expected value.
+    InvalidValue(String, String),
+}
+
+impl Error for SettingsError {}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKey(key) => write!(f, "Unknown engine setting: '{key}'"),
+            Self::InvalidValue(key, expected) => {
+                write!(
+                    f,
+                    "Invalid value for engine setting '{key}': expecting {expected}"
+                )
+            }
+        }
+    }
+}
+
+impl SettingsError {
+    /// Get the full path of the offending settings key.
+    #[inline]
+    #[must_use]
+    pub fn key(&self) -> &str {
+        match self {
+            Self::UnknownKey(key) | Self::InvalidValue(key, ..) => key,
+        }
+    }
+}
+
+/// Get a settings value as an object map.
+fn as_map<'a>(
+    key: &str,
+    value: &'a Dynamic,
+) -> Result<crate::types::dynamic::DynamicReadLock<'a, Map>, SettingsError> {
+    value
+        .read_lock::<Map>()
+        .ok_or_else(|| SettingsError::InvalidValue(key.into(), "an object map".into()))
+}
+
+/// Get a settings value as a set of identifiers.
+fn to_identifiers(
+    key: &str,
+    value: &Dynamic,
+) -> Result<std::collections::BTreeSet<Identifier>, SettingsError> {
+    let invalid = || SettingsError::InvalidValue(key.into(), "an array of strings".into());
+
+    value
+        .read_lock::<Array>()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|s| {
+            s.read_lock::<crate::ImmutableString>()
+                .map(|s| Identifier::from(s.as_str()))
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+/// Get a settings value as a non-negative integer.
+#[cfg(not(feature = "unchecked"))]
+#[allow(clippy::cast_sign_loss)]
+fn as_usize(key: &str, value: &Dynamic) -> Result<usize, SettingsError> {
+    match value.as_int() {
+        Ok(n) if n >= 0 && (n as u64) <= usize::MAX as u64 => Ok(n as usize),
+        _ => Err(SettingsError::InvalidValue(
+            key.into(),
+            "a non-negative integer".into(),
+        )),
+    }
+}
+
+impl Engine {
+    /// Get a snapshot of all observable settings of the [`Engine`] as an [object map][Map].
+    ///
+    /// Not available under `no_object` or `no_index`.
+    ///
+    /// The snapshot contains:
+    ///
+    /// * `version` - version of the snapshot format ([`SETTINGS_VERSION`][crate::SETTINGS_VERSION])
+    /// * `options` - language options (e.g. `strict_variables`, `fast_operators`)
+    /// * `language_version` - [language version][crate::LanguageVersion] of compiled scripts (e.g. `"1.17"`)
+    /// * `optimization_level` - `"none"`, `"simple"` or `"full"` (not under `no_optimize`)
+    /// * `limits` - all limits (e.g. `max_operations`, 0 for unlimited) (not under `unchecked`)
+    /// * `disabled_symbols` - array of disabled keywords and operators
+    /// * `disabled_functions` - array of disabled functions
+    /// * `custom_operators` - custom operators and their precedence (not under `no_custom_syntax`)
+    /// * `custom_syntax` - array of leading symbols of custom syntax (not under `no_custom_syntax`)
+    /// * `global_modules` - array of IDs of global modules (`()` for modules with no ID)
+    /// * `static_modules` - array of names of static modules (not under `no_module`)
+    /// * `callbacks` - whether each callback (e.g. `print`, `progress`) is registered
+    /// * `default_tag` - default value of the custom state
+    ///
+    /// Callbacks are not part of the snapshot, only whether they are registered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// engine.set_strict_variables(true);
+    ///
+    /// let settings = engine.settings();
+    ///
+    /// let options = settings["options"].read_lock::<rhai::Map>().unwrap();
+    ///
+    /// assert!(options["strict_variables"].as_bool().unwrap());
+    /// ```
+    #[must_use]
+    pub fn settings(&self) -> Map {
+        let mut settings = Map::new();
+
+        settings.insert("version".into(), SETTINGS_VERSION.into());
+
+        let options = OPTIONS
+            .iter()
+            .map(|&(name, flag)| (name.into(), self.options.contains(flag).into()))
+            .collect::<Map>();
+        settings.insert("options".into(), options.into());
+
+        settings.insert(
+            "language_version".into(),
+            self.language_version.to_string().into(),
+        );
+
+        #[cfg(not(feature = "no_optimize"))]
+        {
+            let level = match self.optimization_level {
+                crate::OptimizationLevel::None => "none",
+                crate::OptimizationLevel::Simple => "simple",
+                crate::OptimizationLevel::Full => "full",
+            };
+            settings.insert("optimization_level".into(), level.into());
+        }
+
+        #[cfg(not(feature = "unchecked"))]
+        {
+            let mut limits = Map::new();
+            let mut add = |name: &str, value: u64| {
+                limits.insert(name.into(), INT::try_from(value).unwrap_or(INT::MAX).into());
+            };
+            #[cfg(not(feature = "no_function"))]
+            add("max_call_levels", self.max_call_levels() as u64);
+            #[cfg(not(feature = "no_function"))]
+            add(
+                "max_closure_call_levels",
+                self.max_closure_call_levels() as u64,
+            );
+            add("max_operations", self.max_operations());
+            add("max_variables", self.max_variables() as u64);
+            #[cfg(not(feature = "no_module"))]
+            add("max_modules", self.max_modules() as u64);
+            add("max_expr_depth", self.max_expr_depth() as u64);
+            #[cfg(not(feature = "no_function"))]
+            add(
+                "max_function_expr_depth",
+                self.max_function_expr_depth() as u64,
+            );
+            add("max_string_size", self.max_string_size() as u64);
+            add("max_array_size", self.max_array_size() as u64);
+            add("max_map_size", self.max_map_size() as u64);
+            add("max_identifier_len", self.max_identifier_len() as u64);
+            add(
+                "max_string_literal_len",
+                self.max_string_literal_len() as u64,
+            );
+            add(
+                "max_interpolation_depth",
+                self.max_interpolation_depth() as u64,
+            );
+            add("max_tokens", self.max_tokens() as u64);
+            add("max_line_len", self.max_line_len() as u64);
+            add("max_script_file_size", self.max_script_file_size() as u64);
+            settings.insert("limits".into(), limits.into());
+        }
+
+        let disabled_symbols = self
+            .disabled_symbols
+            .iter()
+            .map(|s| s.as_str().into())
+            .collect::<Array>();
+        settings.insert("disabled_symbols".into(), disabled_symbols.into());
+
+        let disabled_functions = self
+            .disabled_functions
+            .iter()
+            .map(|s| s.as_str().into())
+            .collect::<Array>();
+        settings.insert("disabled_functions".into(), disabled_functions.into());
+
+        #[cfg(not(feature = "no_custom_syntax"))]
+        {
+            let custom_operators = self
+                .custom_keywords
+                .iter()
+                .filter_map(|(k, p)| p.map(|p| (k.clone(), (p.get() as INT).into())))
+                .collect::<Map>();
+            settings.insert("custom_operators".into(), custom_operators.into());
+
+            let custom_syntax = self
+                .custom_syntax
+                .keys()
+                .map(|k| k.as_str().into())
+                .collect::<Array>();
+            settings.insert("custom_syntax".into(), custom_syntax.into());
+        }
+
+        let global_modules = self
+            .global_modules
+            .iter()
+            .map(|m| m.id().map_or(Dynamic::UNIT, Into::into))
+            .collect::<Array>();
+        settings.insert("global_modules".into(), global_modules.into());
+
+        #[cfg(not(feature = "no_module"))]
+        {
+            let static_modules = self
+                .global_sub_modules
+                .keys()
+                .chain(self.lazy_sub_modules.keys())
+                .map(|k| k.as_str().into())
+                .collect::<Array>();
+            settings.insert("static_modules".into(), static_modules.into());
+        }
+
+        let mut callbacks = Map::new();
+        callbacks.insert("print".into(), self.print.is_some().into());
+        callbacks.insert("debug".into(), self.debug.is_some().into());
+        #[cfg(not(feature = "unchecked"))]
+        callbacks.insert("progress".into(), self.progress.is_some().into());
+        callbacks.insert(
+            "def_var_filter".into(),
+            self.def_var_filter.is_some().into(),
+        );
+        callbacks.insert("resolve_var".into(), self.resolve_var.is_some().into());
+        callbacks.insert(
+            "resolve_var_chain".into(),
+            self.resolve_var_chain.is_some().into(),
+        );
+        callbacks.insert(
+            "var_write_filter".into(),
+            self.var_write_filter.is_some().into(),
+        );
+        callbacks.insert("token_mapper".into(), self.token_mapper.is_some().into());
+        #[cfg(not(feature = "no_module"))]
+        callbacks.insert(
+            "module_resolver".into(),
+            self.module_resolver.is_some().into(),
+        );
+        #[cfg(feature = "debugging")]
+        callbacks.insert("debugger".into(), self.debugger_interface.is_some().into());
+        settings.insert("callbacks".into(), callbacks.into());
+
+        settings.insert("default_tag".into(), self.def_tag.clone());
+
+        settings
+    }
+
+    /// Apply settings from a snapshot taken via [`Engine::settings`].
+    ///
+    /// Not available under `no_object` or `no_index`.
+    ///
+    /// Only language options, the language version, the optimization level, limits and disabled
+    /// symbols are applied.
+    /// Other entries in the snapshot (e.g. `callbacks`) are ignored.
+    ///
+    /// Any subset of the snapshot can be applied.  All entries are checked before any of them is
+    /// applied, so the [`Engine`] is not changed if an error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SettingsError`] naming the offending key if an entry is not recognized, has an
+    /// invalid value, or if `version` is not [`SETTINGS_VERSION`][crate::SETTINGS_VERSION].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_fast_operators(false).disable_symbol("while");
+    ///
+    /// let mut engine2 = Engine::new();
+    /// engine2.apply_settings(&engine.settings()).unwrap();
+    ///
+    /// assert!(!engine2.fast_operators());
+    /// assert!(engine2.is_symbol_disabled("while"));
+    /// ```
+    pub fn apply_settings(&mut self, settings: &Map) -> Result<&mut Self, SettingsError> {
+        let mut options = self.options;
+        let mut language_version = self.language_version;
+        #[cfg(not(feature = "no_optimize"))]
+        let mut optimization_level = self.optimization_level;
+        #[cfg(not(feature = "unchecked"))]
+        let mut limits = self.limits.clone();
+        let mut disabled_symbols = None;
+        let mut disabled_functions = None;
+
+        for (key, value) in settings {
+            match key.as_str() {
+                "version" => match value.as_int() {
+                    Ok(SETTINGS_VERSION) => (),
+                    _ => {
+                        return Err(SettingsError::InvalidValue(
+                            key.to_string(),
+                            format!("version {SETTINGS_VERSION}"),
+                        ))
+                    }
+                },
+                "options" => {
+                    for (name, value) in &*as_map(key, value)? {
+                        let path = format!("{key}.{name}");
+                        let flag = OPTIONS
+                            .iter()
+                            .find(|&&(n, ..)| n == name.as_str())
+                            .map(|&(.., flag)| flag)
+                            .ok_or_else(|| SettingsError::UnknownKey(path.clone()))?;
+                        let enable = value
+                            .as_bool()
+                            .map_err(|_| SettingsError::InvalidValue(path, "a boolean".into()))?;
+                        options.set(flag, enable);
+                    }
+                }
+                "language_version" => {
+                    language_version = value
+                        .read_lock::<crate::ImmutableString>()
+                        .and_then(|s| s.parse().ok())
+                        .ok_or_else(|| {
+                            SettingsError::InvalidValue(
+                                key.to_string(),
+                                "a version string (e.g. \"1.8\")".into(),
+                            )
+                        })?;
+                }
+                #[cfg(not(feature = "no_optimize"))]
+                "optimization_level" => {
+                    optimization_level = match value.read_lock::<crate::ImmutableString>() {
+                        Some(s) if s.as_str() == "none" => crate::OptimizationLevel::None,
+                        Some(s) if s.as_str() == "simple" => crate::OptimizationLevel::Simple,
+                        Some(s) if s.as_str() == "full" => crate::OptimizationLevel::Full,
+                        _ => {
+                            return Err(SettingsError::InvalidValue(
+                                key.to_string(),
+                                "\"none\", \"simple\" or \"full\"".into(),
+                            ))
+                        }
+                    }
+                }
+                #[cfg(not(feature = "unchecked"))]
+                "limits" => {
+                    use std::num::{NonZeroU64, NonZeroUsize};
+
+                    for (name, value) in &*as_map(key, value)? {
+                        let path = format!("{key}.{name}");
+                        let n = as_usize(&path, value)?;
+
+                        match name.as_str() {
+                            #[cfg(not(feature = "no_function"))]
+                            "max_call_levels" => limits.max_call_stack_depth = n,
+                            #[cfg(not(feature = "no_function"))]
+                            "max_closure_call_levels" => limits.max_closure_call_stack_depth = n,
+                            "max_operations" => limits.max_operations = NonZeroU64::new(n as u64),
+                            "max_variables" => limits.max_variables = n,
+                            #[cfg(not(feature = "no_module"))]
+                            "max_modules" => limits.max_modules = n,
+                            "max_expr_depth" => limits.max_expr_depth = NonZeroUsize::new(n),
+                            #[cfg(not(feature = "no_function"))]
+                            "max_function_expr_depth" => {
+                                limits.max_function_expr_depth = NonZeroUsize::new(n)
+                            }
+                            "max_string_size" => limits.max_string_len = NonZeroUsize::new(n),
+                            "max_array_size" => limits.max_array_size = NonZeroUsize::new(n),
+                            "max_map_size" => limits.max_map_size = NonZeroUsize::new(n),
+                            "max_identifier_len" => {
+                                limits.max_identifier_len = NonZeroUsize::new(n)
+                            }
+                            "max_string_literal_len" => {
+                                limits.max_string_literal_len = NonZeroUsize::new(n)
+                            }
+                            "max_interpolation_depth" => {
+                                limits.max_interpolation_depth = NonZeroUsize::new(n)
+                            }
+                            "max_tokens" => limits.max_tokens = NonZeroUsize::new(n),
+                            "max_line_len" => limits.max_line_len = NonZeroUsize::new(n),
+                            "max_script_file_size" => {
+                                limits.max_script_file_size = NonZeroUsize::new(n)
+                            }
+                            _ => return Err(SettingsError::UnknownKey(path)),
+                        }
+                    }
+                }
+                "disabled_symbols" => disabled_symbols = Some(to_identifiers(key, value)?),
+                "disabled_functions" => disabled_functions = Some(to_identifiers(key, value)?),
+                k if READ_ONLY_KEYS.contains(&k) => (),
+                _ => return Err(SettingsError::UnknownKey(key.to_string())),
+            }
+        }
+
+        self.options = options;
+        self.language_version = language_version;
+        #[cfg(not(feature = "no_optimize"))]
+        {
+            self.optimization_level = optimization_level;
+        }
+        #[cfg(not(feature = "unchecked"))]
+        {
+            self.limits = limits;
+        }
+        if let Some(symbols) = disabled_symbols {
+            self.disabled_symbols = symbols;
+        }
+        if let Some(functions) = disabled_functions {
+            self.disabled_functions = functions;
+        }
+
+        Ok(self)
+    }
+}
@@ -0,0 +1,155 @@
+//! Module that defines the public API to tokenize scripts.
+
+use crate::tokenizer::{lex_raw, Token};
+use crate::{Engine, LexError, Position, SmartString, Span};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+impl Engine {
+    /// Tokenize a script into a stream of [tokens][Token], each with its starting
+    /// [position][Position] and [span][Span] (i.e. the positions of its first and last characters).
+    ///
+    /// The tokens are the same as those seen by the parser: custom keywords/operators surface as
+    /// `Token::Custom`, disabled symbols as [`Token::Reserved`], and the token mapper registered
+    /// via `Engine::on_parse_token` (under `internals`), if any, is applied.
+    ///
+    /// Comments are skipped (see [`tokenize_with_comments`][Engine::tokenize_with_comments]).
+    ///
+    /// Lexical errors are returned as [`Token::LexError`] and tokenizing continues after them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, Position, Token};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let tokens = engine
+    ///     .tokenize("let x = 42;")
+    ///     .map(|(token, pos, _)| (token.to_string(), pos))
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(tokens, [
+    ///     ("let".to_string(), Position::new(1, 1)),
+    ///     ("x".to_string(), Position::new(1, 5)),
+    ///     ("=".to_string(), Position::new(1, 7)),
+    ///     ("42".to_string(), Position::new(1, 9)),
+    ///     (";".to_string(), Position::new(1, 11)),
+    /// ]);
+    /// ```
+    #[inline(always)]
+    pub fn tokenize<'a>(
+        &'a self,
+        script: &'a str,
+    ) -> impl Iterator<Item = (Token, Position, Span)> + 'a {
+        self.tokenize_raw(script, false, false)
+            .map(|(token, pos, span, ..)| (token, pos, span))
+    }
+    /// Tokenize a script into a stream of [tokens][Token], each with its starting
+    /// [position][Position] and [span][Span], including comments as [`Token::Comment`].
+    ///
+    /// See [`tokenize`][Engine::tokenize] for details.
+    #[inline(always)]
+    pub fn tokenize_with_comments<'a>(
+        &'a self,
+        script: &'a str,
+    ) -> impl Iterator<Item = (Token, Position, Span)> + 'a {
+        self.tokenize_raw(script, true, false)
+            .map(|(token, pos, span, ..)| (token, pos, span))
+    }
+    /// Tokenize a script, optionally including comments.
+    ///
+    /// If `with_text` is `true`, the original text of each token in the script is also returned.
+    /// It is empty for symbols, which always have the same text.
+    pub(crate) fn tokenize_raw<'a>(
+        &'a self,
+        script: &'a str,
+        include_comments: bool,
+        with_text: bool,
+    ) -> impl Iterator<Item = (Token, Position, Span, SmartString)> + 'a {
+        let (mut stream, control) =
+            lex_raw(self, std::iter::once(script), self.token_mapper.as_deref());
+        stream.state.include_comments = include_comments;
+        if with_text {
+            stream.state.last_token = Some(SmartString::new_const());
+        }
+
+        // Levels of braces within interpolated strings.
+        // The parser normally switches the tokenizer back to text mode at the closing brace.
+        let mut interpolations = Vec::<usize>::new();
+        let mut within_text = false;
+        let mut done = false;
+
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+
+            let (token, pos) = stream.next()?;
+
+            let span = match token {
+                // The tokenizer consumes the line-break after a comment
+                Token::Comment(ref comment) => Span::new(pos, end_of_text(comment, pos)),
+                // Empty text at the end of an interpolated string has no position
+                _ if pos.is_none() => Span::new(stream.pos, stream.pos),
+                _ => Span::new(pos, stream.pos),
+            };
+            let pos = span.start();
+
+            let text = match stream.state.last_token {
+                // The text of an interpolated string continues after the closing brace
+                Some(ref text) if within_text && !text.is_empty() => text[1..].into(),
+                Some(ref text) => text.clone(),
+                None => SmartString::new_const(),
+            };
+            within_text = false;
+
+            match token {
+                Token::EOF => {
+                    done = true;
+                    return None;
+                }
+                // The tokenizer keeps returning this error, so stop here
+                Token::LexError(ref err) if matches!(**err, LexError::TooManyTokens(..)) => {
+                    done = true;
+                }
+                Token::InterpolatedString(..) => interpolations.push(0),
+                Token::LeftBrace | Token::MapStart => {
+                    if let Some(level) = interpolations.last_mut() {
+                        *level += 1;
+                    }
+                }
+                Token::RightBrace => {
+                    if let Some(level) = interpolations.last_mut() {
+                        *level = level.saturating_sub(1);
+
+                        if *level == 0 {
+                            interpolations.pop();
+                            control.borrow_mut().is_within_text = true;
+                            within_text = true;
+                        }
+                    }
+                }
+                _ => (),
+            }
+
+            Some((token, pos, span, text))
+        })
+    }
+}
+
+/// Get the position of the last character of a piece of text starting at a [position][Position].
+#[must_use]
+fn end_of_text(text: &str, start: Position) -> Position {
+    let mut pos = start;
+
+    for ch in text.chars().skip(1) {
+        if ch == '\n' {
+            pos.new_line();
+        } else {
+            pos.advance();
+        }
+    }
+
+    pos
+}
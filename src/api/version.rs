@@ -0,0 +1,133 @@
+//! Settings for the language version of scripts compiled by the [`Engine`].
+
+use crate::Engine;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{fmt, str::FromStr};
+
+/// Version of the Rhai language accepted by the [`Engine`] when compiling scripts.
+///
+/// Newer syntax features are only available from the language version that introduced them.
+/// Compiling a script that uses a newer feature under an older language version results in a
+/// [`ParseErrorType::UnsupportedLanguageFeature`][crate::ParseErrorType::UnsupportedLanguageFeature]
+/// error.
+///
+/// | Syntax feature                                  | Version |
+/// | ----------------------------------------------- | :-----: |
+/// | Elvis operators `?.` and `?[`                   |   1.8   |
+/// | Null-coalescing operator `??`                   |   1.8   |
+/// | `!in` operator                                  |  1.12   |
+/// | Typed methods (e.g. `fn int.foo() { ... }`)     |  1.14   |
+/// | `is_shared` as a reserved keyword               |  1.14   |
+/// | Guarded `catch` (e.g. `catch (e if e > 0)`)     |  1.17   |
+/// | Default parameter values (e.g. `fn f(x = 1)`)   |  1.17   |
+/// | Rest parameters (e.g. `fn f(x, ...args)`)       |  1.17   |
+/// | Named arguments (e.g. `f(x, y: 42)`)            |  1.17   |
+///
+/// Under language versions before 1.14, `is_shared` is a normal identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LanguageVersion {
+    /// Major version.
+    major: u16,
+    /// Minor version.
+    minor: u16,
+}
+
+impl LanguageVersion {
+    /// The latest language version, which supports all syntax features.
+    pub const LATEST: Self = Self::new(1, 17);
+
+    /// Language version that introduced the Elvis operators and the null-coalescing operator.
+    pub(crate) const V1_8: Self = Self::new(1, 8);
+    /// Language version that introduced the `!in` operator.
+    pub(crate) const V1_12: Self = Self::new(1, 12);
+    /// Language version that introduced typed methods and the `is_shared` keyword.
+    pub(crate) const V1_14: Self = Self::new(1, 14);
+    /// Language version that introduced guarded `catch` clauses.
+    pub(crate) const V1_17: Self = Self::new(1, 17);
+
+    /// Create a new [`LanguageVersion`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+    /// Get the major version.
+    #[inline(always)]
+    #[must_use]
+    pub const fn major(self) -> u16 {
+        self.major
+    }
+    /// Get the minor version.
+    #[inline(always)]
+    #[must_use]
+    pub const fn minor(self) -> u16 {
+        self.minor
+    }
+}
+
+impl Default for LanguageVersion {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl fmt::Display for LanguageVersion {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for LanguageVersion {
+    type Err = ();
+
+    /// Parse a language version in the format `major.minor` (e.g. `1.8`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (major, minor) = s.split_once('.').ok_or(())?;
+        let major = major.parse().map_err(|_| ())?;
+        let minor = minor.parse().map_err(|_| ())?;
+        Ok(Self::new(major, minor))
+    }
+}
+
+impl Engine {
+    /// Get the language version of scripts compiled by the [`Engine`].
+    /// Default is [`LanguageVersion::LATEST`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn language_version(&self) -> LanguageVersion {
+        self.language_version
+    }
+    /// Set the language version of scripts compiled by the [`Engine`].
+    ///
+    /// Syntax features introduced after this version are not available in scripts.
+    /// Features can still be turned off individually via the other language options
+    /// (e.g. [`set_allow_if_expression`][Engine::set_allow_if_expression]) or
+    /// [`disable_symbol`][Engine::disable_symbol].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, LanguageVersion};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// assert!(engine.compile("x ?? 42").is_ok());
+    ///
+    /// engine.set_language_version(LanguageVersion::new(1, 7));
+    ///
+    /// let err = engine.compile("x ?? 42").unwrap_err();
+    ///
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "Null-coalescing operator '??' requires language version 1.8 or above (line 1, position 3)"
+    /// );
+    /// ```
+    #[inline(always)]
+    pub fn set_language_version(&mut self, version: LanguageVersion) -> &mut Self {
+        self.language_version = version;
+        self
+    }
+}
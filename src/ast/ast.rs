@@ -1,13 +1,14 @@
 //! Module defining the AST (abstract syntax tree).
 
-use super::{ASTFlags, Expr, FnAccess, Stmt};
-use crate::{Dynamic, FnNamespace, ImmutableString, Position};
+use super::{ASTFlags, Expr, FnAccess, FreeVariables, Stmt};
+use crate::{Dynamic, FnNamespace, ImmutableString, LanguageVersion, Position};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
     borrow::Borrow,
+    collections::BTreeSet,
     fmt,
-    hash::Hash,
+    hash::{Hash, Hasher},
     ops::{Add, AddAssign},
     ptr,
 };
@@ -23,6 +24,8 @@ pub struct AST {
     source: Option<ImmutableString>,
     /// Global statements.
     body: Box<[Stmt]>,
+    /// Language version the [`AST`] was compiled with.
+    language_version: LanguageVersion,
     /// Script-defined functions.
     #[cfg(not(feature = "no_function"))]
     lib: crate::SharedModule,
@@ -32,6 +35,8 @@ pub struct AST {
     /// [`AST`] documentation.
     #[cfg(feature = "metadata")]
     pub(crate) doc: crate::SmartString,
+    /// Node ID's, keyed by the [hashed kind and position][ASTNode::node_key] of each node.
+    pub(crate) node_ids: crate::Shared<crate::func::StraightHashMap<u32>>,
 }
 
 impl Default for AST {
@@ -83,10 +88,12 @@ impl AST {
                 .into_iter()
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
+            language_version: LanguageVersion::LATEST,
             #[cfg(not(feature = "no_function"))]
             lib: functions.into(),
             #[cfg(not(feature = "no_module"))]
             resolver: None,
+            node_ids: <_>::default(),
         }
     }
     /// _(internals)_ Create a new [`AST`].
@@ -106,10 +113,12 @@ impl AST {
                 .into_iter()
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
+            language_version: LanguageVersion::LATEST,
             #[cfg(not(feature = "no_function"))]
             lib: functions.into(),
             #[cfg(not(feature = "no_module"))]
             resolver: None,
+            node_ids: <_>::default(),
         }
     }
     /// Create a new [`AST`] with a source name.
@@ -156,10 +165,12 @@ impl AST {
             #[cfg(feature = "metadata")]
             doc: crate::SmartString::new_const(),
             body: <_>::default(),
+            language_version: LanguageVersion::LATEST,
             #[cfg(not(feature = "no_function"))]
             lib: crate::Module::new().into(),
             #[cfg(not(feature = "no_module"))]
             resolver: None,
+            node_ids: <_>::default(),
         }
     }
     /// Get the source, if any.
@@ -194,6 +205,20 @@ impl AST {
         self.source = None;
         self
     }
+    /// Get the [language version][LanguageVersion] the [`AST`] was compiled with.
+    ///
+    /// An [`AST`] merged from multiple [`AST`]'s has the latest language version among them.
+    #[inline(always)]
+    #[must_use]
+    pub const fn language_version(&self) -> LanguageVersion {
+        self.language_version
+    }
+    /// Set the [language version][LanguageVersion] the [`AST`] was compiled with.
+    #[inline(always)]
+    pub(crate) fn set_language_version(&mut self, version: LanguageVersion) -> &mut Self {
+        self.language_version = version;
+        self
+    }
     /// Get the documentation (if any).
     /// Exported under the `metadata` feature only.
     ///
@@ -256,6 +281,15 @@ impl AST {
     pub const fn shared_lib(&self) -> &crate::SharedModule {
         &self.lib
     }
+    /// Get a mutable reference to the internal shared [`Module`][crate::Module] containing all
+    /// script-defined functions.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    #[allow(dead_code)]
+    pub(crate) fn shared_lib_mut(&mut self) -> &mut crate::SharedModule {
+        &mut self.lib
+    }
     /// _(internals)_ Get the embedded [module resolver][crate::ModuleResolver].
     /// Exported under the `internals` feature only.
     ///
@@ -301,9 +335,11 @@ impl AST {
             #[cfg(feature = "metadata")]
             doc: self.doc.clone(),
             body: <_>::default(),
+            language_version: self.language_version,
             lib: lib.into(),
             #[cfg(not(feature = "no_module"))]
             resolver: self.resolver.clone(),
+            node_ids: self.node_ids.clone(),
         }
     }
     /// Clone the [`AST`]'s script statements into a new [`AST`].
@@ -316,12 +352,94 @@ impl AST {
             #[cfg(feature = "metadata")]
             doc: self.doc.clone(),
             body: self.body.clone(),
+            language_version: self.language_version,
             #[cfg(not(feature = "no_function"))]
             lib: crate::Module::new().into(),
             #[cfg(not(feature = "no_module"))]
             resolver: self.resolver.clone(),
+            node_ids: self.node_ids.clone(),
         }
     }
+    /// Create a new [`AST`] containing only the functions of this [`AST`].
+    /// No statements are kept.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// Unlike [`clone_functions_only`][AST::clone_functions_only], the [`Module`][crate::Module]
+    /// holding the functions is shared, not copied.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub fn functions_only(&self) -> Self {
+        Self {
+            source: self.source.clone(),
+            #[cfg(feature = "metadata")]
+            doc: self.doc.clone(),
+            body: <_>::default(),
+            language_version: self.language_version,
+            lib: self.lib.clone(),
+            #[cfg(not(feature = "no_module"))]
+            resolver: self.resolver.clone(),
+            node_ids: self.node_ids.clone(),
+        }
+    }
+    /// Split the [`AST`] into a new [`AST`] containing only the statements, and the shared
+    /// [`Module`][crate::Module] containing all the script-defined functions.
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// The functions can be called via [`Engine::call_fn_in_module`][crate::Engine::call_fn_in_module]
+    /// without re-running the statements.
+    ///
+    /// The embedded [module resolver][crate::ModuleResolver] (if any) stays with the statements.
+    /// If functions `import` modules from it, use [`functions_only`][AST::functions_only] instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, Scope};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("
+    ///     fn add(x) { x + offset }
+    ///     let offset = 40;
+    /// ")?;
+    ///
+    /// let (statements, lib) = ast.split();
+    ///
+    /// let mut scope = Scope::new();
+    ///
+    /// // Run the statements once
+    /// engine.run_ast_with_scope(&mut scope, &statements)?;
+    ///
+    /// // Call the functions many times
+    /// for _ in 0..10 {
+    ///     let result = engine.call_fn_in_module::<i64>(&mut scope, &lib, "add", (2_i64,))?;
+    ///     assert_eq!(result, 42);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub fn split(self) -> (Self, crate::SharedModule) {
+        let statements = Self {
+            source: self.source,
+            #[cfg(feature = "metadata")]
+            doc: self.doc,
+            body: self.body,
+            language_version: self.language_version,
+            lib: crate::Module::new().into(),
+            #[cfg(not(feature = "no_module"))]
+            resolver: self.resolver,
+            node_ids: self.node_ids,
+        };
+
+        (statements, self.lib)
+    }
     /// Merge two [`AST`] into one.  Both [`AST`]'s are untouched and a new, merged,
     /// version is returned.
     ///
@@ -547,6 +665,8 @@ impl AST {
             }
         }
 
+        _ast.language_version = self.language_version.max(other.language_version);
+
         #[cfg(feature = "metadata")]
         match (other.doc.as_str(), _ast.doc.as_str()) {
             ("", _) => (),
@@ -656,6 +776,11 @@ impl AST {
             crate::func::shared_make_mut(&mut self.lib).merge_filtered(&other.lib, &_filter);
         }
 
+        self.language_version = self.language_version.max(other.language_version);
+
+        // Positions in the two scripts may clash, so node ID's are no longer meaningful
+        self.node_ids = <_>::default();
+
         #[cfg(feature = "metadata")]
         match (other.doc.as_str(), self.doc.as_str()) {
             ("", _) => (),
@@ -827,17 +952,103 @@ impl AST {
             _ => None,
         })
     }
+    /// Iterate through all constants defined at the top level of the [`AST`], which become global
+    /// constants accessible via the `global` namespace (e.g. `global::ANSWER`).
+    ///
+    /// The value is [`None`] if the constant is not defined with a literal value (or one that
+    /// optimizes into a literal), in which case it is only known after evaluation.
+    ///
+    /// Not available under `no_module` or `no_function`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile(
+    /// "
+    ///     const ANSWER = 42;
+    ///     let x = 1;
+    ///     const NAME = `answer is ${x}`;
+    ///
+    ///     fn foo() { global::ANSWER }
+    /// ")?;
+    ///
+    /// let mut iter = ast.iter_global_constants()
+    ///                   .map(|(name, value)| (name, value.map(|v| v.as_int().unwrap())));
+    ///
+    /// assert_eq!(iter.next(), Some(("ANSWER", Some(42))));
+    /// assert_eq!(iter.next(), Some(("NAME", None)));
+    /// assert_eq!(iter.next(), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(not(feature = "no_function"))]
+    pub fn iter_global_constants(&self) -> impl Iterator<Item = (&str, Option<Dynamic>)> {
+        self.statements().iter().filter_map(|stmt| match stmt {
+            Stmt::Var(x, options, ..) if options.contains(ASTFlags::CONSTANT) => {
+                let (name, expr, ..) = &**x;
+                Some((name.as_str(), expr.get_literal_value()))
+            }
+            _ => None,
+        })
+    }
+    /// Collect the names of all free variables in the [`AST`], i.e. variables that are used
+    /// without being declared in the script (e.g. constants in an external [`Scope`][crate::Scope]).
+    ///
+    /// Variables used in function bodies are included, except for parameters and local variables.
+    ///
+    /// Variables that are accessed dynamically (e.g. via `eval` or custom syntax) are not detected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile(
+    /// "
+    ///     let x = 40;
+    ///     x + y + z
+    /// ")?;
+    ///
+    /// let vars = ast.collect_free_variables();
+    ///
+    /// assert_eq!(vars.iter().map(|v| v.as_str()).collect::<Vec<_>>(), ["y", "z"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn collect_free_variables(&self) -> BTreeSet<ImmutableString> {
+        let mut vars = FreeVariables::collect(self.statements(), None).vars;
+
+        #[cfg(not(feature = "no_function"))]
+        for fn_def in self.iter_fn_def() {
+            vars.extend(FreeVariables::collect(fn_def.body.statements(), &fn_def.params).vars);
+        }
+
+        vars
+    }
     /// _(internals)_ Recursively walk the [`AST`], including function bodies (if any).
     /// Return `false` from the callback to terminate the walk.
     /// Exported under the `internals` feature only.
     #[cfg(feature = "internals")]
     #[inline(always)]
-    pub fn walk(&self, on_node: &mut (impl FnMut(&[ASTNode]) -> bool + ?Sized)) -> bool {
+    pub fn walk<'a>(&'a self, on_node: &mut (impl FnMut(&[ASTNode<'a>]) -> bool + ?Sized)) -> bool {
         self._walk(on_node)
     }
     /// Recursively walk the [`AST`], including function bodies (if any).
     /// Return `false` from the callback to terminate the walk.
-    pub(crate) fn _walk(&self, on_node: &mut (impl FnMut(&[ASTNode]) -> bool + ?Sized)) -> bool {
+    pub(crate) fn _walk<'a>(
+        &'a self,
+        on_node: &mut (impl FnMut(&[ASTNode<'a>]) -> bool + ?Sized),
+    ) -> bool {
         let path = &mut Vec::new();
 
         for stmt in self.statements() {
@@ -854,6 +1065,82 @@ impl AST {
 
         true
     }
+    /// Iterate through the [positions][Position] of all statements in the [`AST`], including those
+    /// in function bodies (if any), in the order they are walked.
+    ///
+    /// Statements without a position are skipped.
+    ///
+    /// This is useful for finding out which statements are never evaluated, e.g. via
+    /// [`CoverageCollector::uncovered_lines`][crate::coverage::CoverageCollector::uncovered_lines].
+    pub fn iter_statement_positions(&self) -> impl Iterator<Item = Position> {
+        let mut positions = Vec::new();
+
+        self._walk(&mut |path| {
+            if let Some(ASTNode::Stmt(stmt)) = path.last() {
+                let pos = stmt.position();
+
+                if !pos.is_none() {
+                    positions.push(pos);
+                }
+            }
+            true
+        });
+
+        positions.into_iter()
+    }
+    /// Assign node ID's to statements and their sub-nodes, in the order they are walked,
+    /// starting from 1.
+    ///
+    /// A node of the same type and at the same [position][Position] as a node walked before it
+    /// shares the same ID. Nodes without a position are not assigned any ID.
+    #[must_use]
+    pub(crate) fn collect_node_ids<'a>(
+        statements: impl IntoIterator<Item = &'a Stmt>,
+    ) -> crate::func::StraightHashMap<u32> {
+        let mut node_ids = crate::func::StraightHashMap::default();
+        let path = &mut Vec::new();
+
+        for stmt in statements {
+            stmt.walk(path, &mut |path: &[ASTNode]| {
+                let node = path.last().unwrap();
+
+                if !node.position().is_none() {
+                    let id = node_ids.len() as u32 + 1;
+                    node_ids.entry(node.node_key()).or_insert(id);
+                }
+                true
+            });
+        }
+
+        node_ids
+    }
+    /// _(internals)_ Find the node with a particular ID.
+    /// Exported under the `internals` feature only.
+    ///
+    /// Node ID's are only assigned when the [`AST`] is compiled with
+    /// [`Engine::set_assign_node_ids`][crate::Engine::set_assign_node_ids] turned on.
+    /// They are kept when the [`AST`] is re-optimized, but not when [`AST`]'s are merged or combined.
+    ///
+    /// Returns [`None`] if no node has that ID (e.g. it has been optimized away).
+    #[cfg(feature = "internals")]
+    #[must_use]
+    pub fn node_by_id(&self, id: u32) -> Option<ASTNode<'_>> {
+        let (&key, ..) = self.node_ids.iter().find(|(.., &v)| v == id)?;
+        let mut result = None;
+
+        self._walk(&mut |path| {
+            let node = path.last().unwrap();
+
+            if node.node_key() == key {
+                result = Some(*node);
+                false
+            } else {
+                true
+            }
+        });
+
+        result
+    }
 }
 
 impl<A: AsRef<AST>> Add<A> for &AST {
@@ -984,4 +1271,37 @@ impl ASTNode<'_> {
             Self::Expr(expr) => expr.position(),
         }
     }
+    /// Get the ID of this [`ASTNode`] in an [`AST`].
+    ///
+    /// Node ID's are only assigned when the [`AST`] is compiled with
+    /// [`Engine::set_assign_node_ids`][crate::Engine::set_assign_node_ids] turned on.
+    ///
+    /// Returns zero if the [`AST`] has no ID for this node.
+    #[inline]
+    #[must_use]
+    pub fn id(&self, ast: &AST) -> u32 {
+        ast.node_ids.get(&self.node_key()).copied().unwrap_or(0)
+    }
+    /// Calculate the key of this [`ASTNode`] in the side-table of node ID's, which is a hash of
+    /// its type and [position][Position].
+    #[must_use]
+    pub(crate) fn node_key(&self) -> u64 {
+        let s = &mut crate::func::hashing::get_hasher();
+
+        match self {
+            // A function call statement is a compact form of an expression statement
+            Self::Stmt(Stmt::Expr(..) | Stmt::FnCall(..)) => 0_u8.hash(s),
+            Self::Stmt(stmt) => {
+                1_u8.hash(s);
+                std::mem::discriminant(*stmt).hash(s);
+            }
+            Self::Expr(expr) => {
+                2_u8.hash(s);
+                std::mem::discriminant(*expr).hash(s);
+            }
+        }
+        self.position().hash(s);
+
+        s.finish()
+    }
 }
@@ -0,0 +1,1348 @@
+//! Module for saving an [`AST`] into, and loading it from, a compact binary format.
+
+use super::{
+    ASTFlags, BinaryExpr, CaseBlocksList, ConditionalExpr, Expr, FlowControl, FnCallExpr,
+    FnCallHashes, Ident, Namespace, OpAssignment, RangeCase, Stmt, StmtBlock,
+    SwitchCasesCollection, TryCatchBlock, AST,
+};
+use crate::func::hashing::get_hasher;
+use crate::func::StraightHashMap;
+use crate::tokenizer::Token;
+use crate::types::dynamic::{AccessMode, Tag, Union};
+use crate::{
+    calc_fn_hash, Dynamic, ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, LanguageVersion,
+    Position, RhaiError, RhaiResultOf, Span, ERR, INT,
+};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    num::{NonZeroU8, NonZeroUsize},
+};
+
+/// Magic bytes at the start of a saved [`AST`].
+const MAGIC: &[u8; 8] = b"\x7fRHAIAST";
+
+/// Version of the binary format.
+///
+/// Bump this whenever the layout of any saved node changes.
+const FORMAT_VERSION: u32 = 3;
+
+/// Features that change the shape of an [`AST`], in the order of their bits in the saved mask.
+const FEATURES: &[(&str, bool)] = &[
+    ("only_i32", cfg!(feature = "only_i32")),
+    ("no_float", cfg!(feature = "no_float")),
+    ("f32_float", cfg!(feature = "f32_float")),
+    ("decimal", cfg!(feature = "decimal")),
+    ("bigint", cfg!(feature = "bigint")),
+    ("no_index", cfg!(feature = "no_index")),
+    ("no_object", cfg!(feature = "no_object")),
+    ("no_function", cfg!(feature = "no_function")),
+    ("no_closure", cfg!(feature = "no_closure")),
+    ("no_module", cfg!(feature = "no_module")),
+    ("no_custom_syntax", cfg!(feature = "no_custom_syntax")),
+    ("no_position", cfg!(feature = "no_position")),
+    ("metadata", cfg!(feature = "metadata")),
+];
+
+/// Get the bit-mask of the [`FEATURES`] that are turned on.
+#[must_use]
+fn features_mask() -> u32 {
+    FEATURES
+        .iter()
+        .enumerate()
+        .filter(|(.., (.., enabled))| *enabled)
+        .fold(0, |mask, (bit, ..)| mask | (1 << bit))
+}
+
+/// Create an error for saving an [`AST`].
+#[cold]
+#[inline(never)]
+fn save_error(message: impl Into<String>) -> RhaiError {
+    ERR::ErrorSystem("Cannot save AST to bytes".into(), message.into().into()).into()
+}
+
+/// Create an error for loading an [`AST`].
+#[cold]
+#[inline(never)]
+fn load_error(message: impl Into<String>) -> RhaiError {
+    ERR::ErrorSystem("Cannot load AST from bytes".into(), message.into().into()).into()
+}
+
+/// Calculate the hash of a `switch` case value.
+#[must_use]
+fn hash_case_value(value: &Dynamic) -> u64 {
+    let hasher = &mut get_hasher();
+    value.hash(hasher);
+    hasher.finish()
+}
+
+/// Calculate the hash of a variable access.
+#[allow(unused_variables)]
+#[must_use]
+fn calc_variable_hash(namespace: &Namespace, name: &str) -> u64 {
+    #[cfg(not(feature = "no_module"))]
+    if !namespace.is_empty() {
+        return crate::calc_var_hash(namespace.path.iter().map(Ident::as_str), name);
+    }
+    0
+}
+
+/// Calculate the hash of a function call, optionally qualified by its namespace.
+#[allow(unused_variables)]
+#[must_use]
+fn calc_call_hash(namespace: &Namespace, qualified: bool, name: &str, num: usize) -> u64 {
+    #[cfg(not(feature = "no_module"))]
+    if qualified {
+        return calc_fn_hash(namespace.path.iter().map(Ident::as_str), name, num);
+    }
+    calc_fn_hash(None, name, num)
+}
+
+impl AST {
+    /// Save this [`AST`] into a compact binary format, e.g. for caching compiled scripts across
+    /// processes.  Load it back via [`AST::from_bytes`].
+    ///
+    /// The format is versioned, and the bytes can only be loaded by the same version of Rhai
+    /// built with the same language features (e.g. `no_float`, `only_i32`).
+    ///
+    /// Pre-calculated hashes are not saved but recalculated when loading, so the bytes do not
+    /// depend on the hashing seed of the current process.
+    ///
+    /// Script-defined functions are saved, as well as the documentation and function
+    /// doc-comments under the `metadata` feature.  The embedded
+    /// [module resolver][crate::ModuleResolver] (if any) is _not_ saved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if this [`AST`] contains constant values that have no binary
+    /// representation (e.g. timestamps, shared values or custom types produced by constants
+    /// propagation during optimization).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, AST};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("fn add(x, y) { x + y } add(40, 2)")?;
+    ///
+    /// let bytes = ast.to_bytes()?;
+    ///
+    /// // ... store the bytes and load them back later, perhaps in another process ...
+    ///
+    /// let ast = AST::from_bytes(&bytes)?;
+    ///
+    /// assert_eq!(engine.eval_ast::<i64>(&ast)?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bytes(&self) -> RhaiResultOf<Vec<u8>> {
+        let mut w = Writer::default();
+
+        w.buf.extend_from_slice(MAGIC);
+        w.buf.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        w.buf.extend_from_slice(&features_mask().to_le_bytes());
+
+        w.write_option(self.source(), |w, s| {
+            w.write_str(s);
+            Ok(())
+        })?;
+        #[cfg(feature = "metadata")]
+        w.write_str(self.doc());
+        w.write_usize(usize::from(self.language_version().major()));
+        w.write_usize(usize::from(self.language_version().minor()));
+
+        #[cfg(not(feature = "no_function"))]
+        {
+            let mut functions: Vec<_> = self
+                .shared_lib()
+                .iter_script_fn()
+                .map(|(.., fn_def)| fn_def)
+                .collect();
+
+            functions.sort_by(|a, b| {
+                a.name
+                    .cmp(&b.name)
+                    .then(a.params.len().cmp(&b.params.len()))
+            });
+
+            w.write_slice(&functions, |w, fn_def| w.write_fn_def(fn_def))?;
+        }
+
+        w.write_slice(self.statements(), Writer::write_stmt)?;
+
+        Ok(w.buf)
+    }
+    /// Load an [`AST`] saved via [`AST::to_bytes`].
+    ///
+    /// All pre-calculated hashes are recalculated for the current process.
+    ///
+    /// The loaded [`AST`] has no embedded [module resolver][crate::ModuleResolver].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a saved [`AST`], were saved by a different version
+    /// of the binary format, or were saved with a different set of language features
+    /// (e.g. `no_float`, `only_i32`).
+    pub fn from_bytes(bytes: &[u8]) -> RhaiResultOf<Self> {
+        let mut r = Reader { bytes };
+
+        if r.read_bytes(MAGIC.len()).ok() != Some(&MAGIC[..]) {
+            return Err(load_error("not a saved AST"));
+        }
+
+        let version = r.read_u32()?;
+
+        if version != FORMAT_VERSION {
+            return Err(load_error(format!(
+                "format version {version} is not supported (expecting version {FORMAT_VERSION})"
+            )));
+        }
+
+        let mask = r.read_u32()?;
+        let current = features_mask();
+
+        if mask != current {
+            let mut mismatches: Vec<_> = FEATURES
+                .iter()
+                .enumerate()
+                .filter(|&(bit, ..)| (mask ^ current) & (1 << bit) != 0)
+                .map(|(.., (name, enabled))| {
+                    if *enabled {
+                        format!("`{name}` is enabled but was not when saved")
+                    } else {
+                        format!("`{name}` is not enabled but was when saved")
+                    }
+                })
+                .collect();
+
+            if mask >> FEATURES.len() != 0 {
+                mismatches.push("unknown features were enabled when saved".into());
+            }
+
+            return Err(load_error(format!(
+                "mismatched features: {}",
+                mismatches.join(", ")
+            )));
+        }
+
+        let source = r.read_option(Reader::read_str)?;
+        #[cfg(feature = "metadata")]
+        let doc = r.read_str()?;
+        let major = r.read_u16()?;
+        let minor = r.read_u16()?;
+
+        #[cfg(not(feature = "no_function"))]
+        let lib = {
+            let functions: Vec<_> = r.read_seq(Reader::read_fn_def)?;
+            let mut lib = crate::Module::new();
+            functions.into_iter().for_each(|fn_def| {
+                lib.set_script_fn(fn_def);
+            });
+            lib
+        };
+
+        let statements: Vec<_> = r.read_seq(Reader::read_stmt)?;
+
+        if !r.bytes.is_empty() {
+            return Err(load_error("unexpected trailing data"));
+        }
+
+        let mut ast = Self::new(
+            statements,
+            #[cfg(not(feature = "no_function"))]
+            lib,
+        );
+        ast.set_language_version(LanguageVersion::new(major, minor));
+        if let Some(source) = source {
+            ast.set_source(source);
+        }
+        #[cfg(feature = "metadata")]
+        {
+            ast.doc = doc.as_str().into();
+        }
+
+        Ok(ast)
+    }
+}
+
+/// Encoder of [`AST`] nodes.
+#[derive(Debug, Default)]
+struct Writer {
+    /// Output buffer.
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn write_u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+    fn write_bool(&mut self, value: bool) {
+        self.write_u8(u8::from(value));
+    }
+    /// Write an unsigned integer in LEB128 encoding.
+    fn write_u64(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.write_u8((value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+        self.write_u8(value as u8);
+    }
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+    /// Write a signed integer in zig-zag encoding.
+    fn write_i64(&mut self, value: i64) {
+        self.write_u64(((value << 1) ^ (value >> 63)) as u64);
+    }
+    #[allow(clippy::useless_conversion)]
+    fn write_int(&mut self, value: INT) {
+        self.write_i64(i64::from(value));
+    }
+    #[cfg(not(feature = "no_float"))]
+    #[allow(clippy::useless_conversion)]
+    fn write_float(&mut self, value: crate::FLOAT) {
+        self.write_u64(u64::from(value.to_bits()));
+    }
+    fn write_str(&mut self, value: &str) {
+        self.write_usize(value.len());
+        self.buf.extend_from_slice(value.as_bytes());
+    }
+    fn write_index(&mut self, index: Option<NonZeroUsize>) {
+        self.write_usize(index.map_or(0, NonZeroUsize::get));
+    }
+    fn write_pos(&mut self, pos: Position) {
+        self.write_usize(pos.line().unwrap_or(0));
+        self.write_usize(pos.position().unwrap_or(0));
+    }
+    fn write_span(&mut self, span: Span) {
+        self.write_pos(span.start());
+        self.write_pos(span.end());
+    }
+    fn write_flags(&mut self, flags: ASTFlags) {
+        self.write_u8(flags.bits());
+    }
+    fn write_ident(&mut self, ident: &Ident) -> RhaiResultOf<()> {
+        self.write_str(&ident.name);
+        self.write_pos(ident.pos);
+        Ok(())
+    }
+    fn write_option<T>(
+        &mut self,
+        value: Option<T>,
+        f: impl FnOnce(&mut Self, T) -> RhaiResultOf<()>,
+    ) -> RhaiResultOf<()> {
+        self.write_bool(value.is_some());
+        value.map_or(Ok(()), |v| f(self, v))
+    }
+    fn write_slice<T>(
+        &mut self,
+        items: &[T],
+        mut f: impl FnMut(&mut Self, &T) -> RhaiResultOf<()>,
+    ) -> RhaiResultOf<()> {
+        self.write_usize(items.len());
+        items.iter().try_for_each(|item| f(self, item))
+    }
+    #[allow(unused_variables)]
+    fn write_namespace(&mut self, namespace: &Namespace) -> RhaiResultOf<()> {
+        #[cfg(not(feature = "no_module"))]
+        {
+            self.write_slice(&namespace.path, Self::write_ident)?;
+            self.write_index(namespace.index);
+        }
+        Ok(())
+    }
+    fn write_dynamic(&mut self, value: &Dynamic) -> RhaiResultOf<()> {
+        self.write_i64(i64::from(value.tag()));
+        self.write_bool(value.is_read_only());
+
+        match value.0 {
+            Union::Unit(..) => self.write_u8(0),
+            Union::Bool(b, ..) => {
+                self.write_u8(1);
+                self.write_bool(b);
+            }
+            Union::Str(ref s, ..) => {
+                self.write_u8(2);
+                self.write_str(s);
+            }
+            Union::Char(c, ..) => {
+                self.write_u8(3);
+                self.write_u64(u64::from(c));
+            }
+            Union::Int(n, ..) => {
+                self.write_u8(4);
+                self.write_int(n);
+            }
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(f, ..) => {
+                self.write_u8(5);
+                self.write_float(*f);
+            }
+            #[cfg(feature = "decimal")]
+            Union::Decimal(ref d, ..) => {
+                self.write_u8(6);
+                self.buf.extend_from_slice(&d.serialize());
+            }
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(ref a, ..) => {
+                self.write_u8(7);
+                self.write_slice(a, Self::write_dynamic)?;
+            }
+            #[cfg(not(feature = "no_index"))]
+            Union::Blob(ref b, ..) => {
+                self.write_u8(8);
+                self.write_usize(b.len());
+                self.buf.extend_from_slice(b);
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(ref m, ..) => {
+                self.write_u8(9);
+                self.write_usize(m.len());
+                for (key, value) in m.iter() {
+                    self.write_str(key);
+                    self.write_dynamic(value)?;
+                }
+            }
+            Union::FnPtr(ref f, ..) if f.environ.is_none() && f.native_fn.is_none() => {
+                self.write_u8(10);
+                self.write_str(f.fn_name());
+                self.write_slice(f.curry(), Self::write_dynamic)?;
+                #[cfg(not(feature = "no_function"))]
+                self.write_bool(f.binds_this);
+            }
+            Union::Variant(..) if value.is::<ExclusiveRange>() => {
+                let range = value.downcast_ref::<ExclusiveRange>().unwrap();
+                self.write_u8(11);
+                self.write_int(range.start);
+                self.write_int(range.end);
+            }
+            Union::Variant(..) if value.is::<InclusiveRange>() => {
+                let range = value.downcast_ref::<InclusiveRange>().unwrap();
+                self.write_u8(12);
+                self.write_int(*range.start());
+                self.write_int(*range.end());
+            }
+            #[cfg(feature = "bigint")]
+            Union::Variant(..) if value.is::<num_bigint::BigInt>() => {
+                let n = value.downcast_ref::<num_bigint::BigInt>().unwrap();
+                let bytes = n.to_signed_bytes_le();
+                self.write_u8(13);
+                self.write_usize(bytes.len());
+                self.buf.extend_from_slice(&bytes);
+            }
+            _ => {
+                return Err(save_error(format!(
+                    "constant of type '{}' cannot be saved",
+                    value.type_name()
+                )))
+            }
+        }
+
+        Ok(())
+    }
+    fn write_block(&mut self, block: &StmtBlock) -> RhaiResultOf<()> {
+        self.write_span(block.span());
+        self.write_slice(block.statements(), Self::write_stmt)
+    }
+    fn write_binary(&mut self, x: &BinaryExpr) -> RhaiResultOf<()> {
+        self.write_expr(&x.lhs)?;
+        self.write_expr(&x.rhs)
+    }
+    fn write_flow(&mut self, x: &FlowControl) -> RhaiResultOf<()> {
+        self.write_expr(&x.expr)?;
+        self.write_block(&x.body)?;
+        self.write_block(&x.branch)
+    }
+    /// Write how a function call hash is calculated, which is recalculated when loading.
+    fn write_call_hash(&mut self, x: &FnCallExpr, hash: u64) -> RhaiResultOf<()> {
+        let (qualified, num) = (0..=x.args.len() + 1)
+            .find_map(|num| {
+                [false, true]
+                    .iter()
+                    .copied()
+                    .filter(|&qualified| !qualified || x.is_qualified())
+                    .find(|&qualified| {
+                        calc_call_hash(&x.namespace, qualified, &x.name, num) == hash
+                    })
+                    .map(|qualified| (qualified, num))
+            })
+            .ok_or_else(|| save_error(format!("unknown hash for call to function '{}'", x.name)))?;
+
+        self.write_bool(qualified);
+        self.write_usize(num);
+        Ok(())
+    }
+    fn write_fn_call(&mut self, x: &FnCallExpr) -> RhaiResultOf<()> {
+        self.write_namespace(&x.namespace)?;
+        self.write_str(&x.name);
+        self.write_call_hash(x, x.hashes.native())?;
+        #[cfg(not(feature = "no_function"))]
+        self.write_option(
+            (!x.hashes.is_native_only()).then(|| x.hashes.script()),
+            |w, hash| w.write_call_hash(x, hash),
+        )?;
+        self.write_slice(&x.args, Self::write_expr)?;
+        self.write_slice(&x.arg_names, Self::write_ident)?;
+        self.write_bool(x.capture_parent_scope);
+        self.write_option(x.op_token.as_ref(), |w, token| {
+            let syntax = token.literal_syntax();
+            if Token::lookup_symbol_from_syntax(syntax).as_ref() != Some(token) {
+                return Err(save_error(format!("operator '{syntax}' cannot be saved")));
+            }
+            w.write_str(syntax);
+            Ok(())
+        })?;
+        self.write_pos(x.end_pos);
+        Ok(())
+    }
+    fn write_expr(&mut self, expr: &Expr) -> RhaiResultOf<()> {
+        match expr {
+            Expr::DynamicConstant(x, pos) => {
+                self.write_u8(0);
+                self.write_dynamic(x)?;
+                self.write_pos(*pos);
+            }
+            Expr::BoolConstant(x, pos) => {
+                self.write_u8(1);
+                self.write_bool(*x);
+                self.write_pos(*pos);
+            }
+            Expr::IntegerConstant(x, pos) => {
+                self.write_u8(2);
+                self.write_int(*x);
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(x, pos) => {
+                self.write_u8(3);
+                self.write_float(**x);
+                self.write_pos(*pos);
+            }
+            Expr::CharConstant(x, pos) => {
+                self.write_u8(4);
+                self.write_u64(u64::from(*x));
+                self.write_pos(*pos);
+            }
+            Expr::StringConstant(x, pos) => {
+                self.write_u8(5);
+                self.write_str(x);
+                self.write_pos(*pos);
+            }
+            Expr::InterpolatedString(x, pos) => {
+                self.write_u8(6);
+                self.write_slice(x, Self::write_expr)?;
+                self.write_pos(*pos);
+            }
+            Expr::Array(x, pos) => {
+                self.write_u8(7);
+                self.write_slice(x, Self::write_expr)?;
+                self.write_pos(*pos);
+            }
+            Expr::Map(x, pos) => {
+                self.write_u8(8);
+                self.write_slice(&x.0, |w, (name, expr)| {
+                    w.write_ident(name)?;
+                    w.write_expr(expr)
+                })?;
+                self.write_usize(x.1.len());
+                for (key, value) in x.1.iter() {
+                    self.write_str(key);
+                    self.write_dynamic(value)?;
+                }
+                self.write_pos(*pos);
+            }
+            Expr::Unit(pos) => {
+                self.write_u8(9);
+                self.write_pos(*pos);
+            }
+            Expr::Variable(x, index, pos) => {
+                self.write_u8(10);
+                self.write_index(x.0);
+                self.write_namespace(&x.1)?;
+                self.write_str(&x.3);
+                self.write_u8(index.map_or(0, NonZeroU8::get));
+                self.write_pos(*pos);
+            }
+            Expr::ThisPtr(pos) => {
+                self.write_u8(11);
+                self.write_pos(*pos);
+            }
+            Expr::Property(x, pos) => {
+                self.write_u8(12);
+                self.write_str(&(x.0).0);
+                self.write_str(&(x.1).0);
+                self.write_str(&x.2);
+                self.write_pos(*pos);
+            }
+            Expr::MethodCall(x, pos) => {
+                self.write_u8(13);
+                self.write_fn_call(x)?;
+                self.write_pos(*pos);
+            }
+            Expr::Stmt(x) => {
+                self.write_u8(14);
+                self.write_block(x)?;
+            }
+            Expr::FnCall(x, pos) => {
+                self.write_u8(15);
+                self.write_fn_call(x)?;
+                self.write_pos(*pos);
+            }
+            Expr::Dot(x, flags, pos) => {
+                self.write_u8(16);
+                self.write_binary(x)?;
+                self.write_flags(*flags);
+                self.write_pos(*pos);
+            }
+            Expr::Index(x, flags, pos) => {
+                self.write_u8(17);
+                self.write_binary(x)?;
+                self.write_flags(*flags);
+                self.write_pos(*pos);
+            }
+            Expr::And(x, pos) => {
+                self.write_u8(18);
+                self.write_binary(x)?;
+                self.write_pos(*pos);
+            }
+            Expr::Or(x, pos) => {
+                self.write_u8(19);
+                self.write_binary(x)?;
+                self.write_pos(*pos);
+            }
+            Expr::Coalesce(x, pos) => {
+                self.write_u8(20);
+                self.write_binary(x)?;
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, pos) => {
+                self.write_u8(21);
+                self.write_slice(&x.inputs, Self::write_expr)?;
+                self.write_slice(&x.tokens, |w, token| {
+                    w.write_str(token);
+                    Ok(())
+                })?;
+                self.write_dynamic(&x.state)?;
+                self.write_bool(x.scope_may_be_changed);
+                self.write_bool(x.self_terminated);
+                self.write_pos(*pos);
+            }
+        }
+
+        Ok(())
+    }
+    fn write_switch(&mut self, cases: &SwitchCasesCollection) -> RhaiResultOf<()> {
+        self.write_slice(&cases.expressions, |w, x| {
+            w.write_expr(&x.condition)?;
+            w.write_expr(&x.expr)
+        })?;
+
+        // Only save the case values still in the hash table (e.g. not removed by the optimizer);
+        // the hash table itself is rebuilt when loading.
+        let case_values: Vec<_> = cases
+            .case_values
+            .iter()
+            .filter(|(value, index)| {
+                cases
+                    .cases
+                    .get(&hash_case_value(value))
+                    .map_or(false, |list| list.contains(index))
+            })
+            .collect();
+
+        if case_values.len() != cases.cases.values().map(|list| list.len()).sum::<usize>() {
+            return Err(save_error("switch cases without values cannot be saved"));
+        }
+
+        self.write_slice(&case_values, |w, (value, index)| {
+            w.write_dynamic(value)?;
+            w.write_usize(*index);
+            Ok(())
+        })?;
+        self.write_slice(&cases.ranges, |w, range| {
+            match range {
+                RangeCase::ExclusiveInt(r, index) => {
+                    w.write_bool(false);
+                    w.write_int(r.start);
+                    w.write_int(r.end);
+                    w.write_usize(*index);
+                }
+                RangeCase::InclusiveInt(r, index) => {
+                    w.write_bool(true);
+                    w.write_int(*r.start());
+                    w.write_int(*r.end());
+                    w.write_usize(*index);
+                }
+            }
+            Ok(())
+        })?;
+        self.write_slice(&cases.literals, |w, (value, index)| {
+            w.write_dynamic(value)?;
+            w.write_usize(*index);
+            Ok(())
+        })?;
+        self.write_option(cases.def_case, |w, index| {
+            w.write_usize(index);
+            Ok(())
+        })
+    }
+    fn write_stmt(&mut self, stmt: &Stmt) -> RhaiResultOf<()> {
+        match stmt {
+            Stmt::Noop(pos) => {
+                self.write_u8(0);
+                self.write_pos(*pos);
+            }
+            Stmt::If(x, pos) => {
+                self.write_u8(1);
+                self.write_flow(x)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Switch(x, pos) => {
+                self.write_u8(2);
+                self.write_expr(&x.0)?;
+                self.write_switch(&x.1)?;
+                self.write_pos(*pos);
+            }
+            Stmt::While(x, pos) => {
+                self.write_u8(3);
+                self.write_flow(x)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Do(x, flags, pos) => {
+                self.write_u8(4);
+                self.write_flow(x)?;
+                self.write_flags(*flags);
+                self.write_pos(*pos);
+            }
+            Stmt::For(x, pos) => {
+                self.write_u8(5);
+                self.write_ident(&x.0)?;
+                self.write_option(x.1.as_ref(), Self::write_ident)?;
+                self.write_option(x.2.as_ref(), Self::write_ident)?;
+                self.write_flow(&x.3)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Var(x, flags, pos) => {
+                self.write_u8(6);
+                self.write_ident(&x.0)?;
+                self.write_expr(&x.1)?;
+                self.write_index(x.2);
+                self.write_flags(*flags);
+                self.write_pos(*pos);
+            }
+            Stmt::Assignment(x) => {
+                self.write_u8(7);
+                self.write_option(
+                    x.0.get_op_assignment_info(),
+                    |w, (.., op_assign, _, _, _)| {
+                        w.write_str(op_assign.literal_syntax());
+                        Ok(())
+                    },
+                )?;
+                self.write_pos(x.0.position());
+                self.write_binary(&x.1)?;
+            }
+            Stmt::FnCall(x, pos) => {
+                self.write_u8(8);
+                self.write_fn_call(x)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Block(x) => {
+                self.write_u8(9);
+                self.write_block(x)?;
+            }
+            Stmt::TryCatch(x, pos) => {
+                self.write_u8(10);
+                self.write_block(&x.try_block)?;
+                self.write_expr(&x.catch_var)?;
+                self.write_expr(&x.guard)?;
+                self.write_block(&x.catch_block)?;
+                self.write_pos(*pos);
+            }
+            Stmt::Expr(x) => {
+                self.write_u8(11);
+                self.write_expr(x)?;
+            }
+            Stmt::BreakLoop(x, flags, pos) => {
+                self.write_u8(12);
+                self.write_option(x.as_deref(), Self::write_expr)?;
+                self.write_flags(*flags);
+                self.write_pos(*pos);
+            }
+            Stmt::Return(x, flags, pos) => {
+                self.write_u8(13);
+                self.write_option(x.as_deref(), Self::write_expr)?;
+                self.write_flags(*flags);
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, pos) => {
+                self.write_u8(14);
+                self.write_expr(&x.0)?;
+                self.write_ident(&x.1)?;
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(x, pos) => {
+                self.write_u8(15);
+                self.write_ident(&x.0)?;
+                self.write_ident(&x.1)?;
+                self.write_pos(*pos);
+            }
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(x) => {
+                self.write_u8(16);
+                self.write_slice(x, |w, (name, index)| {
+                    w.write_ident(name)?;
+                    w.write_index(*index);
+                    Ok(())
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+    #[cfg(not(feature = "no_function"))]
+    fn write_fn_def(&mut self, fn_def: &super::ScriptFnDef) -> RhaiResultOf<()> {
+        self.write_str(&fn_def.name);
+        self.write_bool(fn_def.access.is_public());
+        #[cfg(not(feature = "no_object"))]
+        self.write_option(fn_def.this_type.as_deref(), |w, s| {
+            w.write_str(s);
+            Ok(())
+        })?;
+        self.write_slice(&fn_def.params, |w, s| {
+            w.write_str(s);
+            Ok(())
+        })?;
+        self.write_slice(&fn_def.defaults, Self::write_expr)?;
+        self.write_bool(fn_def.is_variadic);
+        #[cfg(not(feature = "no_optimize"))]
+        self.write_bool(fn_def.no_optimize);
+        #[cfg(feature = "no_optimize")]
+        self.write_bool(false);
+        #[cfg(feature = "metadata")]
+        self.write_slice(&fn_def.comments, |w, s| {
+            w.write_str(s);
+            Ok(())
+        })?;
+        self.write_block(&fn_def.body)
+    }
+}
+
+/// Decoder of [`AST`] nodes.
+#[derive(Debug)]
+struct Reader<'a> {
+    /// Remaining input.
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> RhaiResultOf<&'a [u8]> {
+        if len > self.bytes.len() {
+            return Err(load_error("unexpected end of data"));
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+    fn read_u8(&mut self) -> RhaiResultOf<u8> {
+        self.read_bytes(1).map(|b| b[0])
+    }
+    fn read_u32(&mut self) -> RhaiResultOf<u32> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+    fn read_bool(&mut self) -> RhaiResultOf<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            n => Err(load_error(format!("invalid boolean value {n}"))),
+        }
+    }
+    /// Read an unsigned integer in LEB128 encoding.
+    fn read_u64(&mut self) -> RhaiResultOf<u64> {
+        let mut value = 0_u64;
+
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(load_error("integer too large"))
+    }
+    fn read_usize(&mut self) -> RhaiResultOf<usize> {
+        usize::try_from(self.read_u64()?).map_err(|_| load_error("size too large"))
+    }
+    fn read_u16(&mut self) -> RhaiResultOf<u16> {
+        u16::try_from(self.read_u64()?).map_err(|_| load_error("number too large"))
+    }
+    /// Read a signed integer in zig-zag encoding.
+    fn read_i64(&mut self) -> RhaiResultOf<i64> {
+        let value = self.read_u64()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+    fn read_int(&mut self) -> RhaiResultOf<INT> {
+        INT::try_from(self.read_i64()?).map_err(|_| load_error("integer out of range"))
+    }
+    fn read_char(&mut self) -> RhaiResultOf<char> {
+        u32::try_from(self.read_u64()?)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| load_error("invalid character"))
+    }
+    #[cfg(not(feature = "no_float"))]
+    #[allow(clippy::useless_conversion)]
+    fn read_float(&mut self) -> RhaiResultOf<crate::FLOAT> {
+        let bits = self.read_u64()?;
+        TryFrom::try_from(bits)
+            .map(crate::FLOAT::from_bits)
+            .map_err(|_| load_error("invalid floating-point number"))
+    }
+    fn read_str(&mut self) -> RhaiResultOf<ImmutableString> {
+        let len = self.read_usize()?;
+        let bytes = self.read_bytes(len)?;
+        std::str::from_utf8(bytes)
+            .map(Into::into)
+            .map_err(|_| load_error("invalid UTF-8 string"))
+    }
+    fn read_index(&mut self) -> RhaiResultOf<Option<NonZeroUsize>> {
+        self.read_usize().map(NonZeroUsize::new)
+    }
+    fn read_pos(&mut self) -> RhaiResultOf<Position> {
+        let line = self.read_u16()?;
+        let pos = self.read_u16()?;
+        Ok(if line == 0 {
+            Position::NONE
+        } else {
+            Position::new(line, pos)
+        })
+    }
+    fn read_span(&mut self) -> RhaiResultOf<Span> {
+        Ok(Span::new(self.read_pos()?, self.read_pos()?))
+    }
+    fn read_flags(&mut self) -> RhaiResultOf<ASTFlags> {
+        let bits = self.read_u8()?;
+        ASTFlags::from_bits(bits).ok_or_else(|| load_error(format!("invalid flags {bits}")))
+    }
+    fn read_ident(&mut self) -> RhaiResultOf<Ident> {
+        Ok(Ident {
+            name: self.read_str()?,
+            pos: self.read_pos()?,
+        })
+    }
+    fn read_option<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> RhaiResultOf<T>,
+    ) -> RhaiResultOf<Option<T>> {
+        if self.read_bool()? {
+            f(self).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+    fn read_seq<T, C: FromIterator<T>>(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> RhaiResultOf<T>,
+    ) -> RhaiResultOf<C> {
+        let len = self.read_usize()?;
+        (0..len).map(|_| f(self)).collect()
+    }
+    fn read_namespace(&mut self) -> RhaiResultOf<Namespace> {
+        #[cfg(not(feature = "no_module"))]
+        return Ok(Namespace {
+            path: self.read_seq(Self::read_ident)?,
+            index: self.read_index()?,
+        });
+        #[cfg(feature = "no_module")]
+        return Ok(Namespace::NONE);
+    }
+    fn read_dynamic(&mut self) -> RhaiResultOf<Dynamic> {
+        let tag = Tag::try_from(self.read_i64()?).map_err(|_| load_error("invalid tag"))?;
+        let access = if self.read_bool()? {
+            AccessMode::ReadOnly
+        } else {
+            AccessMode::ReadWrite
+        };
+
+        let mut value = match self.read_u8()? {
+            0 => Dynamic(Union::Unit((), tag, access)),
+            1 => Dynamic(Union::Bool(self.read_bool()?, tag, access)),
+            2 => Dynamic(Union::Str(self.read_str()?, tag, access)),
+            3 => Dynamic(Union::Char(self.read_char()?, tag, access)),
+            4 => Dynamic(Union::Int(self.read_int()?, tag, access)),
+            #[cfg(not(feature = "no_float"))]
+            5 => Dynamic(Union::Float(self.read_float()?.into(), tag, access)),
+            #[cfg(feature = "decimal")]
+            6 => {
+                let bytes = self.read_bytes(16)?;
+                let mut buf = [0_u8; 16];
+                buf.copy_from_slice(bytes);
+                let d = rust_decimal::Decimal::deserialize(buf);
+                Dynamic(Union::Decimal(d.into(), tag, access))
+            }
+            #[cfg(not(feature = "no_index"))]
+            7 => {
+                let array: crate::Array = self.read_seq(Self::read_dynamic)?;
+                Dynamic(Union::Array(array.into(), tag, access))
+            }
+            #[cfg(not(feature = "no_index"))]
+            8 => {
+                let len = self.read_usize()?;
+                let blob = self.read_bytes(len)?.to_vec();
+                Dynamic(Union::Blob(blob.into(), tag, access))
+            }
+            #[cfg(not(feature = "no_object"))]
+            9 => {
+                let len = self.read_usize()?;
+                let mut map = crate::Map::new();
+                for _ in 0..len {
+                    let key = self.read_str()?;
+                    map.insert(key.as_str().into(), self.read_dynamic()?);
+                }
+                Dynamic(Union::Map(map.into(), tag, access))
+            }
+            10 => {
+                let fn_ptr = FnPtr {
+                    name: self.read_str()?,
+                    curry: self.read_seq(Self::read_dynamic)?,
+                    environ: None,
+                    native_fn: None,
+                    #[cfg(not(feature = "no_function"))]
+                    fn_def: None,
+                    #[cfg(not(feature = "no_function"))]
+                    binds_this: self.read_bool()?,
+                };
+                Dynamic(Union::FnPtr(fn_ptr.into(), tag, access))
+            }
+            11 => {
+                let range: ExclusiveRange = self.read_int()?..self.read_int()?;
+                Dynamic::from(range)
+            }
+            12 => {
+                let range: InclusiveRange = self.read_int()?..=self.read_int()?;
+                Dynamic::from(range)
+            }
+            #[cfg(feature = "bigint")]
+            13 => {
+                let len = self.read_usize()?;
+                let n = num_bigint::BigInt::from_signed_bytes_le(self.read_bytes(len)?);
+                Dynamic::from(n)
+            }
+            n => return Err(load_error(format!("invalid value type {n}"))),
+        };
+
+        if let Union::Variant(..) = value.0 {
+            value.set_tag(tag).set_access_mode(access);
+        }
+
+        Ok(value)
+    }
+    fn read_block(&mut self) -> RhaiResultOf<StmtBlock> {
+        let span = self.read_span()?;
+        let statements: Vec<_> = self.read_seq(Self::read_stmt)?;
+        Ok(StmtBlock::new_with_span(statements, span))
+    }
+    fn read_binary(&mut self) -> RhaiResultOf<Box<BinaryExpr>> {
+        Ok(BinaryExpr {
+            lhs: self.read_expr()?,
+            rhs: self.read_expr()?,
+        }
+        .into())
+    }
+    fn read_flow(&mut self) -> RhaiResultOf<Box<FlowControl>> {
+        Ok(FlowControl {
+            expr: self.read_expr()?,
+            body: self.read_block()?,
+            branch: self.read_block()?,
+        }
+        .into())
+    }
+    fn read_call_hash(&mut self, namespace: &Namespace, name: &str) -> RhaiResultOf<u64> {
+        let qualified = self.read_bool()?;
+        let num = self.read_usize()?;
+        Ok(calc_call_hash(namespace, qualified, name, num))
+    }
+    fn read_fn_call(&mut self) -> RhaiResultOf<Box<FnCallExpr>> {
+        let namespace = self.read_namespace()?;
+        let name = self.read_str()?;
+        let native = self.read_call_hash(&namespace, &name)?;
+        #[cfg(not(feature = "no_function"))]
+        let hashes = match self.read_option(|r| r.read_call_hash(&namespace, &name))? {
+            Some(script) => FnCallHashes::from_script_and_native(script, native),
+            None => FnCallHashes::from_native_only(native),
+        };
+        #[cfg(feature = "no_function")]
+        let hashes = FnCallHashes::from_native_only(native);
+
+        Ok(FnCallExpr {
+            namespace,
+            name,
+            hashes,
+            args: self.read_seq(Self::read_expr)?,
+            arg_names: self.read_seq(Self::read_ident)?,
+            capture_parent_scope: self.read_bool()?,
+            op_token: self.read_option(|r| {
+                let syntax = r.read_str()?;
+                Token::lookup_symbol_from_syntax(&syntax)
+                    .ok_or_else(|| load_error(format!("invalid operator '{syntax}'")))
+            })?,
+            end_pos: self.read_pos()?,
+        }
+        .into())
+    }
+    fn read_expr(&mut self) -> RhaiResultOf<Expr> {
+        Ok(match self.read_u8()? {
+            0 => Expr::DynamicConstant(self.read_dynamic()?.into(), self.read_pos()?),
+            1 => Expr::BoolConstant(self.read_bool()?, self.read_pos()?),
+            2 => Expr::IntegerConstant(self.read_int()?, self.read_pos()?),
+            #[cfg(not(feature = "no_float"))]
+            3 => Expr::FloatConstant(self.read_float()?.into(), self.read_pos()?),
+            4 => Expr::CharConstant(self.read_char()?, self.read_pos()?),
+            5 => Expr::StringConstant(self.read_str()?, self.read_pos()?),
+            6 => Expr::InterpolatedString(
+                Box::new(self.read_seq(Self::read_expr)?),
+                self.read_pos()?,
+            ),
+            7 => Expr::Array(Box::new(self.read_seq(Self::read_expr)?), self.read_pos()?),
+            8 => {
+                let items = self.read_seq(|r| Ok((r.read_ident()?, r.read_expr()?)))?;
+                let len = self.read_usize()?;
+                let mut template = super::expr::MapTemplate::new();
+                for _ in 0..len {
+                    let key = self.read_str()?;
+                    template.insert(key.as_str().into(), self.read_dynamic()?);
+                }
+                Expr::Map((items, template).into(), self.read_pos()?)
+            }
+            9 => Expr::Unit(self.read_pos()?),
+            10 => {
+                let index = self.read_index()?;
+                let namespace = self.read_namespace()?;
+                let name = self.read_str()?;
+                let hash = calc_variable_hash(&namespace, &name);
+                let short_index = NonZeroU8::new(self.read_u8()?);
+                Expr::Variable(
+                    (index, namespace, hash, name).into(),
+                    short_index,
+                    self.read_pos()?,
+                )
+            }
+            11 => Expr::ThisPtr(self.read_pos()?),
+            12 => {
+                let getter = self.read_str()?;
+                let hash_get = calc_fn_hash(None, &getter, 1);
+                let setter = self.read_str()?;
+                let hash_set = calc_fn_hash(None, &setter, 2);
+                let prop = self.read_str()?;
+                Expr::Property(
+                    ((getter, hash_get), (setter, hash_set), prop).into(),
+                    self.read_pos()?,
+                )
+            }
+            13 => Expr::MethodCall(self.read_fn_call()?, self.read_pos()?),
+            14 => Expr::Stmt(self.read_block()?.into()),
+            15 => Expr::FnCall(self.read_fn_call()?, self.read_pos()?),
+            16 => Expr::Dot(self.read_binary()?, self.read_flags()?, self.read_pos()?),
+            17 => Expr::Index(self.read_binary()?, self.read_flags()?, self.read_pos()?),
+            18 => Expr::And(self.read_binary()?, self.read_pos()?),
+            19 => Expr::Or(self.read_binary()?, self.read_pos()?),
+            20 => Expr::Coalesce(self.read_binary()?, self.read_pos()?),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            21 => {
+                let custom = super::CustomExpr {
+                    inputs: self.read_seq(Self::read_expr)?,
+                    tokens: self.read_seq(Self::read_str)?,
+                    state: self.read_dynamic()?,
+                    scope_may_be_changed: self.read_bool()?,
+                    self_terminated: self.read_bool()?,
+                };
+                Expr::Custom(custom.into(), self.read_pos()?)
+            }
+            n => return Err(load_error(format!("invalid expression type {n}"))),
+        })
+    }
+    fn read_switch(&mut self) -> RhaiResultOf<SwitchCasesCollection> {
+        let expressions: Vec<_> = self.read_seq(|r| {
+            Ok(ConditionalExpr {
+                condition: r.read_expr()?,
+                expr: r.read_expr()?,
+            })
+        })?;
+
+        let num_expressions = expressions.len();
+        let read_index = |r: &mut Self| {
+            let index = r.read_usize()?;
+            if index < num_expressions {
+                Ok(index)
+            } else {
+                Err(load_error(format!("invalid switch case {index}")))
+            }
+        };
+
+        let case_values: Vec<_> = self.read_seq(|r| Ok((r.read_dynamic()?, read_index(r)?)))?;
+        let ranges = self.read_seq(|r| {
+            let inclusive = r.read_bool()?;
+            let start = r.read_int()?;
+            let end = r.read_int()?;
+            let index = read_index(r)?;
+            Ok(if inclusive {
+                RangeCase::InclusiveInt(start..=end, index)
+            } else {
+                RangeCase::ExclusiveInt(start..end, index)
+            })
+        })?;
+        let literals = self.read_seq(|r| Ok((r.read_dynamic()?, read_index(r)?)))?;
+        let def_case = self.read_option(read_index)?;
+
+        let mut cases = StraightHashMap::<CaseBlocksList>::default();
+
+        for (value, index) in &case_values {
+            cases
+                .entry(hash_case_value(value))
+                .or_insert(CaseBlocksList::new_const())
+                .push(*index);
+        }
+
+        Ok(SwitchCasesCollection {
+            expressions,
+            cases,
+            ranges,
+            literals,
+            case_values,
+            def_case,
+        })
+    }
+    fn read_stmt(&mut self) -> RhaiResultOf<Stmt> {
+        Ok(match self.read_u8()? {
+            0 => Stmt::Noop(self.read_pos()?),
+            1 => Stmt::If(self.read_flow()?, self.read_pos()?),
+            2 => {
+                let expr = self.read_expr()?;
+                let cases = self.read_switch()?;
+                Stmt::Switch((expr, cases).into(), self.read_pos()?)
+            }
+            3 => Stmt::While(self.read_flow()?, self.read_pos()?),
+            4 => Stmt::Do(self.read_flow()?, self.read_flags()?, self.read_pos()?),
+            5 => {
+                let var = self.read_ident()?;
+                let var2 = self.read_option(Self::read_ident)?;
+                let var3 = self.read_option(Self::read_ident)?;
+                let flow = *self.read_flow()?;
+                Stmt::For((var, var2, var3, flow).into(), self.read_pos()?)
+            }
+            6 => {
+                let var = self.read_ident()?;
+                let expr = self.read_expr()?;
+                let index = self.read_index()?;
+                Stmt::Var(
+                    (var, expr, index).into(),
+                    self.read_flags()?,
+                    self.read_pos()?,
+                )
+            }
+            7 => {
+                let op_assign = self.read_option(|r| {
+                    let syntax = r.read_str()?;
+                    Token::lookup_symbol_from_syntax(&syntax)
+                        .filter(Token::is_op_assignment)
+                        .ok_or_else(|| load_error(format!("invalid operator '{syntax}'")))
+                })?;
+                let pos = self.read_pos()?;
+                let op = match op_assign {
+                    Some(token) => OpAssignment::new_op_assignment_from_token(token, pos),
+                    None => OpAssignment::new_assignment(pos),
+                };
+                Stmt::Assignment((op, *self.read_binary()?).into())
+            }
+            8 => Stmt::FnCall(self.read_fn_call()?, self.read_pos()?),
+            9 => Stmt::Block(self.read_block()?.into()),
+            10 => {
+                let block = TryCatchBlock {
+                    try_block: self.read_block()?,
+                    catch_var: self.read_expr()?,
+                    guard: self.read_expr()?,
+                    catch_block: self.read_block()?,
+                };
+                Stmt::TryCatch(block.into(), self.read_pos()?)
+            }
+            11 => Stmt::Expr(self.read_expr()?.into()),
+            12 => Stmt::BreakLoop(
+                self.read_option(|r| r.read_expr().map(Box::new))?,
+                self.read_flags()?,
+                self.read_pos()?,
+            ),
+            13 => Stmt::Return(
+                self.read_option(|r| r.read_expr().map(Box::new))?,
+                self.read_flags()?,
+                self.read_pos()?,
+            ),
+            #[cfg(not(feature = "no_module"))]
+            14 => {
+                let expr = self.read_expr()?;
+                let alias = self.read_ident()?;
+                Stmt::Import((expr, alias).into(), self.read_pos()?)
+            }
+            #[cfg(not(feature = "no_module"))]
+            15 => {
+                let name = self.read_ident()?;
+                let alias = self.read_ident()?;
+                Stmt::Export((name, alias).into(), self.read_pos()?)
+            }
+            #[cfg(not(feature = "no_closure"))]
+            16 => Stmt::Share(Box::new(
+                self.read_seq(|r| Ok((r.read_ident()?, r.read_index()?)))?,
+            )),
+            n => return Err(load_error(format!("invalid statement type {n}"))),
+        })
+    }
+    #[cfg(not(feature = "no_function"))]
+    fn read_fn_def(&mut self) -> RhaiResultOf<super::ScriptFnDef> {
+        let name = self.read_str()?;
+        let access = if self.read_bool()? {
+            super::FnAccess::Public
+        } else {
+            super::FnAccess::Private
+        };
+        #[cfg(not(feature = "no_object"))]
+        let this_type = self.read_option(Self::read_str)?;
+        let params: crate::FnArgsVec<_> = self.read_seq(Self::read_str)?;
+        let defaults: crate::FnArgsVec<_> = self.read_seq(Self::read_expr)?;
+        let is_variadic = self.read_bool()?;
+        let _no_optimize = self.read_bool()?;
+        #[cfg(feature = "metadata")]
+        let comments = self.read_seq(|r| r.read_str().map(|s| s.as_str().into()))?;
+        let body = self.read_block()?;
+
+        if defaults.len() + usize::from(is_variadic) > params.len() {
+            return Err(load_error(format!(
+                "invalid parameters for function '{name}'"
+            )));
+        }
+
+        Ok(super::ScriptFnDef {
+            body,
+            name,
+            access,
+            #[cfg(not(feature = "no_object"))]
+            this_type,
+            params,
+            defaults,
+            is_variadic,
+            #[cfg(not(feature = "no_optimize"))]
+            no_optimize: _no_optimize,
+            #[cfg(feature = "metadata")]
+            comments,
+        })
+    }
+}
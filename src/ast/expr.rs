@@ -6,12 +6,11 @@ use crate::tokenizer::Token;
 use crate::types::dynamic::Union;
 use crate::{
     calc_fn_hash, Dynamic, FnArgsVec, FnPtr, Identifier, ImmutableString, Position, SmartString,
-    StaticVec, INT,
+    Span, StaticVec, INT,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
-    collections::BTreeMap,
     fmt,
     fmt::Write,
     hash::Hash,
@@ -20,6 +19,22 @@ use std::{
     num::{NonZeroU8, NonZeroUsize},
 };
 
+/// Template of property names and constant values for an object map literal.
+#[cfg(not(feature = "map_insertion_order"))]
+pub(crate) type MapTemplate = std::collections::BTreeMap<Identifier, Dynamic>;
+/// Template of property names and constant values for an object map literal, in insertion order.
+#[cfg(feature = "map_insertion_order")]
+pub(crate) type MapTemplate = crate::OrderedMap<Identifier, Dynamic>;
+
+/// Advance a [position][Position] by a number of characters, unless it is [`NONE`][Position::NONE].
+#[must_use]
+pub(super) fn advance_position(mut pos: Position, n: usize) -> Position {
+    if !pos.is_none() {
+        (0..n).for_each(|_| pos.advance());
+    }
+    pos
+}
+
 /// _(internals)_ A binary expression.
 /// Exported under the `internals` feature only.
 #[derive(Debug, Clone, Hash)]
@@ -192,10 +207,20 @@ pub struct FnCallExpr {
     pub hashes: FnCallHashes,
     /// List of function call argument expressions.
     pub args: Box<[Expr]>,
+    /// Names of the trailing named arguments (e.g. `f(x, y: 42)`), if any.
+    ///
+    /// Named arguments always follow positional arguments, so the `i`-th name corresponds to
+    /// argument number `args.len() - arg_names.len() + i`.
+    pub arg_names: Box<[Ident]>,
     /// Does this function call capture the parent scope?
     pub capture_parent_scope: bool,
     /// Is this function call a native operator?
     pub op_token: Option<Token>,
+    /// Ending [position][Position] (location of the closing `)`) of this function call.
+    ///
+    /// [`NONE`][Position::NONE] for operators and function calls without an argument list,
+    /// in which case the end of the last argument is taken instead.
+    pub end_pos: Position,
 }
 
 impl fmt::Debug for FnCallExpr {
@@ -209,6 +234,9 @@ impl fmt::Debug for FnCallExpr {
         ff.field("hash", &self.hashes)
             .field("name", &self.name)
             .field("args", &self.args);
+        if !self.arg_names.is_empty() {
+            ff.field("arg_names", &self.arg_names);
+        }
         if self.op_token.is_some() {
             ff.field("op_token", &self.op_token);
         }
@@ -234,6 +262,38 @@ impl FnCallExpr {
     pub fn into_fn_call_expr(self, pos: Position) -> Expr {
         Expr::FnCall(self.into(), pos)
     }
+    /// Get the ending [position][Position] of this function call.
+    ///
+    /// For operators and function calls without an argument list, this is the end of the last
+    /// argument, if any.
+    #[inline]
+    #[must_use]
+    pub fn end_position(&self) -> Position {
+        if self.end_pos.is_none() {
+            self.args
+                .iter()
+                .map(Expr::end_position)
+                .max()
+                .unwrap_or(Position::NONE)
+        } else {
+            self.end_pos
+        }
+    }
+    /// Get the [span][Span] of this function call located at a [position][Position].
+    #[must_use]
+    pub(crate) fn span(&self, pos: Position) -> Span {
+        // Operators may be positioned at the operator or have their arguments swapped
+        let start = self
+            .args
+            .iter()
+            .map(Expr::start_position)
+            .chain(once(pos))
+            .filter(|p| !p.is_none())
+            .min()
+            .unwrap_or(pos);
+
+        Span::new(start, self.end_position().or_else(pos))
+    }
     /// Are all arguments constant?
     #[inline]
     #[must_use]
@@ -271,10 +331,7 @@ pub enum Expr {
     /// [ expr, ... ]
     Array(Box<FnArgsVec<Expr>>, Position),
     /// #{ name:expr, ... }
-    Map(
-        Box<(StaticVec<(Ident, Expr)>, BTreeMap<Identifier, Dynamic>)>,
-        Position,
-    ),
+    Map(Box<(StaticVec<(Ident, Expr)>, MapTemplate)>, Position),
     /// ()
     Unit(Position),
     /// Variable access - (optional long index, namespace, namespace hash, variable name), optional short index, position
@@ -566,6 +623,8 @@ impl Expr {
                     args: once(Self::StringConstant(f.fn_name().into(), pos)).collect(),
                     capture_parent_scope: false,
                     op_token: None,
+                    arg_names: Box::default(),
+                    end_pos: Position::NONE,
                 }
                 .into(),
                 pos,
@@ -680,6 +739,79 @@ impl Expr {
             _ => self.position(),
         }
     }
+    /// Get the ending [position][Position] of the expression.
+    /// For a binary expression, this will be the end of the right-most RHS instead of the operator.
+    ///
+    /// The ending positions of literal constants, and of the closing brackets of indexing, array
+    /// and object map expressions, are derived instead of recorded, so they may not be exact if the
+    /// script contains extra whitespace or if a literal is written differently (e.g. with escape
+    /// sequences or in hex).
+    #[must_use]
+    pub fn end_position(&self) -> Position {
+        let pos = self.position();
+
+        match self {
+            Self::BoolConstant(true, ..) => advance_position(pos, 3),
+            Self::BoolConstant(false, ..) => advance_position(pos, 4),
+            Self::Unit(..) => advance_position(pos, 1),
+            Self::ThisPtr(..) => advance_position(pos, 3),
+            Self::IntegerConstant(x, ..) => advance_position(pos, x.to_string().len() - 1),
+            #[cfg(not(feature = "no_float"))]
+            Self::FloatConstant(x, ..) => advance_position(pos, x.to_string().len() - 1),
+            Self::CharConstant(x, ..) => {
+                advance_position(pos, format!("{x:?}").chars().count() - 1)
+            }
+            Self::StringConstant(x, ..) => {
+                advance_position(pos, format!("{x:?}").chars().count() - 1)
+            }
+
+            Self::Variable(x, ..) => advance_position(pos, x.3.chars().count().max(1) - 1),
+            Self::Property(x, ..) => advance_position(pos, x.2.chars().count().max(1) - 1),
+
+            Self::InterpolatedString(x, ..) => match x.last() {
+                // Text ends with the closing back-tick
+                Some(Self::StringConstant(s, p)) if !s.is_empty() => {
+                    advance_position(*p, s.chars().count())
+                }
+                // Closing `}` of the interpolated expression followed by the back-tick
+                Some(expr) => advance_position(expr.end_position(), 2),
+                None => pos,
+            },
+            Self::Array(x, ..) => x.last().map_or_else(
+                || advance_position(pos, 1),
+                |e| advance_position(e.end_position(), 1),
+            ),
+            Self::Map(x, ..) => x.0.last().map_or_else(
+                || advance_position(pos, 2),
+                |(_, e)| advance_position(e.end_position(), 1),
+            ),
+
+            Self::FnCall(x, ..) | Self::MethodCall(x, ..) => x.end_position().or_else(pos),
+
+            Self::And(x, ..) | Self::Or(x, ..) | Self::Coalesce(x, ..) | Self::Dot(x, ..) => {
+                x.rhs.end_position()
+            }
+            // Closing `]`
+            Self::Index(x, ..) => advance_position(x.rhs.end_position(), 1),
+
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Self::Custom(x, ..) => x.inputs.iter().map(Self::end_position).max().unwrap_or(pos),
+
+            Self::Stmt(x) => x.end_position().or_else(pos),
+
+            Self::DynamicConstant(..) => pos,
+        }
+    }
+    /// Get the [span][Span] of the expression, from its
+    /// [starting position][Expr::start_position] to its [ending position][Expr::end_position].
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Span {
+        match self {
+            Self::FnCall(x, pos) => x.span(*pos),
+            _ => Span::new(self.start_position(), self.end_position()),
+        }
+    }
     /// Override the [position][Position] of the expression.
     #[inline]
     pub fn set_position(&mut self, new_pos: Position) -> &mut Self {
@@ -823,7 +955,7 @@ impl Expr {
     pub fn walk<'a>(
         &'a self,
         path: &mut Vec<ASTNode<'a>>,
-        on_node: &mut (impl FnMut(&[ASTNode]) -> bool + ?Sized),
+        on_node: &mut (impl FnMut(&[ASTNode<'a>]) -> bool + ?Sized),
     ) -> bool {
         // Push the current node onto the path
         path.push(self.into());
@@ -0,0 +1,184 @@
+//! Module implementing the collection of free variables in an [`AST`][super::AST].
+
+use super::{Expr, FnCallExpr, Stmt};
+use crate::engine::KEYWORD_EVAL;
+use crate::ImmutableString;
+use std::collections::BTreeSet;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Free variables (i.e. variables that are used but not declared locally) in a script or a
+/// function body.
+#[derive(Debug, Clone, Default)]
+pub struct FreeVariables {
+    /// Stack of variables declared locally.
+    stack: Vec<ImmutableString>,
+    /// Free variables found.
+    pub vars: BTreeSet<ImmutableString>,
+    /// Can variables be accessed in ways that cannot be tracked (e.g. via `eval`)?
+    pub is_dynamic: bool,
+}
+
+impl FreeVariables {
+    /// Collect the free variables in a list of statements, with a list of parameters that are
+    /// already declared.
+    #[must_use]
+    pub fn collect<'a>(
+        statements: &[Stmt],
+        params: impl IntoIterator<Item = &'a ImmutableString>,
+    ) -> Self {
+        let mut free_vars = Self {
+            stack: params.into_iter().cloned().collect(),
+            ..Self::default()
+        };
+        free_vars.statements(statements);
+        free_vars.stack.clear();
+        free_vars
+    }
+    /// Is any of the names a free variable?
+    ///
+    /// Always `true` if variables can be accessed in ways that cannot be tracked.
+    #[must_use]
+    pub fn contains_any(&self, names: &[&str]) -> bool {
+        self.is_dynamic || names.iter().any(|&name| self.vars.contains(name))
+    }
+
+    /// Declare a local variable.
+    fn declare(&mut self, name: &ImmutableString) {
+        self.stack.push(name.clone());
+    }
+    /// Use a variable, recording it if it is not declared locally.
+    fn use_var(&mut self, name: &ImmutableString) {
+        if !self.stack.contains(name) {
+            self.vars.insert(name.clone());
+        }
+    }
+    /// Collect the free variables in a block of statements in a new scope.
+    fn block(&mut self, statements: &[Stmt]) {
+        let orig_len = self.stack.len();
+        self.statements(statements);
+        self.stack.truncate(orig_len);
+    }
+    /// Collect the free variables in a list of statements in the current scope.
+    fn statements(&mut self, statements: &[Stmt]) {
+        statements.iter().for_each(|stmt| self.stmt(stmt));
+    }
+    /// Collect the free variables in a statement.
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Noop(..) => (),
+
+            Stmt::If(x, ..) | Stmt::While(x, ..) | Stmt::Do(x, ..) => {
+                self.expr(&x.expr);
+                self.block(x.body.statements());
+                self.block(x.branch.statements());
+            }
+            Stmt::Switch(x, ..) => {
+                self.expr(&x.0);
+                x.1.expressions.iter().for_each(|case| {
+                    self.expr(&case.condition);
+                    self.expr(&case.expr);
+                });
+            }
+            Stmt::For(x, ..) => {
+                let (var, var2, var3, x) = &**x;
+
+                self.expr(&x.expr);
+
+                let orig_len = self.stack.len();
+                self.declare(&var.name);
+                for var in var2.iter().chain(var3) {
+                    self.declare(&var.name);
+                }
+                self.block(x.body.statements());
+                self.stack.truncate(orig_len);
+            }
+            Stmt::Var(x, ..) => {
+                self.expr(&x.1);
+                self.declare(&x.0.name);
+            }
+            Stmt::Assignment(x) => {
+                self.expr(&x.1.lhs);
+                self.expr(&x.1.rhs);
+            }
+            Stmt::FnCall(x, ..) => self.fn_call(x),
+            Stmt::Block(x) => self.block(x.statements()),
+            Stmt::TryCatch(x, ..) => {
+                self.block(x.try_block.statements());
+
+                let orig_len = self.stack.len();
+                if let Expr::Variable(ref v, ..) = x.catch_var {
+                    self.declare(&v.3);
+                }
+                self.expr(&x.guard);
+                self.block(x.catch_block.statements());
+                self.stack.truncate(orig_len);
+            }
+            Stmt::Expr(expr) => self.expr(expr),
+            Stmt::BreakLoop(expr, ..) | Stmt::Return(expr, ..) => {
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, ..) => self.expr(&x.0),
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(x, ..) => self.use_var(&x.0.name),
+
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(x) => x.iter().for_each(|(var, ..)| self.use_var(&var.name)),
+        }
+    }
+    /// Collect the free variables in a function call.
+    fn fn_call(&mut self, x: &FnCallExpr) {
+        x.args.iter().for_each(|arg| self.expr(arg));
+
+        if x.capture_parent_scope || (x.name == KEYWORD_EVAL && !x.is_qualified()) {
+            self.is_dynamic = true;
+        }
+    }
+    /// Collect the free variables in an expression.
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::DynamicConstant(..)
+            | Expr::BoolConstant(..)
+            | Expr::IntegerConstant(..)
+            | Expr::CharConstant(..)
+            | Expr::StringConstant(..)
+            | Expr::Unit(..)
+            | Expr::ThisPtr(..)
+            | Expr::Property(..) => (),
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(..) => (),
+
+            Expr::InterpolatedString(x, ..) | Expr::Array(x, ..) => {
+                x.iter().for_each(|expr| self.expr(expr));
+            }
+            Expr::Map(x, ..) => x.0.iter().for_each(|(.., expr)| self.expr(expr)),
+
+            Expr::Variable(x, ..) if x.1.is_empty() => self.use_var(&x.3),
+            Expr::Variable(..) => (),
+
+            Expr::Stmt(x) => self.block(x.statements()),
+
+            Expr::FnCall(x, ..) | Expr::MethodCall(x, ..) => self.fn_call(x),
+
+            Expr::Dot(x, ..)
+            | Expr::Index(x, ..)
+            | Expr::And(x, ..)
+            | Expr::Or(x, ..)
+            | Expr::Coalesce(x, ..) => {
+                self.expr(&x.lhs);
+                self.expr(&x.rhs);
+            }
+
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, ..) => {
+                x.inputs.iter().for_each(|expr| self.expr(expr));
+                // Custom syntax may access any variable
+                self.is_dynamic = true;
+            }
+        }
+    }
+}
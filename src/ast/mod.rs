@@ -2,11 +2,14 @@
 
 #[allow(clippy::module_inception)]
 pub mod ast;
+pub mod binary;
 pub mod expr;
 pub mod flags;
+pub mod free_vars;
 pub mod ident;
 pub mod namespace;
 pub mod namespace_none;
+pub mod print;
 pub mod script_fn;
 pub mod stmt;
 
@@ -15,6 +18,7 @@ pub use ast::{ASTNode, AST};
 pub use expr::CustomExpr;
 pub use expr::{BinaryExpr, Expr, FnCallExpr, FnCallHashes};
 pub use flags::{ASTFlags, FnAccess};
+pub use free_vars::FreeVariables;
 pub use ident::Ident;
 #[cfg(not(feature = "no_module"))]
 pub use namespace::Namespace;
@@ -24,7 +28,7 @@ pub use namespace_none::Namespace;
 pub use script_fn::{ScriptFnDef, ScriptFnMetadata};
 pub use stmt::{
     CaseBlocksList, ConditionalExpr, FlowControl, OpAssignment, RangeCase, Stmt, StmtBlock,
-    StmtBlockContainer, SwitchCasesCollection,
+    StmtBlockContainer, SwitchCasesCollection, TryCatchBlock,
 };
 
 /// _(internals)_ Placeholder for a script-defined function.
@@ -0,0 +1,1094 @@
+//! Module for printing an [`AST`] back into script text.
+#![cfg(feature = "internals")]
+
+use super::{Expr, FnCallExpr, Stmt, StmtBlock, SwitchCasesCollection, AST};
+use crate::func::hashing::get_hasher;
+use crate::tokenizer::{is_valid_function_name, Token};
+use crate::types::dynamic::Union;
+use crate::{Dynamic, ExclusiveRange, FnPtr, InclusiveRange, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    fmt::Write,
+    hash::{Hash, Hasher},
+};
+
+/// Indentation for each level of nesting.
+const INDENT: &str = "    ";
+
+/// Precedence of an expression that must always be wrapped in parentheses when used as an operand.
+const PREC_LOWEST: u8 = 0;
+/// Precedence of unary operators, which bind tighter than all binary operators.
+const PREC_UNARY: u8 = 250;
+/// Precedence of a primary expression, which never needs to be wrapped in parentheses.
+const PREC_PRIMARY: u8 = u8::MAX;
+
+impl AST {
+    /// _(internals)_ Reconstruct canonical script text from this [`AST`].
+    /// Exported under the `internals` feature only.
+    ///
+    /// Script-defined functions (sorted by name) are printed first, followed by the statements.
+    /// Under the `metadata` feature, the module documentation and function doc-comments are also
+    /// printed.  Other comments and the original formatting are not kept.
+    ///
+    /// Compiling the output yields an [`AST`] that is semantically identical to this one.
+    ///
+    /// # Limitations
+    ///
+    /// Constant values that have no literal representation in script (e.g. BLOB's, timestamps or
+    /// custom types produced by constants propagation during optimization) are printed as `()`
+    /// with a comment naming their type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("let x=(y+2)*3;if x>5{print(`x = ${x}`)}")?;
+    ///
+    /// assert_eq!(
+    ///     ast.to_source(),
+    /// "let x = (y + 2) * 3;
+    /// if x > 5 {
+    ///     print(`x = ${x}`)
+    /// }
+    /// ");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn to_source(&self) -> String {
+        let mut printer = Printer::default();
+
+        #[cfg(feature = "metadata")]
+        if !self.doc().is_empty() {
+            printer.buf.push_str(self.doc());
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        {
+            let mut functions: Vec<_> = self
+                .shared_lib()
+                .iter_script_fn()
+                .map(|(.., fn_def)| fn_def)
+                .filter(|fn_def| !crate::func::is_anonymous_fn(&fn_def.name))
+                .collect();
+
+            functions.sort_by(|a, b| {
+                a.name
+                    .cmp(&b.name)
+                    .then(a.params.len().cmp(&b.params.len()))
+            });
+
+            functions.into_iter().for_each(|fn_def| {
+                if !printer.buf.is_empty() {
+                    printer.buf.push_str("\n\n");
+                }
+                printer.write_fn_def(fn_def);
+            });
+        }
+
+        if self.statements().iter().any(is_printable) {
+            if !printer.buf.is_empty() {
+                printer.buf.push_str("\n\n");
+            }
+            printer.write_statements(self.statements());
+        }
+
+        if !printer.buf.is_empty() {
+            printer.buf.push('\n');
+        }
+
+        printer.buf
+    }
+}
+
+/// Get the precedence of an expression when used as an operand.
+#[must_use]
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::FnCall(x, ..) if x.op_token.is_some() && x.args.len() == 2 => x
+            .op_token
+            .as_ref()
+            .unwrap()
+            .precedence()
+            .map_or(PREC_LOWEST, |p| p.get()),
+        Expr::FnCall(x, ..) if x.op_token.is_some() => PREC_UNARY,
+        Expr::And(..) => Token::And.precedence().unwrap().get(),
+        Expr::Or(..) => Token::Or.precedence().unwrap().get(),
+        Expr::Coalesce(..) => Token::DoubleQuestion.precedence().unwrap().get(),
+
+        Expr::IntegerConstant(n, ..) if *n < 0 => PREC_UNARY,
+        #[cfg(not(feature = "no_float"))]
+        Expr::FloatConstant(f, ..) if f.is_sign_negative() => PREC_UNARY,
+        Expr::DynamicConstant(v, ..) => match v.0 {
+            Union::Int(n, ..) if n < 0 => PREC_UNARY,
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(f, ..) if f.is_sign_negative() => PREC_UNARY,
+            Union::FnPtr(ref f, ..) if is_closure(f) && f.curry().is_empty() => PREC_LOWEST,
+            _ => PREC_PRIMARY,
+        },
+
+        Expr::Stmt(..) => PREC_LOWEST,
+        #[cfg(not(feature = "no_custom_syntax"))]
+        Expr::Custom(..) => PREC_LOWEST,
+
+        _ => PREC_PRIMARY,
+    }
+}
+
+/// Is this expression a number constant?
+#[must_use]
+fn is_number(expr: &Expr) -> bool {
+    match expr {
+        Expr::IntegerConstant(..) => true,
+        #[cfg(not(feature = "no_float"))]
+        Expr::FloatConstant(..) => true,
+        Expr::DynamicConstant(v, ..) => match v.0 {
+            Union::Int(..) => true,
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(..) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Does the function pointer refer to an anonymous function that can be printed as a closure?
+#[must_use]
+fn is_closure(fn_ptr: &FnPtr) -> bool {
+    #[cfg(not(feature = "no_function"))]
+    return fn_ptr.fn_def.is_some() && fn_ptr.is_anonymous();
+    #[cfg(feature = "no_function")]
+    {
+        let _ = fn_ptr;
+        false
+    }
+}
+
+/// If a statements block is generated by the parser for a closure capturing external variables,
+/// return the function pointer and the number of captured variables.
+///
+/// Such a block is made up of [`Share`][Stmt::Share] statements followed by a call to `curry`
+/// with the function pointer and the captured variables.
+#[must_use]
+fn as_closure(block: &StmtBlock) -> Option<(&FnPtr, usize)> {
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let (last, rest) = block.statements().split_last()?;
+
+        if !rest.iter().all(|stmt| matches!(stmt, Stmt::Share(..))) {
+            return None;
+        }
+
+        let x = match last {
+            Stmt::FnCall(x, ..) => x,
+            Stmt::Expr(expr) => match &**expr {
+                Expr::FnCall(x, ..) => x,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        if x.name != crate::engine::KEYWORD_FN_PTR_CURRY || x.is_qualified() {
+            return None;
+        }
+
+        let (first, captured) = x.args.split_first()?;
+
+        let fn_ptr = match first {
+            Expr::DynamicConstant(v, ..) => match v.0 {
+                Union::FnPtr(ref f, ..) if is_closure(f) && f.curry().is_empty() => &**f,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let params = &fn_ptr.fn_def.as_ref()?.params;
+
+        if captured.len() > params.len()
+            || !captured
+                .iter()
+                .zip(params.iter())
+                .all(|(expr, param)| match expr {
+                    Expr::Variable(v, ..) => v.3 == *param,
+                    _ => false,
+                })
+        {
+            return None;
+        }
+
+        Some((fn_ptr, captured.len()))
+    }
+
+    #[cfg(feature = "no_closure")]
+    {
+        let _ = block;
+        None
+    }
+}
+
+/// Get the single statement inside a statements block that can be printed directly as an
+/// expression (e.g. an `if` expression), if any.
+#[must_use]
+fn as_stmt_expr(block: &StmtBlock) -> Option<&Stmt> {
+    match block.statements() {
+        [stmt @ (Stmt::If(..)
+        | Stmt::Switch(..)
+        | Stmt::While(..)
+        | Stmt::Do(..)
+        | Stmt::For(..))] => Some(stmt),
+        _ => None,
+    }
+}
+
+/// Does a statement, as printed, need no semicolon terminator?
+#[must_use]
+fn is_self_terminated(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::Expr(expr) => match &**expr {
+            Expr::Stmt(block) if as_closure(block).is_some() => false,
+            Expr::Stmt(block) => as_stmt_expr(block).map_or(true, Stmt::is_self_terminated),
+            _ => stmt.is_self_terminated(),
+        },
+        _ => stmt.is_self_terminated(),
+    }
+}
+
+/// Does a statement show up in the printed script text?
+///
+/// [`Share`][Stmt::Share] statements are generated by the parser for closures, and so are not
+/// printed.
+#[must_use]
+fn is_printable(stmt: &Stmt) -> bool {
+    #[cfg(not(feature = "no_closure"))]
+    return !matches!(stmt, Stmt::Share(..));
+    #[cfg(feature = "no_closure")]
+    {
+        let _ = stmt;
+        true
+    }
+}
+
+/// Script text printer.
+#[derive(Debug, Default)]
+struct Printer {
+    /// Output buffer.
+    buf: String,
+    /// Current level of indentation.
+    level: usize,
+}
+
+impl Printer {
+    /// Start a new line at the current level of indentation.
+    fn newline(&mut self) {
+        self.buf.push('\n');
+        (0..self.level).for_each(|_| self.buf.push_str(INDENT));
+    }
+
+    /// Print a script-defined function.
+    #[cfg(not(feature = "no_function"))]
+    fn write_fn_def(&mut self, fn_def: &super::ScriptFnDef) {
+        #[cfg(feature = "metadata")]
+        fn_def.comments.iter().for_each(|comment| {
+            self.buf.push_str(comment);
+            self.newline();
+        });
+
+        if fn_def.access.is_private() {
+            self.buf.push_str("private ");
+        }
+        self.buf.push_str("fn ");
+
+        #[cfg(not(feature = "no_object"))]
+        if let Some(ref this_type) = fn_def.this_type {
+            self.write_string(this_type);
+            self.buf.push('.');
+        }
+
+        self.buf.push_str(&fn_def.name);
+        self.buf.push('(');
+        let num_required = fn_def.num_required_params();
+        let num_fixed = fn_def.num_fixed_params();
+        fn_def.params.iter().enumerate().for_each(|(i, param)| {
+            if i > 0 {
+                self.buf.push_str(", ");
+            }
+            if i >= num_fixed {
+                self.buf.push_str("...");
+            }
+            self.buf.push_str(param);
+            if i >= num_required && i < num_fixed {
+                self.buf.push_str(" = ");
+                self.write_expr(&fn_def.defaults[i - num_required]);
+            }
+        });
+        self.buf.push(')');
+        self.buf.push(' ');
+        self.write_block(fn_def.body.statements());
+    }
+
+    /// Print a list of function parameters.
+    #[cfg(not(feature = "no_function"))]
+    fn write_params(&mut self, params: &[crate::ImmutableString], open: char, close: char) {
+        self.buf.push(open);
+        params.iter().enumerate().for_each(|(i, param)| {
+            if i > 0 {
+                self.buf.push_str(", ");
+            }
+            self.buf.push_str(param);
+        });
+        self.buf.push(close);
+    }
+
+    /// Print a list of statements, each on a new line.
+    ///
+    /// The semicolon terminator is omitted for the last statement.
+    fn write_statements(&mut self, statements: &[Stmt]) {
+        let mut statements = statements.iter().filter(|s| is_printable(s)).peekable();
+        let mut first = true;
+
+        while let Some(stmt) = statements.next() {
+            if !first {
+                self.newline();
+            }
+            first = false;
+
+            self.write_stmt(stmt);
+
+            // A no-op requires a semicolon in order to know it is an empty statement
+            if !is_self_terminated(stmt) && (statements.peek().is_some() || stmt.is_noop()) {
+                self.buf.push(';');
+            }
+        }
+    }
+
+    /// Print a list of statements on the current line, separated by semicolons.
+    fn write_statements_inline(&mut self, statements: &[Stmt]) {
+        let mut statements = statements.iter().filter(|s| is_printable(s)).peekable();
+
+        while let Some(stmt) = statements.next() {
+            self.write_stmt(stmt);
+
+            if statements.peek().is_some() || stmt.is_noop() {
+                self.buf.push_str("; ");
+            }
+        }
+    }
+
+    /// Print a statements block wrapped in braces.
+    fn write_block(&mut self, statements: &[Stmt]) {
+        if !statements.iter().any(is_printable) {
+            self.buf.push_str("{}");
+            return;
+        }
+
+        self.buf.push('{');
+        self.level += 1;
+        self.newline();
+        self.write_statements(statements);
+        self.level -= 1;
+        self.newline();
+        self.buf.push('}');
+    }
+
+    /// Print a statement, without the semicolon terminator.
+    fn write_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Noop(..) => (),
+
+            Stmt::If(x, ..) => {
+                self.buf.push_str("if ");
+                self.write_expr(&x.expr);
+                self.buf.push(' ');
+                self.write_block(x.body.statements());
+
+                match x.branch.statements() {
+                    [] => (),
+                    [stmt @ Stmt::If(..)] => {
+                        self.buf.push_str(" else ");
+                        self.write_stmt(stmt);
+                    }
+                    statements => {
+                        self.buf.push_str(" else ");
+                        self.write_block(statements);
+                    }
+                }
+            }
+
+            Stmt::Switch(x, ..) => self.write_switch(&x.0, &x.1),
+
+            Stmt::While(x, ..) if x.expr.is_unit() => {
+                self.buf.push_str("loop ");
+                self.write_block(x.body.statements());
+            }
+            Stmt::While(x, ..) => {
+                self.buf.push_str("while ");
+                self.write_expr(&x.expr);
+                self.buf.push(' ');
+                self.write_block(x.body.statements());
+            }
+            Stmt::Do(x, options, ..) => {
+                self.buf.push_str("do ");
+                self.write_block(x.body.statements());
+                if options.contains(super::ASTFlags::NEGATED) {
+                    self.buf.push_str(" until ");
+                } else {
+                    self.buf.push_str(" while ");
+                }
+                self.write_expr(&x.expr);
+            }
+            Stmt::For(x, ..) => {
+                let (var, var2, var3, x) = &**x;
+
+                self.buf.push_str("for ");
+                match (var2, var3) {
+                    (Some(var2), Some(var3)) => {
+                        let _ = write!(self.buf, "({}, {}, {})", var.name, var2.name, var3.name);
+                    }
+                    (Some(var2), None) => {
+                        let _ = write!(self.buf, "({}, {})", var.name, var2.name);
+                    }
+                    _ => self.buf.push_str(&var.name),
+                }
+                self.buf.push_str(" in ");
+                self.write_expr(&x.expr);
+                self.buf.push(' ');
+                self.write_block(x.body.statements());
+            }
+
+            Stmt::Var(x, options, ..) => {
+                if options.contains(super::ASTFlags::EXPORTED) {
+                    self.buf.push_str("export ");
+                }
+                if options.contains(super::ASTFlags::CONSTANT) {
+                    self.buf.push_str("const ");
+                } else {
+                    self.buf.push_str("let ");
+                }
+                self.buf.push_str(&x.0.name);
+                self.buf.push_str(" = ");
+                self.write_expr(&x.1);
+            }
+            Stmt::Assignment(x) => {
+                let (op_info, exprs) = &**x;
+
+                self.write_expr(&exprs.lhs);
+                self.buf.push(' ');
+                self.buf.push_str(
+                    op_info
+                        .get_op_assignment_info()
+                        .map_or(Token::Equals.literal_syntax(), |info| info.3),
+                );
+                self.buf.push(' ');
+                self.write_expr(&exprs.rhs);
+            }
+
+            Stmt::FnCall(x, ..) => self.write_fn_call(x),
+            Stmt::Expr(expr) => self.write_expr(expr),
+            Stmt::Block(x) => self.write_block(x.statements()),
+
+            Stmt::TryCatch(x, ..) => {
+                self.buf.push_str("try ");
+                self.write_block(x.try_block.statements());
+                self.buf.push_str(" catch ");
+                if let Expr::Variable(ref v, ..) = x.catch_var {
+                    let _ = write!(self.buf, "({}", v.3);
+                    if !matches!(x.guard, Expr::BoolConstant(true, ..)) {
+                        self.buf.push_str(" if ");
+                        self.write_expr(&x.guard);
+                    }
+                    self.buf.push_str(") ");
+                }
+                self.write_block(x.catch_block.statements());
+            }
+
+            Stmt::BreakLoop(expr, options, ..) | Stmt::Return(expr, options, ..) => {
+                let is_break = options.contains(super::ASTFlags::BREAK);
+
+                self.buf.push_str(match stmt {
+                    Stmt::BreakLoop(..) if is_break => "break",
+                    Stmt::BreakLoop(..) => "continue",
+                    _ if is_break => "throw",
+                    _ => "return",
+                });
+
+                if let Some(expr) = expr {
+                    self.buf.push(' ');
+                    self.write_expr(expr);
+                }
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Import(x, ..) => {
+                self.buf.push_str("import ");
+                self.write_expr(&x.0);
+                if !x.1.is_empty() {
+                    let _ = write!(self.buf, " as {}", x.1.name);
+                }
+            }
+            #[cfg(not(feature = "no_module"))]
+            Stmt::Export(x, ..) => {
+                let (name, alias) = &**x;
+
+                let _ = write!(self.buf, "export {}", name.name);
+                if !alias.is_empty() && alias.name != name.name {
+                    let _ = write!(self.buf, " as {}", alias.name);
+                }
+            }
+
+            #[cfg(not(feature = "no_closure"))]
+            Stmt::Share(..) => (),
+        }
+    }
+
+    /// Print a `switch` statement.
+    fn write_switch(&mut self, expr: &Expr, cases: &SwitchCasesCollection) {
+        // Collect the case labels for each branch, in order
+        let mut labels = vec![Vec::<String>::new(); cases.expressions.len()];
+
+        for (value, index) in &cases.case_values {
+            let hasher = &mut get_hasher();
+            value.hash(hasher);
+            let hash = hasher.finish();
+
+            // Skip cases that are removed (e.g. by the optimizer)
+            if cases
+                .cases
+                .get(&hash)
+                .map_or(false, |list| list.contains(index))
+            {
+                let mut printer = Printer::default();
+                printer.write_value(value);
+                labels[*index].push(printer.buf);
+            }
+        }
+        for range in &cases.ranges {
+            let mut printer = Printer::default();
+            match range {
+                super::RangeCase::ExclusiveInt(r, ..) => {
+                    printer.write_int(r.start);
+                    printer.buf.push_str("..");
+                    printer.write_int(r.end);
+                }
+                super::RangeCase::InclusiveInt(r, ..) => {
+                    printer.write_int(*r.start());
+                    printer.buf.push_str("..=");
+                    printer.write_int(*r.end());
+                }
+            }
+            labels[range.index()].push(printer.buf);
+        }
+        if let Some(index) = cases.def_case {
+            labels[index].push("_".into());
+        }
+
+        self.buf.push_str("switch ");
+        self.write_expr(expr);
+        self.buf.push_str(" {");
+        self.level += 1;
+
+        for (branch, labels) in cases.expressions.iter().zip(labels) {
+            if labels.is_empty() {
+                continue;
+            }
+
+            self.newline();
+            self.buf.push_str(&labels.join(" | "));
+            if !branch.is_always_true() {
+                self.buf.push_str(" if ");
+                self.write_expr(&branch.condition);
+            }
+            self.buf.push_str(" => ");
+
+            match branch.expr {
+                Expr::Stmt(ref block) if as_closure(block).is_none() => match block.statements() {
+                    [stmt] => self.write_stmt(stmt),
+                    statements => self.write_block(statements),
+                },
+                ref expr => self.write_expr(expr),
+            }
+            self.buf.push(',');
+        }
+
+        self.level -= 1;
+        self.newline();
+        self.buf.push('}');
+    }
+
+    /// Print an expression.
+    fn write_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::DynamicConstant(v, ..) => self.write_value(v),
+            Expr::BoolConstant(b, ..) => {
+                let _ = write!(self.buf, "{b}");
+            }
+            Expr::IntegerConstant(n, ..) => self.write_int(*n),
+            #[cfg(not(feature = "no_float"))]
+            Expr::FloatConstant(f, ..) => self.write_float(**f),
+            Expr::CharConstant(c, ..) => self.write_char(*c),
+            Expr::StringConstant(s, ..) => self.write_string(s),
+            Expr::InterpolatedString(x, ..) => {
+                self.buf.push('`');
+                for expr in x.iter() {
+                    match expr {
+                        // Literal segments are kept verbatim where possible
+                        Expr::StringConstant(s, ..) if !s.contains("${") && !s.contains('\r') => {
+                            self.buf.push_str(&s.replace('`', "``"));
+                        }
+                        // Interpolated expressions are kept in statements blocks
+                        Expr::Stmt(block) if as_closure(block).is_none() => {
+                            self.buf.push_str("${");
+                            self.write_statements_inline(block.statements());
+                            self.buf.push('}');
+                        }
+                        expr => {
+                            self.buf.push_str("${");
+                            self.write_expr(expr);
+                            self.buf.push('}');
+                        }
+                    }
+                }
+                self.buf.push('`');
+            }
+            Expr::Array(x, ..) => {
+                self.buf.push('[');
+                self.write_args(x);
+                self.buf.push(']');
+            }
+            Expr::Map(x, ..) => {
+                self.buf.push_str("#{");
+                x.0.iter().enumerate().for_each(|(i, (key, value))| {
+                    if i > 0 {
+                        self.buf.push_str(", ");
+                    }
+                    self.write_map_key(&key.name);
+                    self.write_expr(value);
+                });
+                self.buf.push('}');
+            }
+            Expr::Unit(..) => self.buf.push_str("()"),
+
+            Expr::Variable(x, ..) => {
+                #[cfg(not(feature = "no_module"))]
+                if !x.1.is_empty() {
+                    let _ = write!(self.buf, "{}{}", x.1, crate::engine::NAMESPACE_SEPARATOR);
+                }
+                self.buf.push_str(&x.3);
+            }
+            Expr::ThisPtr(..) => self.buf.push_str("this"),
+            Expr::Property(x, ..) => self.buf.push_str(&x.2),
+
+            Expr::Stmt(x) => match as_closure(x) {
+                Some((fn_ptr, num_captured)) => self.write_fn_ptr(fn_ptr, num_captured),
+                None => match as_stmt_expr(x) {
+                    Some(stmt) => self.write_stmt(stmt),
+                    None => self.write_block(x.statements()),
+                },
+            },
+
+            Expr::FnCall(x, ..) => self.write_fn_call(x),
+            Expr::MethodCall(x, ..) => self.write_call(x),
+
+            Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+                if precedence(&x.lhs) < PREC_PRIMARY || is_number(&x.lhs) {
+                    self.buf.push('(');
+                    self.write_expr(&x.lhs);
+                    self.buf.push(')');
+                } else {
+                    self.write_expr(&x.lhs);
+                }
+                self.write_chain(expr);
+            }
+
+            Expr::And(x, ..) => self.write_binary(&x.lhs, &Token::And, &x.rhs),
+            Expr::Or(x, ..) => self.write_binary(&x.lhs, &Token::Or, &x.rhs),
+            Expr::Coalesce(x, ..) => self.write_binary(&x.lhs, &Token::DoubleQuestion, &x.rhs),
+
+            #[cfg(not(feature = "no_custom_syntax"))]
+            Expr::Custom(x, ..) => self.write_custom(x),
+        }
+    }
+
+    /// Print the rest of a chain (i.e. after the first left-hand-side expression) given the chain
+    /// node.
+    fn write_chain(&mut self, chain: &Expr) {
+        let (rhs, options, is_dot) = match chain {
+            Expr::Dot(x, options, ..) => (&x.rhs, *options, true),
+            Expr::Index(x, options, ..) => (&x.rhs, *options, false),
+            _ => unreachable!("`Dot` or `Index` expected but gets {:?}", chain),
+        };
+        let is_optional = options.contains(super::ASTFlags::NEGATED);
+
+        if is_dot {
+            self.buf.push_str(if is_optional { "?." } else { "." });
+
+            match rhs {
+                // Continue the chain
+                Expr::Dot(x, ..) | Expr::Index(x, ..) => {
+                    self.write_expr(&x.lhs);
+                    self.write_chain(rhs);
+                }
+                expr => self.write_expr(expr),
+            }
+        } else {
+            self.buf.push_str(if is_optional { "?[" } else { "[" });
+
+            match rhs {
+                // Continue the chain
+                Expr::Dot(x, ..) | Expr::Index(x, ..)
+                    if !options.contains(super::ASTFlags::BREAK) =>
+                {
+                    self.write_expr(&x.lhs);
+                    self.buf.push(']');
+                    self.write_chain(rhs);
+                }
+                expr => {
+                    self.write_expr(expr);
+                    self.buf.push(']');
+                }
+            }
+        }
+    }
+
+    /// Print a function call, which may be an operator.
+    fn write_fn_call(&mut self, x: &FnCallExpr) {
+        match (&x.op_token, &*x.args) {
+            // `in` operator - the arguments are swapped in the call to `contains`
+            (Some(Token::In | Token::NotIn), [container, item]) => {
+                self.write_binary(item, &Token::In, container);
+            }
+            (Some(op), [lhs, rhs]) => self.write_binary(lhs, op, rhs),
+            (Some(..), [arg]) => {
+                self.buf.push_str(&x.name);
+                self.write_operand(arg, PREC_PRIMARY, false);
+            }
+            _ => self.write_call(x),
+        }
+    }
+
+    /// Print a function call in normal function call style.
+    fn write_call(&mut self, x: &FnCallExpr) {
+        #[cfg(not(feature = "no_module"))]
+        if !x.namespace.is_empty() {
+            let _ = write!(
+                self.buf,
+                "{}{}",
+                x.namespace,
+                crate::engine::NAMESPACE_SEPARATOR
+            );
+        }
+        self.buf.push_str(&x.name);
+        if x.capture_parent_scope {
+            self.buf.push('!');
+        }
+        self.buf.push('(');
+        if x.arg_names.is_empty() {
+            self.write_args(&x.args);
+        } else {
+            let num_positional = x.args.len() - x.arg_names.len();
+
+            x.args.iter().enumerate().for_each(|(i, arg)| {
+                if i > 0 {
+                    self.buf.push_str(", ");
+                }
+                if i >= num_positional {
+                    let _ = write!(self.buf, "{}: ", x.arg_names[i - num_positional].name);
+                }
+                self.write_expr(arg);
+            });
+        }
+        self.buf.push(')');
+    }
+
+    /// Print a comma-separated list of expressions.
+    fn write_args(&mut self, args: &[Expr]) {
+        args.iter().enumerate().for_each(|(i, arg)| {
+            if i > 0 {
+                self.buf.push_str(", ");
+            }
+            self.write_expr(arg);
+        });
+    }
+
+    /// Print a binary operator expression.
+    fn write_binary(&mut self, lhs: &Expr, op: &Token, rhs: &Expr) {
+        let prec = op.precedence().map_or(PREC_LOWEST, |p| p.get());
+        let bind_right = op.is_bind_right();
+
+        self.write_operand(lhs, prec, bind_right);
+        match op {
+            Token::ExclusiveRange | Token::InclusiveRange => {
+                let _ = write!(self.buf, "{op}");
+            }
+            _ => {
+                let _ = write!(self.buf, " {op} ");
+            }
+        }
+        self.write_operand(rhs, prec, !bind_right);
+    }
+
+    /// Print an operand, wrapping it in parentheses when necessary.
+    fn write_operand(&mut self, expr: &Expr, parent: u8, wrap_if_equal: bool) {
+        let prec = precedence(expr);
+
+        let wrap = if parent == PREC_LOWEST {
+            // The precedence of custom operators is not known, so play safe
+            prec < PREC_UNARY
+        } else {
+            prec < parent || (prec == parent && wrap_if_equal)
+        };
+
+        if wrap {
+            self.buf.push('(');
+            self.write_expr(expr);
+            self.buf.push(')');
+        } else {
+            self.write_expr(expr);
+        }
+    }
+
+    /// Print a custom syntax expression.
+    #[cfg(not(feature = "no_custom_syntax"))]
+    fn write_custom(&mut self, x: &super::CustomExpr) {
+        #[allow(clippy::wildcard_imports)]
+        use crate::api::custom_syntax::markers::*;
+
+        // Skip syntax variants, which are kept as inputs but not as tokens
+        let mut inputs = x.inputs.iter().filter(|expr| {
+            !matches!(expr, Expr::StringConstant(s, ..)
+                if s.len() > CUSTOM_SYNTAX_MARKER_SYNTAX_VARIANT.len()
+                    && s.starts_with(CUSTOM_SYNTAX_MARKER_SYNTAX_VARIANT))
+        });
+
+        for (i, token) in x.tokens.iter().enumerate() {
+            if i > 0 {
+                self.buf.push(' ');
+            }
+
+            match token.as_str() {
+                CUSTOM_SYNTAX_MARKER_BLOCK => match inputs.next() {
+                    Some(Expr::Stmt(block)) => self.write_block(block.statements()),
+                    Some(expr) => self.write_expr(expr),
+                    None => (),
+                },
+                CUSTOM_SYNTAX_MARKER_IDENT | CUSTOM_SYNTAX_MARKER_SYMBOL => match inputs.next() {
+                    Some(Expr::Variable(v, ..)) => self.buf.push_str(&v.3),
+                    Some(Expr::StringConstant(s, ..)) => self.buf.push_str(s),
+                    Some(expr) => self.write_expr(expr),
+                    None => (),
+                },
+                CUSTOM_SYNTAX_MARKER_EXPR
+                | CUSTOM_SYNTAX_MARKER_STRING
+                | CUSTOM_SYNTAX_MARKER_INT
+                | CUSTOM_SYNTAX_MARKER_BOOL => {
+                    if let Some(expr) = inputs.next() {
+                        self.write_expr(expr);
+                    }
+                }
+                #[cfg(not(feature = "no_float"))]
+                CUSTOM_SYNTAX_MARKER_FLOAT => {
+                    if let Some(expr) = inputs.next() {
+                        self.write_expr(expr);
+                    }
+                }
+                s => self.buf.push_str(s),
+            }
+        }
+    }
+
+    /// Print a function pointer, skipping the specified number of captured variables (if it is a
+    /// closure).
+    fn write_fn_ptr(&mut self, fn_ptr: &FnPtr, num_captured: usize) {
+        let is_curried = !fn_ptr.curry().is_empty();
+
+        if is_curried {
+            self.buf.push('(');
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        match fn_ptr.fn_def {
+            Some(ref fn_def) if is_closure(fn_ptr) => {
+                self.write_params(&fn_def.params[num_captured..], '|', '|');
+                self.buf.push(' ');
+
+                match fn_def.body.statements() {
+                    [Stmt::FnCall(x, ..)] => self.write_fn_call(x),
+                    [Stmt::Expr(expr)] if !matches!(**expr, Expr::Stmt(..)) => {
+                        self.write_expr(expr)
+                    }
+                    statements => self.write_block(statements),
+                }
+            }
+            _ => {
+                self.buf.push_str(crate::engine::KEYWORD_FN_PTR);
+                self.buf.push('(');
+                self.write_string(fn_ptr.fn_name());
+                self.buf.push(')');
+            }
+        }
+        #[cfg(feature = "no_function")]
+        {
+            let _ = num_captured;
+            self.buf.push_str(crate::engine::KEYWORD_FN_PTR);
+            self.buf.push('(');
+            self.write_string(fn_ptr.fn_name());
+            self.buf.push(')');
+        }
+
+        if is_curried {
+            self.buf.push_str(").");
+            self.buf.push_str(crate::engine::KEYWORD_FN_PTR_CURRY);
+            self.buf.push('(');
+            fn_ptr.iter_curry().enumerate().for_each(|(i, value)| {
+                if i > 0 {
+                    self.buf.push_str(", ");
+                }
+                self.write_value(value);
+            });
+            self.buf.push(')');
+        }
+    }
+
+    /// Print a constant value.
+    fn write_value(&mut self, value: &Dynamic) {
+        match value.0 {
+            Union::Unit(..) => self.buf.push_str("()"),
+            Union::Bool(b, ..) => {
+                let _ = write!(self.buf, "{b}");
+            }
+            Union::Str(ref s, ..) => self.write_string(s),
+            Union::Char(c, ..) => self.write_char(c),
+            Union::Int(n, ..) => self.write_int(n),
+            #[cfg(not(feature = "no_float"))]
+            Union::Float(f, ..) => self.write_float(*f),
+            #[cfg(feature = "decimal")]
+            Union::Decimal(ref d, ..) => {
+                let _ = write!(self.buf, "parse_decimal(\"{d}\")");
+            }
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(ref a, ..) => {
+                self.buf.push('[');
+                a.iter().enumerate().for_each(|(i, item)| {
+                    if i > 0 {
+                        self.buf.push_str(", ");
+                    }
+                    self.write_value(item);
+                });
+                self.buf.push(']');
+            }
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(ref m, ..) => {
+                self.buf.push_str("#{");
+                m.iter().enumerate().for_each(|(i, (key, value))| {
+                    if i > 0 {
+                        self.buf.push_str(", ");
+                    }
+                    self.write_map_key(key);
+                    self.write_value(value);
+                });
+                self.buf.push('}');
+            }
+            Union::FnPtr(ref f, ..) => self.write_fn_ptr(f, 0),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(..) => self.write_value(&value.flatten_clone()),
+
+            _ => {
+                if let Some(range) = value.read_lock::<ExclusiveRange>() {
+                    self.buf.push('(');
+                    self.write_int(range.start);
+                    self.buf.push_str("..");
+                    self.write_int(range.end);
+                    self.buf.push(')');
+                } else if let Some(range) = value.read_lock::<InclusiveRange>() {
+                    self.buf.push('(');
+                    self.write_int(*range.start());
+                    self.buf.push_str("..=");
+                    self.write_int(*range.end());
+                    self.buf.push(')');
+                } else {
+                    let _ = write!(self.buf, "() /* {} */", value.type_name());
+                }
+            }
+        }
+    }
+
+    /// Print an object map property name, followed by a colon.
+    fn write_map_key(&mut self, key: &str) {
+        if is_valid_function_name(key) {
+            self.buf.push_str(key);
+        } else {
+            self.write_string(key);
+        }
+        self.buf.push_str(": ");
+    }
+
+    /// Print an integer constant.
+    fn write_int(&mut self, n: INT) {
+        if n == INT::MIN {
+            // The absolute value of the minimum integer does not fit into the integer type
+            let _ = write!(self.buf, "({} - 1)", n + 1);
+        } else {
+            let _ = write!(self.buf, "{n}");
+        }
+    }
+
+    /// Print a floating-point constant.
+    #[cfg(not(feature = "no_float"))]
+    fn write_float(&mut self, f: crate::FLOAT) {
+        if !f.is_finite() {
+            let _ = write!(self.buf, "parse_float(\"{f:?}\")");
+            return;
+        }
+
+        let s = format!("{f:?}");
+
+        // Make sure that the number contains a decimal point
+        match s.find('e') {
+            Some(pos) if !s[..pos].contains('.') => {
+                self.buf.push_str(&s[..pos]);
+                self.buf.push_str(".0");
+                self.buf.push_str(&s[pos..]);
+            }
+            _ => self.buf.push_str(&s),
+        }
+    }
+
+    /// Print a character constant.
+    fn write_char(&mut self, c: char) {
+        self.buf.push('\'');
+        self.write_escaped(c, '\'');
+        self.buf.push('\'');
+    }
+
+    /// Print a string constant.
+    fn write_string(&mut self, s: &str) {
+        self.buf.push('"');
+        s.chars().for_each(|c| self.write_escaped(c, '"'));
+        self.buf.push('"');
+    }
+
+    /// Print a character inside a literal, escaping it when necessary.
+    fn write_escaped(&mut self, c: char, quote: char) {
+        match c {
+            '\\' => self.buf.push_str("\\\\"),
+            '\n' => self.buf.push_str("\\n"),
+            '\r' => self.buf.push_str("\\r"),
+            '\t' => self.buf.push_str("\\t"),
+            _ if c == quote => {
+                self.buf.push('\\');
+                self.buf.push(c);
+            }
+            _ if c.is_control() && (c as u32) <= 0xffff => {
+                let _ = write!(self.buf, "\\u{:04x}", c as u32);
+            }
+            _ if c.is_control() => {
+                let _ = write!(self.buf, "\\U{:08x}", c as u32);
+            }
+            _ => self.buf.push(c),
+        }
+    }
+}
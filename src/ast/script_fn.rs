@@ -1,7 +1,7 @@
 //! Module defining script-defined functions.
 #![cfg(not(feature = "no_function"))]
 
-use super::{FnAccess, StmtBlock};
+use super::{Expr, FnAccess, StmtBlock};
 use crate::{FnArgsVec, ImmutableString};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -23,6 +23,22 @@ pub struct ScriptFnDef {
     pub this_type: Option<ImmutableString>,
     /// Names of function parameters.
     pub params: FnArgsVec<ImmutableString>,
+    /// Default values of the trailing optional parameters (if any).
+    ///
+    /// The last `defaults.len()` parameters are optional.  When the corresponding arguments are
+    /// not provided, these expressions are evaluated, in order, in the scope of the function
+    /// (where all previous parameters are available).
+    pub defaults: FnArgsVec<Expr>,
+    /// Is the last parameter a rest parameter (e.g. `...args`)?
+    ///
+    /// A rest parameter collects all remaining arguments into an [array][crate::Array].
+    pub is_variadic: bool,
+    /// Is this function excluded from optimization?
+    ///
+    /// Set by an `//! rhai:optimize=off` marker comment placed before the function definition.
+    /// Not available under `no_optimize`.
+    #[cfg(not(feature = "no_optimize"))]
+    pub no_optimize: bool,
     /// _(metadata)_ Function doc-comments (if any). Exported under the `metadata` feature only.
     ///
     /// Doc-comments are comment lines beginning with `///` or comment blocks beginning with `/**`,
@@ -40,6 +56,22 @@ pub struct ScriptFnDef {
     pub comments: Box<[crate::SmartString]>,
 }
 
+impl ScriptFnDef {
+    /// Get the number of parameters, excluding the rest parameter (if any).
+    #[inline(always)]
+    #[must_use]
+    pub fn num_fixed_params(&self) -> usize {
+        self.params.len() - usize::from(self.is_variadic)
+    }
+    /// Get the number of parameters that do not have default values, excluding the rest parameter
+    /// (if any).
+    #[inline(always)]
+    #[must_use]
+    pub fn num_required_params(&self) -> usize {
+        self.num_fixed_params() - self.defaults.len()
+    }
+}
+
 impl fmt::Display for ScriptFnDef {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         #[cfg(not(feature = "no_object"))]
@@ -62,7 +94,12 @@ impl fmt::Display for ScriptFnDef {
             self.name,
             self.params
                 .iter()
-                .map(ImmutableString::as_str)
+                .enumerate()
+                .map(|(i, p)| if self.is_variadic && i == self.params.len() - 1 {
+                    format!("...{p}")
+                } else {
+                    p.to_string()
+                })
                 .collect::<FnArgsVec<_>>()
                 .join(", ")
         )
@@ -90,6 +127,9 @@ pub struct ScriptFnMetadata<'a> {
         serde(default, skip_serializing_if = "Vec::is_empty")
     )]
     pub params: Vec<&'a str>,
+    /// Is the last parameter a rest parameter (e.g. `...args`)?
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_variadic: bool,
     /// Function access mode.
     pub access: FnAccess,
     /// Type of `this` pointer, if any.
@@ -141,7 +181,14 @@ impl fmt::Display for ScriptFnMetadata<'_> {
             self.name,
             self.params
                 .iter()
-                .copied()
+                .enumerate()
+                .map(
+                    |(i, &p)| if self.is_variadic && i == self.params.len() - 1 {
+                        format!("...{p}")
+                    } else {
+                        p.to_string()
+                    }
+                )
                 .collect::<FnArgsVec<_>>()
                 .join(", ")
         )
@@ -154,6 +201,7 @@ impl<'a> From<&'a ScriptFnDef> for ScriptFnMetadata<'a> {
         Self {
             name: &value.name,
             params: value.params.iter().map(ImmutableString::as_str).collect(),
+            is_variadic: value.is_variadic,
             access: value.access,
             #[cfg(not(feature = "no_object"))]
             this_type: value.this_type.as_deref(),
@@ -1,5 +1,6 @@
 //! Module defining script statements.
 
+use super::expr::advance_position;
 use super::{ASTFlags, ASTNode, BinaryExpr, Expr, FnCallExpr, Ident};
 use crate::engine::{KEYWORD_EVAL, OP_EQUALS};
 use crate::func::StraightHashMap;
@@ -18,6 +19,12 @@ use std::{
     ops::{Range, RangeInclusive},
 };
 
+/// Get the ending [position][Position] of an [identifier][Ident].
+#[must_use]
+fn ident_end_position(ident: &Ident) -> Position {
+    advance_position(ident.pos, ident.name.chars().count().max(1) - 1)
+}
+
 /// _(internals)_ An op-assignment operator.
 /// Exported under the `internals` feature only.
 ///
@@ -378,6 +385,18 @@ pub struct SwitchCasesCollection {
     pub cases: StraightHashMap<CaseBlocksList>,
     /// List of range cases.
     pub ranges: Vec<RangeCase>,
+    /// List of case literals (in order) with the index to their [`ConditionalExpr`]'s,
+    /// used to match values that are not hashable (e.g. custom types).
+    ///
+    /// Only filled when switching on custom types is allowed.
+    pub literals: Vec<(Dynamic, usize)>,
+    /// List of all case values (in order) with the index to their [`ConditionalExpr`]'s.
+    ///
+    /// Kept for printing the `switch` statement back into script text and for rebuilding the
+    /// hash table when an [`AST`][crate::AST] is loaded from bytes.
+    ///
+    /// Cases that are removed from the hash table (e.g. by the optimizer) are not removed here.
+    pub case_values: Vec<(Dynamic, usize)>,
     /// Statements block for the default case (there can be no condition for the default case).
     pub def_case: Option<usize>,
 }
@@ -391,6 +410,7 @@ impl Hash for SwitchCasesCollection {
         self.cases.iter().for_each(|kv| kv.hash(state));
 
         self.ranges.hash(state);
+        self.literals.hash(state);
         self.def_case.hash(state);
     }
 }
@@ -623,6 +643,25 @@ pub struct FlowControl {
     pub branch: StmtBlock,
 }
 
+/// _(internals)_ A `try` ... `catch` block.
+/// Exported under the `internals` feature only.
+#[derive(Debug, Clone, Hash)]
+pub struct TryCatchBlock {
+    /// `try` block.
+    pub try_block: StmtBlock,
+    /// `catch` variable, if any.
+    ///
+    /// This is [`UNIT`][Expr::Unit] if there is no `catch` variable.
+    pub catch_var: Expr,
+    /// Guard condition of the `catch` clause.
+    ///
+    /// This is simply [`Expr::BoolConstant`] with `true` if there is actually no guard.
+    /// If the guard evaluates to `false`, the original error is re-thrown.
+    pub guard: Expr,
+    /// `catch` block.
+    pub catch_block: StmtBlock,
+}
+
 /// _(internals)_ A statement.
 /// Exported under the `internals` feature only.
 #[derive(Debug, Clone, Hash)]
@@ -652,8 +691,17 @@ pub enum Stmt {
     /// * [`NONE`][ASTFlags::NONE] = `while`  
     /// * [`NEGATED`][ASTFlags::NEGATED] = `until`
     Do(Box<FlowControl>, ASTFlags, Position),
-    /// `for` `(` id `,` counter `)` `in` expr `{` stmt `}`
-    For(Box<(Ident, Option<Ident>, FlowControl)>, Position),
+    /// `for` id `in` expr `{` stmt `}` | `for` `(` id `,` id `)` `in` expr `{` stmt `}` |
+    /// `for` `(` id `,` id `,` id `)` `in` expr `{` stmt `}`
+    ///
+    /// With two variables, they are either the two elements of a two-element array item, or the
+    /// item followed by a counter.
+    /// With three variables, they are either the three elements of a three-element array item, or
+    /// the two elements of a two-element array item followed by a counter.
+    For(
+        Box<(Ident, Option<Ident>, Option<Ident>, FlowControl)>,
+        Position,
+    ),
     /// \[`export`\] `let`|`const` id `=` expr
     ///
     /// ### Flags
@@ -670,8 +718,8 @@ pub enum Stmt {
     FnCall(Box<FnCallExpr>, Position),
     /// `{` stmt`;` ... `}`
     Block(Box<StmtBlock>),
-    /// `try` `{` stmt; ... `}` `catch` `(` var `)` `{` stmt; ... `}`
-    TryCatch(Box<FlowControl>, Position),
+    /// `try` `{` stmt; ... `}` `catch` `(` var \[`if` expr\] `)` `{` stmt; ... `}`
+    TryCatch(Box<TryCatchBlock>, Position),
     /// [expression][Expr]
     Expr(Box<Expr>),
     /// `continue`/`break` expr
@@ -784,6 +832,69 @@ impl Stmt {
             Self::Share(x) => x[0].0.pos,
         }
     }
+    /// Get the ending [position][Position] of this statement.
+    ///
+    /// The terminating semicolon, if any, is not included.
+    #[must_use]
+    pub fn end_position(&self) -> Position {
+        let pos = self.position();
+
+        let end_pos = match self {
+            Self::Noop(..) => pos,
+
+            Self::If(x, ..) => x.branch.end_position().or_else(x.body.end_position()),
+            Self::Switch(x, ..) => {
+                x.1.expressions
+                    .iter()
+                    .map(|c| c.expr.end_position())
+                    .max()
+                    .unwrap_or_else(|| x.0.end_position())
+            }
+            Self::While(x, ..) => x.branch.end_position().or_else(x.body.end_position()),
+            Self::TryCatch(x, ..) => x
+                .catch_block
+                .end_position()
+                .or_else(x.try_block.end_position()),
+            Self::Do(x, ..) => x.expr.end_position(),
+            Self::For(x, ..) => x.3.body.end_position(),
+
+            Self::Var(x, ..) => match x.1 {
+                Expr::Unit(p) if p.is_none() => ident_end_position(&x.0),
+                ref expr => expr.end_position(),
+            },
+            Self::Assignment(x) => x.1.rhs.end_position(),
+            Self::FnCall(x, ..) => x.end_position(),
+            Self::Block(x) => x.end_position(),
+            Self::Expr(x) => x.end_position(),
+
+            Self::BreakLoop(expr, ..) | Self::Return(expr, ..) => {
+                expr.as_ref().map_or(pos, |e| e.end_position())
+            }
+
+            #[cfg(not(feature = "no_module"))]
+            Self::Import(x, ..) if x.1.name.is_empty() => x.0.end_position(),
+            #[cfg(not(feature = "no_module"))]
+            Self::Import(x, ..) => ident_end_position(&x.1),
+            #[cfg(not(feature = "no_module"))]
+            Self::Export(x, ..) => ident_end_position(&x.1).or_else(ident_end_position(&x.0)),
+
+            #[cfg(not(feature = "no_closure"))]
+            Self::Share(..) => pos,
+        };
+
+        end_pos.or_else(pos)
+    }
+    /// Get the [span][Span] of this statement, from its [position][Stmt::position] to its
+    /// [ending position][Stmt::end_position].
+    #[inline]
+    #[must_use]
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Expr(x) => x.span(),
+            Self::FnCall(x, pos) => x.span(*pos),
+            _ => Span::new(self.position(), self.end_position()),
+        }
+    }
     /// Override the [position][Position] of this statement.
     pub fn set_position(&mut self, new_pos: Position) -> &mut Self {
         match self {
@@ -919,15 +1030,15 @@ impl Stmt {
 
             // For loops can be pure because if the iterable is pure, it is finite,
             // so infinite loops can never occur.
-            Self::For(x, ..) => x.2.expr.is_pure() && x.2.body.iter().all(Self::is_pure),
+            Self::For(x, ..) => x.3.expr.is_pure() && x.3.body.iter().all(Self::is_pure),
 
             Self::Var(..) | Self::Assignment(..) | Self::FnCall(..) => false,
             Self::Block(block, ..) => block.iter().all(Self::is_pure),
             Self::BreakLoop(..) | Self::Return(..) => false,
             Self::TryCatch(x, ..) => {
-                x.expr.is_pure()
-                    && x.body.iter().all(Self::is_pure)
-                    && x.branch.iter().all(Self::is_pure)
+                x.guard.is_pure()
+                    && x.try_block.iter().all(Self::is_pure)
+                    && x.catch_block.iter().all(Self::is_pure)
             }
 
             #[cfg(not(feature = "no_module"))]
@@ -945,7 +1056,8 @@ impl Stmt {
     /// upper block.
     ///
     /// Currently only variable definitions (i.e. `let` and `const`), `import`/`export` statements,
-    /// and `eval` calls (which may in turn define variables) fall under this category.
+    /// `eval` calls and custom syntax that may change the scope (both of which may in turn define
+    /// variables) fall under this category.
     #[inline]
     #[must_use]
     pub fn is_block_dependent(&self) -> bool {
@@ -955,6 +1067,8 @@ impl Stmt {
             Self::Expr(e) => match &**e {
                 Expr::Stmt(s) => s.iter().all(Self::is_block_dependent),
                 Expr::FnCall(x, ..) => !x.is_qualified() && x.name == KEYWORD_EVAL,
+                #[cfg(not(feature = "no_custom_syntax"))]
+                Expr::Custom(x, ..) => x.scope_may_be_changed,
                 _ => false,
             },
 
@@ -1012,7 +1126,7 @@ impl Stmt {
     pub fn walk<'a>(
         &'a self,
         path: &mut Vec<ASTNode<'a>>,
-        on_node: &mut (impl FnMut(&[ASTNode]) -> bool + ?Sized),
+        on_node: &mut (impl FnMut(&[ASTNode<'a>]) -> bool + ?Sized),
     ) -> bool {
         // Push the current node onto the path
         path.push(self.into());
@@ -1087,10 +1201,10 @@ impl Stmt {
                 }
             }
             Self::For(x, ..) => {
-                if !x.2.expr.walk(path, on_node) {
+                if !x.3.expr.walk(path, on_node) {
                     return false;
                 }
-                for s in &x.2.body {
+                for s in &x.3.body {
                     if !s.walk(path, on_node) {
                         return false;
                     }
@@ -1119,12 +1233,15 @@ impl Stmt {
                 }
             }
             Self::TryCatch(x, ..) => {
-                for s in &x.body {
+                for s in &x.try_block {
                     if !s.walk(path, on_node) {
                         return false;
                     }
                 }
-                for s in &x.branch {
+                if !x.guard.walk(path, on_node) {
+                    return false;
+                }
+                for s in &x.catch_block {
                     if !s.walk(path, on_node) {
                         return false;
                     }
@@ -1,9 +1,10 @@
 //! Main module defining the script evaluation [`Engine`].
 
-use crate::api::options::LangOptions;
+use crate::api::options::{DebugFormat, LangOptions};
+use crate::api::version::LanguageVersion;
 use crate::func::native::{
-    locked_write, OnDebugCallback, OnDefVarCallback, OnParseTokenCallback, OnPrintCallback,
-    OnVarCallback,
+    locked_write, OnDebugCallback, OnDefVarCallback, OnFnCallCallback, OnParseTokenCallback,
+    OnPrintCallback, OnStmtCallback, OnVarCallback, OnVarChainCallback, OnVarWriteCallback,
 };
 use crate::packages::{Package, StandardPackage};
 use crate::tokenizer::Token;
@@ -27,6 +28,11 @@ pub const KEYWORD_IS_SHARED: &str = "is_shared";
 pub const KEYWORD_IS_DEF_VAR: &str = "is_def_var";
 #[cfg(not(feature = "no_function"))]
 pub const KEYWORD_IS_DEF_FN: &str = "is_def_fn";
+#[cfg(not(feature = "no_index"))]
+pub const FN_SCOPE_VAR_NAMES: &str = "scope_var_names";
+pub const FN_SCOPE_VAR: &str = "scope_var";
+pub const FN_SCOPE_VAR_TYPE: &str = "scope_var_type";
+pub const FN_DISPATCH: &str = "dispatch";
 #[cfg(not(feature = "no_function"))]
 pub const KEYWORD_THIS: &str = "this";
 #[cfg(not(feature = "no_function"))]
@@ -49,6 +55,12 @@ pub const FN_ANONYMOUS: &str = "anon$";
 /// function to compare two [`Dynamic`] values.
 pub const OP_EQUALS: &str = Token::EqualsTo.literal_syntax();
 
+/// Standard less-than comparison operator.
+///
+/// Some standard functions (e.g. binary searching an [`Array`][crate::Array]) implicitly call
+/// this function to order two [`Dynamic`] values.
+pub const OP_LESS_THAN: &str = Token::LessThan.literal_syntax();
+
 /// Standard containment testing function.
 ///
 /// The `in` operator is implemented as a call to this function.
@@ -90,6 +102,13 @@ pub struct Engine {
     /// A collection of all sub-modules directly loaded into the Engine.
     #[cfg(not(feature = "no_module"))]
     pub(crate) global_sub_modules: std::collections::BTreeMap<Identifier, SharedModule>,
+    /// A collection of all sub-modules loaded into the Engine which are only built upon first access.
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) lazy_sub_modules: std::collections::BTreeMap<Identifier, Locked<LazyModule>>,
+    /// Global constants seeded into every evaluation.
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) global_constants: std::collections::BTreeMap<ImmutableString, Dynamic>,
 
     /// A module resolution service.
     #[cfg(not(feature = "no_module"))]
@@ -98,20 +117,34 @@ pub struct Engine {
     /// Strings interner.
     pub(crate) interned_strings: Option<Box<Locked<StringsInterner>>>,
 
+    /// Cache of compiled regular expressions.
+    #[cfg(feature = "regex")]
+    pub(crate) regex_cache: Option<Box<Locked<crate::types::RegexCache>>>,
+
     /// A set of symbols to disable.
     pub(crate) disabled_symbols: BTreeSet<Identifier>,
+    /// A set of function names to disable.
+    pub(crate) disabled_functions: BTreeSet<Identifier>,
+    /// Allowlist of function name patterns reachable via dynamic dispatch.
+    pub(crate) dispatch_allowlist: Option<Vec<Identifier>>,
     /// A map containing custom keywords and precedence to recognize.
     #[cfg(not(feature = "no_custom_syntax"))]
     pub(crate) custom_keywords: std::collections::BTreeMap<Identifier, Option<Precedence>>,
     /// Custom syntax.
     #[cfg(not(feature = "no_custom_syntax"))]
-    pub(crate) custom_syntax:
-        std::collections::BTreeMap<Identifier, Box<crate::api::custom_syntax::CustomSyntax>>,
+    pub(crate) custom_syntax: std::collections::BTreeMap<
+        Identifier,
+        crate::Shared<crate::api::custom_syntax::CustomSyntax>,
+    >,
 
     /// Callback closure for filtering variable definition.
     pub(crate) def_var_filter: Option<Box<OnDefVarCallback>>,
     /// Callback closure for resolving variable access.
     pub(crate) resolve_var: Option<Box<OnVarCallback>>,
+    /// Callback closure for resolving variable access with property/index chain context.
+    pub(crate) resolve_var_chain: Option<Box<OnVarChainCallback>>,
+    /// Callback closure for intercepting variable writes.
+    pub(crate) var_write_filter: Option<Box<OnVarWriteCallback>>,
     /// Callback closure to remap tokens during parsing.
     pub(crate) token_mapper: Option<Box<OnParseTokenCallback>>,
 
@@ -119,18 +152,44 @@ pub struct Engine {
     pub(crate) print: Option<Box<OnPrintCallback>>,
     /// Callback closure for implementing the `debug` command.
     pub(crate) debug: Option<Box<OnDebugCallback>>,
+    /// Callback closure for tracing function calls.
+    pub(crate) fn_call_tracer: Option<Box<OnFnCallCallback>>,
+    /// Call the function call tracing callback again, with the elapsed time, after each call?
+    #[cfg(not(feature = "no_time"))]
+    pub(crate) time_fn_calls: bool,
+    /// Callback closure for tracing the evaluation of statements.
+    pub(crate) stmt_tracer: Option<Box<OnStmtCallback>>,
     /// Callback closure for progress reporting.
     #[cfg(not(feature = "unchecked"))]
     pub(crate) progress: Option<Box<crate::func::native::OnProgressCallback>>,
+    /// Increment by which the `sleep` function sleeps between calls to the progress callback.
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(not(feature = "no_std"))]
+    pub(crate) sleep_granularity: std::time::Duration,
 
     /// Language options.
     pub(crate) options: LangOptions,
+    /// Language version of compiled scripts.
+    pub(crate) language_version: LanguageVersion,
+    /// Format of the debug output of arrays and object maps.
+    pub(crate) debug_format: DebugFormat,
 
     /// Default value for the custom state.
     pub(crate) def_tag: Dynamic,
 
     /// Script optimization level.
     pub(crate) optimization_level: OptimizationLevel,
+    /// Maximum number of iterations of a `for` loop over a constant range that are unrolled.
+    #[cfg(not(feature = "no_optimize"))]
+    pub(crate) loop_unroll_threshold: usize,
+
+    /// Rounding strategy for [`Decimal`][rust_decimal::Decimal] arithmetic.
+    #[cfg(feature = "decimal")]
+    pub(crate) decimal_rounding: rust_decimal::RoundingStrategy,
+    /// Fixed scale (number of digits after the decimal point) for the results of
+    /// [`Decimal`][rust_decimal::Decimal] arithmetic, if any.
+    #[cfg(feature = "decimal")]
+    pub(crate) decimal_scale: Option<u32>,
 
     /// Max limits.
     #[cfg(not(feature = "unchecked"))]
@@ -153,9 +212,19 @@ impl fmt::Debug for Engine {
         f.field("global_modules", &self.global_modules);
 
         #[cfg(not(feature = "no_module"))]
-        f.field("global_sub_modules", &self.global_sub_modules);
+        f.field("global_sub_modules", &self.global_sub_modules)
+            .field(
+                "lazy_sub_modules",
+                &self.lazy_sub_modules.keys().collect::<Vec<_>>(),
+            );
+
+        #[cfg(not(feature = "no_module"))]
+        #[cfg(not(feature = "no_function"))]
+        f.field("global_constants", &self.global_constants);
 
-        f.field("disabled_symbols", &self.disabled_symbols);
+        f.field("disabled_symbols", &self.disabled_symbols)
+            .field("disabled_functions", &self.disabled_functions)
+            .field("dispatch_allowlist", &self.dispatch_allowlist);
 
         #[cfg(not(feature = "no_custom_syntax"))]
         f.field("custom_keywords", &self.custom_keywords).field(
@@ -169,12 +238,25 @@ impl fmt::Debug for Engine {
 
         f.field("def_var_filter", &self.def_var_filter.is_some())
             .field("resolve_var", &self.resolve_var.is_some())
-            .field("token_mapper", &self.token_mapper.is_some());
+            .field("resolve_var_chain", &self.resolve_var_chain.is_some())
+            .field("var_write_filter", &self.var_write_filter.is_some())
+            .field("token_mapper", &self.token_mapper.is_some())
+            .field("fn_call_tracer", &self.fn_call_tracer.is_some())
+            .field("stmt_tracer", &self.stmt_tracer.is_some());
 
         #[cfg(not(feature = "unchecked"))]
         f.field("progress", &self.progress.is_some());
+        #[cfg(not(feature = "unchecked"))]
+        #[cfg(not(feature = "no_std"))]
+        f.field("sleep_granularity", &self.sleep_granularity);
+
+        f.field("options", &self.options)
+            .field("language_version", &self.language_version)
+            .field("debug_format", &self.debug_format);
 
-        f.field("options", &self.options);
+        #[cfg(feature = "decimal")]
+        f.field("decimal_rounding", &self.decimal_rounding)
+            .field("decimal_scale", &self.decimal_scale);
 
         #[cfg(not(feature = "unchecked"))]
         f.field("limits", &self.limits);
@@ -223,12 +305,23 @@ impl Engine {
 
         #[cfg(not(feature = "no_module"))]
         global_sub_modules: std::collections::BTreeMap::new(),
+        #[cfg(not(feature = "no_module"))]
+        lazy_sub_modules: std::collections::BTreeMap::new(),
+        #[cfg(not(feature = "no_module"))]
+        #[cfg(not(feature = "no_function"))]
+        global_constants: std::collections::BTreeMap::new(),
 
         #[cfg(not(feature = "no_module"))]
         module_resolver: None,
 
         interned_strings: None,
+
+        #[cfg(feature = "regex")]
+        regex_cache: None,
+
         disabled_symbols: BTreeSet::new(),
+        disabled_functions: BTreeSet::new(),
+        dispatch_allowlist: None,
         #[cfg(not(feature = "no_custom_syntax"))]
         custom_keywords: std::collections::BTreeMap::new(),
         #[cfg(not(feature = "no_custom_syntax"))]
@@ -236,15 +329,26 @@ impl Engine {
 
         def_var_filter: None,
         resolve_var: None,
+        resolve_var_chain: None,
+        var_write_filter: None,
         token_mapper: None,
 
         print: None,
         debug: None,
+        fn_call_tracer: None,
+        #[cfg(not(feature = "no_time"))]
+        time_fn_calls: false,
+        stmt_tracer: None,
 
         #[cfg(not(feature = "unchecked"))]
         progress: None,
+        #[cfg(not(feature = "unchecked"))]
+        #[cfg(not(feature = "no_std"))]
+        sleep_granularity: std::time::Duration::from_millis(50),
 
         options: LangOptions::new(),
+        language_version: LanguageVersion::LATEST,
+        debug_format: DebugFormat::Compact,
 
         def_tag: Dynamic::UNIT,
 
@@ -252,6 +356,13 @@ impl Engine {
         optimization_level: OptimizationLevel::Simple,
         #[cfg(feature = "no_optimize")]
         optimization_level: (),
+        #[cfg(not(feature = "no_optimize"))]
+        loop_unroll_threshold: 8,
+
+        #[cfg(feature = "decimal")]
+        decimal_rounding: rust_decimal::RoundingStrategy::MidpointNearestEven,
+        #[cfg(feature = "decimal")]
+        decimal_scale: None,
 
         #[cfg(not(feature = "unchecked"))]
         limits: crate::api::limits::Limits::new(),
@@ -277,6 +388,11 @@ impl Engine {
 
         engine.interned_strings = Some(Locked::new(StringsInterner::new()).into());
 
+        #[cfg(feature = "regex")]
+        {
+            engine.regex_cache = Some(Locked::new(crate::types::RegexCache::new()).into());
+        }
+
         // default print/debug implementations
         #[cfg(not(feature = "no_std"))]
         #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
@@ -309,6 +425,128 @@ impl Engine {
         Self::RAW
     }
 
+    /// Create a new [`Engine`] sharing all the registrations of this [`Engine`].
+    ///
+    /// This is much cheaper than creating a new [`Engine`] and registering everything again,
+    /// because registered modules and packages are shared instead of copied.
+    ///
+    /// The following are shared or copied:
+    /// * global modules and packages, including registered functions and custom types
+    /// * static modules (lazy static modules that are not yet built are built first) and their constants
+    /// * custom syntax and custom operators, disabled symbols and disabled functions
+    /// * language options, optimization level, default tag and limits
+    ///
+    /// The following are _not_ copied and must be set again on the new [`Engine`] if needed:
+    /// * callbacks, including [`on_print`][Engine::on_print], [`on_debug`][Engine::on_debug],
+    ///   [`on_progress`][Engine::on_progress], [`on_var`][Engine::on_var] and the debugger interface
+    /// * the module resolver
+    ///
+    /// The new [`Engine`] has its own strings interner and caches (if this [`Engine`] has them).
+    ///
+    /// Under the `sync` feature, this enables creating an [`Engine`] per thread cheaply,
+    /// with the registrations done only once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.register_fn("add", |x: i64, y: i64| x + y);
+    ///
+    /// let engine2 = engine.clone_registrations();
+    ///
+    /// assert_eq!(engine2.eval::<i64>("add(40, 2)")?, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn clone_registrations(&self) -> Self {
+        Self {
+            global_modules: self.global_modules.clone(),
+
+            #[cfg(not(feature = "no_module"))]
+            global_sub_modules: self
+                .global_sub_modules
+                .iter()
+                .map(|(name, m)| (name.clone(), m.clone()))
+                .chain(
+                    self.lazy_sub_modules
+                        .iter()
+                        .map(|(name, m)| (name.clone(), LazyModule::get(m))),
+                )
+                .collect(),
+            #[cfg(not(feature = "no_module"))]
+            lazy_sub_modules: std::collections::BTreeMap::new(),
+            #[cfg(not(feature = "no_module"))]
+            #[cfg(not(feature = "no_function"))]
+            global_constants: self.global_constants.clone(),
+
+            #[cfg(not(feature = "no_module"))]
+            module_resolver: None,
+
+            interned_strings: self
+                .interned_strings
+                .as_ref()
+                .map(|_| Locked::new(StringsInterner::new()).into()),
+
+            #[cfg(feature = "regex")]
+            regex_cache: self
+                .regex_cache
+                .as_ref()
+                .map(|_| Locked::new(crate::types::RegexCache::new()).into()),
+
+            disabled_symbols: self.disabled_symbols.clone(),
+            disabled_functions: self.disabled_functions.clone(),
+            dispatch_allowlist: self.dispatch_allowlist.clone(),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            custom_keywords: self.custom_keywords.clone(),
+            #[cfg(not(feature = "no_custom_syntax"))]
+            custom_syntax: self.custom_syntax.clone(),
+
+            def_var_filter: None,
+            resolve_var: None,
+            resolve_var_chain: None,
+            var_write_filter: None,
+            token_mapper: None,
+
+            print: None,
+            debug: None,
+            fn_call_tracer: None,
+            #[cfg(not(feature = "no_time"))]
+            time_fn_calls: self.time_fn_calls,
+            stmt_tracer: None,
+
+            #[cfg(not(feature = "unchecked"))]
+            progress: None,
+            #[cfg(not(feature = "unchecked"))]
+            #[cfg(not(feature = "no_std"))]
+            sleep_granularity: self.sleep_granularity,
+
+            options: self.options,
+            language_version: self.language_version,
+            debug_format: self.debug_format,
+
+            def_tag: self.def_tag.clone(),
+
+            optimization_level: self.optimization_level,
+            #[cfg(not(feature = "no_optimize"))]
+            loop_unroll_threshold: self.loop_unroll_threshold,
+
+            #[cfg(feature = "decimal")]
+            decimal_rounding: self.decimal_rounding,
+            #[cfg(feature = "decimal")]
+            decimal_scale: self.decimal_scale,
+
+            #[cfg(not(feature = "unchecked"))]
+            limits: self.limits.clone(),
+
+            #[cfg(feature = "debugging")]
+            debugger_interface: None,
+        }
+    }
+
     /// Get an interned [string][ImmutableString].
     #[cfg(not(feature = "internals"))]
     #[inline(always)]
@@ -355,4 +593,78 @@ impl Engine {
     pub(crate) const fn is_debugger_registered(&self) -> bool {
         self.debugger_interface.is_some()
     }
+
+    /// Get a static module registered with this [`Engine`] by name, building it first if it is
+    /// registered via [`register_lazy_static_module`][Engine::register_lazy_static_module] and
+    /// not yet built.
+    #[cfg(not(feature = "no_module"))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn get_global_sub_module(&self, name: &str) -> Option<SharedModule> {
+        self.global_sub_modules
+            .get(name)
+            .cloned()
+            .or_else(|| self.lazy_sub_modules.get(name).map(LazyModule::get))
+    }
+
+    /// Is there a static module registered with this [`Engine`] by name?
+    ///
+    /// Lazy static modules are not built.
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn has_global_sub_module(&self, name: &str) -> bool {
+        self.global_sub_modules.contains_key(name) || self.lazy_sub_modules.contains_key(name)
+    }
+}
+
+/// A static module registered via [`Engine::register_lazy_static_module`].
+#[cfg(not(feature = "no_module"))]
+pub(crate) enum LazyModule {
+    /// The module is built.
+    Ready(SharedModule),
+    /// The module is not yet built.
+    Lazy(Box<crate::func::native::LazyModuleInit>),
+}
+
+#[cfg(not(feature = "no_module"))]
+impl LazyModule {
+    /// Get the module, building (and indexing) it if this is the first access.
+    ///
+    /// Under `sync`, the write lock ensures that the module is built only once even when
+    /// accessed concurrently.
+    #[cold]
+    #[inline(never)]
+    #[must_use]
+    fn get(cell: &Locked<Self>) -> SharedModule {
+        if let Self::Ready(ref m) = *crate::func::locked_read(cell) {
+            return m.clone();
+        }
+
+        let mut value = locked_write(cell);
+
+        // Check again in case the module has been built in the meantime
+        if let Self::Ready(ref m) = *value {
+            return m.clone();
+        }
+
+        let init = match std::mem::replace(&mut *value, Self::Ready(crate::Module::new().into())) {
+            Self::Lazy(init) => init,
+            Self::Ready(..) => unreachable!("`LazyModule::Lazy`"),
+        };
+
+        let module = init();
+
+        // Index the module (making a clone copy if necessary) if it is not indexed
+        let module = if module.is_indexed() {
+            module
+        } else {
+            let mut module = crate::func::shared_take_or_clone(module);
+            module.build_index();
+            module.into()
+        };
+
+        *value = Self::Ready(module.clone());
+        module
+    }
 }
@@ -2,7 +2,8 @@
 
 use crate::func::{CallableFunction, StraightHashMap};
 use crate::types::BloomFilterU64;
-use crate::{ImmutableString, StaticVec};
+use crate::{Dynamic, ImmutableString, StaticVec};
+use std::collections::BTreeMap;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -14,6 +15,8 @@ pub struct FnResolutionCacheEntry {
     pub func: CallableFunction,
     /// Optional source.
     pub source: Option<ImmutableString>,
+    /// Maximum number of results of calls to the function to memoize (zero if not memoized).
+    pub memo_capacity: usize,
 }
 
 /// _(internals)_ A function resolution cache with a bloom filter.
@@ -44,40 +47,99 @@ impl FnResolutionCache {
 ///
 /// The following caches are contained inside this type:
 /// * A stack of [function resolution caches][FnResolutionCache]
+/// * A cache of dynamic dispatch allowlist checks, keyed by the hash of the function name
+/// * A cache of memoized results of native Rust function calls, keyed by the function hash and
+///   the hash of the argument values
 #[derive(Debug, Clone)]
-pub struct Caches(StaticVec<FnResolutionCache>);
+pub struct Caches {
+    /// Stack of function resolution caches.
+    fn_resolution: StaticVec<FnResolutionCache>,
+    /// Results of checking function names against the dynamic dispatch allowlist.
+    dispatch: BTreeMap<u64, bool>,
+    /// Memoized results of native Rust function calls.
+    memo: BTreeMap<u64, StraightHashMap<Dynamic>>,
+}
 
 impl Caches {
     /// Create an empty [`Caches`].
     #[inline(always)]
     #[must_use]
     pub const fn new() -> Self {
-        Self(StaticVec::new_const())
+        Self {
+            fn_resolution: StaticVec::new_const(),
+            dispatch: BTreeMap::new(),
+            memo: BTreeMap::new(),
+        }
     }
     /// Get the number of function resolution cache(s) in the stack.
     #[inline(always)]
     #[must_use]
     pub fn fn_resolution_caches_len(&self) -> usize {
-        self.0.len()
+        self.fn_resolution.len()
     }
     /// Get a mutable reference to the current function resolution cache.
     #[inline]
     #[must_use]
     pub fn fn_resolution_cache_mut(&mut self) -> &mut FnResolutionCache {
         // Push a new function resolution cache if the stack is empty
-        if self.0.is_empty() {
+        if self.fn_resolution.is_empty() {
             self.push_fn_resolution_cache();
         }
-        self.0.last_mut().unwrap()
+        self.fn_resolution.last_mut().unwrap()
     }
     /// Push an empty function resolution cache onto the stack and make it current.
     #[inline(always)]
     pub fn push_fn_resolution_cache(&mut self) {
-        self.0.push(<_>::default());
+        self.fn_resolution.push(<_>::default());
     }
     /// Rewind the function resolution caches stack to a particular size.
     #[inline(always)]
     pub fn rewind_fn_resolution_caches(&mut self, len: usize) {
-        self.0.truncate(len);
+        self.fn_resolution.truncate(len);
+    }
+    /// Get the number of function names in the dynamic dispatch cache.
+    #[inline(always)]
+    #[must_use]
+    #[allow(dead_code)]
+    pub fn dispatch_cache_len(&self) -> usize {
+        self.dispatch.len()
+    }
+    /// Get a mutable reference to the dynamic dispatch cache.
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn dispatch_cache_mut(&mut self) -> &mut BTreeMap<u64, bool> {
+        &mut self.dispatch
+    }
+    /// Get the memoized result of a native Rust function call, if any.
+    #[inline]
+    #[must_use]
+    pub(crate) fn memoized_result(&self, hash: u64, args_hash: u64) -> Option<&Dynamic> {
+        self.memo.get(&hash).and_then(|m| m.get(&args_hash))
+    }
+    /// Is there any memoized result of calls to a native Rust function?
+    #[inline(always)]
+    #[must_use]
+    pub(crate) fn has_memoized_results(&self, hash: u64) -> bool {
+        self.memo.contains_key(&hash)
+    }
+    /// Memoize the result of a native Rust function call.
+    ///
+    /// When the number of memoized results of the function reaches `capacity`, they are all
+    /// discarded first.
+    #[inline]
+    pub(crate) fn memoize_result(
+        &mut self,
+        hash: u64,
+        args_hash: u64,
+        capacity: usize,
+        result: Dynamic,
+    ) {
+        let results = self.memo.entry(hash).or_default();
+
+        if results.len() >= capacity {
+            results.clear();
+        }
+
+        results.insert(args_hash, result);
     }
 }
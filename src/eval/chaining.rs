@@ -4,11 +4,11 @@
 use super::{Caches, GlobalRuntimeState, Target};
 use crate::ast::{ASTFlags, BinaryExpr, Expr, OpAssignment};
 use crate::engine::{FN_IDX_GET, FN_IDX_SET};
-use crate::eval::search_namespace;
+use crate::eval::{search_namespace, search_scope_chain};
 use crate::types::dynamic::Union;
 use crate::{
     calc_fn_hash, Dynamic, Engine, FnArgsVec, OnceCell, Position, RhaiResult, RhaiResultOf, Scope,
-    ERR,
+    StaticVec, VarChainSegment, ERR,
 };
 use std::hash::Hash;
 #[cfg(feature = "no_std")]
@@ -55,6 +55,33 @@ impl From<&Expr> for ChainType {
     }
 }
 
+/// Get the first segment of a dot/index chain, together with the parent node and right-hand side
+/// continuing the chain after it (if any).
+#[inline]
+#[must_use]
+fn split_chain<'a>(parent: &'a Expr, rhs: &'a Expr) -> (&'a Expr, Option<(&'a Expr, &'a Expr)>) {
+    match rhs {
+        Expr::Dot(x, ..) | Expr::Index(x, ..) if !parent.options().contains(ASTFlags::BREAK) => {
+            (&x.lhs, Some((rhs, &x.rhs)))
+        }
+        _ => (rhs, None),
+    }
+}
+
+/// Get a segment of a dot/index chain if it is statically known.
+#[inline]
+#[must_use]
+fn static_chain_segment<'a>(parent: &Expr, segment: &'a Expr) -> Option<VarChainSegment<'a>> {
+    match (segment, ChainType::from(parent)) {
+        #[cfg(not(feature = "no_object"))]
+        (Expr::Property(x, ..), ChainType::Dotting) => Some(VarChainSegment::Property(&x.2)),
+        #[cfg(not(feature = "no_index"))]
+        (_, ChainType::Indexing) => segment.get_literal_value().map(VarChainSegment::Index),
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
 impl Engine {
     /// Call a get indexer.
     #[inline]
@@ -111,6 +138,28 @@ impl Engine {
     ) -> RhaiResultOf<Target<'t>> {
         self.track_operation(global, Position::NONE)?;
 
+        // val_array[range] - ranges of an array are copies, so they are handled by indexers
+        #[cfg(not(feature = "no_index"))]
+        if target.is_array()
+            && (idx.is::<crate::ExclusiveRange>() || idx.is::<crate::InclusiveRange>())
+        {
+            if use_indexers {
+                return self
+                    .call_indexer_get(global, caches, target, idx, op_pos)
+                    .map(Into::into);
+            }
+
+            return Err(ERR::ErrorIndexingType(
+                format!(
+                    "{} [{}]",
+                    self.map_type_name(target.type_name()),
+                    self.map_type_name(idx.type_name())
+                ),
+                op_pos,
+            )
+            .into());
+        }
+
         match target {
             #[cfg(not(feature = "no_index"))]
             Dynamic(Union::Array(arr, ..)) => {
@@ -407,6 +456,61 @@ impl Engine {
                     },
                 )
             }
+            // id.??? or id[???] with a chain-aware variable resolver
+            (Expr::Variable(x, .., var_pos), None)
+                if self.resolve_var_chain.is_some() && x.1.is_empty() =>
+            {
+                self.track_operation(global, *var_pos)?;
+
+                #[cfg(feature = "debugging")]
+                self.run_debugger(global, caches, scope, this_ptr.as_deref_mut(), lhs)?;
+
+                // Collect the statically-known leading segments of the chain
+                let mut segments = StaticVec::new_const();
+                let mut links = StaticVec::new_const();
+                let mut next = Some((expr, rhs));
+
+                while let Some((parent, rhs)) = next {
+                    links.push((parent, rhs));
+
+                    let (segment, rest) = split_chain(parent, rhs);
+
+                    match static_chain_segment(parent, segment) {
+                        Some(segment) => segments.push(segment),
+                        None => break,
+                    }
+
+                    next = rest;
+                }
+
+                let tp = this_ptr.as_deref_mut();
+                let (mut target, consumed) =
+                    search_scope_chain(self, global, caches, scope, tp, lhs, &segments)?;
+
+                // Skip the consumed segments
+                for segment in &segments[..consumed] {
+                    if matches!(segment, VarChainSegment::Index(..)) {
+                        idx_values.pop();
+                    }
+                }
+
+                match links.get(consumed) {
+                    Some(&(parent, rhs)) => self.eval_dot_index_chain_raw(
+                        global,
+                        caches,
+                        scope2,
+                        this_ptr,
+                        lhs,
+                        parent,
+                        &mut target,
+                        rhs,
+                        idx_values,
+                        None,
+                    ),
+                    // The entire chain is consumed
+                    None => Ok((target.take_or_clone(), false)),
+                }
+            }
             // id.??? or id[???]
             (Expr::Variable(.., var_pos), new_val) => {
                 self.track_operation(global, *var_pos)?;
@@ -414,10 +518,11 @@ impl Engine {
                 #[cfg(feature = "debugging")]
                 self.run_debugger(global, caches, scope, this_ptr.as_deref_mut(), lhs)?;
 
-                let target = &mut search_namespace(self, global, caches, scope, this_ptr, lhs)?;
+                let tp = this_ptr.as_deref_mut();
+                let target = &mut search_namespace(self, global, caches, scope, tp, lhs)?;
 
                 self.eval_dot_index_chain_raw(
-                    global, caches, scope2, None, lhs, expr, target, rhs, idx_values, new_val,
+                    global, caches, scope2, this_ptr, lhs, expr, target, rhs, idx_values, new_val,
                 )
             }
             // {expr}.??? = ??? or {expr}[???] = ???
@@ -702,8 +807,13 @@ impl Engine {
                         );
 
                         #[cfg(feature = "debugging")]
-                        let reset =
-                            self.run_debugger_with_reset(global, caches, scope, this_ptr, rhs)?;
+                        let reset = self.run_debugger_with_reset(
+                            global,
+                            caches,
+                            scope,
+                            this_ptr.as_deref_mut(),
+                            rhs,
+                        )?;
                         #[cfg(feature = "debugging")]
                         defer! { global if Some(reset) => move |g| g.debugger_mut().reset_status(reset) }
 
@@ -718,7 +828,8 @@ impl Engine {
                         let arg1_pos = args.get(0).map_or(Position::NONE, Expr::position);
 
                         self.make_method_call(
-                            global, caches, name, *hashes, target, call_args, arg1_pos, *pos,
+                            global, caches, this_ptr, name, *hashes, target, call_args, arg1_pos,
+                            *pos,
                         )
                     }
                     // {xxx:map}.id op= ???
@@ -887,8 +998,11 @@ impl Engine {
                                 let call_args = &mut idx_values[offset..];
                                 let arg1_pos = args.get(0).map_or(Position::NONE, Expr::position);
 
+                                let tp = _this_ptr.as_deref_mut();
+
                                 self.make_method_call(
-                                    global, caches, name, *hashes, target, call_args, arg1_pos, pos,
+                                    global, caches, tp, name, *hashes, target, call_args, arg1_pos,
+                                    pos,
                                 )?
                                 .0
                                 .into()
@@ -1010,8 +1124,11 @@ impl Engine {
                                     let call_args = &mut idx_values[offset..];
                                     let pos1 = args.get(0).map_or(Position::NONE, Expr::position);
 
+                                    let tp = _this_ptr.as_deref_mut();
+
                                     self.make_method_call(
-                                        global, caches, name, *hashes, target, call_args, pos1, pos,
+                                        global, caches, tp, name, *hashes, target, call_args, pos1,
+                                        pos,
                                     )?
                                     .0
                                 };
@@ -0,0 +1,137 @@
+//! Module implementing the collection of statement coverage.
+
+use crate::func::{locked_read, locked_write};
+use crate::{Engine, ImmutableString, Locked, Shared, AST};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{collections::BTreeMap, fmt};
+
+/// Collector of statement coverage, recording the number of times statements on each line of
+/// each script source are evaluated.
+///
+/// The collector is attached to an [`Engine`] via [`attach`][CoverageCollector::attach], which
+/// registers an [`on_statement`][Engine::on_statement] callback. It is cheap to clone, and all
+/// clones share the same records.
+///
+/// Scripts without a source are recorded under an empty source.
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::coverage::CoverageCollector;
+/// use rhai::Engine;
+///
+/// let mut engine = Engine::new();
+/// let coverage = CoverageCollector::new();
+///
+/// coverage.attach(&mut engine);
+///
+/// let ast = engine.compile(
+/// "
+///     let x = 42;
+///     if x > 0 {
+///         x += 1;
+///     } else {
+///         x -= 1;
+///     }
+/// ")?;
+///
+/// engine.run_ast(&ast)?;
+///
+/// assert_eq!(coverage.hits(None, 2), 1);
+/// assert_eq!(coverage.uncovered_lines(&ast), [6]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct CoverageCollector {
+    /// Hit counts per line, per source.
+    hits: Shared<Locked<BTreeMap<ImmutableString, BTreeMap<usize, u64>>>>,
+}
+
+impl fmt::Debug for CoverageCollector {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(locked_read(&self.hits).iter())
+            .finish()
+    }
+}
+
+impl CoverageCollector {
+    /// Create a new [`CoverageCollector`].
+    #[inline(always)]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Attach this [`CoverageCollector`] to an [`Engine`], replacing any callback previously
+    /// registered via [`on_statement`][Engine::on_statement].
+    #[inline]
+    pub fn attach(&self, engine: &mut Engine) -> &Self {
+        let hits = self.hits.clone();
+
+        engine.on_statement(move |source, pos, _| {
+            let Some(line) = pos.line() else {
+                return;
+            };
+            let source = source.unwrap_or("");
+            let hits = &mut *locked_write(&hits);
+
+            let lines = match hits.get_mut(source) {
+                Some(lines) => lines,
+                None => hits.entry(source.into()).or_default(),
+            };
+
+            *lines.entry(line).or_default() += 1;
+        });
+
+        self
+    }
+    /// Get the number of times statements on a line of a script source have been evaluated.
+    #[inline]
+    #[must_use]
+    pub fn hits(&self, source: Option<&str>, line: usize) -> u64 {
+        locked_read(&self.hits)
+            .get(source.unwrap_or(""))
+            .and_then(|lines| lines.get(&line))
+            .copied()
+            .unwrap_or(0)
+    }
+    /// Get the lines of a script source with statements that have been evaluated, in order,
+    /// together with their hit counts.
+    #[inline]
+    #[must_use]
+    pub fn covered_lines(&self, source: Option<&str>) -> Vec<(usize, u64)> {
+        locked_read(&self.hits)
+            .get(source.unwrap_or(""))
+            .map(|lines| lines.iter().map(|(&line, &hits)| (line, hits)).collect())
+            .unwrap_or_default()
+    }
+    /// Get the lines with statements in an [`AST`] (including function bodies) that have never
+    /// been evaluated, in order.
+    ///
+    /// The [`AST`]'s [source][AST::source] is used to match the records.
+    #[must_use]
+    pub fn uncovered_lines(&self, ast: &AST) -> Vec<usize> {
+        let hits = locked_read(&self.hits);
+        let lines = hits.get(ast.source().unwrap_or(""));
+
+        let mut uncovered: Vec<_> = ast
+            .iter_statement_positions()
+            .filter_map(|pos| pos.line())
+            .filter(|line| lines.map_or(true, |lines| !lines.contains_key(line)))
+            .collect();
+
+        uncovered.sort_unstable();
+        uncovered.dedup();
+        uncovered
+    }
+    /// Clear all records.
+    #[inline]
+    pub fn clear(&self) {
+        locked_write(&self.hits).clear();
+    }
+}
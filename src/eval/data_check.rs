@@ -3,7 +3,7 @@
 
 use super::GlobalRuntimeState;
 use crate::types::dynamic::Union;
-use crate::{Dynamic, Engine, Position, RhaiResultOf, ERR};
+use crate::{Dynamic, Engine, LimitInfo, LimitKind, Position, RhaiResultOf, ERR};
 use std::borrow::Borrow;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -126,36 +126,36 @@ impl Engine {
     /// and should be set afterwards.
     #[cfg(not(feature = "unchecked"))]
     pub(crate) fn throw_on_size(&self, (_arr, _map, s): (usize, usize, usize)) -> RhaiResultOf<()> {
-        if self
-            .limits
-            .max_string_len
-            .map_or(false, |max| s > max.get())
-        {
-            return Err(
-                ERR::ErrorDataTooLarge("Length of string".to_string(), Position::NONE).into(),
-            );
+        if let Some(max) = self.limits.max_string_len.filter(|max| s > max.get()) {
+            let info = LimitInfo::new(LimitKind::StringLength, max.get() as u64, s as u64);
+            return Err(ERR::ErrorDataTooLarge(
+                "Length of string".to_string(),
+                info,
+                Position::NONE,
+            )
+            .into());
         }
 
         #[cfg(not(feature = "no_index"))]
-        if self
-            .limits
-            .max_array_size
-            .map_or(false, |max| _arr > max.get())
-        {
-            return Err(
-                ERR::ErrorDataTooLarge("Size of array/BLOB".to_string(), Position::NONE).into(),
-            );
+        if let Some(max) = self.limits.max_array_size.filter(|max| _arr > max.get()) {
+            let info = LimitInfo::new(LimitKind::ArraySize, max.get() as u64, _arr as u64);
+            return Err(ERR::ErrorDataTooLarge(
+                "Size of array/BLOB".to_string(),
+                info,
+                Position::NONE,
+            )
+            .into());
         }
 
         #[cfg(not(feature = "no_object"))]
-        if self
-            .limits
-            .max_map_size
-            .map_or(false, |max| _map > max.get())
-        {
-            return Err(
-                ERR::ErrorDataTooLarge("Size of object map".to_string(), Position::NONE).into(),
-            );
+        if let Some(max) = self.limits.max_map_size.filter(|max| _map > max.get()) {
+            let info = LimitInfo::new(LimitKind::MapSize, max.get() as u64, _map as u64);
+            return Err(ERR::ErrorDataTooLarge(
+                "Size of object map".to_string(),
+                info,
+                Position::NONE,
+            )
+            .into());
         }
 
         Ok(())
@@ -191,6 +191,26 @@ impl Engine {
         self.check_data_size(value, Position::NONE).map(|_| ())
     }
 
+    /// Get the limit on the number of operations (if any) that is exceeded by `num_operations`.
+    #[cfg(not(feature = "unchecked"))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn exceeded_operations_limit(
+        &self,
+        global: &GlobalRuntimeState,
+        num_operations: u64,
+    ) -> Option<u64> {
+        let max = self.max_operations();
+
+        if max > 0 && num_operations > max {
+            Some(max)
+        } else {
+            global
+                .operations_limit
+                .filter(|&limit| num_operations > limit)
+        }
+    }
+
     /// Check if the number of operations stay within limit.
     #[inline(always)]
     pub(crate) fn track_operation(
@@ -198,12 +218,13 @@ impl Engine {
         global: &mut GlobalRuntimeState,
         pos: Position,
     ) -> RhaiResultOf<()> {
-        global.num_operations += 1;
+        global.num_operations += 1 + global.pending_operations.take();
 
         // Guard against too many operations
         #[cfg(not(feature = "unchecked"))]
-        if self.max_operations() > 0 && global.num_operations > self.max_operations() {
-            return Err(ERR::ErrorTooManyOperations(pos).into());
+        if let Some(limit) = self.exceeded_operations_limit(global, global.num_operations) {
+            let info = LimitInfo::new(LimitKind::Operations, limit, global.num_operations);
+            return Err(ERR::ErrorTooManyOperations(info, pos).into());
         }
 
         self.progress
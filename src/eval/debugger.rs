@@ -500,7 +500,17 @@ impl Engine {
     ) -> Result<Option<DebuggerStatus>, Box<crate::EvalAltResult>> {
         match self.debugger_interface {
             Some(ref x) => {
-                let orig_scope_len = scope.len();
+                // Keep the names of all variables so that changes to the scope made by the
+                // debugger callback can be detected
+                let orig_names: Vec<_> = scope.iter_names_raw().cloned().collect();
+                // Keep the shared values of all captured variables
+                #[cfg(not(feature = "no_closure"))]
+                let orig_shared: Vec<_> = scope
+                    .iter_raw()
+                    .enumerate()
+                    .filter(|(.., (.., value))| value.is_shared())
+                    .map(|(index, (.., value))| (index, value.clone()))
+                    .collect();
 
                 let src = global.source_raw().cloned();
                 let context = EvalContext::new(self, global, caches, scope, this_ptr);
@@ -508,11 +518,35 @@ impl Engine {
 
                 let command = on_debugger(context, event, node, src.as_deref(), node.position());
 
-                if orig_scope_len != scope.len() {
+                // Number of variables which are still at the same positions
+                let unchanged = orig_names
+                    .iter()
+                    .zip(scope.iter_names_raw())
+                    .take_while(|(orig, name)| orig == name)
+                    .count();
+
+                if unchanged != orig_names.len() || unchanged != scope.len() {
                     // The scope is changed, always search from now on
                     global.always_search_scope = true;
                 }
 
+                // Write new values of captured variables back into the shared values,
+                // otherwise closures capturing them will not see the changes
+                #[cfg(not(feature = "no_closure"))]
+                for (index, mut shared) in orig_shared {
+                    if index >= unchanged {
+                        break;
+                    }
+
+                    let value = scope.get_mut_by_index(index);
+
+                    if !value.is_shared() {
+                        *shared.write_lock::<Dynamic>().unwrap() =
+                            mem::replace(value, Dynamic::UNIT);
+                        *value = shared;
+                    }
+                }
+
                 match command? {
                     DebuggerCommand::Continue => {
                         global.debugger_mut().status = DebuggerStatus::CONTINUE;
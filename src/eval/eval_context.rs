@@ -1,7 +1,7 @@
 //! Evaluation context.
 
 use super::{Caches, GlobalRuntimeState};
-use crate::{Dynamic, Engine, Scope};
+use crate::{Dynamic, Engine, Position, RhaiResultOf, Scope, ERR};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -148,6 +148,27 @@ impl<'a, 's, 'ps, 'g, 'c, 't> EvalContext<'a, 's, 'ps, 'g, 'c, 't> {
     pub fn this_ptr_mut(&mut self) -> Option<&mut Dynamic> {
         self.this_ptr.as_deref_mut()
     }
+    /// Set the value of the current bound `this` pointer.
+    ///
+    /// If `this` is a variable captured by a closure, the new value is written into the shared value.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorUnboundThis`][ERR::ErrorUnboundThis] if there is no bound `this` pointer, or
+    /// [`ErrorAssignmentToConstant`][ERR::ErrorAssignmentToConstant] if it is a constant.
+    #[inline]
+    pub fn set_this(&mut self, value: impl Into<Dynamic>) -> RhaiResultOf<()> {
+        match self.this_ptr {
+            Some(ref this) if this.is_read_only() => {
+                Err(ERR::ErrorAssignmentToConstant("this".into(), Position::NONE).into())
+            }
+            Some(ref mut this) => {
+                *this.write_lock::<Dynamic>().unwrap() = value.into();
+                Ok(())
+            }
+            None => Err(ERR::ErrorUnboundThis(Position::NONE).into()),
+        }
+    }
     /// The current nesting level of function calls.
     #[inline(always)]
     #[must_use]
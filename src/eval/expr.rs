@@ -4,7 +4,9 @@ use super::{Caches, EvalContext, GlobalRuntimeState, Target};
 use crate::ast::Expr;
 use crate::packages::string_basic::{print_with_func, FUNC_TO_STRING};
 use crate::types::dynamic::AccessMode;
-use crate::{Dynamic, Engine, RhaiResult, RhaiResultOf, Scope, SmartString, ERR};
+use crate::{
+    Dynamic, Engine, Position, RhaiResult, RhaiResultOf, Scope, SmartString, VarChainSegment, ERR,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{fmt::Write, num::NonZeroUsize};
@@ -35,7 +37,7 @@ pub fn search_imports(
 
     // Do a text-match search if the index doesn't work
     global.find_import(root).map_or_else(
-        || engine.global_sub_modules.get(root).cloned(),
+        || engine.get_global_sub_module(root),
         |offset| global.get_shared_import(offset),
     )
 }
@@ -45,14 +47,36 @@ pub fn search_imports(
 /// # Panics
 ///
 /// Panics if `expr` is not [`Expr::Variable`].
+#[inline(always)]
 pub fn search_scope_only<'s>(
     engine: &Engine,
     global: &mut GlobalRuntimeState,
     caches: &mut Caches,
     scope: &'s mut Scope,
-    this_ptr: Option<&'s mut Dynamic>,
+    this_ptr: Option<&mut Dynamic>,
     expr: &Expr,
 ) -> RhaiResultOf<Target<'s>> {
+    search_scope_chain(engine, global, caches, scope, this_ptr, expr, &[]).map(|(v, ..)| v)
+}
+
+/// Search for a variable within the scope, which is the root of a property/index access chain
+/// with the statically-known leading segments in `chain`.
+///
+/// Returns the variable together with the number of leading segments in `chain` already consumed
+/// by the variable resolver.
+///
+/// # Panics
+///
+/// Panics if `expr` is not [`Expr::Variable`].
+pub fn search_scope_chain<'s>(
+    engine: &Engine,
+    global: &mut GlobalRuntimeState,
+    caches: &mut Caches,
+    scope: &'s mut Scope,
+    mut this_ptr: Option<&mut Dynamic>,
+    expr: &Expr,
+    chain: &[VarChainSegment],
+) -> RhaiResultOf<(Target<'s>, usize)> {
     // Make sure that the pointer indirection is taken only when absolutely necessary.
 
     let index = match expr {
@@ -75,10 +99,12 @@ pub fn search_scope_only<'s>(
                     name: v.3.clone(),
                     curry: Vec::new(),
                     environ: None,
+                    native_fn: None,
                     fn_def: Some(fn_def.clone()),
+                    binds_this: false,
                 }
                 .into();
-                return Ok(val.into());
+                return Ok((val.into(), 0));
             }
 
             v.0.map_or(0, NonZeroUsize::get)
@@ -87,6 +113,29 @@ pub fn search_scope_only<'s>(
         _ => unreachable!("Expr::Variable expected but gets {:?}", expr),
     };
 
+    // Check the chain-aware variable resolver, if any
+    if let Some(ref resolve_var) = engine.resolve_var_chain {
+        let orig_scope_len = scope.len();
+
+        let context = EvalContext::new(engine, global, caches, scope, this_ptr.as_deref_mut());
+        let var_name = expr.get_variable_name(true).expect("`Expr::Variable`");
+        let resolved_var = resolve_var(var_name, index, chain, context);
+
+        if orig_scope_len != scope.len() {
+            // The scope is changed, always search from now on
+            global.always_search_scope = true;
+        }
+
+        match resolved_var {
+            Ok(Some((mut result, consumed))) => {
+                result.set_access_mode(AccessMode::ReadOnly);
+                return Ok((result.into(), consumed.min(chain.len())));
+            }
+            Ok(None) => (),
+            Err(err) => return Err(err.fill_position(expr.position())),
+        }
+    }
+
     // Check the variable resolver, if any
     if let Some(ref resolve_var) = engine.resolve_var {
         let orig_scope_len = scope.len();
@@ -103,7 +152,7 @@ pub fn search_scope_only<'s>(
         match resolved_var {
             Ok(Some(mut result)) => {
                 result.set_access_mode(AccessMode::ReadOnly);
-                return Ok(result.into());
+                return Ok((result.into(), 0));
             }
             Ok(None) => (),
             Err(err) => return Err(err.fill_position(expr.position())),
@@ -130,7 +179,7 @@ pub fn search_scope_only<'s>(
                                     .into(),
                             )
                         },
-                        |val| Ok(val.into()),
+                        |val| Ok((val.into(), 0)),
                     )
             }
         }
@@ -138,7 +187,7 @@ pub fn search_scope_only<'s>(
 
     let val = scope.get_mut_by_index(index);
 
-    Ok(val.into())
+    Ok((val.into(), 0))
 }
 
 /// Search for a variable within the scope or within imports,
@@ -148,7 +197,7 @@ pub fn search_namespace<'s>(
     global: &mut GlobalRuntimeState,
     caches: &mut Caches,
     scope: &'s mut Scope,
-    this_ptr: Option<&'s mut Dynamic>,
+    this_ptr: Option<&mut Dynamic>,
     expr: &Expr,
 ) -> RhaiResultOf<Target<'s>> {
     match expr {
@@ -218,6 +267,23 @@ pub fn search_namespace<'s>(
 }
 
 impl Engine {
+    /// Record the position of the expression that produced a value as its provenance.
+    ///
+    /// Values from operators always take the position of the operator expression.
+    /// Other values keep any existing provenance.
+    #[inline]
+    pub(crate) fn record_provenance(
+        &self,
+        mut value: Dynamic,
+        pos: Position,
+        is_operator: bool,
+    ) -> Dynamic {
+        if is_operator || value.provenance().is_none() {
+            value.set_provenance(pos);
+        }
+        value
+    }
+
     /// Evaluate an expression.
     pub(crate) fn eval_expr(
         &self,
@@ -235,7 +301,7 @@ impl Engine {
         #[cfg(feature = "debugging")]
         defer! { global if Some(reset) => move |g| g.debugger_mut().reset_status(reset) }
 
-        match expr {
+        let result = match expr {
             // Constants
             Expr::IntegerConstant(x, ..) => Ok((*x).into()),
             Expr::StringConstant(x, ..) => Ok(x.clone().into()),
@@ -409,6 +475,20 @@ impl Engine {
 
             #[allow(unreachable_patterns)]
             _ => unreachable!("expression cannot be evaluated: {:?}", expr),
+        };
+
+        let result = match result {
+            Err(err) if self.error_spans() => Err(err.fill_span(expr.span())),
+            r => r,
+        };
+
+        if !self.track_provenance() {
+            return result;
         }
+
+        result.map(|value| match expr {
+            Expr::FnCall(x, pos) => self.record_provenance(value, *pos, x.op_token.is_some()),
+            _ => self.record_provenance(value, expr.start_position(), false),
+        })
     }
 }
@@ -29,6 +29,9 @@ pub struct GlobalRuntimeState {
     /// Stack of imported [modules][crate::Module].
     #[cfg(not(feature = "no_module"))]
     modules: Vec<crate::SharedModule>,
+    /// Imported [modules][crate::Module] re-exported via `export` statements, with their aliases.
+    #[cfg(not(feature = "no_module"))]
+    pub(crate) exported_modules: Vec<(ImmutableString, crate::SharedModule)>,
 
     /// The current stack of loaded [modules][crate::Module] containing script-defined functions.
     #[cfg(not(feature = "no_function"))]
@@ -39,11 +42,24 @@ pub struct GlobalRuntimeState {
     pub source: Option<ImmutableString>,
     /// Number of operations performed.
     pub num_operations: u64,
+    /// Number of operations charged by native functions but not yet added to `num_operations`.
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) pending_operations: std::cell::Cell<u64>,
+    /// Maximum value of `num_operations` allowed for a nested call with its own operations budget,
+    /// on top of the limit set on the [`Engine`].
+    #[cfg(not(feature = "unchecked"))]
+    pub(crate) operations_limit: Option<u64>,
     /// Number of modules loaded.
     #[cfg(not(feature = "no_module"))]
     pub num_modules_loaded: usize,
     /// The current nesting level of function calls.
     pub level: usize,
+    /// The current nesting level of calls to closures and anonymous functions made through
+    /// function pointers.
+    ///
+    /// Only counted when [`max_closure_call_levels`][Engine::max_closure_call_levels] is set,
+    /// otherwise such calls are counted in [`level`][GlobalRuntimeState::level].
+    pub closure_level: usize,
     /// Level of the current scope.
     ///
     /// The global (root) level is zero, a new block (or function call) is one level higher, and so on.
@@ -70,6 +86,18 @@ pub struct GlobalRuntimeState {
     pub constants: Option<SharedGlobalConstants>,
     /// Custom state that can be used by the external host.
     pub tag: Dynamic,
+    /// Stack of script-defined functions being called, with the positions of the calls.
+    ///
+    /// Only recorded when [`capture_stack_on_error`][Engine::capture_stack_on_error] is turned on.
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) call_stack: Vec<(ImmutableString, crate::Position)>,
+    /// Source and node ID's of the [`AST`][crate::AST] being evaluated, if any.
+    ///
+    /// Only kept when [`on_statement`][Engine::on_statement] is registered.
+    pub(crate) node_ids: Option<(
+        Option<ImmutableString>,
+        crate::Shared<crate::func::StraightHashMap<u32>>,
+    )>,
     /// Debugging interface.
     #[cfg(feature = "debugging")]
     pub(crate) debugger: Option<Box<super::Debugger>>,
@@ -85,23 +113,35 @@ impl GlobalRuntimeState {
             imports: Vec::new(),
             #[cfg(not(feature = "no_module"))]
             modules: Vec::new(),
+            #[cfg(not(feature = "no_module"))]
+            exported_modules: Vec::new(),
             #[cfg(not(feature = "no_function"))]
             lib: Vec::new(),
             source: None,
             num_operations: 0,
+            #[cfg(not(feature = "unchecked"))]
+            pending_operations: std::cell::Cell::new(0),
+            #[cfg(not(feature = "unchecked"))]
+            operations_limit: None,
             #[cfg(not(feature = "no_module"))]
             num_modules_loaded: 0,
             scope_level: 0,
             level: 0,
+            closure_level: 0,
             always_search_scope: false,
             #[cfg(not(feature = "no_module"))]
             embedded_module_resolver: None,
             #[cfg(not(feature = "no_module"))]
             #[cfg(not(feature = "no_function"))]
-            constants: None,
+            constants: (!engine.global_constants.is_empty())
+                .then(|| crate::Shared::new(crate::Locked::new(engine.global_constants.clone()))),
 
             tag: engine.default_tag().clone(),
 
+            #[cfg(not(feature = "no_function"))]
+            call_stack: Vec::new(),
+            node_ids: None,
+
             #[cfg(feature = "debugging")]
             debugger: engine.debugger_interface.as_ref().map(|x| {
                 let dbg = crate::eval::Debugger::new(crate::eval::DebuggerStatus::Init);
@@ -268,6 +308,19 @@ impl GlobalRuntimeState {
     pub fn source(&self) -> Option<&str> {
         self.source.as_deref()
     }
+    /// Get the node ID of a statement in the [`AST`][crate::AST] being evaluated.
+    ///
+    /// Returns zero if the [`AST`][crate::AST] has no node ID's or the statement is not part of it.
+    #[must_use]
+    pub(crate) fn stmt_node_id(&self, stmt: &crate::ast::Stmt) -> u32 {
+        match self.node_ids {
+            Some((ref source, ref node_ids)) if !node_ids.is_empty() && *source == self.source => {
+                let key = crate::ast::ASTNode::from(stmt).node_key();
+                node_ids.get(&key).copied().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
     /// Get the current source.
     #[inline(always)]
     #[must_use]
@@ -317,6 +370,7 @@ impl fmt::Debug for GlobalRuntimeState {
 
         #[cfg(not(feature = "no_module"))]
         f.field("imports", &self.scan_imports_raw().collect::<Vec<_>>())
+            .field("exported_modules", &self.exported_modules)
             .field("num_modules_loaded", &self.num_modules_loaded)
             .field("embedded_module_resolver", &self.embedded_module_resolver);
 
@@ -326,6 +380,7 @@ impl fmt::Debug for GlobalRuntimeState {
         f.field("source", &self.source)
             .field("num_operations", &self.num_operations)
             .field("level", &self.level)
+            .field("closure_level", &self.closure_level)
             .field("scope_level", &self.scope_level)
             .field("always_search_scope", &self.always_search_scope);
 
@@ -335,6 +390,9 @@ impl fmt::Debug for GlobalRuntimeState {
 
         f.field("tag", &self.tag);
 
+        #[cfg(not(feature = "no_function"))]
+        f.field("call_stack", &self.call_stack);
+
         #[cfg(feature = "debugging")]
         f.field("debugger", &self.debugger);
 
@@ -1,5 +1,6 @@
 mod cache;
 mod chaining;
+mod coverage;
 mod data_check;
 mod debugger;
 mod eval_context;
@@ -11,6 +12,7 @@ mod target;
 #[allow(unused_imports)]
 pub use cache::FnResolutionCache;
 pub use cache::{Caches, FnResolutionCacheEntry};
+pub use coverage::CoverageCollector;
 #[cfg(not(feature = "unchecked"))]
 #[cfg(not(feature = "no_index"))]
 pub use data_check::calc_array_sizes;
@@ -24,7 +26,7 @@ pub use debugger::{
 pub use eval_context::EvalContext;
 #[cfg(not(feature = "no_module"))]
 pub use expr::search_imports;
-pub use expr::search_namespace;
+pub use expr::{search_namespace, search_scope_chain};
 
 pub use global_state::GlobalRuntimeState;
 #[cfg(not(feature = "no_module"))]
@@ -3,13 +3,17 @@
 use super::{Caches, EvalContext, GlobalRuntimeState, Target};
 use crate::ast::{
     ASTFlags, BinaryExpr, ConditionalExpr, Expr, FlowControl, OpAssignment, Stmt,
-    SwitchCasesCollection,
+    SwitchCasesCollection, TryCatchBlock,
 };
+use crate::engine::OP_EQUALS;
 use crate::eval::search_namespace;
+use crate::func::native::OnVarWriteCallback;
 use crate::func::{get_builtin_op_assignment_fn, get_hasher};
 use crate::tokenizer::Token;
 use crate::types::dynamic::{AccessMode, Union};
-use crate::{Dynamic, Engine, RhaiResult, RhaiResultOf, Scope, VarDefInfo, ERR, INT};
+use crate::{
+    calc_fn_hash, Dynamic, Engine, RhaiResult, RhaiResultOf, Scope, VarDefInfo, VarWrite, ERR, INT,
+};
 use std::hash::{Hash, Hasher};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -18,13 +22,42 @@ use std::prelude::v1::*;
 #[inline(always)]
 fn intern_string(value: Dynamic, engine: &Engine) -> Dynamic {
     match value.0 {
-        Union::Str(..) => engine
-            .get_interned_string(value.into_immutable_string().expect("`ImmutableString`"))
-            .into(),
+        Union::Str(_, tag, ..) => {
+            let mut value: Dynamic = engine
+                .get_interned_string(value.into_immutable_string().expect("`ImmutableString`"))
+                .into();
+            value.set_tag(tag);
+            value
+        }
         _ => value,
     }
 }
 
+/// Get the number of elements in a value if it is an array.
+#[inline(always)]
+#[must_use]
+fn array_len(value: &Dynamic) -> Option<usize> {
+    #[cfg(not(feature = "no_index"))]
+    return value.read_lock::<crate::Array>().map(|arr| arr.len());
+    #[cfg(feature = "no_index")]
+    return None;
+}
+
+/// Destructure an array into the values of up to three `for` loop variables, padding with `()`.
+fn destructure(value: Dynamic) -> [Dynamic; 3] {
+    let mut values = [Dynamic::UNIT, Dynamic::UNIT, Dynamic::UNIT];
+
+    #[cfg(not(feature = "no_index"))]
+    values
+        .iter_mut()
+        .zip(value.cast::<crate::Array>())
+        .for_each(|(v, x)| *v = x.flatten());
+    #[cfg(feature = "no_index")]
+    let _ = value;
+
+    values
+}
+
 impl Engine {
     /// Evaluate a statements block.
     pub(crate) fn eval_stmt_block(
@@ -108,6 +141,103 @@ impl Engine {
         })
     }
 
+    /// Find the iterator function registered for a type.
+    #[must_use]
+    pub(crate) fn get_iter_fn<'a>(
+        &'a self,
+        _global: &'a GlobalRuntimeState,
+        iter_type: std::any::TypeId,
+    ) -> Option<&'a crate::func::IteratorFn> {
+        // lib should only contain scripts, so technically they cannot have iterators
+
+        // Search order:
+        // 1) Global namespace - functions registered via Engine::register_XXX
+        // 2) Global modules - packages
+        // 3) Imported modules - functions marked with global namespace
+        // 4) Global sub-modules - functions marked with global namespace
+        let iter_func = self
+            .global_modules
+            .iter()
+            .find_map(|m| m.get_iter(iter_type));
+
+        #[cfg(not(feature = "no_module"))]
+        let iter_func = iter_func
+            .or_else(|| _global.get_iter(iter_type))
+            .or_else(|| {
+                self.global_sub_modules
+                    .values()
+                    .find_map(|m| m.get_qualified_iter(iter_type))
+            });
+
+        iter_func
+    }
+
+    /// Run the callback registered via [`on_var_write`][Engine::on_var_write], if any, before
+    /// assigning to a variable, `this`, or a property/indexed element of either.
+    #[inline(always)]
+    fn run_var_write_filter(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        op_info: &OpAssignment,
+        lhs: &Expr,
+        new_val: &Dynamic,
+    ) -> RhaiResultOf<()> {
+        match self.var_write_filter {
+            Some(ref filter) => self.run_var_write_filter_raw(
+                filter, global, caches, scope, this_ptr, op_info, lhs, new_val,
+            ),
+            None => Ok(()),
+        }
+    }
+    /// Run the callback registered via [`on_var_write`][Engine::on_var_write].
+    #[inline(never)]
+    fn run_var_write_filter_raw(
+        &self,
+        filter: &OnVarWriteCallback,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        op_info: &OpAssignment,
+        lhs: &Expr,
+        new_val: &Dynamic,
+    ) -> RhaiResultOf<()> {
+        // Find the root of any property/index access chain
+        let mut root = lhs;
+        let mut is_member = false;
+
+        while let Expr::Index(x, ..) | Expr::Dot(x, ..) = root {
+            root = &x.lhs;
+            is_member = true;
+        }
+
+        let name = match root {
+            Expr::ThisPtr(..) => "this",
+            _ => match root.get_variable_name(false) {
+                Some(name) => name,
+                None => return Ok(()),
+            },
+        };
+
+        let op = op_info
+            .get_op_assignment_info()
+            .map(|(.., op_assign, _, _)| op_assign);
+
+        let write = match op {
+            _ if is_member => VarWrite::Member(op.unwrap_or("="), new_val),
+            Some(op) => VarWrite::OpAssign(op, new_val),
+            None => VarWrite::Assign(new_val),
+        };
+
+        let pos = root.position();
+        let context = EvalContext::new(self, global, caches, scope, this_ptr);
+
+        filter(name, write, pos, context).map_err(|err| err.fill_position(pos))
+    }
+
     /// Evaluate an op-assignment statement.
     pub(crate) fn eval_op_assignment(
         &self,
@@ -172,13 +302,39 @@ impl Engine {
                             _ => done = false,
                         }
                         #[cfg(feature = "unchecked")]
-                        match op_x {
-                            PlusAssign => *n1 += *n2,
-                            MinusAssign => *n1 -= *n2,
-                            MultiplyAssign => *n1 *= *n2,
-                            DivideAssign => *n1 /= *n2,
-                            ModuloAssign => *n1 %= *n2,
-                            _ => done = false,
+                        if self.strict_arithmetic() {
+                            #[allow(clippy::wildcard_imports)]
+                            use crate::packages::arithmetic::strict_int::*;
+
+                            match op_x {
+                                PlusAssign => {
+                                    *n1 = add(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                }
+                                MinusAssign => {
+                                    *n1 =
+                                        subtract(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                }
+                                MultiplyAssign => {
+                                    *n1 =
+                                        multiply(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                }
+                                DivideAssign => {
+                                    *n1 = divide(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                }
+                                ModuloAssign => {
+                                    *n1 = modulo(*n1, *n2).map_err(|err| err.fill_position(pos))?
+                                }
+                                _ => done = false,
+                            }
+                        } else {
+                            match op_x {
+                                PlusAssign => *n1 += *n2,
+                                MinusAssign => *n1 -= *n2,
+                                MultiplyAssign => *n1 *= *n2,
+                                DivideAssign => *n1 /= *n2,
+                                ModuloAssign => *n1 %= *n2,
+                                _ => done = false,
+                            }
                         }
                     }
                     #[cfg(not(feature = "no_float"))]
@@ -203,9 +359,12 @@ impl Engine {
                 }
 
                 if !done {
-                    if let Some((func, need_context)) =
-                        get_builtin_op_assignment_fn(op_x, &lock_guard, &new_val)
-                    {
+                    if let Some((func, need_context)) = get_builtin_op_assignment_fn(
+                        op_x,
+                        &lock_guard,
+                        &new_val,
+                        self.strict_arithmetic(),
+                    ) {
                         // We may not need to bump the level because built-in's do not need it.
                         //defer! { let orig_level = global.level; global.level += 1 }
 
@@ -240,6 +399,10 @@ impl Engine {
 
                 self.check_data_size(&*args[0], root.position())?;
             }
+
+            if self.track_provenance() {
+                lock_guard.set_provenance(pos);
+            }
         } else {
             // Normal assignment
             match target {
@@ -267,6 +430,10 @@ impl Engine {
     ) -> RhaiResult {
         self.track_operation(global, stmt.position())?;
 
+        if let Some(ref tracer) = self.stmt_tracer {
+            tracer(global.source(), stmt.position(), global.stmt_node_id(stmt));
+        }
+
         #[cfg(feature = "debugging")]
         let reset =
             self.run_debugger_with_reset(global, caches, scope, this_ptr.as_deref_mut(), stmt)?;
@@ -293,7 +460,17 @@ impl Engine {
 
             // Function call
             Stmt::FnCall(x, pos) => {
-                self.eval_fn_call_expr(global, caches, scope, this_ptr, x, *pos)
+                let result = match self.eval_fn_call_expr(global, caches, scope, this_ptr, x, *pos)
+                {
+                    Err(err) if self.error_spans() => Err(err.fill_span(stmt.span())),
+                    r => r,
+                };
+
+                if self.track_provenance() {
+                    result.map(|v| self.record_provenance(v, *pos, x.op_token.is_some()))
+                } else {
+                    result
+                }
             }
 
             // Assignment
@@ -313,6 +490,16 @@ impl Engine {
 
                         self.track_operation(global, lhs.position())?;
 
+                        self.run_var_write_filter(
+                            global,
+                            caches,
+                            scope,
+                            this_ptr.as_deref_mut(),
+                            op_info,
+                            lhs,
+                            &rhs_val,
+                        )?;
+
                         let target = &mut this_ptr.unwrap().into();
 
                         self.eval_op_assignment(global, caches, op_info, lhs, target, rhs_val)?;
@@ -326,6 +513,16 @@ impl Engine {
 
                     self.track_operation(global, lhs.position())?;
 
+                    self.run_var_write_filter(
+                        global,
+                        caches,
+                        scope,
+                        this_ptr.as_deref_mut(),
+                        op_info,
+                        lhs,
+                        &rhs_val,
+                    )?;
+
                     let mut target = search_namespace(self, global, caches, scope, this_ptr, lhs)?;
 
                     let is_temp_result = !target.is_ref();
@@ -351,6 +548,17 @@ impl Engine {
                         let rhs_val = self
                             .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), rhs)?
                             .flatten();
+
+                        self.run_var_write_filter(
+                            global,
+                            caches,
+                            scope,
+                            this_ptr.as_deref_mut(),
+                            op_info,
+                            lhs,
+                            &rhs_val,
+                        )?;
+
                         let _new_val = Some((intern_string(rhs_val, self), op_info));
 
                         // Must be either `var[index] op= val` or `var.prop op= val`.
@@ -511,6 +719,8 @@ impl Engine {
                         cases,
                         def_case,
                         ranges,
+                        literals,
+                        ..
                     },
                 ) = &**x;
 
@@ -566,6 +776,51 @@ impl Engine {
                             }
                         }
                     }
+                } else if !literals.is_empty() {
+                    // Compare values that are not hashable (e.g. custom types) with each literal
+                    let hash = calc_fn_hash(None, OP_EQUALS, 2);
+                    let mut value = value.flatten();
+
+                    for (literal, index) in literals {
+                        let literal = &mut literal.clone();
+                        let args = &mut [&mut value, literal];
+                        let pos = expr.position();
+
+                        let is_equal = match self.exec_native_fn_call(
+                            global, caches, OP_EQUALS, None, hash, args, true, pos,
+                        ) {
+                            Ok((r, ..)) => r.as_bool().unwrap_or(false),
+                            Err(err) => match *err {
+                                ERR::ErrorFunctionNotFound(ref fn_sig, ..)
+                                    if fn_sig.starts_with(OP_EQUALS) =>
+                                {
+                                    false
+                                }
+                                _ => return Err(err),
+                            },
+                        };
+
+                        if !is_equal {
+                            continue;
+                        }
+
+                        let ConditionalExpr { condition, expr } = &expressions[*index];
+
+                        let cond_result = match condition {
+                            Expr::BoolConstant(b, ..) => *b,
+                            c => self
+                                .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), c)?
+                                .as_bool()
+                                .map_err(|typ| {
+                                    self.make_type_mismatch_err::<bool>(typ, c.position())
+                                })?,
+                        };
+
+                        if cond_result {
+                            result = Some(expr);
+                            break;
+                        }
+                    }
                 }
 
                 result
@@ -668,12 +923,16 @@ impl Engine {
             }
 
             // For loop
-            Stmt::For(x, ..) => {
-                let (var_name, counter, FlowControl { expr, body, .. }) = &**x;
+            Stmt::For(x, pos) => {
+                let (var_name, var2, var3, FlowControl { expr, body, .. }) = &**x;
 
                 // Guard against too many variables
                 #[cfg(not(feature = "unchecked"))]
-                if scope.len() >= self.max_variables() - usize::from(counter.is_some()) {
+                if scope.len()
+                    >= self.max_variables()
+                        - usize::from(var2.is_some())
+                        - usize::from(var3.is_some())
+                {
                     return Err(ERR::ErrorTooManyVariables(var_name.pos).into());
                 }
 
@@ -681,37 +940,20 @@ impl Engine {
                     .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
                     .flatten();
 
-                let iter_type = iter_obj.type_id();
-
-                // lib should only contain scripts, so technically they cannot have iterators
-
-                // Search order:
-                // 1) Global namespace - functions registered via Engine::register_XXX
-                // 2) Global modules - packages
-                // 3) Imported modules - functions marked with global namespace
-                // 4) Global sub-modules - functions marked with global namespace
                 let iter_func = self
-                    .global_modules
-                    .iter()
-                    .find_map(|m| m.get_iter(iter_type));
-
-                #[cfg(not(feature = "no_module"))]
-                let iter_func = iter_func
-                    .or_else(|| global.get_iter(iter_type))
-                    .or_else(|| {
-                        self.global_sub_modules
-                            .values()
-                            .find_map(|m| m.get_qualified_iter(iter_type))
-                    });
-
-                let iter_func = iter_func.ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
+                    .get_iter_fn(global, iter_obj.type_id())
+                    .ok_or_else(|| ERR::ErrorFor(expr.start_position()))?;
 
                 // Restore scope at end of statement
                 defer! { scope => rewind; let orig_scope_len = scope.len(); }
 
                 // Add the loop variables
-                let counter_index = counter.as_ref().map(|counter| {
-                    scope.push(counter.name.clone(), 0 as INT);
+                let index3 = var3.as_ref().map(|var| {
+                    scope.push(var.name.clone(), ());
+                    scope.len() - 1
+                });
+                let index2 = var2.as_ref().map(|var| {
+                    scope.push(var.name.clone(), ());
                     scope.len() - 1
                 });
 
@@ -720,14 +962,60 @@ impl Engine {
 
                 let mut result = Dynamic::UNIT;
 
-                if body.is_empty() {
-                    for _ in iter_func(iter_obj) {
+                // Items must still be destructured into three variables to check their shapes
+                if body.is_empty() && var3.is_none() {
+                    for iter_value in iter_func(iter_obj) {
                         self.track_operation(global, body.position())?;
+
+                        // Errors from a fallible iterator abort the loop
+                        if let Err(err) = iter_value {
+                            return Err(err.fill_position(*pos));
+                        }
                     }
                 } else {
                     for (x, iter_value) in iter_func(iter_obj).enumerate() {
-                        // Increment counter
-                        if let Some(counter_index) = counter_index {
+                        let value = iter_value.map_err(|err| err.fill_position(*pos))?.flatten();
+
+                        // Destructure the value into the loop variables
+                        let (values, counter) = match (index2, index3, array_len(&value)) {
+                            (None, ..) => ([value, Dynamic::UNIT, Dynamic::UNIT], None),
+                            (Some(..), None, Some(2)) => (destructure(value), None),
+                            (Some(index2), None, ..) => (
+                                [value, Dynamic::UNIT, Dynamic::UNIT],
+                                Some((index2, var2.as_ref().unwrap().pos)),
+                            ),
+                            (Some(..), Some(index3), Some(2)) => (
+                                destructure(value),
+                                Some((index3, var3.as_ref().unwrap().pos)),
+                            ),
+                            (Some(..), Some(..), Some(3)) => (destructure(value), None),
+                            (Some(..), Some(..), len) => {
+                                let typ = match len {
+                                    Some(len) => format!("an array of {len} elements"),
+                                    None => self.map_type_name(value.type_name()).into(),
+                                };
+                                return Err(ERR::ErrorMismatchDataType(
+                                    "an array of 2 or 3 elements".into(),
+                                    typ,
+                                    var_name.pos,
+                                )
+                                .into());
+                            }
+                        };
+
+                        // Set loop values
+                        let [value, value2, value3] = values;
+
+                        *scope.get_mut_by_index(index).write_lock().unwrap() = value;
+                        if let Some(index2) = index2 {
+                            *scope.get_mut_by_index(index2).write_lock().unwrap() = value2;
+                        }
+                        if let Some(index3) = index3 {
+                            *scope.get_mut_by_index(index3).write_lock().unwrap() = value3;
+                        }
+
+                        // Set counter
+                        if let Some((counter_index, counter_pos)) = counter {
                             // As the variable increments from 0, this should always work
                             // since any overflow will first be caught below.
                             let index_value = x as INT;
@@ -737,7 +1025,7 @@ impl Engine {
                             if index_value > crate::MAX_USIZE_INT {
                                 return Err(ERR::ErrorArithmetic(
                                     format!("for-loop counter overflow: {x}"),
-                                    counter.as_ref().unwrap().pos,
+                                    counter_pos,
                                 )
                                 .into());
                             }
@@ -746,13 +1034,6 @@ impl Engine {
                                 Dynamic::from_int(index_value);
                         }
 
-                        // Set loop value
-                        let value = iter_value
-                            .map_err(|err| err.fill_position(expr.position()))?
-                            .flatten();
-
-                        *scope.get_mut_by_index(index).write_lock().unwrap() = value;
-
                         // Run block
                         let this_ptr = this_ptr.as_deref_mut();
                         let statements = body.statements();
@@ -790,10 +1071,11 @@ impl Engine {
 
             // Try/Catch statement
             Stmt::TryCatch(x, ..) => {
-                let FlowControl {
-                    body,
-                    expr: catch_var,
-                    branch,
+                let TryCatchBlock {
+                    try_block,
+                    catch_var,
+                    guard,
+                    catch_block,
                 } = &**x;
 
                 match self.eval_stmt_block(
@@ -801,13 +1083,15 @@ impl Engine {
                     caches,
                     scope,
                     this_ptr.as_deref_mut(),
-                    body.statements(),
+                    try_block.statements(),
                     true,
                 ) {
                     r @ Ok(_) => r,
                     Err(err) if err.is_pseudo_error() => Err(err),
                     Err(err) if !err.is_catchable() => Err(err),
                     Err(mut err) => {
+                        let orig_pos = err.position();
+
                         let err_value = match err.unwrap_inner() {
                             // No error variable
                             _ if catch_var.is_unit() => Dynamic::UNIT,
@@ -858,8 +1142,23 @@ impl Engine {
                             scope.push(x.3.clone(), err_value);
                         }
 
+                        // Check the guard condition - re-throw the original error if it fails
+                        if !matches!(guard, Expr::BoolConstant(true, ..)) {
+                            let matched = self
+                                .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), guard)?
+                                .as_bool()
+                                .map_err(|typ| {
+                                    self.make_type_mismatch_err::<bool>(typ, guard.position())
+                                })?;
+
+                            if !matched {
+                                err.set_position(orig_pos);
+                                return Err(err);
+                            }
+                        }
+
                         let this_ptr = this_ptr.as_deref_mut();
-                        let statements = branch.statements();
+                        let statements = catch_block.statements();
 
                         self.eval_stmt_block(global, caches, scope, this_ptr, statements, true)
                             .map(|_| Dynamic::UNIT)
@@ -962,15 +1261,24 @@ impl Engine {
             Stmt::Export(x, ..) => {
                 use crate::ast::Ident;
                 let (Ident { name, pos, .. }, Ident { name: alias, .. }) = &**x;
+                let alias = if alias.is_empty() { name } else { alias };
+
                 // Mark scope variables as public
-                scope.search(name).map_or_else(
-                    || Err(ERR::ErrorVariableNotFound(name.to_string(), *pos).into()),
-                    |index| {
-                        let alias = if alias.is_empty() { name } else { alias };
-                        scope.add_alias_by_index(index, alias.clone());
-                        Ok(Dynamic::UNIT)
-                    },
-                )
+                if let Some(index) = scope.search(name) {
+                    scope.add_alias_by_index(index, alias.clone());
+                    return Ok(Dynamic::UNIT);
+                }
+
+                // Re-export imported modules
+                if let Some(module) = global
+                    .find_import(name)
+                    .and_then(|index| global.get_shared_import(index))
+                {
+                    global.exported_modules.push((alias.clone(), module));
+                    return Ok(Dynamic::UNIT);
+                }
+
+                Err(ERR::ErrorVariableNotFound(name.to_string(), *pos).into())
             }
 
             // Share statement
@@ -39,6 +39,17 @@ fn const_true_fn(_: Option<NativeCallContext>, _: &mut [&mut Dynamic]) -> RhaiRe
 fn const_false_fn(_: Option<NativeCallContext>, _: &mut [&mut Dynamic]) -> RhaiResult {
     Ok(Dynamic::FALSE)
 }
+/// Round a [`Decimal`] result to the fixed scale set in the [`Engine`][crate::Engine], if any.
+#[cfg(feature = "decimal")]
+#[inline]
+fn scale_decimal(ctx: Option<&NativeCallContext>, value: Decimal) -> Decimal {
+    match ctx.map(NativeCallContext::engine) {
+        Some(engine) => engine.decimal_scale.map_or(value, |scale| {
+            value.round_dp_with_strategy(scale, engine.decimal_rounding)
+        }),
+        None => value,
+    }
+}
 /// Returns true if the type is numeric.
 #[inline(always)]
 fn is_numeric(typ: TypeId) -> bool {
@@ -56,6 +67,11 @@ fn is_numeric(typ: TypeId) -> bool {
         return true;
     }
 
+    #[cfg(feature = "bigint")]
+    if typ == TypeId::of::<num_bigint::BigInt>() {
+        return true;
+    }
+
     #[cfg(not(feature = "only_i32"))]
     #[cfg(not(feature = "only_i64"))]
     if typ == TypeId::of::<u8>()
@@ -83,12 +99,35 @@ fn is_numeric(typ: TypeId) -> bool {
 /// Build in common binary operator implementations to avoid the cost of calling a registered function.
 ///
 /// The return function will be registered as a _method_, so the first parameter cannot be consumed.
+///
+/// If `strict` is `true`, integer arithmetic is always checked, even under `unchecked`.
 #[must_use]
-pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<FnBuiltin> {
+#[cfg_attr(not(feature = "unchecked"), allow(unused_variables))]
+pub fn get_builtin_binary_op_fn(
+    op: &Token,
+    x: &Dynamic,
+    y: &Dynamic,
+    strict: bool,
+) -> Option<FnBuiltin> {
     let type1 = x.type_id();
     let type2 = y.type_id();
 
     macro_rules! impl_op {
+        (decimal => $xx:ident $op:tt $yy:ident) => { Some((|ctx, args| {
+            let x = Decimal::from(args[0].$xx().unwrap());
+            let y = Decimal::from(args[1].$yy().unwrap());
+            Ok(scale_decimal(ctx.as_ref(), x $op y).into())
+        }, true)) };
+        (decimal => $xx:ident . $func:ident ( $yy:ident )) => { Some((|ctx, args| {
+            let x = Decimal::from(args[0].$xx().unwrap());
+            let y = Decimal::from(args[1].$yy().unwrap());
+            Ok(scale_decimal(ctx.as_ref(), x.$func(y)).into())
+        }, true)) };
+        (decimal => $func:ident ( $xx:ident, $yy:ident )) => { Some((|ctx, args| {
+            let x = Decimal::from(args[0].$xx().unwrap());
+            let y = Decimal::from(args[1].$yy().unwrap());
+            $func(x, y).map(|v| scale_decimal(ctx.as_ref(), v).into())
+        }, true)) };
         ($xx:ident $op:tt $yy:ident) => { Some((|_, args| {
             let x = &*args[0].read_lock::<$xx>().unwrap();
             let y = &*args[1].read_lock::<$yy>().unwrap();
@@ -170,6 +209,22 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
                 _ => (),
             }
 
+            #[cfg(feature = "unchecked")]
+            if strict {
+                #[allow(clippy::wildcard_imports)]
+                use crate::packages::arithmetic::strict_int::*;
+
+                match op {
+                    Plus => return impl_op!(INT => add(as_int, as_int)),
+                    Minus => return impl_op!(INT => subtract(as_int, as_int)),
+                    Multiply => return impl_op!(INT => multiply(as_int, as_int)),
+                    Divide => return impl_op!(INT => divide(as_int, as_int)),
+                    Modulo => return impl_op!(INT => modulo(as_int, as_int)),
+                    PowerOf => return impl_op!(INT => power(as_int, as_int)),
+                    _ => (),
+                }
+            }
+
             #[cfg(feature = "unchecked")]
             match op {
                 Plus => return impl_op!(INT => as_int + as_int),
@@ -382,6 +437,8 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
                     GreaterThanEqualsTo => impl_op!(FLOAT => $xx >= $yy),
                     LessThan            => impl_op!(FLOAT => $xx < $yy),
                     LessThanEqualsTo    => impl_op!(FLOAT => $xx <= $yy),
+                    ExclusiveRange      => impl_op!(FLOAT => $xx .. $yy),
+                    InclusiveRange      => impl_op!(FLOAT => $xx ..= $yy),
                     _                   => None,
                 };
             }
@@ -405,12 +462,12 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
 
                 #[cfg(not(feature = "unchecked"))]
                 match op {
-                    Plus     => return impl_op!(from Decimal => add($xx, $yy)),
-                    Minus    => return impl_op!(from Decimal => subtract($xx, $yy)),
-                    Multiply => return impl_op!(from Decimal => multiply($xx, $yy)),
-                    Divide   => return impl_op!(from Decimal => divide($xx, $yy)),
-                    Modulo   => return impl_op!(from Decimal => modulo($xx, $yy)),
-                    PowerOf  => return impl_op!(from Decimal => power($xx, $yy)),
+                    Plus     => return impl_op!(decimal => add($xx, $yy)),
+                    Minus    => return impl_op!(decimal => subtract($xx, $yy)),
+                    Multiply => return impl_op!(decimal => multiply($xx, $yy)),
+                    Divide   => return impl_op!(decimal => divide($xx, $yy)),
+                    Modulo   => return impl_op!(decimal => modulo($xx, $yy)),
+                    PowerOf  => return impl_op!(decimal => power($xx, $yy)),
                     _        => ()
                 }
 
@@ -419,12 +476,12 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
 
                 #[cfg(feature = "unchecked")]
                 match op {
-                    Plus     => return impl_op!(from Decimal => $xx + $yy),
-                    Minus    => return impl_op!(from Decimal => $xx - $yy),
-                    Multiply => return impl_op!(from Decimal => $xx * $yy),
-                    Divide   => return impl_op!(from Decimal => $xx / $yy),
-                    Modulo   => return impl_op!(from Decimal => $xx % $yy),
-                    PowerOf  => return impl_op!(from Decimal => $xx.powd($yy)),
+                    Plus     => return impl_op!(decimal => $xx + $yy),
+                    Minus    => return impl_op!(decimal => $xx - $yy),
+                    Multiply => return impl_op!(decimal => $xx * $yy),
+                    Divide   => return impl_op!(decimal => $xx / $yy),
+                    Modulo   => return impl_op!(decimal => $xx % $yy),
+                    PowerOf  => return impl_op!(decimal => $xx.powd($yy)),
                     _        => ()
                 }
 
@@ -617,12 +674,36 @@ pub fn get_builtin_binary_op_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<
 /// Build in common operator assignment implementations to avoid the cost of calling a registered function.
 ///
 /// The return function is registered as a _method_, so the first parameter cannot be consumed.
+///
+/// If `strict` is `true`, integer arithmetic is always checked, even under `unchecked`.
 #[must_use]
-pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Option<FnBuiltin> {
+#[cfg_attr(not(feature = "unchecked"), allow(unused_variables))]
+pub fn get_builtin_op_assignment_fn(
+    op: &Token,
+    x: &Dynamic,
+    y: &Dynamic,
+    strict: bool,
+) -> Option<FnBuiltin> {
     let type1 = x.type_id();
     let type2 = y.type_id();
 
     macro_rules! impl_op {
+        (decimal $x:ident $op:tt $yy:ident) => { Some((|ctx, args| {
+            let y = <$x>::from(args[1].$yy().unwrap());
+            let x = &mut *args[0].write_lock::<$x>().unwrap();
+            *x $op y;
+            Ok((*x = scale_decimal(ctx.as_ref(), *x)).into())
+        }, true)) };
+        (decimal $x:ty => $xx:ident . $func:ident ( $yy:ident )) => { Some((|ctx, args| {
+            let x = args[0].$xx().unwrap();
+            let y = <$x>::from(args[1].$yy().unwrap());
+            Ok((*args[0].write_lock::<$x>().unwrap() = scale_decimal(ctx.as_ref(), x.$func(y))).into())
+        }, true)) };
+        (decimal $x:ty => $func:ident ( $xx:ident, $yy:ident )) => { Some((|ctx, args| {
+            let x = args[0].$xx().unwrap();
+            let y = <$x>::from(args[1].$yy().unwrap());
+            Ok((*args[0].write_lock().unwrap() = scale_decimal(ctx.as_ref(), $func(x, y)?)).into())
+        }, true)) };
         ($x:ty = x $op:tt $yy:ident) => { Some((|_, args| {
             let x = args[0].$yy().unwrap();
             let y = args[1].$yy().unwrap() as $x;
@@ -693,6 +774,22 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
                 _ => (),
             }
 
+            #[cfg(feature = "unchecked")]
+            if strict {
+                #[allow(clippy::wildcard_imports)]
+                use crate::packages::arithmetic::strict_int::*;
+
+                match op {
+                    PlusAssign => return impl_op!(INT => add(as_int, as_int)),
+                    MinusAssign => return impl_op!(INT => subtract(as_int, as_int)),
+                    MultiplyAssign => return impl_op!(INT => multiply(as_int, as_int)),
+                    DivideAssign => return impl_op!(INT => divide(as_int, as_int)),
+                    ModuloAssign => return impl_op!(INT => modulo(as_int, as_int)),
+                    PowerOfAssign => return impl_op!(INT => power(as_int, as_int)),
+                    _ => (),
+                }
+            }
+
             #[cfg(feature = "unchecked")]
             match op {
                 PlusAssign => return impl_op!(INT += as_int),
@@ -896,12 +993,12 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
 
                 #[cfg(not(feature = "unchecked"))]
                 return match op {
-                    PlusAssign      => impl_op!(from $x => add($xx, $yy)),
-                    MinusAssign     => impl_op!(from $x => subtract($xx, $yy)),
-                    MultiplyAssign  => impl_op!(from $x => multiply($xx, $yy)),
-                    DivideAssign    => impl_op!(from $x => divide($xx, $yy)),
-                    ModuloAssign    => impl_op!(from $x => modulo($xx, $yy)),
-                    PowerOfAssign   => impl_op!(from $x => power($xx, $yy)),
+                    PlusAssign      => impl_op!(decimal $x => add($xx, $yy)),
+                    MinusAssign     => impl_op!(decimal $x => subtract($xx, $yy)),
+                    MultiplyAssign  => impl_op!(decimal $x => multiply($xx, $yy)),
+                    DivideAssign    => impl_op!(decimal $x => divide($xx, $yy)),
+                    ModuloAssign    => impl_op!(decimal $x => modulo($xx, $yy)),
+                    PowerOfAssign   => impl_op!(decimal $x => power($xx, $yy)),
                     _               => None,
                 };
 
@@ -910,12 +1007,12 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
 
                 #[cfg(feature = "unchecked")]
                 return match op {
-                    PlusAssign      => impl_op!(from $x += $yy),
-                    MinusAssign     => impl_op!(from $x -= $yy),
-                    MultiplyAssign  => impl_op!(from $x *= $yy),
-                    DivideAssign    => impl_op!(from $x /= $yy),
-                    ModuloAssign    => impl_op!(from $x %= $yy),
-                    PowerOfAssign   => impl_op!(from $x => $xx.powd($yy)),
+                    PlusAssign      => impl_op!(decimal $x += $yy),
+                    MinusAssign     => impl_op!(decimal $x -= $yy),
+                    MultiplyAssign  => impl_op!(decimal $x *= $yy),
+                    DivideAssign    => impl_op!(decimal $x /= $yy),
+                    ModuloAssign    => impl_op!(decimal $x %= $yy),
+                    PowerOfAssign   => impl_op!(decimal $x => $xx.powd($yy)),
                     _               => None,
                 };
             }
@@ -952,6 +1049,31 @@ pub fn get_builtin_op_assignment_fn(op: &Token, x: &Dynamic, y: &Dynamic) -> Opt
             _ => None,
         };
     }
+    // string op= int
+    if (type1, type2) == (TypeId::of::<ImmutableString>(), TypeId::of::<INT>()) {
+        return match op {
+            PlusAssign => Some((
+                |_ctx, args| {
+                    use std::fmt::Write;
+
+                    let mut buf = SmartString::new_const();
+                    write!(&mut buf, "{}", args[1].as_int().unwrap()).unwrap();
+                    let mut x = args[0].write_lock::<ImmutableString>().unwrap();
+
+                    #[cfg(not(feature = "unchecked"))]
+                    _ctx.unwrap()
+                        .engine()
+                        .throw_on_size((0, 0, x.len() + buf.len()))?;
+
+                    x.make_mut().push_str(&buf);
+
+                    Ok(Dynamic::UNIT)
+                },
+                CHECKED_BUILD,
+            )),
+            _ => None,
+        };
+    }
     // char op= string
     if (type1, type2) == (TypeId::of::<char>(), TypeId::of::<ImmutableString>()) {
         return match op {
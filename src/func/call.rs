@@ -1,18 +1,20 @@
 //! Implement function-calling mechanism for [`Engine`].
 
-use super::{get_builtin_binary_op_fn, get_builtin_op_assignment_fn, CallableFunction};
+use super::{
+    get_builtin_binary_op_fn, get_builtin_op_assignment_fn, get_hasher, CallableFunction, FnAny,
+};
 use crate::api::default_limits::MAX_DYNAMIC_PARAMETERS;
-use crate::ast::{Expr, FnCallExpr, FnCallHashes};
+use crate::ast::{Expr, FnCallExpr, FnCallHashes, Ident};
 use crate::engine::{
     KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CALL, KEYWORD_FN_PTR_CURRY,
-    KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_TYPE_OF,
+    KEYWORD_IS_DEF_VAR, KEYWORD_PRINT, KEYWORD_TYPE_OF, OP_CONTAINS, OP_EQUALS,
 };
 use crate::eval::{search_namespace, Caches, FnResolutionCacheEntry, GlobalRuntimeState};
 use crate::tokenizer::{is_valid_function_name, Token};
 use crate::types::dynamic::Union;
 use crate::{
-    calc_fn_hash, calc_fn_hash_full, Dynamic, Engine, FnArgsVec, FnPtr, ImmutableString, Position,
-    RhaiResult, RhaiResultOf, Scope, Shared, SmartString, ERR,
+    calc_fn_hash, calc_fn_hash_full, Dynamic, Engine, FnArgsVec, FnCallInfo, FnPtr,
+    ImmutableString, Position, RhaiResult, RhaiResultOf, Scope, Shared, SmartString, ERR,
 };
 #[cfg(feature = "no_std")]
 use hashbrown::hash_map::Entry;
@@ -23,6 +25,7 @@ use std::prelude::v1::*;
 use std::{
     any::{type_name, TypeId},
     convert::TryFrom,
+    hash::{Hash, Hasher},
     mem,
 };
 
@@ -36,6 +39,13 @@ use crate::FLOAT;
 /// Arguments to a function call, which is a list of [`&mut Dynamic`][Dynamic].
 pub type FnCallArgs<'a> = [&'a mut Dynamic];
 
+/// Starting time of a timed function call.
+#[cfg(not(feature = "no_time"))]
+pub(crate) type FnCallStart = crate::Instant;
+/// Starting time of a timed function call (timing is not available under `no_time`).
+#[cfg(feature = "no_time")]
+pub(crate) type FnCallStart = ();
+
 /// A type that temporarily stores a mutable reference to a `Dynamic`,
 /// replacing it with a cloned copy.
 #[derive(Debug)]
@@ -131,6 +141,47 @@ pub fn ensure_no_data_race(fn_name: &str, args: &FnCallArgs, is_ref_mut: bool) -
     }
 }
 
+/// Replace the arguments at the positions marked in the bit-mask with cloned copies, returning
+/// the original values so that they can be restored via [`restore_borrowed_args`].
+///
+/// This is to prevent a function from consuming argument values that are only borrowed.
+fn copy_borrowed_args(args: &mut FnCallArgs, borrowed_args: u32) -> FnArgsVec<(usize, Dynamic)> {
+    if borrowed_args == 0 {
+        return FnArgsVec::new_const();
+    }
+
+    args.iter_mut()
+        .enumerate()
+        .take(u32::BITS as usize)
+        .filter(|(i, ..)| borrowed_args & (1 << i) != 0)
+        .map(|(i, arg)| {
+            let copy = arg.clone();
+            (i, mem::replace(&mut **arg, copy))
+        })
+        .collect()
+}
+
+/// Calculate a hash of the argument values of a function call for memoization.
+///
+/// Returns [`None`] if any argument cannot be hashed.
+#[must_use]
+fn calc_args_hash(args: &FnCallArgs) -> Option<u64> {
+    if !args.iter().all(|a| a.is_hashable()) {
+        return None;
+    }
+
+    let hasher = &mut get_hasher();
+    args.iter().for_each(|a| a.hash(hasher));
+    Some(hasher.finish())
+}
+
+/// Restore the original argument values replaced by [`copy_borrowed_args`].
+fn restore_borrowed_args(args: &mut FnCallArgs, originals: FnArgsVec<(usize, Dynamic)>) {
+    for (i, value) in originals {
+        *args[i] = value;
+    }
+}
+
 /// Is a function name an anonymous function?
 #[cfg(not(feature = "no_function"))]
 #[inline]
@@ -157,6 +208,16 @@ impl Engine {
         )
     }
 
+    /// Raise an error if a function has been disabled via [`Engine::disable_function`].
+    #[inline(always)]
+    fn check_fn_disabled(&self, fn_name: &str, pos: Position) -> RhaiResultOf<()> {
+        if !self.disabled_functions.is_empty() && self.disabled_functions.contains(fn_name) {
+            let msg = format!("{fn_name} (disabled by host)");
+            return Err(ERR::ErrorFunctionNotFound(msg, pos).into());
+        }
+        Ok(())
+    }
+
     /// Resolve a normal (non-qualified) function call.
     ///
     /// Search order:
@@ -166,7 +227,7 @@ impl Engine {
     /// 4) Imported modules - functions marked with global namespace
     /// 5) Static registered modules
     #[must_use]
-    fn resolve_fn<'s>(
+    pub(crate) fn resolve_fn<'s>(
         &self,
         _global: &GlobalRuntimeState,
         caches: &'s mut Caches,
@@ -197,31 +258,36 @@ impl Engine {
                         .iter()
                         .rev()
                         .chain(self.global_modules.iter())
-                        .find_map(|m| m.get_fn(hash).map(|f| (f, m.id_raw())));
+                        .find_map(|m| {
+                            m.get_fn_with_memo_capacity(hash)
+                                .map(|(f, memo)| (f, m.id_raw(), memo))
+                        });
                     #[cfg(feature = "no_function")]
                     let func = None;
 
                     let func = func.or_else(|| {
-                        self.global_modules
-                            .iter()
-                            .find_map(|m| m.get_fn(hash).map(|f| (f, m.id_raw())))
+                        self.global_modules.iter().find_map(|m| {
+                            m.get_fn_with_memo_capacity(hash)
+                                .map(|(f, memo)| (f, m.id_raw(), memo))
+                        })
                     });
 
                     #[cfg(not(feature = "no_module"))]
                     let func = func
-                        .or_else(|| _global.get_qualified_fn(hash, true))
+                        .or_else(|| _global.get_qualified_fn(hash, true).map(|(f, s)| (f, s, 0)))
                         .or_else(|| {
                             self.global_sub_modules
                                 .values()
                                 .filter(|m| m.contains_indexed_global_functions())
-                                .find_map(|m| m.get_qualified_fn(hash).map(|f| (f, m.id_raw())))
+                                .find_map(|m| m.get_qualified_fn(hash).map(|f| (f, m.id_raw(), 0)))
                         });
 
-                    if let Some((f, s)) = func {
+                    if let Some((f, s, memo_capacity)) = func {
                         // Specific version found
                         let new_entry = FnResolutionCacheEntry {
                             func: f.clone(),
                             source: s.cloned(),
+                            memo_capacity,
                         };
                         return if cache.filter.is_absent_and_set(hash) {
                             // Do not cache "one-hit wonders"
@@ -255,6 +321,10 @@ impl Engine {
                                 .values()
                                 .any(|m| m.may_contain_dynamic_fn(hash_base));
 
+                        // The `in` operator always checks for `Dynamic` versions of `contains`
+                        let is_dynamic =
+                            is_dynamic || matches!(op_token, Some(Token::In | Token::NotIn));
+
                         // Set maximum bitmask when there are dynamic versions of the function
                         if is_dynamic {
                             max_bitmask = 1usize << usize::min(num_args, MAX_DYNAMIC_PARAMETERS);
@@ -274,25 +344,43 @@ impl Engine {
                                 Some(token) if token.is_op_assignment() => {
                                     let (first_arg, rest_args) = args.split_first().unwrap();
 
-                                    get_builtin_op_assignment_fn(token, first_arg, rest_args[0])
-                                        .map(|(f, has_context)| FnResolutionCacheEntry {
+                                    get_builtin_op_assignment_fn(
+                                        token,
+                                        first_arg,
+                                        rest_args[0],
+                                        self.strict_arithmetic(),
+                                    )
+                                    .map(|(f, has_context)| {
+                                        FnResolutionCacheEntry {
                                             func: CallableFunction::Method {
                                                 func: Shared::new(f),
                                                 has_context,
                                                 is_pure: false,
+                                                is_foldable: false,
+                                                borrowed_args: 0,
                                             },
                                             source: None,
-                                        })
+                                            memo_capacity: 0,
+                                        }
+                                    })
                                 }
-                                Some(token) => get_builtin_binary_op_fn(token, args[0], args[1])
-                                    .map(|(f, has_context)| FnResolutionCacheEntry {
-                                        func: CallableFunction::Method {
-                                            func: Shared::new(f),
-                                            has_context,
-                                            is_pure: true,
-                                        },
-                                        source: None,
-                                    }),
+                                Some(token) => get_builtin_binary_op_fn(
+                                    token,
+                                    args[0],
+                                    args[1],
+                                    self.strict_arithmetic(),
+                                )
+                                .map(|(f, has_context)| FnResolutionCacheEntry {
+                                    func: CallableFunction::Method {
+                                        func: Shared::new(f),
+                                        has_context,
+                                        is_pure: true,
+                                        is_foldable: false,
+                                        borrowed_args: 0,
+                                    },
+                                    source: None,
+                                    memo_capacity: 0,
+                                }),
                             });
 
                         return if cache.filter.is_absent_and_set(hash) {
@@ -329,6 +417,82 @@ impl Engine {
         }
     }
 
+    /// Resolve a variadic script-defined function (i.e. with a rest parameter) that accepts
+    /// `num_args` arguments.
+    #[cfg(not(feature = "no_function"))]
+    #[must_use]
+    fn resolve_variadic_fn<'s>(
+        &self,
+        global: &GlobalRuntimeState,
+        caches: &'s mut Caches,
+        local_entry: &'s mut Option<FnResolutionCacheEntry>,
+        hash_variadic: u64,
+        num_args: usize,
+    ) -> Option<&'s FnResolutionCacheEntry> {
+        self.resolve_fn(
+            global,
+            caches,
+            local_entry,
+            None,
+            hash_variadic,
+            None,
+            false,
+        )
+        .filter(|entry| {
+            entry.func.get_script_fn_def().map_or(false, |fn_def| {
+                fn_def.is_variadic && num_args >= fn_def.num_required_params()
+            })
+        })
+    }
+
+    /// Call the function call tracing callback, if any, before a function is run.
+    ///
+    /// Returns the starting time of the call if it is to be timed.
+    #[inline(always)]
+    pub(crate) fn trace_fn_call(
+        &self,
+        name: &str,
+        source: Option<&str>,
+        pos: Position,
+        level: usize,
+        is_native: bool,
+    ) -> Option<FnCallStart> {
+        let tracer = self.fn_call_tracer.as_ref()?;
+
+        tracer(&FnCallInfo::new(name, source, pos, level, is_native, None));
+
+        #[cfg(not(feature = "no_time"))]
+        return self.time_fn_calls.then(crate::Instant::now);
+        #[cfg(feature = "no_time")]
+        return None;
+    }
+    /// Call the function call tracing callback again, with the elapsed time, after a timed
+    /// function call returns.
+    #[cfg_attr(feature = "no_time", allow(unused_variables))]
+    #[inline(always)]
+    pub(crate) fn trace_fn_return(
+        &self,
+        start: Option<FnCallStart>,
+        name: &str,
+        source: Option<&str>,
+        pos: Position,
+        level: usize,
+        is_native: bool,
+    ) {
+        #[cfg(not(feature = "no_time"))]
+        if let Some(start) = start {
+            let elapsed = Some(start.elapsed());
+            let tracer = self
+                .fn_call_tracer
+                .as_ref()
+                .expect("`fn_call_tracer` is `Some`");
+
+            tracer(&FnCallInfo::new(
+                name, source, pos, level, is_native, elapsed,
+            ));
+        }
+    }
+
     /// # Main Entry-Point (Native by Name)
     ///
     /// Call a native Rust function registered with the [`Engine`] by name.
@@ -339,6 +503,7 @@ impl Engine {
     /// All function arguments not in the first position are always passed by value and thus consumed.
     ///
     /// **DO NOT** reuse the argument values except for the first `&mut` argument - all others are silently replaced by `()`!
+    #[inline(always)]
     pub(crate) fn exec_native_fn_call(
         &self,
         global: &mut GlobalRuntimeState,
@@ -349,9 +514,38 @@ impl Engine {
         args: &mut FnCallArgs,
         is_ref_mut: bool,
         pos: Position,
+    ) -> RhaiResultOf<(Dynamic, bool)> {
+        self.exec_native_fn_call_raw(
+            global, caches, name, op_token, hash, args, is_ref_mut, 0, pos,
+        )
+    }
+    /// Call a native Rust function registered with the [`Engine`] by name.
+    ///
+    /// Arguments at the positions marked in the `borrowed_args` bit-mask are borrowed from the
+    /// caller and are never consumed. They are passed by reference to functions that take them
+    /// by shared reference (e.g. `&Array` or `&Map`), and are cloned otherwise.
+    fn exec_native_fn_call_raw(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        name: &str,
+        op_token: Option<&Token>,
+        hash: u64,
+        args: &mut FnCallArgs,
+        is_ref_mut: bool,
+        borrowed_args: u32,
+        pos: Position,
     ) -> RhaiResultOf<(Dynamic, bool)> {
         self.track_operation(global, pos)?;
 
+        // Check if the result of the call is already memoized
+        if caches.has_memoized_results(hash) {
+            if let Some(result) = calc_args_hash(args).and_then(|h| caches.memoized_result(hash, h))
+            {
+                return Ok((result.clone(), false));
+            }
+        }
+
         // Check if function access already in the cache
         let local_entry = &mut None;
 
@@ -365,9 +559,21 @@ impl Engine {
             true,
         );
 
-        if let Some(FnResolutionCacheEntry { func, source }) = func {
+        if let Some(FnResolutionCacheEntry {
+            func,
+            source,
+            memo_capacity,
+        }) = func
+        {
             debug_assert!(func.is_native());
 
+            // Hash the argument values before the function consumes them
+            let memo = match *memo_capacity {
+                0 => None,
+                _ if func.is_method() => None,
+                capacity => calc_args_hash(args).map(|h| (h, capacity)),
+            };
+
             // Push a new call stack frame
             #[cfg(feature = "debugging")]
             let orig_call_stack_len = global
@@ -385,6 +591,9 @@ impl Engine {
                 backup.change_first_arg_to_copy(args);
             }
 
+            // Clone borrowed arguments that the function would otherwise consume
+            let borrowed = copy_borrowed_args(args, borrowed_args & !func.borrowed_args());
+
             #[cfg(feature = "debugging")]
             if self.is_debugger_registered() {
                 let source = source.clone().or_else(|| global.source.clone());
@@ -397,6 +606,14 @@ impl Engine {
                 );
             }
 
+            let fn_call_start = self.trace_fn_call(
+                name,
+                source.as_deref().or_else(|| global.source()),
+                pos,
+                global.level,
+                true,
+            );
+
             // Run external function
             let is_method = func.is_method();
             let context = func
@@ -419,6 +636,16 @@ impl Engine {
             if swap {
                 backup.restore_first_arg(args);
             }
+            restore_borrowed_args(args, borrowed);
+
+            self.trace_fn_return(
+                fn_call_start,
+                name,
+                source.as_deref().or_else(|| global.source()),
+                pos,
+                global.level,
+                true,
+            );
 
             #[cfg(feature = "debugging")]
             if self.is_debugger_registered() {
@@ -450,6 +677,10 @@ impl Engine {
 
             let result = _result?;
 
+            if let Some((args_hash, capacity)) = memo {
+                caches.memoize_result(hash, args_hash, capacity, result.clone());
+            }
+
             // Check the data size of any `&mut` object, which may be changed.
             #[cfg(not(feature = "unchecked"))]
             if is_ref_mut && !args.is_empty() {
@@ -482,9 +713,53 @@ impl Engine {
             });
         }
 
+        // `contains` function not found - scan the items of an iterable type
+        if name == OP_CONTAINS && args.len() == 2 {
+            if let Some(iter_func) = self.get_iter_fn(global, args[0].type_id()) {
+                let hash = calc_fn_hash(None, OP_EQUALS, 2);
+
+                for item in iter_func(args[0].flatten_clone()) {
+                    let item = &mut item.map_err(|err| err.fill_position(pos))?.flatten();
+                    let value = &mut args[1].clone();
+                    let args = &mut [item, value];
+                    let token = Some(&Token::EqualsTo);
+
+                    match self.exec_native_fn_call(
+                        global, caches, OP_EQUALS, token, hash, args, false, pos,
+                    ) {
+                        Ok((r, ..)) if r.as_bool().unwrap_or(false) => {
+                            return Ok((Dynamic::TRUE, false))
+                        }
+                        Ok(..) => (),
+                        Err(err) => match *err {
+                            ERR::ErrorFunctionNotFound(ref fn_sig, ..)
+                                if fn_sig.starts_with(OP_EQUALS) => {}
+                            _ => return Err(err),
+                        },
+                    }
+                }
+
+                return Ok((Dynamic::FALSE, false));
+            }
+        }
+
         // Error handling
 
         match name {
+            // `in` operator - `contains` function not found?
+            OP_CONTAINS if matches!(op_token, Some(Token::In | Token::NotIn)) => {
+                debug_assert_eq!(args.len(), 2);
+
+                let t0 = self.map_type_name(args[0].type_name());
+                let t1 = self.map_type_name(args[1].type_name());
+
+                Err(ERR::ErrorFunctionNotFound(
+                    format!("type '{t0}' does not support the 'in' operator (no 'contains' function for '{t1}')"),
+                    pos,
+                )
+                .into())
+            }
+
             // index getter function not found?
             #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
             crate::engine::FN_IDX_GET => {
@@ -543,6 +818,19 @@ impl Engine {
                 .into())
             }
 
+            // Raise error, with the origins of the arguments if tracked
+            _ if self.track_provenance() && args.iter().any(|a| a.provenance().is_some()) => {
+                let origins = args
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, a)| a.provenance().map(|p| format!("#{} from {p}", i + 1)))
+                    .collect::<FnArgsVec<_>>()
+                    .join("; ");
+                let sig = self.gen_fn_call_signature(name, args);
+
+                Err(ERR::ErrorFunctionNotFound(format!("{sig} [{origins}]"), pos).into())
+            }
+
             // Raise error
             _ => {
                 Err(ERR::ErrorFunctionNotFound(self.gen_fn_call_signature(name, args), pos).into())
@@ -560,7 +848,40 @@ impl Engine {
     /// value. All function arguments not in the first position are always passed by value and thus consumed.
     ///
     /// **DO NOT** reuse the argument values except for the first `&mut` argument - all others are silently replaced by `()`!
+    #[inline(always)]
     pub(crate) fn exec_fn_call(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: Option<&mut Scope>,
+        fn_name: &str,
+        op_token: Option<&Token>,
+        hashes: FnCallHashes,
+        args: &mut FnCallArgs,
+        is_ref_mut: bool,
+        is_method_call: bool,
+        pos: Position,
+    ) -> RhaiResultOf<(Dynamic, bool)> {
+        self.exec_fn_call_raw(
+            global,
+            caches,
+            scope,
+            fn_name,
+            op_token,
+            hashes,
+            args,
+            is_ref_mut,
+            is_method_call,
+            0,
+            pos,
+        )
+    }
+    /// Perform an actual function call, native Rust or scripted, by name.
+    ///
+    /// Arguments at the positions marked in the `borrowed_args` bit-mask are borrowed from the
+    /// caller and are never consumed. They are passed by reference to native functions that take
+    /// them by shared reference (e.g. `&Array` or `&Map`), and are cloned otherwise.
+    fn exec_fn_call_raw(
         &self,
         global: &mut GlobalRuntimeState,
         caches: &mut Caches,
@@ -571,8 +892,11 @@ impl Engine {
         args: &mut FnCallArgs,
         is_ref_mut: bool,
         _is_method_call: bool,
+        borrowed_args: u32,
         pos: Position,
     ) -> RhaiResultOf<(Dynamic, bool)> {
+        self.check_fn_disabled(fn_name, pos)?;
+
         // These may be redirected from method style calls.
         if hashes.is_native_only()
             && match fn_name {
@@ -627,7 +951,28 @@ impl Engine {
                 resolved = self.resolve_fn(global, caches, local_entry, None, hash, None, false);
             }
 
-            if let Some(FnResolutionCacheEntry { func, source }) = resolved.cloned() {
+            // Variadic script function?
+            if resolved.is_none() {
+                let hash = crate::calc_variadic_fn_hash(None, fn_name);
+                let num_args = args.len() - usize::from(_is_method_call);
+
+                #[cfg(not(feature = "no_object"))]
+                if _is_method_call && !args.is_empty() {
+                    let typed_hash = crate::calc_typed_method_hash(
+                        hash,
+                        self.map_type_name(args[0].type_name()),
+                    );
+                    resolved =
+                        self.resolve_variadic_fn(global, caches, local_entry, typed_hash, num_args);
+                }
+
+                if resolved.is_none() {
+                    resolved =
+                        self.resolve_variadic_fn(global, caches, local_entry, hash, num_args);
+                }
+            }
+
+            if let Some(FnResolutionCacheEntry { func, source, .. }) = resolved.cloned() {
                 // Script function call
                 debug_assert!(func.is_script());
 
@@ -635,6 +980,9 @@ impl Engine {
                 let func = func.get_script_fn_def().expect("script-defined function");
 
                 if func.body.is_empty() {
+                    let src = source.as_deref();
+                    let start = self.trace_fn_call(&func.name, src, pos, global.level, false);
+                    self.trace_fn_return(start, &func.name, src, pos, global.level, false);
                     return Ok((Dynamic::UNIT, false));
                 }
 
@@ -649,7 +997,10 @@ impl Engine {
                 let orig_source = mem::replace(&mut global.source, source);
                 defer! { global => move |g| g.source = orig_source }
 
-                return if _is_method_call {
+                // Script functions consume their arguments, so clone borrowed arguments
+                let borrowed = copy_borrowed_args(args, borrowed_args);
+
+                let result = if _is_method_call {
                     // Method call of script function - map first argument to `this`
                     let (first_arg, args) = args.split_first_mut().unwrap();
                     let this_ptr = Some(&mut **first_arg);
@@ -670,19 +1021,68 @@ impl Engine {
                     defer! { args = (args) if swap => move |a| backup.restore_first_arg(a) }
 
                     self.call_script_fn(global, caches, scope, None, environ, func, args, true, pos)
-                }
-                .map(|r| (r, false));
+                };
+
+                restore_borrowed_args(args, borrowed);
+
+                return result.map(|r| (r, false));
             }
         }
 
         // Native function call
         let hash = hashes.native();
 
-        self.exec_native_fn_call(
-            global, caches, fn_name, op_token, hash, args, is_ref_mut, pos,
+        // Native method call on a constant that is not a reference (e.g. a global constant or a
+        // module variable), which must not be mutated
+        if _is_method_call && !is_ref_mut && !args.is_empty() && args[0].is_read_only() {
+            self.ensure_no_native_mutation(global, caches, fn_name, op_token, hash, args, pos)?;
+        }
+
+        self.exec_native_fn_call_raw(
+            global,
+            caches,
+            fn_name,
+            op_token,
+            hash,
+            args,
+            is_ref_mut,
+            borrowed_args,
+            pos,
         )
     }
 
+    /// Raise [`ErrorNonPureMethodCallOnConstant`][ERR::ErrorNonPureMethodCallOnConstant] if the
+    /// native function that would be called takes its first argument by `&mut`.
+    ///
+    /// Non-pure plugin functions are already checked when called, and `to_string`/`to_debug`
+    /// are assumed to be pure.
+    fn ensure_no_native_mutation(
+        &self,
+        global: &GlobalRuntimeState,
+        caches: &mut Caches,
+        fn_name: &str,
+        op_token: Option<&Token>,
+        hash: u64,
+        args: &mut FnCallArgs,
+        pos: Position,
+    ) -> RhaiResultOf<()> {
+        if fn_name == crate::FUNC_TO_STRING || fn_name == crate::FUNC_TO_DEBUG {
+            return Ok(());
+        }
+
+        let local_entry = &mut None;
+        let args = Some(args);
+
+        match self.resolve_fn(global, caches, local_entry, op_token, hash, args, true) {
+            Some(FnResolutionCacheEntry { func, .. })
+                if func.is_method() && !func.is_plugin_fn() =>
+            {
+                Err(ERR::ErrorNonPureMethodCallOnConstant(fn_name.to_string(), pos).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Evaluate an argument.
     #[inline]
     pub(crate) fn get_arg_value(
@@ -717,12 +1117,34 @@ impl Engine {
             .map(|r| (r, arg_expr.start_position()))
     }
 
+    /// Call the native Rust closure carried by a function pointer (i.e. created via
+    /// [`FnPtr::from_fn`]).
+    ///
+    /// Curried arguments, if any, must already be added to the arguments.
+    pub(crate) fn call_native_fn_ptr(
+        &self,
+        global: &mut GlobalRuntimeState,
+        fn_name: &str,
+        func: &FnAny,
+        args: &mut FnCallArgs,
+        pos: Position,
+    ) -> RhaiResult {
+        self.track_operation(global, pos)?;
+
+        let context = (self, fn_name, None, &*global, pos).into();
+
+        func(Some(context), args)
+            .and_then(|r| self.check_data_size(r, pos))
+            .map_err(|err| err.fill_position(pos))
+    }
+
     /// Call a dot method.
     #[cfg(not(feature = "no_object"))]
     pub(crate) fn make_method_call(
         &self,
         global: &mut GlobalRuntimeState,
         caches: &mut Caches,
+        _this_ptr: Option<&mut Dynamic>,
         fn_name: &str,
         mut hash: FnCallHashes,
         target: &mut crate::eval::Target,
@@ -752,12 +1174,21 @@ impl Engine {
                     Some(fn_def) if fn_def.params.len() == args.len() => {
                         let scope = &mut Scope::new();
                         let environ = fn_ptr.environ.as_ref().map(<_>::as_ref);
+                        // Closures that bind `this` take the `this` of the caller
+                        let this_ptr = _this_ptr.filter(|_| fn_ptr.binds_this());
 
-                        self.call_script_fn(
-                            global, caches, scope, None, environ, fn_def, args, true, pos,
+                        self.call_script_fn_ptr(
+                            global, caches, scope, this_ptr, environ, fn_def, args, pos,
                         )
                         .map(|v| (v, false))
                     }
+                    // Native Rust closure - short-circuit
+                    _ if fn_ptr.is_native() => {
+                        let func = fn_ptr.native_fn.as_deref().expect("native function");
+
+                        self.call_native_fn_ptr(global, fn_ptr.fn_name(), func, args, pos)
+                            .map(|v| (v, false))
+                    }
                     _ => {
                         let _is_anon = false;
                         #[cfg(not(feature = "no_function"))]
@@ -807,11 +1238,22 @@ impl Engine {
                         name,
                         curry,
                         environ,
+                        native_fn,
                         fn_def,
+                        ..
                     },
                 ) = (fn_ptr.is_anonymous(), fn_ptr);
                 #[cfg(feature = "no_function")]
-                let (is_anon, FnPtr { name, curry, .. }, fn_def) = (false, fn_ptr, ());
+                let (
+                    is_anon,
+                    FnPtr {
+                        name,
+                        curry,
+                        native_fn,
+                        ..
+                    },
+                    fn_def,
+                ) = (false, fn_ptr, ());
 
                 // Adding the curried arguments and the remaining arguments
                 let mut curry = curry.into_iter().collect::<FnArgsVec<_>>();
@@ -831,8 +1273,8 @@ impl Engine {
                         let this_ptr = Some(target.as_mut());
                         let environ = environ.as_deref();
 
-                        self.call_script_fn(
-                            global, caches, scope, this_ptr, environ, &fn_def, args, true, pos,
+                        self.call_script_fn_ptr(
+                            global, caches, scope, this_ptr, environ, &fn_def, args, pos,
                         )
                         .map(|v| (v, false))
                     }
@@ -842,6 +1284,13 @@ impl Engine {
                         // Add the first argument with the object pointer
                         args.insert(0, target.as_mut());
 
+                        // Native Rust closure - short-circuit
+                        if let Some(func) = native_fn {
+                            return self
+                                .call_native_fn_ptr(global, &name, &*func, args, pos)
+                                .map(|v| (v, false));
+                        }
+
                         // Recalculate hash
                         let num_args = args.len();
 
@@ -897,6 +1346,7 @@ impl Engine {
                 let mut fn_name = fn_name;
                 let _redirected;
                 let mut _linked = None;
+                let mut _native = None;
                 let mut _arg_values;
                 let mut call_args = call_args;
 
@@ -920,6 +1370,8 @@ impl Engine {
                                 call_args = &mut _arg_values;
                             }
 
+                            _native = fn_ptr.native_fn.clone();
+
                             let _fn_def = ();
                             #[cfg(not(feature = "no_function"))]
                             let _fn_def = fn_ptr.fn_def.as_deref();
@@ -972,8 +1424,8 @@ impl Engine {
                         let this_ptr = Some(target.as_mut());
                         let args = &mut call_args.iter_mut().collect::<FnArgsVec<_>>();
 
-                        self.call_script_fn(
-                            global, caches, scope, this_ptr, environ, &fn_def, args, true, pos,
+                        self.call_script_fn_ptr(
+                            global, caches, scope, this_ptr, environ, &fn_def, args, pos,
                         )
                         .map(|v| (v, false))
                     }
@@ -987,9 +1439,16 @@ impl Engine {
                             .chain(call_args.iter_mut())
                             .collect::<FnArgsVec<_>>();
 
-                        self.exec_fn_call(
-                            global, caches, None, fn_name, None, hash, args, is_ref_mut, true, pos,
-                        )
+                        match _native {
+                            // Native Rust closure - short-circuit
+                            Some(func) => self
+                                .call_native_fn_ptr(global, fn_name, &*func, args, pos)
+                                .map(|v| (v, false)),
+                            None => self.exec_fn_call(
+                                global, caches, None, fn_name, None, hash, args, is_ref_mut, true,
+                                pos,
+                            ),
+                        }
                     }
                 }
             }
@@ -1003,6 +1462,124 @@ impl Engine {
         Ok((result, updated))
     }
 
+    /// Call a script-defined function with named arguments in normal function-call style.
+    ///
+    /// Argument values are evaluated in the order they are written, then reordered into
+    /// positional order according to the parameter names of the resolved function.
+    #[cfg(not(feature = "no_function"))]
+    fn make_named_args_function_call(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        fn_name: &str,
+        args_expr: &[&Expr],
+        arg_names: &[Ident],
+        hashes: FnCallHashes,
+        capture_scope: bool,
+        pos: Position,
+    ) -> RhaiResult {
+        let num_args = args_expr.len();
+        let num_positional = num_args - arg_names.len();
+
+        let mut arg_values = FnArgsVec::with_capacity(num_args);
+
+        for expr in args_expr {
+            let (value, ..) =
+                self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
+            arg_values.push(value.flatten());
+        }
+
+        // Only script-defined functions have parameter names
+        let fn_def = if hashes.is_native_only() {
+            None
+        } else {
+            let mut local_entry = None;
+
+            self.resolve_fn(
+                global,
+                caches,
+                &mut local_entry,
+                None,
+                hashes.script(),
+                None,
+                false,
+            )
+            .and_then(|entry| entry.func.get_script_fn_def().cloned())
+        };
+        let fn_def = match fn_def {
+            Some(fn_def) => fn_def,
+            None if hashes.is_native_only() => {
+                let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
+                let sig = self.gen_fn_call_signature(fn_name, args);
+                return Err(ERR::ErrorFunctionNotFound(sig, pos).into());
+            }
+            None => {
+                let mut local_entry = None;
+                let hash = crate::calc_variadic_fn_hash(None, fn_name);
+
+                match self
+                    .resolve_variadic_fn(global, caches, &mut local_entry, hash, num_args)
+                    .and_then(|entry| entry.func.get_script_fn_def().cloned())
+                {
+                    Some(fn_def) => fn_def,
+                    None => {
+                        let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
+                        let sig = self.gen_fn_call_signature(fn_name, args);
+                        return Err(ERR::ErrorFunctionNotFound(sig, pos).into());
+                    }
+                }
+            }
+        };
+
+        // Reorder the named arguments into their parameter positions
+        let params = &fn_def.params[..fn_def.num_fixed_params()];
+        let mut values = arg_values.into_iter();
+        let mut ordered = values
+            .by_ref()
+            .take(num_positional)
+            .map(Some)
+            .collect::<FnArgsVec<_>>();
+
+        for (arg_name, value) in arg_names.iter().zip(values) {
+            let index = params
+                .iter()
+                .position(|p| *p == arg_name.name)
+                .ok_or_else(|| {
+                    let msg = format!("{fn_name} (no parameter named '{}')", arg_name.name);
+                    ERR::ErrorFunctionNotFound(msg, arg_name.pos)
+                })?;
+
+            if index >= ordered.len() {
+                ordered.resize(index + 1, None);
+            } else if ordered[index].is_some() {
+                let msg = format!("{fn_name} (parameter '{}' given twice)", arg_name.name);
+                return Err(ERR::ErrorFunctionNotFound(msg, arg_name.pos).into());
+            }
+
+            ordered[index] = Some(value);
+        }
+
+        if let Some(index) = ordered.iter().position(Option::is_none) {
+            let msg = format!("{fn_name} (missing argument for '{}')", params[index]);
+            return Err(ERR::ErrorFunctionNotFound(msg, pos).into());
+        }
+
+        let mut arg_values = ordered.into_iter().flatten().collect::<FnArgsVec<_>>();
+        let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
+        let scope = if capture_scope && !scope.is_empty() {
+            Some(scope)
+        } else {
+            None
+        };
+
+        self.exec_fn_call(
+            global, caches, scope, fn_name, None, hashes, args, false, false, pos,
+        )
+        .map(|(v, ..)| v)
+    }
+
     /// Call a function in normal function-call style.
     pub(crate) fn make_function_call(
         &self,
@@ -1014,6 +1591,7 @@ impl Engine {
         op_token: Option<&Token>,
         first_arg: Option<&Expr>,
         args_expr: &[Expr],
+        _arg_names: &[Ident],
         hashes: FnCallHashes,
         capture_scope: bool,
         pos: Position,
@@ -1026,6 +1604,32 @@ impl Engine {
         let mut hashes = hashes;
         let redirected; // Handle call() - Redirect function call
 
+        if op_token.is_none() {
+            self.check_fn_disabled(fn_name, pos)?;
+        }
+
+        // Handle named arguments
+        #[cfg(not(feature = "no_function"))]
+        if !_arg_names.is_empty() {
+            let args_expr = first_arg
+                .into_iter()
+                .chain(args_expr.iter())
+                .collect::<FnArgsVec<_>>();
+
+            return self.make_named_args_function_call(
+                global,
+                caches,
+                scope,
+                this_ptr,
+                fn_name,
+                &args_expr,
+                _arg_names,
+                hashes,
+                capture_scope,
+                pos,
+            );
+        }
+
         match fn_name {
             _ if op_token.is_some() => (),
 
@@ -1049,7 +1653,9 @@ impl Engine {
                         name,
                         curry: extra_curry,
                         environ,
+                        native_fn,
                         fn_def,
+                        binds_this,
                     },
                 ) = (fn_ptr.is_anonymous(), fn_ptr);
                 #[cfg(feature = "no_function")]
@@ -1058,12 +1664,29 @@ impl Engine {
                     FnPtr {
                         name,
                         curry: extra_curry,
+                        native_fn,
                         ..
                     },
                 ) = (false, fn_ptr);
 
                 curry.extend(extra_curry);
 
+                // Native Rust closure - short-circuit
+                if let Some(func) = native_fn {
+                    // Evaluate arguments
+                    let mut arg_values = FnArgsVec::with_capacity(curry.len() + args_expr.len());
+                    arg_values.extend(curry);
+                    for expr in args_expr {
+                        let this_ptr = this_ptr.as_deref_mut();
+                        let (value, _) =
+                            self.get_arg_value(global, caches, scope, this_ptr, expr)?;
+                        arg_values.push(value);
+                    }
+                    let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
+
+                    return self.call_native_fn_ptr(global, &name, &*func, args, pos);
+                }
+
                 // Linked to scripted function - short-circuit
                 #[cfg(not(feature = "no_function"))]
                 if let Some(fn_def) = fn_def {
@@ -1081,9 +1704,11 @@ impl Engine {
                         let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
                         let scope = &mut Scope::new();
                         let environ = environ.as_deref();
+                        // Closures that bind `this` take the `this` of the caller
+                        let this_ptr = this_ptr.filter(|_| binds_this);
 
-                        return self.call_script_fn(
-                            global, caches, scope, None, environ, &fn_def, args, true, pos,
+                        return self.call_script_fn_ptr(
+                            global, caches, scope, this_ptr, environ, &fn_def, args, pos,
                         );
                     }
                 }
@@ -1178,8 +1803,21 @@ impl Engine {
                     Dynamic::FALSE
                 } else if num_params >= 0 {
                     #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-                    let hash_script = calc_fn_hash(None, &fn_name, num_params as usize);
-                    self.has_script_fn(global, caches, hash_script).into()
+                    let num_params = num_params as usize;
+                    let hash_script = calc_fn_hash(None, &fn_name, num_params);
+                    let hash_variadic = crate::calc_variadic_fn_hash(None, &fn_name);
+
+                    (self.has_script_fn(global, caches, hash_script)
+                        || self
+                            .resolve_variadic_fn(
+                                global,
+                                caches,
+                                &mut None,
+                                hash_variadic,
+                                num_params,
+                            )
+                            .is_some())
+                    .into()
                 } else {
                     Dynamic::FALSE
                 });
@@ -1220,11 +1858,27 @@ impl Engine {
                     Dynamic::FALSE
                 } else if num_params >= 0 {
                     #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                    let num_params = num_params as usize;
                     let hash_script = crate::calc_typed_method_hash(
-                        calc_fn_hash(None, &fn_name, num_params as usize),
+                        calc_fn_hash(None, &fn_name, num_params),
                         &this_type,
                     );
-                    self.has_script_fn(global, caches, hash_script).into()
+                    let hash_variadic = crate::calc_typed_method_hash(
+                        crate::calc_variadic_fn_hash(None, &fn_name),
+                        &this_type,
+                    );
+
+                    (self.has_script_fn(global, caches, hash_script)
+                        || self
+                            .resolve_variadic_fn(
+                                global,
+                                caches,
+                                &mut None,
+                                hash_variadic,
+                                num_params,
+                            )
+                            .is_some())
+                    .into()
                 } else {
                     Dynamic::FALSE
                 });
@@ -1241,6 +1895,100 @@ impl Engine {
                 return Ok(scope.contains(&var_name).into());
             }
 
+            // Handle scope_var_names()
+            #[cfg(not(feature = "no_index"))]
+            crate::engine::FN_SCOPE_VAR_NAMES if num_args == 0 && self.scope_introspection() => {
+                return Ok(scope
+                    .iter_raw()
+                    .map(|(name, ..)| self.get_interned_string(name).into())
+                    .collect::<crate::Array>()
+                    .into());
+            }
+
+            // Handle scope_var(var_name) and scope_var_type(var_name)
+            crate::engine::FN_SCOPE_VAR | crate::engine::FN_SCOPE_VAR_TYPE
+                if num_args == 1 && self.scope_introspection() =>
+            {
+                let arg = first_arg.unwrap();
+                let (arg_value, arg_pos) =
+                    self.get_arg_value(global, caches, scope, this_ptr, arg)?;
+                let var_name = arg_value
+                    .into_immutable_string()
+                    .map_err(|typ| self.make_type_mismatch_err::<ImmutableString>(typ, arg_pos))?;
+                let value = scope
+                    .get(&var_name)
+                    .ok_or_else(|| ERR::ErrorVariableNotFound(var_name.to_string(), arg_pos))?;
+
+                return Ok(if fn_name == crate::engine::FN_SCOPE_VAR {
+                    let mut value = value.flatten_clone();
+                    value.set_access_mode(crate::types::dynamic::AccessMode::ReadOnly);
+                    value
+                } else {
+                    self.get_interned_string(self.map_type_name(value.type_name()))
+                        .into()
+                });
+            }
+
+            // Handle dispatch(prefix, suffix, ...)
+            crate::engine::FN_DISPATCH if num_args >= 2 && self.is_dynamic_dispatch_enabled() => {
+                let first = first_arg.unwrap();
+                let (arg_value, arg_pos) =
+                    self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), first)?;
+                let prefix = arg_value
+                    .into_immutable_string()
+                    .map_err(|typ| self.make_type_mismatch_err::<ImmutableString>(typ, arg_pos))?;
+
+                let (arg_value, arg_pos) = self.get_arg_value(
+                    global,
+                    caches,
+                    scope,
+                    this_ptr.as_deref_mut(),
+                    &args_expr[0],
+                )?;
+                let suffix = arg_value
+                    .into_immutable_string()
+                    .map_err(|typ| self.make_type_mismatch_err::<ImmutableString>(typ, arg_pos))?;
+
+                // Short names are kept inline without allocation
+                let mut name = SmartString::new_const();
+                name.push_str(&prefix);
+                name.push_str(&suffix);
+
+                let args_expr = &args_expr[1..];
+                let num_args = args_expr.len();
+                let hash = calc_fn_hash(None, &name, 0);
+
+                let allowed = *caches
+                    .dispatch_cache_mut()
+                    .entry(hash)
+                    .or_insert_with(|| self.is_dispatch_allowed(&name));
+
+                if !allowed {
+                    return Err(ERR::ErrorDispatchNotAllowed(name.into(), pos).into());
+                }
+
+                let hashes = if is_valid_function_name(&name) {
+                    FnCallHashes::from_hash(calc_fn_hash(None, &name, num_args))
+                } else {
+                    FnCallHashes::from_native_only(calc_fn_hash(None, &name, num_args))
+                };
+
+                return self.make_function_call(
+                    global,
+                    caches,
+                    scope,
+                    this_ptr,
+                    &name,
+                    None,
+                    args_expr.first(),
+                    args_expr.get(1..).unwrap_or_default(),
+                    &[],
+                    hashes,
+                    false,
+                    pos,
+                );
+            }
+
             // Handle eval(script)
             KEYWORD_EVAL if num_args == 1 => {
                 // eval - only in function call style
@@ -1286,15 +2034,15 @@ impl Engine {
         }
 
         // Normal function call - except for Fn, curry, call and eval (handled above)
-        let mut arg_values = FnArgsVec::with_capacity(num_args);
-        let mut args = FnArgsVec::with_capacity(num_args + curry.len());
-        let mut is_ref_mut = false;
 
         // Capture parent scope?
         //
         // If so, do it separately because we cannot convert the first argument (if it is a simple
         // variable access) to &mut because `scope` is needed.
         if capture_scope && !scope.is_empty() {
+            let mut arg_values = FnArgsVec::with_capacity(num_args);
+            let mut args = FnArgsVec::with_capacity(num_args + curry.len());
+
             for expr in first_arg.iter().copied().chain(args_expr.iter()) {
                 let (value, ..) =
                     self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
@@ -1308,13 +2056,141 @@ impl Engine {
 
             return self
                 .exec_fn_call(
-                    global, caches, scope, fn_name, op_token, hashes, &mut args, is_ref_mut, false,
-                    pos,
+                    global, caches, scope, fn_name, op_token, hashes, &mut args, false, false, pos,
                 )
                 .map(|(v, ..)| v);
         }
 
-        // Call with blank scope
+        // Lend simple variables in the second and later positions to the function
+        let mut lent = if curry.is_empty() {
+            self.lend_args(global, scope, first_arg, args_expr)?
+        } else {
+            FnArgsVec::new_const()
+        };
+
+        let result = self.make_function_call_with_lent_args(
+            global, caches, scope, this_ptr, fn_name, op_token, first_arg, args_expr, &mut curry,
+            &mut lent, hashes, pos,
+        );
+
+        // Restore the lent variables
+        for (.., index, value) in lent {
+            *scope.get_mut_by_index(index) = value;
+        }
+
+        result
+    }
+
+    /// Move simple variables in the second and later positions of a function call out of the
+    /// scope, so that they can be lent to the function instead of being cloned.
+    ///
+    /// Returns a list of (argument position in `args_expr`, scope index, value) for the variables
+    /// moved, which must be restored into the scope after the call.
+    ///
+    /// Variables are lent only when all arguments are literals or simple variables (so that
+    /// evaluating the other arguments cannot observe the moved values), and each variable is used
+    /// only once in the call.
+    fn lend_args(
+        &self,
+        global: &mut GlobalRuntimeState,
+        scope: &mut Scope,
+        first_arg: Option<&Expr>,
+        args_expr: &[Expr],
+    ) -> RhaiResultOf<FnArgsVec<(usize, usize, Dynamic)>> {
+        let mut lent = FnArgsVec::new_const();
+
+        if args_expr.is_empty()
+            || self.resolve_var.is_some()
+            || self.resolve_var_chain.is_some()
+            || self.track_provenance()
+        {
+            return Ok(lent);
+        }
+
+        #[cfg(feature = "debugging")]
+        if self.is_debugger_registered() {
+            return Ok(lent);
+        }
+
+        let all_args = || first_arg.into_iter().chain(args_expr.iter());
+
+        if !all_args().all(|expr| match expr {
+            Expr::ThisPtr(..) => true,
+            Expr::Variable(x, ..) => x.1.is_empty(),
+            _ => expr.get_literal_value().is_some(),
+        }) {
+            return Ok(lent);
+        }
+
+        let mut candidates = FnArgsVec::new_const();
+
+        for (i, expr) in args_expr.iter().enumerate().take(u32::BITS as usize - 1) {
+            let (x, short_index, pos) = match expr {
+                Expr::Variable(x, short_index, pos) => (x, short_index, *pos),
+                _ => continue,
+            };
+
+            // The variable must not be used in any other argument
+            if all_args()
+                .filter(|e| e.get_variable_name(true) == Some(&x.3))
+                .count()
+                > 1
+            {
+                continue;
+            }
+
+            let index = if global.always_search_scope {
+                scope.search(&x.3)
+            } else {
+                short_index.map(|n| scope.len() - n.get() as usize)
+            };
+
+            match index {
+                #[cfg(not(feature = "no_closure"))]
+                Some(index) if scope.get_mut_by_index(index).is_shared() => (),
+                Some(index) => {
+                    self.track_operation(global, pos)?;
+                    candidates.push((i, index));
+                }
+                None => (),
+            }
+        }
+
+        lent.extend(
+            candidates
+                .into_iter()
+                .map(|(i, index)| (i, index, scope.get_mut_by_index(index).take())),
+        );
+
+        Ok(lent)
+    }
+
+    /// Call a function in normal function-call style with a blank scope.
+    ///
+    /// The variables in `lent` (see [`lend_args`][Engine::lend_args]) are passed to the function in
+    /// place of the corresponding arguments, which are not evaluated.
+    fn make_function_call_with_lent_args(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        mut this_ptr: Option<&mut Dynamic>,
+        fn_name: &str,
+        op_token: Option<&Token>,
+        first_arg: Option<&Expr>,
+        args_expr: &[Expr],
+        curry: &mut FnArgsVec<Dynamic>,
+        lent: &mut [(usize, usize, Dynamic)],
+        hashes: FnCallHashes,
+        pos: Position,
+    ) -> RhaiResult {
+        let num_args = usize::from(first_arg.is_some()) + args_expr.len();
+        let mut arg_values = FnArgsVec::with_capacity(num_args);
+        let mut args = FnArgsVec::with_capacity(num_args + curry.len());
+        let mut is_ref_mut = false;
+
+        let is_lent = |i: usize| lent.iter().any(|&(n, ..)| n == i);
+
         #[cfg(not(feature = "no_closure"))]
         let has_non_shared_this_ptr = this_ptr.as_ref().map_or(false, |v| !v.is_shared());
         #[cfg(feature = "no_closure")]
@@ -1334,7 +2210,7 @@ impl Engine {
                 self.run_debugger(global, caches, scope, this_ptr.as_deref_mut(), _first_expr)?;
 
                 // func(x, ...) -> x.func(...)
-                for expr in args_expr {
+                for (.., expr) in args_expr.iter().enumerate().filter(|(i, ..)| !is_lent(*i)) {
                     let (value, ..) =
                         self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
                     arg_values.push(value.flatten());
@@ -1350,7 +2226,7 @@ impl Engine {
                 self.run_debugger(global, caches, scope, this_ptr.as_deref_mut(), first_expr)?;
 
                 // func(x, ...) -> x.func(...)
-                for expr in args_expr {
+                for (.., expr) in args_expr.iter().enumerate().filter(|(i, ..)| !is_lent(*i)) {
                     let (value, ..) =
                         self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
                     arg_values.push(value.flatten());
@@ -1374,7 +2250,12 @@ impl Engine {
             }
             _ => {
                 // func(..., ...)
-                for expr in first_arg.into_iter().chain(args_expr.iter()) {
+                if let Some(expr) = first_arg {
+                    let (value, ..) =
+                        self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
+                    arg_values.push(value.flatten());
+                }
+                for (.., expr) in args_expr.iter().enumerate().filter(|(i, ..)| !is_lent(*i)) {
                     let (value, ..) =
                         self.get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), expr)?;
                     arg_values.push(value.flatten());
@@ -1383,10 +2264,37 @@ impl Engine {
             }
         }
 
-        args.extend(arg_values.iter_mut());
+        // Interleave the lent values with the other arguments
+        let num_first = arg_values.len() + lent.len() - args_expr.len();
+        let (first_values, rest_values) = arg_values.split_at_mut(num_first);
+        let mut rest_values = rest_values.iter_mut();
+        let mut lent_values = lent.iter_mut().peekable();
+        let mut borrowed_args = 0;
 
-        self.exec_fn_call(
-            global, caches, None, fn_name, op_token, hashes, &mut args, is_ref_mut, false, pos,
+        args.extend(first_values.iter_mut());
+
+        for i in 0..args_expr.len() {
+            match lent_values.next_if(|(n, ..)| *n == i) {
+                Some((.., value)) => {
+                    borrowed_args |= 1 << args.len();
+                    args.push(value);
+                }
+                None => args.push(rest_values.next().unwrap()),
+            }
+        }
+
+        self.exec_fn_call_raw(
+            global,
+            caches,
+            None,
+            fn_name,
+            op_token,
+            hashes,
+            &mut args,
+            is_ref_mut,
+            false,
+            borrowed_args,
+            pos,
         )
         .map(|(v, ..)| v)
     }
@@ -1405,6 +2313,11 @@ impl Engine {
         hash: u64,
         pos: Position,
     ) -> RhaiResult {
+        if !self.disabled_functions.is_empty() {
+            let sep = crate::engine::NAMESPACE_SEPARATOR;
+            self.check_fn_disabled(&format!("{namespace}{sep}{fn_name}"), pos)?;
+        }
+
         let mut arg_values = FnArgsVec::with_capacity(args_expr.len());
         let args = &mut FnArgsVec::with_capacity(args_expr.len());
         let mut first_arg_value = None;
@@ -1526,6 +2439,18 @@ impl Engine {
             }
         }
 
+        // Then search variadic script-defined functions in namespace
+        #[cfg(not(feature = "no_function"))]
+        if func.is_none() {
+            let hash_variadic =
+                crate::calc_variadic_fn_hash(namespace.path.iter().map(|m| m.as_str()), fn_name);
+
+            func = module.get_qualified_fn(hash_variadic).filter(|f| {
+                f.get_script_fn_def()
+                    .map_or(false, |f| args.len() >= f.num_required_params())
+            });
+        }
+
         // Clone first argument if the function is not a method after-all
         if !func.map_or(true, CallableFunction::is_method) {
             if let Some(first) = first_arg_value {
@@ -1556,20 +2481,33 @@ impl Engine {
             }
 
             Some(f) if f.is_plugin_fn() => {
+                let level = global.level;
+                let start = self.trace_fn_call(fn_name, module.id(), pos, level, true);
+
                 let f = f.get_plugin_fn().expect("plugin function");
                 let context = f
                     .has_context()
                     .then(|| (self, fn_name, module.id(), &*global, pos).into());
-                f.call(context, args)
-                    .and_then(|r| self.check_data_size(r, pos))
+                let result = f
+                    .call(context, args)
+                    .and_then(|r| self.check_data_size(r, pos));
+
+                self.trace_fn_return(start, fn_name, module.id(), pos, level, true);
+                result
             }
 
             Some(f) if f.is_native() => {
+                let level = global.level;
+                let start = self.trace_fn_call(fn_name, module.id(), pos, level, true);
+
                 let func = f.get_native_fn().expect("native function");
                 let context = f
                     .has_context()
                     .then(|| (self, fn_name, module.id(), &*global, pos).into());
-                func(context, args).and_then(|r| self.check_data_size(r, pos))
+                let result = func(context, args).and_then(|r| self.check_data_size(r, pos));
+
+                self.trace_fn_return(start, fn_name, module.id(), pos, level, true);
+                result
             }
 
             Some(f) => unreachable!("unknown function type: {:?}", f),
@@ -1654,6 +2592,7 @@ impl Engine {
             name,
             hashes,
             args,
+            arg_names,
             op_token,
             capture_parent_scope: capture,
             ..
@@ -1662,21 +2601,38 @@ impl Engine {
         let op_token = op_token.as_ref();
 
         // Short-circuit native unary operator call if under Fast Operators mode
-        if self.fast_operators() && args.len() == 1 && op_token == Some(&Token::Bang) {
+        if self.fast_operators() && args.len() == 1 && op_token.is_some() {
+            #[allow(clippy::wildcard_imports)]
+            use Token::*;
+
             let mut value = self
                 .get_arg_value(global, caches, scope, this_ptr.as_deref_mut(), &args[0])?
                 .0
                 .flatten();
 
-            return if let Union::Bool(b, ..) = value.0 {
-                Ok((!b).into())
-            } else {
-                let operand = &mut [&mut value];
-                self.exec_fn_call(
+            #[allow(clippy::unnecessary_unwrap)]
+            match (op_token.unwrap(), &value.0) {
+                (Bang, Union::Bool(b, ..)) => return Ok((!*b).into()),
+                (Plus | UnaryPlus, Union::Int(..)) => return Ok(value),
+                // Leave overflows to the negation function to report
+                (Minus | UnaryMinus, Union::Int(n, ..)) if *n != crate::INT::MIN => {
+                    return Ok((-*n).into())
+                }
+                #[cfg(not(feature = "no_float"))]
+                (Plus | UnaryPlus, Union::Float(..)) => return Ok(value),
+                #[cfg(not(feature = "no_float"))]
+                (Minus | UnaryMinus, Union::Float(f, ..)) => return Ok((-**f).into()),
+                _ => (),
+            }
+
+            // Other types (e.g. custom types) call the operator function with one parameter
+            let operand = &mut [&mut value];
+
+            return self
+                .exec_fn_call(
                     global, caches, None, name, op_token, *hashes, operand, false, false, pos,
                 )
-                .map(|(v, ..)| v)
-            };
+                .map(|(v, ..)| v);
         }
 
         // Short-circuit native binary operator call if under Fast Operators mode
@@ -1737,6 +2693,20 @@ impl Engine {
                         _ => (),
                     }
                     #[cfg(feature = "unchecked")]
+                    if self.strict_arithmetic() {
+                        #[allow(clippy::wildcard_imports)]
+                        use crate::packages::arithmetic::strict_int::*;
+
+                        match op_token {
+                            Plus => return add(*n1, *n2).map(Into::into),
+                            Minus => return subtract(*n1, *n2).map(Into::into),
+                            Multiply => return multiply(*n1, *n2).map(Into::into),
+                            Divide => return divide(*n1, *n2).map(Into::into),
+                            Modulo => return modulo(*n1, *n2).map(Into::into),
+                            _ => (),
+                        }
+                    }
+                    #[cfg(feature = "unchecked")]
                     match op_token {
                         EqualsTo => return Ok((n1 == n2).into()),
                         NotEqualsTo => return Ok((n1 != n2).into()),
@@ -1856,7 +2826,7 @@ impl Engine {
                 (Union::Variant(..), _) | (_, Union::Variant(..)) => (),
                 _ => {
                     if let Some((func, need_context)) =
-                        get_builtin_binary_op_fn(op_token, &lhs, &rhs)
+                        get_builtin_binary_op_fn(op_token, &lhs, &rhs, self.strict_arithmetic())
                     {
                         // We may not need to bump the level because built-in's do not need it.
                         //defer! { let orig_level = global.level; global.level += 1 }
@@ -1895,8 +2865,8 @@ impl Engine {
         );
 
         self.make_function_call(
-            global, caches, scope, this_ptr, name, op_token, first_arg, rest_args, *hashes,
-            *capture, pos,
+            global, caches, scope, this_ptr, name, op_token, first_arg, rest_args, arg_names,
+            *hashes, *capture, pos,
         )
     }
 }
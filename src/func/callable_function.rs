@@ -41,6 +41,10 @@ pub enum CallableFunction {
         has_context: bool,
         /// This is a dummy field and is not used.
         is_pure: bool,
+        /// Can the function be evaluated at compile time when all arguments are constants?
+        is_foldable: bool,
+        /// Bit-mask of the parameters taken by shared reference, which are never consumed.
+        borrowed_args: u32,
     },
     /// A native Rust object method with the first argument passed by reference,
     /// and the rest passed by value.
@@ -51,6 +55,10 @@ pub enum CallableFunction {
         has_context: bool,
         /// Allow operating on constants?
         is_pure: bool,
+        /// Can the function be evaluated at compile time when all arguments are constants?
+        is_foldable: bool,
+        /// Bit-mask of the parameters taken by shared reference, which are never consumed.
+        borrowed_args: u32,
     },
     /// An iterator function.
     Iterator {
@@ -61,6 +69,8 @@ pub enum CallableFunction {
     Plugin {
         /// Shared function pointer.
         func: Shared<FnPlugin>,
+        /// Can the function be evaluated at compile time when all arguments are constants?
+        is_foldable: bool,
     },
     /// A script-defined function.
     #[cfg(not(feature = "no_function"))]
@@ -118,6 +128,36 @@ impl CallableFunction {
             Self::Script { .. } => false,
         }
     }
+    /// Is this a native Rust function that can be evaluated at compile time (i.e. constant-folded)
+    /// when all its arguments are constants?
+    #[inline]
+    #[must_use]
+    pub const fn is_foldable(&self) -> bool {
+        match self {
+            Self::Pure { is_foldable, .. }
+            | Self::Method { is_foldable, .. }
+            | Self::Plugin { is_foldable, .. } => *is_foldable,
+            Self::Iterator { .. } => false,
+
+            #[cfg(not(feature = "no_function"))]
+            Self::Script { .. } => false,
+        }
+    }
+    /// Set whether this native Rust function can be evaluated at compile time (i.e. constant-folded).
+    ///
+    /// Has no effect on iterators and script-defined functions.
+    #[inline]
+    pub fn set_foldable(&mut self, foldable: bool) {
+        match self {
+            Self::Pure { is_foldable, .. }
+            | Self::Method { is_foldable, .. }
+            | Self::Plugin { is_foldable, .. } => *is_foldable = foldable,
+            Self::Iterator { .. } => (),
+
+            #[cfg(not(feature = "no_function"))]
+            Self::Script { .. } => (),
+        }
+    }
     /// Is this a native Rust method function?
     #[inline]
     #[must_use]
@@ -200,6 +240,20 @@ impl CallableFunction {
             Self::Script { .. } => false,
         }
     }
+    /// Get the bit-mask of the parameters taken by shared reference (e.g. `&Array` or `&Map`),
+    /// which are borrowed and never consumed by the function.
+    ///
+    /// Bit `n` is set if parameter `n` is borrowed.
+    #[inline]
+    #[must_use]
+    pub const fn borrowed_args(&self) -> u32 {
+        match self {
+            Self::Pure { borrowed_args, .. } | Self::Method { borrowed_args, .. } => *borrowed_args,
+            Self::Plugin { .. } | Self::Iterator { .. } => 0,
+            #[cfg(not(feature = "no_function"))]
+            Self::Script { .. } => 0,
+        }
+    }
     /// Get the access mode.
     #[inline]
     #[must_use]
@@ -312,6 +366,7 @@ impl<T: PluginFunction + 'static + SendSync> From<T> for CallableFunction {
     fn from(func: T) -> Self {
         Self::Plugin {
             func: Shared::new(func),
+            is_foldable: false,
         }
     }
 }
@@ -319,6 +374,9 @@ impl<T: PluginFunction + 'static + SendSync> From<T> for CallableFunction {
 impl From<Shared<FnPlugin>> for CallableFunction {
     #[inline(always)]
     fn from(func: Shared<FnPlugin>) -> Self {
-        Self::Plugin { func }
+        Self::Plugin {
+            func,
+            is_foldable: false,
+        }
     }
 }
@@ -133,6 +133,39 @@ pub fn calc_fn_hash<'a>(
     s.finish()
 }
 
+/// Calculate a [`u64`] hash key from a namespace-qualified function name for a variadic
+/// script-defined function (i.e. with a rest parameter), which does not depend on the number of
+/// parameters.
+///
+/// Module names making up the namespace are passed in via `&str` references from an iterator.
+///
+/// If the function is not namespace-qualified, pass [`None`] as the namespace.
+///
+/// # Note
+///
+/// The first module name is skipped.  Hashing starts from the _second_ module in the chain.
+#[cfg(not(feature = "no_function"))]
+#[inline]
+#[must_use]
+pub fn calc_variadic_fn_hash<'a>(
+    namespace: impl IntoIterator<Item = &'a str>,
+    fn_name: &str,
+) -> u64 {
+    let s = &mut get_hasher();
+    s.write_u8(b'R'); // hash a discriminant
+    let mut count = 0;
+    namespace.into_iter().for_each(|m| {
+        // We always skip the first module
+        if count > 0 {
+            m.hash(s);
+        }
+        count += 1;
+    });
+    s.write_usize(count);
+    fn_name.hash(s);
+    s.finish()
+}
+
 /// Calculate a [`u64`] hash key from a base [`u64`] hash key and a list of parameter types.
 ///
 /// Parameter types are passed in via [`TypeId`] values from an iterator.
@@ -25,12 +25,14 @@ pub use func::Func;
 #[cfg(not(feature = "no_object"))]
 #[cfg(not(feature = "no_function"))]
 pub use hashing::calc_typed_method_hash;
+#[cfg(not(feature = "no_function"))]
+pub use hashing::calc_variadic_fn_hash;
 pub use hashing::{calc_fn_hash, calc_fn_hash_full, calc_var_hash, get_hasher, StraightHashMap};
 #[cfg(feature = "internals")]
 #[allow(deprecated)]
 pub use native::NativeCallContextStore;
 pub use native::{
     locked_read, locked_write, shared_get_mut, shared_make_mut, shared_take, shared_take_or_clone,
-    shared_try_take, IteratorFn, Locked, NativeCallContext, SendSync, Shared,
+    shared_try_take, FnAny, IteratorFn, Locked, NativeCallContext, SendSync, Shared,
 };
 pub use register::RegisterNativeFunction;
@@ -7,10 +7,9 @@ use crate::plugin::PluginFunction;
 use crate::tokenizer::{is_valid_function_name, Token, TokenizeState};
 use crate::types::dynamic::Variant;
 use crate::{
-    calc_fn_hash, Dynamic, Engine, EvalContext, FnArgsVec, FuncArgs, Position, RhaiResult,
-    RhaiResultOf, StaticVec, VarDefInfo, ERR,
+    calc_fn_hash, Dynamic, Engine, EvalContext, FnArgsVec, FnCallInfo, FuncArgs, Position,
+    RhaiResult, RhaiResultOf, StaticVec, VarChainSegment, VarDefInfo, VarWrite,
 };
-use std::any::type_name;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -243,6 +242,56 @@ impl<'a> NativeCallContext<'a> {
     pub const fn tag(&self) -> Option<&Dynamic> {
         Some(&self.global.tag)
     }
+    /// Charge a number of operations, proportional to the amount of work done by the function,
+    /// towards the limit on the number of operations (if any).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorTooManyOperations`][crate::EvalAltResult::ErrorTooManyOperations] if the
+    /// limit is exceeded.
+    #[inline]
+    pub fn track_operations(&self, num: u64) -> RhaiResultOf<()> {
+        #[cfg(not(feature = "unchecked"))]
+        {
+            let pending = self.global.pending_operations.get().saturating_add(num);
+            self.global.pending_operations.set(pending);
+
+            let num_operations = self.global.num_operations.saturating_add(pending);
+
+            if let Some(limit) = self
+                .engine
+                .exceeded_operations_limit(self.global, num_operations)
+            {
+                let info =
+                    crate::LimitInfo::new(crate::LimitKind::Operations, limit, num_operations);
+                return Err(crate::ERR::ErrorTooManyOperations(info, self.pos).into());
+            }
+        }
+        #[cfg(feature = "unchecked")]
+        let _ = num;
+
+        Ok(())
+    }
+    /// Call the progress callback (if any) with the current number of operations.
+    ///
+    /// Returns [`ErrorTerminated`][crate::EvalAltResult::ErrorTerminated] if the callback
+    /// requests termination.
+    #[inline]
+    pub(crate) fn check_progress(&self) -> RhaiResultOf<()> {
+        #[cfg(not(feature = "unchecked"))]
+        if let Some(ref progress) = self.engine.progress {
+            let num_operations = self
+                .global
+                .num_operations
+                .saturating_add(self.global.pending_operations.get());
+
+            if let Some(token) = progress(num_operations) {
+                return Err(crate::ERR::ErrorTerminated(token, self.pos).into());
+            }
+        }
+
+        Ok(())
+    }
     /// Get an iterator over the current set of modules imported via `import` statements
     /// in reverse order.
     ///
@@ -252,6 +301,46 @@ impl<'a> NativeCallContext<'a> {
     pub fn iter_imports(&self) -> impl Iterator<Item = (&str, &crate::Module)> {
         self.global.iter_imports()
     }
+    /// Get the value of a global constant (i.e. one accessible via `global::NAME`), if any.
+    ///
+    /// Global constants include those set via [`Engine::set_global_constants`] as well as
+    /// top-level constants defined in scripts that contain functions.
+    ///
+    /// The value returned is a clone.
+    ///
+    /// Not available under `no_module` or `no_function`.
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    #[must_use]
+    pub fn global_constant(&self, name: &str) -> Option<Dynamic> {
+        self.global
+            .constants
+            .as_ref()
+            .and_then(|constants| crate::func::locked_read(constants).get(name).cloned())
+    }
+    /// Get an iterator over all global constants (i.e. those accessible via `global::NAME`),
+    /// in sorted order of their names.
+    ///
+    /// The values returned are clones.
+    ///
+    /// Not available under `no_module` or `no_function`.
+    #[cfg(not(feature = "no_module"))]
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    pub fn iter_global_constants(&self) -> impl Iterator<Item = (crate::ImmutableString, Dynamic)> {
+        self.global
+            .constants
+            .as_ref()
+            .map(|constants| {
+                crate::func::locked_read(constants)
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
+    }
     /// _(internals)_ The current [`GlobalRuntimeState`], if any.
     /// Exported under the `internals` feature only.
     ///
@@ -302,22 +391,64 @@ impl<'a> NativeCallContext<'a> {
 
         let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
 
-        self._call_fn_raw(fn_name, args, false, false, false)
-            .and_then(|result| {
-                result.try_cast_raw().map_err(|r| {
-                    let result_type = self.engine().map_type_name(r.type_name());
-                    let cast_type = match type_name::<T>() {
-                        typ if typ.contains("::") => self.engine.map_type_name(typ),
-                        typ => typ,
-                    };
-                    ERR::ErrorMismatchOutputType(
-                        cast_type.into(),
-                        result_type.into(),
-                        Position::NONE,
-                    )
-                    .into()
-                })
-            })
+        self._call_fn_raw(fn_name, args, false, false, false, None)
+            .and_then(|result| self.engine().cast_dynamic(result))
+    }
+    /// Call a function inside the call context with the provided arguments, allowing the call to
+    /// perform at most `max_operations` operations.
+    ///
+    /// Not available under `unchecked`.
+    ///
+    /// The budget applies on top of the limit set via
+    /// [`Engine::set_max_operations`][crate::Engine::set_max_operations], and the callback
+    /// registered via [`Engine::on_progress`][crate::Engine::on_progress] is still called.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorTooManyOperations`][crate::EvalAltResult::ErrorTooManyOperations], at the
+    /// position of the current function call, if the call exceeds the budget.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, EvalAltResult, NativeCallContext, INT};
+    ///
+    /// let mut engine = Engine::new();
+    ///
+    /// // Call a function, returning -1 if it takes too long
+    /// engine.register_fn("try_call", |context: NativeCallContext, name: &str| -> Result<INT, _> {
+    ///     match context.call_fn_with_budget::<INT>(name, (), 100) {
+    ///         Err(err) if matches!(*err, EvalAltResult::ErrorTooManyOperations(..)) => Ok(-1),
+    ///         result => result,
+    ///     }
+    /// });
+    ///
+    /// # #[cfg(not(feature = "no_function"))]
+    /// assert_eq!(engine.eval::<INT>("
+    ///     fn quick() { 42 }
+    ///     fn slow() { loop {} }
+    ///
+    ///     try_call(\"quick\") + try_call(\"slow\")
+    /// ")?, 41);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "unchecked"))]
+    #[inline]
+    pub fn call_fn_with_budget<T: Variant + Clone>(
+        &self,
+        fn_name: impl AsRef<str>,
+        args: impl FuncArgs,
+        max_operations: u64,
+    ) -> RhaiResultOf<T> {
+        let mut arg_values = StaticVec::new_const();
+        args.parse(&mut arg_values);
+
+        let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
+
+        self._call_fn_raw(fn_name, args, false, false, false, Some(max_operations))
+            .and_then(|result| self.engine().cast_dynamic(result))
     }
     /// Call a registered native Rust function inside the call context with the provided arguments.
     ///
@@ -335,22 +466,8 @@ impl<'a> NativeCallContext<'a> {
 
         let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
 
-        self._call_fn_raw(fn_name, args, true, false, false)
-            .and_then(|result| {
-                result.try_cast_raw().map_err(|r| {
-                    let result_type = self.engine().map_type_name(r.type_name());
-                    let cast_type = match type_name::<T>() {
-                        typ if typ.contains("::") => self.engine.map_type_name(typ),
-                        typ => typ,
-                    };
-                    ERR::ErrorMismatchOutputType(
-                        cast_type.into(),
-                        result_type.into(),
-                        Position::NONE,
-                    )
-                    .into()
-                })
-            })
+        self._call_fn_raw(fn_name, args, true, false, false, None)
+            .and_then(|result| self.engine().cast_dynamic(result))
     }
     /// Call a function (native Rust or scripted) inside the call context.
     ///
@@ -384,7 +501,7 @@ impl<'a> NativeCallContext<'a> {
         #[cfg(not(feature = "no_function"))]
         let native_only = native_only && !crate::parser::is_anonymous_fn(name);
 
-        self._call_fn_raw(fn_name, args, native_only, is_ref_mut, is_method_call)
+        self._call_fn_raw(fn_name, args, native_only, is_ref_mut, is_method_call, None)
     }
     /// Call a registered native Rust function inside the call context.
     ///
@@ -413,7 +530,7 @@ impl<'a> NativeCallContext<'a> {
         is_ref_mut: bool,
         args: &mut [&mut Dynamic],
     ) -> RhaiResult {
-        self._call_fn_raw(fn_name, args, true, is_ref_mut, false)
+        self._call_fn_raw(fn_name, args, true, is_ref_mut, false, None)
     }
 
     /// Call a function (native Rust or scripted) inside the call context.
@@ -424,19 +541,30 @@ impl<'a> NativeCallContext<'a> {
         native_only: bool,
         is_ref_mut: bool,
         is_method_call: bool,
+        _max_operations: Option<u64>,
     ) -> RhaiResult {
         let global = &mut self.global.clone();
         global.level += 1;
 
+        // Limit the number of operations of the call (the limit goes away with the cloned state)
+        #[cfg(not(feature = "unchecked"))]
+        let limit = _max_operations.map(|max| {
+            let limit = global
+                .num_operations
+                .saturating_add(global.pending_operations.get())
+                .saturating_add(max);
+            global.operations_limit = Some(global.operations_limit.map_or(limit, |l| l.min(limit)));
+            limit
+        });
+
         let caches = &mut Caches::new();
 
         let fn_name = fn_name.as_ref();
         let op_token = Token::lookup_symbol_from_syntax(fn_name);
         let args_len = args.len();
 
-        if native_only {
-            return self
-                .engine()
+        let result = if native_only {
+            self.engine()
                 .exec_native_fn_call(
                     global,
                     caches,
@@ -447,36 +575,49 @@ impl<'a> NativeCallContext<'a> {
                     is_ref_mut,
                     Position::NONE,
                 )
-                .map(|(r, ..)| r);
-        }
-
-        // Native or script
-
-        let hash = match is_method_call {
-            #[cfg(not(feature = "no_function"))]
-            true => FnCallHashes::from_script_and_native(
-                calc_fn_hash(None, fn_name, args_len - 1),
-                calc_fn_hash(None, fn_name, args_len),
-            ),
-            #[cfg(feature = "no_function")]
-            true => FnCallHashes::from_native_only(calc_fn_hash(None, fn_name, args_len)),
-            _ => FnCallHashes::from_hash(calc_fn_hash(None, fn_name, args_len)),
+                .map(|(r, ..)| r)
+        } else {
+            // Native or script
+
+            let hash = match is_method_call {
+                #[cfg(not(feature = "no_function"))]
+                true => FnCallHashes::from_script_and_native(
+                    calc_fn_hash(None, fn_name, args_len - 1),
+                    calc_fn_hash(None, fn_name, args_len),
+                ),
+                #[cfg(feature = "no_function")]
+                true => FnCallHashes::from_native_only(calc_fn_hash(None, fn_name, args_len)),
+                _ => FnCallHashes::from_hash(calc_fn_hash(None, fn_name, args_len)),
+            };
+
+            self.engine()
+                .exec_fn_call(
+                    global,
+                    caches,
+                    None,
+                    fn_name,
+                    op_token.as_ref(),
+                    hash,
+                    args,
+                    is_ref_mut,
+                    is_method_call,
+                    Position::NONE,
+                )
+                .map(|(r, ..)| r)
         };
 
-        self.engine()
-            .exec_fn_call(
-                global,
-                caches,
-                None,
-                fn_name,
-                op_token.as_ref(),
-                hash,
-                args,
-                is_ref_mut,
-                is_method_call,
-                Position::NONE,
-            )
-            .map(|(r, ..)| r)
+        // Attribute the error to this call if the operations budget is exceeded
+        #[cfg(not(feature = "unchecked"))]
+        if let Some(limit) = limit {
+            return result.map_err(|err| match *err {
+                crate::ERR::ErrorTooManyOperations(info, ..) if global.num_operations > limit => {
+                    crate::ERR::ErrorTooManyOperations(info, self.pos).into()
+                }
+                _ => err,
+            });
+        }
+
+        result
     }
 }
 
@@ -571,6 +712,15 @@ pub type IteratorFn = dyn Fn(Dynamic) -> Box<dyn Iterator<Item = RhaiResultOf<Dy
 pub type IteratorFn =
     dyn Fn(Dynamic) -> Box<dyn Iterator<Item = RhaiResultOf<Dynamic>>> + Send + Sync;
 
+/// Function that builds a static module upon first access.
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "sync"))]
+pub type LazyModuleInit = dyn FnOnce() -> crate::SharedModule;
+/// Function that builds a static module upon first access.
+#[cfg(not(feature = "no_module"))]
+#[cfg(feature = "sync")]
+pub type LazyModuleInit = dyn FnOnce() -> crate::SharedModule + Send + Sync;
+
 /// Plugin function trait object.
 #[cfg(not(feature = "sync"))]
 pub type FnPlugin = dyn PluginFunction;
@@ -601,6 +751,20 @@ pub type OnDebugCallback = dyn Fn(&str, Option<&str>, Position);
 #[cfg(feature = "sync")]
 pub type OnDebugCallback = dyn Fn(&str, Option<&str>, Position) + Send + Sync;
 
+/// Callback function for tracing function calls.
+#[cfg(not(feature = "sync"))]
+pub type OnFnCallCallback = dyn Fn(&FnCallInfo);
+/// Callback function for tracing function calls.
+#[cfg(feature = "sync")]
+pub type OnFnCallCallback = dyn Fn(&FnCallInfo) + Send + Sync;
+
+/// Callback function for tracing the evaluation of statements.
+#[cfg(not(feature = "sync"))]
+pub type OnStmtCallback = dyn Fn(Option<&str>, Position, u32);
+/// Callback function for tracing the evaluation of statements.
+#[cfg(feature = "sync")]
+pub type OnStmtCallback = dyn Fn(Option<&str>, Position, u32) + Send + Sync;
+
 /// Callback function for mapping tokens during parsing.
 #[cfg(not(feature = "sync"))]
 pub type OnParseTokenCallback = dyn Fn(Token, Position, &TokenizeState) -> Token;
@@ -616,6 +780,24 @@ pub type OnVarCallback = dyn Fn(&str, usize, EvalContext) -> RhaiResultOf<Option
 pub type OnVarCallback =
     dyn Fn(&str, usize, EvalContext) -> RhaiResultOf<Option<Dynamic>> + Send + Sync;
 
+/// Callback function for variable access with property/index chain context.
+#[cfg(not(feature = "sync"))]
+pub type OnVarChainCallback =
+    dyn Fn(&str, usize, &[VarChainSegment], EvalContext) -> RhaiResultOf<Option<(Dynamic, usize)>>;
+/// Callback function for variable access with property/index chain context.
+#[cfg(feature = "sync")]
+pub type OnVarChainCallback = dyn Fn(&str, usize, &[VarChainSegment], EvalContext) -> RhaiResultOf<Option<(Dynamic, usize)>>
+    + Send
+    + Sync;
+
+/// Callback function for variable writes.
+#[cfg(not(feature = "sync"))]
+pub type OnVarWriteCallback = dyn Fn(&str, VarWrite, Position, EvalContext) -> RhaiResultOf<()>;
+/// Callback function for variable writes.
+#[cfg(feature = "sync")]
+pub type OnVarWriteCallback =
+    dyn Fn(&str, VarWrite, Position, EvalContext) -> RhaiResultOf<()> + Send + Sync;
+
 /// Callback function for variable definition.
 #[cfg(not(feature = "sync"))]
 pub type OnDefVarCallback = dyn Fn(bool, VarDefInfo, EvalContext) -> RhaiResultOf<bool>;
@@ -64,11 +64,68 @@ pub fn by_value<T: Variant + Clone>(data: &mut Dynamic) -> T {
         return reify! { data.take().into_string().expect("`ImmutableString`") => !!! T };
     }
 
+    #[cfg(not(feature = "no_index"))]
+    if TypeId::of::<T>() == TypeId::of::<&crate::Array>() {
+        return by_shared_ref::<T, crate::Array>(data);
+    }
+    #[cfg(not(feature = "no_index"))]
+    if TypeId::of::<T>() == TypeId::of::<&crate::Blob>() {
+        return by_shared_ref::<T, crate::Blob>(data);
+    }
+    #[cfg(not(feature = "no_object"))]
+    if TypeId::of::<T>() == TypeId::of::<&crate::Map>() {
+        return by_shared_ref::<T, crate::Map>(data);
+    }
+    if TypeId::of::<T>() == TypeId::of::<&crate::ImmutableString>() {
+        return by_shared_ref::<T, crate::ImmutableString>(data);
+    }
+
     // We consume the argument and then replace it with () - the argument is not supposed to be used again.
     // This way, we avoid having to clone the argument again, because it is already a clone when passed here.
     data.take().cast::<T>()
 }
 
+/// Dereference into a shared reference `T` (which must be `&X`) without consuming the argument.
+#[inline(always)]
+#[must_use]
+fn by_shared_ref<T: Variant + Clone, X: Variant + Clone>(data: &mut Dynamic) -> T {
+    *data = data.take().flatten();
+
+    let r = data.downcast_ref::<X>().expect("checked");
+    // SAFETY: We already checked that `T` is `&X`, so it is safe to cast here.
+    unsafe { mem::transmute_copy::<_, T>(&r) }
+}
+
+/// Is the parameter type `T` a shared reference that is borrowed (i.e. not consumed) by [`by_value`]?
+#[inline(always)]
+#[must_use]
+pub fn is_borrowed_param<T: Variant + Clone>() -> bool {
+    let type_id = TypeId::of::<T>();
+
+    #[cfg(not(feature = "no_index"))]
+    if type_id == TypeId::of::<&crate::Array>() || type_id == TypeId::of::<&crate::Blob>() {
+        return true;
+    }
+    #[cfg(not(feature = "no_object"))]
+    if type_id == TypeId::of::<&crate::Map>() {
+        return true;
+    }
+
+    type_id == TypeId::of::<&str>() || type_id == TypeId::of::<&crate::ImmutableString>()
+}
+
+/// Make a bit-mask of the parameters that are borrowed (i.e. not consumed).
+#[inline]
+#[must_use]
+fn make_borrowed_args_mask(borrowed: &[bool]) -> u32 {
+    borrowed
+        .iter()
+        .take(u32::BITS as usize)
+        .enumerate()
+        .filter(|(.., &b)| b)
+        .fold(0, |mask, (i, ..)| mask | (1 << i))
+}
+
 /// Trait to register custom Rust functions.
 ///
 /// # Type Parameters
@@ -162,7 +219,7 @@ macro_rules! def_register {
 
                     // Map the result
                     Ok(Dynamic::from(r))
-                }), has_context: false, is_pure }
+                }), has_context: false, is_pure, is_foldable: false, borrowed_args: make_borrowed_args_mask(&[$(is_borrowed_param::<$par>()),*]) }
             }
         }
 
@@ -186,7 +243,7 @@ macro_rules! def_register {
 
                     // Map the result
                     Ok(Dynamic::from(r))
-                }), has_context: true, is_pure }
+                }), has_context: true, is_pure, is_foldable: false, borrowed_args: make_borrowed_args_mask(&[$(is_borrowed_param::<$par>()),*]) }
             }
         }
 
@@ -206,7 +263,7 @@ macro_rules! def_register {
 
                     // Call the function with each argument value
                     self($($arg),*).map(Dynamic::from)
-                }), has_context: false, is_pure }
+                }), has_context: false, is_pure, is_foldable: false, borrowed_args: make_borrowed_args_mask(&[$(is_borrowed_param::<$par>()),*]) }
             }
         }
 
@@ -228,7 +285,7 @@ macro_rules! def_register {
 
                     // Call the function with each argument value
                     self(ctx, $($arg),*).map(Dynamic::from)
-                }), has_context: true, is_pure }
+                }), has_context: true, is_pure, is_foldable: false, borrowed_args: make_borrowed_args_mask(&[$(is_borrowed_param::<$par>()),*]) }
             }
         }
 
@@ -5,6 +5,7 @@ use super::call::FnCallArgs;
 use crate::ast::ScriptFnDef;
 use crate::eval::{Caches, GlobalRuntimeState};
 use crate::func::EncapsulatedEnviron;
+use crate::types::dynamic::AccessMode;
 use crate::{Dynamic, Engine, Position, RhaiResult, Scope, ERR};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -34,22 +35,39 @@ impl Engine {
         rewind_scope: bool,
         pos: Position,
     ) -> RhaiResult {
-        debug_assert_eq!(fn_def.params.len(), args.len());
+        debug_assert!(args.len() >= fn_def.num_required_params());
+        debug_assert!(fn_def.is_variadic || args.len() <= fn_def.params.len());
 
         self.track_operation(global, pos)?;
 
         // Check for stack overflow
         #[cfg(not(feature = "unchecked"))]
         if global.level > self.max_call_levels() {
-            return Err(ERR::ErrorStackOverflow(pos).into());
+            let info = crate::LimitInfo::new(
+                crate::LimitKind::CallLevels,
+                self.max_call_levels() as u64,
+                global.level as u64,
+            );
+            return Err(ERR::ErrorStackOverflow(info, pos).into());
         }
 
+        let fn_call_start =
+            self.trace_fn_call(&fn_def.name, global.source(), pos, global.level, false);
+
         #[cfg(feature = "debugging")]
-        if self.debugger_interface.is_none() && fn_def.body.is_empty() {
-            return Ok(Dynamic::UNIT);
-        }
+        let is_empty = self.debugger_interface.is_none() && fn_def.body.is_empty();
         #[cfg(not(feature = "debugging"))]
-        if fn_def.body.is_empty() {
+        let is_empty = fn_def.body.is_empty();
+
+        if is_empty {
+            self.trace_fn_return(
+                fn_call_start,
+                &fn_def.name,
+                global.source(),
+                pos,
+                global.level,
+                false,
+            );
             return Ok(Dynamic::UNIT);
         }
 
@@ -70,11 +88,28 @@ impl Engine {
             return Err(ERR::ErrorTooManyVariables(pos).into());
         }
 
+        let num_fixed = fn_def.num_fixed_params();
+
         // Put arguments into scope as variables
-        scope.extend(fn_def.params.iter().cloned().zip(args.iter_mut().map(|v| {
-            // Actually consume the arguments instead of cloning them
-            v.take()
-        })));
+        scope.extend(
+            fn_def
+                .params
+                .iter()
+                .cloned()
+                .zip(args.iter_mut().take(num_fixed).map(|v| {
+                    // Actually consume the arguments instead of cloning them
+                    v.take()
+                })),
+        );
+
+        // Collect the remaining arguments for the rest parameter
+        #[cfg(not(feature = "no_index"))]
+        let rest = fn_def.is_variadic.then(|| {
+            args.iter_mut()
+                .skip(num_fixed)
+                .map(|v| v.take())
+                .collect::<crate::Array>()
+        });
 
         // Push a new call stack frame
         #[cfg(feature = "debugging")]
@@ -115,16 +150,47 @@ impl Engine {
             self.run_debugger(global, caches, scope, this_ptr.as_deref_mut(), &node)?;
         }
 
-        // Evaluate the function
-        let mut _result: RhaiResult = self
-            .eval_stmt_block(
-                global,
-                caches,
-                scope,
-                this_ptr.as_deref_mut(),
-                fn_def.body.statements(),
-                rewind_scope,
-            )
+        // Push a new frame onto the call stack captured into errors
+        let orig_error_call_stack_len = global.call_stack.len();
+
+        if self.capture_stack_on_error() {
+            global.call_stack.push((fn_def.name.clone(), pos));
+        }
+
+        let mut num_params_in_scope = args.len().min(num_fixed);
+
+        // Put default values of missing optional parameters into scope
+        let mut _result: RhaiResult = fn_def.defaults
+            [num_params_in_scope - fn_def.num_required_params()..]
+            .iter()
+            .zip(fn_def.params.iter().skip(num_params_in_scope))
+            .try_for_each(|(expr, name)| {
+                let value = self
+                    .eval_expr(global, caches, scope, this_ptr.as_deref_mut(), expr)?
+                    .flatten();
+                scope.push_entry(name.clone(), AccessMode::ReadWrite, value);
+                num_params_in_scope += 1;
+                Ok(())
+            })
+            .and_then(|()| {
+                // Put the remaining arguments into scope as the rest parameter
+                #[cfg(not(feature = "no_index"))]
+                if let Some(rest) = rest {
+                    let name = fn_def.params[num_fixed].clone();
+                    scope.push_entry(name, AccessMode::ReadWrite, rest.into());
+                    num_params_in_scope += 1;
+                }
+
+                // Evaluate the function
+                self.eval_stmt_block(
+                    global,
+                    caches,
+                    scope,
+                    this_ptr.as_deref_mut(),
+                    fn_def.body.statements(),
+                    rewind_scope,
+                )
+            })
             .or_else(|err| match *err {
                 // Convert return statement to return value
                 ERR::Return(x, ..) => Ok(x),
@@ -148,12 +214,14 @@ impl Engine {
                         .to_string(),
                     #[cfg(feature = "no_module")]
                     global.source().unwrap_or("").to_string(),
-                    err,
+                    err.fill_call_stack(&global.call_stack),
                     pos,
                 )
                 .into()),
             });
 
+        global.call_stack.truncate(orig_error_call_stack_len);
+
         #[cfg(feature = "debugging")]
         if self.is_debugger_registered() {
             let trigger = match global.debugger_mut().status {
@@ -186,9 +254,9 @@ impl Engine {
         // Remove all local variables and imported modules
         if rewind_scope {
             scope.rewind(orig_scope_len);
-        } else if !args.is_empty() {
+        } else if num_params_in_scope > 0 {
             // Remove arguments only, leaving new variables in the scope
-            scope.remove_range(orig_scope_len, args.len());
+            scope.remove_range(orig_scope_len, num_params_in_scope);
         }
         global.lib.truncate(orig_lib_len);
         #[cfg(not(feature = "no_module"))]
@@ -203,9 +271,63 @@ impl Engine {
         // Restore state
         caches.rewind_fn_resolution_caches(orig_fn_resolution_caches_len);
 
+        self.trace_fn_return(
+            fn_call_start,
+            &fn_def.name,
+            global.source(),
+            pos,
+            global.level,
+            false,
+        );
+
         _result
     }
 
+    /// Call a script-defined function linked to a function pointer (e.g. a closure).
+    ///
+    /// If [`max_closure_call_levels`][Engine::max_closure_call_levels] is set, the call is counted
+    /// in [`closure_level`][GlobalRuntimeState::closure_level] and checked against that limit.
+    /// Otherwise it is counted as a normal function call.
+    ///
+    /// # WARNING
+    ///
+    /// All function call arguments are consumed, just like [`call_script_fn`][Engine::call_script_fn].
+    pub(crate) fn call_script_fn_ptr(
+        &self,
+        global: &mut GlobalRuntimeState,
+        caches: &mut Caches,
+        scope: &mut Scope,
+        this_ptr: Option<&mut Dynamic>,
+        environ: Option<&EncapsulatedEnviron>,
+        fn_def: &ScriptFnDef,
+        args: &mut FnCallArgs,
+        pos: Position,
+    ) -> RhaiResult {
+        #[cfg(not(feature = "unchecked"))]
+        if self.max_closure_call_levels() > 0 {
+            defer! { let orig_closure_level = global.closure_level; global.closure_level += 1 }
+
+            if global.closure_level > self.max_closure_call_levels() {
+                let info = crate::LimitInfo::new(
+                    crate::LimitKind::ClosureCallLevels,
+                    self.max_closure_call_levels() as u64,
+                    global.closure_level as u64,
+                );
+                return Err(ERR::ErrorStackOverflow(info, pos).into());
+            }
+
+            return self.call_script_fn(
+                global, caches, scope, this_ptr, environ, fn_def, args, true, pos,
+            );
+        }
+
+        defer! { let orig_level = global.level; global.level += 1 }
+
+        self.call_script_fn(
+            global, caches, scope, this_ptr, environ, fn_def, args, true, pos,
+        )
+    }
+
     // Does a script-defined function exist?
     #[must_use]
     pub(crate) fn has_script_fn(
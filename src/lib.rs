@@ -108,6 +108,7 @@ mod reify;
 #[macro_use]
 mod defer;
 
+mod analyzer;
 mod api;
 mod ast;
 pub mod config;
@@ -228,11 +229,14 @@ use once_cell::race::OnceBox as OnceCell;
 
 #[allow(deprecated)]
 pub use api::build_type::{CustomType, TypeBuilder};
+pub use api::format_script::FormatOptions;
 #[cfg(not(feature = "no_custom_syntax"))]
-pub use api::custom_syntax::Expression;
+pub use api::custom_syntax::{CustomSyntaxPosition, Expression};
 #[cfg(not(feature = "no_std"))]
 #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
 pub use api::files::{eval_file, run_file};
+pub use api::options::{DebugFormat, ShadowingPolicy};
+pub use api::version::LanguageVersion;
 pub use api::{eval::eval, run::run};
 pub use ast::{FnAccess, AST};
 use defer::Deferred;
@@ -241,18 +245,32 @@ pub use eval::EvalContext;
 #[cfg(not(feature = "no_function"))]
 #[cfg(not(feature = "no_object"))]
 use func::calc_typed_method_hash;
+#[cfg(not(feature = "no_function"))]
+use func::calc_variadic_fn_hash;
 use func::{calc_fn_hash, calc_fn_hash_full, calc_var_hash};
 pub use func::{plugin, FuncArgs, NativeCallContext, RegisterNativeFunction};
-pub use module::{FnNamespace, Module};
+#[cfg(not(feature = "no_module"))]
+pub use module::{AstToModuleOptions, ExportFilter};
+pub use module::{FnMetadata, FnNamespace, Module};
+#[cfg(feature = "metadata")]
+pub use module::{FnSignatureInfo, FnSignatureKind, FnSignatureOptions, FnSignatureSource};
 pub use packages::string_basic::{FUNC_TO_DEBUG, FUNC_TO_STRING};
 pub use rhai_codegen::*;
 #[cfg(not(feature = "no_time"))]
 pub use types::Instant;
 pub use types::{
-    Dynamic, EvalAltResult, FnPtr, ImmutableString, LexError, ParseError, ParseErrorType, Position,
-    Scope, VarDefInfo,
+    Dynamic, EvalAltResult, FnCallInfo, FnPtr, ImmutableString, LexError, LimitInfo, LimitKind,
+    ParseError, ParseErrorType, ParseWarning, ParseWarningKind, Position, Scope, Span,
+    StringsInternerStats, VarChainSegment, VarDefInfo, VarWrite,
 };
 
+pub use tokenizer::Token;
+
+/// Module containing types for collecting the coverage of scripts.
+pub mod coverage {
+    pub use super::eval::CoverageCollector;
+}
+
 /// _(debugging)_ Module containing types for debugging.
 /// Exported under the `debugging` feature only.
 #[cfg(feature = "debugging")]
@@ -292,7 +310,8 @@ pub use func::Func;
 pub use ast::ScriptFnMetadata;
 
 #[cfg(not(feature = "no_function"))]
-pub use api::call_fn::CallFnOptions;
+pub use api::call_fn::{CallFnOptions, CallFnsOptions, FnCallSpec};
+pub use api::compile::CompileOptions;
 
 /// Variable-sized array of [`Dynamic`] values.
 ///
@@ -306,18 +325,42 @@ pub type Array = Vec<Dynamic>;
 #[cfg(not(feature = "no_index"))]
 pub type Blob = Vec<u8>;
 
+#[cfg(not(feature = "no_index"))]
+pub use types::{BlobView, BlobViewIter};
+
 /// A dictionary of [`Dynamic`] values with string keys.
 ///
 /// Not available under `no_object`.
 ///
 /// [`SmartString`](https://crates.io/crates/smartstring) is used as the key type because most
 /// property names are ASCII and short, fewer than 23 characters, so they can be stored inline.
+///
+/// Under the `map_insertion_order` feature, an `OrderedMap` is used instead so that entries are
+/// kept in insertion order.
 #[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "map_insertion_order"))]
 pub type Map = std::collections::BTreeMap<Identifier, Dynamic>;
 
+/// A dictionary of [`Dynamic`] values with string keys, kept in insertion order.
+///
+/// Not available under `no_object`.
+///
+/// [`SmartString`](https://crates.io/crates/smartstring) is used as the key type because most
+/// property names are ASCII and short, fewer than 23 characters, so they can be stored inline.
+#[cfg(not(feature = "no_object"))]
+#[cfg(feature = "map_insertion_order")]
+pub type Map = OrderedMap<Identifier, Dynamic>;
+
+#[cfg(feature = "map_insertion_order")]
+pub use types::{ordered_map, ordered_map::OrderedMap};
+
 #[cfg(not(feature = "no_object"))]
 pub use api::json::format_map_as_json;
 
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+pub use api::settings::{SettingsError, SETTINGS_VERSION};
+
 #[cfg(not(feature = "no_module"))]
 pub use module::ModuleResolver;
 
@@ -342,12 +385,12 @@ pub use types::dynamic::{AccessMode, DynamicReadLock, DynamicWriteLock, Variant}
 pub use types::FloatWrapper;
 
 #[cfg(feature = "internals")]
-pub use types::{BloomFilterU64, CustomTypeInfo, Span, StringsInterner};
+pub use types::{BloomFilterU64, CustomTypeInfo, StringsInterner};
 
 #[cfg(feature = "internals")]
 pub use tokenizer::{
     get_next_token, is_valid_function_name, is_valid_identifier, parse_string_literal, InputStream,
-    MultiInputsStream, Token, TokenIterator, TokenizeState, TokenizerControl,
+    MultiInputsStream, TokenIterator, TokenizeState, TokenizerControl,
     TokenizerControlBlock,
 };
 
@@ -361,6 +404,7 @@ pub use api::default_limits;
 pub use ast::{
     ASTFlags, ASTNode, BinaryExpr, ConditionalExpr, Expr, FlowControl, FnCallExpr, FnCallHashes,
     Ident, OpAssignment, RangeCase, ScriptFnDef, Stmt, StmtBlock, SwitchCasesCollection,
+    TryCatchBlock,
 };
 
 #[cfg(feature = "internals")]
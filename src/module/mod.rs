@@ -89,6 +89,9 @@ pub struct FuncInfoMetadata {
     pub num_params: usize,
     /// Parameter types (if applicable).
     pub param_types: Box<[TypeId]>,
+    /// Maximum number of results of calls to this native Rust function to keep during each
+    /// evaluation (zero if not memoized).
+    pub memo_capacity: usize,
     /// Parameter names and types (if available).
     #[cfg(feature = "metadata")]
     pub params_info: Box<[Identifier]>,
@@ -155,6 +158,96 @@ impl FuncInfo {
 
         signature
     }
+    /// Get the introspection information of the function.
+    #[must_use]
+    fn to_fn_metadata(&self) -> FnMetadata<'_> {
+        #[cfg(feature = "metadata")]
+        let (param_type_names, return_type) = if self.func.is_script() {
+            (
+                vec!["Dynamic".into(); self.metadata.num_params],
+                "Dynamic".into(),
+            )
+        } else {
+            (
+                self.metadata
+                    .params_info
+                    .iter()
+                    .map(|param| {
+                        param
+                            .split_once(':')
+                            .map_or("Dynamic".into(), |(.., typ)| format_type(typ, false))
+                    })
+                    .collect(),
+                format_type(&self.metadata.return_type, true),
+            )
+        };
+
+        FnMetadata {
+            name: &self.metadata.name,
+            namespace: self.metadata.namespace,
+            access: self.metadata.access,
+            num_params: self.metadata.num_params,
+            param_types: &self.metadata.param_types,
+            #[cfg(feature = "metadata")]
+            param_type_names,
+            #[cfg(feature = "metadata")]
+            return_type,
+            is_script: self.func.is_script(),
+            is_plugin_fn: self.func.is_plugin_fn(),
+            is_pure: self.func.is_pure(),
+            is_method: self.func.is_method(),
+            is_foldable: self.func.is_foldable(),
+        }
+    }
+}
+
+/// Introspection information of a function in a [`Module`], as returned by
+/// [`Module::iter_fn_metadata`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub struct FnMetadata<'a> {
+    /// Function name.
+    pub name: &'a str,
+    /// Function namespace.
+    pub namespace: FnNamespace,
+    /// Function access mode.
+    pub access: FnAccess,
+    /// Number of parameters.
+    pub num_params: usize,
+    /// Types of the parameters of a native Rust function.
+    ///
+    /// The first parameter of a method is its object type (e.g. [`Array`][crate::Array] for
+    /// `&mut Array`).
+    ///
+    /// Empty for script-defined functions, which take parameters of any type.
+    pub param_types: &'a [TypeId],
+    /// _(metadata)_ Type names of the parameters, as registered.
+    /// Exported under the `metadata` feature only.
+    ///
+    /// Empty if the type names were not registered (e.g. the function is registered via
+    /// [`Module::set_raw_fn`] without metadata).
+    #[cfg(feature = "metadata")]
+    pub param_type_names: Vec<std::borrow::Cow<'a, str>>,
+    /// _(metadata)_ Type name of the return value, as registered.
+    /// Exported under the `metadata` feature only.
+    ///
+    /// Empty if the function returns `()` or if the type name was not registered.
+    #[cfg(feature = "metadata")]
+    pub return_type: std::borrow::Cow<'a, str>,
+    /// Is this a script-defined function?
+    pub is_script: bool,
+    /// Is this a plugin function?
+    pub is_plugin_fn: bool,
+    /// Is this a pure function, i.e. it does not modify its first argument and so can be called on
+    /// constants?
+    pub is_pure: bool,
+    /// Is this a native Rust method, i.e. its first argument is passed by reference?
+    pub is_method: bool,
+    /// Can this native Rust function be evaluated at compile time (i.e. constant-folded) when all
+    /// its arguments are constants?
+    ///
+    /// Functions are not foldable unless marked via [`Module::update_fn_foldable`].
+    pub is_foldable: bool,
 }
 
 /// _(internals)_ Calculate a [`u64`] hash key from a namespace-qualified function name and parameter types.
@@ -521,6 +614,19 @@ impl Module {
             .add_type_with_comments::<T>(name, comments);
         self
     }
+    /// Map a custom type to a friendly display name, recording the hashes of the functions
+    /// registered together with it.
+    #[cfg(feature = "metadata")]
+    #[inline(always)]
+    pub(crate) fn set_custom_type_with_functions<T>(
+        &mut self,
+        name: &str,
+        functions: impl IntoIterator<Item = u64>,
+    ) -> &mut Self {
+        self.custom_types
+            .add_type_with_functions::<T>(name, functions);
+        self
+    }
     /// Map a custom type to a friendly display name.
     ///
     /// ```
@@ -818,45 +924,87 @@ impl Module {
             .and_then(|c| c.get(&hash_var).cloned())
     }
 
+    /// Calculate the hashes of a script-defined function, one for each number of arguments that
+    /// it accepts (from the number of required parameters to the number of fixed parameters).
+    ///
+    /// A variadic function has an additional hash that does not depend on the number of
+    /// parameters, with the total number of parameters (including the rest parameter).
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    fn calc_script_fn_hashes(
+        fn_def: &crate::ast::ScriptFnDef,
+    ) -> impl Iterator<Item = (usize, u64)> + '_ {
+        let num_fixed = fn_def.num_fixed_params();
+
+        (fn_def.num_required_params()..=fn_def.params.len()).map(move |num_params| {
+            let hash_script = if num_params <= num_fixed {
+                crate::calc_fn_hash(None, &fn_def.name, num_params)
+            } else {
+                crate::calc_variadic_fn_hash(None, &fn_def.name)
+            };
+            #[cfg(not(feature = "no_object"))]
+            let hash_script = fn_def.this_type.as_ref().map_or(hash_script, |this_type| {
+                crate::calc_typed_method_hash(hash_script, this_type)
+            });
+            (num_params, hash_script)
+        })
+    }
+
     /// Set a script-defined function into the [`Module`].
     ///
     /// If there is an existing function of the same name and number of arguments, it is replaced.
+    ///
+    /// A function with optional parameters (i.e. parameters with default values) is set once for
+    /// each number of arguments that it accepts.  A variadic function (i.e. with a rest parameter)
+    /// is also set under a hash that does not depend on the number of parameters.  The hash for the
+    /// total number of parameters (or the variadic hash) is returned.
     #[cfg(not(feature = "no_function"))]
     #[inline]
     pub fn set_script_fn(&mut self, fn_def: impl Into<Shared<crate::ast::ScriptFnDef>>) -> u64 {
         let fn_def = fn_def.into();
 
         // None + function name + number of arguments.
-        let namespace = FnNamespace::Internal;
-        let num_params = fn_def.params.len();
-        let hash_script = crate::calc_fn_hash(None, &fn_def.name, num_params);
         #[cfg(not(feature = "no_object"))]
-        let (hash_script, namespace) =
-            fn_def
-                .this_type
-                .as_ref()
-                .map_or((hash_script, namespace), |this_type| {
-                    (
-                        crate::calc_typed_method_hash(hash_script, this_type),
-                        FnNamespace::Global,
-                    )
-                });
+        let namespace = if fn_def.this_type.is_some() {
+            FnNamespace::Global
+        } else {
+            FnNamespace::Internal
+        };
+        #[cfg(feature = "no_object")]
+        let namespace = FnNamespace::Internal;
 
-        // Catch hash collisions in testing environment only.
-        #[cfg(feature = "testing-environ")]
-        if let Some(f) = self.functions.as_ref().and_then(|f| f.get(&hash_script)) {
-            panic!(
-                "Hash {} already exists when registering function {:#?}:\n{:#?}",
-                hash_script, fn_def, f
-            );
-        }
+        let functions = self
+            .functions
+            .get_or_insert_with(|| new_hash_map(FN_MAP_SIZE));
 
         #[cfg(feature = "metadata")]
-        let params_info = fn_def.params.iter().map(Into::into).collect();
+        let num_fixed = fn_def.num_fixed_params();
+        let mut hash_fn = 0;
 
-        self.functions
-            .get_or_insert_with(|| new_hash_map(FN_MAP_SIZE))
-            .insert(
+        for (num_params, hash_script) in Self::calc_script_fn_hashes(&fn_def) {
+            // Catch hash collisions in testing environment only.
+            #[cfg(feature = "testing-environ")]
+            if let Some(f) = functions.get(&hash_script) {
+                panic!(
+                    "Hash {} already exists when registering function {:#?}:\n{:#?}",
+                    hash_script, fn_def, f
+                );
+            }
+
+            #[cfg(feature = "metadata")]
+            let params_info = fn_def.params[..num_params]
+                .iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    if i < num_fixed {
+                        p.into()
+                    } else {
+                        format!("...{p}").into()
+                    }
+                })
+                .collect();
+
+            functions.insert(
                 hash_script,
                 FuncInfo {
                     metadata: FuncInfoMetadata {
@@ -868,6 +1016,7 @@ impl Module {
                         this_type: fn_def.this_type.clone(),
                         num_params,
                         param_types: <_>::default(),
+                        memo_capacity: 0,
                         #[cfg(feature = "metadata")]
                         params_info,
                         #[cfg(feature = "metadata")]
@@ -876,18 +1025,24 @@ impl Module {
                         comments: <_>::default(),
                     }
                     .into(),
-                    func: fn_def.into(),
+                    func: fn_def.clone().into(),
                 },
             );
 
+            hash_fn = hash_script;
+        }
+
         self.flags
             .remove(ModuleFlags::INDEXED | ModuleFlags::INDEXED_GLOBAL_FUNCTIONS);
 
-        hash_script
+        hash_fn
     }
 
     /// Get a shared reference to the script-defined function in the [`Module`] based on name
     /// and number of parameters.
+    ///
+    /// A variadic function (i.e. with a rest parameter) matches any number of parameters that it
+    /// accepts.
     #[cfg(not(feature = "no_function"))]
     #[inline]
     #[must_use]
@@ -902,6 +1057,13 @@ impl Module {
             lib.values()
                 .find(|&f| f.metadata.num_params == num_params && f.metadata.name == name)
                 .and_then(|f| f.func.get_script_fn_def())
+                .or_else(|| {
+                    // Variadic functions accept any number of arguments above the fixed parameters
+                    lib.values()
+                        .filter(|&f| f.metadata.name == name)
+                        .filter_map(|f| f.func.get_script_fn_def())
+                        .find(|f| f.is_variadic && num_params >= f.num_required_params())
+                })
         })
     }
 
@@ -1099,6 +1261,99 @@ impl Module {
         self
     }
 
+    /// Mark a registered native Rust function as foldable, i.e. pure and deterministic so that
+    /// calls to it with only constant arguments can be evaluated at compile time.
+    ///
+    /// The [`u64`] hash is returned by the [`set_native_fn`][Module::set_native_fn] call.
+    ///
+    /// Foldable functions are evaluated during optimization even under
+    /// [`OptimizationLevel::Simple`][crate::OptimizationLevel::Simple].
+    /// Any error raised during such evaluation leaves the function call in place.
+    ///
+    /// Has no effect on script-defined functions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rhai::{Engine, Module, INT};
+    /// let mut module = Module::new();
+    /// let hash = module.set_native_fn("square", |x: INT| Ok(x * x));
+    /// module.update_fn_foldable(hash, true);
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.register_global_module(module.into());
+    ///
+    /// // The call is replaced by its result at compile time
+    /// let ast = engine.compile("square(7)")?;
+    ///
+    /// assert_eq!(engine.eval_ast::<INT>(&ast)?, 49);
+    /// # Ok::<(), Box<rhai::EvalAltResult>>(())
+    /// ```
+    #[inline]
+    pub fn update_fn_foldable(&mut self, hash_fn: u64, foldable: bool) -> &mut Self {
+        if let Some(f) = self.functions.as_mut().and_then(|m| m.get_mut(&hash_fn)) {
+            f.func.set_foldable(foldable);
+            self.flags
+                .remove(ModuleFlags::INDEXED | ModuleFlags::INDEXED_GLOBAL_FUNCTIONS);
+        }
+        self
+    }
+
+    /// Memoize the results of calls to a registered native Rust function, i.e. cache up to
+    /// `capacity` results keyed by the argument values, so that calls with the same arguments
+    /// during the same evaluation skip the actual call. Set `capacity` to zero to turn off
+    /// memoization.
+    ///
+    /// The [`u64`] hash is returned by the [`set_native_fn`][Module::set_native_fn] call.
+    ///
+    /// The function must be pure and deterministic. Cached results are discarded at the end of
+    /// each evaluation (e.g. each call to [`Engine::eval`][crate::Engine::eval]). When the cache of
+    /// a function is full, it is cleared before caching a new result.
+    ///
+    /// A cache hit still counts as one operation towards
+    /// [`max_operations`][crate::Engine::max_operations].
+    ///
+    /// Calls are not memoized when any argument cannot be hashed (e.g. a custom type), or when the
+    /// function takes its first argument by mutable reference (i.e. it is a method).
+    /// Namespace-qualified calls are also not memoized.
+    ///
+    /// Has no effect on script-defined functions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use rhai::{Engine, Module, INT};
+    /// # use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # use std::sync::Arc;
+    /// let calls = Arc::new(AtomicUsize::new(0));
+    /// let counter = calls.clone();
+    ///
+    /// let mut module = Module::new();
+    /// let hash = module.set_native_fn("square", move |x: INT| {
+    ///     counter.fetch_add(1, Ordering::Relaxed);
+    ///     Ok(x * x)
+    /// });
+    /// module.update_fn_memoization(hash, 100);
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.register_global_module(module.into());
+    ///
+    /// let result = engine.eval::<INT>("let sum = 0; for i in 0..10 { sum += square(7); } sum")?;
+    ///
+    /// assert_eq!(result, 490);
+    /// assert_eq!(calls.load(Ordering::Relaxed), 1);
+    /// # Ok::<(), Box<rhai::EvalAltResult>>(())
+    /// ```
+    #[inline]
+    pub fn update_fn_memoization(&mut self, hash_fn: u64, capacity: usize) -> &mut Self {
+        if let Some(f) = self.functions.as_mut().and_then(|m| m.get_mut(&hash_fn)) {
+            if f.func.is_native() {
+                f.metadata.memo_capacity = capacity;
+            }
+        }
+        self
+    }
+
     /// Remap type ID.
     #[inline]
     #[must_use]
@@ -1114,6 +1369,25 @@ impl Module {
             // Map String to ImmutableString
             return TypeId::of::<ImmutableString>();
         }
+        if type_id == TypeId::of::<&ImmutableString>() {
+            // Map &ImmutableString to ImmutableString
+            return TypeId::of::<ImmutableString>();
+        }
+        #[cfg(not(feature = "no_index"))]
+        if type_id == TypeId::of::<&crate::Array>() {
+            // Map &Array to Array
+            return TypeId::of::<crate::Array>();
+        }
+        #[cfg(not(feature = "no_index"))]
+        if type_id == TypeId::of::<&crate::Blob>() {
+            // Map &Blob to Blob
+            return TypeId::of::<crate::Blob>();
+        }
+        #[cfg(not(feature = "no_object"))]
+        if type_id == TypeId::of::<&crate::Map>() {
+            // Map &Map to Map
+            return TypeId::of::<crate::Map>();
+        }
 
         type_id
     }
@@ -1275,6 +1549,7 @@ impl Module {
                 this_type: None,
                 num_params: param_types.len(),
                 param_types: param_types.into_boxed_slice(),
+                memo_capacity: 0,
                 #[cfg(feature = "metadata")]
                 params_info: param_names.into_boxed_slice(),
                 #[cfg(feature = "metadata")]
@@ -1381,6 +1656,8 @@ impl Module {
                 func: Shared::new(f),
                 has_context: true,
                 is_pure: false,
+                is_foldable: false,
+                borrowed_args: 0,
             },
         )
     }
@@ -1750,16 +2027,20 @@ impl Module {
         )
     }
 
-    /// Look up a native Rust function by hash.
+    /// Look up a native Rust function by hash, together with the number of its results to
+    /// memoize (zero if not memoized).
     ///
     /// The [`u64`] hash is returned by the [`set_native_fn`][Module::set_native_fn] call.
     #[inline]
     #[must_use]
-    pub(crate) fn get_fn(&self, hash_native: u64) -> Option<&CallableFunction> {
+    pub(crate) fn get_fn_with_memo_capacity(
+        &self,
+        hash_native: u64,
+    ) -> Option<(&CallableFunction, usize)> {
         self.functions
             .as_ref()
             .and_then(|m| m.get(&hash_native))
-            .map(|f| &f.func)
+            .map(|f| (&f.func, f.metadata.memo_capacity))
     }
 
     /// Can the particular function with [`Dynamic`] parameter(s) exist in the [`Module`]?
@@ -2047,6 +2328,31 @@ impl Module {
         self.functions.iter().flat_map(StraightHashMap::values)
     }
 
+    /// Get an iterator over the introspection information of all the functions in the [`Module`],
+    /// including native Rust functions, plugin functions and script-defined functions.
+    ///
+    /// The order of the functions is not specified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Dynamic, Module, INT};
+    /// use std::any::TypeId;
+    ///
+    /// let mut module = Module::new();
+    /// module.set_native_fn("calc", |x: INT, y: bool| Ok(if y { x } else { -x }));
+    ///
+    /// let f = module.iter_fn_metadata().find(|f| f.name == "calc").unwrap();
+    ///
+    /// assert_eq!(f.num_params, 2);
+    /// assert_eq!(f.param_types, [TypeId::of::<INT>(), TypeId::of::<bool>()]);
+    /// assert!(!f.is_script);
+    /// ```
+    #[inline]
+    pub fn iter_fn_metadata(&self) -> impl Iterator<Item = FnMetadata<'_>> {
+        self.iter_fn().map(FuncInfo::to_fn_metadata)
+    }
+
     /// Get an iterator over all script-defined functions in the [`Module`].
     ///
     /// Function metadata includes:
@@ -2068,15 +2374,18 @@ impl Module {
             &Shared<crate::ast::ScriptFnDef>,
         ),
     > + '_ {
-        self.iter_fn().filter(|&f| f.func.is_script()).map(|f| {
-            (
-                f.metadata.namespace,
-                f.metadata.access,
-                f.metadata.name.as_str(),
-                f.metadata.num_params,
-                f.func.get_script_fn_def().expect("script-defined function"),
-            )
-        })
+        // Skip the additional entries of functions with optional parameters
+        self.iter_fn()
+            .filter_map(|f| match f.func.get_script_fn_def() {
+                Some(fn_def) if f.metadata.num_params == fn_def.params.len() => Some((
+                    f.metadata.namespace,
+                    f.metadata.access,
+                    f.metadata.name.as_str(),
+                    f.metadata.num_params,
+                    fn_def,
+                )),
+                _ => None,
+            })
     }
 
     /// Get an iterator over all script-defined functions in the [`Module`].
@@ -2092,14 +2401,8 @@ impl Module {
     pub fn iter_script_fn_info(
         &self,
     ) -> impl Iterator<Item = (FnNamespace, FnAccess, &str, usize)> {
-        self.iter_fn().filter(|&f| f.func.is_script()).map(|f| {
-            (
-                f.metadata.namespace,
-                f.metadata.access,
-                f.metadata.name.as_str(),
-                f.metadata.num_params,
-            )
-        })
+        self.iter_script_fn()
+            .map(|(namespace, access, name, num_params, ..)| (namespace, access, name, num_params))
     }
 
     /// _(internals)_ Get an iterator over all script-defined functions in the [`Module`].
@@ -2133,6 +2436,10 @@ impl Module {
     /// The entire [`AST`][crate::AST] is encapsulated into each function, allowing functions to
     /// cross-call each other.
     ///
+    /// Imported modules become sub-modules. If any imported module is re-exported via an `export`
+    /// statement (e.g. `import "inner" as inner; export inner as util;`), only the re-exported
+    /// modules become sub-modules, under their aliases.
+    ///
     /// # Example
     ///
     /// ```
@@ -2171,15 +2478,90 @@ impl Module {
     /// In particular, the [`global`][crate::GlobalRuntimeState] parameter allows the entire
     /// calling environment to be encapsulated, including automatic global constants.
     #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
     pub fn eval_ast_as_new_raw(
         engine: &crate::Engine,
         scope: &mut crate::Scope,
         global: &mut crate::eval::GlobalRuntimeState,
         ast: &crate::AST,
+    ) -> RhaiResultOf<Self> {
+        Self::eval_ast_as_new_raw_with_options(
+            engine,
+            scope,
+            global,
+            ast,
+            &AstToModuleOptions::new(),
+        )
+    }
+    /// Create a new [`Module`] by evaluating an [`AST`][crate::AST], exporting only the items
+    /// selected by [`AstToModuleOptions`].
+    ///
+    /// The entire [`AST`][crate::AST] is encapsulated into each function, allowing functions to
+    /// cross-call each other, even those that are not exported.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorVariableNotFound`][crate::EvalAltResult::ErrorVariableNotFound] or
+    /// [`ErrorFunctionNotFound`][crate::EvalAltResult::ErrorFunctionNotFound] if a name in an
+    /// [`ExportFilter::Only`] list is not available for export.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{AstToModuleOptions, Engine, ExportFilter, Module, Scope};
+    ///
+    /// let engine = Engine::new();
+    /// let ast = engine.compile("let answer = 42; let question = 0; export answer; export question;")?;
+    ///
+    /// let options = AstToModuleOptions::new().export_variables(ExportFilter::only(["answer"]));
+    /// let module = Module::eval_ast_as_new_with_options(Scope::new(), &ast, &engine, &options)?;
+    ///
+    /// assert!(module.contains_var("answer"));
+    /// assert!(!module.contains_var("question"));
+    ///
+    /// let options = AstToModuleOptions::new().export_variables(ExportFilter::only(["unknown"]));
+    /// assert!(Module::eval_ast_as_new_with_options(Scope::new(), &ast, &engine, &options).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_module"))]
+    #[inline(always)]
+    pub fn eval_ast_as_new_with_options(
+        scope: crate::Scope,
+        ast: &crate::AST,
+        engine: &crate::Engine,
+        options: &AstToModuleOptions,
+    ) -> RhaiResultOf<Self> {
+        let mut scope = scope;
+        let global = &mut crate::eval::GlobalRuntimeState::new(engine);
+
+        Self::eval_ast_as_new_raw_with_options(engine, &mut scope, global, ast, options)
+    }
+    /// Create a new [`Module`] by evaluating an [`AST`][crate::AST], exporting only the items
+    /// selected by [`AstToModuleOptions`].
+    ///
+    /// The entire [`AST`][crate::AST] is encapsulated into each function, allowing functions to
+    /// cross-call each other, even those that are not exported.
+    ///
+    /// # WARNING - Low Level API
+    ///
+    /// This function is very low level.
+    ///
+    /// In particular, the [`global`][crate::GlobalRuntimeState] parameter allows the entire
+    /// calling environment to be encapsulated, including automatic global constants.
+    #[cfg(not(feature = "no_module"))]
+    pub fn eval_ast_as_new_raw_with_options(
+        engine: &crate::Engine,
+        scope: &mut crate::Scope,
+        global: &mut crate::eval::GlobalRuntimeState,
+        ast: &crate::AST,
+        options: &AstToModuleOptions,
     ) -> RhaiResultOf<Self> {
         // Save global state
         let orig_scope_len = scope.len();
         let orig_imports_len = global.num_imports();
+        let orig_exported_modules_len = global.exported_modules.len();
         let orig_source = global.source.clone();
 
         #[cfg(not(feature = "no_function"))]
@@ -2196,17 +2578,26 @@ impl Module {
         // Create new module
         let mut module = Self::new();
 
-        // Extra modules left become sub-modules
+        // Extra modules left become sub-modules, unless modules are explicitly re-exported
         let mut imports = Vec::new();
 
         if result.is_ok() {
+            let exported_modules = &global.exported_modules[orig_exported_modules_len..];
+
             global
                 .scan_imports_raw()
                 .skip(orig_imports_len)
                 .for_each(|(k, m)| {
                     imports.push((k.clone(), m.clone()));
-                    module.set_sub_module(k.clone(), m.clone());
+                    if options.include_submodules && exported_modules.is_empty() {
+                        module.set_sub_module(k.clone(), m.clone());
+                    }
                 });
+
+            // Re-exported modules always become sub-modules under their aliases
+            for (alias, m) in exported_modules {
+                module.set_sub_module(alias.clone(), m.clone());
+            }
         }
 
         // Restore global state
@@ -2214,6 +2605,7 @@ impl Module {
         let constants = std::mem::replace(&mut global.constants, orig_constants);
 
         global.truncate_imports(orig_imports_len);
+        global.exported_modules.truncate(orig_exported_modules_len);
 
         #[cfg(not(feature = "no_function"))]
         global.lib.truncate(orig_lib_len);
@@ -2233,6 +2625,7 @@ impl Module {
         });
 
         // Variables with an alias left in the scope become module variables
+        let mut available_vars = Vec::new();
         let mut i = scope.len();
         while i > 0 {
             i -= 1;
@@ -2251,6 +2644,13 @@ impl Module {
                 }
             });
 
+            aliases.retain(|alias| {
+                if !available_vars.contains(alias) {
+                    available_vars.push(alias.clone());
+                }
+                options.export_variables.contains(alias)
+            });
+
             match aliases.len() {
                 0 => (),
                 1 => {
@@ -2288,24 +2688,84 @@ impl Module {
                 FnAccess::Public => true,
                 FnAccess::Private => false,
             })
+            .filter(|&f| options.export_functions.contains(&f.name))
             .for_each(|f| {
-                let hash = module.set_script_fn(f.clone());
-                let f = module.functions.as_mut().unwrap().get_mut(&hash).unwrap();
-
-                // Encapsulate AST environment
-                if let CallableFunction::Script {
-                    environ: ref mut e, ..
-                } = f.func
-                {
-                    *e = Some(environ.clone());
+                module.set_script_fn(f.clone());
+
+                let functions = module.functions.as_mut().unwrap();
+
+                // Encapsulate AST environment (into all versions with optional parameters)
+                for (.., hash) in Self::calc_script_fn_hashes(f) {
+                    if let Some(FuncInfo {
+                        func:
+                            CallableFunction::Script {
+                                environ: ref mut e, ..
+                            },
+                        ..
+                    }) = functions.get_mut(&hash)
+                    {
+                        *e = Some(environ.clone());
+                    }
                 }
             });
 
+        // All names to export must be available
+        let format_available = |names: &[&str]| {
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names
+                    .iter()
+                    .map(|n| format!("`{n}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+        };
+
+        let available_vars: Vec<_> = available_vars.iter().map(|s| s.as_str()).collect();
+
+        if let Some(name) = options.export_variables.find_missing(&available_vars) {
+            return Err(crate::ERR::ErrorVariableNotFound(
+                format!(
+                    "{name} (exported variables: {})",
+                    format_available(&available_vars)
+                ),
+                crate::Position::NONE,
+            )
+            .into());
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        let available_fns = {
+            let mut names: Vec<_> = ast
+                .iter_fn_def()
+                .filter(|f| f.access == FnAccess::Public)
+                .map(|f| f.name.as_str())
+                .collect();
+            names.sort_unstable();
+            names.dedup();
+            names
+        };
+        #[cfg(feature = "no_function")]
+        let available_fns = Vec::new();
+
+        if let Some(name) = options.export_functions.find_missing(&available_fns) {
+            return Err(crate::ERR::ErrorFunctionNotFound(
+                format!(
+                    "{name} (non-private functions: {})",
+                    format_available(&available_fns)
+                ),
+                crate::Position::NONE,
+            )
+            .into());
+        }
+
         module.id = ast.source_raw().cloned();
 
         #[cfg(feature = "metadata")]
         module.set_doc(ast.doc());
 
+        // Index only after the exported items are filtered
         module.build_index();
 
         Ok(module)
@@ -2394,11 +2854,19 @@ impl Module {
                 if f.func.is_script() {
                     #[cfg(not(feature = "no_function"))]
                     {
-                        let hash_script = crate::calc_fn_hash(
-                            path.iter().copied(),
-                            &f.metadata.name,
-                            f.metadata.num_params,
-                        );
+                        let hash_script = match f.func.get_script_fn_def() {
+                            Some(fn_def)
+                                if fn_def.is_variadic
+                                    && f.metadata.num_params == fn_def.params.len() =>
+                            {
+                                crate::calc_variadic_fn_hash(path.iter().copied(), &f.metadata.name)
+                            }
+                            _ => crate::calc_fn_hash(
+                                path.iter().copied(),
+                                &f.metadata.name,
+                                f.metadata.num_params,
+                            ),
+                        };
                         #[cfg(not(feature = "no_object"))]
                         let hash_script = f
                             .metadata
@@ -2577,6 +3045,15 @@ impl Module {
     }
 }
 
+mod options;
+mod signatures;
+
+#[cfg(not(feature = "no_module"))]
+pub use options::{AstToModuleOptions, ExportFilter};
+
+#[cfg(feature = "metadata")]
+pub use signatures::{FnSignatureInfo, FnSignatureKind, FnSignatureOptions, FnSignatureSource};
+
 /// Module containing all built-in [module resolvers][ModuleResolver].
 #[cfg(not(feature = "no_module"))]
 pub mod resolvers;
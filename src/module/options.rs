@@ -0,0 +1,118 @@
+//! Module defining options for creating a [`Module`][super::Module] from an [`AST`][crate::AST].
+#![cfg(not(feature = "no_module"))]
+
+use crate::Identifier;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Filter selecting the items to export when creating a [`Module`][super::Module] from an
+/// [`AST`][crate::AST].
+///
+/// Not available under `no_module`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ExportFilter {
+    /// Export all items.
+    All,
+    /// Export no items.
+    None,
+    /// Export only items with these names.
+    ///
+    /// It is an error if any name is not found among the items available for export.
+    Only(Vec<Identifier>),
+}
+
+impl Default for ExportFilter {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::All
+    }
+}
+
+impl ExportFilter {
+    /// Create an [`ExportFilter`] that exports only items with the specified names.
+    #[inline]
+    #[must_use]
+    pub fn only(names: impl IntoIterator<Item = impl Into<Identifier>>) -> Self {
+        Self::Only(names.into_iter().map(Into::into).collect())
+    }
+    /// Does this [`ExportFilter`] select an item with the specified name?
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::None => false,
+            Self::Only(names) => names.iter().any(|n| n == name),
+        }
+    }
+    /// Find the first name in this [`ExportFilter`] that is not in a list of available names.
+    #[must_use]
+    pub(crate) fn find_missing<'a>(&'a self, available: &[&str]) -> Option<&'a str> {
+        match self {
+            Self::All | Self::None => None,
+            Self::Only(names) => names
+                .iter()
+                .map(Identifier::as_str)
+                .find(|&n| !available.contains(&n)),
+        }
+    }
+}
+
+/// Options for creating a [`Module`][super::Module] from an [`AST`][crate::AST] via
+/// [`Module::eval_ast_as_new_with_options`][super::Module::eval_ast_as_new_with_options].
+///
+/// Not available under `no_module`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct AstToModuleOptions {
+    /// Exported variables to keep as module variables. Default [`ExportFilter::All`].
+    pub export_variables: ExportFilter,
+    /// Non-private functions to keep as module functions. Default [`ExportFilter::All`].
+    pub export_functions: ExportFilter,
+    /// Keep imported modules as sub-modules? Default `true`.
+    ///
+    /// Modules re-exported via `export` statements always become sub-modules.
+    pub include_submodules: bool,
+}
+
+impl Default for AstToModuleOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AstToModuleOptions {
+    /// Create a default [`AstToModuleOptions`], which exports everything.
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            export_variables: ExportFilter::All,
+            export_functions: ExportFilter::All,
+            include_submodules: true,
+        }
+    }
+    /// Set the filter for exported variables.
+    #[inline(always)]
+    #[must_use]
+    pub fn export_variables(mut self, filter: ExportFilter) -> Self {
+        self.export_variables = filter;
+        self
+    }
+    /// Set the filter for non-private functions.
+    #[inline(always)]
+    #[must_use]
+    pub fn export_functions(mut self, filter: ExportFilter) -> Self {
+        self.export_functions = filter;
+        self
+    }
+    /// Set whether to keep imported modules as sub-modules.
+    #[inline(always)]
+    #[must_use]
+    pub const fn include_submodules(mut self, value: bool) -> Self {
+        self.include_submodules = value;
+        self
+    }
+}
@@ -1,5 +1,6 @@
+use crate::eval::GlobalRuntimeState;
 use crate::{
-    Engine, ModuleResolver, Position, RhaiResultOf, SharedModule, StaticVec, ERR,
+    Engine, ModuleResolver, Position, RhaiResultOf, Scope, SharedModule, StaticVec, AST, ERR,
     STATIC_VEC_INLINE_SIZE,
 };
 #[cfg(feature = "no_std")]
@@ -9,6 +10,9 @@ use std::{ops::AddAssign, slice::Iter};
 /// [Module][crate::Module] resolution service that holds a collection of module resolvers,
 /// to be searched in sequential order.
 ///
+/// A module resolver that does not find a module falls back to the next one in the collection.
+/// Any other error (e.g. a parse error in a module script that is found) aborts the search.
+///
 /// # Example
 ///
 /// ```
@@ -145,7 +149,29 @@ impl<M: ModuleResolver + 'static> AddAssign<M> for ModuleResolversCollection {
     }
 }
 
+/// Is an error returned by a [module resolver][ModuleResolver] a "not found" error,
+/// meaning that the next module resolver should be tried?
+#[inline(always)]
+fn is_not_found(err: &ERR) -> bool {
+    matches!(err, ERR::ErrorModuleNotFound(..))
+}
+
+/// Convert a hard error returned by a [module resolver][ModuleResolver] into the error
+/// returned by the [`ModuleResolversCollection`].
+#[inline]
+fn hard_error(err: Box<ERR>) -> Box<ERR> {
+    match *err {
+        ERR::ErrorInModule(_, err, _) => err,
+        _ => err,
+    }
+}
+
 impl ModuleResolver for ModuleResolversCollection {
+    /// Resolve a module based on a path string.
+    ///
+    /// Each module resolver is tried in order.  If a module resolver does not find the module,
+    /// the next one is tried.  Any other error (e.g. a parse error in a module script that is
+    /// found) is returned immediately without trying the remaining module resolvers.
     fn resolve(
         &self,
         engine: &Engine,
@@ -155,15 +181,75 @@ impl ModuleResolver for ModuleResolversCollection {
     ) -> RhaiResultOf<SharedModule> {
         for resolver in &self.0 {
             match resolver.resolve(engine, source_path, path, pos) {
-                Ok(module) => return Ok(module),
-                Err(err) => match *err {
-                    ERR::ErrorModuleNotFound(..) => continue,
-                    ERR::ErrorInModule(_, err, _) => return Err(err),
-                    _ => panic!("ModuleResolver::resolve returns error that is not ErrorModuleNotFound or ErrorInModule"),
-                },
+                Err(err) if is_not_found(&err) => continue,
+                Err(err) => return Err(hard_error(err)),
+                result => return result,
             }
         }
 
         Err(ERR::ErrorModuleNotFound(path.into(), pos).into())
     }
+
+    fn resolve_raw(
+        &self,
+        engine: &Engine,
+        global: &mut GlobalRuntimeState,
+        scope: &mut Scope,
+        path: &str,
+        pos: Position,
+    ) -> RhaiResultOf<SharedModule> {
+        for resolver in &self.0 {
+            match resolver.resolve_raw(engine, global, scope, path, pos) {
+                Err(err) if is_not_found(&err) => continue,
+                Err(err) => return Err(hard_error(err)),
+                result => return result,
+            }
+        }
+
+        Err(ERR::ErrorModuleNotFound(path.into(), pos).into())
+    }
+
+    /// Resolve an `AST` based on a path string.
+    ///
+    /// Each module resolver is tried in order, in the same way as [`resolve`][Self::resolve].
+    ///
+    /// If a module resolver does not support resolving an `AST` but serves the module, [`None`]
+    /// is returned so that the module resolved by that module resolver is not shadowed by one
+    /// later in the collection.
+    fn resolve_ast(
+        &self,
+        engine: &Engine,
+        source_path: Option<&str>,
+        path: &str,
+        pos: Position,
+    ) -> Option<RhaiResultOf<AST>> {
+        for resolver in &self.0 {
+            match resolver.resolve_ast(engine, source_path, path, pos) {
+                Some(Err(err)) if is_not_found(&err) => continue,
+                Some(Err(err)) => return Some(Err(hard_error(err))),
+                Some(result) => return Some(result),
+                None => match resolver.resolve(engine, source_path, path, pos) {
+                    Err(err) if is_not_found(&err) => continue,
+                    Err(err) => return Some(Err(hard_error(err))),
+                    Ok(..) => return None,
+                },
+            }
+        }
+
+        Some(Err(ERR::ErrorModuleNotFound(path.into(), pos).into()))
+    }
+
+    /// Get the source path of a module based on a path string.
+    ///
+    /// Returns the source path from the first module resolver that serves the module.
+    fn resolve_source_path(
+        &self,
+        engine: &Engine,
+        source_path: Option<&str>,
+        path: &str,
+    ) -> Option<String> {
+        self.0
+            .iter()
+            .find_map(|r| r.resolve_source_path(engine, source_path, path))
+    }
 }
@@ -11,11 +11,41 @@ use crate::{
 use std::{
     collections::BTreeMap,
     io::Error as IoError,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
 };
 
 pub const RHAI_SCRIPT_EXTENSION: &str = "rhai";
 
+/// Modification time and size of a script file, used to detect changes.
+type FileStamp = (SystemTime, u64);
+
+/// Get the [`FileStamp`] of a script file, if available.
+#[inline]
+#[must_use]
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Does a path start with `./` or `../`, i.e. is it relative to the importing script?
+#[inline]
+#[must_use]
+fn is_relative_to_source(path: &Path) -> bool {
+    matches!(
+        path.components().next(),
+        Some(Component::CurDir | Component::ParentDir)
+    )
+}
+
+/// Is an error caused by a script file that does not exist?
+#[inline]
+#[must_use]
+fn is_file_not_found(err: &(dyn std::error::Error + 'static)) -> bool {
+    err.downcast_ref::<IoError>()
+        .map_or(false, |err| err.kind() == std::io::ErrorKind::NotFound)
+}
+
 /// A [module][Module] resolution service that loads [module][Module] script files from the file system.
 ///
 /// ## Caching
@@ -26,6 +56,21 @@ pub const RHAI_SCRIPT_EXTENSION: &str = "rhai";
 /// Use [`clear_cache`][FileModuleResolver::clear_cache] or
 /// [`clear_cache_for_path`][FileModuleResolver::clear_cache_for_path] to clear the internal cache.
 ///
+/// Turn on [`set_check_timestamps`][FileModuleResolver::set_check_timestamps] to reload script
+/// files that have changed on disk since they were cached (e.g. for live reloading).
+///
+/// ## Relative paths
+///
+/// Paths starting with `./` or `../` are resolved relative to the directory of the script that
+/// contains the `import` statement, so a library of nested script files can be relocated as a
+/// whole.  If the importing script has no source (e.g. a script that is not loaded from a file),
+/// such paths fall back to the base path.
+///
+/// Other relative paths are resolved relative to the base path, or to the directory of the
+/// importing script if there is no base path.
+///
+/// For this to work, the source of each loaded [module][Module] is the full path of its script file.
+///
 /// ## Namespace
 ///
 /// When a function within a script file module is called, all functions defined within the same
@@ -51,8 +96,9 @@ pub struct FileModuleResolver {
     base_path: Option<PathBuf>,
     extension: Identifier,
     cache_enabled: bool,
+    check_timestamps: bool,
     scope: Scope<'static>,
-    cache: Locked<BTreeMap<PathBuf, SharedModule>>,
+    cache: Locked<BTreeMap<PathBuf, (SharedModule, Option<FileStamp>)>>,
 }
 
 impl Default for FileModuleResolver {
@@ -131,6 +177,7 @@ impl FileModuleResolver {
             base_path: None,
             extension: extension.into(),
             cache_enabled: true,
+            check_timestamps: false,
             cache: BTreeMap::new().into(),
             scope: Scope::new(),
         }
@@ -161,6 +208,7 @@ impl FileModuleResolver {
             base_path: Some(path.into()),
             extension: extension.into(),
             cache_enabled: true,
+            check_timestamps: false,
             cache: BTreeMap::new().into(),
             scope: Scope::new(),
         }
@@ -232,6 +280,24 @@ impl FileModuleResolver {
         self.cache_enabled
     }
 
+    /// Enable/disable checking the timestamps of cached script files.
+    ///
+    /// When enabled, the script file of a cached module is checked (via its modification time and
+    /// size) each time the module is resolved, and reloaded if it has changed.
+    ///
+    /// Default is `false`.
+    #[inline(always)]
+    pub fn set_check_timestamps(&mut self, enable: bool) -> &mut Self {
+        self.check_timestamps = enable;
+        self
+    }
+    /// Are the timestamps of cached script files checked?
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_check_timestamps(&self) -> bool {
+        self.check_timestamps
+    }
+
     /// Is a particular path cached?
     #[inline]
     #[must_use]
@@ -255,22 +321,48 @@ impl FileModuleResolver {
     pub fn clear_cache_for_path(&mut self, path: impl AsRef<Path>) -> Option<SharedModule> {
         locked_write(&self.cache)
             .remove_entry(path.as_ref())
-            .map(|(.., v)| v)
+            .map(|(.., (v, ..))| v)
     }
     /// Construct a full file path.
+    ///
+    /// `source_path` is the directory of the importing script, if any.
     #[must_use]
     pub fn get_file_path(&self, path: &str, source_path: Option<&Path>) -> PathBuf {
         let path = Path::new(path);
+        let source_path = source_path.filter(|p| !p.as_os_str().is_empty());
 
         let mut file_path;
 
         if path.is_relative() {
-            file_path = self
-                .base_path
-                .clone()
-                .or_else(|| source_path.map(Into::into))
-                .unwrap_or_default();
-            file_path.push(path);
+            match source_path {
+                // Paths starting with `./` or `../` are relative to the importing script
+                Some(source_path) if is_relative_to_source(path) => {
+                    file_path = source_path.to_path_buf();
+
+                    for component in path.components() {
+                        match component {
+                            Component::CurDir => (),
+                            Component::ParentDir
+                                if matches!(
+                                    file_path.components().next_back(),
+                                    Some(Component::Normal(..))
+                                ) =>
+                            {
+                                file_path.pop();
+                            }
+                            _ => file_path.push(component),
+                        }
+                    }
+                }
+                _ => {
+                    file_path = self
+                        .base_path
+                        .clone()
+                        .or_else(|| source_path.map(Into::into))
+                        .unwrap_or_default();
+                    file_path.push(path);
+                }
+            }
         } else {
             file_path = path.into();
         }
@@ -297,29 +389,37 @@ impl FileModuleResolver {
 
         let file_path = self.get_file_path(path, source_path);
 
+        // Stat the file before loading it, so changes made during loading are detected next time
+        let stamp = if self.is_cache_enabled() && self.check_timestamps {
+            file_stamp(&file_path)
+        } else {
+            None
+        };
+
         if self.is_cache_enabled() {
-            if let Some(module) = locked_read(&self.cache).get(&file_path) {
-                return Ok(module.clone());
+            if let Some((module, cached_stamp)) = locked_read(&self.cache).get(&file_path) {
+                if !self.check_timestamps || (stamp.is_some() && *cached_stamp == stamp) {
+                    return Ok(module.clone());
+                }
             }
         }
 
-        let mut ast = engine
+        // The source of the module is the full path of its script file
+        let ast = engine
             .compile_file_with_scope(&self.scope, file_path.clone())
             .map_err(|err| match *err {
-                ERR::ErrorSystem(.., err) if err.is::<IoError>() => {
+                ERR::ErrorSystem(.., ref e) if is_file_not_found(&**e) => {
                     Box::new(ERR::ErrorModuleNotFound(path.to_string(), pos))
                 }
                 _ => Box::new(ERR::ErrorInModule(path.to_string(), err, pos)),
             })?;
 
-        ast.set_source(path);
-
         let m: Shared<_> = Module::eval_ast_as_new_raw(engine, scope, global, &ast)
             .map_err(|err| Box::new(ERR::ErrorInModule(path.to_string(), err, pos)))?
             .into();
 
         if self.is_cache_enabled() {
-            locked_write(&self.cache).insert(file_path, m.clone());
+            locked_write(&self.cache).insert(file_path, (m.clone(), stamp));
         }
 
         Ok(m)
@@ -373,11 +473,27 @@ impl ModuleResolver for FileModuleResolver {
                     ast
                 })
                 .map_err(|err| match *err {
-                    ERR::ErrorSystem(.., err) if err.is::<IoError>() => {
+                    ERR::ErrorSystem(.., ref e) if is_file_not_found(&**e) => {
                         ERR::ErrorModuleNotFound(path.to_string(), pos).into()
                     }
                     _ => ERR::ErrorInModule(path.to_string(), err, pos).into(),
                 }),
         )
     }
+
+    /// Get the full path of the script file of a module based on a path string.
+    ///
+    /// Returns [`None`] if the script file does not exist.
+    fn resolve_source_path(
+        &self,
+        _: &Engine,
+        source_path: Option<&str>,
+        path: &str,
+    ) -> Option<String> {
+        let file_path = self.get_file_path(path, source_path.map(Path::new));
+
+        file_path
+            .is_file()
+            .then(|| file_path.to_string_lossy().into_owned())
+    }
 }
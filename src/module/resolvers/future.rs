@@ -0,0 +1,176 @@
+use crate::func::{locked_read, locked_write};
+use crate::{Engine, Identifier, Locked, ModuleResolver, Position, RhaiResultOf, SharedModule};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{collections::BTreeMap, fmt, future::Future, pin::Pin};
+
+/// A boxed [`Future`] that loads a [module][crate::Module].
+pub type ModuleFuture = Pin<Box<dyn Future<Output = RhaiResultOf<SharedModule>>>>;
+
+/// Function that starts loading a [module][crate::Module] based on a path string.
+#[cfg(not(feature = "sync"))]
+pub type ModuleLoaderFn = dyn Fn(&str) -> ModuleFuture;
+/// Function that starts loading a [module][crate::Module] based on a path string.
+#[cfg(feature = "sync")]
+pub type ModuleLoaderFn = dyn Fn(&str) -> ModuleFuture + Send + Sync;
+
+/// Function that runs a [`ModuleFuture`] to completion, blocking until it is done.
+#[cfg(not(feature = "sync"))]
+pub type ModuleExecutorFn = dyn Fn(ModuleFuture) -> RhaiResultOf<SharedModule>;
+/// Function that runs a [`ModuleFuture`] to completion, blocking until it is done.
+#[cfg(feature = "sync")]
+pub type ModuleExecutorFn = dyn Fn(ModuleFuture) -> RhaiResultOf<SharedModule> + Send + Sync;
+
+/// A [module][crate::Module] resolution service that loads [modules][crate::Module] from an
+/// asynchronous source (e.g. a database accessed via async Rust).
+///
+/// Module resolution in Rhai is synchronous, so this acts as a bridge: the _loader_ function
+/// returns a [`Future`] for a path, which is then run to completion by the _executor_ function
+/// (e.g. `block_on` of an async runtime).
+///
+/// ## Caching
+///
+/// Resolved [modules][crate::Module] are cached by path, so the [`Future`] for each path only runs
+/// once.  Errors are not cached.
+///
+/// Use [`clear_cache`][AsyncModuleResolver::clear_cache] or
+/// [`clear_cache_for_path`][AsyncModuleResolver::clear_cache_for_path] to clear the cache.
+///
+/// # Example
+///
+/// ```
+/// use rhai::{Engine, Module, INT};
+/// use rhai::module_resolvers::AsyncModuleResolver;
+/// # use std::{future::Future, sync::Arc, task::{Context, Poll, Wake, Waker}};
+/// #
+/// # fn block_on<F: Future>(future: F) -> F::Output {
+/// #     struct NoopWaker;
+/// #     impl Wake for NoopWaker { fn wake(self: Arc<Self>) {} }
+/// #     let waker = Waker::from(Arc::new(NoopWaker));
+/// #     let mut cx = Context::from_waker(&waker);
+/// #     let mut future = Box::pin(future);
+/// #     loop {
+/// #         if let Poll::Ready(value) = future.as_mut().poll(&mut cx) { return value; }
+/// #     }
+/// # }
+///
+/// let resolver = AsyncModuleResolver::new(
+///     // Start loading the module
+///     |path| {
+///         let path = path.to_string();
+///         Box::pin(async move {
+///             let mut module = Module::new();
+///             module.set_var("name", path);
+///             Ok(module.into())
+///         })
+///     },
+///     // Run the future to completion
+///     |future| block_on(future),
+/// );
+///
+/// let mut engine = Engine::new();
+/// engine.set_module_resolver(resolver);
+///
+/// let name = engine.eval::<String>(r#"import "hello" as m; m::name"#).unwrap();
+///
+/// assert_eq!(name, "hello");
+/// ```
+pub struct AsyncModuleResolver {
+    loader: Box<ModuleLoaderFn>,
+    executor: Box<ModuleExecutorFn>,
+    cache: Locked<BTreeMap<Identifier, SharedModule>>,
+}
+
+impl fmt::Debug for AsyncModuleResolver {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncModuleResolver")
+            .field("cache", &self.cache)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AsyncModuleResolver {
+    /// Create a new [`AsyncModuleResolver`] with a loader function and an executor function.
+    ///
+    /// The loader function returns a [`Future`] that loads the [module][crate::Module] for a path.
+    /// If the module does not exist, the [`Future`] should return
+    /// [`ErrorModuleNotFound`][crate::EvalAltResult::ErrorModuleNotFound].
+    ///
+    /// The executor function runs the [`Future`] to completion (e.g. via `block_on`).
+    #[cfg(not(feature = "sync"))]
+    #[inline(always)]
+    #[must_use]
+    pub fn new(
+        loader: impl Fn(&str) -> ModuleFuture + 'static,
+        executor: impl Fn(ModuleFuture) -> RhaiResultOf<SharedModule> + 'static,
+    ) -> Self {
+        Self {
+            loader: Box::new(loader),
+            executor: Box::new(executor),
+            cache: Locked::new(BTreeMap::new()),
+        }
+    }
+    /// Create a new [`AsyncModuleResolver`] with a loader function and an executor function.
+    ///
+    /// The loader function returns a [`Future`] that loads the [module][crate::Module] for a path.
+    /// If the module does not exist, the [`Future`] should return
+    /// [`ErrorModuleNotFound`][crate::EvalAltResult::ErrorModuleNotFound].
+    ///
+    /// The executor function runs the [`Future`] to completion (e.g. via `block_on`).
+    #[cfg(feature = "sync")]
+    #[inline(always)]
+    #[must_use]
+    pub fn new(
+        loader: impl Fn(&str) -> ModuleFuture + Send + Sync + 'static,
+        executor: impl Fn(ModuleFuture) -> RhaiResultOf<SharedModule> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            loader: Box::new(loader),
+            executor: Box::new(executor),
+            cache: Locked::new(BTreeMap::new()),
+        }
+    }
+    /// Is a particular path cached?
+    #[inline]
+    #[must_use]
+    pub fn is_cached(&self, path: &str) -> bool {
+        locked_read(&self.cache).contains_key(path)
+    }
+    /// Empty the internal cache.
+    #[inline]
+    pub fn clear_cache(&mut self) -> &mut Self {
+        locked_write(&self.cache).clear();
+        self
+    }
+    /// Remove the specified path from internal cache.
+    ///
+    /// The next time this path is resolved, the [module][crate::Module] will be loaded once again.
+    #[inline]
+    #[must_use]
+    pub fn clear_cache_for_path(&mut self, path: &str) -> Option<SharedModule> {
+        locked_write(&self.cache).remove(path)
+    }
+}
+
+impl ModuleResolver for AsyncModuleResolver {
+    fn resolve(
+        &self,
+        _: &Engine,
+        _: Option<&str>,
+        path: &str,
+        _: Position,
+    ) -> RhaiResultOf<SharedModule> {
+        if let Some(module) = locked_read(&self.cache).get(path) {
+            return Ok(module.clone());
+        }
+
+        // The cache is not locked while the future runs, so that the module can import others.
+        let module = (self.executor)((self.loader)(path))?;
+
+        locked_write(&self.cache).insert(path.into(), module.clone());
+
+        Ok(module)
+    }
+}
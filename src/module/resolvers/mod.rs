@@ -7,6 +7,7 @@ use std::prelude::v1::*;
 mod collection;
 mod dummy;
 mod file;
+mod future;
 mod stat;
 
 pub use collection::ModuleResolversCollection;
@@ -14,6 +15,7 @@ pub use dummy::DummyModuleResolver;
 #[cfg(not(feature = "no_std"))]
 #[cfg(any(not(target_family = "wasm"), not(target_os = "unknown")))]
 pub use file::FileModuleResolver;
+pub use future::{AsyncModuleResolver, ModuleExecutorFn, ModuleFuture, ModuleLoaderFn};
 pub use stat::StaticModuleResolver;
 
 /// Trait that encapsulates a module resolution service.
@@ -64,4 +66,20 @@ pub trait ModuleResolver: SendSync {
     ) -> Option<RhaiResultOf<AST>> {
         None
     }
+
+    /// Get the source path (e.g. the full file path) of a module based on a path string.
+    ///
+    /// Returns [`None`] (default) if the module is not found or has no source path.
+    ///
+    /// This is useful, for example, for debuggers to locate the source of a module.
+    #[allow(unused_variables)]
+    #[must_use]
+    fn resolve_source_path(
+        &self,
+        engine: &Engine,
+        source: Option<&str>,
+        path: &str,
+    ) -> Option<String> {
+        None
+    }
 }
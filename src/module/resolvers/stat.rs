@@ -166,4 +166,12 @@ impl ModuleResolver for StaticModuleResolver {
             .cloned()
             .ok_or_else(|| ERR::ErrorModuleNotFound(path.into(), pos).into())
     }
+
+    /// Get the source path of a module based on a path string.
+    ///
+    /// Modules are held in memory, so the source path is the path string itself.
+    #[inline]
+    fn resolve_source_path(&self, _: &Engine, _: Option<&str>, path: &str) -> Option<String> {
+        self.0.contains_key(path).then(|| path.into())
+    }
 }
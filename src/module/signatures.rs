@@ -0,0 +1,388 @@
+//! Module defining structured function signatures of a [`Module`], for documentation.
+#![cfg(feature = "metadata")]
+
+use super::{FnAccess, FuncInfo, Module};
+use crate::api::formatting::{format_type, map_std_type_name};
+#[cfg(not(feature = "no_object"))]
+use crate::engine::{FN_GET, FN_SET};
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+use crate::engine::{FN_IDX_GET, FN_IDX_SET};
+use crate::tokenizer::is_valid_function_name;
+use crate::{Identifier, SmartString};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{borrow::Cow, fmt};
+
+/// _(metadata)_ Kind of a function in a [`FnSignatureInfo`].
+/// Exported under the `metadata` feature only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum FnSignatureKind {
+    /// Normal function.
+    Function,
+    /// Operator function (e.g. `+`).
+    Operator,
+    /// Property getter. The name is the property name.
+    Getter,
+    /// Property setter. The name is the property name.
+    Setter,
+    /// Indexer getter. The name is `[]`.
+    IndexGetter,
+    /// Indexer setter. The name is `[]`.
+    IndexSetter,
+}
+
+/// _(metadata)_ Where a function in a [`FnSignatureInfo`] comes from.
+/// Exported under the `metadata` feature only.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FnSignatureSource {
+    /// The [`Module`] that the signatures are generated from.
+    Module,
+    /// The global namespace of the [`Engine`][crate::Engine].
+    Global,
+    /// A static module registered into the [`Engine`][crate::Engine].
+    /// Wrapped value is the name of the static module.
+    StaticModule(Identifier),
+    /// A package registered into the [`Engine`][crate::Engine].
+    /// Wrapped value is the ID of the package module, if any.
+    Package(Option<Identifier>),
+    /// Script-defined functions in an [`AST`][crate::AST].
+    Script,
+}
+
+/// _(metadata)_ Structured signature of a function in a [`Module`].
+/// Exported under the `metadata` feature only.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct FnSignatureInfo {
+    /// Function name.
+    ///
+    /// For property getters and setters, this is the name of the property.
+    /// For indexer getters and setters, this is `[]`.
+    pub name: Identifier,
+    /// Kind of the function.
+    pub kind: FnSignatureKind,
+    /// Path of sub-modules (if any) that the function is in, e.g. `["foo", "bar"]` for
+    /// functions called as `foo::bar::func()`.
+    pub namespace: Vec<Identifier>,
+    /// Where the function comes from.
+    pub source: FnSignatureSource,
+    /// Function access mode.
+    pub access: FnAccess,
+    /// Is the function script-defined?
+    pub is_script: bool,
+    /// Parameter names and types.
+    ///
+    /// Parameter names are `_` and types are [`None`] if not available in the metadata.
+    pub params: Vec<(Identifier, Option<Identifier>)>,
+    /// Return type, if available.
+    pub return_type: Option<Identifier>,
+    /// Doc-comments.
+    pub comments: Vec<SmartString>,
+    /// Signature of the function, in the same format as [`Module::gen_fn_signatures`].
+    pub signature: String,
+}
+
+/// Human-readable form of the signature, e.g. `Foo.name: String` for a property getter.
+///
+/// See [`Engine::gen_fn_signatures`][crate::Engine::gen_fn_signatures] for the format.
+impl fmt::Display for FnSignatureInfo {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.format(|typ| map_std_type_name(typ, false).into()))
+    }
+}
+
+/// _(metadata)_ Options for generating structured function signatures via
+/// [`Module::fn_signatures_grouped`].
+/// Exported under the `metadata` feature only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct FnSignatureOptions {
+    /// Include private functions? Default `false`.
+    pub include_private: bool,
+    /// Include operator functions (e.g. `+`)? Default `true`.
+    pub include_operators: bool,
+    /// Include property getters/setters and indexers? Default `true`.
+    pub include_properties: bool,
+    /// Include functions in sub-modules, recursively? Default `true`.
+    pub include_sub_modules: bool,
+}
+
+impl Default for FnSignatureOptions {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FnSignatureOptions {
+    /// Create a default [`FnSignatureOptions`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            include_private: false,
+            include_operators: true,
+            include_properties: true,
+            include_sub_modules: true,
+        }
+    }
+    /// Set whether to include private functions.
+    #[inline(always)]
+    #[must_use]
+    pub const fn include_private(mut self, value: bool) -> Self {
+        self.include_private = value;
+        self
+    }
+    /// Set whether to include operator functions (e.g. `+`).
+    #[inline(always)]
+    #[must_use]
+    pub const fn include_operators(mut self, value: bool) -> Self {
+        self.include_operators = value;
+        self
+    }
+    /// Set whether to include property getters/setters and indexers.
+    #[inline(always)]
+    #[must_use]
+    pub const fn include_properties(mut self, value: bool) -> Self {
+        self.include_properties = value;
+        self
+    }
+    /// Set whether to include functions in sub-modules, recursively.
+    #[inline(always)]
+    #[must_use]
+    pub const fn include_sub_modules(mut self, value: bool) -> Self {
+        self.include_sub_modules = value;
+        self
+    }
+}
+
+/// Decode the kind of a function and its display name from its internal name.
+fn decode_fn_name(name: &str) -> (FnSignatureKind, &str) {
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    if name == FN_IDX_GET {
+        return (FnSignatureKind::IndexGetter, "[]");
+    }
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    if name == FN_IDX_SET {
+        return (FnSignatureKind::IndexSetter, "[]");
+    }
+    #[cfg(not(feature = "no_object"))]
+    if let Some(prop) = name.strip_prefix(FN_GET) {
+        return (FnSignatureKind::Getter, prop);
+    }
+    #[cfg(not(feature = "no_object"))]
+    if let Some(prop) = name.strip_prefix(FN_SET) {
+        return (FnSignatureKind::Setter, prop);
+    }
+
+    if is_valid_function_name(name) {
+        (FnSignatureKind::Function, name)
+    } else {
+        (FnSignatureKind::Operator, name)
+    }
+}
+
+impl FnSignatureInfo {
+    /// Create a [`FnSignatureInfo`] from a function.
+    fn new(f: &FuncInfo, namespace: &[Identifier], source: &FnSignatureSource) -> Self {
+        let metadata = &f.metadata;
+        let name = metadata.name.as_str();
+
+        let (kind, name) = decode_fn_name(name);
+
+        let params = if metadata.params_info.is_empty() {
+            (0..metadata.num_params)
+                .map(|_| ("_".into(), None))
+                .collect()
+        } else {
+            metadata
+                .params_info
+                .iter()
+                .map(|param| {
+                    let mut segment = param.splitn(2, ':');
+                    let name = match segment.next().unwrap().trim() {
+                        "" => "_",
+                        s => s,
+                    };
+                    let typ = segment.next().map(|typ| format_type(typ, false).into());
+                    (name.into(), typ)
+                })
+                .collect()
+        };
+
+        let return_type = if f.func.is_script() {
+            None
+        } else {
+            match format_type(&metadata.return_type, true) {
+                s if s.is_empty() => None,
+                s => Some(s.into()),
+            }
+        };
+
+        // Doc-comments of script-defined functions are kept in the function definition
+        #[cfg(not(feature = "no_function"))]
+        let comments = match f.func.get_script_fn_def() {
+            Some(fn_def) if metadata.comments.is_empty() => fn_def.comments.to_vec(),
+            _ => metadata.comments.to_vec(),
+        };
+        #[cfg(feature = "no_function")]
+        let comments = metadata.comments.to_vec();
+
+        Self {
+            name: name.into(),
+            kind,
+            namespace: namespace.to_vec(),
+            source: source.clone(),
+            access: metadata.access,
+            is_script: f.func.is_script(),
+            params,
+            return_type,
+            comments,
+            signature: f.gen_signature(),
+        }
+    }
+}
+
+impl FnSignatureInfo {
+    /// Render the signature in human-readable form, formatting type names via `format_type`.
+    ///
+    /// `&mut` is stripped from type names before formatting. Missing types are shown as `_`.
+    pub(crate) fn format<'a>(&'a self, format_type: impl Fn(&'a str) -> Cow<'a, str>) -> String {
+        let typ = |index: usize| {
+            self.params
+                .get(index)
+                .and_then(|(.., typ)| typ.as_deref())
+                .map_or_else(
+                    || "_".into(),
+                    |t| format_type(t.trim_start_matches("&mut ")),
+                )
+        };
+        let return_type = self.return_type.as_deref().map(&format_type);
+
+        match self.kind {
+            FnSignatureKind::Getter => match return_type {
+                Some(r) => format!("{}.{}: {r}", typ(0), self.name),
+                None => format!("{}.{}", typ(0), self.name),
+            },
+            FnSignatureKind::Setter => format!("{}.{} = {}", typ(0), self.name, typ(1)),
+            FnSignatureKind::IndexGetter => match return_type {
+                Some(r) => format!("{}[{}] -> {r}", typ(0), typ(1)),
+                None => format!("{}[{}]", typ(0), typ(1)),
+            },
+            FnSignatureKind::IndexSetter => format!("{}[{}] = {}", typ(0), typ(1), typ(2)),
+            FnSignatureKind::Function | FnSignatureKind::Operator => {
+                let mut signature = String::new();
+
+                if self.kind == FnSignatureKind::Function {
+                    for ns in &self.namespace {
+                        signature.push_str(ns);
+                        signature.push_str("::");
+                    }
+                }
+
+                signature.push_str(&self.name);
+                signature.push('(');
+
+                for (i, (name, typ)) in self.params.iter().enumerate() {
+                    if i > 0 {
+                        signature.push_str(", ");
+                    }
+                    signature.push_str(name);
+
+                    if let Some(typ) = typ {
+                        signature.push_str(": ");
+
+                        if let Some(t) = typ.strip_prefix("&mut ") {
+                            signature.push_str("&mut ");
+                            signature.push_str(&format_type(t));
+                        } else {
+                            signature.push_str(&format_type(typ));
+                        }
+                    }
+                }
+
+                signature.push(')');
+
+                if let Some(r) = return_type {
+                    signature.push_str(" -> ");
+                    signature.push_str(&r);
+                }
+
+                signature
+            }
+        }
+    }
+}
+
+impl Module {
+    /// _(metadata)_ Generate structured signatures for the functions in the [`Module`].
+    /// Exported under the `metadata` feature only.
+    ///
+    /// Functions are sorted by name, kind and signature, followed by functions in sub-modules
+    /// (if included) in the order of the sub-module names, so the output is deterministic.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{FnSignatureKind, FnSignatureOptions, Module};
+    ///
+    /// let mut module = Module::new();
+    /// module.set_native_fn("calc", |x: i64| Ok(x + 1));
+    /// module.set_getter_fn("value", |x: &mut i64| Ok(*x));
+    ///
+    /// let signatures = module.fn_signatures_grouped(FnSignatureOptions::new());
+    ///
+    /// assert_eq!(signatures.len(), 2);
+    /// assert_eq!(signatures[0].name, "calc");
+    /// assert_eq!(signatures[1].name, "value");
+    /// assert_eq!(signatures[1].kind, FnSignatureKind::Getter);
+    /// ```
+    #[must_use]
+    pub fn fn_signatures_grouped(&self, options: FnSignatureOptions) -> Vec<FnSignatureInfo> {
+        let mut signatures = Vec::new();
+        self.collect_fn_signatures(
+            options,
+            &mut Vec::new(),
+            &FnSignatureSource::Module,
+            &mut signatures,
+        );
+        signatures
+    }
+
+    /// Collect structured signatures for the functions in the [`Module`] and its sub-modules.
+    pub(crate) fn collect_fn_signatures(
+        &self,
+        options: FnSignatureOptions,
+        namespace: &mut Vec<Identifier>,
+        source: &FnSignatureSource,
+        signatures: &mut Vec<FnSignatureInfo>,
+    ) {
+        let mut list = self
+            .iter_fn()
+            .filter(|f| options.include_private || f.metadata.access == FnAccess::Public)
+            .map(|f| FnSignatureInfo::new(f, namespace, source))
+            .filter(|f| match f.kind {
+                FnSignatureKind::Function => true,
+                FnSignatureKind::Operator => options.include_operators,
+                FnSignatureKind::Getter
+                | FnSignatureKind::Setter
+                | FnSignatureKind::IndexGetter
+                | FnSignatureKind::IndexSetter => options.include_properties,
+            })
+            .collect::<Vec<_>>();
+
+        list.sort_by(|a, b| (&a.name, a.kind, &a.signature).cmp(&(&b.name, b.kind, &b.signature)));
+        signatures.extend(list);
+
+        if options.include_sub_modules {
+            for (name, m) in self.iter_sub_modules_raw() {
+                namespace.push(name.clone());
+                m.collect_fn_signatures(options, namespace, source, signatures);
+                namespace.pop();
+            }
+        }
+    }
+}
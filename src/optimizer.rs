@@ -2,12 +2,12 @@
 #![cfg(not(feature = "no_optimize"))]
 
 use crate::ast::{
-    ASTFlags, Expr, FlowControl, OpAssignment, Stmt, StmtBlock, StmtBlockContainer,
+    ASTFlags, ASTNode, Expr, FlowControl, Ident, OpAssignment, Stmt, StmtBlock, StmtBlockContainer,
     SwitchCasesCollection,
 };
 use crate::engine::{
-    KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CURRY, KEYWORD_PRINT,
-    KEYWORD_TYPE_OF, OP_NOT,
+    KEYWORD_DEBUG, KEYWORD_EVAL, KEYWORD_FN_PTR, KEYWORD_FN_PTR_CURRY, KEYWORD_IS_DEF_VAR,
+    KEYWORD_PRINT, KEYWORD_TYPE_OF, OP_NOT,
 };
 use crate::eval::{Caches, GlobalRuntimeState};
 use crate::func::builtin::get_builtin_binary_op_fn;
@@ -15,8 +15,8 @@ use crate::func::hashing::get_hasher;
 use crate::module::ModuleFlags;
 use crate::tokenizer::Token;
 use crate::{
-    calc_fn_hash, calc_fn_hash_full, Dynamic, Engine, FnArgsVec, FnPtr, ImmutableString, Position,
-    Scope, AST,
+    calc_fn_hash, calc_fn_hash_full, Dynamic, Engine, ExclusiveRange, FnArgsVec, FnPtr,
+    ImmutableString, InclusiveRange, Position, Scope, AST, INT,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -36,7 +36,8 @@ use std::{
 pub enum OptimizationLevel {
     /// No optimization performed.
     None,
-    /// Only perform simple optimizations without evaluating functions.
+    /// Only perform simple optimizations without evaluating functions,
+    /// except for those explicitly marked as foldable.
     #[default]
     Simple,
     /// Full optimizations performed, including evaluating functions.
@@ -69,6 +70,9 @@ impl<'a> OptimizerState<'a> {
     pub fn new(
         engine: &'a Engine,
         lib: &'a [crate::SharedModule],
+        #[cfg(not(feature = "no_module"))] resolver: Option<
+            crate::Shared<crate::module::resolvers::StaticModuleResolver>,
+        >,
         optimization_level: OptimizationLevel,
     ) -> Self {
         let mut _global = GlobalRuntimeState::new(engine);
@@ -78,6 +82,10 @@ impl<'a> OptimizerState<'a> {
         {
             _global.lib = _lib.to_vec();
         }
+        #[cfg(not(feature = "no_module"))]
+        {
+            _global.embedded_module_resolver = resolver;
+        }
 
         Self {
             is_dirty: false,
@@ -125,6 +133,50 @@ impl<'a> OptimizerState<'a> {
             .find(|(n, _)| n == name)
             .and_then(|(_, value)| value.as_ref())
     }
+    /// Add an imported module to the stack, resolving it if its path is a constant found in the
+    /// embedded module resolver.
+    ///
+    /// Modules that cannot be resolved are added as empty modules so that they shadow static
+    /// modules with the same name.
+    #[cfg(not(feature = "no_module"))]
+    pub fn push_import(&mut self, path: &Expr, name: ImmutableString) {
+        use crate::ModuleResolver;
+
+        let module = match (path, &self.global.embedded_module_resolver) {
+            (Expr::StringConstant(path, ..), Some(resolver))
+                if self.engine.fold_module_constants() =>
+            {
+                resolver
+                    .resolve(self.engine, None, path, Position::NONE)
+                    .ok()
+            }
+            _ => None,
+        };
+
+        self.global
+            .push_import(name, module.unwrap_or_else(|| crate::Module::new().into()));
+    }
+    /// Look up a constant in a statically-known module.
+    #[cfg(not(feature = "no_module"))]
+    pub fn find_module_constant(
+        &self,
+        namespace: &crate::ast::Namespace,
+        hash_var: u64,
+    ) -> Option<Dynamic> {
+        if !self.propagate_constants || !self.engine.fold_module_constants() {
+            return None;
+        }
+
+        let root = namespace.root();
+
+        self.global
+            .find_import(root)
+            .map_or_else(
+                || self.engine.get_global_sub_module(root),
+                |index| self.global.get_shared_import(index),
+            )
+            .and_then(|module| module.get_qualified_var(hash_var))
+    }
     /// Call a registered function
     #[inline]
     pub fn call_fn_with_const_args(
@@ -147,6 +199,86 @@ impl<'a> OptimizerState<'a> {
             .ok()
             .map(|(v, ..)| v)
     }
+    /// Call a registered function only if it is marked as foldable.
+    #[inline]
+    pub fn call_foldable_fn_with_const_args(
+        &mut self,
+        fn_name: &str,
+        arg_values: &mut [Dynamic],
+    ) -> Option<Dynamic> {
+        let hash = calc_fn_hash(None, fn_name, arg_values.len());
+        let args = &mut arg_values.iter_mut().collect::<FnArgsVec<_>>();
+        let local_entry = &mut None;
+
+        let is_foldable = self
+            .engine
+            .resolve_fn(
+                &self.global,
+                &mut self.caches,
+                local_entry,
+                None,
+                hash,
+                Some(args),
+                true,
+            )
+            .map_or(false, |entry| entry.func.is_foldable());
+
+        if !is_foldable {
+            return None;
+        }
+
+        self.engine
+            .exec_native_fn_call(
+                &mut self.global,
+                &mut self.caches,
+                fn_name,
+                None,
+                hash,
+                args,
+                false,
+                Position::NONE,
+            )
+            .ok()
+            .map(|(v, ..)| v)
+    }
+    /// Call a namespace-qualified registered function only if it is marked as foldable.
+    #[cfg(not(feature = "no_module"))]
+    pub fn call_qualified_foldable_fn_with_const_args(
+        &mut self,
+        x: &crate::ast::FnCallExpr,
+        arg_values: &[Dynamic],
+    ) -> Option<Dynamic> {
+        let module = crate::eval::search_imports(self.engine, &self.global, &x.namespace)?;
+        let hash = x.hashes.native();
+
+        // Script-defined functions in the namespace override native functions
+        if module.contains_qualified_fn(hash) {
+            return None;
+        }
+
+        let hash_qualified_fn = calc_fn_hash_full(hash, arg_values.iter().map(Dynamic::type_id));
+
+        if !module
+            .get_qualified_fn(hash_qualified_fn)
+            .map_or(false, crate::func::CallableFunction::is_foldable)
+        {
+            return None;
+        }
+
+        self.engine
+            .make_qualified_function_call(
+                &mut self.global,
+                &mut self.caches,
+                &mut Scope::new(),
+                None,
+                &x.namespace,
+                &x.name,
+                &x.args,
+                hash,
+                Position::NONE,
+            )
+            .ok()
+    }
 }
 
 /// Optimize a block of [statements][Stmt].
@@ -195,6 +327,8 @@ fn optimize_stmt_block(
         state.clear_dirty();
 
         let orig_constants_len = state.variables.len(); // Original number of constants in the state, for restore later
+        #[cfg(not(feature = "no_module"))]
+        let orig_imports_len = state.global.num_imports();
         let orig_propagate_constants = state.propagate_constants;
 
         // Remove everything following control flow breaking statements
@@ -360,6 +494,8 @@ fn optimize_stmt_block(
 
         // Pop the stack and remove all the local constants
         state.rewind_var(orig_constants_len);
+        #[cfg(not(feature = "no_module"))]
+        state.global.truncate_imports(orig_imports_len);
         state.propagate_constants = orig_propagate_constants;
 
         if !state.is_dirty() {
@@ -385,6 +521,173 @@ impl StmtBlock {
     }
 }
 
+/// Unroll a `for` loop over a small constant integer range into a sequence of copies of the
+/// loop body, with the loop variable (and counter, if any) substituted as constants.
+///
+/// Returns `None` if the loop cannot be unrolled, e.g. when the body contains `break`/`continue`
+/// or modifies the loop variable.
+fn unroll_for_loop(
+    x: &(Ident, Option<Ident>, Option<Ident>, FlowControl),
+    pos: Position,
+    state: &mut OptimizerState,
+    preserve_result: bool,
+) -> Option<Stmt> {
+    /// Get the name of the variable at the root of a property/index chain.
+    fn root_var_name(expr: &Expr) -> Option<&str> {
+        match expr {
+            Expr::Variable(x, ..) if x.1.is_empty() => Some(&x.3),
+            Expr::Dot(x, ..) | Expr::Index(x, ..) => root_var_name(&x.lhs),
+            _ => None,
+        }
+    }
+
+    let (var, counter, var3, FlowControl { expr, body, .. }) = x;
+    let threshold = state.engine.loop_unroll_threshold;
+
+    // Variable definition filters must not see the placeholder variables
+    if threshold == 0 || !expr.is_constant() || state.engine.def_var_filter.is_some() {
+        return None;
+    }
+
+    // Ranges yield numbers, which cannot be destructured into three variables
+    if var3.is_some() {
+        return None;
+    }
+
+    let value = expr.get_literal_value()?;
+    let iter_type = value.type_id();
+
+    let values: Vec<_> = if value.is::<ExclusiveRange>() {
+        value
+            .cast::<ExclusiveRange>()
+            .take(threshold + 1)
+            .map(Dynamic::from_int)
+            .collect()
+    } else if value.is::<InclusiveRange>() {
+        value
+            .cast::<InclusiveRange>()
+            .take(threshold + 1)
+            .map(Dynamic::from_int)
+            .collect()
+    } else {
+        return None;
+    };
+
+    if values.len() > threshold {
+        return None;
+    }
+
+    // The range iterator must not be overridden by a custom iterator
+    if !state
+        .engine
+        .global_modules
+        .iter()
+        .find(|m| m.contains_iter(iter_type))
+        .map_or(false, |m| m.flags.contains(ModuleFlags::STANDARD_LIB))
+    {
+        return None;
+    }
+
+    let is_loop_var =
+        |name: &str| name == var.name || counter.as_ref().map_or(false, |c| name == c.name);
+
+    // Check that the loop body can be duplicated with the loop variables as constants
+    let can_unroll = body.iter().all(|stmt| {
+        stmt.walk(&mut Vec::new(), &mut |path: &[ASTNode]| {
+            match path.last().unwrap() {
+                // break/continue for this loop
+                ASTNode::Stmt(Stmt::BreakLoop(..)) => path.iter().rev().skip(1).any(|n| {
+                    matches!(
+                        n,
+                        ASTNode::Stmt(Stmt::While(..) | Stmt::Do(..) | Stmt::For(..))
+                    )
+                }),
+                // Closures capturing the loop variables
+                #[cfg(not(feature = "no_closure"))]
+                ASTNode::Stmt(Stmt::Share(..)) => false,
+                // Modifying the loop variables
+                ASTNode::Stmt(Stmt::Assignment(x, ..)) => {
+                    !root_var_name(&x.1.lhs).map_or(false, is_loop_var)
+                }
+                ASTNode::Expr(Expr::Dot(x, ..) | Expr::Index(x, ..)) => {
+                    !root_var_name(&x.lhs).map_or(false, is_loop_var)
+                }
+                // Functions that access the scope
+                ASTNode::Stmt(Stmt::FnCall(x, ..)) | ASTNode::Expr(Expr::FnCall(x, ..)) => {
+                    !x.capture_parent_scope
+                        && (x.is_qualified()
+                            || (x.name != KEYWORD_EVAL && x.name != KEYWORD_IS_DEF_VAR))
+                }
+                #[cfg(not(feature = "no_custom_syntax"))]
+                ASTNode::Expr(Expr::Custom(..)) => false,
+                _ => true,
+            }
+        })
+    });
+
+    if !can_unroll {
+        return None;
+    }
+
+    let is_dirty = state.is_dirty();
+    let orig_len = state.variables.len();
+    let mut statements = StmtBlockContainer::new_const();
+
+    // Keep the loop variables as placeholders in the scope so that the pre-calculated
+    // offsets of all other variables accessed within the loop body remain valid
+    if let Some(counter) = counter {
+        let var_def = (counter.clone(), Expr::Unit(counter.pos), None).into();
+        statements.push(Stmt::Var(var_def, ASTFlags::empty(), counter.pos));
+    }
+    let var_def = (var.clone(), Expr::Unit(var.pos), None).into();
+    statements.push(Stmt::Var(var_def, ASTFlags::empty(), var.pos));
+
+    for (index, value) in values.into_iter().enumerate() {
+        if let Some(counter) = counter {
+            state.push_var(counter.name.clone(), Some((index as INT).into()));
+        }
+        state.push_var(var.name.clone(), Some(value));
+
+        let stmts = optimize_stmt_block(body.iter().cloned().collect(), state, false, true, false);
+
+        state.rewind_var(orig_len);
+
+        // All references to the loop variables must have been replaced by constants
+        let has_loop_var = !stmts.iter().all(|stmt| {
+            stmt.walk(
+                &mut Vec::new(),
+                &mut |path: &[ASTNode]| match path.last().unwrap() {
+                    ASTNode::Expr(Expr::Variable(x, ..)) => !x.1.is_empty() || !is_loop_var(&x.3),
+                    _ => true,
+                },
+            )
+        });
+
+        if has_loop_var {
+            if !is_dirty {
+                state.clear_dirty();
+            }
+            return None;
+        }
+
+        if stmts.iter().any(Stmt::is_block_dependent) {
+            let (start, end) = (body.position(), body.end_position());
+            statements.push(Stmt::Block(StmtBlock::new(stmts, start, end).into()));
+        } else {
+            statements.extend(stmts);
+        }
+    }
+
+    // The value of a `for` loop is always ()
+    if preserve_result {
+        statements.push(Stmt::Expr(Expr::Unit(pos).into()));
+    }
+
+    Some(Stmt::Block(
+        StmtBlock::new(statements, pos, body.end_position()).into(),
+    ))
+}
+
 /// Optimize a [statement][Stmt].
 fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: bool) {
     #[inline(always)]
@@ -509,6 +812,7 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                     cases,
                     ranges,
                     def_case,
+                    ..
                 },
             ) = &mut **x;
 
@@ -654,6 +958,7 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                     expressions,
                     cases,
                     ranges,
+                    literals,
                     def_case,
                     ..
                 },
@@ -712,6 +1017,16 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                 }
             });
 
+            // Remove false literals
+            literals.retain(|&(.., index)| {
+                if expressions[index].is_always_false() {
+                    state.set_dirty();
+                    false
+                } else {
+                    true
+                }
+            });
+
             if let Some(index) = def_case {
                 optimize_expr(&mut expressions[*index].expr, state, false);
             }
@@ -721,6 +1036,7 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                 if *def_case != Some(index)
                     && cases.values().flat_map(|c| c.iter()).all(|&n| n != index)
                     && ranges.iter().all(|r| r.index() != index)
+                    && literals.iter().all(|&(.., n)| n != index)
                     && !b.expr.is_unit()
                 {
                     b.expr = Expr::Unit(b.expr.position());
@@ -754,10 +1070,28 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
                 optimize_stmt_block(x.body.take_statements(), state, false, true, false);
         }
         // for id in expr { block }
-        Stmt::For(x, ..) => {
-            optimize_expr(&mut x.2.expr, state, false);
-            *x.2.body.statements_mut() =
-                optimize_stmt_block(x.2.body.take_statements(), state, false, true, false);
+        Stmt::For(x, pos) => {
+            optimize_expr(&mut x.3.expr, state, false);
+
+            // for id in small-constant-range { block } -> { block } { block } ...
+            if let Some(unrolled) = unroll_for_loop(x, *pos, state, preserve_result) {
+                state.set_dirty();
+                *stmt = unrolled;
+                return;
+            }
+
+            // The loop variables shadow any constants of the same names
+            let orig_len = state.variables.len();
+            if let Some(ref var3) = x.2 {
+                state.push_var(var3.name.clone(), None);
+            }
+            if let Some(ref var2) = x.1 {
+                state.push_var(var2.name.clone(), None);
+            }
+            state.push_var(x.0.name.clone(), None);
+            *x.3.body.statements_mut() =
+                optimize_stmt_block(x.3.body.take_statements(), state, false, true, false);
+            state.rewind_var(orig_len);
         }
         // let id = expr;
         Stmt::Var(x, options, ..) if !options.contains(ASTFlags::CONSTANT) => {
@@ -765,7 +1099,10 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
         }
         // import expr as var;
         #[cfg(not(feature = "no_module"))]
-        Stmt::Import(x, ..) => optimize_expr(&mut x.0, state, false),
+        Stmt::Import(x, ..) => {
+            optimize_expr(&mut x.0, state, false);
+            state.push_import(&x.0, x.1.name.clone());
+        }
         // { block }
         Stmt::Block(block) => {
             let mut stmts =
@@ -785,18 +1122,26 @@ fn optimize_stmt(stmt: &mut Stmt, state: &mut OptimizerState, preserve_result: b
             }
         }
         // try { pure try_block } catch ( var ) { catch_block } -> try_block
-        Stmt::TryCatch(x, ..) if x.body.iter().all(Stmt::is_pure) => {
+        Stmt::TryCatch(x, ..) if x.try_block.iter().all(Stmt::is_pure) => {
             // If try block is pure, there will never be any exceptions
             state.set_dirty();
-            *x.body.statements_mut() =
-                optimize_stmt_block(x.body.take_statements(), state, false, true, false);
+            *x.try_block.statements_mut() =
+                optimize_stmt_block(x.try_block.take_statements(), state, false, true, false);
         }
-        // try { try_block } catch ( var ) { catch_block }
+        // try { try_block } catch ( var if guard ) { catch_block }
         Stmt::TryCatch(x, ..) => {
-            *x.body.statements_mut() =
-                optimize_stmt_block(x.body.take_statements(), state, false, true, false);
-            *x.branch.statements_mut() =
-                optimize_stmt_block(x.branch.take_statements(), state, false, true, false);
+            *x.try_block.statements_mut() =
+                optimize_stmt_block(x.try_block.take_statements(), state, false, true, false);
+
+            // The error variable shadows any constant of the same name
+            let orig_len = state.variables.len();
+            if let Expr::Variable(ref v, ..) = x.catch_var {
+                state.push_var(v.3.clone(), None);
+            }
+            optimize_expr(&mut x.guard, state, false);
+            *x.catch_block.statements_mut() =
+                optimize_stmt_block(x.catch_block.take_statements(), state, false, true, false);
+            state.rewind_var(orig_len);
         }
 
         // expr(stmt)
@@ -1149,9 +1494,28 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
             x.args.iter_mut().for_each(|arg_expr| optimize_expr(arg_expr, state, false));
         }
 
+        // Call foldable functions in namespaces
+        #[cfg(not(feature = "no_module"))]
+        Expr::FnCall(x, pos)
+                if x.is_qualified() // Qualified
+                && x.namespace.index.is_none() // Not an imported module
+                && x.constant_args() // all arguments are constants
+        => {
+            let arg_values = x.args.iter().map(|arg_expr| arg_expr.get_literal_value().unwrap()).collect::<FnArgsVec<_>>();
+
+            if let Some(result) = state.call_qualified_foldable_fn_with_const_args(x, &arg_values) {
+                state.set_dirty();
+                *expr = Expr::from_dynamic(result, *pos);
+                return;
+            }
+
+            x.args.iter_mut().for_each(|arg_expr| optimize_expr(arg_expr, state, false));
+        }
+
         // Call built-in operators
         Expr::FnCall(x, pos)
                 if !x.is_qualified() // Non-qualified
+                && x.arg_names.is_empty() // No named arguments
                 && state.optimization_level == OptimizationLevel::Simple // simple optimizations
                 && x.constant_args() // all arguments are constants
         => {
@@ -1173,7 +1537,7 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
                 }
                 // Overloaded operators can override built-in.
                 _ if x.args.len() == 2 && x.op_token.is_some() && (state.engine.fast_operators() || !state.engine.has_native_fn_override(x.hashes.native(), &arg_types)) => {
-                    if let Some((f, ctx)) = get_builtin_binary_op_fn(x.op_token.as_ref().unwrap(), &arg_values[0], &arg_values[1]) {
+                    if let Some((f, ctx)) = get_builtin_binary_op_fn(x.op_token.as_ref().unwrap(), &arg_values[0], &arg_values[1], state.engine.strict_arithmetic()) {
                         let context = ctx.then(|| (state.engine, x.name.as_str(), None, &state.global, *pos).into());
                         let (first, second) = arg_values.split_first_mut().unwrap();
 
@@ -1184,6 +1548,21 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
                         }
                     }
                 }
+                // Functions explicitly marked as foldable
+                _ if x.op_token.is_none() => {
+                    // First search for script-defined functions (can override built-in)
+                    let _has_script_fn = false;
+                    #[cfg(not(feature = "no_function"))]
+                    let _has_script_fn = !x.hashes.is_native_only() && state.global.lib.iter().find_map(|m| m.get_script_fn(&x.name, x.args.len())).is_some();
+
+                    if !_has_script_fn {
+                        if let Some(result) = state.call_foldable_fn_with_const_args(&x.name, arg_values) {
+                            state.set_dirty();
+                            *expr = Expr::from_dynamic(result, *pos);
+                            return;
+                        }
+                    }
+                }
                 _ => ()
             }
 
@@ -1208,6 +1587,7 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         // Eagerly call functions
         Expr::FnCall(x, pos)
                 if !x.is_qualified() // non-qualified
+                && x.arg_names.is_empty() // no named arguments
                 && state.optimization_level == OptimizationLevel::Full // full optimizations
                 && x.constant_args() // all arguments are constants
         => {
@@ -1257,8 +1637,15 @@ fn optimize_expr(expr: &mut Expr, state: &mut OptimizerState, _chaining: bool) {
         }),
 
         // constant-name
+        // namespace::constant
         #[cfg(not(feature = "no_module"))]
-        Expr::Variable(x, ..) if !x.1.is_empty() => (),
+        Expr::Variable(x, ..) if !x.1.is_empty() => {
+            if let Some(value) = state.find_module_constant(&x.1, x.2) {
+                // Point to the original usage, including the namespace
+                *expr = Expr::from_dynamic(value, x.1.position());
+                state.set_dirty();
+            }
+        }
         Expr::Variable(x, .., pos) if state.propagate_constants && state.find_literal_constant(&x.3).is_some() => {
             // Replace constant with value
             *expr = Expr::from_dynamic(state.find_literal_constant(&x.3).unwrap().clone(), *pos);
@@ -1316,6 +1703,9 @@ impl Engine {
         statements: StmtBlockContainer,
         scope: Option<&Scope>,
         lib: &[crate::SharedModule],
+        #[cfg(not(feature = "no_module"))] resolver: Option<
+            crate::Shared<crate::module::resolvers::StaticModuleResolver>,
+        >,
         optimization_level: OptimizationLevel,
     ) -> StmtBlockContainer {
         let mut statements = statements;
@@ -1327,7 +1717,13 @@ impl Engine {
         }
 
         // Set up the state
-        let mut state = OptimizerState::new(self, lib, optimization_level);
+        let mut state = OptimizerState::new(
+            self,
+            lib,
+            #[cfg(not(feature = "no_module"))]
+            resolver,
+            optimization_level,
+        );
 
         // Add constants from global modules
         self.global_modules
@@ -1354,6 +1750,9 @@ pub fn optimize_into_ast(
     scope: Option<&Scope>,
     statements: StmtBlockContainer,
     #[cfg(not(feature = "no_function"))] functions: Vec<crate::Shared<crate::ast::ScriptFnDef>>,
+    #[cfg(not(feature = "no_module"))] resolver: Option<
+        crate::Shared<crate::module::resolvers::StaticModuleResolver>,
+    >,
     optimization_level: OptimizationLevel,
 ) -> AST {
     let mut statements = statements;
@@ -1379,6 +1778,9 @@ pub fn optimize_into_ast(
                     #[cfg(not(feature = "no_object"))]
                     this_type: fn_def.this_type.clone(),
                     params: fn_def.params.clone(),
+                    defaults: fn_def.defaults.clone(),
+                    is_variadic: fn_def.is_variadic,
+                    no_optimize: fn_def.no_optimize,
                     #[cfg(feature = "metadata")]
                     comments: <_>::default(),
                 })
@@ -1389,6 +1791,11 @@ pub fn optimize_into_ast(
             let lib2 = &[lib2.into()];
 
             for fn_def in functions {
+                if fn_def.no_optimize {
+                    module.set_script_fn(fn_def);
+                    continue;
+                }
+
                 let mut fn_def = crate::func::shared_take_or_clone(fn_def);
 
                 // Optimize the function body
@@ -1396,6 +1803,8 @@ pub fn optimize_into_ast(
                     fn_def.body.take_statements(),
                     scope,
                     lib2,
+                    #[cfg(not(feature = "no_module"))]
+                    resolver.clone(),
                     optimization_level,
                 );
 
@@ -1410,14 +1819,128 @@ pub fn optimize_into_ast(
 
     statements.shrink_to_fit();
 
-    AST::new(
+    let mut ast = AST::new(
         match optimization_level {
             OptimizationLevel::None => statements,
-            OptimizationLevel::Simple | OptimizationLevel::Full => {
-                engine.optimize_top_level(statements, scope, &[lib.clone()], optimization_level)
-            }
+            OptimizationLevel::Simple | OptimizationLevel::Full => engine.optimize_top_level(
+                statements,
+                scope,
+                &[lib.clone()],
+                #[cfg(not(feature = "no_module"))]
+                resolver.clone(),
+                optimization_level,
+            ),
         },
         #[cfg(not(feature = "no_function"))]
         lib,
-    )
+    );
+    ast.set_language_version(engine.language_version());
+    #[cfg(not(feature = "no_module"))]
+    {
+        ast.resolver = resolver;
+    }
+    ast
+}
+
+/// Re-optimize an optimized [`AST`], only for the statements and functions that use any of a
+/// list of changed constants.
+///
+/// The top-level statements are re-optimized as a whole if any of them uses a changed constant.
+/// Functions that do not use any changed constant are kept as-is.
+pub fn optimize_changes_into_ast(
+    engine: &Engine,
+    scope: Option<&Scope>,
+    ast: AST,
+    changed_constants: &[&str],
+    optimization_level: OptimizationLevel,
+) -> AST {
+    let mut ast = ast;
+
+    if optimization_level == OptimizationLevel::None {
+        return ast;
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    let lib: crate::SharedModule = {
+        let changed: Vec<_> = ast
+            .iter_fn_def()
+            .map(|fn_def| {
+                !fn_def.no_optimize
+                    && crate::ast::FreeVariables::collect(fn_def.body.statements(), &fn_def.params)
+                        .contains_any(changed_constants)
+            })
+            .collect();
+
+        if changed.iter().any(|&c| c) {
+            // We only need the script library's signatures for optimization purposes
+            let mut lib2 = crate::Module::new();
+
+            ast.iter_fn_def()
+                .map(|fn_def| crate::ast::ScriptFnDef {
+                    name: fn_def.name.clone(),
+                    access: fn_def.access,
+                    body: crate::ast::StmtBlock::NONE,
+                    #[cfg(not(feature = "no_object"))]
+                    this_type: fn_def.this_type.clone(),
+                    params: fn_def.params.clone(),
+                    defaults: fn_def.defaults.clone(),
+                    is_variadic: fn_def.is_variadic,
+                    no_optimize: fn_def.no_optimize,
+                    #[cfg(feature = "metadata")]
+                    comments: <_>::default(),
+                })
+                .for_each(|script_def| {
+                    lib2.set_script_fn(script_def);
+                });
+
+            let lib2 = &[lib2.into()];
+            let mut module = crate::Module::new();
+
+            for (fn_def, changed) in ast.iter_fn_def().zip(changed) {
+                if !changed {
+                    // Keep the function untouched
+                    module.set_script_fn(fn_def.clone());
+                    continue;
+                }
+
+                let mut fn_def = (**fn_def).clone();
+
+                // Re-optimize the function body
+                *fn_def.body.statements_mut() = engine.optimize_top_level(
+                    fn_def.body.take_statements(),
+                    scope,
+                    lib2,
+                    #[cfg(not(feature = "no_module"))]
+                    ast.resolver.clone(),
+                    optimization_level,
+                );
+
+                module.set_script_fn(fn_def);
+            }
+
+            *ast.shared_lib_mut() = module.into();
+        }
+
+        ast.shared_lib().clone()
+    };
+    #[cfg(feature = "no_function")]
+    let lib: crate::SharedModule = crate::Module::new().into();
+
+    if crate::ast::FreeVariables::collect(ast.statements(), None).contains_any(changed_constants) {
+        let statements = mem::take(ast.statements_mut()).into_vec().into();
+
+        *ast.statements_mut() = engine
+            .optimize_top_level(
+                statements,
+                scope,
+                &[lib],
+                #[cfg(not(feature = "no_module"))]
+                ast.resolver.clone(),
+                optimization_level,
+            )
+            .into_iter()
+            .collect();
+    }
+
+    ast
 }
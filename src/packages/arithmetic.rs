@@ -247,6 +247,57 @@ mod int_functions {
     }
 }
 
+/// Checked arithmetic for [`INT`], used by built-in operators under `unchecked`
+/// when [strict arithmetic][crate::Engine::set_strict_arithmetic] is turned on.
+#[cfg(feature = "unchecked")]
+pub mod strict_int {
+    use super::*;
+
+    #[inline]
+    pub fn add(x: INT, y: INT) -> RhaiResultOf<INT> {
+        x.checked_add(y)
+            .ok_or_else(|| make_err(format!("Addition overflow: {x} + {y}")))
+    }
+    #[inline]
+    pub fn subtract(x: INT, y: INT) -> RhaiResultOf<INT> {
+        x.checked_sub(y)
+            .ok_or_else(|| make_err(format!("Subtraction overflow: {x} - {y}")))
+    }
+    #[inline]
+    pub fn multiply(x: INT, y: INT) -> RhaiResultOf<INT> {
+        x.checked_mul(y)
+            .ok_or_else(|| make_err(format!("Multiplication overflow: {x} * {y}")))
+    }
+    #[inline]
+    pub fn divide(x: INT, y: INT) -> RhaiResultOf<INT> {
+        // Detect division by zero
+        if y == 0 {
+            Err(make_err(format!("Division by zero: {x} / {y}")))
+        } else {
+            x.checked_div(y)
+                .ok_or_else(|| make_err(format!("Division overflow: {x} / {y}")))
+        }
+    }
+    #[inline]
+    pub fn modulo(x: INT, y: INT) -> RhaiResultOf<INT> {
+        x.checked_rem(y)
+            .ok_or_else(|| make_err(format!("Modulo division by zero or overflow: {x} % {y}")))
+    }
+    #[inline]
+    pub fn power(x: INT, y: INT) -> RhaiResultOf<INT> {
+        if cfg!(not(feature = "only_i32")) && y > (u32::MAX as INT) {
+            Err(make_err(format!("Exponential overflow: {x} ** {y}")))
+        } else if y < 0 {
+            Err(make_err(format!(
+                "Integer raised to a negative power: {x} ** {y}"
+            )))
+        } else {
+            x.checked_pow(y as u32)
+                .ok_or_else(|| make_err(format!("Exponential overflow: {x} ** {y}")))
+        }
+    }
+}
+
 gen_arithmetic_functions!(arith_basic => INT);
 
 #[cfg(not(feature = "only_i32"))]
@@ -495,6 +546,7 @@ mod f64_functions {
 #[export_module]
 pub mod decimal_functions {
     use rust_decimal::{prelude::Zero, Decimal};
+    use std::convert::TryFrom;
 
     #[cfg(not(feature = "unchecked"))]
     pub mod builtin {
@@ -567,4 +619,67 @@ pub mod decimal_functions {
     pub const fn is_zero(x: Decimal) -> bool {
         x.is_zero()
     }
+    /// Round the decimal number to the specified `scale` (i.e. number of digits after the
+    /// decimal point) and return it, using the rounding strategy of the `Engine`
+    /// (by default, mid-points are rounded towards the closest even number).
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = parse_decimal("2.345");
+    ///
+    /// print(x.round_to(2));       // prints 2.34
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn round_to(ctx: NativeCallContext, x: Decimal, scale: INT) -> RhaiResultOf<Decimal> {
+        let scale = decimal_scale(scale)?;
+        Ok(x.round_dp_with_strategy(scale, ctx.engine().decimal_rounding()))
+    }
+    /// Return the decimal number with exactly the specified `scale` (i.e. number of digits after
+    /// the decimal point), rounding it with the rounding strategy of the `Engine` or padding it
+    /// with trailing zeros as needed.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = parse_decimal("1.5");
+    ///
+    /// print(x.with_scale(3));     // prints 1.500
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn with_scale(ctx: NativeCallContext, x: Decimal, scale: INT) -> RhaiResultOf<Decimal> {
+        let scale = decimal_scale(scale)?;
+        let mut x = x.round_dp_with_strategy(scale, ctx.engine().decimal_rounding());
+        x.rescale(scale);
+        Ok(x)
+    }
+    /// Change the decimal number to have exactly the specified `scale` (i.e. number of digits
+    /// after the decimal point), rounding it with the rounding strategy of the `Engine` or
+    /// padding it with trailing zeros as needed.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = parse_decimal("2.345");
+    ///
+    /// x.rescale(2);
+    ///
+    /// print(x);                   // prints 2.34
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn rescale(ctx: NativeCallContext, x: &mut Decimal, scale: INT) -> RhaiResultOf<()> {
+        *x = with_scale(ctx, *x, scale)?;
+        Ok(())
+    }
+
+    /// Check that a scale is valid for a decimal number.
+    fn decimal_scale(scale: INT) -> RhaiResultOf<u32> {
+        // The maximum scale of a decimal number is 28
+        match u32::try_from(scale) {
+            Ok(s) if s <= 28 => Ok(s),
+            _ => Err(make_err(format!(
+                "Invalid scale for decimal number: {scale}"
+            ))),
+        }
+    }
 }
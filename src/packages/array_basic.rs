@@ -1,9 +1,10 @@
 #![cfg(not(feature = "no_index"))]
 
 use crate::api::deprecated::deprecated_array_functions;
-use crate::engine::OP_EQUALS;
+use crate::engine::{OP_EQUALS, OP_LESS_THAN};
 use crate::eval::{calc_index, calc_offset_len};
 use crate::module::ModuleFlags;
+use crate::packages::equality::EqualityComparer;
 use crate::plugin::*;
 
 use crate::{
@@ -12,7 +13,7 @@ use crate::{
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
-use std::{any::TypeId, cmp::Ordering, mem};
+use std::{any::TypeId, cmp::Ordering, mem, ops::Range};
 
 def_package! {
     /// Package of basic array utilities.
@@ -27,6 +28,130 @@ def_package! {
     }
 }
 
+/// Calculate the actual position of an `index` into an array with `len` elements.
+///
+/// Negative indices count from the end. If `allow_end` is `true`, `len` itself is also valid.
+///
+/// Indices that are out of bounds are errors.
+#[allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation,
+    clippy::unnecessary_cast
+)]
+fn calc_bounded_index(len: usize, index: INT, allow_end: bool) -> RhaiResultOf<usize> {
+    let pos = if index < 0 {
+        (len as u64).checked_sub(index.unsigned_abs() as u64)
+    } else {
+        Some(index as u64)
+    };
+
+    match pos {
+        Some(pos) if pos < len as u64 || (allow_end && pos == len as u64) => Ok(pos as usize),
+        _ => Err(ERR::ErrorArrayBounds(len, index, Position::NONE).into()),
+    }
+}
+/// Calculate the actual span of an exclusive range of indices into an array with `len` elements.
+///
+/// Negative indices count from the end. A range that ends before it starts is empty.
+fn calc_bounded_range(len: usize, range: &ExclusiveRange) -> RhaiResultOf<Range<usize>> {
+    let start = calc_bounded_index(len, range.start, true)?;
+    let end = calc_bounded_index(len, range.end, true)?;
+    Ok(start..usize::max(start, end))
+}
+/// Calculate the actual span of an inclusive range of indices into an array with `len` elements.
+///
+/// Negative indices count from the end. A range that ends before it starts is empty.
+fn calc_bounded_inclusive_range(len: usize, range: &InclusiveRange) -> RhaiResultOf<Range<usize>> {
+    let start = calc_bounded_index(len, *range.start(), true)?;
+    let end = calc_bounded_index(len, *range.end(), false)? + 1;
+    Ok(start..usize::max(start, end))
+}
+/// Binary search a sorted array, where `compare` orders an element relative to the target.
+///
+/// Returns the index of a matching element, or `-(p + 1)` where `p` is the insertion point.
+#[allow(clippy::cast_possible_wrap)]
+fn binary_search_with(
+    array: &mut Array,
+    mut compare: impl FnMut(&mut Dynamic) -> RhaiResultOf<Ordering>,
+) -> RhaiResultOf<INT> {
+    let (mut lo, mut hi) = (0, array.len());
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+
+        match compare(&mut array[mid])? {
+            Ordering::Less => lo = mid + 1,
+            Ordering::Greater => hi = mid,
+            Ordering::Equal => return Ok(mid as INT),
+        }
+    }
+
+    Ok(-(lo as INT) - 1)
+}
+/// Replace a span of an array with the elements of another array, which may be of a different length.
+fn replace_range(
+    ctx: &NativeCallContext,
+    array: &mut Array,
+    range: Range<usize>,
+    replace: Array,
+) -> RhaiResultOf<()> {
+    // Check if array will be over max size limit
+    #[cfg(not(feature = "unchecked"))]
+    if ctx.engine().max_array_size() > 0 {
+        let (a, m, s) = crate::eval::calc_array_sizes(&replace);
+        ctx.engine()
+            .throw_on_size((array.len() - range.len() + a, m, s))?;
+    }
+
+    // Elements after the range must be moved if the length changes
+    let moved = if range.len() == replace.len() {
+        replace.len()
+    } else {
+        replace.len() + array.len() - range.end
+    };
+    ctx.track_operations(moved as u64)?;
+
+    if range.len() == replace.len() {
+        for (item, value) in array[range].iter_mut().zip(replace) {
+            *item = value;
+        }
+    } else {
+        array.splice(range, replace);
+    }
+
+    Ok(())
+}
+/// Copy a span of an array to another position within the same array, which may overlap.
+fn copy_range_within(
+    ctx: &NativeCallContext,
+    array: &mut Array,
+    range: Range<usize>,
+    dest: INT,
+) -> RhaiResultOf<()> {
+    let len = array.len();
+    let count = range.len();
+    let target = calc_bounded_index(len, dest, true)?;
+
+    if count > len - target {
+        return Err(ERR::ErrorArrayBounds(len, dest, Position::NONE).into());
+    }
+
+    ctx.track_operations(count as u64)?;
+
+    // Copy in the direction that never overwrites elements that are yet to be copied
+    if target <= range.start {
+        for i in 0..count {
+            array[target + i] = array[range.start + i].clone();
+        }
+    } else {
+        for i in (0..count).rev() {
+            array[target + i] = array[range.start + i].clone();
+        }
+    }
+
+    Ok(())
+}
+
 #[export_module]
 pub mod array_functions {
     /// Number of elements in the array.
@@ -493,6 +618,266 @@ pub mod array_functions {
     }
     /// Copy an exclusive range of the array and return it as a new array.
     ///
+    /// This is the index getter for `array[range]`.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x[1..3]);         // prints "[2, 3]"
+    ///
+    /// print(x[-2..5]);        // prints "[4, 5]"
+    /// ```
+    #[rhai_fn(index_get, return_raw, pure)]
+    pub fn get_range(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: ExclusiveRange,
+    ) -> RhaiResultOf<Array> {
+        let range = calc_bounded_range(array.len(), &range)?;
+        ctx.track_operations(range.len() as u64)?;
+        Ok(array[range].to_vec())
+    }
+    /// Copy an inclusive range of the array and return it as a new array.
+    ///
+    /// This is the index getter for `array[range]`.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// print(x[1..=3]);        // prints "[2, 3, 4]"
+    ///
+    /// print(x[-2..=-1]);      // prints "[4, 5]"
+    /// ```
+    #[rhai_fn(index_get, return_raw, pure)]
+    pub fn get_inclusive_range(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: InclusiveRange,
+    ) -> RhaiResultOf<Array> {
+        let range = calc_bounded_inclusive_range(array.len(), &range)?;
+        ctx.track_operations(range.len() as u64)?;
+        Ok(array[range].to_vec())
+    }
+    /// Replace an exclusive range of the array with the elements of another array.
+    ///
+    /// This is the index setter for `array[range] = replace`.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds.
+    /// * The other array may be shorter or longer than the range, in which case the array shrinks or grows.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x[1..3] = [7, 8, 9];
+    ///
+    /// print(x);       // prints "[1, 7, 8, 9, 4, 5]"
+    ///
+    /// x[-3..6] = [];
+    ///
+    /// print(x);       // prints "[1, 7, 8]"
+    /// ```
+    #[rhai_fn(index_set, return_raw)]
+    pub fn set_range(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: ExclusiveRange,
+        replace: Array,
+    ) -> RhaiResultOf<()> {
+        let range = calc_bounded_range(array.len(), &range)?;
+        replace_range(&ctx, array, range, replace)
+    }
+    /// Replace an inclusive range of the array with the elements of another array.
+    ///
+    /// This is the index setter for `array[range] = replace`.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds.
+    /// * The other array may be shorter or longer than the range, in which case the array shrinks or grows.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x[1..=3] = [0];
+    ///
+    /// print(x);       // prints "[1, 0, 5]"
+    /// ```
+    #[rhai_fn(index_set, return_raw)]
+    pub fn set_inclusive_range(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: InclusiveRange,
+        replace: Array,
+    ) -> RhaiResultOf<()> {
+        let range = calc_bounded_inclusive_range(array.len(), &range)?;
+        replace_range(&ctx, array, range, replace)
+    }
+    /// Set all elements in the array to copies of a specified `value`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x.fill(0);
+    ///
+    /// print(x);       // prints "[0, 0, 0, 0, 0]"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn fill(ctx: NativeCallContext, array: &mut Array, value: Dynamic) -> RhaiResultOf<()> {
+        if array.is_empty() {
+            return Ok(());
+        }
+
+        ctx.track_operations(array.len() as u64)?;
+        array.fill(value);
+        Ok(())
+    }
+    /// Set all elements within an exclusive range of the array to copies of a specified `value`.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x.fill_range(1..3, 0);
+    ///
+    /// print(x);       // prints "[1, 0, 0, 4, 5]"
+    /// ```
+    #[rhai_fn(name = "fill_range", return_raw)]
+    pub fn fill_range(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: ExclusiveRange,
+        value: Dynamic,
+    ) -> RhaiResultOf<()> {
+        let range = calc_bounded_range(array.len(), &range)?;
+        ctx.track_operations(range.len() as u64)?;
+        array[range].fill(value);
+        Ok(())
+    }
+    /// Set all elements within an inclusive range of the array to copies of a specified `value`.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x.fill_range(-2..=-1, 0);
+    ///
+    /// print(x);       // prints "[1, 2, 3, 0, 0]"
+    /// ```
+    #[rhai_fn(name = "fill_range", return_raw)]
+    pub fn fill_inclusive_range(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: InclusiveRange,
+        value: Dynamic,
+    ) -> RhaiResultOf<()> {
+        let range = calc_bounded_inclusive_range(array.len(), &range)?;
+        ctx.track_operations(range.len() as u64)?;
+        array[range].fill(value);
+        Ok(())
+    }
+    /// Copy the elements within an exclusive range of the array to the `dest` position in the
+    /// same array, overwriting the elements there.
+    ///
+    /// The source and destination may overlap.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * If `dest` < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds, or if there is not enough room at `dest`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x.copy_within(0..3, 2);
+    ///
+    /// print(x);       // prints "[1, 2, 1, 2, 3]"
+    /// ```
+    #[rhai_fn(name = "copy_within", return_raw)]
+    pub fn copy_within(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: ExclusiveRange,
+        dest: INT,
+    ) -> RhaiResultOf<()> {
+        let range = calc_bounded_range(array.len(), &range)?;
+        copy_range_within(&ctx, array, range, dest)
+    }
+    /// Copy the elements within an inclusive range of the array to the `dest` position in the
+    /// same array, overwriting the elements there.
+    ///
+    /// The source and destination may overlap.
+    ///
+    /// * If the start or end of the range < 0, position counts from the end of the array.
+    /// * If `dest` < 0, position counts from the end of the array.
+    /// * It is an error if the range is out of bounds, or if there is not enough room at `dest`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x.copy_within(2..=4, 0);
+    ///
+    /// print(x);       // prints "[3, 4, 5, 4, 5]"
+    /// ```
+    #[rhai_fn(name = "copy_within", return_raw)]
+    pub fn copy_within_inclusive_range(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        range: InclusiveRange,
+        dest: INT,
+    ) -> RhaiResultOf<()> {
+        let range = calc_bounded_inclusive_range(array.len(), &range)?;
+        copy_range_within(&ctx, array, range, dest)
+    }
+    /// Swap the elements at the `index1` and `index2` positions in the array.
+    ///
+    /// * If an index < 0, position counts from the end of the array (`-1` is the last element).
+    /// * It is an error if either index is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// x.swap(0, -1);
+    ///
+    /// print(x);       // prints "[5, 2, 3, 4, 1]"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn swap(array: &mut Array, index1: INT, index2: INT) -> RhaiResultOf<()> {
+        let index1 = calc_bounded_index(array.len(), index1, false)?;
+        let index2 = calc_bounded_index(array.len(), index2, false)?;
+        array.swap(index1, index2);
+        Ok(())
+    }
+    /// Copy an exclusive range of the array and return it as a new array.
+    ///
     /// # Example
     ///
     /// ```rhai
@@ -752,6 +1137,59 @@ pub mod array_functions {
 
         Ok(ar)
     }
+    /// Iterate through all the elements in the array, applying a `filter` function to each element
+    /// in turn, and return a two-element array containing a copy of all elements (in order) that
+    /// return `true`, followed by a copy of all the other elements.
+    ///
+    /// # No Function Parameter
+    ///
+    /// Array element (mutable) is bound to `this`.
+    ///
+    /// This method is marked _pure_; the `filter` function should not mutate array elements.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element`: copy of array element
+    /// * `index` _(optional)_: current index in the array
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 2, 3, 4, 5];
+    ///
+    /// let y = x.partition(|v| v >= 3);
+    ///
+    /// print(y);       // prints "[[3, 4, 5], [1, 2]]"
+    ///
+    /// let y = x.partition(|v, i| v * i >= 10);
+    ///
+    /// print(y);       // prints "[[4, 5], [1, 2, 3]]"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn partition(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        filter: FnPtr,
+    ) -> RhaiResultOf<Array> {
+        let mut matched = Array::new();
+        let mut rest = Array::new();
+
+        for (i, item) in array.iter_mut().enumerate() {
+            let ex = [(i as INT).into()];
+
+            if filter
+                .call_raw_with_extra_args("partition", &ctx, Some(item), [], ex, Some(0))?
+                .as_bool()
+                .unwrap_or(false)
+            {
+                matched.push(item.clone());
+            } else {
+                rest.push(item.clone());
+            }
+        }
+
+        Ok(vec![matched.into(), rest.into()])
+    }
     /// Return `true` if the array contains an element that equals `value`.
     ///
     /// The operator `==` is used to compare elements with `value` and must be defined,
@@ -1029,7 +1467,7 @@ pub mod array_functions {
     ///
     /// * If `start` < 0, position counts from the end of the array (`-1` is the last element).
     /// * If `start` < -length of array, position counts from the beginning of the array.
-    /// * If `start` ≥ length of array, `-1` is returned.
+    /// * If `start` ≥ length of array, `()` is returned.
     ///
     /// # No Function Parameter
     ///
@@ -1274,8 +1712,11 @@ pub mod array_functions {
             name: ctx.engine().get_interned_string(OP_EQUALS),
             curry: Vec::new(),
             environ: None,
+            native_fn: None,
             #[cfg(not(feature = "no_function"))]
             fn_def: None,
+            #[cfg(not(feature = "no_function"))]
+            binds_this: false,
         };
         dedup_by_comparer(ctx, array, comparer);
     }
@@ -1316,6 +1757,76 @@ pub mod array_functions {
                 .unwrap_or(false)
         });
     }
+    /// Iterate through all the elements in the array, applying a `mapper` function to each element
+    /// in turn, and group _consecutive_ elements that map to equal keys into arrays.
+    ///
+    /// The operator `==` is used to compare keys and must be defined for keys of the same type.
+    /// Keys of different types are never equal.
+    ///
+    /// # No Function Parameter
+    ///
+    /// Array element (mutable) is bound to `this`.
+    ///
+    /// This method is marked _pure_; the `mapper` function should not mutate array elements.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element`: copy of array element
+    /// * `index` _(optional)_: current index in the array
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 3, 2, 4, 6, 5, 7];
+    ///
+    /// let y = x.chunk_by(|v| v % 2);
+    ///
+    /// print(y);       // prints "[[1, 3], [2, 4, 6], [5, 7]]"
+    ///
+    /// let y = x.chunk_by(|v, i| i / 3);
+    ///
+    /// print(y);       // prints "[[1, 3, 2], [4, 6, 5], [7]]"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn chunk_by(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        mapper: FnPtr,
+    ) -> RhaiResultOf<Array> {
+        let mut chunks = Array::new();
+        let mut chunk = Array::new();
+        let mut last_key: Option<Dynamic> = None;
+
+        for (i, item) in array.iter_mut().enumerate() {
+            let ex = [(i as INT).into()];
+
+            let mut key =
+                mapper.call_raw_with_extra_args("chunk_by", &ctx, Some(item), [], ex, Some(0))?;
+
+            if let Some(ref mut last_key) = last_key {
+                let same = if key.type_id() == last_key.type_id() {
+                    ctx.call_native_fn_raw(OP_EQUALS, true, &mut [last_key, &mut key])?
+                        .as_bool()
+                        .unwrap_or(false)
+                } else {
+                    false
+                };
+
+                if !same {
+                    chunks.push(mem::take(&mut chunk).into());
+                }
+            }
+
+            chunk.push(item.clone());
+            last_key = Some(key);
+        }
+
+        if !chunk.is_empty() {
+            chunks.push(chunk.into());
+        }
+
+        Ok(chunks)
+    }
     /// Reduce an array by iterating through all elements while applying the `reducer` function.
     ///
     /// # Function Parameters
@@ -1519,6 +2030,11 @@ pub mod array_functions {
     }
     /// Sort the array based on applying the `comparer` function.
     ///
+    /// The sort is stable, i.e. elements that compare equal keep their original order.
+    ///
+    /// If the `comparer` function fails or does not return an integer, the error is returned and
+    /// the array is left untouched.
+    ///
     /// # Function Parameters
     ///
     /// * `element1`: copy of the current array element to compare
@@ -1539,27 +2055,145 @@ pub mod array_functions {
     /// x.sort(|a, b| if a > b { -1 } else if a < b { 1 } else { 0 });
     ///
     /// print(x);       // prints "[10, 9, 8, 7, 6, 5, 4, 3, 2, 1]"
+    ///
+    /// let y = [#{name: "a", age: 2}, #{name: "b", age: 1}, #{name: "c", age: 2}];
+    ///
+    /// // Elements with equal ages keep their order
+    /// y.sort(|a, b| a.age - b.age);
+    ///
+    /// print(y.map(|v| v.name));   // prints "[b, a, c]"
     /// ```
-    pub fn sort(ctx: NativeCallContext, array: &mut Array, comparer: FnPtr) {
+    #[rhai_fn(return_raw)]
+    pub fn sort(ctx: NativeCallContext, array: &mut Array, comparer: FnPtr) -> RhaiResultOf<()> {
         if array.len() <= 1 {
-            return;
+            return Ok(());
         }
 
-        array.sort_by(|x, y| {
-            comparer
-                .call_raw(&ctx, None, [x.clone(), y.clone()])
-                .ok()
-                .and_then(|v| v.as_int().ok())
-                .map_or_else(
-                    || x.type_id().cmp(&y.type_id()),
-                    |v| match v {
-                        v if v > 0 => Ordering::Greater,
-                        v if v < 0 => Ordering::Less,
-                        0 => Ordering::Equal,
-                        _ => unreachable!("v is {}", v),
-                    },
-                )
+        // Sort the indices first so the array is not touched if the comparer fails
+        let mut order = (0..array.len()).collect::<Vec<_>>();
+        let mut error = None;
+
+        order.sort_by(|&a, &b| {
+            if error.is_some() {
+                return Ordering::Equal;
+            }
+
+            match comparer
+                .call_raw(&ctx, None, [array[a].clone(), array[b].clone()])
+                .and_then(|v| ctx.engine().cast_dynamic::<INT>(v))
+            {
+                Ok(v) => v.cmp(&0),
+                Err(err) => {
+                    error = Some(err);
+                    Ordering::Equal
+                }
+            }
         });
+
+        if let Some(err) = error {
+            return Err(err);
+        }
+
+        let mut items = mem::take(array);
+        *array = order
+            .into_iter()
+            .map(|i| mem::take(&mut items[i]))
+            .collect();
+
+        Ok(())
+    }
+    /// Search a sorted array for a particular `value` and return its index.
+    ///
+    /// If no element equals `value`, `-(p + 1)` is returned, where `p` is the position at which
+    /// `value` would be inserted to keep the array sorted. Therefore the result is negative if and
+    /// only if `value` is not found.
+    ///
+    /// The array must be sorted in ascending order, otherwise the result is meaningless.
+    /// If there are multiple elements equal to `value`, the index of any one of them is returned.
+    ///
+    /// The operators `==` and `<` are used to compare elements with `value` and must be defined.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [1, 3, 5, 7, 9];
+    ///
+    /// print(x.binary_search(7));      // prints 3
+    ///
+    /// print(x.binary_search(4));      // prints -3: would be inserted at index 2
+    ///
+    /// print(x.binary_search(42));     // prints -6: would be inserted at the end
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn binary_search(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        value: Dynamic,
+    ) -> RhaiResultOf<INT> {
+        binary_search_with(array, |item| {
+            let mut value = value.clone();
+
+            if ctx
+                .call_native_fn_raw(OP_EQUALS, true, &mut [item, &mut value])?
+                .as_bool()
+                .unwrap_or(false)
+            {
+                return Ok(Ordering::Equal);
+            }
+
+            let less = ctx
+                .call_native_fn_raw(OP_LESS_THAN, true, &mut [item, &mut value])?
+                .as_bool()
+                .unwrap_or(false);
+
+            Ok(if less {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            })
+        })
+    }
+    /// Search a sorted array by applying the `comparer` function and return the index of the
+    /// element for which it returns zero.
+    ///
+    /// If no such element exists, `-(p + 1)` is returned, where `p` is the position at which
+    /// such an element would be inserted to keep the array sorted. Therefore the result is
+    /// negative if and only if nothing is found.
+    ///
+    /// The array must be sorted consistently with the `comparer` function, otherwise the result
+    /// is meaningless.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `element`: copy of the array element to compare
+    ///
+    /// ## Return Value
+    ///
+    /// * Any integer > 0 if `element` is _after_ the target
+    /// * Zero if `element` is the target
+    /// * Any integer < 0 if `element` is _before_ the target
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = [#{id: 1}, #{id: 4}, #{id: 9}];
+    ///
+    /// print(x.binary_search_by(|v| v.id - 4));    // prints 1
+    ///
+    /// print(x.binary_search_by(|v| v.id - 5));    // prints -3: would be inserted at index 2
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn binary_search_by(
+        ctx: NativeCallContext,
+        array: &mut Array,
+        comparer: FnPtr,
+    ) -> RhaiResultOf<INT> {
+        binary_search_with(array, |item| {
+            comparer
+                .call_raw(&ctx, None, [item.clone()])
+                .and_then(|v| ctx.engine().cast_dynamic::<INT>(v))
+                .map(|v| v.cmp(&0))
+        })
     }
     /// Sort the array.
     ///
@@ -1575,6 +2209,11 @@ pub mod array_functions {
     /// * booleans
     /// * `()`
     ///
+    /// Characters and strings are sorted in Unicode code-point order, which does not depend on
+    /// the locale. To sort strings caselessly, sort them with a comparer function on `casefold`.
+    ///
+    /// The sort is stable, i.e. equal elements keep their original order.
+    ///
     /// # Example
     ///
     /// ```rhai
@@ -1980,28 +2619,7 @@ pub mod array_functions {
 
         let mut array2 = array2;
 
-        for (a1, a2) in array1.iter_mut().zip(array2.iter_mut()) {
-            if !ctx
-                .call_native_fn_raw(OP_EQUALS, true, &mut [a1, a2])
-                .or_else(|err| match *err {
-                    ERR::ErrorFunctionNotFound(ref fn_sig, ..) if fn_sig.starts_with(OP_EQUALS) => {
-                        if a1.type_id() == a2.type_id() {
-                            // No default when comparing same type
-                            Err(err)
-                        } else {
-                            Ok(Dynamic::FALSE)
-                        }
-                    }
-                    _ => Err(err),
-                })?
-                .as_bool()
-                .unwrap_or(false)
-            {
-                return Ok(false);
-            }
-        }
-
-        Ok(true)
+        EqualityComparer::new(&ctx).arrays_equal(array1, &mut array2)
     }
     /// Return `true` if two arrays are not-equal (i.e. any element not equal or not in the same order).
     ///
@@ -0,0 +1,207 @@
+#![cfg(feature = "bigint")]
+#![allow(non_snake_case)]
+
+use super::arithmetic::make_err;
+use crate::module::ModuleFlags;
+use crate::plugin::*;
+use crate::{def_package, RhaiResultOf, INT};
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_float"))]
+use crate::FLOAT;
+
+macro_rules! gen_bigint_functions {
+    ($root:ident => $($arg_name:ident ( $x_type:ty, $y_type:ty )),+) => {
+        pub mod $root { $(pub mod $arg_name {
+            use super::super::*;
+
+            #[export_module]
+            #[allow(clippy::cmp_owned)]
+            pub mod functions {
+                #[rhai_fn(name = "+")]
+                pub fn add(x: $x_type, y: $y_type) -> BigInt {
+                    BigInt::from(x) + BigInt::from(y)
+                }
+                #[rhai_fn(name = "-")]
+                pub fn subtract(x: $x_type, y: $y_type) -> BigInt {
+                    BigInt::from(x) - BigInt::from(y)
+                }
+                #[rhai_fn(name = "*")]
+                pub fn multiply(x: $x_type, y: $y_type) -> BigInt {
+                    BigInt::from(x) * BigInt::from(y)
+                }
+                #[rhai_fn(name = "/", return_raw)]
+                pub fn divide(x: $x_type, y: $y_type) -> RhaiResultOf<BigInt> {
+                    let (x, y) = (BigInt::from(x), BigInt::from(y));
+
+                    if y.is_zero() {
+                        Err(make_err(format!("Division by zero: {x} / {y}")))
+                    } else {
+                        Ok(x / y)
+                    }
+                }
+                #[rhai_fn(name = "%", return_raw)]
+                pub fn modulo(x: $x_type, y: $y_type) -> RhaiResultOf<BigInt> {
+                    let (x, y) = (BigInt::from(x), BigInt::from(y));
+
+                    if y.is_zero() {
+                        Err(make_err(format!("Modulo division by zero: {x} % {y}")))
+                    } else {
+                        Ok(x % y)
+                    }
+                }
+                #[rhai_fn(name = "==")]
+                pub fn eq(x: $x_type, y: $y_type) -> bool {
+                    BigInt::from(x) == BigInt::from(y)
+                }
+                #[rhai_fn(name = "!=")]
+                pub fn ne(x: $x_type, y: $y_type) -> bool {
+                    BigInt::from(x) != BigInt::from(y)
+                }
+                #[rhai_fn(name = ">")]
+                pub fn gt(x: $x_type, y: $y_type) -> bool {
+                    BigInt::from(x) > BigInt::from(y)
+                }
+                #[rhai_fn(name = ">=")]
+                pub fn gte(x: $x_type, y: $y_type) -> bool {
+                    BigInt::from(x) >= BigInt::from(y)
+                }
+                #[rhai_fn(name = "<")]
+                pub fn lt(x: $x_type, y: $y_type) -> bool {
+                    BigInt::from(x) < BigInt::from(y)
+                }
+                #[rhai_fn(name = "<=")]
+                pub fn lte(x: $x_type, y: $y_type) -> bool {
+                    BigInt::from(x) <= BigInt::from(y)
+                }
+            }
+        })* }
+    }
+}
+
+gen_bigint_functions!(mixed => bigint_bigint(BigInt, BigInt), bigint_int(BigInt, INT), int_bigint(INT, BigInt));
+
+macro_rules! reg_functions {
+    ($mod_name:ident += $root:ident ; $($arg_name:ident),+ ) => { $(
+        combine_with_exported_module!($mod_name, "bigint", $root::$arg_name::functions);
+    )* }
+}
+
+def_package! {
+    /// Package of basic [`BigInt`][num_bigint::BigInt] utilities.
+    pub BasicBigIntPackage(lib) {
+        lib.flags |= ModuleFlags::STANDARD_LIB;
+
+        combine_with_exported_module!(lib, "bigint", bigint_functions);
+        reg_functions!(lib += mixed; bigint_bigint, bigint_int, int_bigint);
+    }
+}
+
+#[export_module]
+mod bigint_functions {
+    use std::convert::TryFrom;
+
+    /// Convert the integer into an arbitrary-precision integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = bigint(42);
+    ///
+    /// print(x * x * x * x * x * x * x * x * x * x * x * x);   // prints 30129469486639681536
+    /// ```
+    #[rhai_fn(name = "bigint", name = "to_bigint")]
+    pub fn from_int(x: INT) -> BigInt {
+        x.into()
+    }
+    /// Convert the floating-point number into an arbitrary-precision integer,
+    /// truncating any fractional part.
+    ///
+    /// An error is raised if the number is infinite or NaN.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "bigint", name = "to_bigint", return_raw)]
+    #[allow(clippy::unnecessary_cast)]
+    pub fn from_float(x: FLOAT) -> RhaiResultOf<BigInt> {
+        use num_traits::FromPrimitive;
+
+        BigInt::from_f64(x as f64).ok_or_else(|| make_err(format!("Cannot convert to bigint: {x}")))
+    }
+    /// Parse a string into an arbitrary-precision integer.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = bigint("123456789012345678901234567890");
+    ///
+    /// print(x + 1);       // prints 123456789012345678901234567891
+    /// ```
+    #[rhai_fn(name = "bigint", name = "parse_bigint", return_raw)]
+    pub fn parse(s: &str) -> RhaiResultOf<BigInt> {
+        BigInt::parse_bytes(s.trim().as_bytes(), 10)
+            .ok_or_else(|| make_err(format!("Error parsing bigint: '{s}'")))
+    }
+    /// Convert the arbitrary-precision integer into an integer.
+    ///
+    /// An error is raised if the number does not fit into an integer.
+    #[rhai_fn(return_raw)]
+    pub fn to_int(x: &mut BigInt) -> RhaiResultOf<INT> {
+        INT::try_from(&*x).map_err(|_| make_err(format!("Integer overflow: to_int({x})")))
+    }
+    /// Convert the arbitrary-precision integer into a floating-point number.
+    ///
+    /// Numbers too large to be represented become infinity.
+    #[cfg(not(feature = "no_float"))]
+    pub fn to_float(x: &mut BigInt) -> FLOAT {
+        use num_traits::ToPrimitive;
+
+        x.to_f64().map_or(FLOAT::NAN, |v| v as FLOAT)
+    }
+
+    /// Return the arbitrary-precision integer raised to the power of `y`.
+    #[rhai_fn(name = "**", return_raw)]
+    pub fn power(x: BigInt, y: INT) -> RhaiResultOf<BigInt> {
+        if y < 0 {
+            Err(make_err(format!("Integer raised to a negative power: {x} ** {y}")))
+        } else {
+            u32::try_from(y)
+                .map(|y| x.pow(y))
+                .map_err(|_| make_err(format!("Exponential overflow: {x} ** {y}")))
+        }
+    }
+    /// Return the negated value of the arbitrary-precision integer.
+    #[rhai_fn(name = "-")]
+    pub fn neg(x: BigInt) -> BigInt {
+        -x
+    }
+    /// Return the arbitrary-precision integer unchanged.
+    #[rhai_fn(name = "+")]
+    pub fn plus(x: BigInt) -> BigInt {
+        x
+    }
+    /// Return the absolute value of the arbitrary-precision integer.
+    pub fn abs(x: &mut BigInt) -> BigInt {
+        x.abs()
+    }
+    /// Return the sign (as an integer) of the arbitrary-precision integer according to the following:
+    ///
+    /// * `0` if the number is zero
+    /// * `1` if the number is positive
+    /// * `-1` if the number is negative
+    pub fn sign(x: &mut BigInt) -> INT {
+        if x.is_zero() {
+            0
+        } else if x.is_negative() {
+            -1
+        } else {
+            1
+        }
+    }
+    /// Return true if the arbitrary-precision integer is zero.
+    #[rhai_fn(get = "is_zero", name = "is_zero")]
+    pub fn is_zero(x: &mut BigInt) -> bool {
+        x.is_zero()
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     def_package, ExclusiveRange, InclusiveRange, Position, RhaiResultOf, ERR, INT, INT_BITS,
     UNSIGNED_INT,
 };
+use std::iter::{ExactSizeIterator, FusedIterator};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -13,10 +14,46 @@ def_package! {
     pub BitFieldPackage(lib) {
         lib.flags |= ModuleFlags::STANDARD_LIB;
 
+        lib.set_iterator::<BitPositions>();
+
         combine_with_exported_module!(lib, "bit_field", bit_field_functions);
     }
 }
 
+// Iterator over the positions of the set bits in a number
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct BitPositions(UNSIGNED_INT);
+
+impl Iterator for BitPositions {
+    type Item = INT;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            None
+        } else {
+            let bit = self.0.trailing_zeros();
+            // Clear the lowest set bit
+            self.0 &= self.0 - 1;
+            Some(bit as INT)
+        }
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl FusedIterator for BitPositions {}
+
+impl ExactSizeIterator for BitPositions {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+}
+
 #[export_module]
 mod bit_field_functions {
     /// Return `true` if the specified `bit` in the number is set.
@@ -241,4 +278,92 @@ mod bit_field_functions {
 
         Ok(())
     }
+    /// Return an iterator over the positions of all the bits that are set in the number,
+    /// starting from the LSB (Least Significant Bit).
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = 0b1010_0100;
+    ///
+    /// for bit in x.bit_positions() {
+    ///     print(bit);         // prints 2, 5, 7
+    /// }
+    /// ```
+    #[allow(clippy::cast_sign_loss)]
+    pub fn bit_positions(value: INT) -> BitPositions {
+        BitPositions(value as UNSIGNED_INT)
+    }
+    /// Return the number of bits that are set in the number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = 0b1010_0100;
+    ///
+    /// print(x.popcount());    // prints 3
+    /// ```
+    pub fn popcount(value: INT) -> INT {
+        value.count_ones() as INT
+    }
+    /// Return the number of leading zero bits (i.e. from the MSB) in the number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = 1;
+    ///
+    /// print(x.leading_zeros());   // prints 63 on 64-bit
+    /// ```
+    pub fn leading_zeros(value: INT) -> INT {
+        value.leading_zeros() as INT
+    }
+    /// Return the number of trailing zero bits (i.e. from the LSB) in the number.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = 0b1010_0100;
+    ///
+    /// print(x.trailing_zeros());  // prints 2
+    /// ```
+    pub fn trailing_zeros(value: INT) -> INT {
+        value.trailing_zeros() as INT
+    }
+    /// Rotate the bits in the number to the left by the specified number of `bits`,
+    /// wrapping the bits shifted out of the MSB around to the LSB.
+    ///
+    /// If `bits` < 0, the bits are rotated to the right instead.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = 0b0110;
+    ///
+    /// print(x.rotate_left(2));    // prints 24
+    ///
+    /// print(x.rotate_left(-1));   // prints 3
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn rotate_left(value: INT, bits: INT) -> INT {
+        value.rotate_left(bits.rem_euclid(INT_BITS as INT) as u32)
+    }
+    /// Rotate the bits in the number to the right by the specified number of `bits`,
+    /// wrapping the bits shifted out of the LSB around to the MSB.
+    ///
+    /// If `bits` < 0, the bits are rotated to the left instead.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = 0b0110;
+    ///
+    /// print(x.rotate_right(1));   // prints 3
+    ///
+    /// print(x.rotate_right(-2));  // prints 24
+    /// ```
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn rotate_right(value: INT, bits: INT) -> INT {
+        value.rotate_right(bits.rem_euclid(INT_BITS as INT) as u32)
+    }
 }
@@ -4,8 +4,8 @@ use crate::eval::{calc_index, calc_offset_len};
 use crate::module::ModuleFlags;
 use crate::plugin::*;
 use crate::{
-    def_package, Array, Blob, Dynamic, ExclusiveRange, InclusiveRange, NativeCallContext,
-    RhaiResultOf, INT, INT_BYTES, MAX_USIZE_INT,
+    def_package, Array, Blob, BlobView, Dynamic, ExclusiveRange, InclusiveRange, NativeCallContext,
+    Position, RhaiResultOf, ERR, INT, INT_BYTES, MAX_USIZE_INT,
 };
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
@@ -20,6 +20,7 @@ def_package! {
         lib.flags |= ModuleFlags::STANDARD_LIB;
 
         combine_with_exported_module!(lib, "blob", blob_functions);
+        combine_with_exported_module!(lib, "blob_view", blob_view_functions);
         combine_with_exported_module!(lib, "parse_int", parse_int_functions);
         combine_with_exported_module!(lib, "write_int", write_int_functions);
         combine_with_exported_module!(lib, "write_string", write_string_functions);
@@ -30,8 +31,9 @@ def_package! {
             combine_with_exported_module!(lib, "write_float", write_float_functions);
         }
 
-        // Register blob iterator
+        // Register blob iterators
         lib.set_iterable::<Blob>();
+        lib.set_iterable::<BlobView>();
     }
 }
 
@@ -378,8 +380,14 @@ pub mod blob_functions {
         // Check if blob will be over max size limit
         #[cfg(not(feature = "unchecked"))]
         if _ctx.engine().max_array_size() > 0 && len > _ctx.engine().max_array_size() {
+            let info = crate::LimitInfo::new(
+                crate::LimitKind::ArraySize,
+                _ctx.engine().max_array_size() as u64,
+                len as u64,
+            );
             return Err(crate::ERR::ErrorDataTooLarge(
                 "Size of BLOB".to_string(),
+                info,
                 crate::Position::NONE,
             )
             .into());
@@ -976,32 +984,297 @@ pub mod blob_functions {
     }
 }
 
+/// Create a view into a range of bytes in a BLOB view, sharing the same storage.
+fn sub_view(view: &BlobView, start: INT, len: INT) -> BlobView {
+    if view.is_empty() || len <= 0 {
+        return view.slice(view.len(), 0);
+    }
+
+    let (start, len) = calc_offset_len(view.len(), start, len);
+
+    view.slice(start, len)
+}
+
 #[export_module]
-mod parse_int_functions {
-    #[inline]
-    fn parse_int(blob: &mut Blob, start: INT, len: INT, is_le: bool) -> INT {
-        if blob.is_empty() || len <= 0 {
+pub mod blob_view_functions {
+    /// Create a view into the entire BLOB.
+    ///
+    /// A BLOB view shares the storage of the BLOB, and views created from a BLOB view share
+    /// the same storage without copying.  Modifying a BLOB view whose storage is shared first
+    /// copies the bytes in the view (i.e. copy-on-write).
+    ///
+    /// The BLOB is copied into shared storage if it is held in a variable, so create views
+    /// from other views to avoid copying the BLOB more than once.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let v = blob(10, 0x42).view();
+    ///
+    /// print(type_of(v));      // prints "blob-view"
+    ///
+    /// print(v.len());         // prints 10
+    /// ```
+    #[rhai_fn(name = "view")]
+    pub fn view(blob: Blob) -> BlobView {
+        BlobView::new(blob)
+    }
+    /// Create a view into the bytes within an exclusive `range` in the BLOB.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b += 1; b += 2; b += 3; b += 4; b += 5;
+    ///
+    /// let v = b.view(1..3);
+    ///
+    /// print(v);       // prints "[0203]"
+    /// ```
+    #[rhai_fn(name = "view")]
+    pub fn view_range(blob: Blob, range: ExclusiveRange) -> BlobView {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        sub_view(&BlobView::new(blob), start, end - start)
+    }
+    /// Create a view into the bytes within an inclusive `range` in the BLOB.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b += 1; b += 2; b += 3; b += 4; b += 5;
+    ///
+    /// let v = b.view(1..=3);
+    ///
+    /// print(v);       // prints "[020304]"
+    /// ```
+    #[rhai_fn(name = "view")]
+    pub fn view_range_inclusive(blob: Blob, range: InclusiveRange) -> BlobView {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        sub_view(&BlobView::new(blob), start, end - start + 1)
+    }
+    /// Create a view into `len` bytes beginning at the `start` position in the BLOB.
+    ///
+    /// * If `start` < 0, position counts from the end of the BLOB (`-1` is the last byte).
+    /// * If `start` < -length of BLOB, position counts from the beginning of the BLOB.
+    /// * If `start` ≥ length of BLOB, an empty view is returned.
+    /// * If `len` ≤ 0, an empty view is returned.
+    /// * If `start` position + `len` ≥ length of BLOB, the view covers the entire portion of the BLOB after the `start` position.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let b = blob();
+    ///
+    /// b += 1; b += 2; b += 3; b += 4; b += 5;
+    ///
+    /// let v = b.view(-3, 2);
+    ///
+    /// print(v);       // prints "[0304]"
+    /// ```
+    #[rhai_fn(name = "view")]
+    pub fn view_offset(blob: Blob, start: INT, len: INT) -> BlobView {
+        sub_view(&BlobView::new(blob), start, len)
+    }
+    /// Create a view into the bytes within an exclusive `range` in the BLOB view.
+    ///
+    /// The new view shares the same storage, so no bytes are copied.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let v = blob(10, 0x42).view();
+    ///
+    /// let v2 = v.view(1..3);
+    ///
+    /// print(v2);      // prints "[4242]"
+    /// ```
+    #[rhai_fn(name = "view", pure)]
+    pub fn view_of_view_range(view: &mut BlobView, range: ExclusiveRange) -> BlobView {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        sub_view(view, start, end - start)
+    }
+    /// Create a view into the bytes within an inclusive `range` in the BLOB view.
+    ///
+    /// The new view shares the same storage, so no bytes are copied.
+    #[rhai_fn(name = "view", pure)]
+    pub fn view_of_view_range_inclusive(view: &mut BlobView, range: InclusiveRange) -> BlobView {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        sub_view(view, start, end - start + 1)
+    }
+    /// Create a view into `len` bytes beginning at the `start` position in the BLOB view.
+    ///
+    /// The new view shares the same storage, so no bytes are copied.
+    ///
+    /// * If `start` < 0, position counts from the end of the BLOB view (`-1` is the last byte).
+    /// * If `start` < -length of BLOB view, position counts from the beginning of the BLOB view.
+    /// * If `start` ≥ length of BLOB view, an empty view is returned.
+    /// * If `len` ≤ 0, an empty view is returned.
+    /// * If `start` position + `len` ≥ length of BLOB view, the view covers the entire portion of the BLOB view after the `start` position.
+    #[rhai_fn(name = "view", pure)]
+    pub fn view_of_view(view: &mut BlobView, start: INT, len: INT) -> BlobView {
+        sub_view(view, start, len)
+    }
+    /// Copy the bytes in the BLOB view into a new BLOB.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let v = blob(10, 0x42).view(0..3);
+    ///
+    /// let b = v.to_blob();
+    ///
+    /// print(type_of(b));      // prints "blob"
+    /// ```
+    #[rhai_fn(pure)]
+    pub fn to_blob(view: &mut BlobView) -> Blob {
+        view.to_blob()
+    }
+    /// Convert the BLOB view into an array of integers.
+    #[rhai_fn(pure)]
+    pub fn to_array(view: &mut BlobView) -> Array {
+        view.iter().map(|&ch| (ch as INT).into()).collect()
+    }
+    /// Return the length of the BLOB view.
+    #[rhai_fn(name = "len", get = "len", pure)]
+    pub fn len(view: &mut BlobView) -> INT {
+        view.len() as INT
+    }
+    /// Return true if the BLOB view is empty.
+    #[rhai_fn(name = "is_empty", get = "is_empty", pure)]
+    pub fn is_empty(view: &mut BlobView) -> bool {
+        view.len() == 0
+    }
+    /// Return `true` if the BLOB view contains a specified byte value.
+    #[rhai_fn(name = "contains", pure)]
+    pub fn contains(view: &mut BlobView, value: INT) -> bool {
+        #[allow(clippy::cast_sign_loss)]
+        view.contains(&((value & 0x0000_00ff) as u8))
+    }
+    /// Get the byte value at the `index` position in the BLOB view.
+    ///
+    /// * If `index` < 0, position counts from the end of the BLOB view (`-1` is the last byte).
+    /// * If `index` < -length of BLOB view, zero is returned.
+    /// * If `index` ≥ length of BLOB view, zero is returned.
+    #[rhai_fn(pure)]
+    pub fn get(view: &mut BlobView, index: INT) -> INT {
+        if view.is_empty() {
             return 0;
         }
-        let (start, len) = calc_offset_len(blob.len(), start, len);
 
-        if len == 0 {
-            return 0;
+        let (index, ..) = calc_offset_len(view.len(), index, 0);
+
+        view.get(index).map_or(0, |&b| b as INT)
+    }
+    /// Set the particular `index` position in the BLOB view to a new byte `value`.
+    ///
+    /// If the storage of the BLOB view is shared, the bytes in the view are first copied.
+    ///
+    /// * If `index` < 0, position counts from the end of the BLOB view (`-1` is the last byte).
+    /// * If `index` < -length of BLOB view, the BLOB view is not modified.
+    /// * If `index` ≥ length of BLOB view, the BLOB view is not modified.
+    pub fn set(view: &mut BlobView, index: INT, value: INT) {
+        if view.is_empty() {
+            return;
         }
 
-        let len = usize::min(len, INT_BYTES);
+        let (index, ..) = calc_offset_len(view.len(), index, 0);
 
-        let mut buf = [0_u8; INT_BYTES];
+        if index >= view.len() {
+            return;
+        }
 
-        buf[..len].copy_from_slice(&blob[start..][..len]);
+        #[allow(clippy::cast_sign_loss)]
+        {
+            view.make_mut()[index] = (value & 0x0000_00ff) as u8;
+        }
+    }
+    /// Get the byte value at the `index` position in the BLOB view.
+    #[rhai_fn(index_get, return_raw, pure)]
+    pub fn index_get(view: &mut BlobView, index: INT) -> RhaiResultOf<INT> {
+        let len = view.len();
+        let index = calc_index(len, index, true, || {
+            ERR::ErrorArrayBounds(len, index, Position::NONE).into()
+        })?;
 
-        if is_le {
-            INT::from_le_bytes(buf)
-        } else {
-            INT::from_be_bytes(buf)
+        Ok(view[index] as INT)
+    }
+    /// Set the byte value at the `index` position in the BLOB view.
+    ///
+    /// If the storage of the BLOB view is shared, the bytes in the view are first copied.
+    #[rhai_fn(index_set, return_raw)]
+    pub fn index_set(view: &mut BlobView, index: INT, value: INT) -> RhaiResultOf<()> {
+        let len = view.len();
+        let index = calc_index(len, index, true, || {
+            ERR::ErrorArrayBounds(len, index, Position::NONE).into()
+        })?;
+
+        #[allow(clippy::cast_sign_loss)]
+        {
+            view.make_mut()[index] = (value & 0x0000_00ff) as u8;
         }
+
+        Ok(())
+    }
+    /// Create a view into the bytes within an exclusive `range` in the BLOB view.
+    #[rhai_fn(index_get, pure)]
+    pub fn index_get_range(view: &mut BlobView, range: ExclusiveRange) -> BlobView {
+        view_of_view_range(view, range)
+    }
+    /// Create a view into the bytes within an inclusive `range` in the BLOB view.
+    #[rhai_fn(index_get, pure)]
+    pub fn index_get_range_inclusive(view: &mut BlobView, range: InclusiveRange) -> BlobView {
+        view_of_view_range_inclusive(view, range)
     }
+    /// Convert the BLOB view into a string, in the same format as a BLOB.
+    #[rhai_fn(name = "to_string", name = "to_debug", pure)]
+    pub fn to_string(view: &mut BlobView) -> String {
+        format!("{view:?}")
+    }
+    /// Return `true` if two BLOB views contain the same bytes.
+    #[rhai_fn(name = "==", pure)]
+    pub fn equals(view1: &mut BlobView, view2: BlobView) -> bool {
+        *view1 == view2
+    }
+    /// Return `true` if two BLOB views do not contain the same bytes.
+    #[rhai_fn(name = "!=", pure)]
+    pub fn not_equals(view1: &mut BlobView, view2: BlobView) -> bool {
+        *view1 != view2
+    }
+}
+
+#[inline]
+fn parse_int(blob: &[u8], start: INT, len: INT, is_le: bool) -> INT {
+    if blob.is_empty() || len <= 0 {
+        return 0;
+    }
+    let (start, len) = calc_offset_len(blob.len(), start, len);
+
+    if len == 0 {
+        return 0;
+    }
+
+    let len = usize::min(len, INT_BYTES);
+
+    let mut buf = [0_u8; INT_BYTES];
+
+    buf[..len].copy_from_slice(&blob[start..][..len]);
+
+    if is_le {
+        INT::from_le_bytes(buf)
+    } else {
+        INT::from_be_bytes(buf)
+    }
+}
 
+#[export_module]
+mod parse_int_functions {
     /// Parse the bytes within an exclusive `range` in the BLOB as an `INT`
     /// in little-endian byte order.
     ///
@@ -1134,36 +1407,85 @@ mod parse_int_functions {
     pub fn parse_be_int(blob: &mut Blob, start: INT, len: INT) -> INT {
         parse_int(blob, start, len, false)
     }
+    /// Parse the bytes within an exclusive `range` in the BLOB view as an `INT`
+    /// in little-endian byte order.
+    #[rhai_fn(name = "parse_le_int", pure)]
+    pub fn parse_le_int_view_range(view: &mut BlobView, range: ExclusiveRange) -> INT {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        parse_le_int_view(view, start, end - start)
+    }
+    /// Parse the bytes within an inclusive `range` in the BLOB view as an `INT`
+    /// in little-endian byte order.
+    #[rhai_fn(name = "parse_le_int", pure)]
+    pub fn parse_le_int_view_range_inclusive(view: &mut BlobView, range: InclusiveRange) -> INT {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        parse_le_int_view(view, start, end - start + 1)
+    }
+    /// Parse the bytes beginning at the `start` position in the BLOB view as an `INT`
+    /// in little-endian byte order.
+    ///
+    /// The bytes are not copied.
+    #[rhai_fn(name = "parse_le_int", pure)]
+    pub fn parse_le_int_view(view: &mut BlobView, start: INT, len: INT) -> INT {
+        parse_int(view, start, len, true)
+    }
+    /// Parse the bytes within an exclusive `range` in the BLOB view as an `INT`
+    /// in big-endian byte order.
+    #[rhai_fn(name = "parse_be_int", pure)]
+    pub fn parse_be_int_view_range(view: &mut BlobView, range: ExclusiveRange) -> INT {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        parse_be_int_view(view, start, end - start)
+    }
+    /// Parse the bytes within an inclusive `range` in the BLOB view as an `INT`
+    /// in big-endian byte order.
+    #[rhai_fn(name = "parse_be_int", pure)]
+    pub fn parse_be_int_view_range_inclusive(view: &mut BlobView, range: InclusiveRange) -> INT {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        parse_be_int_view(view, start, end - start + 1)
+    }
+    /// Parse the bytes beginning at the `start` position in the BLOB view as an `INT`
+    /// in big-endian byte order.
+    ///
+    /// The bytes are not copied.
+    #[rhai_fn(name = "parse_be_int", pure)]
+    pub fn parse_be_int_view(view: &mut BlobView, start: INT, len: INT) -> INT {
+        parse_int(view, start, len, false)
+    }
 }
 
 #[cfg(not(feature = "no_float"))]
-#[export_module]
-mod parse_float_functions {
-    #[inline]
-    fn parse_float(blob: &mut Blob, start: INT, len: INT, is_le: bool) -> FLOAT {
-        if blob.is_empty() || len <= 0 {
-            return 0.0;
-        }
+#[inline]
+fn parse_float(blob: &[u8], start: INT, len: INT, is_le: bool) -> FLOAT {
+    if blob.is_empty() || len <= 0 {
+        return 0.0;
+    }
 
-        let (start, len) = calc_offset_len(blob.len(), start, len);
+    let (start, len) = calc_offset_len(blob.len(), start, len);
 
-        if len == 0 {
-            return 0.0;
-        }
+    if len == 0 {
+        return 0.0;
+    }
 
-        let len = usize::min(len, FLOAT_BYTES);
+    let len = usize::min(len, FLOAT_BYTES);
 
-        let mut buf = [0_u8; FLOAT_BYTES];
+    let mut buf = [0_u8; FLOAT_BYTES];
 
-        buf[..len].copy_from_slice(&blob[start..][..len]);
+    buf[..len].copy_from_slice(&blob[start..][..len]);
 
-        if is_le {
-            FLOAT::from_le_bytes(buf)
-        } else {
-            FLOAT::from_be_bytes(buf)
-        }
+    if is_le {
+        FLOAT::from_le_bytes(buf)
+    } else {
+        FLOAT::from_be_bytes(buf)
     }
+}
 
+#[cfg(not(feature = "no_float"))]
+#[export_module]
+mod parse_float_functions {
     /// Parse the bytes within an exclusive `range` in the BLOB as a `FLOAT`
     /// in little-endian byte order.
     ///
@@ -1236,32 +1558,87 @@ mod parse_float_functions {
     pub fn parse_be_float(blob: &mut Blob, start: INT, len: INT) -> FLOAT {
         parse_float(blob, start, len, false)
     }
+    /// Parse the bytes within an exclusive `range` in the BLOB view as a `FLOAT`
+    /// in little-endian byte order.
+    #[rhai_fn(name = "parse_le_float", pure)]
+    pub fn parse_le_float_view_range(view: &mut BlobView, range: ExclusiveRange) -> FLOAT {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        parse_le_float_view(view, start, end - start)
+    }
+    /// Parse the bytes within an inclusive `range` in the BLOB view as a `FLOAT`
+    /// in little-endian byte order.
+    #[rhai_fn(name = "parse_le_float", pure)]
+    pub fn parse_le_float_view_range_inclusive(
+        view: &mut BlobView,
+        range: InclusiveRange,
+    ) -> FLOAT {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        parse_le_float_view(view, start, end - start + 1)
+    }
+    /// Parse the bytes beginning at the `start` position in the BLOB view as a `FLOAT`
+    /// in little-endian byte order.
+    ///
+    /// The bytes are not copied.
+    #[rhai_fn(name = "parse_le_float", pure)]
+    pub fn parse_le_float_view(view: &mut BlobView, start: INT, len: INT) -> FLOAT {
+        parse_float(view, start, len, true)
+    }
+    /// Parse the bytes within an exclusive `range` in the BLOB view as a `FLOAT`
+    /// in big-endian byte order.
+    #[rhai_fn(name = "parse_be_float", pure)]
+    pub fn parse_be_float_view_range(view: &mut BlobView, range: ExclusiveRange) -> FLOAT {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        parse_be_float_view(view, start, end - start)
+    }
+    /// Parse the bytes within an inclusive `range` in the BLOB view as a `FLOAT`
+    /// in big-endian byte order.
+    #[rhai_fn(name = "parse_be_float", pure)]
+    pub fn parse_be_float_view_range_inclusive(
+        view: &mut BlobView,
+        range: InclusiveRange,
+    ) -> FLOAT {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        parse_be_float_view(view, start, end - start + 1)
+    }
+    /// Parse the bytes beginning at the `start` position in the BLOB view as a `FLOAT`
+    /// in big-endian byte order.
+    ///
+    /// The bytes are not copied.
+    #[rhai_fn(name = "parse_be_float", pure)]
+    pub fn parse_be_float_view(view: &mut BlobView, start: INT, len: INT) -> FLOAT {
+        parse_float(view, start, len, false)
+    }
 }
 
-#[export_module]
-mod write_int_functions {
-    #[inline]
-    fn write_int(blob: &mut Blob, start: INT, len: INT, value: INT, is_le: bool) {
-        if blob.is_empty() || len <= 0 {
-            return;
-        }
+#[inline]
+fn write_int(blob: &mut [u8], start: INT, len: INT, value: INT, is_le: bool) {
+    if blob.is_empty() || len <= 0 {
+        return;
+    }
 
-        let (start, len) = calc_offset_len(blob.len(), start, len);
+    let (start, len) = calc_offset_len(blob.len(), start, len);
 
-        if len == 0 {
-            return;
-        }
+    if len == 0 {
+        return;
+    }
 
-        let len = usize::min(len, INT_BYTES);
+    let len = usize::min(len, INT_BYTES);
 
-        let buf = if is_le {
-            value.to_le_bytes()
-        } else {
-            value.to_be_bytes()
-        };
+    let buf = if is_le {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    };
 
-        blob[start..][..len].copy_from_slice(&buf[..len]);
-    }
+    blob[start..][..len].copy_from_slice(&buf[..len]);
+}
+
+#[export_module]
+mod write_int_functions {
     /// Write an `INT` value to the bytes within an exclusive `range` in the BLOB
     /// in little-endian byte order.
     ///
@@ -1384,32 +1761,90 @@ mod write_int_functions {
     pub fn write_be_int(blob: &mut Blob, start: INT, len: INT, value: INT) {
         write_int(blob, start, len, value, false);
     }
+    /// Write an `INT` value to the bytes within an exclusive `range` in the BLOB view
+    /// in little-endian byte order.
+    #[rhai_fn(name = "write_le")]
+    pub fn write_le_int_view_range(view: &mut BlobView, range: ExclusiveRange, value: INT) {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        write_le_int_view(view, start, end - start, value);
+    }
+    /// Write an `INT` value to the bytes within an inclusive `range` in the BLOB view
+    /// in little-endian byte order.
+    #[rhai_fn(name = "write_le")]
+    pub fn write_le_int_view_range_inclusive(
+        view: &mut BlobView,
+        range: InclusiveRange,
+        value: INT,
+    ) {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        write_le_int_view(view, start, end - start + 1, value);
+    }
+    /// Write an `INT` value to the bytes beginning at the `start` position in the BLOB view
+    /// in little-endian byte order.
+    ///
+    /// If the storage of the BLOB view is shared, the bytes in the view are first copied.
+    #[rhai_fn(name = "write_le")]
+    pub fn write_le_int_view(view: &mut BlobView, start: INT, len: INT, value: INT) {
+        write_int(view.make_mut(), start, len, value, true);
+    }
+    /// Write an `INT` value to the bytes within an exclusive `range` in the BLOB view
+    /// in big-endian byte order.
+    #[rhai_fn(name = "write_be")]
+    pub fn write_be_int_view_range(view: &mut BlobView, range: ExclusiveRange, value: INT) {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        write_be_int_view(view, start, end - start, value);
+    }
+    /// Write an `INT` value to the bytes within an inclusive `range` in the BLOB view
+    /// in big-endian byte order.
+    #[rhai_fn(name = "write_be")]
+    pub fn write_be_int_view_range_inclusive(
+        view: &mut BlobView,
+        range: InclusiveRange,
+        value: INT,
+    ) {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        write_be_int_view(view, start, end - start + 1, value);
+    }
+    /// Write an `INT` value to the bytes beginning at the `start` position in the BLOB view
+    /// in big-endian byte order.
+    ///
+    /// If the storage of the BLOB view is shared, the bytes in the view are first copied.
+    #[rhai_fn(name = "write_be")]
+    pub fn write_be_int_view(view: &mut BlobView, start: INT, len: INT, value: INT) {
+        write_int(view.make_mut(), start, len, value, false);
+    }
 }
 
 #[cfg(not(feature = "no_float"))]
-#[export_module]
-mod write_float_functions {
-    #[inline]
-    fn write_float(blob: &mut Blob, start: INT, len: INT, value: FLOAT, is_le: bool) {
-        if blob.is_empty() || len <= 0 {
-            return;
-        }
+#[inline]
+fn write_float(blob: &mut [u8], start: INT, len: INT, value: FLOAT, is_le: bool) {
+    if blob.is_empty() || len <= 0 {
+        return;
+    }
 
-        let (start, len) = calc_offset_len(blob.len(), start, len);
+    let (start, len) = calc_offset_len(blob.len(), start, len);
 
-        if len == 0 {
-            return;
-        }
+    if len == 0 {
+        return;
+    }
 
-        let len = usize::min(len, FLOAT_BYTES);
-        let buf = if is_le {
-            value.to_le_bytes()
-        } else {
-            value.to_be_bytes()
-        };
+    let len = usize::min(len, FLOAT_BYTES);
+    let buf = if is_le {
+        value.to_le_bytes()
+    } else {
+        value.to_be_bytes()
+    };
 
-        blob[start..][..len].copy_from_slice(&buf[..len]);
-    }
+    blob[start..][..len].copy_from_slice(&buf[..len]);
+}
+
+#[cfg(not(feature = "no_float"))]
+#[export_module]
+mod write_float_functions {
     /// Write a `FLOAT` value to the bytes within an exclusive `range` in the BLOB
     /// in little-endian byte order.
     ///
@@ -1484,6 +1919,62 @@ mod write_float_functions {
     pub fn write_be_float(blob: &mut Blob, start: INT, len: INT, value: FLOAT) {
         write_float(blob, start, len, value, false);
     }
+    /// Write a `FLOAT` value to the bytes within an exclusive `range` in the BLOB view
+    /// in little-endian byte order.
+    #[rhai_fn(name = "write_le")]
+    pub fn write_le_float_view_range(view: &mut BlobView, range: ExclusiveRange, value: FLOAT) {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        write_le_float_view(view, start, end - start, value);
+    }
+    /// Write a `FLOAT` value to the bytes within an inclusive `range` in the BLOB view
+    /// in little-endian byte order.
+    #[rhai_fn(name = "write_le")]
+    pub fn write_le_float_view_range_inclusive(
+        view: &mut BlobView,
+        range: InclusiveRange,
+        value: FLOAT,
+    ) {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        write_le_float_view(view, start, end - start + 1, value);
+    }
+    /// Write a `FLOAT` value to the bytes beginning at the `start` position in the BLOB view
+    /// in little-endian byte order.
+    ///
+    /// If the storage of the BLOB view is shared, the bytes in the view are first copied.
+    #[rhai_fn(name = "write_le")]
+    pub fn write_le_float_view(view: &mut BlobView, start: INT, len: INT, value: FLOAT) {
+        write_float(view.make_mut(), start, len, value, true);
+    }
+    /// Write a `FLOAT` value to the bytes within an exclusive `range` in the BLOB view
+    /// in big-endian byte order.
+    #[rhai_fn(name = "write_be")]
+    pub fn write_be_float_view_range(view: &mut BlobView, range: ExclusiveRange, value: FLOAT) {
+        let start = INT::max(range.start, 0);
+        let end = INT::max(range.end, start);
+        write_be_float_view(view, start, end - start, value);
+    }
+    /// Write a `FLOAT` value to the bytes within an inclusive `range` in the BLOB view
+    /// in big-endian byte order.
+    #[rhai_fn(name = "write_be")]
+    pub fn write_be_float_view_range_inclusive(
+        view: &mut BlobView,
+        range: InclusiveRange,
+        value: FLOAT,
+    ) {
+        let start = INT::max(*range.start(), 0);
+        let end = INT::max(*range.end(), start);
+        write_be_float_view(view, start, end - start + 1, value);
+    }
+    /// Write a `FLOAT` value to the bytes beginning at the `start` position in the BLOB view
+    /// in big-endian byte order.
+    ///
+    /// If the storage of the BLOB view is shared, the bytes in the view are first copied.
+    #[rhai_fn(name = "write_be")]
+    pub fn write_be_float_view(view: &mut BlobView, start: INT, len: INT, value: FLOAT) {
+        write_float(view.make_mut(), start, len, value, false);
+    }
 }
 
 #[export_module]
@@ -0,0 +1,143 @@
+//! Equality of arrays and object maps.
+#![cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+
+use crate::engine::OP_EQUALS;
+use crate::types::dynamic::Union;
+use crate::{Dynamic, NativeCallContext, RhaiResultOf, ERR};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+#[cfg(not(feature = "no_index"))]
+use crate::Array;
+#[cfg(not(feature = "no_object"))]
+use crate::Map;
+
+/// Comparer of arrays and object maps, including nested ones.
+///
+/// Nested arrays and object maps (including shared ones) are compared directly instead of via
+/// the `==` operator, so that a pair of shared values that is revisited (i.e. a shared value that
+/// contains itself) is considered equal instead of causing infinite recursion.
+///
+/// All other values are compared via the `==` operator.
+pub struct EqualityComparer<'a, 'c> {
+    /// Call context used to call the `==` operator.
+    ctx: &'a NativeCallContext<'c>,
+    /// Pairs of values, at least one of which is shared, being compared.
+    #[cfg(not(feature = "no_closure"))]
+    visiting: crate::StaticVec<(*const (), *const ())>,
+}
+
+impl<'a, 'c> EqualityComparer<'a, 'c> {
+    /// Create a new [`EqualityComparer`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(ctx: &'a NativeCallContext<'c>) -> Self {
+        Self {
+            ctx,
+            #[cfg(not(feature = "no_closure"))]
+            visiting: crate::StaticVec::new_const(),
+        }
+    }
+    /// Are two arrays equal?
+    ///
+    /// Elements of different types without an `==` operator are not equal.
+    #[cfg(not(feature = "no_index"))]
+    pub fn arrays_equal(&mut self, array1: &mut Array, array2: &mut Array) -> RhaiResultOf<bool> {
+        if array1.len() != array2.len() {
+            return Ok(false);
+        }
+
+        for (a1, a2) in array1.iter_mut().zip(array2.iter_mut()) {
+            if !self.values_equal(a1, a2, true)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+    /// Are two object maps equal?
+    #[cfg(not(feature = "no_object"))]
+    pub fn maps_equal(&mut self, map1: &mut Map, map2: &mut Map) -> RhaiResultOf<bool> {
+        if map1.len() != map2.len() {
+            return Ok(false);
+        }
+
+        for (m1, v1) in map1 {
+            match map2.get_mut(m1) {
+                Some(v2) => {
+                    if !self.values_equal(v1, v2, false)? {
+                        return Ok(false);
+                    }
+                }
+                _ => return Ok(false),
+            }
+        }
+
+        Ok(true)
+    }
+    /// Are two values equal?
+    ///
+    /// If `in_array` is `true`, values of different types without an `==` operator are not equal.
+    fn values_equal(
+        &mut self,
+        value1: &mut Dynamic,
+        value2: &mut Dynamic,
+        in_array: bool,
+    ) -> RhaiResultOf<bool> {
+        #[cfg(not(feature = "no_closure"))]
+        if value1.is_shared() || value2.is_shared() {
+            // Values that are not shared are identified by their addresses
+            let id = |value: &Dynamic| match value.shared_ptr() {
+                ptr if ptr.is_null() => (value as *const Dynamic).cast(),
+                ptr => ptr,
+            };
+            let pair = (id(value1), id(value2));
+
+            if self.visiting.contains(&pair) {
+                return Ok(true);
+            }
+
+            // A shared value that is locked is being compared (or modified) higher up
+            let (Some(mut value1), Some(mut value2)) =
+                (value1.try_flatten_clone(), value2.try_flatten_clone())
+            else {
+                return Ok(true);
+            };
+
+            self.visiting.push(pair);
+            let result = self.values_equal(&mut value1, &mut value2, in_array);
+            self.visiting.pop();
+            return result;
+        }
+
+        match (&mut value1.0, &mut value2.0) {
+            #[cfg(not(feature = "no_index"))]
+            (Union::Array(a1, ..), Union::Array(a2, ..)) => return self.arrays_equal(a1, a2),
+            #[cfg(not(feature = "no_object"))]
+            (Union::Map(m1, ..), Union::Map(m2, ..)) => return self.maps_equal(m1, m2),
+            _ => (),
+        }
+
+        let result = self
+            .ctx
+            .call_native_fn_raw(OP_EQUALS, true, &mut [value1, value2]);
+
+        let result = if in_array {
+            result.or_else(|err| match *err {
+                ERR::ErrorFunctionNotFound(ref fn_sig, ..) if fn_sig.starts_with(OP_EQUALS) => {
+                    if value1.type_id() == value2.type_id() {
+                        // No default when comparing same type
+                        Err(err)
+                    } else {
+                        Ok(Dynamic::FALSE)
+                    }
+                }
+                _ => Err(err),
+            })?
+        } else {
+            result?
+        };
+
+        Ok(result.as_bool().unwrap_or(false))
+    }
+}
@@ -9,7 +9,7 @@ use std::prelude::v1::*;
 use std::{
     any::type_name,
     cmp::Ordering,
-    fmt::Debug,
+    fmt::{Debug, Display},
     iter::{ExactSizeIterator, FusedIterator},
     ops::{Range, RangeInclusive},
     vec::IntoIter,
@@ -48,6 +48,7 @@ pub struct StepRange<T> {
     pub step: T,
     pub add: fn(T, T) -> Option<T>,
     pub dir: i8,
+    pub inclusive: bool,
 }
 
 impl<T: Debug> Debug for StepRange<T> {
@@ -58,12 +59,30 @@ impl<T: Debug> Debug for StepRange<T> {
             .field(&self.from)
             .field(&self.to)
             .field(&self.step)
+            .field(&self.inclusive)
             .finish()
     }
 }
 
 impl<T: Copy + PartialOrd> StepRange<T> {
     pub fn new(from: T, to: T, step: T, add: fn(T, T) -> Option<T>) -> RhaiResultOf<Self> {
+        Self::new_raw(from, to, step, add, false)
+    }
+    pub fn new_inclusive(
+        from: T,
+        to: T,
+        step: T,
+        add: fn(T, T) -> Option<T>,
+    ) -> RhaiResultOf<Self> {
+        Self::new_raw(from, to, step, add, true)
+    }
+    fn new_raw(
+        from: T,
+        to: T,
+        step: T,
+        add: fn(T, T) -> Option<T>,
+        inclusive: bool,
+    ) -> RhaiResultOf<Self> {
         let mut dir = 0;
 
         if let Some(n) = add(from, step) {
@@ -79,9 +98,11 @@ impl<T: Copy + PartialOrd> StepRange<T> {
                 .into());
             }
 
-            match from.partial_cmp(&to).unwrap_or(Ordering::Equal) {
-                Ordering::Less if n > from => dir = 1,
-                Ordering::Greater if n < from => dir = -1,
+            match from.partial_cmp(&to) {
+                Some(Ordering::Less) if n > from => dir = 1,
+                Some(Ordering::Greater) if n < from => dir = -1,
+                Some(Ordering::Equal) if inclusive && n > from => dir = 1,
+                Some(Ordering::Equal) if inclusive && n < from => dir = -1,
                 _ => (),
             }
         }
@@ -92,6 +113,7 @@ impl<T: Copy + PartialOrd> StepRange<T> {
             step,
             add,
             dir,
+            inclusive,
         })
     }
 }
@@ -106,11 +128,19 @@ impl<T: Copy + PartialOrd> Iterator for StepRange<T> {
 
         let v = self.from;
 
-        self.from = (self.add)(self.from, self.step)?;
+        // Stop after this value if the next one overflows
+        let Some(next) = (self.add)(self.from, self.step) else {
+            self.dir = 0;
+            return Some(v);
+        };
+
+        self.from = next;
 
         match self.dir.cmp(&0) {
-            Ordering::Greater if self.from >= self.to => self.dir = 0,
-            Ordering::Less if self.from <= self.to => self.dir = 0,
+            Ordering::Greater if self.inclusive && self.from > self.to => self.dir = 0,
+            Ordering::Greater if !self.inclusive && self.from >= self.to => self.dir = 0,
+            Ordering::Less if self.inclusive && self.from < self.to => self.dir = 0,
+            Ordering::Less if !self.inclusive && self.from <= self.to => self.dir = 0,
             Ordering::Equal => unreachable!("`dir` != 0"),
             _ => (),
         }
@@ -652,6 +682,45 @@ def_package! {
     }
 }
 
+/// Create a [`StepRange`] over a range for `step_by`.
+///
+/// Unlike `range`, an error is raised if `step` goes against the direction of a non-empty range.
+fn step_by<T: Copy + PartialOrd + Display>(
+    from: T,
+    to: T,
+    step: T,
+    inclusive: bool,
+    add: fn(T, T) -> Option<T>,
+) -> RhaiResultOf<StepRange<T>> {
+    let range = if inclusive {
+        StepRange::new_inclusive(from, to, step, add)?
+    } else {
+        StepRange::new(from, to, step, add)?
+    };
+
+    let is_empty = match from.partial_cmp(&to) {
+        Some(Ordering::Equal) => !inclusive,
+        Some(..) => false,
+        None => true,
+    };
+
+    if range.dir == 0 && !is_empty {
+        let op = if inclusive { "..=" } else { ".." };
+        return Err(ERR::ErrorArithmetic(
+            format!("step value {step} goes against the direction of the range {from}{op}{to}"),
+            Position::NONE,
+        )
+        .into());
+    }
+
+    Ok(range)
+}
+
+#[cfg(not(feature = "unchecked"))]
+const INT_ADD: fn(INT, INT) -> Option<INT> = std_add;
+#[cfg(feature = "unchecked")]
+const INT_ADD: fn(INT, INT) -> Option<INT> = regular_add;
+
 #[export_module]
 mod range_functions {
     /// Return the start of the exclusive range.
@@ -720,4 +789,192 @@ mod range_functions {
     pub fn contains_inclusive(range: &mut InclusiveRange, value: INT) -> bool {
         range.contains(&value)
     }
+
+    /// Return an iterator over the exclusive range, each iteration increasing by `step`.
+    ///
+    /// If the range is reversed (i.e. `start` > `end`), `step` must be negative and iteration
+    /// goes backwards.
+    ///
+    /// An error is raised if `step` goes against the direction of the range.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// // prints 0, 3, 6, 9
+    /// for n in (0..10).step_by(3) {
+    ///     print(n);
+    /// }
+    ///
+    /// // prints 10, 7, 4, 1
+    /// for n in (10..0).step_by(-3) {
+    ///     print(n);
+    /// }
+    /// ```
+    #[rhai_fn(name = "step_by", return_raw, pure)]
+    pub fn step_by_exclusive(
+        range: &mut ExclusiveRange,
+        step: INT,
+    ) -> RhaiResultOf<StepRange<INT>> {
+        step_by(range.start, range.end, step, false, INT_ADD)
+    }
+    /// Return an iterator over the inclusive range, each iteration increasing by `step`.
+    ///
+    /// If the range is reversed (i.e. `start` > `end`), `step` must be negative and iteration
+    /// goes backwards.
+    ///
+    /// An error is raised if `step` goes against the direction of the range.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// // prints 0, 5, 10
+    /// for n in (0..=10).step_by(5) {
+    ///     print(n);
+    /// }
+    ///
+    /// // prints 10, 5, 0
+    /// for n in (10..=0).step_by(-5) {
+    ///     print(n);
+    /// }
+    /// ```
+    #[rhai_fn(name = "step_by", return_raw, pure)]
+    pub fn step_by_inclusive(
+        range: &mut InclusiveRange,
+        step: INT,
+    ) -> RhaiResultOf<StepRange<INT>> {
+        step_by(*range.start(), *range.end(), step, true, INT_ADD)
+    }
+
+    /// Return the start of the exclusive floating-point range.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "start", name = "start", pure)]
+    pub fn start_float(range: &mut Range<FLOAT>) -> FLOAT {
+        range.start
+    }
+    /// Return the end of the exclusive floating-point range.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "end", name = "end", pure)]
+    pub fn end_float(range: &mut Range<FLOAT>) -> FLOAT {
+        range.end
+    }
+    /// Return `true` if the range is inclusive.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "is_inclusive", name = "is_inclusive", pure)]
+    pub fn is_inclusive_float(range: &mut Range<FLOAT>) -> bool {
+        let _ = range;
+        false
+    }
+    /// Return `true` if the range is exclusive.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "is_exclusive", name = "is_exclusive", pure)]
+    pub fn is_exclusive_float(range: &mut Range<FLOAT>) -> bool {
+        let _ = range;
+        true
+    }
+    /// Return true if the range contains no values.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "is_empty", name = "is_empty", pure)]
+    pub fn is_empty_float(range: &mut Range<FLOAT>) -> bool {
+        range.is_empty()
+    }
+    /// Return `true` if the range contains a specified value.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "contains", pure)]
+    pub fn contains_float(range: &mut Range<FLOAT>, value: FLOAT) -> bool {
+        range.contains(&value)
+    }
+    /// Return `true` if the range contains a specified integer value.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "contains", pure)]
+    pub fn contains_float_int(range: &mut Range<FLOAT>, value: INT) -> bool {
+        range.contains(&(value as FLOAT))
+    }
+    /// Return an iterator over the exclusive floating-point range, each iteration increasing by `step`.
+    ///
+    /// If the range is reversed (i.e. `start` > `end`), `step` must be negative and iteration
+    /// goes backwards.
+    ///
+    /// An error is raised if `step` goes against the direction of the range.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// // prints 0.0, 0.25, 0.5, 0.75
+    /// for n in (0.0..1.0).step_by(0.25) {
+    ///     print(n);
+    /// }
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "step_by", return_raw, pure)]
+    pub fn step_by_float(range: &mut Range<FLOAT>, step: FLOAT) -> RhaiResultOf<StepRange<FLOAT>> {
+        step_by(range.start, range.end, step, false, regular_add)
+    }
+
+    /// Return the start of the inclusive floating-point range.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "start", name = "start", pure)]
+    pub fn start_float_inclusive(range: &mut RangeInclusive<FLOAT>) -> FLOAT {
+        *range.start()
+    }
+    /// Return the end of the inclusive floating-point range.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "end", name = "end", pure)]
+    pub fn end_float_inclusive(range: &mut RangeInclusive<FLOAT>) -> FLOAT {
+        *range.end()
+    }
+    /// Return `true` if the range is inclusive.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "is_inclusive", name = "is_inclusive", pure)]
+    pub fn is_inclusive_float_inclusive(range: &mut RangeInclusive<FLOAT>) -> bool {
+        let _ = range;
+        true
+    }
+    /// Return `true` if the range is exclusive.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "is_exclusive", name = "is_exclusive", pure)]
+    pub fn is_exclusive_float_inclusive(range: &mut RangeInclusive<FLOAT>) -> bool {
+        let _ = range;
+        false
+    }
+    /// Return true if the range contains no values.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(get = "is_empty", name = "is_empty", pure)]
+    pub fn is_empty_float_inclusive(range: &mut RangeInclusive<FLOAT>) -> bool {
+        range.is_empty()
+    }
+    /// Return `true` if the range contains a specified value.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "contains", pure)]
+    pub fn contains_float_inclusive(range: &mut RangeInclusive<FLOAT>, value: FLOAT) -> bool {
+        range.contains(&value)
+    }
+    /// Return `true` if the range contains a specified integer value.
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "contains", pure)]
+    pub fn contains_float_inclusive_int(range: &mut RangeInclusive<FLOAT>, value: INT) -> bool {
+        range.contains(&(value as FLOAT))
+    }
+    /// Return an iterator over the inclusive floating-point range, each iteration increasing by `step`.
+    ///
+    /// If the range is reversed (i.e. `start` > `end`), `step` must be negative and iteration
+    /// goes backwards.
+    ///
+    /// An error is raised if `step` goes against the direction of the range.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// // prints 0.0, 0.25, 0.5, 0.75, 1.0
+    /// for n in (0.0..=1.0).step_by(0.25) {
+    ///     print(n);
+    /// }
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "step_by", return_raw, pure)]
+    pub fn step_by_float_inclusive(
+        range: &mut RangeInclusive<FLOAT>,
+        step: FLOAT,
+    ) -> RhaiResultOf<StepRange<FLOAT>> {
+        step_by(*range.start(), *range.end(), step, true, regular_add)
+    }
 }
@@ -98,8 +98,8 @@ mod core_functions {
     /// ```
     #[rhai_fn(name = "set_tag", set = "tag", return_raw)]
     pub fn set_tag(value: &mut Dynamic, tag: INT) -> RhaiResultOf<()> {
-        const TAG_MIN: Tag = Tag::MIN;
-        const TAG_MAX: Tag = Tag::MAX;
+        const TAG_MIN: Tag = Dynamic::MIN_TAG;
+        const TAG_MAX: Tag = Dynamic::MAX_TAG;
 
         if tag < TAG_MIN as INT {
             return Err(ERR::ErrorArithmetic(
@@ -123,9 +123,44 @@ mod core_functions {
         value.set_tag(tag as Tag);
         Ok(())
     }
+    /// Return the provenance of a value as an object map containing the `line` number and the
+    /// character `position` of the expression that produced it.
+    ///
+    /// Returns `()` if provenance tracking is not enabled or if the value has no recorded provenance.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let x = 42;
+    ///
+    /// let y = x;
+    ///
+    /// print(provenance(y));   // prints #{"line": 1, "position": 9}
+    /// ```
+    #[cfg(not(feature = "no_object"))]
+    pub fn provenance(ctx: NativeCallContext, value: Dynamic) -> Dynamic {
+        if !ctx.engine().track_provenance() {
+            return Dynamic::UNIT;
+        }
+
+        value.provenance().map_or(Dynamic::UNIT, |pos| {
+            let mut map = crate::Map::new();
+            map.insert(
+                "line".into(),
+                pos.line().map_or(Dynamic::UNIT, |n| (n as INT).into()),
+            );
+            map.insert(
+                "position".into(),
+                pos.position().map_or(Dynamic::UNIT, |n| (n as INT).into()),
+            );
+            map.into()
+        })
+    }
 
     /// Block the current thread for a particular number of `seconds`.
     ///
+    /// The script can still be terminated via the progress callback while sleeping.
+    ///
     /// # Example
     ///
     /// ```rhai
@@ -134,19 +169,23 @@ mod core_functions {
     /// ```
     #[cfg(not(feature = "no_float"))]
     #[cfg(not(feature = "no_std"))]
-    #[rhai_fn(name = "sleep")]
-    pub fn sleep_float(seconds: FLOAT) {
+    #[rhai_fn(name = "sleep", return_raw)]
+    pub fn sleep_float(ctx: NativeCallContext, seconds: FLOAT) -> RhaiResultOf<()> {
         if seconds <= 0.0 {
-            return;
+            return Ok(());
         }
 
         #[cfg(not(feature = "f32_float"))]
-        std::thread::sleep(std::time::Duration::from_secs_f64(seconds));
+        let duration = std::time::Duration::from_secs_f64(seconds);
         #[cfg(feature = "f32_float")]
-        std::thread::sleep(std::time::Duration::from_secs_f32(seconds));
+        let duration = std::time::Duration::from_secs_f32(seconds);
+
+        sleep_for(&ctx, duration)
     }
     /// Block the current thread for a particular number of `seconds`.
     ///
+    /// The script can still be terminated via the progress callback while sleeping.
+    ///
     /// # Example
     ///
     /// ```rhai
@@ -154,13 +193,28 @@ mod core_functions {
     /// sleep(10);
     /// ```
     #[cfg(not(feature = "no_std"))]
-    pub fn sleep(seconds: INT) {
+    #[rhai_fn(return_raw)]
+    pub fn sleep(ctx: NativeCallContext, seconds: INT) -> RhaiResultOf<()> {
         if seconds <= 0 {
-            return;
+            return Ok(());
         }
 
         #[allow(clippy::cast_sign_loss)]
-        std::thread::sleep(std::time::Duration::from_secs(seconds as u64));
+        sleep_for(&ctx, std::time::Duration::from_secs(seconds as u64))
+    }
+    /// Give the host a chance to terminate the script by calling the progress callback.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// loop {
+    ///     do_some_work();
+    ///     yield_now();
+    /// }
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn yield_now(ctx: NativeCallContext) -> RhaiResultOf<()> {
+        ctx.check_progress()
     }
 
     /// Parse a JSON string into a value.
@@ -181,6 +235,32 @@ mod core_functions {
     }
 }
 
+/// Block the current thread for a [`Duration`][std::time::Duration], calling the progress
+/// callback (if any) between increments.
+#[cfg(not(feature = "no_std"))]
+fn sleep_for(_ctx: &NativeCallContext, duration: std::time::Duration) -> RhaiResultOf<()> {
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let step = _ctx.engine().sleep_granularity();
+
+        if _ctx.engine().progress.is_some() && !step.is_zero() {
+            let mut remaining = duration;
+
+            while remaining > step {
+                std::thread::sleep(step);
+                remaining -= step;
+                _ctx.check_progress()?;
+            }
+
+            std::thread::sleep(remaining);
+            return Ok(());
+        }
+    }
+
+    std::thread::sleep(duration);
+    Ok(())
+}
+
 #[cfg(not(feature = "no_function"))]
 #[cfg(not(feature = "no_index"))]
 #[cfg(not(feature = "no_object"))]
@@ -255,6 +335,7 @@ fn collect_fn_metadata(
             "is_anonymous".into(),
             func.name.starts_with(FN_ANONYMOUS).into(),
         );
+        map.insert("is_variadic".into(), func.is_variadic.into());
         #[cfg(not(feature = "no_object"))]
         if let Some(ref this_type) = func.this_type {
             map.insert("this_type".into(), this_type.into());
@@ -1,14 +1,18 @@
 #![cfg(not(feature = "no_object"))]
 
-use crate::engine::OP_EQUALS;
 use crate::module::ModuleFlags;
+use crate::packages::equality::EqualityComparer;
 use crate::plugin::*;
-use crate::{def_package, Dynamic, ImmutableString, Map, NativeCallContext, RhaiResultOf, INT};
+use crate::{
+    def_package, Dynamic, FnPtr, ImmutableString, Map, NativeCallContext, RhaiResultOf, ERR, INT,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
 #[cfg(not(feature = "no_index"))]
-use crate::Array;
+use crate::{Array, Identifier};
+#[cfg(not(feature = "no_index"))]
+use std::{any::TypeId, cmp::Ordering};
 
 def_package! {
     /// Package of basic object map utilities.
@@ -16,6 +20,12 @@ def_package! {
         lib.flags |= ModuleFlags::STANDARD_LIB;
 
         combine_with_exported_module!(lib, "map", map_functions);
+
+        // Register properties iterator, each as a `[name, value]` array
+        #[cfg(not(feature = "no_index"))]
+        lib.set_iter(TypeId::of::<Map>(), |value| Box::new(
+            value.cast::<Map>().into_iter().map(|(k, v)| Dynamic::from_array(vec![k.into(), v]))
+        ));
     }
 }
 
@@ -167,6 +177,40 @@ mod map_functions {
         map1.extend(map2);
         map1
     }
+    /// Make a copy of the object map, deeply merge all property values of another object map into
+    /// it, then return it.
+    ///
+    /// Nested object maps are merged recursively. When a property exists in both object maps
+    /// (and they are not both object maps), the `resolver` function is called to decide the
+    /// merged value.
+    ///
+    /// # Function Parameters
+    ///
+    /// * `key`: name of the conflicting property
+    /// * `value1`: copy of the property value in the object map
+    /// * `value2`: copy of the property value in the other object map
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = #{a:1, b:#{x:1, y:2}};
+    /// let n = #{a:41, b:#{y:40, z:3}, c:0};
+    ///
+    /// let r = m.merge(n, |key, v1, v2| v1 + v2);
+    ///
+    /// print(r);       // prints "#{a:42, b:#{x:1, y:42, z:3}, c:0}"
+    /// ```
+    #[rhai_fn(name = "merge", return_raw, pure)]
+    pub fn merge_with(
+        ctx: NativeCallContext,
+        map: &mut Map,
+        map2: Map,
+        resolver: FnPtr,
+    ) -> RhaiResultOf<Map> {
+        let mut result = map.clone();
+        deep_merge(&ctx, &mut result, map2, &resolver)?;
+        Ok(result)
+    }
     /// Add all property values of another object map into the object map.
     /// Only properties that do not originally exist in the object map are added.
     ///
@@ -214,28 +258,13 @@ mod map_functions {
         if map1.len() != map2.len() {
             return Ok(false);
         }
-
-        if !map1.is_empty() {
-            let mut map2 = map2;
-
-            for (m1, v1) in map1 {
-                match map2.get_mut(m1) {
-                    Some(v2) => {
-                        let equals = ctx
-                            .call_native_fn_raw(OP_EQUALS, true, &mut [v1, v2])?
-                            .as_bool()
-                            .unwrap_or(false);
-
-                        if !equals {
-                            return Ok(false);
-                        }
-                    }
-                    _ => return Ok(false),
-                }
-            }
+        if map1.is_empty() {
+            return Ok(true);
         }
 
-        Ok(true)
+        let mut map2 = map2;
+
+        EqualityComparer::new(&ctx).maps_equal(map1, &mut map2)
     }
     /// Return `true` if two object maps are not equal (i.e. at least one property value is not equal).
     ///
@@ -294,6 +323,140 @@ mod map_functions {
 
         map.values().cloned().collect()
     }
+    /// Return an array with all the properties in the object map, each as a `[name, value]` array.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = #{a:1, b:2, c:3};
+    ///
+    /// print(m.entries());     // prints "[["a", 1], ["b", 2], ["c", 3]]"
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(pure)]
+    pub fn entries(map: &mut Map) -> Array {
+        if map.is_empty() {
+            return Array::new();
+        }
+
+        map.iter()
+            .map(|(k, v)| Dynamic::from_array(vec![k.clone().into(), v.clone()]))
+            .collect()
+    }
+    /// Return an array with all the property names in the object map, sorted via a comparer
+    /// function.
+    ///
+    /// The comparer function takes two property names as parameters and must return an integer:
+    ///
+    /// * Any positive integer: the first name is _after_ the second
+    /// * Zero: the names are equal
+    /// * Any negative integer: the first name is _before_ the second
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = #{a:1, bbb:2, cc:3};
+    ///
+    /// print(m.keys_sorted_by(|x, y| y.len - x.len));      // prints ["bbb", "cc", "a"]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn keys_sorted_by(
+        ctx: NativeCallContext,
+        map: &mut Map,
+        comparer: FnPtr,
+    ) -> RhaiResultOf<Array> {
+        Ok(sort_by_key(&ctx, map, &comparer)?
+            .into_iter()
+            .map(|(k, ..)| k.clone().into())
+            .collect())
+    }
+    /// Return an array with all the property values in the object map, in the order of their
+    /// property names sorted via a comparer function.
+    ///
+    /// The comparer function takes two property names as parameters and must return an integer:
+    ///
+    /// * Any positive integer: the first name is _after_ the second
+    /// * Zero: the names are equal
+    /// * Any negative integer: the first name is _before_ the second
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = #{a:1, bbb:2, cc:3};
+    ///
+    /// print(m.values_sorted_by_key(|x, y| y.len - x.len));    // prints [2, 3, 1]
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw, pure)]
+    pub fn values_sorted_by_key(
+        ctx: NativeCallContext,
+        map: &mut Map,
+        comparer: FnPtr,
+    ) -> RhaiResultOf<Array> {
+        Ok(sort_by_key(&ctx, map, &comparer)?
+            .into_iter()
+            .map(|(.., v)| v.clone())
+            .collect())
+    }
+    /// Create an object map from an array of `[name, value]` arrays.
+    ///
+    /// Each entry must be an array of two items, the first of which is a string or character.
+    /// Later entries replace earlier ones with the same name.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let m = from_entries([["a", 1], ["b", 2], ['c', 3]]);
+    ///
+    /// print(m);       // prints "#{a:1, b:2, c:3}"
+    /// ```
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(return_raw)]
+    pub fn from_entries(ctx: NativeCallContext, entries: Array) -> RhaiResultOf<Map> {
+        let mut map = Map::new();
+
+        for (i, entry) in entries.into_iter().enumerate() {
+            let make_err = |msg: String| {
+                ERR::ErrorRuntime(
+                    format!("Invalid entry at index {i}: {msg}").into(),
+                    ctx.position(),
+                )
+            };
+
+            let typ = entry.type_name();
+            let mut entry = entry.into_array().map_err(|_| {
+                make_err(format!(
+                    "expecting an array of [name, value] but found '{typ}'"
+                ))
+            })?;
+
+            if entry.len() != 2 {
+                let len = entry.len();
+                return Err(make_err(format!(
+                    "expecting an array of [name, value] but found {len} items"
+                ))
+                .into());
+            }
+
+            let value = entry.pop().unwrap();
+            let key = entry.pop().unwrap();
+
+            let key = if key.is_char() {
+                key.as_char().unwrap().to_string().into()
+            } else {
+                let typ = key.type_name();
+                key.into_immutable_string()
+                    .map_err(|_| make_err(format!("expecting a string name but found '{typ}'")))?
+                    .as_str()
+                    .into()
+            };
+
+            map.insert(key, value);
+        }
+
+        Ok(map)
+    }
     /// Return the JSON representation of the object map.
     ///
     /// # Data types
@@ -320,3 +483,61 @@ mod map_functions {
         return crate::format_map_as_json(map);
     }
 }
+
+/// Deeply merge the properties of `map2` into `map`, calling `resolver` on conflicts.
+fn deep_merge(
+    ctx: &NativeCallContext,
+    map: &mut Map,
+    map2: Map,
+    resolver: &FnPtr,
+) -> RhaiResultOf<()> {
+    for (key, value2) in map2 {
+        match map.get_mut(&key) {
+            Some(value) if value.is_map() && value2.is_map() => {
+                let mut inner = value.write_lock::<Map>().unwrap();
+                deep_merge(ctx, &mut inner, value2.cast::<Map>(), resolver)?;
+            }
+            Some(value) => {
+                let args = [key.as_str().into(), value.clone(), value2];
+                *value = resolver.call_raw(ctx, None, args)?;
+            }
+            None => {
+                map.insert(key, value2);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Sort the properties of an object map by their names via a comparer function.
+#[cfg(not(feature = "no_index"))]
+fn sort_by_key<'a>(
+    ctx: &NativeCallContext,
+    map: &'a Map,
+    comparer: &FnPtr,
+) -> RhaiResultOf<Vec<(&'a Identifier, &'a Dynamic)>> {
+    let mut entries = map.iter().collect::<Vec<_>>();
+    let mut error = None;
+
+    entries.sort_by(|(a, ..), (b, ..)| {
+        if error.is_some() {
+            return Ordering::Equal;
+        }
+
+        let args = (
+            ImmutableString::from(a.as_str()),
+            ImmutableString::from(b.as_str()),
+        );
+
+        match comparer.call_within_context::<INT>(ctx, args) {
+            Ok(v) => v.cmp(&0),
+            Err(err) => {
+                error = Some(err);
+                Ordering::Equal
+            }
+        }
+    });
+
+    error.map_or(Ok(entries), Err)
+}
@@ -4,9 +4,12 @@ use crate::{Engine, Module, SharedModule};
 
 pub(crate) mod arithmetic;
 pub(crate) mod array_basic;
+pub(crate) mod bigint_basic;
 pub(crate) mod bit_field;
 pub(crate) mod blob_basic;
+mod case_folding;
 pub(crate) mod debugging;
+mod equality;
 pub(crate) mod fn_basic;
 pub(crate) mod iter_basic;
 pub(crate) mod lang_core;
@@ -22,6 +25,8 @@ pub(crate) mod time_basic;
 pub use arithmetic::ArithmeticPackage;
 #[cfg(not(feature = "no_index"))]
 pub use array_basic::BasicArrayPackage;
+#[cfg(feature = "bigint")]
+pub use bigint_basic::BasicBigIntPackage;
 pub use bit_field::BitFieldPackage;
 #[cfg(not(feature = "no_index"))]
 pub use blob_basic::BasicBlobPackage;
@@ -18,6 +18,7 @@ def_package! {
     /// * [`BasicBlobPackage`][super::BasicBlobPackage]
     /// * [`BasicMapPackage`][super::BasicMapPackage]
     /// * [`BasicTimePackage`][super::BasicTimePackage]
+    /// * [`BasicBigIntPackage`][super::BasicBigIntPackage]
     /// * [`MoreStringPackage`][super::MoreStringPackage]
     pub StandardPackage(lib) :
             CorePackage,
@@ -28,6 +29,7 @@ def_package! {
             #[cfg(not(feature = "no_index"))] BasicBlobPackage,
             #[cfg(not(feature = "no_object"))] BasicMapPackage,
             #[cfg(not(feature = "no_time"))] BasicTimePackage,
+            #[cfg(feature = "bigint")] BasicBigIntPackage,
             MoreStringPackage
     {
         lib.flags |= ModuleFlags::STANDARD_LIB;
@@ -1,5 +1,7 @@
 use crate::module::ModuleFlags;
 use crate::plugin::*;
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+use crate::{types::dynamic::Union, DebugFormat};
 use crate::{def_package, FnPtr, ImmutableString, SmartString, INT};
 use std::any::TypeId;
 use std::fmt::{Binary, LowerHex, Octal, Write};
@@ -63,6 +65,135 @@ pub fn print_with_func(
     }
 }
 
+/// Formatter of arrays and object maps, including nested ones, in debug format.
+///
+/// Shared arrays and object maps that have already been visited are output as `<shared #n>`,
+/// so that cycles do not cause infinite recursion.
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+struct DebugFormatter<'a, 'c> {
+    /// Call context used to format all other values via `to_debug`.
+    ctx: &'a NativeCallContext<'c>,
+    /// Output format.
+    format: DebugFormat,
+    /// Shared arrays and object maps visited, in order.
+    #[cfg(not(feature = "no_closure"))]
+    shared: crate::StaticVec<*const ()>,
+}
+
+#[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+impl<'a, 'c> DebugFormatter<'a, 'c> {
+    /// Create a new [`DebugFormatter`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(ctx: &'a NativeCallContext<'c>, format: DebugFormat) -> Self {
+        Self {
+            ctx,
+            format,
+            #[cfg(not(feature = "no_closure"))]
+            shared: crate::StaticVec::new_const(),
+        }
+    }
+    /// Start a new line at a nesting level (pretty format only).
+    fn write_new_line(&self, buf: &mut SmartString, level: usize) {
+        if let DebugFormat::Pretty { indent } = self.format {
+            buf.push('\n');
+            (0..indent * level).for_each(|_| buf.push(' '));
+        }
+    }
+    /// Write the separator after an item.
+    fn write_separator(&self, buf: &mut SmartString, is_last: bool) {
+        match self.format {
+            DebugFormat::Compact if is_last => (),
+            DebugFormat::Compact => buf.push_str(", "),
+            DebugFormat::Pretty { .. } => buf.push(','),
+        }
+    }
+    /// Write an array.
+    #[cfg(not(feature = "no_index"))]
+    pub fn write_array(&mut self, buf: &mut SmartString, array: &mut Array, level: usize) {
+        let len = array.len();
+        buf.push('[');
+
+        array.iter_mut().enumerate().for_each(|(i, item)| {
+            self.write_new_line(buf, level + 1);
+            self.write_value(buf, item, level + 1);
+            self.write_separator(buf, i == len - 1);
+        });
+
+        if len > 0 {
+            self.write_new_line(buf, level);
+        }
+        buf.push(']');
+    }
+    /// Write an object map.
+    #[cfg(not(feature = "no_object"))]
+    pub fn write_map(&mut self, buf: &mut SmartString, map: &mut Map, level: usize) {
+        let len = map.len();
+        buf.push_str("#{");
+
+        map.iter_mut().enumerate().for_each(|(i, (key, value))| {
+            self.write_new_line(buf, level + 1);
+            write!(buf, "{key:?}: ").unwrap();
+            self.write_value(buf, value, level + 1);
+            self.write_separator(buf, i == len - 1);
+        });
+
+        if len > 0 {
+            self.write_new_line(buf, level);
+        }
+        buf.push('}');
+    }
+    /// Write any value.
+    fn write_value(&mut self, buf: &mut SmartString, value: &mut Dynamic, level: usize) {
+        match value.0 {
+            #[cfg(not(feature = "no_index"))]
+            Union::Array(ref mut array, ..) => return self.write_array(buf, array, level),
+            #[cfg(not(feature = "no_object"))]
+            Union::Map(ref mut map, ..) => return self.write_map(buf, map, level),
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(ref cell, ..) => {
+                let cell = cell.clone();
+                let ptr = crate::Shared::as_ptr(&cell).cast::<()>();
+
+                if let Some(n) = self.shared.iter().position(|&p| p == ptr) {
+                    write!(buf, "<shared #{}>", n + 1).unwrap();
+                    return;
+                }
+
+                // A shared value that cannot be locked is currently being formatted
+                #[cfg(not(feature = "sync"))]
+                let guard = cell.try_borrow_mut().ok();
+                #[cfg(feature = "sync")]
+                let guard = cell.try_write().ok();
+
+                match guard {
+                    Some(mut guard) => match guard.0 {
+                        #[cfg(not(feature = "no_index"))]
+                        Union::Array(..) => {
+                            self.shared.push(ptr);
+                            return self.write_value(buf, &mut guard, level);
+                        }
+                        #[cfg(not(feature = "no_object"))]
+                        Union::Map(..) => {
+                            self.shared.push(ptr);
+                            return self.write_value(buf, &mut guard, level);
+                        }
+                        _ => (),
+                    },
+                    None => {
+                        self.shared.push(ptr);
+                        write!(buf, "<shared #{}>", self.shared.len()).unwrap();
+                        return;
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        buf.push_str(&print_with_func(FUNC_TO_DEBUG, self.ctx, value));
+    }
+}
+
 #[export_module]
 mod print_debug_functions {
     /// Convert the value of the `item` into a string.
@@ -195,56 +326,38 @@ mod print_debug_functions {
 
     /// Convert the array into a string.
     #[cfg(not(feature = "no_index"))]
-    #[rhai_fn(
-        name = "print",
-        name = "to_string",
-        name = "debug",
-        name = "to_debug",
-        pure
-    )]
+    #[rhai_fn(name = "print", name = "to_string", pure)]
     pub fn format_array(ctx: NativeCallContext, array: &mut Array) -> ImmutableString {
-        let len = array.len();
-        let mut result = SmartString::new_const();
-        result.push('[');
-
-        array.iter_mut().enumerate().for_each(|(i, x)| {
-            result.push_str(&print_with_func(FUNC_TO_DEBUG, &ctx, x));
-            if i < len - 1 {
-                result.push_str(", ");
-            }
-        });
-
-        result.push(']');
-        result.into()
+        let mut buf = SmartString::new_const();
+        DebugFormatter::new(&ctx, DebugFormat::Compact).write_array(&mut buf, array, 0);
+        buf.into()
+    }
+    /// Convert the array into a string in debug format.
+    #[cfg(not(feature = "no_index"))]
+    #[rhai_fn(name = "debug", name = "to_debug", pure)]
+    pub fn debug_array(ctx: NativeCallContext, array: &mut Array) -> ImmutableString {
+        let mut buf = SmartString::new_const();
+        let format = ctx.engine().debug_format();
+        DebugFormatter::new(&ctx, format).write_array(&mut buf, array, 0);
+        buf.into()
     }
 
     /// Convert the object map into a string.
     #[cfg(not(feature = "no_object"))]
-    #[rhai_fn(
-        name = "print",
-        name = "to_string",
-        name = "debug",
-        name = "to_debug",
-        pure
-    )]
+    #[rhai_fn(name = "print", name = "to_string", pure)]
     pub fn format_map(ctx: NativeCallContext, map: &mut Map) -> ImmutableString {
-        let len = map.len();
-        let mut result = SmartString::new_const();
-        result.push_str("#{");
-
-        map.iter_mut().enumerate().for_each(|(i, (k, v))| {
-            write!(
-                result,
-                "{:?}: {}{}",
-                k,
-                &print_with_func(FUNC_TO_DEBUG, &ctx, v),
-                if i < len - 1 { ", " } else { "" }
-            )
-            .unwrap();
-        });
-
-        result.push('}');
-        result.into()
+        let mut buf = SmartString::new_const();
+        DebugFormatter::new(&ctx, DebugFormat::Compact).write_map(&mut buf, map, 0);
+        buf.into()
+    }
+    /// Convert the object map into a string in debug format.
+    #[cfg(not(feature = "no_object"))]
+    #[rhai_fn(name = "debug", name = "to_debug", pure)]
+    pub fn debug_map(ctx: NativeCallContext, map: &mut Map) -> ImmutableString {
+        let mut buf = SmartString::new_const();
+        let format = ctx.engine().debug_format();
+        DebugFormatter::new(&ctx, format).write_map(&mut buf, map, 0);
+        buf.into()
     }
 }
 
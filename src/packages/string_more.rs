@@ -8,6 +8,7 @@ use crate::{
 use std::prelude::v1::*;
 use std::{any::TypeId, mem};
 
+use super::case_folding::CASE_FOLDING;
 use super::string_basic::{print_with_func, FUNC_TO_STRING};
 
 def_package! {
@@ -16,7 +17,70 @@ def_package! {
         lib.flags |= ModuleFlags::STANDARD_LIB;
 
         combine_with_exported_module!(lib, "string", string_functions);
+
+        #[cfg(feature = "regex")]
+        combine_with_exported_module!(lib, "regex", regex_functions);
+
+        #[cfg(feature = "normalize")]
+        combine_with_exported_module!(lib, "normalize", normalize_functions);
+
+        #[cfg(feature = "unicode-segmentation")]
+        {
+            lib.set_iterator::<GraphemesStream>();
+            combine_with_exported_module!(lib, "graphemes", grapheme_functions);
+        }
+    }
+}
+
+/// Iterator over the grapheme clusters of a string.
+#[cfg(feature = "unicode-segmentation")]
+#[derive(Debug, Clone)]
+pub struct GraphemesStream(std::vec::IntoIter<ImmutableString>);
+
+#[cfg(feature = "unicode-segmentation")]
+impl Iterator for GraphemesStream {
+    type Item = ImmutableString;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Get a compiled regular expression from the [`Engine`][crate::Engine]'s cache, compiling it if necessary.
+#[cfg(feature = "regex")]
+fn get_regex(ctx: &NativeCallContext, pattern: &str) -> RhaiResultOf<regex_lite::Regex> {
+    match ctx.engine().regex_cache {
+        Some(ref cache) => crate::func::locked_write(cache).get(pattern),
+        None => regex_lite::Regex::new(pattern),
+    }
+    .map_err(|err| crate::ERR::ErrorRuntime(err.to_string().into(), ctx.position()).into())
+}
+
+/// Fold a string for caseless matching, according to the full case folding of Unicode.
+///
+/// The folding is locale-independent, so the Turkic mappings of `I` and `ı` do not apply.
+fn case_fold(string: &str) -> SmartString {
+    let mut buf = SmartString::new_const();
+
+    for ch in string.chars() {
+        if ch.is_ascii() {
+            buf.push(ch.to_ascii_lowercase());
+            continue;
+        }
+
+        match CASE_FOLDING.binary_search_by_key(&ch, |&(c, ..)| c) {
+            Ok(index) => buf.push_str(CASE_FOLDING[index].1),
+            Err(..) => buf.extend(ch.to_lowercase()),
+        }
     }
+
+    buf
 }
 
 #[export_module]
@@ -45,9 +109,7 @@ mod string_functions {
             return;
         }
 
-        let mut buf = <ImmutableString as AsRef<SmartString>>::as_ref(string).clone();
-        buf.push_str(&s);
-        *string = buf.into();
+        string.make_mut().push_str(&s);
     }
     #[rhai_fn(name = "+", pure)]
     pub fn add_prepend(
@@ -138,9 +200,7 @@ mod string_functions {
                 return;
             }
 
-            let mut s = <ImmutableString as AsRef<SmartString>>::as_ref(string).clone();
-            s.push_str(&String::from_utf8_lossy(&utf8));
-            *string = s.into();
+            string.make_mut().push_str(&String::from_utf8_lossy(&utf8));
         }
         #[rhai_fn(name = "+")]
         pub fn add_prepend(utf8: Blob, string: &str) -> ImmutableString {
@@ -446,6 +506,35 @@ mod string_functions {
 
         *string = string.to_lowercase().into();
     }
+    /// Reverse the string in place.
+    ///
+    /// Under the `unicode-segmentation` feature, grapheme clusters (e.g. emoji sequences or
+    /// characters with combining accents) are kept intact. Otherwise, characters are reversed.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "hello, world!";
+    ///
+    /// text.reverse();
+    ///
+    /// print(text);        // prints "!dlrow ,olleh"
+    /// ```
+    pub fn reverse(string: &mut ImmutableString) {
+        if string.len() <= 1 {
+            return;
+        }
+
+        #[cfg(feature = "unicode-segmentation")]
+        let reversed: SmartString =
+            unicode_segmentation::UnicodeSegmentation::graphemes(string.as_str(), true)
+                .rev()
+                .collect();
+        #[cfg(not(feature = "unicode-segmentation"))]
+        let reversed: SmartString = string.chars().rev().collect();
+
+        *string = reversed.into();
+    }
 
     /// Convert the character to upper-case and return it as a new character.
     ///
@@ -519,6 +608,62 @@ mod string_functions {
     pub fn make_lower_char(character: &mut char) {
         *character = to_lower_char(*character);
     }
+    /// Fold the string for caseless matching and return it as a new string.
+    ///
+    /// Full Unicode case folding is used, which does not depend on the locale.
+    ///
+    /// * `ß` folds to `ss`.
+    /// * `I` folds to `i`, and `İ` (capital I with dot above) folds to `i` followed by U+0307 (combining dot above).
+    /// * `ı` (dotless small i) is unchanged, so `"ı".casefold() != "I".casefold()`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "Straße";
+    ///
+    /// print(text.casefold());     // prints "strasse"
+    ///
+    /// print(text);                // prints "Straße"
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn casefold(
+        ctx: NativeCallContext,
+        string: &mut ImmutableString,
+    ) -> RhaiResultOf<ImmutableString> {
+        if string.is_empty() {
+            return Ok(string.clone());
+        }
+
+        ctx.track_operations(string.len() as u64)?;
+
+        Ok(case_fold(string).into())
+    }
+    /// Return `true` if the string is equal to another string when compared caselessly,
+    /// i.e. after full Unicode case folding of both (see `casefold`).
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "STRASSE";
+    ///
+    /// print(text.eq_ignore_case("straße"));   // prints true
+    ///
+    /// print(text.eq_ignore_case("strasze"));  // prints false
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn eq_ignore_case(ctx: NativeCallContext, string: &str, other: &str) -> RhaiResultOf<bool> {
+        if string == other {
+            return Ok(true);
+        }
+
+        ctx.track_operations((string.len() + other.len()) as u64)?;
+
+        if string.is_ascii() && other.is_ascii() {
+            return Ok(string.eq_ignore_ascii_case(other));
+        }
+
+        Ok(case_fold(string) == case_fold(other))
+    }
 
     /// Return `true` if the string contains a specified string.
     ///
@@ -1286,8 +1431,14 @@ mod string_functions {
         // Check if string will be over max size limit
         #[cfg(not(feature = "unchecked"))]
         if _ctx.engine().max_string_size() > 0 && len > _ctx.engine().max_string_size() {
+            let info = crate::LimitInfo::new(
+                crate::LimitKind::StringLength,
+                _ctx.engine().max_string_size() as u64,
+                len as u64,
+            );
             return Err(crate::ERR::ErrorDataTooLarge(
                 "Length of string".to_string(),
+                info,
                 crate::Position::NONE,
             )
             .into());
@@ -1307,8 +1458,14 @@ mod string_functions {
 
         #[cfg(not(feature = "unchecked"))]
         if _ctx.engine().max_string_size() > 0 && string.len() > _ctx.engine().max_string_size() {
+            let info = crate::LimitInfo::new(
+                crate::LimitKind::StringLength,
+                _ctx.engine().max_string_size() as u64,
+                string.len() as u64,
+            );
             return Err(crate::ERR::ErrorDataTooLarge(
                 "Length of string".to_string(),
+                info,
                 crate::Position::NONE,
             )
             .into());
@@ -1350,8 +1507,14 @@ mod string_functions {
         // Check if string will be over max size limit
         #[cfg(not(feature = "unchecked"))]
         if _ctx.engine().max_string_size() > 0 && len > _ctx.engine().max_string_size() {
+            let info = crate::LimitInfo::new(
+                crate::LimitKind::StringLength,
+                _ctx.engine().max_string_size() as u64,
+                len as u64,
+            );
             return Err(crate::ERR::ErrorDataTooLarge(
                 "Length of string".to_string(),
+                info,
                 crate::Position::NONE,
             )
             .into());
@@ -1378,8 +1541,14 @@ mod string_functions {
 
         #[cfg(not(feature = "unchecked"))]
         if _ctx.engine().max_string_size() > 0 && string.len() > _ctx.engine().max_string_size() {
+            let info = crate::LimitInfo::new(
+                crate::LimitKind::StringLength,
+                _ctx.engine().max_string_size() as u64,
+                string.len() as u64,
+            );
             return Err(crate::ERR::ErrorDataTooLarge(
                 "Length of string".to_string(),
+                info,
                 crate::Position::NONE,
             )
             .into());
@@ -1688,3 +1857,222 @@ mod string_functions {
         }
     }
 }
+
+#[cfg(feature = "normalize")]
+#[export_module]
+mod normalize_functions {
+    use unicode_normalization::UnicodeNormalization;
+
+    /// Convert the string to the specified Unicode normalization form and return it as a new string.
+    ///
+    /// `form` must be one of `"NFC"`, `"NFD"`, `"NFKC"` or `"NFKD"`.
+    ///
+    /// Strings that differ only in their composition compare equal after normalization
+    /// into the same form.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "e\u0301";      // e + combining acute accent
+    ///
+    /// print(text.normalize("NFC") == "\u00e9");      // prints true
+    ///
+    /// print(text.normalize("NFD") == text);          // prints true
+    /// ```
+    #[rhai_fn(return_raw, pure)]
+    pub fn normalize(
+        ctx: NativeCallContext,
+        string: &mut ImmutableString,
+        form: &str,
+    ) -> RhaiResultOf<ImmutableString> {
+        let normalized: SmartString = match form {
+            "NFC" => string.nfc().collect(),
+            "NFD" => string.nfd().collect(),
+            "NFKC" => string.nfkc().collect(),
+            "NFKD" => string.nfkd().collect(),
+            _ => {
+                return Err(crate::ERR::ErrorRuntime(
+                    format!("Invalid normalization form: '{form}'").into(),
+                    ctx.position(),
+                )
+                .into())
+            }
+        };
+
+        ctx.track_operations(string.len() as u64)?;
+
+        Ok(normalized.into())
+    }
+}
+
+#[cfg(feature = "unicode-segmentation")]
+#[export_module]
+mod grapheme_functions {
+    use super::GraphemesStream;
+    use unicode_segmentation::UnicodeSegmentation;
+
+    /// Return an iterator over the grapheme clusters (i.e. user-perceived characters) in the string.
+    ///
+    /// Each grapheme cluster is returned as a string.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// for g in graphemes("e\u0301👨‍👩‍👧") {
+    ///     print(g);       // prints "é" then "👨‍👩‍👧"
+    /// }
+    /// ```
+    pub fn graphemes(string: &str) -> GraphemesStream {
+        GraphemesStream(
+            string
+                .graphemes(true)
+                .map(Into::into)
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+    /// Return the length of the string, in number of grapheme clusters (i.e. user-perceived characters).
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "👨‍👩‍👧 e\u0301";
+    ///
+    /// print(text.len);                // prints 8
+    ///
+    /// print(text.grapheme_len());     // prints 3
+    /// ```
+    pub fn grapheme_len(string: &str) -> INT {
+        if string.is_empty() {
+            return 0;
+        }
+
+        string.graphemes(true).count() as INT
+    }
+    /// Copy a portion of the string, counted in grapheme clusters (i.e. user-perceived characters),
+    /// and return it as a new string.
+    ///
+    /// * If `start` < 0, position counts from the end of the string (`-1` is the last grapheme cluster).
+    /// * If `start` < -length of string, position counts from the beginning of the string.
+    /// * If `start` ≥ length of string, an empty string is returned.
+    /// * If `len` ≤ 0, an empty string is returned.
+    /// * If `start` position + `len` ≥ length of string, entire portion of the string after the `start` position is copied and returned.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "a👨‍👩‍👧e\u0301z";
+    ///
+    /// print(text.sub_graphemes(1, 2));    // prints "👨‍👩‍👧é"
+    ///
+    /// print(text.sub_graphemes(-2, 1));   // prints "é"
+    /// ```
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub fn sub_graphemes(
+        ctx: NativeCallContext,
+        string: &str,
+        start: INT,
+        len: INT,
+    ) -> ImmutableString {
+        if string.is_empty() || len <= 0 {
+            return ctx.engine().const_empty_string();
+        }
+
+        let graphemes: Vec<_> = string.graphemes(true).collect();
+
+        let offset = if start < 0 {
+            let abs_start = start.unsigned_abs();
+
+            #[allow(clippy::unnecessary_cast)]
+            if abs_start as u64 > MAX_USIZE_INT as u64 {
+                return ctx.engine().const_empty_string();
+            }
+
+            graphemes.len().saturating_sub(abs_start as usize)
+        } else if start > MAX_USIZE_INT || start as usize >= graphemes.len() {
+            return ctx.engine().const_empty_string();
+        } else {
+            start as usize
+        };
+
+        let len = (len.min(MAX_USIZE_INT) as usize).min(graphemes.len() - offset);
+
+        graphemes[offset..offset + len].concat().into()
+    }
+}
+
+#[cfg(feature = "regex")]
+#[export_module]
+mod regex_functions {
+    /// Replace all matches of the regular expression `pattern` in the string with `replacement`,
+    /// returning the new string.
+    ///
+    /// Capture groups can be referenced in `replacement` by index (e.g. `$1`) or by name (e.g. `$name`).
+    /// Use `$$` for a literal `$`.
+    ///
+    /// An error is raised if `pattern` is not a valid regular expression.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let text = "2023-12-25";
+    ///
+    /// let x = text.replace_re("(\\d+)-(\\d+)-(\\d+)", "$3/$2/$1");
+    ///
+    /// print(x);       // prints "25/12/2023"
+    /// ```
+    #[rhai_fn(return_raw)]
+    pub fn replace_re(
+        ctx: NativeCallContext,
+        string: &str,
+        pattern: &str,
+        replacement: &str,
+    ) -> RhaiResultOf<ImmutableString> {
+        let re = get_regex(&ctx, pattern)?;
+        Ok(re.replace_all(string, replacement).into_owned().into())
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    pub mod arrays {
+        use crate::Array;
+
+        /// Return an array containing all non-overlapping matches of the regular expression
+        /// `pattern` in the string.
+        ///
+        /// An error is raised if `pattern` is not a valid regular expression.
+        ///
+        /// # Example
+        ///
+        /// ```rhai
+        /// let text = "a1 b22 c333";
+        ///
+        /// print(text.matches("\\d+"));      // prints ["1", "22", "333"]
+        /// ```
+        #[rhai_fn(return_raw)]
+        pub fn matches(ctx: NativeCallContext, string: &str, pattern: &str) -> RhaiResultOf<Array> {
+            let re = get_regex(&ctx, pattern)?;
+            Ok(re.find_iter(string).map(|m| m.as_str().into()).collect())
+        }
+        /// Split the string into segments separated by matches of the regular expression `pattern`,
+        /// returning an array of the segments.
+        ///
+        /// An error is raised if `pattern` is not a valid regular expression.
+        ///
+        /// # Example
+        ///
+        /// ```rhai
+        /// let text = "a, b;c ,d";
+        ///
+        /// print(text.split_re("\\s*[,;]\\s*"));      // prints ["a", "b", "c", "d"]
+        /// ```
+        #[rhai_fn(return_raw)]
+        pub fn split_re(
+            ctx: NativeCallContext,
+            string: &str,
+            pattern: &str,
+        ) -> RhaiResultOf<Array> {
+            let re = get_regex(&ctx, pattern)?;
+            Ok(re.split(string).map(Into::into).collect())
+        }
+    }
+}
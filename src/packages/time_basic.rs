@@ -3,7 +3,9 @@
 use super::arithmetic::make_err as make_arithmetic_err;
 use crate::module::ModuleFlags;
 use crate::plugin::*;
-use crate::{def_package, Dynamic, RhaiResult, RhaiResultOf, INT};
+use crate::{def_package, Dynamic, ImmutableString, RhaiResult, RhaiResultOf, INT};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
 
 #[cfg(not(feature = "no_float"))]
 use crate::FLOAT;
@@ -114,6 +116,165 @@ mod time_functions {
         }
     }
 
+    /// Return the number of seconds between two timestamps, which is negative if `timestamp2`
+    /// is later than `timestamp1`.
+    ///
+    /// This is the same as `timestamp1 - timestamp2`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let now = timestamp();
+    /// let later = now + 90;
+    ///
+    /// print(timestamp_diff(later, now));      // prints 90.0
+    ///
+    /// print(timestamp_diff(now, later));      // prints -90.0
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    pub fn timestamp_diff(timestamp1: Instant, timestamp2: Instant) -> FLOAT {
+        if timestamp2 > timestamp1 {
+            -(timestamp2 - timestamp1).as_secs_f64() as FLOAT
+        } else {
+            (timestamp1 - timestamp2).as_secs_f64() as FLOAT
+        }
+    }
+    /// Return the number of milliseconds between two timestamps, which is negative if `timestamp2`
+    /// is later than `timestamp1`.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let now = timestamp();
+    /// let later = now + 90;
+    ///
+    /// print(timestamp_diff(later, now));      // prints 90000
+    ///
+    /// print(timestamp_diff(now, later));      // prints -90000
+    /// ```
+    #[cfg(feature = "no_float")]
+    #[rhai_fn(name = "timestamp_diff", return_raw)]
+    pub fn timestamp_diff_millis(timestamp1: Instant, timestamp2: Instant) -> RhaiResultOf<INT> {
+        let (millis, negative) = if timestamp2 > timestamp1 {
+            ((timestamp2 - timestamp1).as_millis(), true)
+        } else {
+            ((timestamp1 - timestamp2).as_millis(), false)
+        };
+
+        if cfg!(not(feature = "unchecked")) && millis > (INT::MAX as u128) {
+            return Err(make_arithmetic_err(format!(
+                "Integer overflow for timestamp difference: {}{millis}",
+                if negative { "-" } else { "" }
+            )));
+        }
+
+        Ok(if negative {
+            -(millis as INT)
+        } else {
+            millis as INT
+        })
+    }
+
+    /// Format a duration as a number of days, hours, minutes and seconds, omitting zero parts.
+    #[allow(clippy::cast_possible_truncation)]
+    fn format_duration(negative: bool, millis: u128) -> ImmutableString {
+        const MILLIS_PER_MINUTE: u128 = 60 * 1000;
+        const MILLIS_PER_HOUR: u128 = 60 * MILLIS_PER_MINUTE;
+        const MILLIS_PER_DAY: u128 = 24 * MILLIS_PER_HOUR;
+
+        let seconds = millis % MILLIS_PER_MINUTE / 1000;
+        let fraction = (millis % 1000) as u32;
+
+        let mut parts = [
+            (millis / MILLIS_PER_DAY, 'd'),
+            (millis % MILLIS_PER_DAY / MILLIS_PER_HOUR, 'h'),
+            (millis % MILLIS_PER_HOUR / MILLIS_PER_MINUTE, 'm'),
+        ]
+        .iter()
+        .filter(|&&(value, ..)| value > 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect::<Vec<_>>();
+
+        if fraction > 0 {
+            let fraction = format!("{fraction:03}");
+            parts.push(format!("{seconds}.{}s", fraction.trim_end_matches('0')));
+        } else if seconds > 0 || parts.is_empty() {
+            parts.push(format!("{seconds}s"));
+        }
+
+        let text = parts.join(" ");
+
+        if negative && millis > 0 {
+            format!("-{text}").into()
+        } else {
+            text.into()
+        }
+    }
+
+    /// Convert a duration in number of `seconds` into a string of days, hours, minutes and seconds,
+    /// omitting zero parts.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// print(duration_to_string(83));          // prints "1m 23s"
+    ///
+    /// print(duration_to_string(90061));       // prints "1d 1h 1m 1s"
+    ///
+    /// print(duration_to_string(-3600));       // prints "-1h"
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn duration_to_string(seconds: INT) -> ImmutableString {
+        format_duration(seconds < 0, seconds.unsigned_abs() as u128 * 1000)
+    }
+    /// Convert a duration in number of `seconds` into a string of days, hours, minutes and seconds,
+    /// omitting zero parts.
+    ///
+    /// Fractions of a second are kept up to milliseconds.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let now = timestamp();
+    ///
+    /// print(duration_to_string(83.5));                        // prints "1m 23.5s"
+    ///
+    /// print(duration_to_string(timestamp_diff(now + 3660, now)));    // prints "1h 1m"
+    /// ```
+    #[cfg(not(feature = "no_float"))]
+    #[rhai_fn(name = "duration_to_string", return_raw)]
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub fn duration_to_string_float(seconds: FLOAT) -> RhaiResultOf<ImmutableString> {
+        let millis = (seconds.abs() * 1000.0).round();
+
+        if cfg!(not(feature = "unchecked")) && (millis.is_nan() || millis > u128::MAX as FLOAT) {
+            return Err(make_arithmetic_err(format!(
+                "Integer overflow for duration: {seconds}"
+            )));
+        }
+
+        Ok(format_duration(seconds < 0.0, millis as u128))
+    }
+    /// Convert a duration in number of `milliseconds` into a string of days, hours, minutes and
+    /// seconds, omitting zero parts.
+    ///
+    /// # Example
+    ///
+    /// ```rhai
+    /// let now = timestamp();
+    ///
+    /// print(duration_to_string(83500));                        // prints "1m 23.5s"
+    ///
+    /// print(duration_to_string(timestamp_diff(now + 3660, now)));     // prints "1h 1m"
+    /// ```
+    #[cfg(feature = "no_float")]
+    #[rhai_fn(name = "duration_to_string")]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn duration_to_string_millis(milliseconds: INT) -> ImmutableString {
+        format_duration(milliseconds < 0, milliseconds.unsigned_abs() as u128)
+    }
+
     #[cfg(not(feature = "no_float"))]
     pub mod float_functions {
         #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
@@ -170,6 +331,11 @@ mod time_functions {
         pub fn add(timestamp: Instant, seconds: FLOAT) -> RhaiResultOf<Instant> {
             add_impl(timestamp, seconds)
         }
+        /// Add the specified number of `seconds` to the timestamp and return it as a new timestamp.
+        #[rhai_fn(return_raw, name = "+")]
+        pub fn add_to(seconds: FLOAT, timestamp: Instant) -> RhaiResultOf<Instant> {
+            add_impl(timestamp, seconds)
+        }
         /// Add the specified number of `seconds` to the timestamp.
         #[rhai_fn(return_raw, name = "+=")]
         pub fn add_assign(timestamp: &mut Instant, seconds: FLOAT) -> RhaiResultOf<()> {
@@ -189,40 +355,44 @@ mod time_functions {
         }
     }
 
-    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_sign_loss, clippy::unnecessary_cast)]
     fn add_impl(timestamp: Instant, seconds: INT) -> RhaiResultOf<Instant> {
         if seconds < 0 {
-            return subtract_impl(timestamp, -seconds);
+            return subtract_duration(timestamp, seconds.unsigned_abs() as u64);
         }
+        add_duration(timestamp, seconds as u64)
+    }
+    #[allow(clippy::cast_sign_loss, clippy::unnecessary_cast)]
+    fn subtract_impl(timestamp: Instant, seconds: INT) -> RhaiResultOf<Instant> {
+        if seconds < 0 {
+            return add_duration(timestamp, seconds.unsigned_abs() as u64);
+        }
+        subtract_duration(timestamp, seconds as u64)
+    }
+    fn add_duration(timestamp: Instant, seconds: u64) -> RhaiResultOf<Instant> {
         if cfg!(not(feature = "unchecked")) {
             timestamp
-                .checked_add(Duration::from_secs(seconds as u64))
+                .checked_add(Duration::from_secs(seconds))
                 .ok_or_else(|| {
                     make_arithmetic_err(format!(
                         "Timestamp overflow when adding {seconds} second(s)"
                     ))
                 })
         } else {
-            Ok(timestamp + Duration::from_secs(seconds as u64))
+            Ok(timestamp + Duration::from_secs(seconds))
         }
     }
-    #[allow(clippy::cast_sign_loss)]
-    fn subtract_impl(timestamp: Instant, seconds: INT) -> RhaiResultOf<Instant> {
-        if seconds < 0 {
-            return add_impl(timestamp, -seconds);
-        }
+    fn subtract_duration(timestamp: Instant, seconds: u64) -> RhaiResultOf<Instant> {
         if cfg!(not(feature = "unchecked")) {
             timestamp
-                .checked_sub(Duration::from_secs(seconds as u64))
+                .checked_sub(Duration::from_secs(seconds))
                 .ok_or_else(|| {
                     make_arithmetic_err(format!(
                         "Timestamp overflow when subtracting {seconds} second(s)"
                     ))
                 })
         } else {
-            Ok(timestamp
-                .checked_sub(Duration::from_secs(seconds as u64))
-                .unwrap())
+            Ok(timestamp.checked_sub(Duration::from_secs(seconds)).unwrap())
         }
     }
 
@@ -231,6 +401,11 @@ mod time_functions {
     pub fn add(timestamp: Instant, seconds: INT) -> RhaiResultOf<Instant> {
         add_impl(timestamp, seconds)
     }
+    /// Add the specified number of `seconds` to the timestamp and return it as a new timestamp.
+    #[rhai_fn(return_raw, name = "+")]
+    pub fn add_to(seconds: INT, timestamp: Instant) -> RhaiResultOf<Instant> {
+        add_impl(timestamp, seconds)
+    }
     /// Add the specified number of `seconds` to the timestamp.
     #[rhai_fn(return_raw, name = "+=")]
     pub fn add_assign(timestamp: &mut Instant, seconds: INT) -> RhaiResultOf<()> {
@@ -1,10 +1,10 @@
 //! Main module defining the lexer and parser.
 
-use crate::api::options::LangOptions;
+use crate::api::options::{LangOptions, ShadowingPolicy};
 use crate::ast::{
     ASTFlags, BinaryExpr, CaseBlocksList, ConditionalExpr, Expr, FlowControl, FnCallExpr,
     FnCallHashes, Ident, Namespace, OpAssignment, RangeCase, ScriptFnDef, Stmt, StmtBlock,
-    StmtBlockContainer, SwitchCasesCollection,
+    StmtBlockContainer, SwitchCasesCollection, TryCatchBlock,
 };
 use crate::engine::{Precedence, OP_CONTAINS, OP_NOT};
 use crate::eval::{Caches, GlobalRuntimeState};
@@ -19,8 +19,9 @@ use crate::types::{
 };
 use crate::{
     calc_fn_hash, Dynamic, Engine, EvalAltResult, EvalContext, ExclusiveRange, FnArgsVec,
-    ImmutableString, InclusiveRange, LexError, OptimizationLevel, ParseError, Position, Scope,
-    Shared, SmartString, StaticVec, VarDefInfo, AST, PERR,
+    ImmutableString, InclusiveRange, LanguageVersion, LexError, OptimizationLevel, ParseError,
+    ParseWarning, ParseWarningKind, Position, Scope, Shared, SmartString, StaticVec, VarDefInfo,
+    AST, PERR,
 };
 use bitflags::bitflags;
 #[cfg(feature = "no_std")]
@@ -79,12 +80,26 @@ pub struct ParseState<'e, 's> {
     ///
     /// All consequent calls to [`access_var`][ParseState::access_var] will not be affected.
     pub allow_capture: bool,
+    /// An indicator that, when set to `true`, allows the next identifier token to be parsed as the
+    /// name of a named function call argument (e.g. `f(x: 42)`).
+    ///
+    /// It is reset upon the next call to `parse_primary`.
+    pub allow_named_arg: bool,
+    /// Is `this` referred to (used to mark closures that bind `this`)?
+    #[cfg(not(feature = "no_function"))]
+    pub refers_to_this: bool,
     /// Encapsulates a local stack with imported [module][crate::Module] names.
     #[cfg(not(feature = "no_module"))]
     pub imports: Vec<ImmutableString>,
     /// List of globally-imported [module][crate::Module] names.
     #[cfg(not(feature = "no_module"))]
     pub global_imports: Vec<ImmutableString>,
+    /// Warnings found in the script, if they are collected.
+    pub warnings: Option<Vec<crate::ParseWarning>>,
+    /// Positions of the parameters of script-defined functions, keyed by function hash.
+    /// Recorded only when warnings are collected.
+    #[cfg(not(feature = "no_function"))]
+    pub param_positions: StraightHashMap<FnArgsVec<Position>>,
 }
 
 impl fmt::Debug for ParseState<'_, '_> {
@@ -104,10 +119,20 @@ impl fmt::Debug for ParseState<'_, '_> {
         f.field("external_vars", &self.external_vars)
             .field("allow_capture", &self.allow_capture);
 
+        f.field("allow_named_arg", &self.allow_named_arg);
+
+        #[cfg(not(feature = "no_function"))]
+        f.field("refers_to_this", &self.refers_to_this);
+
         #[cfg(not(feature = "no_module"))]
         f.field("imports", &self.imports)
             .field("global_imports", &self.global_imports);
 
+        f.field("warnings", &self.warnings);
+
+        #[cfg(not(feature = "no_function"))]
+        f.field("param_positions", &self.param_positions);
+
         f.finish()
     }
 }
@@ -127,6 +152,9 @@ impl<'e, 's> ParseState<'e, 's> {
             #[cfg(not(feature = "no_closure"))]
             external_vars: Vec::new(),
             allow_capture: true,
+            allow_named_arg: false,
+            #[cfg(not(feature = "no_function"))]
+            refers_to_this: false,
             interned_strings,
             external_constants,
             global: None,
@@ -136,6 +164,9 @@ impl<'e, 's> ParseState<'e, 's> {
             imports: Vec::new(),
             #[cfg(not(feature = "no_module"))]
             global_imports: Vec::new(),
+            warnings: None,
+            #[cfg(not(feature = "no_function"))]
+            param_positions: StraightHashMap::default(),
         }
     }
 
@@ -435,6 +466,7 @@ impl Expr {
             #[cfg(not(feature = "no_float"))]
             Self::FloatConstant(..) => "a floating-point number",
             Self::CharConstant(..) => "a character",
+            #[cfg(feature = "no_index")]
             Self::Map(..) => "an object map",
             _ => return Ok(self),
         };
@@ -621,6 +653,43 @@ fn optimize_combo_chain(expr: &mut Expr) {
 }
 
 impl Engine {
+    /// Make sure that a syntax feature is available under the language version of the [`Engine`].
+    fn ensure_language_version(
+        &self,
+        feature: &str,
+        version: LanguageVersion,
+        pos: Position,
+    ) -> ParseResult<()> {
+        if self.language_version() >= version {
+            Ok(())
+        } else {
+            Err(PERR::UnsupportedLanguageFeature(feature.into(), version.to_string()).into_err(pos))
+        }
+    }
+
+    /// Make sure that an operator token is available under the language version of the [`Engine`].
+    fn ensure_token_language_version(&self, token: &Token, pos: Position) -> ParseResult<()> {
+        match token {
+            #[cfg(not(feature = "no_object"))]
+            Token::Elvis => {
+                self.ensure_language_version("Elvis operator '?.'", LanguageVersion::V1_8, pos)
+            }
+            #[cfg(not(feature = "no_index"))]
+            Token::QuestionBracket => {
+                self.ensure_language_version("Elvis operator '?['", LanguageVersion::V1_8, pos)
+            }
+            Token::DoubleQuestion => self.ensure_language_version(
+                "Null-coalescing operator '??'",
+                LanguageVersion::V1_8,
+                pos,
+            ),
+            Token::NotIn => {
+                self.ensure_language_version("'!in' operator", LanguageVersion::V1_12, pos)
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Parse a function call.
     fn parse_fn_call(
         &self,
@@ -655,9 +724,11 @@ impl Engine {
             Token::LexError(err) => return Err(err.clone().into_err(*token_pos)),
             // id()
             Token::RightParen => {
-                if !no_args {
-                    eat_token(input, &Token::RightParen);
-                }
+                let end_pos = if no_args {
+                    Position::NONE
+                } else {
+                    eat_token(input, &Token::RightParen)
+                };
 
                 #[cfg(not(feature = "no_module"))]
                 let hash = if _namespace.is_empty() {
@@ -675,7 +746,7 @@ impl Engine {
                         && index.is_none()
                         && !is_global
                         && !state.global_imports.iter().any(|m| m == root)
-                        && !self.global_sub_modules.contains_key(root)
+                        && !self.has_global_sub_module(root)
                     {
                         return Err(
                             PERR::ModuleUndefined(root.into()).into_err(_namespace.position())
@@ -704,6 +775,8 @@ impl Engine {
                     namespace: _namespace,
                     hashes,
                     args: args.into_boxed_slice(),
+                    arg_names: Box::default(),
+                    end_pos,
                 }
                 .into_fn_call_expr(settings.pos));
             }
@@ -712,18 +785,72 @@ impl Engine {
         }
 
         let settings = settings.level_up()?;
+        let mut arg_names = Vec::<Ident>::new();
 
         loop {
             match input.peek().expect(NEVER_ENDS) {
                 // id(...args, ) - handle trailing comma
                 (Token::RightParen, ..) => (),
-                _ => args.push(self.parse_expr(input, state, lib, settings)?),
+                _ => {
+                    #[cfg(not(feature = "no_function"))]
+                    {
+                        state.allow_named_arg = true;
+                    }
+
+                    let expr = self.parse_expr(input, state, lib, settings)?;
+
+                    match expr {
+                        // id(...args, name: expr
+                        Expr::Variable(x, None, pos)
+                            if x.0.is_none()
+                                && x.1.is_empty()
+                                && input.peek().expect(NEVER_ENDS).0 == Token::Colon =>
+                        {
+                            let version = LanguageVersion::V1_17;
+                            self.ensure_language_version("Named argument", version, pos)?;
+
+                            if !_namespace.is_empty() {
+                                let msg = "Named arguments are not supported in qualified calls";
+                                return Err(LexError::ImproperSymbol(
+                                    Token::Colon.literal_syntax().into(),
+                                    msg.into(),
+                                )
+                                .into_err(pos));
+                            }
+
+                            let name = x.3;
+
+                            if arg_names.iter().any(|n| n.name == name) {
+                                return Err(PERR::FnDuplicatedParam(
+                                    id.to_string(),
+                                    name.to_string(),
+                                )
+                                .into_err(pos));
+                            }
+
+                            eat_token(input, &Token::Colon);
+                            args.push(self.parse_expr(input, state, lib, settings)?);
+                            arg_names.push(Ident { name, pos });
+                        }
+                        // id(...name: expr, expr
+                        expr if !arg_names.is_empty() => {
+                            return Err(PERR::MissingToken(
+                                Token::Colon.into(),
+                                format!(
+                                    "for this argument to '{id}' as it follows a named argument"
+                                ),
+                            )
+                            .into_err(expr.start_position()))
+                        }
+                        expr => args.push(expr),
+                    }
+                }
             }
 
             match input.peek().expect(NEVER_ENDS) {
                 // id(...args)
                 (Token::RightParen, ..) => {
-                    eat_token(input, &Token::RightParen);
+                    let end_pos = eat_token(input, &Token::RightParen);
 
                     #[cfg(not(feature = "no_module"))]
                     let hash = if _namespace.is_empty() {
@@ -742,7 +869,7 @@ impl Engine {
                             && index.is_none()
                             && !is_global
                             && !state.global_imports.iter().any(|m| m == root)
-                            && !self.global_sub_modules.contains_key(root)
+                            && !self.has_global_sub_module(root)
                         {
                             return Err(
                                 PERR::ModuleUndefined(root.into()).into_err(_namespace.position())
@@ -771,6 +898,8 @@ impl Engine {
                         namespace: _namespace,
                         hashes,
                         args: args.into_boxed_slice(),
+                        arg_names: arg_names.into_boxed_slice(),
+                        end_pos,
                     }
                     .into_fn_call_expr(settings.pos));
                 }
@@ -913,6 +1042,7 @@ impl Engine {
                     // If another indexing level, right-bind it
                     (Token::LeftBracket | Token::QuestionBracket, ..) => {
                         let (token, pos) = input.next().expect(NEVER_ENDS);
+                        self.ensure_token_language_version(&token, pos)?;
                         let prev_pos = settings.pos;
                         settings.pos = pos;
                         let settings = settings.level_up()?;
@@ -1031,7 +1161,7 @@ impl Engine {
         settings.pos = eat_token(input, &Token::MapStart);
 
         let mut map = StaticVec::<(Ident, Expr)>::new();
-        let mut template = std::collections::BTreeMap::<crate::Identifier, crate::Dynamic>::new();
+        let mut template = crate::ast::expr::MapTemplate::new();
 
         loop {
             const MISSING_RBRACE: &str = "to end this object map literal";
@@ -1168,6 +1298,8 @@ impl Engine {
         let mut expressions = Vec::<ConditionalExpr>::new();
         let mut cases = StraightHashMap::<CaseBlocksList>::default();
         let mut ranges = Vec::<RangeCase>::new();
+        let mut literals = Vec::<(Dynamic, usize)>::new();
+        let mut case_values = Vec::<(Dynamic, usize)>::new();
         let mut def_case = None;
         let mut def_case_pos = Position::NONE;
 
@@ -1315,6 +1447,12 @@ impl Engine {
                         .entry(hash)
                         .or_insert(CaseBlocksList::new_const())
                         .push(index);
+
+                    case_values.push((value.clone(), index));
+
+                    if self.allow_switch_on_custom_types() {
+                        literals.push((value, index));
+                    }
                 }
             }
 
@@ -1344,11 +1482,15 @@ impl Engine {
         expressions.shrink_to_fit();
         cases.shrink_to_fit();
         ranges.shrink_to_fit();
+        literals.shrink_to_fit();
+        case_values.shrink_to_fit();
 
         let cases = SwitchCasesCollection {
             expressions,
             cases,
             ranges,
+            literals,
+            case_values,
             def_case,
         };
 
@@ -1364,6 +1506,8 @@ impl Engine {
         mut settings: ParseSettings,
         options: ChainingFlags,
     ) -> ParseResult<Expr> {
+        let allow_named_arg = std::mem::take(&mut state.allow_named_arg);
+
         let (token, token_pos) = input.peek().expect(NEVER_ENDS);
 
         settings.pos = *token_pos;
@@ -1406,6 +1550,12 @@ impl Engine {
                 input.next();
                 Expr::DynamicConstant(Box::new(x.into()), settings.pos)
             }
+            #[cfg(feature = "bigint")]
+            Token::BigIntConstant(x) => {
+                let x = x.0.clone();
+                input.next();
+                Expr::DynamicConstant(Box::new(Dynamic::from(x)), settings.pos)
+            }
 
             // { - block statement as expression
             Token::LeftBrace if settings.has_option(LangOptions::STMT_EXPR) => {
@@ -1498,7 +1648,8 @@ impl Engine {
                 #[cfg(not(feature = "no_closure"))]
                 let options = self.options & !LangOptions::STRICT_VAR; // a capturing closure can access variables not defined locally, so turn off Strict Variables mode
                 #[cfg(feature = "no_closure")]
-                let options = self.options | (settings.options & LangOptions::STRICT_VAR);
+                let options = (self.options & !LangOptions::STRICT_VAR)
+                    | (settings.options & LangOptions::STRICT_VAR);
 
                 // Brand new flags, turn on function scope and closure scope
                 let flags = ParseSettingFlags::FN_SCOPE
@@ -1513,11 +1664,15 @@ impl Engine {
                     ..settings
                 };
 
+                // Collect warnings in the closure body
+                new_state.warnings = state.warnings.take();
+
                 let result =
                     self.parse_anon_fn(input, new_state, lib, new_settings.level_up()?, state);
 
                 // Restore the strings interner by swapping it back
                 std::mem::swap(state.interned_strings, new_state.interned_strings);
+                state.warnings = new_state.warnings.take();
 
                 let (expr, fn_def) = result?;
 
@@ -1590,9 +1745,11 @@ impl Engine {
                             }
                         }
                         (Token::LexError(err), pos) => match *err {
-                            LexError::UnterminatedString | LexError::StringTooLong(_) => {
-                                return Err(err.into_err(pos))
-                            }
+                            LexError::UnterminatedString
+                            | LexError::StringTooLong(_)
+                            | LexError::StringLiteralTooLong(_)
+                            | LexError::TooManyTokens(_)
+                            | LexError::LineTooLong(_) => return Err(err.into_err(pos)),
                             _ => unreachable!("improper lex error: {:?}", err),
                         },
                         (token, ..) => unreachable!(
@@ -1627,6 +1784,12 @@ impl Engine {
             {
                 let (key, syntax) = self.custom_syntax.get_key_value(&**key).unwrap();
                 let (.., pos) = input.next().expect(NEVER_ENDS);
+
+                // Statement-only custom syntax is handled in `parse_expr_stmt`
+                if syntax.position == crate::CustomSyntaxPosition::Statement {
+                    return Err(PERR::CustomSyntaxStatementOnly(key.to_string()).into_err(pos));
+                }
+
                 let settings = settings.level_up_with_position(pos)?;
                 self.parse_custom_syntax(input, state, lib, settings, key, syntax)?
             }
@@ -1669,6 +1832,14 @@ impl Engine {
                         let name = state.get_interned_string(*s);
                         Expr::Variable((None, ns, 0, name).into(), None, settings.pos)
                     }
+                    // Named function call argument
+                    (Token::Colon, _) if allow_named_arg => {
+                        // Once the identifier consumed we must enable next variables capturing
+                        state.allow_capture = true;
+
+                        let name = state.get_interned_string(*s);
+                        Expr::Variable((None, ns, 0, name).into(), None, settings.pos)
+                    }
                     // Normal variable access
                     _ => {
                         let (index, is_func) = state.access_var(&s, lib, settings.pos);
@@ -1720,6 +1891,7 @@ impl Engine {
                     _ if *s == crate::engine::KEYWORD_THIS => {
                         // OK within a function scope
                         if settings.has_flag(ParseSettingFlags::FN_SCOPE) {
+                            state.refers_to_this = true;
                             Expr::ThisPtr(settings.pos)
                         } else {
                             // Cannot access to `this` as a variable not in a function scope
@@ -1778,6 +1950,7 @@ impl Engine {
 
             let (tail_token, tail_pos) = input.next().expect(NEVER_ENDS);
             settings.pos = tail_pos;
+            self.ensure_token_language_version(&tail_token, tail_pos)?;
 
             lhs = match (lhs, tail_token) {
                 // Qualified function call with !
@@ -1931,7 +2104,7 @@ impl Engine {
                         && index.is_none()
                         && !is_global
                         && !state.global_imports.iter().any(|m| m == root)
-                        && !self.global_sub_modules.contains_key(root)
+                        && !self.has_global_sub_module(root)
                     {
                         return Err(
                             PERR::ModuleUndefined(root.into()).into_err(namespace.position())
@@ -1994,6 +2167,8 @@ impl Engine {
                         args: vec![expr].into_boxed_slice(),
                         op_token: Some(token),
                         capture_parent_scope: false,
+                        arg_names: Box::default(),
+                        end_pos: Position::NONE,
                     }
                     .into_fn_call_expr(pos)),
                 }
@@ -2016,6 +2191,8 @@ impl Engine {
                         args: vec![expr].into_boxed_slice(),
                         op_token: Some(token),
                         capture_parent_scope: false,
+                        arg_names: Box::default(),
+                        end_pos: Position::NONE,
                     }
                     .into_fn_call_expr(pos)),
                 }
@@ -2033,6 +2210,8 @@ impl Engine {
                         .into_boxed_slice(),
                     op_token: Some(token),
                     capture_parent_scope: false,
+                    arg_names: Box::default(),
+                    end_pos: Position::NONE,
                 }
                 .into_fn_call_expr(pos))
             }
@@ -2153,6 +2332,17 @@ impl Engine {
         }
     }
 
+    /// Make an error for named arguments in a method-style function call.
+    #[cfg(not(feature = "no_object"))]
+    #[cold]
+    fn make_named_method_call_error(f: &FnCallExpr) -> ParseError {
+        LexError::ImproperSymbol(
+            Token::Colon.literal_syntax().into(),
+            "Named arguments are not supported in method-call style".into(),
+        )
+        .into_err(f.arg_names[0].pos)
+    }
+
     /// Make a dot expression.
     #[cfg(not(feature = "no_object"))]
     fn make_dot_expr(
@@ -2210,6 +2400,10 @@ impl Engine {
                 )
                 .into_err(func_pos))
             }
+            // lhs.func(name: expr)
+            (.., Expr::FnCall(f, ..)) if !f.arg_names.is_empty() => {
+                Err(Self::make_named_method_call_error(&f))
+            }
             // lhs.func(...)
             (lhs, Expr::FnCall(mut f, func_pos)) => {
                 // Recalculate hash
@@ -2265,6 +2459,10 @@ impl Engine {
                         };
                         Ok(Expr::Dot(BinaryExpr { lhs, rhs }.into(), op_flags, op_pos))
                     }
+                    // lhs.func(name: expr).dot_rhs or lhs.func(name: expr)[idx_rhs]
+                    Expr::FnCall(f, ..) if !f.arg_names.is_empty() => {
+                        Err(Self::make_named_method_call_error(&f))
+                    }
                     // lhs.func().dot_rhs or lhs.func()[idx_rhs]
                     Expr::FnCall(mut f, func_pos) => {
                         // Recalculate hash
@@ -2350,6 +2548,7 @@ impl Engine {
             }
 
             let (op_token, pos) = input.next().expect(NEVER_ENDS);
+            self.ensure_token_language_version(&op_token, pos)?;
 
             let rhs = self.parse_unary(input, state, lib, settings)?;
 
@@ -2391,6 +2590,8 @@ impl Engine {
                 args: vec![root, rhs].into_boxed_slice(),
                 op_token: native_only.then(|| op_token.clone()),
                 capture_parent_scope: false,
+                arg_names: Box::default(),
+                end_pos: Position::NONE,
             };
 
             root = match op_token {
@@ -2444,6 +2645,8 @@ impl Engine {
                             args: vec![fn_call].into_boxed_slice(),
                             op_token: Some(Token::Bang),
                             capture_parent_scope: false,
+                            arg_names: Box::default(),
+                            end_pos: Position::NONE,
                         };
                         not_base.into_fn_call_expr(pos)
                     }
@@ -2489,6 +2692,7 @@ impl Engine {
         let mut inputs = Vec::new();
         let mut segments = Vec::new();
         let mut tokens = Vec::new();
+        let mut new_vars = Vec::new();
 
         // Adjust the variables stack
         if syntax.scope_may_be_changed {
@@ -2518,6 +2722,17 @@ impl Engine {
                     inputs.push(Expr::StringConstant(state.get_interned_string(seg), pos));
                     break;
                 }
+                Ok(Some(seg)) if seg.starts_with(CUSTOM_SYNTAX_MARKER_DECLARE_VAR) => {
+                    let name = &seg[CUSTOM_SYNTAX_MARKER_DECLARE_VAR.len()..];
+
+                    if !is_valid_identifier(name) {
+                        return Err(PERR::VariableExpected.into_err(settings.pos));
+                    }
+
+                    new_vars.push(state.get_interned_string(name));
+                    segments.push(seg);
+                    continue;
+                }
                 Ok(Some(seg)) => seg,
                 Ok(None) => break,
                 Err(err) => return Err(err.0.into_err(settings.pos)),
@@ -2654,12 +2869,19 @@ impl Engine {
             KEYWORD_SEMICOLON | KEYWORD_CLOSE_BRACE
         );
 
+        // New variables are visible after the custom syntax
+        let scope_may_be_changed = syntax.scope_may_be_changed || !new_vars.is_empty();
+
+        for name in new_vars {
+            state.stack.push(name, ());
+        }
+
         Ok(Expr::Custom(
             crate::ast::CustomExpr {
                 inputs: inputs.into_boxed_slice(),
                 tokens: tokens.into_boxed_slice(),
                 state: user_state,
-                scope_may_be_changed: syntax.scope_may_be_changed,
+                scope_may_be_changed,
                 self_terminated,
             }
             .into(),
@@ -2816,10 +3038,12 @@ impl Engine {
         let mut settings = settings.level_up_with_position(eat_token(input, &Token::For))?;
 
         // for name ...
-        let (name, name_pos, counter_name, counter_pos) = if match_token(input, &Token::LeftParen).0
-        {
-            // ( name, counter )
-            let (name, name_pos) = parse_var_name(input)?;
+        let mut names = StaticVec::<(SmartString, Position)>::new_const();
+
+        if match_token(input, &Token::LeftParen).0 {
+            // ( name, name2 ) | ( name, name2, name3 )
+            names.push(parse_var_name(input)?);
+
             let (has_comma, pos) = match_token(input, &Token::Comma);
             if !has_comma {
                 return Err(PERR::MissingToken(
@@ -2828,26 +3052,33 @@ impl Engine {
                 )
                 .into_err(pos));
             }
-            let (counter_name, counter_pos) = parse_var_name(input)?;
 
-            if counter_name == name {
-                return Err(PERR::DuplicatedVariable(counter_name.into()).into_err(counter_pos));
+            loop {
+                let (name, pos) = parse_var_name(input)?;
+
+                if names.iter().any(|(n, ..)| *n == name) {
+                    return Err(PERR::DuplicatedVariable(name.into()).into_err(pos));
+                }
+
+                names.push((name, pos));
+
+                if names.len() >= 3 || !match_token(input, &Token::Comma).0 {
+                    break;
+                }
             }
 
             let (has_close_paren, pos) = match_token(input, &Token::RightParen);
             if !has_close_paren {
                 return Err(PERR::MissingToken(
                     Token::RightParen.into(),
-                    "to close the iteration variable".into(),
+                    "to close the iteration variables".into(),
                 )
                 .into_err(pos));
             }
-            (name, name_pos, Some(counter_name), counter_pos)
         } else {
             // name
-            let (name, name_pos) = parse_var_name(input)?;
-            (name, name_pos, None, Position::NONE)
-        };
+            names.push(parse_var_name(input)?);
+        }
 
         // for name in ...
         match input.next().expect(NEVER_ENDS) {
@@ -2868,21 +3099,23 @@ impl Engine {
             .parse_expr(input, state, lib, settings)?
             .ensure_iterable()?;
 
-        let counter_var = counter_name.map(|counter_name| Ident {
-            name: state.get_interned_string(counter_name),
-            pos: counter_pos,
+        let mut vars = names.into_iter().map(|(name, pos)| Ident {
+            name: state.get_interned_string(name),
+            pos,
         });
 
-        let loop_var = Ident {
-            name: state.get_interned_string(name),
-            pos: name_pos,
-        };
+        let loop_var = vars.next().unwrap();
+        let var2 = vars.next();
+        let var3 = vars.next();
 
         let prev_stack_len = {
             let prev_stack_len = state.stack.len();
 
-            if let Some(ref counter_var) = counter_var {
-                state.stack.push(counter_var.name.clone(), ());
+            if let Some(ref var3) = var3 {
+                state.stack.push(var3.name.clone(), ());
+            }
+            if let Some(ref var2) = var2 {
+                state.stack.push(var2.name.clone(), ());
             }
             state.stack.push(&loop_var.name, ());
 
@@ -2897,7 +3130,7 @@ impl Engine {
         let branch = StmtBlock::NONE;
 
         Ok(Stmt::For(
-            Box::new((loop_var, counter_var, FlowControl { expr, body, branch })),
+            Box::new((loop_var, var2, var3, FlowControl { expr, body, branch })),
             settings.pos,
         ))
     }
@@ -2918,13 +3151,32 @@ impl Engine {
         // let name ...
         let (name, pos) = parse_var_name(input)?;
 
-        if !self.allow_shadowing() && state.stack.iter().any(|(v, ..)| v == name) {
-            return Err(PERR::VariableExists(name.into()).into_err(pos));
+        // Variables in the external scope are not visible inside functions
+        let will_shadow = state.stack.iter().any(|(v, ..)| v == name)
+            || (!settings.has_flag(ParseSettingFlags::FN_SCOPE)
+                && state
+                    .external_constants
+                    .map_or(false, |scope| scope.contains(&name)));
+
+        if will_shadow {
+            match self.shadowing_policy() {
+                ShadowingPolicy::Allow => (),
+                ShadowingPolicy::Warn => {
+                    if let Some(ref mut warnings) = state.warnings {
+                        warnings.push(ParseWarning::new(
+                            ParseWarningKind::ShadowedVariable,
+                            pos,
+                            format!("Variable '{name}' shadows an existing variable"),
+                        ));
+                    }
+                }
+                ShadowingPolicy::Deny => {
+                    return Err(PERR::VariableExists(name.into()).into_err(pos))
+                }
+            }
         }
 
         if let Some(ref filter) = self.def_var_filter {
-            let will_shadow = state.stack.iter().any(|(v, ..)| v == name);
-
             let global = state
                 .global
                 .get_or_insert_with(|| GlobalRuntimeState::new(self).into());
@@ -3223,6 +3475,28 @@ impl Engine {
     ) -> ParseResult<Stmt> {
         settings.pos = input.peek().expect(NEVER_ENDS).1;
 
+        // Custom syntax with position constraints
+        #[cfg(not(feature = "no_custom_syntax"))]
+        if let (Token::Custom(key) | Token::Reserved(key) | Token::Identifier(key), pos) =
+            input.peek().expect(NEVER_ENDS)
+        {
+            if let Some((key, syntax)) = self.custom_syntax.get_key_value(&**key) {
+                match syntax.position {
+                    crate::CustomSyntaxPosition::Statement => {
+                        input.next().expect(NEVER_ENDS);
+                        let settings = settings.level_up()?;
+                        let expr =
+                            self.parse_custom_syntax(input, state, lib, settings, key, syntax)?;
+                        return Ok(Stmt::Expr(expr.into()));
+                    }
+                    crate::CustomSyntaxPosition::Expression => {
+                        return Err(PERR::CustomSyntaxExpressionOnly(key.to_string()).into_err(*pos))
+                    }
+                    crate::CustomSyntaxPosition::Both => (),
+                }
+            }
+        }
+
         let expr = self.parse_expr(input, state, lib, settings)?;
 
         let (op, pos) = match input.peek().expect(NEVER_ENDS) {
@@ -3362,8 +3636,12 @@ impl Engine {
                             new_state.global_imports.extend(state.imports.clone());
                         }
 
+                        // Collect warnings in the function body
+                        new_state.warnings = state.warnings.take();
+
                         // Brand new options
-                        let options = self.options | (settings.options & LangOptions::STRICT_VAR);
+                        let options = (self.options & !LangOptions::STRICT_VAR)
+                            | (settings.options & LangOptions::STRICT_VAR);
 
                         // Brand new flags, turn on function scope
                         let flags = ParseSettingFlags::FN_SCOPE
@@ -3379,7 +3657,7 @@ impl Engine {
                             max_expr_depth: self.max_function_expr_depth(),
                         };
 
-                        let f = self.parse_fn(
+                        let (f, param_positions) = self.parse_fn(
                             input,
                             new_state,
                             lib,
@@ -3389,24 +3667,97 @@ impl Engine {
                             comments,
                         )?;
 
-                        let hash = calc_fn_hash(None, &f.name, f.params.len());
+                        state.warnings = new_state.warnings.take();
+
+                        // Exclude the function from optimization if preceded by a marker
+                        #[cfg(not(feature = "no_optimize"))]
+                        let f = ScriptFnDef {
+                            no_optimize: state
+                                .tokenizer_control
+                                .borrow()
+                                .optimize_off_positions
+                                .contains(&token_pos),
+                            ..f
+                        };
+
+                        self.check_param_shadowing(state, &f, &param_positions)?;
 
-                        #[cfg(not(feature = "no_object"))]
-                        let hash = f
-                            .this_type
-                            .as_ref()
-                            .map_or(hash, |typ| crate::calc_typed_method_hash(hash, typ));
+                        let calc_hash = |num_params| {
+                            let hash = calc_fn_hash(None, &f.name, num_params);
+
+                            #[cfg(not(feature = "no_object"))]
+                            let hash = f
+                                .this_type
+                                .as_ref()
+                                .map_or(hash, |typ| crate::calc_typed_method_hash(hash, typ));
+
+                            hash
+                        };
+
+                        // A variadic function is keyed by its name only, so there can only be one
+                        let hash = if f.is_variadic {
+                            let hash = crate::calc_variadic_fn_hash(None, &f.name);
+
+                            #[cfg(not(feature = "no_object"))]
+                            let hash = f
+                                .this_type
+                                .as_ref()
+                                .map_or(hash, |typ| crate::calc_typed_method_hash(hash, typ));
+
+                            hash
+                        } else {
+                            calc_hash(f.params.len())
+                        };
 
-                        if !lib.is_empty() && lib.contains_key(&hash) {
+                        if f.is_variadic && lib.contains_key(&hash) {
                             return Err(PERR::FnDuplicatedDefinition(
                                 f.name.to_string(),
-                                f.params.len(),
+                                f.num_fixed_params(),
                             )
                             .into_err(pos));
                         }
 
+                        // A function with optional parameters is callable with any number of
+                        // arguments between the number of required parameters and the total,
+                        // so it must not overlap with any other definition of the same function.
+                        if !lib.is_empty() {
+                            let arities = f.num_required_params()..=f.num_fixed_params();
+
+                            let duplicated = arities
+                                .clone()
+                                .find(|&n| lib.contains_key(&calc_hash(n)))
+                                .or_else(|| {
+                                    lib.values()
+                                        .filter(|g| {
+                                            (!g.defaults.is_empty() || g.is_variadic)
+                                                && g.name == f.name
+                                        })
+                                        .filter(|_g| {
+                                            #[cfg(not(feature = "no_object"))]
+                                            return _g.this_type == f.this_type;
+                                            #[cfg(feature = "no_object")]
+                                            return true;
+                                        })
+                                        .find_map(|g| {
+                                            arities.clone().find(|n| {
+                                                (g.num_required_params()..=g.num_fixed_params())
+                                                    .contains(n)
+                                            })
+                                        })
+                                });
+
+                            if let Some(n) = duplicated {
+                                return Err(PERR::FnDuplicatedDefinition(f.name.to_string(), n)
+                                    .into_err(pos));
+                            }
+                        }
+
                         lib.insert(hash, f.into());
 
+                        if state.warnings.is_some() {
+                            state.param_positions.insert(hash, param_positions);
+                        }
+
                         Ok(Stmt::Noop(pos))
                     }
 
@@ -3530,7 +3881,7 @@ impl Engine {
         let settings = settings.level_up_with_position(eat_token(input, &Token::Try))?;
 
         // try { try_block }
-        let body = self.parse_block(input, state, lib, settings)?.into();
+        let try_block = self.parse_block(input, state, lib, settings)?.into();
 
         // try { try_block } catch
         let (matched, catch_pos) = match_token(input, &Token::Catch);
@@ -3543,8 +3894,30 @@ impl Engine {
         }
 
         // try { try_block } catch (
-        let catch_var = if match_token(input, &Token::LeftParen).0 {
+        let (catch_var, guard) = if match_token(input, &Token::LeftParen).0 {
             let (name, pos) = parse_var_name(input)?;
+            let name = state.get_interned_string(name);
+            state.stack.push(name.clone(), ());
+
+            // try { try_block } catch ( var if guard
+            let (has_guard, guard_pos) = match_token(input, &Token::If);
+
+            let guard = if has_guard {
+                self.ensure_language_version(
+                    "Guarded catch clause",
+                    LanguageVersion::V1_17,
+                    guard_pos,
+                )?;
+                ensure_not_statement_expr(input, "a boolean")?;
+                let guard = self
+                    .parse_expr(input, state, lib, settings)?
+                    .ensure_bool_expr()?;
+                ensure_not_assignment(input)?;
+                guard
+            } else {
+                Expr::BoolConstant(true, Position::NONE)
+            };
+
             let (matched, err_pos) = match_token(input, &Token::RightParen);
 
             if !matched {
@@ -3555,20 +3928,21 @@ impl Engine {
                 .into_err(err_pos));
             }
 
-            let name = state.get_interned_string(name);
-            state.stack.push(name.clone(), ());
-            Ident { name, pos }
+            (Ident { name, pos }, guard)
         } else {
-            Ident {
-                name: state.get_interned_string(""),
-                pos: Position::NONE,
-            }
+            (
+                Ident {
+                    name: state.get_interned_string(""),
+                    pos: Position::NONE,
+                },
+                Expr::BoolConstant(true, Position::NONE),
+            )
         };
 
         // try { try_block } catch ( var ) { catch_block }
-        let branch = self.parse_block(input, state, lib, settings)?.into();
+        let catch_block = self.parse_block(input, state, lib, settings)?.into();
 
-        let expr = if catch_var.is_empty() {
+        let catch_var = if catch_var.is_empty() {
             Expr::Unit(catch_var.pos)
         } else {
             // Remove the error variable from the stack
@@ -3582,12 +3956,18 @@ impl Engine {
         };
 
         Ok(Stmt::TryCatch(
-            FlowControl { expr, body, branch }.into(),
+            TryCatchBlock {
+                try_block,
+                catch_var,
+                guard,
+                catch_block,
+            }
+            .into(),
             settings.pos,
         ))
     }
 
-    /// Parse a function definition.
+    /// Parse a function definition, returning it together with the positions of its parameters.
     #[cfg(not(feature = "no_function"))]
     fn parse_fn(
         &self,
@@ -3597,7 +3977,7 @@ impl Engine {
         settings: ParseSettings,
         access: crate::FnAccess,
         #[cfg(feature = "metadata")] comments: impl IntoIterator<Item = crate::Identifier>,
-    ) -> ParseResult<ScriptFnDef> {
+    ) -> ParseResult<(ScriptFnDef, FnArgsVec<Position>)> {
         let settings = settings.level_up()?;
 
         let (token, pos) = input.next().expect(NEVER_ENDS);
@@ -3609,6 +3989,7 @@ impl Engine {
 
             match token {
                 Token::StringConstant(s) if next_token == &Token::Period => {
+                    self.ensure_language_version("Typed method", LanguageVersion::V1_14, pos)?;
                     eat_token(input, &Token::Period);
                     let s = match s.as_str() {
                         "int" => state.get_interned_string(std::any::type_name::<crate::INT>()),
@@ -3626,6 +4007,7 @@ impl Engine {
                     .into_err(*next_pos))
                 }
                 Token::Identifier(s) if next_token == &Token::Period => {
+                    self.ensure_language_version("Typed method", LanguageVersion::V1_14, pos)?;
                     eat_token(input, &Token::Period);
                     let s = match s.as_str() {
                         "int" => state.get_interned_string(std::any::type_name::<crate::INT>()),
@@ -3660,6 +4042,8 @@ impl Engine {
         };
 
         let mut params = StaticVec::<(ImmutableString, _)>::new_const();
+        let mut defaults = FnArgsVec::new_const();
+        let mut is_variadic = false;
 
         if !no_params {
             let sep_err = format!("to separate the parameters of function '{name}'");
@@ -3674,9 +4058,72 @@ impl Engine {
                             );
                         }
 
+                        // param = default_value
+                        let (has_default, eq_pos) = match_token(input, &Token::Equals);
+
+                        if has_default {
+                            self.ensure_language_version(
+                                "Default parameter value",
+                                LanguageVersion::V1_17,
+                                eq_pos,
+                            )?;
+                            // Previous parameters are available to the default value
+                            let expr = self.parse_expr(input, state, lib, settings.level_up()?)?;
+                            defaults.push(expr);
+                        } else if !defaults.is_empty() {
+                            return Err(PERR::MissingToken(
+                                Token::Equals.into(),
+                                format!(
+                                    "to provide a default value for the parameter '{s}' of function '{name}' because it follows an optional parameter"
+                                ),
+                            )
+                            .into_err(eq_pos));
+                        }
+
+                        let s = state.get_interned_string(*s);
+                        state.stack.push(s.clone(), ());
+                        params.push((s, pos));
+                    }
+                    // ...rest
+                    #[cfg(not(feature = "no_index"))]
+                    (Token::Reserved(s), pos) if s.as_str() == "..." => {
+                        self.ensure_language_version(
+                            "Rest parameter",
+                            LanguageVersion::V1_17,
+                            pos,
+                        )?;
+
+                        let (s, pos) = match input.next().expect(NEVER_ENDS) {
+                            (Token::Identifier(s), pos) => (s, pos),
+                            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                            (.., pos) => return Err(PERR::VariableExpected.into_err(pos)),
+                        };
+
+                        if params.iter().any(|(p, _)| p == &*s) {
+                            return Err(
+                                PERR::FnDuplicatedParam(name.into(), s.to_string()).into_err(pos)
+                            );
+                        }
+
                         let s = state.get_interned_string(*s);
                         state.stack.push(s.clone(), ());
                         params.push((s, pos));
+                        is_variadic = true;
+
+                        // The rest parameter must be the last
+                        match input.next().expect(NEVER_ENDS) {
+                            (Token::RightParen, ..) => break,
+                            (Token::LexError(err), pos) => return Err(err.into_err(pos)),
+                            (.., pos) => {
+                                return Err(PERR::MissingToken(
+                                    Token::RightParen.into(),
+                                    format!(
+                                        "to close the parameters list of function '{name}' because the rest parameter must be the last"
+                                    ),
+                                )
+                                .into_err(pos))
+                            }
+                        }
                     }
                     (Token::LexError(err), pos) => return Err(err.into_err(pos)),
                     (.., pos) => {
@@ -3706,19 +4153,71 @@ impl Engine {
         }
         .into();
 
+        let param_positions = params.iter().map(|&(.., pos)| pos).collect();
         let mut params: FnArgsVec<_> = params.into_iter().map(|(p, ..)| p).collect();
         params.shrink_to_fit();
+        defaults.shrink_to_fit();
 
-        Ok(ScriptFnDef {
+        let fn_def = ScriptFnDef {
             name: state.get_interned_string(name),
             access,
             #[cfg(not(feature = "no_object"))]
             this_type,
             params,
+            defaults,
+            is_variadic,
             body,
+            #[cfg(not(feature = "no_optimize"))]
+            no_optimize: false,
             #[cfg(feature = "metadata")]
             comments: comments.into_iter().collect(),
-        })
+        };
+
+        Ok((fn_def, param_positions))
+    }
+
+    /// Check the parameters of a function definition against the global variables visible at the
+    /// point of the definition, according to the policy on parameters shadowing.
+    #[cfg(not(feature = "no_function"))]
+    fn check_param_shadowing(
+        &self,
+        state: &mut ParseState,
+        fn_def: &ScriptFnDef,
+        param_positions: &[Position],
+    ) -> ParseResult<()> {
+        let policy = self.param_shadowing_policy();
+
+        if policy == ShadowingPolicy::Allow {
+            return Ok(());
+        }
+
+        for (param, &pos) in fn_def.params.iter().zip(param_positions) {
+            let is_global = state.stack.iter().any(|(v, ..)| v == param)
+                || state
+                    .external_constants
+                    .map_or(false, |scope| scope.contains(param));
+
+            if !is_global {
+                continue;
+            }
+
+            if policy == ShadowingPolicy::Deny {
+                return Err(PERR::VariableExists(param.to_string()).into_err(pos));
+            }
+
+            if let Some(ref mut warnings) = state.warnings {
+                warnings.push(ParseWarning::new(
+                    ParseWarningKind::ShadowedParameter,
+                    pos,
+                    format!(
+                        "Parameter '{param}' of function '{}' shadows a global variable",
+                        fn_def.name
+                    ),
+                ));
+            }
+        }
+
+        Ok(())
     }
 
     /// Creates a curried expression from a list of external variables
@@ -3768,6 +4267,8 @@ impl Engine {
             args: args.into_boxed_slice(),
             op_token: None,
             capture_parent_scope: false,
+            arg_names: Box::default(),
+            end_pos: Position::NONE,
         }
         .into_fn_call_expr(pos);
 
@@ -3879,7 +4380,11 @@ impl Engine {
             #[cfg(not(feature = "no_object"))]
             this_type: None,
             params,
+            defaults: FnArgsVec::new_const(),
+            is_variadic: false,
             body: body.into(),
+            #[cfg(not(feature = "no_optimize"))]
+            no_optimize: false,
             #[cfg(not(feature = "no_function"))]
             #[cfg(feature = "metadata")]
             comments: <_>::default(),
@@ -3889,8 +4394,11 @@ impl Engine {
             name: fn_name,
             curry: Vec::new(),
             environ: None,
+            native_fn: None,
             #[cfg(not(feature = "no_function"))]
             fn_def: Some(script.clone()),
+            #[cfg(not(feature = "no_function"))]
+            binds_this: state.refers_to_this,
         };
         let expr = Expr::DynamicConstant(Box::new(fn_ptr.into()), settings.pos);
 
@@ -3938,22 +4446,67 @@ impl Engine {
         let mut statements = StmtBlockContainer::new_const();
         statements.push(Stmt::Expr(expr.into()));
 
+        #[cfg(not(feature = "no_function"))]
+        let functions: Vec<_> = functions.into_iter().map(|(.., v)| v).collect();
+
+        if self.strict_functions() {
+            self.check_fn_calls(
+                &statements,
+                #[cfg(not(feature = "no_function"))]
+                &functions,
+            )?;
+        }
+
+        let node_ids = self.collect_node_ids(
+            &statements,
+            #[cfg(not(feature = "no_function"))]
+            &functions,
+        );
+
         #[cfg(not(feature = "no_optimize"))]
-        return Ok(crate::optimizer::optimize_into_ast(
+        let mut ast = crate::optimizer::optimize_into_ast(
             self,
             state.external_constants,
             statements,
             #[cfg(not(feature = "no_function"))]
-            functions.into_iter().map(|(.., v)| v).collect(),
+            functions,
+            #[cfg(not(feature = "no_module"))]
+            None,
             _optimization_level,
-        ));
+        );
 
         #[cfg(feature = "no_optimize")]
-        return Ok(AST::new(
-            statements,
-            #[cfg(not(feature = "no_function"))]
-            crate::Module::from(functions.into_iter().map(|(.., v)| v)),
-        ));
+        let mut ast = {
+            let mut ast = AST::new(
+                statements,
+                #[cfg(not(feature = "no_function"))]
+                crate::Module::from(functions),
+            );
+            ast.set_language_version(self.language_version());
+            ast
+        };
+
+        ast.node_ids = node_ids.into();
+        Ok(ast)
+    }
+
+    /// Assign node ID's to the statements and functions (if any) of a script about to be
+    /// optimized, if enabled.
+    fn collect_node_ids(
+        &self,
+        statements: &[Stmt],
+        #[cfg(not(feature = "no_function"))] functions: &[Shared<ScriptFnDef>],
+    ) -> StraightHashMap<u32> {
+        if !self.assign_node_ids() {
+            return StraightHashMap::default();
+        }
+
+        #[cfg(not(feature = "no_function"))]
+        let statements = statements
+            .iter()
+            .chain(functions.iter().flat_map(|f| f.body.iter()));
+
+        AST::collect_node_ids(statements)
     }
 
     /// Parse the global level statements.
@@ -4021,38 +4574,94 @@ impl Engine {
         &self,
         input: TokenStream,
         state: &mut ParseState,
+        process_settings: impl FnOnce(&mut ParseSettings),
         _optimization_level: OptimizationLevel,
     ) -> ParseResult<AST> {
-        let (statements, _lib) = self.parse_global_level(input, state, |_| {})?;
+        // An optimization marker before any code turns off optimization for the whole script
+        #[cfg(not(feature = "no_optimize"))]
+        let (input, _optimization_level) = {
+            let mut input = input;
+            let (.., pos) = input.peek().expect(NEVER_ENDS);
+
+            if state
+                .tokenizer_control
+                .borrow()
+                .optimize_off_positions
+                .contains(pos)
+            {
+                (input, OptimizationLevel::None)
+            } else {
+                (input, _optimization_level)
+            }
+        };
+
+        let (statements, _lib) = self.parse_global_level(input, state, process_settings)?;
+
+        if self.strict_functions() {
+            self.check_fn_calls(
+                &statements,
+                #[cfg(not(feature = "no_function"))]
+                &_lib,
+            )?;
+        }
+
+        // Analyze the script before it is optimized
+        if let Some(ref mut warnings) = state.warnings {
+            warnings.extend(self.analyze(
+                &statements,
+                #[cfg(not(feature = "no_function"))]
+                &_lib,
+                #[cfg(not(feature = "no_function"))]
+                &state.param_positions,
+            ));
+            warnings.sort_by_key(|w| (w.position.line(), w.position.position()));
+        }
+
+        let node_ids = self.collect_node_ids(
+            &statements,
+            #[cfg(not(feature = "no_function"))]
+            &_lib,
+        );
 
         #[cfg(not(feature = "no_optimize"))]
-        return Ok(crate::optimizer::optimize_into_ast(
+        let mut ast = crate::optimizer::optimize_into_ast(
             self,
             state.external_constants,
             statements,
             #[cfg(not(feature = "no_function"))]
             _lib,
+            #[cfg(not(feature = "no_module"))]
+            None,
             _optimization_level,
-        ));
+        );
 
         #[cfg(feature = "no_optimize")]
         #[cfg(not(feature = "no_function"))]
-        {
+        let mut ast = {
             let mut m = crate::Module::new();
 
             _lib.into_iter().for_each(|fn_def| {
                 m.set_script_fn(fn_def);
             });
 
-            return Ok(AST::new(statements, m));
-        }
+            let mut ast = AST::new(statements, m);
+            ast.set_language_version(self.language_version());
+            ast
+        };
 
         #[cfg(feature = "no_optimize")]
         #[cfg(feature = "no_function")]
-        return Ok(AST::new(
-            statements,
-            #[cfg(not(feature = "no_function"))]
-            crate::Module::new(),
-        ));
+        let mut ast = {
+            let mut ast = AST::new(
+                statements,
+                #[cfg(not(feature = "no_function"))]
+                crate::Module::new(),
+            );
+            ast.set_language_version(self.language_version());
+            ast
+        };
+
+        ast.node_ids = node_ids.into();
+        Ok(ast)
     }
 }
@@ -107,7 +107,41 @@ impl<'de> DynamicDeserializer<'de> {
 /// # }
 /// ```
 pub fn from_dynamic<'de, T: Deserialize<'de>>(value: &'de Dynamic) -> RhaiResultOf<T> {
-    T::deserialize(DynamicDeserializer::new(value))
+    T::deserialize(DynamicDeserializer::new(value)).map_err(|err| with_available_keys(err, value))
+}
+
+/// Add the keys of an object map to an error about a missing field or an unmatched enum variant
+/// (e.g. a missing tag field of an internally-tagged enum) raised when deserializing from it.
+///
+/// Errors that already list the available keys (i.e. from a nested map) are not changed.
+#[cold]
+#[inline(never)]
+fn with_available_keys(err: RhaiError, _value: &Dynamic) -> RhaiError {
+    #[cfg(not(feature = "no_object"))]
+    if let ERR::ErrorParsing(
+        crate::ParseErrorType::BadInput(LexError::ImproperSymbol(_, ref msg)),
+        ..,
+    ) = *err
+    {
+        let unmatched = msg.starts_with("missing field")
+            || msg.starts_with("unknown variant")
+            || msg.contains("did not match any variant");
+
+        if unmatched && !msg.contains("(available keys: ") {
+            if let Some(map) = _value.read_lock::<crate::Map>() {
+                let keys = map
+                    .keys()
+                    .map(|k| format!("`{k}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let keys = if keys.is_empty() { "none" } else { &keys };
+
+                return Error::custom(format!("{msg} (available keys: {keys})"));
+            }
+        }
+    }
+
+    err
 }
 
 impl Error for RhaiError {
@@ -426,10 +460,12 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
         return self.0.downcast_ref::<crate::Map>().map_or_else(
             || self.type_error(),
             |map| {
-                _visitor.visit_map(IterateMap::new(
-                    map.keys().map(crate::SmartString::as_str),
-                    map.values(),
-                ))
+                _visitor
+                    .visit_map(IterateMap::new(
+                        map.keys().map(crate::SmartString::as_str),
+                        map.values(),
+                    ))
+                    .map_err(|err| with_available_keys(err, self.0))
             },
         );
 
@@ -467,7 +503,13 @@ impl<'de> Deserializer<'de> for DynamicDeserializer<'de> {
                                 tag: key,
                                 content: DynamicDeserializer::new(value),
                             }),
-                            _ => self.type_error(),
+                            _ => Err(Error::custom(format!(
+                                "expecting a map with a single key naming a variant of enum `{}` ({}), but found {} keys ({})",
+                                _name,
+                                _variants.iter().map(|v| format!("`{v}`")).collect::<Vec<_>>().join(", "),
+                                map.len(),
+                                map.keys().map(|k| format!("`{k}`")).collect::<Vec<_>>().join(", "),
+                            ))),
                         }
                     },
                 );
@@ -516,7 +558,9 @@ impl<'de, ITER: Iterator<Item = &'de Dynamic>> serde::de::SeqAccess<'de>
     ) -> RhaiResultOf<Option<T::Value>> {
         // Deserialize each item coming out of the iterator.
         self.iter.next().map_or(Ok(None), |item| {
-            seed.deserialize(item.into_deserializer()).map(Some)
+            seed.deserialize(item.into_deserializer())
+                .map(Some)
+                .map_err(|err| with_available_keys(err, item))
         })
     }
 }
@@ -561,7 +605,9 @@ impl<'de, K: Iterator<Item = &'de str>, V: Iterator<Item = &'de Dynamic>> serde:
         seed: S,
     ) -> RhaiResultOf<S::Value> {
         // Deserialize each value item coming out of the iterator.
-        seed.deserialize(self.values.next().unwrap().into_deserializer())
+        let value = self.values.next().unwrap();
+        seed.deserialize(value.into_deserializer())
+            .map_err(|err| with_available_keys(err, value))
     }
 }
 
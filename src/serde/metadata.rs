@@ -33,6 +33,8 @@ struct CustomTypeMetadata<'a> {
     pub display_name: &'a str,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub doc_comments: Vec<&'a str>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub functions: Vec<SmartString>,
 }
 
 impl PartialOrd for CustomTypeMetadata<'_> {
@@ -50,12 +52,20 @@ impl Ord for CustomTypeMetadata<'_> {
     }
 }
 
-impl<'a> From<(&'a str, &'a CustomTypeInfo)> for CustomTypeMetadata<'a> {
-    fn from(value: (&'a str, &'a CustomTypeInfo)) -> Self {
+impl<'a> CustomTypeMetadata<'a> {
+    /// Create a [`CustomTypeMetadata`] for a custom type registered in a [`Module`][crate::Module],
+    /// listing the signatures of the functions registered together with it.
+    fn new(type_name: &'a str, info: &'a CustomTypeInfo, module: &crate::Module) -> Self {
         Self {
-            type_name: value.0,
-            display_name: &value.1.display_name,
-            doc_comments: value.1.comments.iter().map(<_>::as_ref).collect(),
+            type_name,
+            display_name: &info.display_name,
+            doc_comments: info.comments.iter().map(<_>::as_ref).collect(),
+            functions: info
+                .functions
+                .iter()
+                .filter_map(|&hash| module.iter_fn().find(|f| f.metadata.hash == hash))
+                .map(|f| f.gen_signature().into())
+                .collect(),
         }
     }
 }
@@ -71,6 +81,9 @@ struct FnMetadata<'a> {
     pub name: &'a str,
     #[cfg(not(feature = "no_function"))]
     pub is_anonymous: bool,
+    #[cfg(not(feature = "no_function"))]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub is_variadic: bool,
     #[serde(rename = "type")]
     pub typ: FnType,
     #[cfg(not(feature = "no_object"))]
@@ -122,6 +135,10 @@ impl<'a> From<&'a FuncInfo> for FnMetadata<'a> {
             name: &info.metadata.name,
             #[cfg(not(feature = "no_function"))]
             is_anonymous: crate::parser::is_anonymous_fn(&info.metadata.name),
+            #[cfg(not(feature = "no_function"))]
+            is_variadic: info.func.get_script_fn_def().map_or(false, |f| {
+                f.is_variadic && info.metadata.num_params == f.params.len()
+            }),
             typ,
             #[cfg(not(feature = "no_object"))]
             this_type: info.metadata.this_type.as_deref(),
@@ -195,7 +212,7 @@ impl<'a> From<&'a crate::Module> for ModuleMetadata<'a> {
 
         let mut custom_types = module
             .iter_custom_types()
-            .map(Into::into)
+            .map(|(name, info)| CustomTypeMetadata::new(name, info, module))
             .collect::<Vec<_>>();
         custom_types.sort();
 
@@ -244,8 +261,11 @@ pub fn gen_metadata_to_json(
                 global_doc.push_str(m.doc());
             }
 
-            m.iter_custom_types()
-                .for_each(|c| global.custom_types.push(c.into()));
+            m.iter_custom_types().for_each(|(name, info)| {
+                global
+                    .custom_types
+                    .push(CustomTypeMetadata::new(name, info, m));
+            });
 
             m.iter_fn().for_each(|f| {
                 #[allow(unused_mut)]
@@ -260,9 +280,13 @@ pub fn gen_metadata_to_json(
 
     #[cfg(not(feature = "no_function"))]
     if let Some(ast) = _ast {
-        ast.shared_lib()
-            .iter_custom_types()
-            .for_each(|c| global.custom_types.push(c.into()));
+        let lib = ast.shared_lib();
+
+        lib.iter_custom_types().for_each(|(name, info)| {
+            global
+                .custom_types
+                .push(CustomTypeMetadata::new(name, info, lib));
+        });
 
         ast.shared_lib().iter_fn().for_each(|f| {
             #[allow(unused_mut)]
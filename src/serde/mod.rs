@@ -4,8 +4,11 @@
 mod de;
 mod deserialize;
 mod metadata;
+mod scope;
 mod ser;
 mod serialize;
 
 pub use de::{from_dynamic, DynamicDeserializer};
+#[cfg(not(feature = "no_object"))]
+pub use scope::{from_scope, to_scope, to_scope_constants};
 pub use ser::{to_dynamic, DynamicSerializer};
@@ -0,0 +1,133 @@
+//! Conversion between a [`Scope`][crate::Scope] and types implementing [`serde`] traits.
+#![cfg(not(feature = "no_object"))]
+
+use super::{from_dynamic, to_dynamic};
+use crate::{Dynamic, Map, Position, RhaiResultOf, Scope, ERR};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Serialize a Rust type that implements [`serde::Serialize`] into a [`Scope`], with one variable
+/// per field (or per key in the case of a map).
+///
+/// Each value is converted via [`to_dynamic`], so nested structs become object maps.
+///
+/// Not available under `no_object`.
+///
+/// # Errors
+///
+/// Returns [`ErrorMismatchDataType`][crate::EvalAltResult::ErrorMismatchDataType] if the value does
+/// not serialize into an object map (e.g. it is a primitive or a sequence).
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, INT};
+/// use rhai::serde::to_scope;
+///
+/// #[derive(serde::Serialize)]
+/// struct Config {
+///     width: INT,
+///     height: INT,
+///     title: String,
+/// }
+///
+/// let config = Config { width: 40, height: 2, title: "hello".into() };
+///
+/// let mut scope = to_scope(&config)?;
+///
+/// let engine = Engine::new();
+///
+/// assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "width * height")?, 80);
+/// assert_eq!(engine.eval_with_scope::<String>(&mut scope, "title")?, "hello");
+/// # Ok(())
+/// # }
+/// ```
+#[inline(always)]
+pub fn to_scope<T: Serialize>(value: T) -> RhaiResultOf<Scope<'static>> {
+    serialize_into_scope(value, false)
+}
+
+/// Serialize a Rust type that implements [`serde::Serialize`] into a [`Scope`], with one constant
+/// per field (or per key in the case of a map).
+///
+/// See [`to_scope`] for details.
+///
+/// Not available under `no_object`.
+///
+/// # Errors
+///
+/// Returns [`ErrorMismatchDataType`][crate::EvalAltResult::ErrorMismatchDataType] if the value does
+/// not serialize into an object map (e.g. it is a primitive or a sequence).
+#[inline(always)]
+pub fn to_scope_constants<T: Serialize>(value: T) -> RhaiResultOf<Scope<'static>> {
+    serialize_into_scope(value, true)
+}
+
+/// Serialize a value into a [`Scope`], optionally as constants.
+fn serialize_into_scope<T: Serialize>(value: T, constant: bool) -> RhaiResultOf<Scope<'static>> {
+    let value = to_dynamic(value)?;
+
+    let map = value.try_cast_raw::<Map>().map_err(|v| {
+        ERR::ErrorMismatchDataType("map".into(), v.type_name().into(), Position::NONE)
+    })?;
+
+    let mut scope = Scope::with_capacity(map.len());
+
+    for (name, value) in map {
+        if constant {
+            scope.push_constant_dynamic(name, value);
+        } else {
+            scope.push_dynamic(name, value);
+        }
+    }
+
+    Ok(scope)
+}
+
+/// Deserialize the variables in a [`Scope`] into a Rust type that implements
+/// [`serde::Deserialize`], with one field (or key in the case of a map) per variable.
+///
+/// Shared values are flattened. When a variable name is shadowed, the latest value is used.
+///
+/// Not available under `no_object`.
+///
+/// # Errors
+///
+/// Returns an error if the variables in the [`Scope`] cannot be deserialized into the type
+/// (e.g. a field is missing or of the wrong type).
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+/// use rhai::{Engine, Scope, INT};
+/// use rhai::serde::from_scope;
+///
+/// #[derive(Debug, PartialEq, serde::Deserialize)]
+/// struct Output {
+///     total: INT,
+///     label: String,
+/// }
+///
+/// let engine = Engine::new();
+/// let mut scope = Scope::new();
+///
+/// engine.run_with_scope(&mut scope, r#"let total = 0; for n in 1..=4 { total += n; } let label = "sum";"#)?;
+///
+/// let output: Output = from_scope(&scope)?;
+///
+/// assert_eq!(output, Output { total: 10, label: "sum".into() });
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_scope<T: DeserializeOwned>(scope: &Scope) -> RhaiResultOf<T> {
+    let map = scope
+        .iter()
+        .map(|(name, _, value)| (name.into(), value))
+        .collect::<Map>();
+
+    from_dynamic(&Dynamic::from_map(map))
+}
@@ -3,6 +3,7 @@
 use crate::types::dynamic::Union;
 use crate::{Dynamic, ImmutableString, Scope};
 use serde::{ser::SerializeSeq, Serialize, Serializer};
+use std::any::type_name;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -14,6 +15,24 @@ use crate::types::dynamic::Variant;
 
 impl Serialize for Dynamic {
     fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        let tag = self.tag();
+
+        // Keep the tag when serializing into a `Dynamic`
+        if tag != Dynamic::DEFAULT_TAG && type_name::<S::Ok>() == type_name::<Dynamic>() {
+            return self.serialize_value(ser).map(|value| {
+                let mut value = reify! { value => !!! Dynamic };
+                value.set_tag(tag);
+                reify! { value => !!! S::Ok }
+            });
+        }
+
+        self.serialize_value(ser)
+    }
+}
+
+impl Dynamic {
+    /// Serialize the value of this [`Dynamic`], without its tag.
+    fn serialize_value<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
         match self.0 {
             Union::Unit(..) => ser.serialize_unit(),
             Union::Bool(x, ..) => ser.serialize_bool(x),
@@ -67,6 +86,10 @@ impl Serialize for Dynamic {
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(ref x, ..) => ser.serialize_str(x.as_ref().type_name()),
 
+            #[cfg(feature = "bigint")]
+            Union::Variant(ref v, ..) if (***v).as_any().is::<num_bigint::BigInt>() => {
+                ser.serialize_str(&self.to_string())
+            }
             Union::Variant(ref v, ..) => ser.serialize_str((***v).type_name()),
 
             #[cfg(not(feature = "no_closure"))]
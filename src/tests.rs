@@ -21,7 +21,10 @@ fn check_struct_sizes() {
         size_of::<Position>(),
         if cfg!(feature = "no_position") { 0 } else { 4 }
     );
+    assert_eq!(size_of::<Span>(), 2 * size_of::<Position>());
     assert_eq!(size_of::<tokenizer::Token>(), 2 * WORD_SIZE);
+    // Spans of expressions and statements are derived from their child nodes, and ending positions
+    // are only recorded inside boxed nodes (e.g. function calls), in order to keep these small.
     assert_eq!(size_of::<ast::Expr>(), if PACKED { 12 } else { 16 });
     assert_eq!(size_of::<Option<ast::Expr>>(), if PACKED { 12 } else { 16 });
     assert_eq!(size_of::<ast::Stmt>(), if IS_32_BIT { 12 } else { 16 });
@@ -45,8 +48,8 @@ fn check_struct_sizes() {
     assert_eq!(size_of::<Scope>(), 72);
     assert_eq!(
         size_of::<FnPtr>(),
-        48 - if cfg!(feature = "no_function") {
-            WORD_SIZE
+        56 - if cfg!(feature = "no_function") {
+            2 * WORD_SIZE
         } else {
             0
         }
@@ -70,3 +73,42 @@ fn check_struct_sizes() {
         }
     );
 }
+
+/// This test is to make sure that dynamic dispatch caches allowlist checks per function name.
+#[test]
+fn check_dispatch_cache() {
+    use crate::eval::{Caches, GlobalRuntimeState};
+    use crate::*;
+
+    let mut engine = Engine::new();
+    engine.register_fn("handle_a", |x: INT| x);
+    engine.register_fn("handle_b", |x: INT| x);
+    engine.set_dynamic_dispatch_allowlist(["handle_*"]);
+
+    let ast = engine
+        .compile(r#"dispatch("handle_", "a", 1) + dispatch("handle_", "a", 2) + dispatch("handle_", "b", 3)"#)
+        .unwrap();
+
+    let global = &mut GlobalRuntimeState::new(&engine);
+    let caches = &mut Caches::new();
+    let scope = &mut Scope::new();
+
+    let result = engine
+        .eval_ast_with_scope_raw(global, caches, scope, &ast)
+        .unwrap();
+    assert_eq!(result.as_int().unwrap(), 6);
+    assert_eq!(caches.dispatch_cache_len(), 2);
+
+    // Subsequent dispatches use the cached result instead of checking the allowlist again
+    caches
+        .dispatch_cache_mut()
+        .insert(calc_fn_hash(None, "handle_b", 0), false);
+
+    let err = engine
+        .eval_ast_with_scope_raw(global, caches, scope, &ast)
+        .unwrap_err();
+    assert!(
+        matches!(*err, EvalAltResult::ErrorDispatchNotAllowed(ref name, ..) if name == "handle_b")
+    );
+    assert_eq!(caches.dispatch_cache_len(), 2);
+}
@@ -2,7 +2,9 @@
 
 use crate::engine::Precedence;
 use crate::func::native::OnParseTokenCallback;
-use crate::{Engine, Identifier, LexError, Position, SmartString, INT, UNSIGNED_INT};
+use crate::{
+    Engine, Identifier, LanguageVersion, LexError, Position, SmartString, INT, UNSIGNED_INT,
+};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
@@ -23,6 +25,11 @@ pub struct TokenizerControlBlock {
     /// Global comments.
     #[cfg(feature = "metadata")]
     pub global_comments: String,
+    /// Positions of the tokens preceded by an `//! rhai:optimize=off` marker comment.
+    ///
+    /// Not available under `no_optimize`.
+    #[cfg(not(feature = "no_optimize"))]
+    pub optimize_off_positions: Vec<Position>,
     /// Whitespace-compressed version of the script (if any).
     ///
     /// Set to `Some` in order to collect a compressed script.
@@ -38,6 +45,8 @@ impl TokenizerControlBlock {
             is_within_text: false,
             #[cfg(feature = "metadata")]
             global_comments: String::new(),
+            #[cfg(not(feature = "no_optimize"))]
+            optimize_off_positions: Vec::new(),
             compressed: None,
         }
     }
@@ -54,8 +63,7 @@ const NUMBER_SEPARATOR: char = '_';
 /// A stream of tokens.
 pub type TokenStream<'a> = Peekable<TokenIterator<'a>>;
 
-/// _(internals)_ A Rhai language token.
-/// Exported under the `internals` feature only.
+/// A Rhai language token.
 #[derive(Debug, PartialEq, Clone, Hash)]
 #[non_exhaustive]
 pub enum Token {
@@ -71,6 +79,11 @@ pub enum Token {
     /// Requires the `decimal` feature, including its text representation.
     #[cfg(feature = "decimal")]
     DecimalConstant(Box<(rust_decimal::Decimal, Identifier)>),
+    /// A [`BigInt`][num_bigint::BigInt] constant, including its text representation.
+    ///
+    /// Requires the `bigint` feature.
+    #[cfg(feature = "bigint")]
+    BigIntConstant(Box<(num_bigint::BigInt, Identifier)>),
     /// An identifier.
     Identifier(Box<Identifier>),
     /// A character constant.
@@ -286,6 +299,8 @@ impl fmt::Display for Token {
             FloatConstant(v) => write!(f, "{}", v.0),
             #[cfg(feature = "decimal")]
             DecimalConstant(d) => write!(f, "{}", d.0),
+            #[cfg(feature = "bigint")]
+            BigIntConstant(n) => write!(f, "{}n", n.0),
             StringConstant(s) => write!(f, r#""{s}""#),
             InterpolatedString(..) => f.write_str("string"),
             CharConstant(c) => write!(f, "{c}"),
@@ -695,6 +710,8 @@ impl Token {
             FloatConstant(..) => false,
             #[cfg(feature = "decimal")]
             DecimalConstant(..) => false,
+            #[cfg(feature = "bigint")]
+            BigIntConstant(..) => false,
             StringConstant(..)
             | InterpolatedString(..)
             | CharConstant(..)
@@ -1138,6 +1155,31 @@ pub struct TokenizeState {
     /// Not available under `unchecked`.
     #[cfg(not(feature = "unchecked"))]
     pub max_string_len: Option<std::num::NonZeroUsize>,
+    /// Maximum length of an identifier.
+    ///
+    /// Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    pub max_identifier_len: Option<std::num::NonZeroUsize>,
+    /// Maximum length of a string literal.
+    ///
+    /// Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    pub max_string_literal_len: Option<std::num::NonZeroUsize>,
+    /// Maximum nesting depth of interpolated strings.
+    ///
+    /// Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    pub max_interpolation_depth: Option<std::num::NonZeroUsize>,
+    /// Current nesting depth of interpolated strings.
+    ///
+    /// Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    pub interpolation_depth: usize,
+    /// Number of tokens produced so far.
+    ///
+    /// Not available under `unchecked`.
+    #[cfg(not(feature = "unchecked"))]
+    pub num_tokens: usize,
     /// Can the next token be a unary operator?
     pub next_token_cannot_be_unary: bool,
     /// Shared object to allow controlling the tokenizer externally.
@@ -1152,6 +1194,11 @@ pub struct TokenizeState {
     ///
     /// Set to `Some` to begin tracking this information.
     pub last_token: Option<SmartString>,
+    /// Is there an `//! rhai:optimize=off` marker comment not yet attached to a token?
+    ///
+    /// Not available under `no_optimize`.
+    #[cfg(not(feature = "no_optimize"))]
+    pub is_optimize_off_pending: bool,
 }
 
 /// _(internals)_ Trait that encapsulates a peekable character input stream.
@@ -1279,6 +1326,12 @@ pub fn parse_string_literal(
                 return Err((LexError::StringTooLong(max.get()), start));
             }
         }
+        #[cfg(not(feature = "unchecked"))]
+        if let Some(max) = state.max_string_literal_len {
+            if result.len() > max.get() {
+                return Err((LexError::StringLiteralTooLong(max.get()), start));
+            }
+        }
 
         // Close wrapper
         if termination_char == next_char && escape.is_empty() {
@@ -1426,6 +1479,12 @@ pub fn parse_string_literal(
             return Err((LexError::StringTooLong(max.get()), start));
         }
     }
+    #[cfg(not(feature = "unchecked"))]
+    if let Some(max) = state.max_string_literal_len {
+        if result.len() > max.get() {
+            return Err((LexError::StringLiteralTooLong(max.get()), start));
+        }
+    }
 
     Ok((result, interpolated, first_char))
 }
@@ -1512,6 +1571,17 @@ pub fn is_doc_comment(comment: &str) -> bool {
         || (comment.starts_with("/**") && !comment.starts_with("/***"))
 }
 
+/// Test if the comment is an `//! rhai:optimize=off` marker, which turns off optimization for
+/// the script (if placed before any code) or for the function definition that follows it.
+#[cfg(not(feature = "no_optimize"))]
+#[inline]
+#[must_use]
+fn is_optimize_off_marker(comment: &str) -> bool {
+    comment
+        .strip_prefix("//!")
+        .map_or(false, |s| s.trim() == "rhai:optimize=off")
+}
+
 /// _(internals)_ Get the next token from the input stream.
 /// Exported under the `internals` feature only.
 #[inline(always)]
@@ -1528,6 +1598,18 @@ pub fn get_next_token(
         state.next_token_cannot_be_unary = !token.is_next_unary();
     }
 
+    // Attach a pending `//! rhai:optimize=off` marker to the next token that is not a comment
+    #[cfg(not(feature = "no_optimize"))]
+    if state.is_optimize_off_pending {
+        if let Some((ref token, pos)) = result {
+            if !matches!(token, Token::Comment(..)) {
+                state.is_optimize_off_pending = false;
+                let control = &mut *state.tokenizer_control.borrow_mut();
+                control.optimize_off_positions.push(pos);
+            }
+        }
+    }
+
     result
 }
 
@@ -1571,6 +1653,11 @@ fn get_next_token_inner(
                 if interpolated {
                     Some((Token::InterpolatedString(result.into()), start_pos))
                 } else {
+                    // End of an interpolated string
+                    #[cfg(not(feature = "unchecked"))]
+                    {
+                        state.interpolation_depth = state.interpolation_depth.saturating_sub(1);
+                    }
                     Some((Token::StringConstant(result.into()), start_pos))
                 }
             },
@@ -1595,11 +1682,17 @@ fn get_next_token_inner(
                 let mut result = SmartString::new_const();
                 let mut radix_base: Option<u32> = None;
                 let mut valid: fn(char) -> bool = is_numeric_digit;
+                #[cfg(feature = "bigint")]
+                let mut is_bigint = false;
+                let mut separators = Vec::new();
                 result.push(c);
 
                 while let Some(next_char) = stream.peek_next() {
                     match next_char {
                         NUMBER_SEPARATOR => {
+                            if state.last_token.is_some() {
+                                separators.push(result.len());
+                            }
                             stream.eat_next_and_advance(pos);
                         }
                         ch if valid(ch) => {
@@ -1687,6 +1780,14 @@ fn get_next_token_inner(
                             });
                         }
 
+                        // n suffix - BigInt literal (integers only)
+                        #[cfg(feature = "bigint")]
+                        'n' if radix_base.is_some() || !result.contains(['.', 'e']) => {
+                            stream.eat_next_and_advance(pos);
+                            is_bigint = true;
+                            break;
+                        }
+
                         _ => break,
                     }
                 }
@@ -1697,10 +1798,42 @@ fn get_next_token_inner(
                 });
 
                 if let Some(ref mut last) = state.last_token {
-                    *last = result.clone();
+                    // Keep the separators in the textual syntax
+                    let offset = usize::from(negated.is_some());
+                    let mut start = 0;
+                    last.clear();
+                    for index in separators {
+                        last.push_str(&result[start..index + offset]);
+                        last.push(NUMBER_SEPARATOR);
+                        start = index + offset;
+                    }
+                    last.push_str(&result[start..]);
                 }
 
                 // Parse number
+                #[cfg(feature = "bigint")]
+                if is_bigint {
+                    if let Some(ref mut last) = state.last_token {
+                        last.push('n');
+                    }
+
+                    let num = match radix_base {
+                        Some(radix) => {
+                            let digits = result.trim_start_matches('-');
+                            num_bigint::BigInt::parse_bytes(&digits.as_bytes()[2..], radix)
+                                .map(|v| if negated.is_some() { -v } else { v })
+                        }
+                        None => num_bigint::BigInt::parse_bytes(result.as_bytes(), 10),
+                    };
+
+                    let token = match num {
+                        Some(v) => Token::BigIntConstant((v, result).into()),
+                        None => Token::LexError(LERR::MalformedNumber(format!("{result}n")).into()),
+                    };
+
+                    return Some((token, num_pos));
+                }
+
                 let token = if let Some(radix) = radix_base {
                     let result = &result[2..];
 
@@ -1780,6 +1913,18 @@ fn get_next_token_inner(
                     |(err, err_pos)| Some((Token::LexError(err.into()), err_pos)),
                     |(result, interpolated, ..)| {
                         if interpolated {
+                            // Start of an interpolated string
+                            #[cfg(not(feature = "unchecked"))]
+                            {
+                                state.interpolation_depth += 1;
+
+                                if let Some(max) = state.max_interpolation_depth {
+                                    if state.interpolation_depth > max.get() {
+                                        let err = LERR::InterpolationTooDeep(max.get());
+                                        return Some((Token::LexError(err.into()), start_pos));
+                                    }
+                                }
+                            }
                             Some((Token::InterpolatedString(result.into()), start_pos))
                         } else {
                             Some((Token::StringConstant(result.into()), start_pos))
@@ -1932,7 +2077,7 @@ fn get_next_token_inner(
                             _ => Some("///".into()),
                         }
                     }
-                    #[cfg(feature = "metadata")]
+                    #[cfg(any(feature = "metadata", not(feature = "no_optimize")))]
                     Some('!') => {
                         stream.eat_next_and_advance(pos);
                         Some("//!".into())
@@ -1961,14 +2106,30 @@ fn get_next_token_inner(
                 }
 
                 match comment {
+                    #[cfg(not(feature = "no_optimize"))]
+                    Some(comment) if is_optimize_off_marker(&comment) => {
+                        state.is_optimize_off_pending = true;
+
+                        if state.include_comments {
+                            return Some((Token::Comment(comment.into()), start_pos));
+                        }
+                    }
                     #[cfg(feature = "metadata")]
                     Some(comment) if comment.starts_with("//!") => {
-                        let g = &mut state.tokenizer_control.borrow_mut().global_comments;
-                        if !g.is_empty() {
-                            g.push('\n');
+                        {
+                            let g = &mut state.tokenizer_control.borrow_mut().global_comments;
+                            if !g.is_empty() {
+                                g.push('\n');
+                            }
+                            g.push_str(&comment);
+                        }
+                        if state.include_comments {
+                            return Some((Token::Comment(comment.into()), start_pos));
                         }
-                        g.push_str(&comment);
                     }
+                    #[cfg(not(feature = "metadata"))]
+                    #[cfg(not(feature = "no_optimize"))]
+                    Some(comment) if comment.starts_with("//!") && !state.include_comments => (),
                     Some(comment) => return Some((Token::Comment(comment.into()), start_pos)),
                     None => (),
                 }
@@ -2257,6 +2418,19 @@ fn parse_identifier_token(
         match next_char {
             x if is_id_continue(x) => {
                 stream.eat_next_and_advance(pos);
+
+                #[cfg(not(feature = "unchecked"))]
+                if let Some(max) = state.max_identifier_len {
+                    if identifier.len() + x.len_utf8() > max.get() {
+                        // Skip the rest of the identifier without keeping it
+                        while stream.peek_next().map_or(false, is_id_continue) {
+                            stream.eat_next_and_advance(pos);
+                        }
+                        let err = LERR::IdentifierTooLong(max.get());
+                        return (Token::LexError(err.into()), start_pos);
+                    }
+                }
+
                 identifier.push(x);
                 if let Some(ref mut last) = state.last_token {
                     last.push(x);
@@ -2500,6 +2674,16 @@ impl<'a> Iterator for TokenIterator<'a> {
             )
         };
 
+        // Guard against too many tokens
+        #[cfg(not(feature = "unchecked"))]
+        if let Some(max) = self.engine.limits.max_tokens {
+            if self.state.num_tokens >= max.get() {
+                let err = LERR::TooManyTokens(max.get());
+                return Some((Token::LexError(err.into()), self.pos));
+            }
+            self.state.num_tokens += 1;
+        }
+
         let (token, pos) = match get_next_token(&mut self.stream, &mut self.state, &mut self.pos) {
             // {EOF}
             None => return None,
@@ -2552,6 +2736,8 @@ impl<'a> Iterator for TokenIterator<'a> {
                 (.., true) => Token::Custom(s),
                 #[cfg(feature = "no_custom_syntax")]
                 (.., true) => unreachable!("no custom operators"),
+                // `is_shared` is a normal identifier in older language versions.
+                ("is_shared", false) if self.engine.language_version() < LanguageVersion::V1_14 => Token::Identifier(s),
                 // Reserved keyword that is not custom and disabled.
                 (token, false) if self.engine.is_symbol_disabled(token) => {
                     let msg = format!("reserved {} '{token}' is disabled", if is_valid_identifier(token) { "keyword"} else {"symbol"});
@@ -2584,6 +2770,17 @@ impl<'a> Iterator for TokenIterator<'a> {
             Some(r) => r,
         };
 
+        // Guard against overly long lines (errors in the token itself take precedence)
+        #[cfg(not(feature = "unchecked"))]
+        if let Some(max) = self.engine.limits.max_line_len {
+            if !matches!(token, Token::LexError(..))
+                && self.pos.position().map_or(false, |p| p > max.get())
+            {
+                let err = LERR::LineTooLong(max.get());
+                return Some((Token::LexError(err.into()), self.pos));
+            }
+        }
+
         // Run the mapper, if any
         let token = match self.token_mapper {
             Some(func) => func(token, pos, &self.state),
@@ -2680,7 +2877,7 @@ impl Engine {
 #[must_use]
 pub fn lex_raw<'a>(
     engine: &'a Engine,
-    inputs: impl IntoIterator<Item = &'a (impl AsRef<str> + 'a)>,
+    inputs: impl IntoIterator<Item = &'a (impl AsRef<str> + ?Sized + 'a)>,
     token_mapper: Option<&'a OnParseTokenCallback>,
 ) -> (TokenIterator<'a>, TokenizerControl) {
     let buffer: TokenizerControl = RefCell::new(TokenizerControlBlock::new()).into();
@@ -2694,12 +2891,24 @@ pub fn lex_raw<'a>(
             state: TokenizeState {
                 #[cfg(not(feature = "unchecked"))]
                 max_string_len: std::num::NonZeroUsize::new(engine.max_string_size()),
+                #[cfg(not(feature = "unchecked"))]
+                max_identifier_len: engine.limits.max_identifier_len,
+                #[cfg(not(feature = "unchecked"))]
+                max_string_literal_len: engine.limits.max_string_literal_len,
+                #[cfg(not(feature = "unchecked"))]
+                max_interpolation_depth: engine.limits.max_interpolation_depth,
+                #[cfg(not(feature = "unchecked"))]
+                interpolation_depth: 0,
+                #[cfg(not(feature = "unchecked"))]
+                num_tokens: 0,
                 next_token_cannot_be_unary: false,
                 tokenizer_control: buffer,
                 comment_level: 0,
                 include_comments: false,
                 is_within_text_terminated_by: None,
                 last_token: None,
+                #[cfg(not(feature = "no_optimize"))]
+                is_optimize_off_pending: false,
             },
             pos: Position::new(1, 0),
             stream: MultiInputsStream {
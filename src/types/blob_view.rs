@@ -0,0 +1,214 @@
+//! The `BlobView` type.
+#![cfg(not(feature = "no_index"))]
+
+use crate::{Blob, Shared};
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+/// A view into a range of bytes in a shared [`Blob`], without copying.
+///
+/// Views of the same [`Blob`] share its storage.  Mutating a view whose storage is shared with
+/// other views first copies the bytes in the view into new storage (i.e. copy-on-write), so
+/// mutations are never visible via other views.
+///
+/// Not available under `no_index`.
+///
+/// # Example
+///
+/// ```
+/// use rhai::BlobView;
+///
+/// let view = BlobView::new(vec![1_u8, 2, 3, 4, 5]);
+/// let mut sub = view.slice(1, 3);
+///
+/// assert_eq!(&*sub, &[2, 3, 4]);
+/// assert!(sub.is_shared());
+///
+/// sub.make_mut()[0] = 42;
+///
+/// assert_eq!(&*sub, &[42, 3, 4]);
+/// assert_eq!(&*view, &[1, 2, 3, 4, 5]);
+/// assert!(!sub.is_shared());
+/// ```
+#[derive(Clone)]
+pub struct BlobView {
+    /// Shared storage.
+    blob: Shared<Blob>,
+    /// Offset of the view into the storage.
+    offset: usize,
+    /// Number of bytes in the view.
+    len: usize,
+}
+
+impl fmt::Debug for BlobView {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        self.iter().try_for_each(|b| write!(f, "{b:02x}"))?;
+        f.write_str("]")
+    }
+}
+
+impl Deref for BlobView {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.blob[self.offset..][..self.len]
+    }
+}
+
+impl AsRef<[u8]> for BlobView {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl PartialEq for BlobView {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for BlobView {}
+
+impl Hash for BlobView {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl From<Blob> for BlobView {
+    #[inline(always)]
+    fn from(value: Blob) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Shared<Blob>> for BlobView {
+    #[inline(always)]
+    fn from(value: Shared<Blob>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl IntoIterator for BlobView {
+    type Item = crate::INT;
+    type IntoIter = BlobViewIter;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        BlobViewIter {
+            view: self,
+            index: 0,
+        }
+    }
+}
+
+/// An iterator over the bytes in a [`BlobView`], as integers.
+#[derive(Debug, Clone)]
+pub struct BlobViewIter {
+    view: BlobView,
+    index: usize,
+}
+
+impl Iterator for BlobViewIter {
+    type Item = crate::INT;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = *self.view.get(self.index)?;
+        self.index += 1;
+        Some(byte as crate::INT)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.view.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for BlobViewIter {}
+
+impl BlobView {
+    /// Create a new [`BlobView`] covering an entire [`Blob`].
+    ///
+    /// A [`Blob`] that is already in shared storage is not copied.
+    #[inline]
+    #[must_use]
+    pub fn new(blob: impl Into<Shared<Blob>>) -> Self {
+        let blob = blob.into();
+        let len = blob.len();
+
+        Self {
+            blob,
+            offset: 0,
+            len,
+        }
+    }
+    /// Create a new [`BlobView`] into a range of bytes in this view, sharing the same storage.
+    ///
+    /// The range is clipped to the bytes in this view.
+    #[inline]
+    #[must_use]
+    pub fn slice(&self, start: usize, len: usize) -> Self {
+        let start = start.min(self.len);
+        let len = len.min(self.len - start);
+
+        Self {
+            blob: self.blob.clone(),
+            offset: self.offset + start,
+            len,
+        }
+    }
+    /// Get the shared storage of this [`BlobView`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn shared_blob(&self) -> &Shared<Blob> {
+        &self.blob
+    }
+    /// Get the offset of this [`BlobView`] into its shared storage.
+    #[inline(always)]
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+    /// Is the storage of this [`BlobView`] shared with other views?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_shared(&self) -> bool {
+        Shared::strong_count(&self.blob) > 1
+    }
+    /// Get a mutable reference to the bytes in this [`BlobView`].
+    ///
+    /// If the storage is shared with other views, the bytes in this view are first copied into
+    /// new storage.
+    #[inline]
+    #[must_use]
+    pub fn make_mut(&mut self) -> &mut [u8] {
+        if Shared::get_mut(&mut self.blob).is_none() {
+            self.blob = Shared::new(self.to_vec());
+            self.offset = 0;
+        }
+
+        let (offset, len) = (self.offset, self.len);
+        let blob = Shared::get_mut(&mut self.blob).unwrap();
+        &mut blob[offset..][..len]
+    }
+    /// Copy the bytes in this [`BlobView`] into a new [`Blob`].
+    #[inline(always)]
+    #[must_use]
+    pub fn to_blob(&self) -> Blob {
+        self.to_vec()
+    }
+}
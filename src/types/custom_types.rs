@@ -27,6 +27,10 @@ pub struct CustomTypeInfo {
     /// Each line in non-block doc-comments starts with `///`.
     #[cfg(feature = "metadata")]
     pub comments: Box<[crate::SmartString]>,
+    /// Hashes of the functions registered together with the custom type (e.g. via a
+    /// [`TypeBuilder`][crate::TypeBuilder]), in order of registration.
+    #[cfg(feature = "metadata")]
+    pub functions: Box<[u64]>,
 }
 
 /// _(internals)_ A collection of custom types.
@@ -61,6 +65,8 @@ impl CustomTypesCollection {
             display_name: name.into(),
             #[cfg(feature = "metadata")]
             comments: <_>::default(),
+            #[cfg(feature = "metadata")]
+            functions: <_>::default(),
         };
         self.add_raw(type_name, custom_type);
     }
@@ -79,6 +85,7 @@ impl CustomTypesCollection {
             type_name: type_name.clone(),
             display_name: name.into(),
             comments: comments.into_iter().map(Into::into).collect(),
+            functions: <_>::default(),
         };
         self.add_raw(type_name, custom_type);
     }
@@ -92,6 +99,8 @@ impl CustomTypesCollection {
                 display_name: name.into(),
                 #[cfg(feature = "metadata")]
                 comments: <_>::default(),
+                #[cfg(feature = "metadata")]
+                functions: <_>::default(),
             },
         );
     }
@@ -107,6 +116,27 @@ impl CustomTypesCollection {
                 display_name: name.into(),
                 #[cfg(feature = "metadata")]
                 comments: comments.iter().map(|&s| s.into()).collect(),
+                #[cfg(feature = "metadata")]
+                functions: <_>::default(),
+            },
+        );
+    }
+    /// Register a custom type together with the hashes of its functions.
+    /// Exported under the `metadata` feature only.
+    #[cfg(feature = "metadata")]
+    #[inline(always)]
+    pub fn add_type_with_functions<T>(
+        &mut self,
+        name: &str,
+        functions: impl IntoIterator<Item = u64>,
+    ) {
+        self.add_raw(
+            type_name::<T>(),
+            CustomTypeInfo {
+                type_name: type_name::<T>().into(),
+                display_name: name.into(),
+                comments: <_>::default(),
+                functions: functions.into_iter().collect(),
             },
         );
     }
@@ -1,6 +1,6 @@
 //! Helper module which defines the [`Dynamic`] data type.
 
-use crate::{ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, INT};
+use crate::{ExclusiveRange, FnPtr, ImmutableString, InclusiveRange, Position, INT};
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
@@ -180,9 +180,28 @@ impl<'d, T: Any + Clone> DerefMut for DynamicWriteLock<'d, T> {
 }
 
 impl Dynamic {
+    /// The tag of a [`Dynamic`] value that has no arbitrary data attached.
+    pub const DEFAULT_TAG: Tag = DEFAULT_TAG_VALUE;
+    /// The smallest value of the arbitrary data that can be attached to a [`Dynamic`].
+    pub const MIN_TAG: Tag = Tag::MIN;
+    /// The largest value of the arbitrary data that can be attached to a [`Dynamic`].
+    pub const MAX_TAG: Tag = Tag::MAX;
+
     /// Get the arbitrary data attached to this [`Dynamic`].
+    ///
+    /// The tag is kept when the value is cloned, stored in an array or object map, shared
+    /// (e.g. captured by a closure) or converted via [`to_dynamic`][crate::serde::to_dynamic] and
+    /// [`from_dynamic`][crate::serde::from_dynamic].
+    ///
+    /// The tag is between [`Dynamic::MIN_TAG`] and [`Dynamic::MAX_TAG`] and defaults to
+    /// [`Dynamic::DEFAULT_TAG`].
+    ///
+    /// # Panics or Deadlocks When Value is Shared
+    ///
+    /// Under the `sync` feature, this call may deadlock, or [panic](https://doc.rust-lang.org/std/sync/struct.RwLock.html#panics-1).
+    /// Otherwise, this call panics if the data is currently borrowed for write.
     #[must_use]
-    pub const fn tag(&self) -> Tag {
+    pub fn tag(&self) -> Tag {
         match self.0 {
             Union::Unit((), tag, _)
             | Union::Bool(_, tag, _)
@@ -203,10 +222,17 @@ impl Dynamic {
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(_, tag, _) => tag,
             #[cfg(not(feature = "no_closure"))]
-            Union::Shared(_, tag, _) => tag,
+            Union::Shared(ref cell, ..) => crate::func::locked_read(cell).tag(),
         }
     }
     /// Attach arbitrary data to this [`Dynamic`].
+    ///
+    /// If the value is shared, the tag is also attached to the underlying value.
+    ///
+    /// # Panics or Deadlocks When Value is Shared
+    ///
+    /// Under the `sync` feature, this call may deadlock, or [panic](https://doc.rust-lang.org/std/sync/struct.RwLock.html#panics-1).
+    /// Otherwise, this call panics if the data is currently borrowed.
     pub fn set_tag(&mut self, value: Tag) -> &mut Self {
         match self.0 {
             Union::Unit((), ref mut tag, _)
@@ -228,10 +254,51 @@ impl Dynamic {
             #[cfg(not(feature = "no_time"))]
             Union::TimeStamp(_, ref mut tag, _) => *tag = value,
             #[cfg(not(feature = "no_closure"))]
-            Union::Shared(_, ref mut tag, _) => *tag = value,
+            Union::Shared(ref cell, ref mut tag, _) => {
+                *tag = value;
+                crate::func::locked_write(cell).set_tag(value);
+            }
         }
         self
     }
+    /// Get the provenance of this [`Dynamic`], i.e. the position of the expression that produced it.
+    ///
+    /// Provenance is only recorded when
+    /// [`Engine::set_track_provenance`][crate::Engine::set_track_provenance] is turned on,
+    /// in which case it is kept in the _tag_ of the value.
+    ///
+    /// Returns [`None`] if there is no recorded provenance.
+    ///
+    /// On 32-bit targets, only the line number is recorded.
+    #[inline]
+    #[must_use]
+    pub fn provenance(&self) -> Option<Position> {
+        #[cfg(not(target_pointer_width = "32"))]
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let (line, pos) = ((self.tag() as u32 >> 16) as u16, self.tag() as u16);
+        #[cfg(target_pointer_width = "32")]
+        #[allow(clippy::cast_sign_loss)]
+        let (line, pos) = (self.tag() as u16, 0);
+
+        if line == 0 {
+            None
+        } else {
+            Some(Position::new(line, pos))
+        }
+    }
+    /// Record the provenance of this [`Dynamic`] in its _tag_.
+    #[inline]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub(crate) fn set_provenance(&mut self, pos: Position) -> &mut Self {
+        let line = pos.line().unwrap_or(0) as u16;
+
+        #[cfg(not(target_pointer_width = "32"))]
+        let tag = ((u32::from(line) << 16) | pos.position().unwrap_or(0) as u32) as Tag;
+        #[cfg(target_pointer_width = "32")]
+        let tag = line as Tag;
+
+        self.set_tag(tag)
+    }
     /// Does this [`Dynamic`] hold a variant data type instead of one of the supported system
     /// primitive types?
     #[inline(always)]
@@ -490,12 +557,34 @@ impl fmt::Display for Dynamic {
                     return fmt::Display::fmt(value, f);
                 }
 
+                #[cfg(feature = "bigint")]
+                if let Some(value) = _value_any.downcast_ref::<num_bigint::BigInt>() {
+                    return fmt::Display::fmt(value, f);
+                }
+
                 if let Some(range) = _value_any.downcast_ref::<ExclusiveRange>() {
                     return write!(f, "{}..{}", range.start, range.end);
                 } else if let Some(range) = _value_any.downcast_ref::<InclusiveRange>() {
                     return write!(f, "{}..={}", range.start(), range.end());
                 }
 
+                #[cfg(not(feature = "no_float"))]
+                if let Some(range) = _value_any.downcast_ref::<std::ops::Range<crate::FLOAT>>() {
+                    let (start, end) = (
+                        super::FloatWrapper::new(range.start),
+                        super::FloatWrapper::new(range.end),
+                    );
+                    return write!(f, "{start}..{end}");
+                } else if let Some(range) =
+                    _value_any.downcast_ref::<std::ops::RangeInclusive<crate::FLOAT>>()
+                {
+                    let (start, end) = (
+                        super::FloatWrapper::new(*range.start()),
+                        super::FloatWrapper::new(*range.end()),
+                    );
+                    return write!(f, "{start}..={end}");
+                }
+
                 f.write_str((***v).type_name())
             }
 
@@ -592,12 +681,26 @@ impl fmt::Debug for Dynamic {
                     return fmt::Debug::fmt(value, f);
                 }
 
+                #[cfg(feature = "bigint")]
+                if let Some(value) = _value_any.downcast_ref::<num_bigint::BigInt>() {
+                    return fmt::Debug::fmt(value, f);
+                }
+
                 if let Some(range) = _value_any.downcast_ref::<ExclusiveRange>() {
                     return write!(f, "{}..{}", range.start, range.end);
                 } else if let Some(range) = _value_any.downcast_ref::<InclusiveRange>() {
                     return write!(f, "{}..={}", range.start(), range.end());
                 }
 
+                #[cfg(not(feature = "no_float"))]
+                if let Some(range) = _value_any.downcast_ref::<std::ops::Range<crate::FLOAT>>() {
+                    return write!(f, "{range:?}");
+                } else if let Some(range) =
+                    _value_any.downcast_ref::<std::ops::RangeInclusive<crate::FLOAT>>()
+                {
+                    return write!(f, "{range:?}");
+                }
+
                 f.write_str((***v).type_name())
             }
 
@@ -995,10 +1098,11 @@ impl Dynamic {
 
             #[cfg(not(feature = "no_float"))]
             Union::Float(..) => true,
+            // Shared values nested within data are not followed, as they may contain themselves
             #[cfg(not(feature = "no_index"))]
-            Union::Array(..) => true,
+            Union::Array(ref a, ..) => a.iter().all(|v| v.is_hashable_data()),
             #[cfg(not(feature = "no_object"))]
-            Union::Map(..) => true,
+            Union::Map(ref m, ..) => m.values().all(|v| v.is_hashable_data()),
 
             #[cfg(not(feature = "no_closure"))]
             Union::Shared(ref cell, ..) => crate::func::locked_read(cell).is_hashable(),
@@ -1006,6 +1110,16 @@ impl Dynamic {
             _ => false,
         }
     }
+    /// Can this [`Dynamic`], nested within other data, be hashed?
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    #[must_use]
+    fn is_hashable_data(&self) -> bool {
+        match self.0 {
+            #[cfg(not(feature = "no_closure"))]
+            Union::Shared(..) => false,
+            _ => self.is_hashable(),
+        }
+    }
     /// Create a [`Dynamic`] from any type.  A [`Dynamic`] value is simply returned as is.
     ///
     /// # Arrays
@@ -1123,18 +1237,17 @@ impl Dynamic {
     /// values.
     ///
     /// If the [`Dynamic`] value is already shared, this method returns itself.
+    ///
+    /// The tag of the value is kept.
     #[cfg(not(feature = "no_closure"))]
     #[inline]
     pub fn into_shared(self) -> Self {
         let _access = self.access_mode();
+        let tag = self.tag();
 
         match self.0 {
             Union::Shared(..) => self,
-            _ => Self(Union::Shared(
-                crate::Locked::new(self).into(),
-                DEFAULT_TAG_VALUE,
-                _access,
-            )),
+            _ => Self(Union::Shared(crate::Locked::new(self).into(), tag, _access)),
         }
     }
     /// Return this [`Dynamic`], replacing it with [`Dynamic::UNIT`].
@@ -1176,6 +1289,35 @@ impl Dynamic {
     /// Casting to a [`Dynamic`] just returns as is, but if it contains a shared value,
     /// it is cloned into a [`Dynamic`] with a normal value.
     ///
+    /// Returns the value itself (with any shared value flattened) if types mismatched.
+    ///
+    /// # Panics or Deadlocks
+    ///
+    /// Under the `sync` feature, this call may deadlock, or [panic](https://doc.rust-lang.org/std/sync/struct.RwLock.html#panics-1).
+    /// Otherwise, this call panics if the data is currently borrowed for write.
+    ///
+    /// These normally shouldn't occur since most operations in Rhai is single-threaded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Dynamic;
+    ///
+    /// let x = Dynamic::from(42_u32);
+    ///
+    /// let x = x.try_cast_result::<String>().expect_err("x should not be a string");
+    ///
+    /// assert_eq!(x.try_cast_result::<u32>().expect("x should be u32"), 42);
+    /// ```
+    #[inline(always)]
+    pub fn try_cast_result<T: Any>(self) -> Result<T, Self> {
+        self.try_cast_raw()
+    }
+    /// Convert the [`Dynamic`] value into specific type.
+    ///
+    /// Casting to a [`Dynamic`] just returns as is, but if it contains a shared value,
+    /// it is cloned into a [`Dynamic`] with a normal value.
+    ///
     /// Returns itself if types mismatched.
     #[allow(unused_mut)]
     pub(crate) fn try_cast_raw<T: Any>(mut self) -> Result<T, Self> {
@@ -1392,6 +1534,112 @@ impl Dynamic {
             _ => self,
         }
     }
+    /// Flatten the [`Dynamic`] together with all the shared values nested within it (e.g. items
+    /// in an array or object map, or curried arguments of a function pointer), producing a value
+    /// that does not contain any shared value.
+    ///
+    /// Shared values are flattened as per [`flatten`][Dynamic::flatten].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorCyclicData`][crate::EvalAltResult::ErrorCyclicData] if a shared value
+    /// contains itself, either directly or via other values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Array, Dynamic, EvalAltResult};
+    ///
+    /// let mut x = Dynamic::from_array(vec![Dynamic::from(42_i64)]).into_shared();
+    /// let y = Dynamic::from_array(vec![x.clone()]);
+    ///
+    /// # #[cfg(not(feature = "no_closure"))]
+    /// # {
+    /// assert!(y.clone().flatten_deep().unwrap().into_array().unwrap()[0].is_array());
+    ///
+    /// // Make `x` contain itself
+    /// let x2 = x.clone();
+    /// x.write_lock::<Array>().unwrap().push(x2);
+    ///
+    /// assert!(matches!(*y.flatten_deep().unwrap_err(), EvalAltResult::ErrorCyclicData(..)));
+    /// # }
+    /// ```
+    pub fn flatten_deep(self) -> crate::RhaiResultOf<Self> {
+        fn flatten_inner(
+            value: Dynamic,
+            _ancestors: &mut crate::StaticVec<*const ()>,
+        ) -> crate::RhaiResultOf<Dynamic> {
+            match value.0 {
+                #[cfg(not(feature = "no_closure"))]
+                Union::Shared(ref cell, ..) => {
+                    let ptr = crate::Shared::as_ptr(cell).cast::<()>();
+
+                    if _ancestors.contains(&ptr) {
+                        let typ = crate::func::locked_read(cell).type_name();
+                        return Err(crate::ERR::ErrorCyclicData(typ.into(), Position::NONE).into());
+                    }
+
+                    _ancestors.push(ptr);
+                    let result = flatten_inner(value.flatten(), _ancestors);
+                    _ancestors.pop();
+                    result
+                }
+                #[cfg(not(feature = "no_index"))]
+                Union::Array(mut a, tag, access) => {
+                    for v in a.iter_mut() {
+                        *v = flatten_inner(v.take(), _ancestors)?;
+                    }
+                    Ok(Dynamic(Union::Array(a, tag, access)))
+                }
+                #[cfg(not(feature = "no_object"))]
+                Union::Map(mut m, tag, access) => {
+                    for v in m.values_mut() {
+                        *v = flatten_inner(v.take(), _ancestors)?;
+                    }
+                    Ok(Dynamic(Union::Map(m, tag, access)))
+                }
+                Union::FnPtr(mut f, tag, access) => {
+                    for v in f.iter_curry_mut() {
+                        *v = flatten_inner(v.take(), _ancestors)?;
+                    }
+                    Ok(Dynamic(Union::FnPtr(f, tag, access)))
+                }
+                _ => Ok(value),
+            }
+        }
+
+        flatten_inner(self, &mut crate::StaticVec::new_const())
+    }
+    /// Get the address of the shared value held by this [`Dynamic`], or a null pointer if it is
+    /// not shared.
+    ///
+    /// Used to identify shared values when detecting cycles.
+    #[cfg(not(feature = "no_closure"))]
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn shared_ptr(&self) -> *const () {
+        match self.0 {
+            Union::Shared(ref cell, ..) => crate::Shared::as_ptr(cell).cast(),
+            _ => std::ptr::null(),
+        }
+    }
+    /// Flatten the [`Dynamic`] and clone it, as per [`flatten_clone`][Dynamic::flatten_clone].
+    ///
+    /// Returns [`None`] if the [`Dynamic`] is a shared value that is currently locked for write.
+    #[cfg(not(feature = "no_closure"))]
+    #[cfg(any(not(feature = "no_index"), not(feature = "no_object")))]
+    #[inline]
+    #[must_use]
+    pub(crate) fn try_flatten_clone(&self) -> Option<Self> {
+        match self.0 {
+            #[cfg(not(feature = "sync"))]
+            Union::Shared(ref cell, ..) => cell.try_borrow().ok().map(|v| v.clone()),
+            #[cfg(feature = "sync")]
+            Union::Shared(ref cell, ..) => cell.try_read().ok().map(|v| v.clone()),
+            _ => Some(self.clone()),
+        }
+    }
     /// Is the [`Dynamic`] a shared value that is locked?
     ///
     /// Not available under `no_closure`.
@@ -1979,59 +2227,63 @@ impl Dynamic {
         }
     }
     /// Convert the [`Dynamic`] into a [`Vec`].
-    /// Returns the name of the actual type if any cast fails.
+    ///
+    /// A shared value is flattened first (i.e. cloned only if it is referenced elsewhere).
+    /// No elements are copied when converting into a [`Vec<Dynamic>`][Vec].
     ///
     /// Not available under `no_index`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ErrorMismatchDataType`][crate::EvalAltResult::ErrorMismatchDataType] if the
+    /// [`Dynamic`] is not an array, or naming the index and type of the first element that
+    /// cannot be cast.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Dynamic;
+    ///
+    /// let x: Dynamic = vec![1_u32, 2, 3].into();
+    ///
+    /// assert_eq!(x.clone().into_typed_array::<u32>().unwrap(), [1, 2, 3]);
+    ///
+    /// let err = x.into_typed_array::<String>().unwrap_err();
+    ///
+    /// assert_eq!(err.to_string(), "Data type incorrect: u32 at index 0 (expecting String)");
+    /// ```
     #[cfg(not(feature = "no_index"))]
-    #[inline(always)]
-    pub fn into_typed_array<T: Variant + Clone>(self) -> Result<Vec<T>, &'static str> {
-        match self.0 {
+    #[inline]
+    pub fn into_typed_array<T: Variant + Clone>(self) -> crate::RhaiResultOf<Vec<T>> {
+        let type_name = || crate::api::formatting::map_std_type_name(type_name::<T>(), false);
+
+        match self.flatten().0 {
+            Union::Array(a, ..) if TypeId::of::<T>() == TypeId::of::<Self>() => {
+                Ok(reify! { *a => !!! Vec<T> })
+            }
             Union::Array(a, ..) => a
                 .into_iter()
-                .map(|v| {
-                    #[cfg(not(feature = "no_closure"))]
-                    let typ = if v.is_shared() {
-                        // Avoid panics/deadlocks with shared values
-                        "<shared>"
-                    } else {
-                        v.type_name()
-                    };
-                    #[cfg(feature = "no_closure")]
-                    let typ = v.type_name();
-
-                    v.try_cast::<T>().ok_or(typ)
+                .enumerate()
+                .map(|(i, v)| {
+                    v.try_cast_raw::<T>().map_err(|v| {
+                        crate::ERR::ErrorMismatchDataType(
+                            type_name().into(),
+                            format!("{} at index {i}", v.type_name()),
+                            Position::NONE,
+                        )
+                        .into()
+                    })
                 })
                 .collect(),
             Union::Blob(b, ..) if TypeId::of::<T>() == TypeId::of::<u8>() => {
                 Ok(reify! { *b => !!! Vec<T> })
             }
-            #[cfg(not(feature = "no_closure"))]
-            Union::Shared(ref cell, ..) => {
-                match crate::func::locked_read(cell).0 {
-                    Union::Array(ref a, ..) => {
-                        a.iter()
-                            .map(|v| {
-                                #[cfg(not(feature = "no_closure"))]
-                                let typ = if v.is_shared() {
-                                    // Avoid panics/deadlocks with shared values
-                                    "<shared>"
-                                } else {
-                                    v.type_name()
-                                };
-                                #[cfg(feature = "no_closure")]
-                                let typ = v.type_name();
-
-                                v.read_lock::<T>().ok_or(typ).map(|v| v.clone())
-                            })
-                            .collect()
-                    }
-                    Union::Blob(ref b, ..) if TypeId::of::<T>() == TypeId::of::<u8>() => {
-                        Ok(reify! { b.clone() => !!! Vec<T> })
-                    }
-                    _ => Err(cell.type_name()),
-                }
-            }
-            _ => Err(self.type_name()),
+            typ => Err(crate::ERR::ErrorMismatchDataType(
+                format!("array of {}", type_name()),
+                Self(typ).type_name().into(),
+                Position::NONE,
+            )
+            .into()),
         }
     }
     /// Convert the [`Dynamic`] into a [`Blob`][crate::Blob].
@@ -2136,13 +2388,14 @@ impl FromStr for Dynamic {
 }
 #[cfg(not(feature = "no_index"))]
 impl<T: Variant + Clone> From<Vec<T>> for Dynamic {
+    /// Convert a [`Vec`] into an [`Array`][crate::Array].
+    ///
+    /// A [`Vec<Dynamic>`][Vec] is used directly as the array, without copying its elements.
     #[inline]
     fn from(value: Vec<T>) -> Self {
-        Self(Union::Array(
-            Box::new(value.into_iter().map(Self::from).collect()),
-            DEFAULT_TAG_VALUE,
-            ReadWrite,
-        ))
+        let array =
+            reify! { value => |v: crate::Array| v, || value.into_iter().map(Self::from).collect() };
+        Self::from_array(array)
     }
 }
 #[cfg(not(feature = "no_index"))]
@@ -2158,6 +2411,20 @@ impl<T: Variant + Clone> From<&[T]> for Dynamic {
 }
 #[cfg(not(feature = "no_index"))]
 impl<T: Variant + Clone> std::iter::FromIterator<T> for Dynamic {
+    /// Collect the items of an iterator into an [`Array`][crate::Array].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::Dynamic;
+    /// # #[allow(unused_imports)]
+    /// use std::iter::FromIterator;
+    ///
+    /// let x = Dynamic::from_iter((1..=3).map(|n| n * 10));
+    ///
+    /// assert!(x.is_array());
+    /// assert_eq!(x.into_typed_array::<i32>().unwrap(), [10, 20, 30]);
+    /// ```
     #[inline]
     fn from_iter<X: IntoIterator<Item = T>>(iter: X) -> Self {
         Self(Union::Array(
@@ -2217,6 +2484,23 @@ impl<K: Into<crate::Identifier>, T: Variant + Clone> From<std::collections::BTre
     }
 }
 #[cfg(not(feature = "no_object"))]
+#[cfg(feature = "map_insertion_order")]
+impl<K: Into<crate::Identifier>, T: Variant + Clone> From<crate::OrderedMap<K, T>> for Dynamic {
+    #[inline]
+    fn from(value: crate::OrderedMap<K, T>) -> Self {
+        Self(Union::Map(
+            Box::new(
+                value
+                    .into_iter()
+                    .map(|(k, v)| (k.into(), Self::from(v)))
+                    .collect(),
+            ),
+            DEFAULT_TAG_VALUE,
+            ReadWrite,
+        ))
+    }
+}
+#[cfg(not(feature = "no_object"))]
 impl<K: Into<crate::Identifier>> From<std::collections::BTreeSet<K>> for Dynamic {
     #[inline]
     fn from(value: std::collections::BTreeSet<K>) -> Self {
@@ -2260,3 +2544,17 @@ impl From<InclusiveRange> for Dynamic {
         Self::from(value)
     }
 }
+#[cfg(not(feature = "no_float"))]
+impl From<std::ops::Range<crate::FLOAT>> for Dynamic {
+    #[inline(always)]
+    fn from(value: std::ops::Range<crate::FLOAT>) -> Self {
+        Self::from(value)
+    }
+}
+#[cfg(not(feature = "no_float"))]
+impl From<std::ops::RangeInclusive<crate::FLOAT>> for Dynamic {
+    #[inline(always)]
+    fn from(value: std::ops::RangeInclusive<crate::FLOAT>) -> Self {
+        Self::from(value)
+    }
+}
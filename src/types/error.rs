@@ -1,13 +1,70 @@
 //! Module containing error definitions for the evaluation process.
 
-use crate::{Dynamic, ImmutableString, ParseErrorType, Position, INT};
+use crate::{Dynamic, ImmutableString, ParseErrorType, Position, Span, INT};
 #[cfg(feature = "no_std")]
 use core_error::Error;
 #[cfg(not(feature = "no_std"))]
 use std::error::Error;
-use std::fmt;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
+use std::{any::Any, fmt};
+
+/// Kind of limit reported by a [`LimitInfo`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum LimitKind {
+    /// Maximum number of operations.
+    Operations,
+    /// Maximum depth of function calls.
+    CallLevels,
+    /// Maximum depth of closure calls.
+    ClosureCallLevels,
+    /// Maximum length of strings, in bytes.
+    StringLength,
+    /// Maximum size of arrays and BLOB's.
+    ArraySize,
+    /// Maximum size of object maps.
+    MapSize,
+}
+
+/// Information on a limit that has been exceeded.
+///
+/// Carried by [`ErrorTooManyOperations`][EvalAltResult::ErrorTooManyOperations],
+/// [`ErrorStackOverflow`][EvalAltResult::ErrorStackOverflow] and
+/// [`ErrorDataTooLarge`][EvalAltResult::ErrorDataTooLarge].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub struct LimitInfo {
+    /// Kind of limit.
+    pub kind: LimitKind,
+    /// The configured limit.
+    pub limit: u64,
+    /// The measured value that exceeds the limit.
+    pub actual: u64,
+}
+
+impl LimitInfo {
+    /// Create a new [`LimitInfo`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new(kind: LimitKind, limit: u64, actual: u64) -> Self {
+        Self {
+            kind,
+            limit,
+            actual,
+        }
+    }
+    /// Dump the limit information into an object map.
+    #[cfg(not(feature = "no_object"))]
+    #[allow(clippy::cast_possible_wrap, clippy::cast_possible_truncation)]
+    fn dump_fields(&self, map: &mut crate::Map) {
+        map.insert("kind".into(), format!("{:?}", self.kind).into());
+        map.insert("limit".into(), (self.limit as INT).into());
+        map.insert("actual".into(), (self.actual as INT).into());
+    }
+}
 
 /// Evaluation result.
 ///
@@ -49,6 +106,9 @@ pub enum EvalAltResult {
     ErrorFunctionNotFound(String, Position),
     /// Usage of an unknown [module][crate::Module]. Wrapped value is the [module][crate::Module] name.
     ErrorModuleNotFound(String, Position),
+    /// Dynamic dispatch to a function name that is not in the allowlist.
+    /// Wrapped value is the function name.
+    ErrorDispatchNotAllowed(String, Position),
 
     /// An error has occurred inside a called function.
     /// Wrapped values are the function name, function source, and the interior error.
@@ -56,6 +116,20 @@ pub enum EvalAltResult {
     /// An error has occurred while loading a [module][crate::Module].
     /// Wrapped value are the [module][crate::Module] name and the interior error.
     ErrorInModule(String, Box<Self>, Position),
+    /// An error has occurred while evaluating an expression.
+    /// Wrapped values are the interior error and the [span][Span] of the expression.
+    ///
+    /// Only raised when [`Engine::set_error_spans`][crate::Engine::set_error_spans] is turned on.
+    /// The [position][Position] is the same as that of the interior error.
+    ErrorWithSpan(Box<Self>, Span, Position),
+    /// An error has occurred inside a script-defined function.
+    /// Wrapped values are the interior error and the call stack at the time, i.e. the names of the
+    /// functions being called (outermost first) with the [positions][Position] of the calls.
+    ///
+    /// Only raised when [`Engine::set_capture_stack_on_error`][crate::Engine::set_capture_stack_on_error]
+    /// is turned on.
+    /// The [position][Position] is the same as that of the interior error.
+    ErrorWithCallStack(Box<Self>, Box<[(ImmutableString, Position)]>, Position),
 
     /// Access to `this` that is not bound.
     ErrorUnboundThis(Position),
@@ -84,6 +158,9 @@ pub enum EvalAltResult {
 
     /// Data race detected when accessing a variable. Wrapped value is the variable name.
     ErrorDataRace(String, Position),
+    /// A shared value contains itself, either directly or via other values.
+    /// Wrapped value is the type name of the shared value.
+    ErrorCyclicData(String, Position),
     /// Calling a non-pure method on a constant.  Wrapped value is the function name.
     ErrorNonPureMethodCallOnConstant(String, Position),
     /// Assignment to a constant variable. Wrapped value is the variable name.
@@ -94,15 +171,18 @@ pub enum EvalAltResult {
     ErrorArithmetic(String, Position),
 
     /// Number of operations over maximum limit.
-    ErrorTooManyOperations(Position),
+    /// Wrapped value is the limit information.
+    ErrorTooManyOperations(LimitInfo, Position),
     /// Number of variables over maximum limit.
     ErrorTooManyVariables(Position),
     /// [Modules][crate::Module] over maximum limit.
     ErrorTooManyModules(Position),
     /// Call stack over maximum limit.
-    ErrorStackOverflow(Position),
-    /// Data value over maximum size limit. Wrapped value is the type name.
-    ErrorDataTooLarge(String, Position),
+    /// Wrapped value is the limit information.
+    ErrorStackOverflow(LimitInfo, Position),
+    /// Data value over maximum size limit.
+    /// Wrapped values are the type name and the limit information.
+    ErrorDataTooLarge(String, LimitInfo, Position),
     /// The script is prematurely terminated. Wrapped value is the termination token.
     ErrorTerminated(Dynamic, Position),
 
@@ -133,6 +213,10 @@ impl Error for EvalAltResult {}
 impl fmt::Display for EvalAltResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Self::ErrorWithSpan(err, ..) | Self::ErrorWithCallStack(err, ..) => {
+                return fmt::Display::fmt(err, f)
+            }
+
             Self::ErrorSystem(s, err) if s.is_empty() => write!(f, "{err}")?,
             Self::ErrorSystem(s, err) => write!(f, "{s}: {err}")?,
 
@@ -162,7 +246,9 @@ impl fmt::Display for EvalAltResult {
             Self::ErrorIndexNotFound(s, ..) => write!(f, "Invalid index: {s}")?,
             Self::ErrorFunctionNotFound(s, ..) => write!(f, "Function not found: {s}")?,
             Self::ErrorModuleNotFound(s, ..) => write!(f, "Module not found: {s}")?,
+            Self::ErrorDispatchNotAllowed(s, ..) => write!(f, "Dynamic dispatch not allowed: {s}")?,
             Self::ErrorDataRace(s, ..) => write!(f, "Data race detected on variable '{s}'")?,
+            Self::ErrorCyclicData(s, ..) => write!(f, "Shared {s} contains itself")?,
 
             Self::ErrorDotExpr(s, ..) if s.is_empty() => f.write_str("Malformed dot expression")?,
             Self::ErrorDotExpr(s, ..) => f.write_str(s)?,
@@ -313,6 +399,8 @@ impl EvalAltResult {
             Self::ErrorFunctionNotFound(..)
             | Self::ErrorInFunctionCall(..)
             | Self::ErrorInModule(..)
+            | Self::ErrorWithSpan(..)
+            | Self::ErrorWithCallStack(..)
             | Self::ErrorUnboundThis(..)
             | Self::ErrorMismatchDataType(..)
             | Self::ErrorArrayBounds(..)
@@ -326,7 +414,9 @@ impl EvalAltResult {
             | Self::ErrorPropertyNotFound(..)
             | Self::ErrorIndexNotFound(..)
             | Self::ErrorModuleNotFound(..)
+            | Self::ErrorDispatchNotAllowed(..)
             | Self::ErrorDataRace(..)
+            | Self::ErrorCyclicData(..)
             | Self::ErrorNonPureMethodCallOnConstant(..)
             | Self::ErrorAssignmentToConstant(..)
             | Self::ErrorMismatchOutputType(..)
@@ -373,6 +463,10 @@ impl EvalAltResult {
     #[cold]
     #[inline(never)]
     pub(crate) fn dump_fields(&self, map: &mut crate::Map) {
+        if let Self::ErrorWithSpan(err, ..) | Self::ErrorWithCallStack(err, ..) = self {
+            return err.dump_fields(map);
+        }
+
         map.insert(
             "error".into(),
             format!("{self:?}")
@@ -383,20 +477,28 @@ impl EvalAltResult {
         );
 
         match self {
-            Self::LoopBreak(..) | Self::Return(..) | Self::Exit(..) => (),
+            Self::LoopBreak(..)
+            | Self::Return(..)
+            | Self::Exit(..)
+            | Self::ErrorWithSpan(..)
+            | Self::ErrorWithCallStack(..) => (),
 
             Self::ErrorSystem(..)
             | Self::ErrorParsing(..)
             | Self::ErrorUnboundThis(..)
             | Self::ErrorFor(..)
             | Self::ErrorArithmetic(..)
-            | Self::ErrorTooManyOperations(..)
             | Self::ErrorTooManyVariables(..)
             | Self::ErrorTooManyModules(..)
-            | Self::ErrorStackOverflow(..)
             | Self::ErrorRuntime(..) => (),
 
-            Self::ErrorFunctionNotFound(f, ..) | Self::ErrorNonPureMethodCallOnConstant(f, ..) => {
+            Self::ErrorTooManyOperations(info, ..) | Self::ErrorStackOverflow(info, ..) => {
+                info.dump_fields(map);
+            }
+
+            Self::ErrorFunctionNotFound(f, ..)
+            | Self::ErrorDispatchNotAllowed(f, ..)
+            | Self::ErrorNonPureMethodCallOnConstant(f, ..) => {
                 map.insert("function".into(), f.into());
             }
             Self::ErrorInFunctionCall(f, s, ..) => {
@@ -431,8 +533,12 @@ impl EvalAltResult {
                 map.insert("property".into(), p.into());
             }
 
-            Self::ErrorIndexingType(t, ..) | Self::ErrorDataTooLarge(t, ..) => {
+            Self::ErrorIndexingType(t, ..) | Self::ErrorCyclicData(t, ..) => {
+                map.insert("type".into(), t.into());
+            }
+            Self::ErrorDataTooLarge(t, info, ..) => {
                 map.insert("type".into(), t.into());
+                info.dump_fields(map);
             }
             Self::ErrorTerminated(t, ..) => {
                 map.insert("token".into(), t.clone());
@@ -458,12 +564,140 @@ impl EvalAltResult {
     #[inline(never)]
     pub fn unwrap_inner(&self) -> &Self {
         match self {
-            Self::ErrorInFunctionCall(.., err, _) | Self::ErrorInModule(.., err, _) => {
-                err.unwrap_inner()
-            }
+            Self::ErrorInFunctionCall(.., err, _)
+            | Self::ErrorInModule(.., err, _)
+            | Self::ErrorWithSpan(err, ..)
+            | Self::ErrorWithCallStack(err, ..) => err.unwrap_inner(),
+            _ => self,
+        }
+    }
+    /// Unwrap this error and get the very base error (mutable).
+    #[cold]
+    #[inline(never)]
+    fn unwrap_inner_mut(&mut self) -> &mut Self {
+        match self {
+            Self::ErrorInFunctionCall(.., err, _)
+            | Self::ErrorInModule(.., err, _)
+            | Self::ErrorWithSpan(err, ..)
+            | Self::ErrorWithCallStack(err, ..) => err.unwrap_inner_mut(),
             _ => self,
         }
     }
+    /// Get the value thrown by a `throw` statement (i.e. the value of an
+    /// [`ErrorRuntime`][EvalAltResult::ErrorRuntime]), if any.
+    ///
+    /// Errors raised inside function calls or modules are unwrapped to get to the thrown value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// use rhai::Engine;
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let err = engine.run("fn foo() { throw 42; } foo()").unwrap_err();
+    ///
+    /// assert_eq!(err.error_value().unwrap().as_int().unwrap(), 42);
+    /// # }
+    /// ```
+    #[cold]
+    #[inline(never)]
+    #[must_use]
+    pub fn error_value(&self) -> Option<&Dynamic> {
+        match self.unwrap_inner() {
+            Self::ErrorRuntime(value, ..) => Some(value),
+            _ => None,
+        }
+    }
+    /// Take the value thrown by a `throw` statement (i.e. the value of an
+    /// [`ErrorRuntime`][EvalAltResult::ErrorRuntime]) if it is of type `T`.
+    ///
+    /// Errors raised inside function calls or modules are unwrapped to get to the thrown value.
+    ///
+    /// The value is replaced by `()` if it is taken, and left untouched otherwise.
+    /// Since the value is moved out, `T` does not need to be [`Clone`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, INT};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let mut err = engine.run("throw 42;").unwrap_err();
+    ///
+    /// assert_eq!(err.try_take_error_value::<String>(), None);
+    /// assert_eq!(err.try_take_error_value::<INT>(), Some(42));
+    /// assert!(err.error_value().unwrap().is_unit());
+    /// ```
+    #[cold]
+    #[inline(never)]
+    #[must_use]
+    pub fn try_take_error_value<T: Any>(&mut self) -> Option<T> {
+        match self.unwrap_inner_mut() {
+            Self::ErrorRuntime(value, ..) => match value.take().try_cast_result::<T>() {
+                Ok(v) => Some(v),
+                Err(v) => {
+                    *value = v;
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+    /// Get the [span][Span] of the expression where this error occurs, if any.
+    ///
+    /// Errors only carry spans when [`Engine::set_error_spans`][crate::Engine::set_error_spans]
+    /// is turned on.
+    #[cold]
+    #[inline(never)]
+    #[must_use]
+    pub const fn span(&self) -> Option<Span> {
+        match self {
+            Self::ErrorWithSpan(_, span, _) => Some(*span),
+            _ => None,
+        }
+    }
+    /// Get the call stack of script-defined functions at the time this error occurs, if any.
+    ///
+    /// Each frame holds the name of a function being called (outermost first) together with the
+    /// [position][Position] of the call.
+    ///
+    /// Errors only carry call stacks when
+    /// [`Engine::set_capture_stack_on_error`][crate::Engine::set_capture_stack_on_error] is turned on.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "no_function"))]
+    /// # {
+    /// use rhai::Engine;
+    ///
+    /// let mut engine = Engine::new();
+    /// engine.set_capture_stack_on_error(true);
+    ///
+    /// let err = engine.run("fn foo() { throw 42; } fn bar() { foo() } bar()").unwrap_err();
+    ///
+    /// let stack = err.call_stack().unwrap();
+    /// let names: Vec<_> = stack.iter().map(|(name, _)| name.as_str()).collect();
+    ///
+    /// assert_eq!(names, ["bar", "foo"]);
+    /// # }
+    /// ```
+    #[cold]
+    #[inline(never)]
+    #[must_use]
+    pub fn call_stack(&self) -> Option<&[(ImmutableString, Position)]> {
+        match self {
+            Self::ErrorWithCallStack(_, stack, _) => Some(stack),
+            Self::ErrorInFunctionCall(.., err, _)
+            | Self::ErrorInModule(.., err, _)
+            | Self::ErrorWithSpan(err, ..) => err.call_stack(),
+            _ => None,
+        }
+    }
     /// Get the [position][Position] of this error.
     #[cold]
     #[inline(never)]
@@ -476,6 +710,8 @@ impl EvalAltResult {
             | Self::ErrorFunctionNotFound(.., pos)
             | Self::ErrorInFunctionCall(.., pos)
             | Self::ErrorInModule(.., pos)
+            | Self::ErrorWithSpan(.., pos)
+            | Self::ErrorWithCallStack(.., pos)
             | Self::ErrorUnboundThis(pos)
             | Self::ErrorMismatchDataType(.., pos)
             | Self::ErrorArrayBounds(.., pos)
@@ -489,16 +725,18 @@ impl EvalAltResult {
             | Self::ErrorPropertyNotFound(.., pos)
             | Self::ErrorIndexNotFound(.., pos)
             | Self::ErrorModuleNotFound(.., pos)
+            | Self::ErrorDispatchNotAllowed(.., pos)
             | Self::ErrorDataRace(.., pos)
+            | Self::ErrorCyclicData(.., pos)
             | Self::ErrorNonPureMethodCallOnConstant(.., pos)
             | Self::ErrorAssignmentToConstant(.., pos)
             | Self::ErrorMismatchOutputType(.., pos)
             | Self::ErrorDotExpr(.., pos)
             | Self::ErrorArithmetic(.., pos)
-            | Self::ErrorTooManyOperations(pos)
+            | Self::ErrorTooManyOperations(.., pos)
             | Self::ErrorTooManyVariables(pos)
             | Self::ErrorTooManyModules(pos)
-            | Self::ErrorStackOverflow(pos)
+            | Self::ErrorStackOverflow(.., pos)
             | Self::ErrorDataTooLarge(.., pos)
             | Self::ErrorTerminated(.., pos)
             | Self::ErrorCustomSyntax(.., pos)
@@ -534,6 +772,11 @@ impl EvalAltResult {
         match self {
             Self::ErrorSystem(..) => (),
 
+            Self::ErrorWithSpan(err, .., pos) | Self::ErrorWithCallStack(err, .., pos) => {
+                err.set_position(new_position);
+                *pos = new_position;
+            }
+
             Self::ErrorParsing(.., pos)
             | Self::ErrorFunctionNotFound(.., pos)
             | Self::ErrorInFunctionCall(.., pos)
@@ -551,16 +794,18 @@ impl EvalAltResult {
             | Self::ErrorPropertyNotFound(.., pos)
             | Self::ErrorIndexNotFound(.., pos)
             | Self::ErrorModuleNotFound(.., pos)
+            | Self::ErrorDispatchNotAllowed(.., pos)
             | Self::ErrorDataRace(.., pos)
+            | Self::ErrorCyclicData(.., pos)
             | Self::ErrorNonPureMethodCallOnConstant(.., pos)
             | Self::ErrorAssignmentToConstant(.., pos)
             | Self::ErrorMismatchOutputType(.., pos)
             | Self::ErrorDotExpr(.., pos)
             | Self::ErrorArithmetic(.., pos)
-            | Self::ErrorTooManyOperations(pos)
+            | Self::ErrorTooManyOperations(.., pos)
             | Self::ErrorTooManyVariables(pos)
             | Self::ErrorTooManyModules(pos)
-            | Self::ErrorStackOverflow(pos)
+            | Self::ErrorStackOverflow(.., pos)
             | Self::ErrorDataTooLarge(.., pos)
             | Self::ErrorTerminated(.., pos)
             | Self::ErrorCustomSyntax(.., pos)
@@ -582,4 +827,32 @@ impl EvalAltResult {
         }
         self
     }
+    /// Consume the current [`EvalAltResult`] and wrap it with the [`Span`] of an expression if
+    /// it is a catchable error that does not already have a span.
+    #[cold]
+    #[inline(never)]
+    #[must_use]
+    pub(crate) fn fill_span(self: Box<Self>, span: Span) -> Box<Self> {
+        if span.is_none() || !self.is_catchable() || matches!(*self, Self::ErrorWithSpan(..)) {
+            return self;
+        }
+        let pos = self.position();
+        Self::ErrorWithSpan(self, span, pos).into()
+    }
+    /// Consume the current [`EvalAltResult`] and wrap it with a call stack if it is a catchable
+    /// error that does not already have one.
+    #[cfg(not(feature = "no_function"))]
+    #[cold]
+    #[inline(never)]
+    #[must_use]
+    pub(crate) fn fill_call_stack(
+        self: Box<Self>,
+        stack: &[(ImmutableString, Position)],
+    ) -> Box<Self> {
+        if stack.is_empty() || !self.is_catchable() || self.call_stack().is_some() {
+            return self;
+        }
+        let pos = self.position();
+        Self::ErrorWithCallStack(self, stack.into(), pos).into()
+    }
 }
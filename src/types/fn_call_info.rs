@@ -0,0 +1,94 @@
+//! Function call tracing information.
+
+use crate::Position;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::time::Duration;
+
+/// Information on a function call, passed to the tracing callback registered via
+/// [`Engine::on_fn_call`][crate::Engine::on_fn_call].
+#[derive(Debug, Clone, Hash)]
+pub struct FnCallInfo<'a> {
+    /// Name of the function.
+    name: &'a str,
+    /// Source of the function call, if any.
+    source: Option<&'a str>,
+    /// Position of the function call.
+    position: Position,
+    /// The current function call level.
+    level: usize,
+    /// `true` if the function is a native Rust function.
+    is_native: bool,
+    /// Time taken by the function call, if it has already returned.
+    elapsed: Option<Duration>,
+}
+
+impl<'a> FnCallInfo<'a> {
+    /// Create a new [`FnCallInfo`].
+    #[inline(always)]
+    #[must_use]
+    pub(crate) const fn new(
+        name: &'a str,
+        source: Option<&'a str>,
+        position: Position,
+        level: usize,
+        is_native: bool,
+        elapsed: Option<Duration>,
+    ) -> Self {
+        Self {
+            name,
+            source,
+            position,
+            level,
+            is_native,
+            elapsed,
+        }
+    }
+    /// Name of the function.
+    #[inline(always)]
+    #[must_use]
+    pub const fn name(&self) -> &str {
+        self.name
+    }
+    /// Source of the function call, if any.
+    #[inline(always)]
+    #[must_use]
+    pub const fn source(&self) -> Option<&str> {
+        self.source
+    }
+    /// Position of the function call.
+    #[inline(always)]
+    #[must_use]
+    pub const fn position(&self) -> Position {
+        self.position
+    }
+    /// The function call level (i.e. call depth) of this call, with one being a call made at
+    /// global level.
+    #[inline(always)]
+    #[must_use]
+    pub const fn level(&self) -> usize {
+        self.level
+    }
+    /// `true` if the function is a native Rust function, `false` if it is script-defined.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_native(&self) -> bool {
+        self.is_native
+    }
+    /// `true` if the function is script-defined.
+    #[inline(always)]
+    #[must_use]
+    pub const fn is_script(&self) -> bool {
+        !self.is_native
+    }
+    /// Time taken by the function call.
+    ///
+    /// This is [`None`] when the callback is called before the function runs, and
+    /// [`Some`] when it is called again after the function returns (only if registered via
+    /// `Engine::on_fn_call_timed`).
+    #[inline(always)]
+    #[must_use]
+    pub const fn elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+}
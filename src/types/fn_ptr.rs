@@ -1,7 +1,7 @@
 //! The `FnPtr` type.
 
 use crate::eval::GlobalRuntimeState;
-use crate::func::EncapsulatedEnviron;
+use crate::func::{EncapsulatedEnviron, FnAny, SendSync};
 use crate::tokenizer::{is_reserved_keyword_or_symbol, is_valid_function_name, Token};
 use crate::types::dynamic::Variant;
 use crate::{
@@ -11,7 +11,6 @@ use crate::{
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 use std::{
-    any::type_name,
     convert::{TryFrom, TryInto},
     fmt,
     hash::{Hash, Hasher},
@@ -26,8 +25,11 @@ pub struct FnPtr {
     pub(crate) name: ImmutableString,
     pub(crate) curry: Vec<Dynamic>,
     pub(crate) environ: Option<Shared<EncapsulatedEnviron>>,
+    pub(crate) native_fn: Option<Shared<FnAny>>,
     #[cfg(not(feature = "no_function"))]
     pub(crate) fn_def: Option<Shared<crate::ast::ScriptFnDef>>,
+    #[cfg(not(feature = "no_function"))]
+    pub(crate) binds_this: bool,
 }
 
 impl Hash for FnPtr {
@@ -39,9 +41,17 @@ impl Hash for FnPtr {
         // Hash the shared [`EncapsulatedEnviron`] by hashing its shared pointer.
         self.environ.as_ref().map(Shared::as_ptr).hash(state);
 
+        // Hash the native Rust closure by hashing its shared pointer.
+        self.native_fn
+            .as_ref()
+            .map(|f| Shared::as_ptr(f).cast::<()>())
+            .hash(state);
+
         // Hash the linked [`ScriptFnDef`][crate::ast::ScriptFnDef] by hashing its shared pointer.
         #[cfg(not(feature = "no_function"))]
         self.fn_def.as_ref().map(Shared::as_ptr).hash(state);
+        #[cfg(not(feature = "no_function"))]
+        self.binds_this.hash(state);
     }
 }
 
@@ -56,6 +66,10 @@ impl fmt::Debug for FnPtr {
         });
         ff.finish()?;
 
+        if self.native_fn.is_some() {
+            f.write_str(": <native>")?;
+        }
+
         #[cfg(not(feature = "no_function"))]
         if let Some(ref fn_def) = self.fn_def {
             write!(f, ": {fn_def}")?;
@@ -71,6 +85,53 @@ impl FnPtr {
     pub fn new(name: impl Into<ImmutableString>) -> RhaiResultOf<Self> {
         name.into().try_into()
     }
+    /// Create a new function pointer that carries a native Rust closure.
+    ///
+    /// The closure is called directly when the function pointer is called (e.g. via `call`), so
+    /// it does not need to be registered into the [`Engine`] and is not visible to scripts by name.
+    ///
+    /// `name` is only used for display (e.g. `to_string`) and for serialization.
+    /// It must be a valid function name.
+    ///
+    /// Curried arguments are passed to the closure before the arguments of the call.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Engine, FnPtr, Scope, INT};
+    ///
+    /// let engine = Engine::new();
+    /// let mut scope = Scope::new();
+    ///
+    /// let add = FnPtr::from_fn("add", |_, args| {
+    ///     let x = args[0].as_int().unwrap();
+    ///     let y = args[1].as_int().unwrap();
+    ///     Ok((x + y).into())
+    /// })?;
+    ///
+    /// scope.push("add", add);
+    ///
+    /// assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "call(add, 40, 2)")?, 42);
+    /// assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "call(curry(add, 1), 41)")?, 42);
+    /// assert_eq!(engine.eval_with_scope::<String>(&mut scope, "to_string(add)")?, "Fn(add)");
+    ///
+    /// // The closure is not registered as a function
+    /// assert!(engine.eval_with_scope::<INT>(&mut scope, "add(40, 2)").is_err());
+    /// # Ok::<_, Box<rhai::EvalAltResult>>(())
+    /// ```
+    #[inline]
+    pub fn from_fn(
+        name: impl Into<ImmutableString>,
+        func: impl Fn(NativeCallContext, &mut [&mut Dynamic]) -> RhaiResult + SendSync + 'static,
+    ) -> RhaiResultOf<Self> {
+        let mut fn_ptr = Self::new(name)?;
+        fn_ptr.native_fn = Some(Shared::new(
+            move |ctx: Option<NativeCallContext>, args: &mut [&mut Dynamic]| {
+                func(ctx.expect("`NativeCallContext`"), args)
+            },
+        ));
+        Ok(fn_ptr)
+    }
     /// Get the name of the function.
     #[inline(always)]
     #[must_use]
@@ -110,6 +171,12 @@ impl FnPtr {
         self.curry = values.into_iter().collect();
         self
     }
+    /// Does the function pointer carry a native Rust closure (i.e. created via [`FnPtr::from_fn`])?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_native(&self) -> bool {
+        self.native_fn.is_some()
+    }
     /// Is the function pointer curried?
     #[inline(always)]
     #[must_use]
@@ -125,6 +192,18 @@ impl FnPtr {
     pub fn is_anonymous(&self) -> bool {
         crate::func::is_anonymous_fn(&self.name)
     }
+    /// Does the function pointer refer to a closure that binds `this`?
+    ///
+    /// Such a closure refers to `this` in its body.  When it is called via `call` without an
+    /// explicit object, it is bound to the `this` of the calling function.
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline(always)]
+    #[must_use]
+    pub const fn binds_this(&self) -> bool {
+        self.binds_this
+    }
     /// Call the function pointer with curried arguments (if any).
     /// The function may be script-defined (not available under `no_function`) or native Rust.
     ///
@@ -174,17 +253,94 @@ impl FnPtr {
 
         let ctx = (engine, self.fn_name(), None, &*global, Position::NONE).into();
 
-        self.call_raw(&ctx, None, arg_values).and_then(|result| {
-            result.try_cast_raw().map_err(|r| {
-                let result_type = engine.map_type_name(r.type_name());
-                let cast_type = match type_name::<T>() {
-                    typ if typ.contains("::") => engine.map_type_name(typ),
-                    typ => typ,
-                };
-                ERR::ErrorMismatchOutputType(cast_type.into(), result_type.into(), Position::NONE)
-                    .into()
-            })
-        })
+        self.call_raw(&ctx, None, arg_values)
+            .and_then(|result| engine.cast_dynamic(result))
+    }
+    /// Bind the functions defined in an [`AST`] to the function pointer as its encapsulated
+    /// environment, so that it can be called via [`call_with_environ`][FnPtr::call_with_environ]
+    /// without the [`AST`].
+    ///
+    /// If the function pointer is not yet linked to a script-defined function, it is linked to the
+    /// first function with the same name in the [`AST`] (if any).
+    ///
+    /// Not available under `no_function`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> Result<(), Box<rhai::EvalAltResult>> {
+    /// use rhai::{Engine, FnPtr};
+    ///
+    /// let engine = Engine::new();
+    ///
+    /// let ast = engine.compile("fn foo(x) { bar(x) * 2 } fn bar(x) { x + 1 }")?;
+    ///
+    /// let mut fn_ptr = FnPtr::new("foo")?;
+    /// fn_ptr.bind_environ(&ast);
+    ///
+    /// drop(ast);
+    ///
+    /// let result: i64 = fn_ptr.call_with_environ(&engine, ( 20_i64, ))?;
+    ///
+    /// assert_eq!(result, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    pub fn bind_environ(&mut self, ast: &AST) -> &mut Self {
+        if self.fn_def.is_none() {
+            self.fn_def = ast.iter_fn_def().find(|f| f.name == self.name).cloned();
+        }
+
+        self.environ = Some(Shared::new(EncapsulatedEnviron {
+            lib: ast.shared_lib().clone(),
+            #[cfg(not(feature = "no_module"))]
+            imports: Vec::new(),
+            #[cfg(not(feature = "no_module"))]
+            constants: None,
+        }));
+
+        self
+    }
+    /// Call the function pointer with curried arguments (if any), using its encapsulated
+    /// environment (if any) instead of an [`AST`].
+    /// The function may be script-defined or native Rust.
+    ///
+    /// The environment is bound via [`bind_environ`][FnPtr::bind_environ] or
+    /// [`Engine::collect_fn_ptrs`].
+    ///
+    /// Not available under `no_function`.
+    #[cfg(not(feature = "no_function"))]
+    #[inline]
+    pub fn call_with_environ<T: Variant + Clone>(
+        &self,
+        engine: &Engine,
+        args: impl FuncArgs,
+    ) -> RhaiResultOf<T> {
+        let mut arg_values = StaticVec::new_const();
+        args.parse(&mut arg_values);
+
+        let global = &mut GlobalRuntimeState::new(engine);
+
+        if let Some(ref environ) = self.environ {
+            global.lib.push(environ.lib.clone());
+
+            #[cfg(not(feature = "no_module"))]
+            {
+                environ
+                    .imports
+                    .iter()
+                    .cloned()
+                    .for_each(|(n, m)| global.push_import(n, m));
+                global.constants = environ.constants.clone();
+            }
+        }
+
+        let ctx = (engine, self.fn_name(), None, &*global, Position::NONE).into();
+
+        self.call_raw(&ctx, None, arg_values)
+            .and_then(|result| engine.cast_dynamic(result))
     }
     /// Call the function pointer with curried arguments (if any).
     /// The function may be script-defined (not available under `no_function`) or native Rust.
@@ -201,17 +357,8 @@ impl FnPtr {
         let mut arg_values = StaticVec::new_const();
         args.parse(&mut arg_values);
 
-        self.call_raw(context, None, arg_values).and_then(|result| {
-            result.try_cast_raw().map_err(|r| {
-                let result_type = context.engine().map_type_name(r.type_name());
-                let cast_type = match type_name::<T>() {
-                    typ if typ.contains("::") => context.engine().map_type_name(typ),
-                    typ => typ,
-                };
-                ERR::ErrorMismatchOutputType(cast_type.into(), result_type.into(), Position::NONE)
-                    .into()
-            })
-        })
+        self.call_raw(context, None, arg_values)
+            .and_then(|result| context.engine().cast_dynamic(result))
     }
     /// Call the function pointer with curried arguments (if any).
     /// The function may be script-defined (not available under `no_function`) or native Rust.
@@ -252,16 +399,33 @@ impl FnPtr {
         let args = &mut StaticVec::with_capacity(arg_values.len() + 1);
         args.extend(arg_values.iter_mut());
 
+        let is_method = this_ptr.is_some();
+
+        // Native Rust closure?
+        if let Some(func) = self.native_fn.as_deref() {
+            if let Some(obj) = this_ptr {
+                args.insert(0, obj);
+            }
+
+            let global = &mut context.global_runtime_state().clone();
+
+            return context.engine().call_native_fn_ptr(
+                global,
+                self.fn_name(),
+                func,
+                args,
+                context.position(),
+            );
+        }
+
         // Linked to scripted function?
         #[cfg(not(feature = "no_function"))]
         match self.fn_def {
             Some(ref fn_def) if fn_def.params.len() == args.len() => {
                 let global = &mut context.global_runtime_state().clone();
-                global.level += 1;
-
                 let caches = &mut crate::eval::Caches::new();
 
-                return context.engine().call_script_fn(
+                return context.engine().call_script_fn_ptr(
                     global,
                     caches,
                     &mut crate::Scope::new(),
@@ -269,15 +433,12 @@ impl FnPtr {
                     self.environ.as_deref(),
                     fn_def,
                     args,
-                    true,
                     context.position(),
                 );
             }
             _ => (),
         }
 
-        let is_method = this_ptr.is_some();
-
         if let Some(obj) = this_ptr {
             args.insert(0, obj);
         }
@@ -472,8 +633,11 @@ impl TryFrom<ImmutableString> for FnPtr {
                 name: value,
                 curry: Vec::new(),
                 environ: None,
+                native_fn: None,
                 #[cfg(not(feature = "no_function"))]
                 fn_def: None,
+                #[cfg(not(feature = "no_function"))]
+                binds_this: false,
             })
         } else if is_reserved_keyword_or_symbol(&value).0
             || Token::lookup_symbol_from_syntax(&value).is_some()
@@ -495,7 +659,9 @@ impl<T: Into<Shared<crate::ast::ScriptFnDef>>> From<T> for FnPtr {
             name: fn_def.name.clone(),
             curry: Vec::new(),
             environ: None,
+            native_fn: None,
             fn_def: Some(fn_def),
+            binds_this: false,
         }
     }
 }
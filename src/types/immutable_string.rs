@@ -749,6 +749,23 @@ impl ImmutableString {
     /// Then return a mutable reference to the [`SmartString`].
     ///
     /// If there are other references to the same string, a cloned copy is used.
+    /// Otherwise the string is modified in place without any allocation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::ImmutableString;
+    ///
+    /// let mut s: ImmutableString = "hello".into();
+    /// let s2 = s.clone();
+    /// assert_eq!(s.strong_count(), 2);
+    ///
+    /// s.make_mut().push_str(", world!");
+    ///
+    /// assert_eq!(s, "hello, world!");
+    /// assert_eq!(s2, "hello");
+    /// assert_eq!(s.strong_count(), 1);
+    /// ```
     #[inline(always)]
     #[must_use]
     pub fn make_mut(&mut self) -> &mut SmartString {
@@ -15,7 +15,7 @@ use std::{
     ops::AddAssign,
 };
 
-/// Maximum number of strings interned.
+/// Default maximum number of strings interned.
 pub const MAX_INTERNED_STRINGS: usize = 1024;
 
 /// Maximum length of strings interned.
@@ -29,6 +29,20 @@ pub struct StringsInterner {
     cache: StraightHashMap<ImmutableString>,
     /// Bloom filter to avoid caching "one-hit wonders".
     bloom_filter: BloomFilterU64,
+    /// Maximum number of strings interned.
+    max: usize,
+}
+
+/// Statistics of a [`StringsInterner`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+#[non_exhaustive]
+pub struct StringsInternerStats {
+    /// Number of strings interned.
+    pub count: usize,
+    /// Estimated number of bytes held by the interned strings.
+    pub bytes: usize,
+    /// Maximum number of strings interned.
+    pub max: usize,
 }
 
 impl Default for StringsInterner {
@@ -52,9 +66,37 @@ impl StringsInterner {
     #[inline(always)]
     #[must_use]
     pub fn new() -> Self {
+        Self::new_with_max(MAX_INTERNED_STRINGS)
+    }
+
+    /// Create a new [`StringsInterner`] with a maximum number of strings interned.
+    #[inline(always)]
+    #[must_use]
+    pub fn new_with_max(max: usize) -> Self {
         Self {
             cache: <_>::default(),
             bloom_filter: BloomFilterU64::new(),
+            max,
+        }
+    }
+
+    /// Get the maximum number of strings interned.
+    #[inline(always)]
+    #[must_use]
+    pub const fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Set the maximum number of strings interned.
+    ///
+    /// If there are more strings interned than the new maximum, the excess strings are evicted.
+    /// Strings already handed out are not affected.
+    #[inline]
+    pub fn set_max(&mut self, max: usize) {
+        self.max = max;
+
+        if self.cache.len() > max {
+            self.evict(max, None);
         }
     }
 
@@ -87,7 +129,7 @@ impl StringsInterner {
         }
 
         if self.cache.is_empty() {
-            self.cache.reserve(MAX_INTERNED_STRINGS);
+            self.cache.reserve(self.max.min(MAX_INTERNED_STRINGS));
         }
 
         let result = match self.cache.entry(hash) {
@@ -101,33 +143,42 @@ impl StringsInterner {
         result
     }
 
-    /// If the interner is over capacity, remove the longest entry that has the lowest count
+    /// If the interner is over capacity, remove the longest entries that have the lowest counts.
     #[inline]
     fn throttle_cache(&mut self, skip_hash: u64) {
-        if self.cache.len() <= MAX_INTERNED_STRINGS {
+        if self.cache.len() <= self.max {
             return;
         }
 
         // Leave some buffer to grow when shrinking the cache.
         // We leave at least two entries, one for the empty string, and one for the string
         // that has just been inserted.
-        while self.cache.len() > MAX_INTERNED_STRINGS - 3 {
+        self.evict(self.max.saturating_sub(3).max(1), Some(skip_hash));
+    }
+
+    /// Remove the longest entries that have the lowest counts until only `target` entries remain,
+    /// optionally keeping one particular entry.
+    fn evict(&mut self, target: usize, skip_hash: Option<u64>) {
+        while self.cache.len() > target {
             let mut max_len = 0;
             let mut min_count = usize::MAX;
-            let mut index = 0;
+            let mut index = None;
 
             for (&k, v) in &self.cache {
-                if k != skip_hash
+                if Some(k) != skip_hash
                     && (v.strong_count() < min_count
                         || (v.strong_count() == min_count && v.len() > max_len))
                 {
                     max_len = v.len();
                     min_count = v.strong_count();
-                    index = k;
+                    index = Some(k);
                 }
             }
 
-            self.cache.remove(&index);
+            match index {
+                Some(k) => self.cache.remove(&k),
+                None => break,
+            };
         }
     }
 
@@ -148,10 +199,23 @@ impl StringsInterner {
     }
 
     /// Clear all interned strings.
+    ///
+    /// Strings already handed out are not affected.
     #[inline(always)]
-    #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.cache.clear();
+        self.bloom_filter.clear();
+    }
+
+    /// Get statistics of the interned strings.
+    #[inline]
+    #[must_use]
+    pub fn stats(&self) -> StringsInternerStats {
+        StringsInternerStats {
+            count: self.cache.len(),
+            bytes: self.cache.values().map(|s| s.len()).sum(),
+            max: self.max,
+        }
     }
 }
 
@@ -1,33 +1,45 @@
 //! Module defining Rhai data types.
 
+pub mod blob_view;
 pub mod bloom_filter;
 pub mod custom_types;
 pub mod dynamic;
 pub mod error;
 pub mod float;
+pub mod fn_call_info;
 pub mod fn_ptr;
 pub mod immutable_string;
 pub mod interner;
+pub mod ordered_map;
 pub mod parse_error;
+pub mod parse_warning;
 pub mod position;
 pub mod position_none;
+#[cfg(feature = "regex")]
+pub mod regex_cache;
 pub mod scope;
 pub mod var_def;
 pub mod variant;
 
+#[cfg(not(feature = "no_index"))]
+pub use blob_view::{BlobView, BlobViewIter};
 pub use bloom_filter::BloomFilterU64;
 pub use custom_types::{CustomTypeInfo, CustomTypesCollection};
 pub use dynamic::Dynamic;
 #[cfg(not(feature = "no_time"))]
 pub use dynamic::Instant;
-pub use error::EvalAltResult;
+pub use error::{EvalAltResult, LimitInfo, LimitKind};
 #[cfg(not(feature = "no_float"))]
 pub use float::FloatWrapper;
+pub use fn_call_info::FnCallInfo;
 pub use fn_ptr::FnPtr;
 pub use immutable_string::ImmutableString;
-pub use interner::StringsInterner;
+pub use interner::{StringsInterner, StringsInternerStats};
 pub use parse_error::{LexError, ParseError, ParseErrorType};
-pub use var_def::VarDefInfo;
+pub use parse_warning::{ParseWarning, ParseWarningKind};
+#[cfg(feature = "regex")]
+pub use regex_cache::RegexCache;
+pub use var_def::{VarChainSegment, VarDefInfo, VarWrite};
 
 #[cfg(not(feature = "no_position"))]
 pub use position::{Position, Span};
@@ -0,0 +1,559 @@
+//! An insertion-ordered map type.
+#![cfg(feature = "map_insertion_order")]
+
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+use std::{
+    borrow::Borrow,
+    collections::BTreeMap,
+    fmt,
+    hash::{Hash, Hasher},
+    iter::FromIterator,
+    ops::Index,
+    slice, vec,
+};
+
+/// A map that keeps its entries in insertion order.
+///
+/// The API mirrors [`BTreeMap`] so that it can be used as a drop-in replacement for the
+/// [`Map`][crate::Map] type under the `map_insertion_order` feature.
+///
+/// Entries are stored in a [`Vec`] in the order they are first inserted, with a [`BTreeMap`]
+/// indexing each key to its position in the list.  Replacing the value of an existing key keeps
+/// its position.  Removing an entry is _O(n)_ because the following entries are shifted up.
+///
+/// Equality and hashing do not depend on the order of entries.
+///
+/// # Example
+///
+/// ```
+/// use rhai::OrderedMap;
+///
+/// let mut map = OrderedMap::new();
+///
+/// map.insert("z", 1);
+/// map.insert("a", 2);
+/// map.insert("m", 3);
+/// map.insert("z", 42);
+///
+/// assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["z", "a", "m"]);
+/// assert_eq!(map["z"], 42);
+///
+/// map.remove("a");
+///
+/// assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["z", "m"]);
+/// ```
+#[derive(Clone)]
+pub struct OrderedMap<K, V> {
+    /// Entries, in insertion order.
+    entries: Vec<(K, V)>,
+    /// Index of each key into `entries`.
+    index: BTreeMap<K, usize>,
+}
+
+impl<K, V> Default for OrderedMap<K, V> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> OrderedMap<K, V> {
+    /// Create a new empty [`OrderedMap`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            index: BTreeMap::new(),
+        }
+    }
+    /// Number of entries in the [`OrderedMap`].
+    #[inline(always)]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Is the [`OrderedMap`] empty?
+    #[inline(always)]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Remove all entries.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.index.clear();
+    }
+    /// Iterate through all entries, in insertion order.
+    #[inline(always)]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter(self.entries.iter())
+    }
+    /// Iterate through all entries mutably, in insertion order.
+    #[inline(always)]
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut(self.entries.iter_mut())
+    }
+    /// Iterate through all keys, in insertion order.
+    #[inline(always)]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys(self.entries.iter())
+    }
+    /// Iterate through all values, in insertion order.
+    #[inline(always)]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values(self.entries.iter())
+    }
+    /// Iterate through all values mutably, in insertion order.
+    #[inline(always)]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut(self.entries.iter_mut())
+    }
+    /// Consume the [`OrderedMap`], returning all keys in insertion order.
+    #[inline]
+    pub fn into_keys(self) -> impl Iterator<Item = K> {
+        self.entries.into_iter().map(|(k, _)| k)
+    }
+    /// Consume the [`OrderedMap`], returning all values in insertion order.
+    #[inline]
+    pub fn into_values(self) -> impl Iterator<Item = V> {
+        self.entries.into_iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: Ord, V> OrderedMap<K, V> {
+    /// Get a reference to the value of a key.
+    #[inline]
+    #[must_use]
+    pub fn get<Q: ?Sized + Ord>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.index.get(key).map(|&i| &self.entries[i].1)
+    }
+    /// Get a reference to the key and value of a key.
+    #[inline]
+    #[must_use]
+    pub fn get_key_value<Q: ?Sized + Ord>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        self.index.get(key).map(|&i| {
+            let (k, v) = &self.entries[i];
+            (k, v)
+        })
+    }
+    /// Get a mutable reference to the value of a key.
+    #[inline]
+    #[must_use]
+    pub fn get_mut<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        match self.index.get(key) {
+            Some(&i) => Some(&mut self.entries[i].1),
+            None => None,
+        }
+    }
+    /// Does the [`OrderedMap`] contain a key?
+    #[inline(always)]
+    #[must_use]
+    pub fn contains_key<Q: ?Sized + Ord>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.index.contains_key(key)
+    }
+    /// Remove an entry, returning its value.
+    ///
+    /// The order of the other entries is kept.
+    #[inline(always)]
+    pub fn remove<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+    /// Remove an entry, returning its key and value.
+    ///
+    /// The order of the other entries is kept.
+    pub fn remove_entry<Q: ?Sized + Ord>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.index.remove(key)?;
+
+        self.index
+            .values_mut()
+            .filter(|i| **i > index)
+            .for_each(|i| *i -= 1);
+
+        Some(self.entries.remove(index))
+    }
+    /// Keep only the entries that satisfy a predicate.
+    ///
+    /// The order of the remaining entries is kept.
+    pub fn retain(&mut self, mut f: impl FnMut(&K, &mut V) -> bool) {
+        let len = self.entries.len();
+
+        self.entries.retain_mut(|(k, v)| f(k, v));
+
+        if self.entries.len() != len {
+            self.reindex();
+        }
+    }
+    /// Rebuild the key index from the list of entries.
+    fn reindex(&mut self) {
+        let index = &mut self.index;
+
+        self.entries
+            .iter()
+            .enumerate()
+            .for_each(|(i, (k, _))| *index.get_mut(k).unwrap() = i);
+
+        let entries = &self.entries;
+        index.retain(|k, i| entries.get(*i).map_or(false, |(key, _)| key == k));
+    }
+}
+
+impl<K: Ord + Clone, V> OrderedMap<K, V> {
+    /// Insert an entry, returning the previous value of the key, if any.
+    ///
+    /// A new key is added to the end.  An existing key keeps its position.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.index.get(&key) {
+            Some(&i) => Some(std::mem::replace(&mut self.entries[i].1, value)),
+            None => {
+                self.index.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+    /// Get the entry of a key for in-place manipulation.
+    #[inline]
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        match self.index.get(&key) {
+            Some(&index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
+        }
+    }
+    /// Move all entries from another [`OrderedMap`] into this one, leaving it empty.
+    #[inline]
+    pub fn append(&mut self, other: &mut Self) {
+        self.extend(std::mem::take(other));
+    }
+}
+
+impl<K: Ord, V: PartialEq> PartialEq for OrderedMap<K, V> {
+    /// Two [`OrderedMap`]'s are equal if they contain the same entries, regardless of order.
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Ord, V: Eq> Eq for OrderedMap<K, V> {}
+
+impl<K: Hash, V: Hash> Hash for OrderedMap<K, V> {
+    /// Entries are hashed in key order so that equal [`OrderedMap`]'s hash the same.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.entries.len());
+
+        self.index.iter().for_each(|(k, &i)| {
+            k.hash(state);
+            self.entries[i].1.hash(state);
+        });
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OrderedMap<K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord + Borrow<Q>, Q: ?Sized + Ord, V> Index<&Q> for OrderedMap<K, V> {
+    type Output = V;
+
+    #[inline]
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found")
+    }
+}
+
+impl<K: Ord + Clone, V> Extend<(K, V)> for OrderedMap<K, V> {
+    #[inline]
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(K, V)> for OrderedMap<K, V> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Ord + Clone, V, const N: usize> From<[(K, V); N]> for OrderedMap<K, V> {
+    #[inline(always)]
+    fn from(value: [(K, V); N]) -> Self {
+        IntoIterator::into_iter(value).collect()
+    }
+}
+
+impl<K, V> IntoIterator for OrderedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = vec::IntoIter<(K, V)>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a OrderedMap<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a mut OrderedMap<K, V> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    #[inline(always)]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Implement an iterator over the list of entries of an [`OrderedMap`].
+macro_rules! impl_iter {
+    ($(#[$attr:meta])* $name:ident, $inner:ident, $item:ty, |$x:pat_param| $map:expr) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub struct $name<'a, K, V>(slice::$inner<'a, (K, V)>);
+
+        impl<'a, K, V> Iterator for $name<'a, K, V> {
+            type Item = $item;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next().map(|$x| $map)
+            }
+            #[inline(always)]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+
+        impl<'a, K, V> DoubleEndedIterator for $name<'a, K, V> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.0.next_back().map(|$x| $map)
+            }
+        }
+
+        impl<'a, K, V> ExactSizeIterator for $name<'a, K, V> {}
+    };
+}
+
+impl_iter!(
+    /// An iterator over the entries of an [`OrderedMap`], in insertion order.
+    Iter, Iter, (&'a K, &'a V), |(k, v)| (k, v)
+);
+impl_iter!(
+    /// A mutable iterator over the entries of an [`OrderedMap`], in insertion order.
+    IterMut, IterMut, (&'a K, &'a mut V), |(k, v)| (&*k, v)
+);
+impl_iter!(
+    /// An iterator over the keys of an [`OrderedMap`], in insertion order.
+    Keys, Iter, &'a K, |(k, _)| k
+);
+impl_iter!(
+    /// An iterator over the values of an [`OrderedMap`], in insertion order.
+    Values, Iter, &'a V, |(_, v)| v
+);
+impl_iter!(
+    /// A mutable iterator over the values of an [`OrderedMap`], in insertion order.
+    ValuesMut, IterMut, &'a mut V, |(_, v)| v
+);
+
+/// An entry in an [`OrderedMap`], which may be occupied or vacant.
+pub enum Entry<'a, K, V> {
+    /// An existing entry.
+    Occupied(OccupiedEntry<'a, K, V>),
+    /// A missing entry.
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+impl<'a, K: Ord + Clone, V> Entry<'a, K, V> {
+    /// Get the key of this entry.
+    #[inline]
+    #[must_use]
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key(),
+        }
+    }
+    /// Insert a value if the entry is vacant, then return a mutable reference to the value.
+    #[inline]
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+    /// Insert the result of a function if the entry is vacant, then return a mutable reference
+    /// to the value.
+    #[inline]
+    pub fn or_insert_with(self, default: impl FnOnce() -> V) -> &'a mut V {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default()),
+        }
+    }
+    /// Insert the default value if the entry is vacant, then return a mutable reference to the value.
+    #[inline(always)]
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+    /// Modify the value if the entry is occupied.
+    #[inline]
+    pub fn and_modify(mut self, f: impl FnOnce(&mut V)) -> Self {
+        if let Self::Occupied(ref mut entry) = self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// An existing entry in an [`OrderedMap`].
+pub struct OccupiedEntry<'a, K, V> {
+    /// The [`OrderedMap`].
+    map: &'a mut OrderedMap<K, V>,
+    /// Index of the entry.
+    index: usize,
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    /// Get the key of this entry.
+    #[inline(always)]
+    #[must_use]
+    pub fn key(&self) -> &K {
+        &self.map.entries[self.index].0
+    }
+    /// Get a reference to the value of this entry.
+    #[inline(always)]
+    #[must_use]
+    pub fn get(&self) -> &V {
+        &self.map.entries[self.index].1
+    }
+    /// Get a mutable reference to the value of this entry.
+    #[inline(always)]
+    #[must_use]
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.entries[self.index].1
+    }
+    /// Convert into a mutable reference to the value of this entry.
+    #[inline(always)]
+    #[must_use]
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.entries[self.index].1
+    }
+    /// Replace the value of this entry, returning the old value.
+    #[inline(always)]
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+/// A missing entry in an [`OrderedMap`].
+pub struct VacantEntry<'a, K, V> {
+    /// The [`OrderedMap`].
+    map: &'a mut OrderedMap<K, V>,
+    /// The key.
+    key: K,
+}
+
+impl<'a, K: Ord + Clone, V> VacantEntry<'a, K, V> {
+    /// Get the key of this entry.
+    #[inline(always)]
+    #[must_use]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+    /// Take ownership of the key.
+    #[inline(always)]
+    #[must_use]
+    pub fn into_key(self) -> K {
+        self.key
+    }
+    /// Insert a value at the end of the [`OrderedMap`], returning a mutable reference to it.
+    #[inline]
+    pub fn insert(self, value: V) -> &'a mut V {
+        let index = self.map.entries.len();
+        self.map.index.insert(self.key.clone(), index);
+        self.map.entries.push((self.key, value));
+        &mut self.map.entries[index].1
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: serde::Serialize, V: serde::Serialize> serde::Serialize for OrderedMap<K, V> {
+    #[inline]
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for OrderedMap<K, V>
+where
+    K: serde::Deserialize<'de> + Ord + Clone,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderedMapVisitor<K, V>(std::marker::PhantomData<(K, V)>);
+
+        impl<'de, K, V> serde::de::Visitor<'de> for OrderedMapVisitor<K, V>
+        where
+            K: serde::Deserialize<'de> + Ord + Clone,
+            V: serde::Deserialize<'de>,
+        {
+            type Value = OrderedMap<K, V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut access: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut map = OrderedMap::new();
+
+                while let Some((k, v)) = access.next_entry()? {
+                    map.insert(k, v);
+                }
+
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(OrderedMapVisitor(std::marker::PhantomData))
+    }
+}
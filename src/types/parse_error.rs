@@ -31,6 +31,16 @@ pub enum LexError {
     MalformedIdentifier(String),
     /// Bad symbol encountered when tokenizing the script text.
     ImproperSymbol(String, String),
+    /// An identifier is longer than the maximum allowed length.
+    IdentifierTooLong(usize),
+    /// A string literal is longer than the maximum allowed length for string literals.
+    StringLiteralTooLong(usize),
+    /// Interpolated strings are nested deeper than the maximum allowed depth.
+    InterpolationTooDeep(usize),
+    /// The script contains more than the maximum allowed number of tokens.
+    TooManyTokens(usize),
+    /// A line in the script is longer than the maximum allowed length.
+    LineTooLong(usize),
 }
 
 impl Error for LexError {}
@@ -49,6 +59,15 @@ impl fmt::Display for LexError {
                 write!(f, "Invalid symbol encountered: '{s}'")
             }
             Self::ImproperSymbol(.., d) => f.write_str(d),
+            Self::IdentifierTooLong(max) => write!(f, "Identifier is too long (max {max})"),
+            Self::StringLiteralTooLong(max) => {
+                write!(f, "String literal is too long (max {max})")
+            }
+            Self::InterpolationTooDeep(max) => {
+                write!(f, "Interpolated strings are nested too deeply (max {max})")
+            }
+            Self::TooManyTokens(max) => write!(f, "Script has too many tokens (max {max})"),
+            Self::LineTooLong(max) => write!(f, "Line is too long (max {max})"),
         }
     }
 }
@@ -175,12 +194,28 @@ pub enum ParseErrorType {
     ///
     /// Only appears when strict variables mode is enabled.
     ModuleUndefined(String),
+    /// A function is not found.
+    /// Wrapped values are the name of the function, the number of arguments in the call, and the
+    /// name of a similar function, if any.
+    ///
+    /// Only appears when strict functions mode is enabled.
+    FnUndefined(String, usize, Option<String>),
     /// Expression exceeding the maximum levels of complexity.
     ExprTooDeep,
     /// Literal exceeding the maximum size. Wrapped values are the data type name and the maximum size.
     LiteralTooLarge(String, usize),
     /// Break statement not inside a loop.
     LoopBreak,
+    /// A custom syntax that can only be used as a statement is used inside an expression.
+    /// Wrapped value is the first symbol of the custom syntax.
+    CustomSyntaxStatementOnly(String),
+    /// A custom syntax that can only be used inside an expression is used as a statement.
+    /// Wrapped value is the first symbol of the custom syntax.
+    CustomSyntaxExpressionOnly(String),
+    /// A syntax feature is not available under the [language version][crate::LanguageVersion]
+    /// of the [`Engine`][crate::Engine].
+    /// Wrapped values are the name of the feature and the minimum language version required.
+    UnsupportedLanguageFeature(String, String),
 }
 
 impl fmt::Display for ParseErrorType {
@@ -217,6 +252,18 @@ impl fmt::Display for ParseErrorType {
             Self::VariableExists(s) => write!(f, "Variable already defined: {s}"),
             Self::VariableUndefined(s) => write!(f, "Undefined variable: {s}"),
             Self::ModuleUndefined(s) => write!(f, "Undefined module: {s}"),
+            Self::FnUndefined(s, n, hint) => {
+                write!(f, "Undefined function: {s} with ")?;
+                match n {
+                    0 => f.write_str("no arguments")?,
+                    1 => f.write_str("1 argument")?,
+                    _ => write!(f, "{n} arguments")?,
+                }
+                match hint {
+                    Some(hint) => write!(f, " (did you mean {hint}?)"),
+                    None => Ok(()),
+                }
+            }
 
             Self::MismatchedType(r, a) => write!(f, "Expecting {r}, not {a}"),
             Self::ExprExpected(s) => write!(f, "Expecting {s} expression"),
@@ -247,6 +294,9 @@ impl fmt::Display for ParseErrorType {
             Self::WrongExport => f.write_str("Export statement can only appear at global level"),
             Self::ExprTooDeep => f.write_str("Expression exceeds maximum complexity"),
             Self::LoopBreak => f.write_str("Break statement should only be used inside a loop"),
+            Self::CustomSyntaxStatementOnly(s) => write!(f, "'{s}' can only be used as a statement"),
+            Self::CustomSyntaxExpressionOnly(s) => write!(f, "'{s}' can only be used inside an expression"),
+            Self::UnsupportedLanguageFeature(s, v) => write!(f, "{s} requires language version {v} or above"),
 
             #[allow(deprecated)]
             Self::DuplicatedSwitchCase => f.write_str("Duplicated switch case"),
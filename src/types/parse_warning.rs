@@ -0,0 +1,77 @@
+//! Module containing warning definitions for the parsing process.
+
+use crate::Position;
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Kind of a [`ParseWarning`].
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
+#[non_exhaustive]
+pub enum ParseWarningKind {
+    /// A local variable or constant is declared but never read.
+    UnusedVariable,
+    /// A parameter of a script-defined function is never read.
+    UnusedParameter,
+    /// A statement can never be reached because it follows `return`, `throw`, `break` or `continue`.
+    UnreachableCode,
+    /// The condition of an `if`, `while` or `do` statement is a constant.
+    ConstantCondition,
+    /// A variable declaration shadows an existing variable.
+    ShadowedVariable,
+    /// A parameter of a script-defined function shadows a global variable.
+    ShadowedParameter,
+}
+
+impl fmt::Display for ParseWarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::UnusedVariable => "unused variable",
+            Self::UnusedParameter => "unused parameter",
+            Self::UnreachableCode => "unreachable code",
+            Self::ConstantCondition => "constant condition",
+            Self::ShadowedVariable => "shadowed variable",
+            Self::ShadowedParameter => "shadowed parameter",
+        })
+    }
+}
+
+/// Warning on a possible mistake in a script, found by
+/// [`Engine::compile_with_warnings`][crate::Engine::compile_with_warnings].
+///
+/// Warnings do not stop a script from being compiled.
+#[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[must_use]
+pub struct ParseWarning {
+    /// Kind of the warning.
+    pub kind: ParseWarningKind,
+    /// Location of the warning.
+    pub position: Position,
+    /// Description of the warning.
+    pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+
+        // Do not write any position if None
+        if !self.position.is_none() {
+            write!(f, " ({})", self.position)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ParseWarning {
+    /// Create a new [`ParseWarning`].
+    #[inline(always)]
+    pub fn new(kind: ParseWarningKind, position: Position, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            position,
+            message: message.into(),
+        }
+    }
+}
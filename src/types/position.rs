@@ -189,8 +189,7 @@ impl AddAssign for Position {
     }
 }
 
-/// _(internals)_ A span consisting of a starting and an ending [positions][Position].
-/// Exported under the `internals` feature only.
+/// A span consisting of a starting and an ending [positions][Position].
 #[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy)]
 pub struct Span {
     /// Starting [position][Position].
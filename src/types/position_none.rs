@@ -104,8 +104,7 @@ impl AddAssign for Position {
     fn add_assign(&mut self, rhs: Self) {}
 }
 
-/// _(internals)_ A span consisting of a starting and an ending [positions][Position].
-/// Exported under the `internals` feature only.
+/// A span consisting of a starting and an ending [positions][Position].
 #[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Default)]
 pub struct Span;
 
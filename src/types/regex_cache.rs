@@ -0,0 +1,57 @@
+//! A cache for compiled regular expressions.
+
+use regex_lite::{Error, Regex};
+use std::fmt;
+#[cfg(feature = "no_std")]
+use std::prelude::v1::*;
+
+/// Maximum number of compiled regular expressions cached.
+pub const MAX_CACHED_REGEXES: usize = 16;
+
+/// A small least-recently-used cache of compiled regular expressions, keyed by pattern.
+#[derive(Clone, Default)]
+pub struct RegexCache {
+    /// Cached patterns, with the most recently used at the end.
+    cache: Vec<Regex>,
+}
+
+impl fmt::Debug for RegexCache {
+    #[cold]
+    #[inline(never)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.cache.iter().map(Regex::as_str))
+            .finish()
+    }
+}
+
+impl RegexCache {
+    /// Create a new [`RegexCache`].
+    #[inline(always)]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { cache: Vec::new() }
+    }
+
+    /// Get a compiled regular expression for a pattern, compiling and caching it if necessary.
+    ///
+    /// # Errors
+    ///
+    /// Returns the compilation error if the pattern is not a valid regular expression.
+    pub fn get(&mut self, pattern: &str) -> Result<Regex, Error> {
+        if let Some(index) = self.cache.iter().position(|re| re.as_str() == pattern) {
+            let re = self.cache.remove(index);
+            self.cache.push(re.clone());
+            return Ok(re);
+        }
+
+        let re = Regex::new(pattern)?;
+
+        if self.cache.len() >= MAX_CACHED_REGEXES {
+            self.cache.remove(0);
+        }
+        self.cache.push(re.clone());
+
+        Ok(re)
+    }
+}
@@ -354,6 +354,50 @@ impl Scope<'_> {
     ) -> &mut Self {
         self.push_entry(name.into().into(), AccessMode::ReadOnly, value)
     }
+    /// Add (push) a new _shared_ entry to the [`Scope`] and return the shared value.
+    ///
+    /// Not available under `no_closure`.
+    ///
+    /// Scripts always read and write the variable via the shared value, so changes made to it
+    /// outside of script (e.g. in an [`on_progress`][crate::Engine::on_progress] callback) are
+    /// visible to the script the next time it reads the variable, and vice versa.
+    ///
+    /// Avoid holding a lock on the shared value while the script is running, otherwise the script
+    /// cannot access the variable.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rhai::{Dynamic, Engine, Scope};
+    ///
+    /// let engine = Engine::new();
+    /// let mut my_scope = Scope::new();
+    ///
+    /// let mut x = Dynamic::from(my_scope.push_shared("x", 42_i64));
+    ///
+    /// engine.run_with_scope(&mut my_scope, "x += 1;")?;
+    /// assert_eq!(*x.read_lock::<i64>().unwrap(), 43);
+    ///
+    /// *x.write_lock::<i64>().unwrap() = 0;
+    /// assert_eq!(my_scope.get_value::<i64>("x").expect("x should exist"), 0);
+    /// # Ok::<_, Box<rhai::EvalAltResult>>(())
+    /// ```
+    #[cfg(not(feature = "no_closure"))]
+    #[inline]
+    pub fn push_shared(
+        &mut self,
+        name: impl Into<Identifier>,
+        value: impl Variant + Clone,
+    ) -> crate::Shared<crate::Locked<Dynamic>> {
+        let value: crate::Shared<crate::Locked<Dynamic>> =
+            crate::Locked::new(Dynamic::from(value)).into();
+        self.push_entry(
+            name.into().into(),
+            AccessMode::ReadWrite,
+            value.clone().into(),
+        );
+        value
+    }
     /// Add (push) a new entry with a [`Dynamic`] value to the [`Scope`].
     #[inline]
     pub(crate) fn push_entry(
@@ -929,6 +973,12 @@ impl Scope<'_> {
             .zip(self.values.iter())
             .map(|(name, value)| (name.as_str(), value.is_read_only(), value))
     }
+    /// Get an iterator to the names of entries in the [`Scope`].
+    #[cfg(feature = "debugging")]
+    #[inline(always)]
+    pub(crate) fn iter_names_raw(&self) -> impl Iterator<Item = &ImmutableString> {
+        self.names.iter()
+    }
     /// Get a reverse iterator to entries in the [`Scope`].
     /// Shared values are not expanded.
     #[inline]
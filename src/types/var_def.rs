@@ -1,5 +1,6 @@
-//! Variable declaration information.
+//! Variable declaration and access information.
 
+use crate::Dynamic;
 #[cfg(feature = "no_std")]
 use std::prelude::v1::*;
 
@@ -97,3 +98,29 @@ impl<'a> VarDefInfo<'a> {
         self.will_shadow
     }
 }
+
+/// A statically-known segment of a property/index access chain on a variable,
+/// passed to the variable resolver registered via [`Engine::on_var_chain`][crate::Engine::on_var_chain].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum VarChainSegment<'a> {
+    /// Property access: `.name`
+    Property(&'a str),
+    /// Indexing with a literal value: `[value]`
+    Index(Dynamic),
+}
+
+/// A write to a variable, passed to the callback registered via
+/// [`Engine::on_var_write`][crate::Engine::on_var_write].
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum VarWrite<'a> {
+    /// Assignment of a new value: `name = value`
+    Assign(&'a Dynamic),
+    /// Op-assignment with the operator (e.g. `+=`) and the right-hand-side value: `name op= value`
+    OpAssign(&'a str, &'a Dynamic),
+    /// Assignment or op-assignment to a property or an indexed element of the variable, with the
+    /// operator (`=` for plain assignment) and the right-hand-side value:
+    /// `name.prop op= value` or `name[index] op= value`
+    Member(&'a str, &'a Dynamic),
+}
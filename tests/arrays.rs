@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_index"))]
-use rhai::{Array, Dynamic, Engine, ParseErrorType, INT};
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ParseErrorType, INT};
 use std::iter::FromIterator;
 
 #[test]
@@ -487,6 +487,207 @@ fn test_arrays_map_reduce() {
         .unwrap();
 }
 
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_closure"))]
+#[test]
+fn test_arrays_partition_chunk_by() {
+    let engine = Engine::new();
+
+    let result = engine.eval::<Array>("let x = [1, 2, 3, 4, 5]; let limit = 3; x.partition(|v| v >= limit)").unwrap();
+    assert_eq!(result.len(), 2);
+    assert_eq!(result[0].clone().into_typed_array::<INT>().unwrap(), [3, 4, 5]);
+    assert_eq!(result[1].clone().into_typed_array::<INT>().unwrap(), [1, 2]);
+
+    let result = engine.eval::<Array>("fn big(v) { v > 3 } [1, 5, 2, 6].partition(Fn(\"big\"))").unwrap();
+    assert_eq!(result[0].clone().into_typed_array::<INT>().unwrap(), [5, 6]);
+    assert_eq!(result[1].clone().into_typed_array::<INT>().unwrap(), [1, 2]);
+
+    let result = engine.eval::<Array>("[].partition(|v| v > 0)").unwrap();
+    assert!(result[0].clone().into_array().unwrap().is_empty());
+    assert!(result[1].clone().into_array().unwrap().is_empty());
+
+    let result = engine.eval::<Array>("let x = [1, 3, 2, 4, 6, 5, 7]; let m = 2; x.chunk_by(|v| v % m)").unwrap();
+    let result = result.into_iter().map(|v| v.into_typed_array::<INT>().unwrap()).collect::<Vec<_>>();
+    assert_eq!(result, [vec![1, 3], vec![2, 4, 6], vec![5, 7]]);
+
+    let result = engine.eval::<Array>("[1, 2, 3, 4, 5].chunk_by(|v, i| i / 2)").unwrap();
+    let result = result.into_iter().map(|v| v.into_typed_array::<INT>().unwrap()).collect::<Vec<_>>();
+    assert_eq!(result, [vec![1, 2], vec![3, 4], vec![5]]);
+
+    let result = engine.eval::<Array>(r#"fn kind(v) { if v > 2 { "big" } else { () } } [1, 2, 3, 4, 1].chunk_by(Fn("kind"))"#).unwrap();
+    let result = result.into_iter().map(|v| v.into_typed_array::<INT>().unwrap()).collect::<Vec<_>>();
+    assert_eq!(result, [vec![1, 2], vec![3, 4], vec![1]]);
+
+    assert!(engine.eval::<Array>("[].chunk_by(|v| v)").unwrap().is_empty());
+
+    assert!(matches!(
+        *engine.run(r#"let count = 0; [1, 2, 3, 4].partition(|v| { count += 1; if v == 3 { throw "boom"; } v > 1 })"#).unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.clone().into_string().unwrap() == "boom")
+    ));
+    assert!(matches!(
+        *engine.run(r#"[1, 2, 3, 4].chunk_by(|v| if v == 3 { throw "boom"; } else { v })"#).unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.clone().into_string().unwrap() == "boom")
+    ));
+}
+
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_closure"))]
+#[test]
+fn test_arrays_index_of_find() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.index_of(|v| v > 2, -4)").unwrap(), 6);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.index_of(|v| v > 2, -99)").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.index_of(|v, i| v * i > 10, 1)").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.index_of(|v, i| v * i > 10, -4)").unwrap(), 6);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.index_of(|v| v > 4, 0)").unwrap(), -1);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.index_of(|v| v > 0, 8)").unwrap(), -1);
+    assert_eq!(engine.eval::<INT>("[].index_of(|v| true, -1)").unwrap(), -1);
+
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.find(|v| v < 3, -3)").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.find(|v, i| i > 4)").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.find(|v, i| v + i > 7, -5)").unwrap(), 3);
+    engine.eval::<()>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.find(|v| v > 4, -3)").unwrap();
+    engine.eval::<()>("let x = [1, 2, 3, 4, 1, 2, 3, 4]; x.find(|v| v > 0, 8)").unwrap();
+    assert_eq!(engine.eval::<String>(r#"let x = [1, 2, 3]; x.find(|v| v > 3) ?? "not found""#).unwrap(), "not found");
+
+    // Searching stops at the first match
+    assert_eq!(engine.eval::<INT>("let n = 0; [1, 2, 3, 4, 5].index_of(|v| { n += 1; v == 2 }); n").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("let n = 0; [1, 2, 3, 4, 5].find(|v| { n += 1; v == 4 }, -3); n").unwrap(), 2);
+
+    let err = engine.run("let x = [1, 2, 3, 4];\nx.find(|v| if v == 3 { throw \"boom\" } else { false }, -3)").unwrap_err();
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.position().line(), Some(2));
+    assert!(matches!(
+        *err,
+        EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.clone().into_string().unwrap() == "boom")
+    ));
+    assert!(matches!(
+        *engine.run(r#"[1, 2, 3, 4].index_of(|v, i| if i == 1 { throw "boom" } else { false }, -99)"#).unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.clone().into_string().unwrap() == "boom")
+    ));
+}
+
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_closure"))]
+#[test]
+fn test_arrays_sort_binary_search() {
+    let engine = Engine::new();
+
+    // Sorting with a comparer is stable
+    let result = engine
+        .eval::<Array>(
+            r#"
+                let x = [#{n: "a", k: 2}, #{n: "b", k: 1}, #{n: "c", k: 2}, #{n: "d", k: 1}, #{n: "e", k: 0}];
+                x.sort(|a, b| a.k - b.k);
+                x.map(|v| v.n)
+            "#,
+        )
+        .unwrap();
+    assert_eq!(result.into_iter().map(|v| v.into_string().unwrap()).collect::<Vec<_>>(), ["e", "b", "d", "a", "c"]);
+
+    // Comparer errors abort the sort and leave the array untouched
+    assert!(matches!(
+        *engine.run(r#"let x = [3, 1, 2]; x.sort(|a, b| if a == 2 || b == 2 { throw "boom" } else { a - b })"#).unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.clone().into_string().unwrap() == "boom")
+    ));
+    assert_eq!(
+        engine.eval::<Array>(r#"let x = [3, 1, 2]; try { x.sort(|a, b| if a == 2 || b == 2 { throw "boom" } else { a - b }) } catch {} x"#).unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>(),
+        [3, 1, 2]
+    );
+    assert!(matches!(*engine.run("let x = [3, 1, 2]; x.sort(|a, b| a > b)").unwrap_err(), EvalAltResult::ErrorMismatchOutputType(..)));
+
+    assert_eq!(engine.eval::<INT>("let x = [1, 3, 5, 7, 9]; x.binary_search(7)").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("let x = [1, 3, 5, 7, 9]; x.binary_search(1)").unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("let x = [1, 3, 5, 7, 9]; x.binary_search(4)").unwrap(), -3);
+    assert_eq!(engine.eval::<INT>("let x = [1, 3, 5, 7, 9]; x.binary_search(0)").unwrap(), -1);
+    assert_eq!(engine.eval::<INT>("let x = [1, 3, 5, 7, 9]; x.binary_search(42)").unwrap(), -6);
+    assert_eq!(engine.eval::<INT>(r#"let x = ["a", "c", "e"]; x.binary_search("d")"#).unwrap(), -3);
+    assert_eq!(engine.eval::<INT>("[].binary_search(1)").unwrap(), -1);
+
+    assert_eq!(engine.eval::<INT>("let x = [#{id: 1}, #{id: 4}, #{id: 9}]; x.binary_search_by(|v| v.id - 4)").unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("let x = [#{id: 1}, #{id: 4}, #{id: 9}]; x.binary_search_by(|v| v.id - 5)").unwrap(), -3);
+    assert_eq!(engine.eval::<INT>("let x = [#{id: 1}, #{id: 4}, #{id: 9}]; x.binary_search_by(|v| v.id - 10)").unwrap(), -4);
+    assert!(matches!(
+        *engine.run(r#"[1, 2, 3].binary_search_by(|v| throw "boom")"#).unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.clone().into_string().unwrap() == "boom")
+    ));
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_arrays_bulk() {
+    let mut engine = Engine::new();
+
+    // Range assignment - same size, shrinking and growing
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x[1..3] = [7, 8]; x").unwrap().into_typed_array::<INT>().unwrap(), [1, 7, 8, 4, 5]);
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x[1..=3] = [0]; x").unwrap().into_typed_array::<INT>().unwrap(), [1, 0, 5]);
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3]; x[1..2] = [7, 8, 9]; x").unwrap().into_typed_array::<INT>().unwrap(), [1, 7, 8, 9, 3]);
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3]; x[3..3] = [4, 5]; x").unwrap().into_typed_array::<INT>().unwrap(), [1, 2, 3, 4, 5]);
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x[-3..-1] = []; x").unwrap().into_typed_array::<INT>().unwrap(), [1, 2, 5]);
+    assert_eq!(engine.eval::<Dynamic>("let x = [[1, 2], [3]]; x[0][0..1] += [9]; x[0]").unwrap().into_typed_array::<INT>().unwrap(), [1, 9, 2]);
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x[-2..=-1]").unwrap().into_typed_array::<INT>().unwrap(), [4, 5]);
+
+    // Overlapping copies in both directions
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x.copy_within(0..3, 2); x").unwrap().into_typed_array::<INT>().unwrap(), [1, 2, 1, 2, 3]);
+    assert_eq!(
+        engine
+            .eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x.copy_within(1..=4, 0); x")
+            .unwrap()
+            .into_typed_array::<INT>()
+            .unwrap(),
+        [2, 3, 4, 5, 5]
+    );
+    assert_eq!(
+        engine
+            .eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x.copy_within(-2..5, -4); x")
+            .unwrap()
+            .into_typed_array::<INT>()
+            .unwrap(),
+        [1, 4, 5, 4, 5]
+    );
+
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x.fill_range(1..3, 0); x").unwrap().into_typed_array::<INT>().unwrap(), [1, 0, 0, 4, 5]);
+    assert_eq!(
+        engine
+            .eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x.fill_range(-2..=-1, 0); x")
+            .unwrap()
+            .into_typed_array::<INT>()
+            .unwrap(),
+        [1, 2, 3, 0, 0]
+    );
+    assert_eq!(engine.eval::<Dynamic>("let x = [1, 2, 3]; x.fill(9); x").unwrap().into_typed_array::<INT>().unwrap(), [9, 9, 9]);
+    assert_eq!(
+        engine
+            .eval::<Dynamic>("let x = [1, 2, 3, 4, 5]; x.swap(0, -1); x.swap(-4, 2); x")
+            .unwrap()
+            .into_typed_array::<INT>()
+            .unwrap(),
+        [5, 3, 2, 4, 1]
+    );
+
+    // Out-of-bounds errors carry the index and array length
+    assert!(matches!(*engine.run("let x = [1, 2, 3]; x[1..9] = [0];").unwrap_err(), EvalAltResult::ErrorArrayBounds(3, 9, ..)));
+    assert!(matches!(*engine.run("let x = [1, 2, 3]; x[-4..2] = [0];").unwrap_err(), EvalAltResult::ErrorArrayBounds(3, -4, ..)));
+    assert!(matches!(*engine.run("let x = [1, 2, 3]; x.swap(0, -4);").unwrap_err(), EvalAltResult::ErrorArrayBounds(3, -4, ..)));
+    assert!(matches!(*engine.run("let x = [1, 2, 3]; x.swap(3, 0);").unwrap_err(), EvalAltResult::ErrorArrayBounds(3, 3, ..)));
+    assert!(matches!(*engine.run("let x = [1, 2, 3]; x.copy_within(0..2, 2);").unwrap_err(), EvalAltResult::ErrorArrayBounds(3, 2, ..)));
+    assert!(matches!(*engine.run("let x = [1, 2, 3]; x.fill_range(0..=3, 0);").unwrap_err(), EvalAltResult::ErrorArrayBounds(3, 3, ..)));
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        engine.set_max_array_size(5);
+        engine.run("let x = [1, 2, 3]; x[1..2] = [7, 8, 9];").unwrap();
+        assert!(matches!(*engine.run("let x = [1, 2, 3]; x[1..1] = [7, 8, 9];").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+        engine.set_max_array_size(0);
+
+        engine.set_max_operations(500);
+        engine.run("let x = []; x.pad(100, 0); x.fill(1); x.copy_within(0..50, 50);").unwrap();
+        assert!(matches!(*engine.run("let x = []; x.pad(1000, 0); x.fill(1);").unwrap_err(), EvalAltResult::ErrorTooManyOperations(..)));
+        assert!(matches!(*engine.run("let x = []; x.pad(1000, 0); x[0..1] = [];").unwrap_err(), EvalAltResult::ErrorTooManyOperations(..)));
+    }
+}
+
 #[test]
 fn test_arrays_elvis() {
     let engine = Engine::new();
@@ -495,3 +696,49 @@ fn test_arrays_elvis() {
 
     engine.run("let x = (); x?[2] = 42").unwrap();
 }
+
+#[test]
+fn test_arrays_typed() {
+    let engine = Engine::new();
+
+    let items: Vec<(INT, String)> = vec![(1, "one".into()), (2, "two".into()), (3, "three".into())];
+
+    let array = Dynamic::from_iter(items.iter().map(|(n, s)| Dynamic::from(vec![Dynamic::from(*n), Dynamic::from(s.clone())])));
+
+    let mut scope = rhai::Scope::new();
+    scope.push("items", array);
+
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, r#"items.push([4, "four"]); for i in 0..items.len() { items[i][0] *= 10; } items"#)
+        .unwrap();
+
+    let items = result
+        .into_typed_array::<Array>()
+        .unwrap()
+        .into_iter()
+        .map(|item| {
+            let mut item = Dynamic::from(item).into_typed_array::<Dynamic>().unwrap().into_iter();
+            (item.next().unwrap().as_int().unwrap(), item.next().unwrap().into_string().unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(items, [(10, "one".into()), (20, "two".into()), (30, "three".into()), (40, "four".to_string())]);
+
+    let err = engine.eval::<Dynamic>(r#"[1, "two", 3]"#).unwrap().into_typed_array::<INT>().unwrap_err();
+    match *err {
+        EvalAltResult::ErrorMismatchDataType(expected, actual, ..) => {
+            assert_eq!(expected, std::any::type_name::<INT>());
+            assert_eq!(actual, "string at index 1");
+        }
+        err => panic!("wrong error: {}", err),
+    }
+
+    let err = Dynamic::from(42 as INT).into_typed_array::<INT>().unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorMismatchDataType(..)));
+
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let shared = Dynamic::from_iter([1 as INT, 2, 3]).into_shared();
+        assert_eq!(shared.into_typed_array::<INT>().unwrap(), [1, 2, 3]);
+    }
+}
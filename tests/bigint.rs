@@ -0,0 +1,82 @@
+#![cfg(feature = "bigint")]
+use rhai::{Dynamic, Engine, EvalAltResult, INT};
+
+#[test]
+fn test_bigint() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("type_of(42n)").unwrap(), "bigint");
+    assert_eq!(engine.eval::<String>(r#"type_of(bigint("42"))"#).unwrap(), "bigint");
+
+    assert_eq!(
+        engine
+            .eval::<Dynamic>(
+                "
+                    fn factorial(n) {
+                        let result = 1n;
+                        for i in 2..=n { result *= i; }
+                        result
+                    }
+                    factorial(50)
+                "
+            )
+            .unwrap()
+            .to_string(),
+        "30414093201713378043612608166064768844377641568960512000000000000"
+    );
+
+    assert_eq!(engine.eval::<String>("(123n * 1_000_000_000_000n).to_string()").unwrap(), "123000000000000");
+    assert_eq!(engine.eval::<String>("(0xffn + 1).to_string()").unwrap(), "256");
+    assert_eq!(engine.eval::<String>("(-5n).to_string()").unwrap(), "-5");
+    assert_eq!(engine.eval::<String>("(2n ** 100).to_string()").unwrap(), "1267650600228229401496703205376");
+    assert_eq!(engine.eval::<String>(r#"`${bigint("123456789012345678901234567890") + 10}`"#).unwrap(), "123456789012345678901234567900");
+}
+
+#[test]
+fn test_bigint_mixed() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("(10n + 5).to_string()").unwrap(), "15");
+    assert_eq!(engine.eval::<String>("(5 - 10n).to_string()").unwrap(), "-5");
+    assert_eq!(engine.eval::<String>("(7 * 6n).to_string()").unwrap(), "42");
+    assert_eq!(engine.eval::<String>("(43n / 2).to_string()").unwrap(), "21");
+    assert_eq!(engine.eval::<String>("(43 % 5n).to_string()").unwrap(), "3");
+
+    assert!(engine.eval::<bool>("42n == 42").unwrap());
+    assert!(engine.eval::<bool>("42 == 42n").unwrap());
+    assert!(engine.eval::<bool>("41n < 42").unwrap());
+    assert!(engine.eval::<bool>("100000000000000000000n > 42").unwrap());
+    assert!(engine.eval::<bool>("1n != 2n").unwrap());
+
+    assert!(matches!(*engine.eval::<Dynamic>("42n / 0").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+}
+
+#[test]
+fn test_bigint_conversions() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("to_int(42n)").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("to_bigint(-42).to_int()").unwrap(), -42);
+    assert_eq!(engine.eval::<INT>("sign(-42n)").unwrap(), -1);
+    assert_eq!(engine.eval::<String>("abs(-42n).to_string()").unwrap(), "42");
+    assert!(engine.eval::<bool>("0n.is_zero").unwrap());
+
+    assert!(matches!(*engine.eval::<INT>("(2n ** 200).to_int()").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<Dynamic>(r#"bigint("12abc")"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<rhai::FLOAT>("to_float(42n)").unwrap(), 42.0);
+        assert_eq!(engine.eval::<String>("bigint(42.9).to_string()").unwrap(), "42");
+    }
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_bigint_serde() {
+    let engine = Engine::new();
+
+    let x = engine.eval::<Dynamic>("2n ** 100").unwrap();
+
+    assert_eq!(serde_json::to_string(&x).unwrap(), r#""1267650600228229401496703205376""#);
+}
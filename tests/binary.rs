@@ -0,0 +1,152 @@
+use rhai::{Engine, EvalAltResult, AST, INT};
+
+/// Save the [`AST`] into bytes and load it back, then check that both evaluate to the same result
+/// and that saving is stable.
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "only_i32")))]
+fn check_round_trip(engine: &Engine, script: &str) {
+    let ast = engine.compile(script).unwrap();
+    let bytes = ast.to_bytes().unwrap_or_else(|err| panic!("cannot save:\n{}\n{}", script, err));
+    let ast2 = AST::from_bytes(&bytes).unwrap_or_else(|err| panic!("cannot load:\n{}\n{}", script, err));
+
+    let result = engine.eval_ast::<rhai::Dynamic>(&ast).map_err(|err| err.to_string());
+    let result2 = engine.eval_ast::<rhai::Dynamic>(&ast2).map_err(|err| err.to_string());
+
+    assert_eq!(format!("{result:?}"), format!("{result2:?}"), "different results for:\n{script}");
+    assert_eq!(ast2.to_bytes().unwrap(), bytes, "unstable bytes for:\n{script}");
+}
+
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "only_i32")))]
+const SCRIPTS: &[&str] = &[
+    "let x = (1 + 2) * 3 - -4; let y = 2 ** 3 ** 2; x * 1000 + y",
+    "let x = 10; x -= 3; x *= 2; x /= 7; x %= 5; x <<= 4; x >>= 1; x |= 1; x &= 13; x ^= 6; x",
+    "let x = 42; x > 40 && !(x == 50) || x < 0",
+    "const X = -9223372036854775807 - 1; [X, 0xff, 'c', 1.5e-3, \"hello\", true, ()]",
+    "let x = 42; `multi\nline ${x + 1} and ${if x > 10 { `inner ${x}` } else { \"small\" }}`",
+    "let x = 0; while x < 10 { x += 1; if x == 5 { break; } } x",
+    "let x = 0; loop { x += 1; if x % 2 == 0 { continue; } if x > 10 { break x * 2; } }",
+    "let x = 0; do { x += 1; } while x < 10; do { x += 2; } until x >= 20; x",
+    "let sum = 0; for (x, i) in [10, 20, 30] { sum += x * i; } for x in 0..10 { sum += x; } for x in 0..=10 { sum += x; } sum",
+    "let x = 'c'; switch x { 'a' | 'b' => 1, 'c' if true => { let y = 2; y }, _ => 3 }",
+    r#"let x = "world"; switch x { "hello" => 1, "world" => 2 }"#,
+    "let x = 42; switch x { 1 => 1, 2 if x > 0 => 2, 0..10 => 3, 40..=50 => 4, _ => 5 }",
+    "switch 2 { 1 => 10, 2 => 20, _ => 30 }",
+    r#"let r = ""; try { throw "oops"; } catch (err) { r = err; } try { throw 1; } catch { r += "!"; } r"#,
+    "let x = (); let y = x ?? 42; [y, x?.foo, x?[1]]",
+    "let a = [1, [2, 3], #{x: 4}]; a[1][0] = 10; a[2].x += 1; a",
+    r#"let m = #{a: 1, "b c": [2, 3], d: #{e: 'x'}}; m.d.e = 'y'; m["b c"][1] += 10; m"#,
+    "let x = 2 in [1, 2, 3]; let y = 4 !in [1, 2, 3]; [x, y]",
+    "let r = 1..10; let s = 1..=10; [r, s, r.start, s.end]",
+    "fn add(x, y) { x + y } private fn sub(x, y) { return x - y; } add(1, 2) * sub(5, 3)",
+    "fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } } fib(10)",
+    "fn add(x, y = 1, z = x * y) { x + y + z } add(1) + add(1, 2) + add(1, 2, 3)",
+    "fn log(fmt, ...args) { fmt + args.len() } log(\"a\") + log(\"b\", 1, 2)",
+    "fn area(width, height) { width * height } area(height: 2, width: 3) + area(4, height: 5)",
+    "fn inc() { this += 1; } let x = 41; x.inc(); x",
+    "fn foo() { x } let x = 42; foo!()",
+    "fn folded(x) { 1 + 2 + x }\n//! rhai:optimize=off\nfn kept(x) { 1 + 2 + x }\nfolded(1) + kept(2)",
+    "fn foo(x) { x * 2 } let f = Fn(\"foo\"); let g = f.curry(21); f.call(1) + g.call()",
+    "let a = [1, 2, 3, 4, 5]; a.map(|x| x * 2).filter(|x| x > 4).reduce(|sum, x| sum + x, 0)",
+    "let x = 40; let y = \"hi\"; let f = |a| a + x + y.len; x = 2; f.call(1)",
+    "let obj = #{ value: 1, action: || this.value += 1 }; obj.action(); obj.value",
+];
+
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "only_i32")))]
+#[test]
+fn test_binary_round_trip() {
+    let engine = Engine::new();
+
+    for script in SCRIPTS {
+        check_round_trip(&engine, script);
+    }
+
+    #[cfg(not(feature = "no_optimize"))]
+    for level in [rhai::OptimizationLevel::None, rhai::OptimizationLevel::Simple, rhai::OptimizationLevel::Full] {
+        let mut engine = Engine::new();
+        engine.set_optimization_level(level);
+
+        for script in SCRIPTS {
+            check_round_trip(&engine, script);
+        }
+    }
+}
+
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_binary_modules() {
+    let mut engine = Engine::new();
+
+    let mut module = rhai::Module::new();
+    module.set_var("answer", 41 as INT);
+    module.set_native_fn("inc", |x: INT| Ok(x + 1));
+    engine.register_static_module("hello", module.into());
+
+    let ast = engine.compile("hello::inc(hello::answer)").unwrap();
+    let ast = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+}
+
+#[test]
+fn test_binary_source() {
+    let engine = Engine::new();
+
+    let mut ast = engine.compile("40 + 2").unwrap();
+    ast.set_source("hello");
+
+    let ast = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(ast.source(), Some("hello"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+}
+
+#[cfg(feature = "metadata")]
+#[test]
+fn test_binary_doc_comments() {
+    let engine = Engine::new();
+
+    let ast = engine
+        .compile(
+            "
+                //! Module documentation.
+
+                /// Add two numbers.
+                fn add(x, y) { x + y }
+
+                add(1, 2)
+            ",
+        )
+        .unwrap();
+
+    let ast = AST::from_bytes(&ast.to_bytes().unwrap()).unwrap();
+
+    assert_eq!(ast.doc(), "//! Module documentation.");
+    assert_eq!(ast.iter_functions().next().unwrap().comments, vec!["/// Add two numbers."]);
+}
+
+#[test]
+fn test_binary_errors() {
+    let engine = Engine::new();
+
+    let bytes = engine.compile("40 + 2").unwrap().to_bytes().unwrap();
+
+    let err = |bytes: &[u8]| match *AST::from_bytes(bytes).unwrap_err() {
+        EvalAltResult::ErrorSystem(_, err) => err.to_string(),
+        err => panic!("unexpected error: {}", err),
+    };
+
+    assert_eq!(err(b"hello, world!"), "not a saved AST");
+
+    let mut bad = bytes.clone();
+    bad[8] = 42;
+    assert_eq!(err(&bad), "format version 42 is not supported (expecting version 3)");
+
+    let mut bad = bytes.clone();
+    bad[12] ^= 0b10;
+    assert!(err(&bad).starts_with("mismatched features: `no_float`"));
+
+    assert_eq!(err(&bytes[..bytes.len() - 1]), "unexpected end of data");
+
+    let mut bad = bytes.clone();
+    bad.push(0);
+    assert_eq!(err(&bad), "unexpected trailing data");
+}
@@ -1,4 +1,4 @@
-use rhai::{Engine, INT};
+use rhai::{Engine, EvalAltResult, INT};
 
 #[test]
 fn test_left_shift() {
@@ -68,3 +68,44 @@ fn test_bit_fields() {
         5
     );
 }
+
+#[test]
+fn test_bit_utilities() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x = 0b1010_0100; x.popcount()").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("popcount(-1)").unwrap(), INT::BITS as INT);
+    assert_eq!(engine.eval::<INT>("let x = 1; x.leading_zeros()").unwrap(), INT::BITS as INT - 1);
+    assert_eq!(engine.eval::<INT>("leading_zeros(-1)").unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("let x = 0b1010_0100; x.trailing_zeros()").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("trailing_zeros(0)").unwrap(), INT::BITS as INT);
+
+    assert_eq!(engine.eval::<INT>("let x = 0b0110; x.rotate_left(2)").unwrap(), 24);
+    assert_eq!(engine.eval::<INT>("let x = 0b0110; x.rotate_left(-1)").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("let x = 0b0110; x.rotate_right(1)").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("let x = 0b0110; x.rotate_right(-2)").unwrap(), 24);
+    assert_eq!(engine.eval::<INT>("let x = 1; x.rotate_right(1)").unwrap(), INT::MIN);
+    assert_eq!(engine.eval::<INT>("let x = 1; x.rotate_left(1000)").unwrap(), 1 << (1000 % INT::BITS));
+
+    assert_eq!(engine.eval::<INT>("let sum = 0; for bit in bit_positions(0b1010_0100) { sum = sum * 10 + bit; } sum").unwrap(), 257);
+    assert_eq!(engine.eval::<INT>("let count = 0; for bit in bit_positions(0) { count += 1; } count").unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("let last = 0; for bit in bit_positions(-1) { last = bit; } last").unwrap(), INT::BITS as INT - 1);
+
+    assert!(engine.eval::<bool>("let x = 0; x.set_bit(-1, true); x < 0").unwrap());
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_bit_fields_out_of_range() {
+    let engine = Engine::new();
+
+    let max = INT::BITS as INT;
+
+    assert!(matches!(*engine.eval::<bool>(&format!("let x = 10; x[{max}]")).unwrap_err(), EvalAltResult::ErrorBitFieldBounds(n, i, ..) if n == INT::BITS as usize && i == max));
+    assert!(matches!(*engine.run(&format!("let x = 10; x[{max}] = true;")).unwrap_err(), EvalAltResult::ErrorBitFieldBounds(..)));
+    assert!(matches!(*engine.eval::<bool>(&format!("get_bit(10, {max})")).unwrap_err(), EvalAltResult::ErrorBitFieldBounds(..)));
+    assert!(matches!(*engine.run(&format!("let x = 10; x.set_bit({max}, true);")).unwrap_err(), EvalAltResult::ErrorBitFieldBounds(..)));
+
+    assert!(engine.eval::<bool>(&format!("let x = 1 << {}; x[-1]", max - 1)).unwrap());
+    assert!(engine.eval::<bool>(&format!("let x = 1; x[-{max}]")).unwrap());
+}
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_index"))]
-use rhai::{Blob, Engine, Scope, INT};
+use rhai::{Blob, BlobView, Engine, EvalAltResult, Scope, Shared, INT};
 use std::iter::FromIterator;
 
 #[test]
@@ -114,3 +114,94 @@ fn test_blobs_write_string() {
     assert_eq!(engine.eval::<Blob>(r#"let x = blob(10, 0); write_utf8(x, 3..9, "❤❤❤❤"); x"#).unwrap(), "\0\0\0\u{2764}\u{2764}\0".as_bytes());
     assert_eq!(engine.eval::<Blob>(r#"let x = blob(10, 0); write_utf8(x, 3..7, "❤❤❤❤"); x"#).unwrap(), vec![0, 0, 0, 226, 157, 164, 226, 0, 0, 0]);
 }
+
+#[test]
+fn test_blobs_view() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>("type_of(blob(10).view())").unwrap(), "blob-view");
+    assert_eq!(
+        engine
+            .eval::<INT>("let b = blob(); b += 1; b += 2; b += 3; b += 4; b += 5; let v = b.view(1..4); v[0] + v[-1] + v.len")
+            .unwrap(),
+        9
+    );
+    assert_eq!(engine.eval::<INT>("let b = blob(); b += 1; b += 2; b += 3; b += 4; b += 5; b.view(-3, 2).view(1..=1)[0]").unwrap(), 4);
+    assert_eq!(engine.eval::<String>("let b = blob(); b += 1; b += 2; b += 3; b.view(1..3).to_string()").unwrap(), "[0203]");
+    assert_eq!(engine.eval::<INT>("let v = blob(5, 2).view(); let s = 0; for x in v { s += x } s").unwrap(), 10);
+    assert_eq!(engine.eval::<Blob>("blob(5, 2).view(1..3).to_blob()").unwrap(), [2, 2]);
+    assert!(engine.eval::<bool>("blob(5, 2).view(1..3) == blob(2, 2).view()").unwrap());
+
+    assert!(matches!(*engine.eval::<INT>("blob(3).view()[3]").unwrap_err(), EvalAltResult::ErrorArrayBounds(3, 3, ..)));
+
+    // Copy-on-write
+    assert_eq!(engine.eval::<INT>("let v = blob(5).view(); let w = v.view(0..2); w[0] = 42; v[0] * 100 + w[0]").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("let v = blob(5).view(); let w = v.view(1..3); w.set(0, 42); v.get(1) * 100 + w.get(0)").unwrap(), 42);
+
+    #[cfg(not(feature = "only_i32"))]
+    {
+        assert_eq!(engine.eval::<INT>("let v = blob(16).view(4..12); v.write_be(0, 8, 0x1234); v.parse_be_int(0..8)").unwrap(), 0x1234);
+        assert_eq!(engine.eval::<INT>("let v = blob(16).view(4..12); v.write_le(2..=3, 0x1234); v.parse_le_int(2, 2)").unwrap(), 0x1234);
+    }
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(
+        engine
+            .eval::<rhai::FLOAT>("let v = blob(16).view(); v.write_le(0, 16, 1.5); v.view(0..8).parse_le_float(0, 16)")
+            .unwrap(),
+        1.5
+    );
+}
+
+#[test]
+fn test_blobs_view_zero_copy() {
+    const SIZE: usize = 10_000_000;
+
+    let engine = Engine::new();
+    let data = BlobView::new(vec![0_u8; SIZE]);
+
+    let mut scope = Scope::new();
+    scope.push("data", data.clone());
+
+    // Views of views share the same storage
+    let views = engine
+        .eval_with_scope::<rhai::Array>(&mut scope, "let a = data.view(1000, 100); let b = a.view(10..20); [a, b, b[0..5]]")
+        .unwrap();
+
+    for view in views {
+        let view = view.cast::<BlobView>();
+        assert!(view.is_shared());
+        assert!(Shared::ptr_eq(view.shared_blob(), data.shared_blob()));
+    }
+    // `data` here and in the scope, plus `a` and `b` in the scope
+    assert_eq!(Shared::strong_count(data.shared_blob()), 4);
+
+    // Reading does not copy
+    #[cfg(not(feature = "only_i32"))]
+    assert_eq!(
+        engine
+            .eval_with_scope::<INT>(&mut scope, "data.view(SIZE - 8, 8).parse_le_int(0, 8)".replace("SIZE", &SIZE.to_string()).as_str())
+            .unwrap(),
+        0
+    );
+
+    // Writing to a shared view only copies the bytes in the view
+    let view = engine.eval_with_scope::<BlobView>(&mut scope, "let v = data.view(0..4); v.write_le(0, 4, 0x01020304); v").unwrap();
+    assert!(!Shared::ptr_eq(view.shared_blob(), data.shared_blob()));
+    assert_eq!(view.shared_blob().len(), 4);
+    assert_eq!(&*view, &[4, 3, 2, 1]);
+    assert!(data.iter().take(4).all(|&b| b == 0));
+
+    // A view of a temporary BLOB takes over its storage
+    let view = engine.eval::<BlobView>("blob(SIZE).view(5, 10)".replace("SIZE", &SIZE.to_string()).as_str()).unwrap();
+    assert_eq!(view.shared_blob().len(), SIZE);
+    assert_eq!((view.offset(), view.len()), (5, 10));
+    assert!(!view.is_shared());
+
+    // Writing to an unshared view does not copy
+    let view = engine
+        .eval::<BlobView>("let v = blob(SIZE).view(5, 10); v.write_le(0, 4, 0x01020304); v[9] = 42; v".replace("SIZE", &SIZE.to_string()).as_str())
+        .unwrap();
+    assert_eq!(view.shared_blob().len(), SIZE);
+    assert_eq!(&view[..4], &[4, 3, 2, 1]);
+    assert_eq!(view[9], 42);
+}
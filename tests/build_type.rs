@@ -156,3 +156,84 @@ fn build_type() {
         6,
     );
 }
+
+#[test]
+fn build_type_constructor() {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Foo {
+        x: INT,
+    }
+
+    impl CustomType for Foo {
+        fn build(mut builder: TypeBuilder<Self>) {
+            builder
+                .with_constructor(|x: INT| Self { x })
+                .with_constructor(|| Self { x: 42 })
+                .with_name("Foo")
+                .with_get_set("x", |f: &mut Self| f.x, |f: &mut Self, x: INT| f.x = x);
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct Bar;
+
+    impl CustomType for Bar {
+        fn build(mut builder: TypeBuilder<Self>) {
+            builder.with_constructor(|| Self);
+        }
+    }
+
+    let mut engine = Engine::new();
+    engine.build_type::<Foo>().build_type::<Bar>();
+
+    assert_eq!(engine.eval::<Foo>("let f = Foo(1); f.x += 2; f").unwrap(), Foo { x: 3 });
+    assert_eq!(engine.eval::<INT>("Foo().x").unwrap(), 42);
+    assert_eq!(engine.eval::<String>("type_of(Foo(1))").unwrap(), "Foo");
+    assert!(engine.eval::<String>("type_of(Bar())").unwrap().ends_with("Bar"));
+}
+
+#[cfg(feature = "metadata")]
+#[cfg(not(feature = "only_i32"))]
+#[test]
+fn build_type_metadata() {
+    #[derive(Debug, Clone)]
+    struct Foo {
+        x: INT,
+    }
+
+    impl CustomType for Foo {
+        fn build(mut builder: TypeBuilder<Self>) {
+            builder
+                .with_name("Foo")
+                .with_constructor(|x: INT| Self { x })
+                .with_fn("bump", |f: &mut Self| f.x += 1)
+                .with_get_set("x", |f: &mut Self| f.x, |f: &mut Self, x: INT| f.x = x);
+        }
+    }
+
+    let mut engine = Engine::new();
+    engine.build_type::<Foo>();
+    engine.register_fn("unrelated", |x: INT| x);
+
+    let json: serde_json::Value = serde_json::from_str(&engine.gen_fn_metadata_to_json(false).unwrap()).unwrap();
+    let custom_types = json["customTypes"].as_array().unwrap();
+
+    assert_eq!(custom_types.len(), 1);
+    assert_eq!(custom_types[0]["displayName"], "Foo");
+    assert_eq!(custom_types[0]["functions"], serde_json::json!(["Foo(_: i64) -> Foo", "bump(_: &mut Foo)", "get$x(_: &mut Foo) -> i64", "set$x(_: &mut Foo, _: i64)"]));
+
+    #[cfg(feature = "internals")]
+    {
+        let definitions = engine.definitions().include_standard_packages(false).static_module();
+        let (before, after) = definitions.split_once("// type Foo").unwrap();
+
+        assert!(before.contains("fn unrelated("));
+        assert!(!before.contains("fn Foo("));
+        assert!(after.contains("fn Foo(_: int) -> Foo;"));
+        assert!(after.contains("fn bump(_: Foo)"));
+        assert!(after.contains("fn get x(_: Foo) -> int;"));
+        assert!(!after.contains("fn unrelated("));
+    }
+
+    assert_eq!(engine.eval::<INT>("let f = Foo(1); f.bump(); f.x").unwrap(), 2);
+}
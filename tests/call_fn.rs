@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_function"))]
-use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, FnPtr, Func, FuncArgs, Scope, AST, INT};
+use rhai::{CallFnOptions, CallFnsOptions, Dynamic, Engine, EvalAltResult, FnCallSpec, FnPtr, Func, FuncArgs, NativeCallContext, Scope, AST, INT};
 use std::any::TypeId;
 
 #[test]
@@ -87,6 +87,146 @@ fn test_call_fn_scope() {
     assert_eq!(scope.len(), 100);
 }
 
+#[test]
+fn test_call_fns() {
+    let mut engine = Engine::new();
+
+    let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let count = counter.clone();
+    engine.register_fn("ran", move || {
+        count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    });
+
+    let ast = engine
+        .compile(
+            "
+                fn pre_update() { ticks += 1; log += \"pre;\"; }
+                fn update(x) { log += `update(${x});`; ticks * x }
+                fn post_update() { log += \"post;\"; let done = true; ticks }
+                fn fail(msg) { throw msg; }
+                fn action(x) { this += x; }
+
+                ran();
+            ",
+        )
+        .unwrap();
+
+    let mut scope = Scope::new();
+    scope.push("ticks", 0 as INT);
+    scope.push("log", "");
+
+    // Scope effects of earlier calls are visible to later calls
+    let calls = [FnCallSpec::new("pre_update", ()), FnCallSpec::new("update", (21 as INT,)), FnCallSpec::new("pre_update", ()), FnCallSpec::new("post_update", ())];
+    let results = engine.call_fns(&mut scope, &ast, &calls);
+
+    assert_eq!(results.len(), 4);
+    assert!(results[0].as_ref().unwrap().is_unit());
+    assert_eq!(results[1].as_ref().unwrap().as_int().unwrap(), 21);
+    assert_eq!(results[3].as_ref().unwrap().as_int().unwrap(), 2);
+    assert_eq!(scope.get_value::<INT>("ticks").unwrap(), 2);
+    assert_eq!(scope.get_value::<String>("log").unwrap(), "pre;update(21);pre;post;");
+    assert!(!scope.contains("done"));
+
+    // The AST body runs once per batch
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    engine.call_fns(&mut scope, &ast, &[]);
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    let options = CallFnsOptions::new().eval_ast(false).rewind_scope(false);
+    engine.call_fns_with_options(options, &mut scope, &ast, &calls, &mut []);
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!(scope.get_value::<INT>("ticks").unwrap(), 4);
+    assert!(scope.get_value::<bool>("done").unwrap());
+
+    // Stop at the first error
+    let calls = [FnCallSpec::new("pre_update", ()), FnCallSpec::new("fail", ("oops",)), FnCallSpec::new("pre_update", ()), FnCallSpec::new("unknown", ())];
+    let results = engine.call_fns(&mut scope, &ast, &calls);
+
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(matches!(**results[1].as_ref().unwrap_err(), EvalAltResult::ErrorInFunctionCall(ref f, .., ref err, _) if f == "fail" && err.to_string().contains("oops")));
+    assert_eq!(scope.get_value::<INT>("ticks").unwrap(), 5);
+
+    // Continue after errors
+    let options = CallFnsOptions::new().stop_on_error(false);
+    let results = engine.call_fns_with_options(options, &mut scope, &ast, &calls, &mut []);
+
+    assert_eq!(results.len(), 4);
+    assert!(results[0].is_ok());
+    assert!(matches!(**results[1].as_ref().unwrap_err(), EvalAltResult::ErrorInFunctionCall(..)));
+    assert!(results[2].is_ok());
+    assert!(matches!(**results[3].as_ref().unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+    assert_eq!(scope.get_value::<INT>("ticks").unwrap(), 7);
+
+    // Binding the 'this' pointer
+    let mut values = [Dynamic::from(1 as INT), Dynamic::from(10 as INT)];
+    let calls = [FnCallSpec::new("action", (41 as INT,)).bind_this_ptr(0), FnCallSpec::new("action", (32 as INT,)).bind_this_ptr(1), FnCallSpec::new("action", (1 as INT,)).bind_this_ptr(2)];
+    let results = engine.call_fns_with_options(CallFnsOptions::new(), &mut scope, &ast, &calls, &mut values);
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(**results[2].as_ref().unwrap_err(), EvalAltResult::ErrorArrayBounds(2, 2, ..)));
+    assert_eq!(values[0].as_int().unwrap(), 42);
+    assert_eq!(values[1].as_int().unwrap(), 42);
+}
+
+#[test]
+fn test_call_fn_split_ast() {
+    let mut engine = Engine::new();
+
+    let counter = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let count = counter.clone();
+    engine.register_fn("ran", move || {
+        count.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    });
+
+    let ast = engine
+        .compile(
+            "
+                fn add(x) { x + offset }
+                fn make_adder(x) { |y| x + y }
+
+                ran();
+                let offset = 40;
+            ",
+        )
+        .unwrap();
+
+    let functions = ast.functions_only();
+    assert!(functions.has_functions());
+    engine.run_ast(&functions).unwrap();
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+    let (statements, lib) = ast.split();
+    assert!(!statements.has_functions());
+
+    // The functions module is shared, not copied
+    #[cfg(not(feature = "sync"))]
+    assert_eq!(std::rc::Rc::strong_count(&lib), 2);
+    #[cfg(feature = "sync")]
+    assert_eq!(std::sync::Arc::strong_count(&lib), 2);
+
+    let mut scope = Scope::new();
+    engine.run_ast_with_scope(&mut scope, &statements).unwrap();
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    for n in 0..10 {
+        assert_eq!(engine.call_fn_in_module::<INT>(&mut scope, &lib, "add", (n as INT,)).unwrap(), 40 + n);
+    }
+
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let adder = engine.call_fn_in_module::<FnPtr>(&mut scope, &lib, "make_adder", (40 as INT,)).unwrap();
+        assert_eq!(adder.call::<INT>(&engine, &functions, (2 as INT,)).unwrap(), 42);
+    }
+
+    assert!(matches!(*engine.call_fn_in_module::<INT>(&mut scope, &lib, "unknown", ()).unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    // No top-level statements were re-run
+    assert_eq!(counter.load(std::sync::atomic::Ordering::SeqCst), 1);
+    assert_eq!(scope.len(), 1);
+}
+
 struct Options {
     pub foo: bool,
     pub bar: String,
@@ -309,3 +449,66 @@ fn test_call_fn_events() {
     assert!(handler.scope.get_value::<bool>("state").unwrap());
     assert_eq!(handler.on_event("start", 999).as_int().unwrap(), 1041);
 }
+
+#[test]
+fn test_call_fn_output_type_mismatch() {
+    #[derive(Debug, Clone)]
+    struct TestStruct;
+
+    let mut engine = Engine::new();
+    engine.register_type_with_name::<TestStruct>("TestStruct");
+    engine.register_fn("call_cb", |context: NativeCallContext, callback: FnPtr| -> Result<bool, _> { callback.call_within_context(&context, ()) });
+    engine.register_fn("call_cb_native", |context: NativeCallContext| -> Result<bool, _> { context.call_native_fn("make_test_struct", ()) });
+    engine.register_fn("make_test_struct", || TestStruct);
+
+    let ast = engine.compile("fn foo() { 42 } fn bar() { make_test_struct() }").unwrap();
+    let expected_int = format!("Output type incorrect: {} (expecting bool)", std::any::type_name::<INT>());
+
+    // call_fn
+    let err = engine.call_fn::<bool>(&mut Scope::new(), &ast, "foo", ()).unwrap_err();
+    assert_eq!(err.to_string(), expected_int);
+    let err = engine.call_fn::<INT>(&mut Scope::new(), &ast, "bar", ()).unwrap_err();
+    assert_eq!(err.to_string(), format!("Output type incorrect: TestStruct (expecting {})", std::any::type_name::<INT>()));
+    let err = engine.call_fn::<TestStruct>(&mut Scope::new(), &ast, "foo", ()).unwrap_err();
+    assert_eq!(err.to_string(), format!("Output type incorrect: {} (expecting TestStruct)", std::any::type_name::<INT>()));
+
+    // FnPtr::call
+    let err = FnPtr::new("foo").unwrap().call::<bool>(&engine, &ast, ()).unwrap_err();
+    assert_eq!(err.to_string(), expected_int);
+
+    // FnPtr::call_within_context
+    let err = engine.eval::<bool>("call_cb(|| 42)").unwrap_err();
+    assert!(err.to_string().contains(&expected_int), "{}", err);
+
+    // NativeCallContext::call_native_fn
+    let err = engine.eval::<bool>("call_cb_native()").unwrap_err();
+    assert!(err.to_string().contains("Output type incorrect: TestStruct (expecting bool)"), "{}", err);
+
+    // Engine::eval
+    let err = engine.eval::<bool>("42").unwrap_err();
+    assert_eq!(err.to_string(), expected_int);
+
+    // Engine::cast_dynamic
+    assert_eq!(engine.cast_dynamic::<INT>(Dynamic::from(42 as INT)).unwrap(), 42);
+    let err = engine.cast_dynamic::<bool>(Dynamic::from(42 as INT)).unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorMismatchOutputType(..)));
+    assert_eq!(err.to_string(), expected_int);
+    let err = engine.cast_dynamic::<bool>(Dynamic::from(TestStruct)).unwrap_err();
+    assert_eq!(err.to_string(), "Output type incorrect: TestStruct (expecting bool)");
+
+    // Dynamic::try_cast_result
+    let value = Dynamic::from(42 as INT).try_cast_result::<bool>().unwrap_err();
+    assert_eq!(value.as_int().unwrap(), 42);
+    assert_eq!(value.try_cast_result::<INT>().unwrap(), 42);
+
+    // Shared values are flattened first
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let shared = Dynamic::from(42 as INT).into_shared();
+        assert_eq!(shared.clone().try_cast_result::<INT>().unwrap(), 42);
+        let value = shared.clone().try_cast_result::<bool>().unwrap_err();
+        assert!(!value.is_shared());
+        assert_eq!(engine.cast_dynamic::<INT>(shared.clone()).unwrap(), 42);
+        assert_eq!(engine.cast_dynamic::<bool>(shared).unwrap_err().to_string(), expected_int);
+    }
+}
@@ -434,3 +434,93 @@ fn test_closures_callback() {
 
     assert_eq!(cb.run(21).unwrap(), 42);
 }
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+fn test_closures_bind_this() {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<FnPtr>("|x| this + x").unwrap().binds_this());
+    assert!(!engine.eval::<FnPtr>("|x| x + 1").unwrap().binds_this());
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn apply(f) {
+                        f.call(1);
+                        call(f, 2);
+                        f.call(3) * 10
+                    }
+
+                    let obj = #{ total: 0 };
+                    let r = obj.apply(|x| { this.total += x; this.total });
+                    obj.total + r
+                "
+            )
+            .unwrap(),
+        66
+    );
+
+    // Called outside of any method context
+    let err = engine.run("let f = |x| this.total += x; f.call(1);").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorInFunctionCall(.., ref e, _) if matches!(**e, EvalAltResult::ErrorUnboundThis(..))), "{}", err);
+
+    let err = engine.run("fn apply(f) { f.call(1) } apply(|x| this.total += x);").unwrap_err();
+    assert!(
+        matches!(*err, EvalAltResult::ErrorInFunctionCall(.., ref e, _) if matches!(**e, EvalAltResult::ErrorInFunctionCall(.., ref e, _) if matches!(**e, EvalAltResult::ErrorUnboundThis(..)))),
+        "{}",
+        err
+    );
+}
+
+#[test]
+#[cfg(not(feature = "no_closure"))]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+fn test_closures_cyclic_data() {
+    use rhai::{Array, Map};
+
+    let engine = Engine::new();
+
+    // x = [42, x]
+    let mut x = Dynamic::from_array(vec![Dynamic::from(42 as INT)]).into_shared();
+    let x2 = x.clone();
+    x.write_lock::<Array>().unwrap().push(x2);
+
+    // y = [42, y]
+    let mut y = Dynamic::from_array(vec![Dynamic::from(42 as INT)]).into_shared();
+    let y2 = y.clone();
+    y.write_lock::<Array>().unwrap().push(y2);
+
+    // m = #{ a: [m], x: x }
+    let mut m = Dynamic::from_map(Map::new()).into_shared();
+    let a = Dynamic::from_array(vec![m.clone()]).into_shared();
+    m.write_lock::<Map>().unwrap().insert("a".into(), a);
+    m.write_lock::<Map>().unwrap().insert("x".into(), x.clone());
+
+    let mut scope = Scope::new();
+    scope.push("x", x.clone()).push("y", y).push("m", m.clone());
+
+    // Equality
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "x == x").unwrap());
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "x == y").unwrap());
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "x == [42, [42, x]]").unwrap());
+    assert!(!engine.eval_with_scope::<bool>(&mut scope, "x == [42, [42, [42]]]").unwrap());
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "x != [42, [0, y]]").unwrap());
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "m == m").unwrap());
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "m == #{ a: [m], x: y }").unwrap());
+    assert!(!engine.eval_with_scope::<bool>(&mut scope, "m == #{ a: [#{}], x: x }").unwrap());
+
+    // Printing
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "x.to_string()").unwrap(), "[42, <shared #1>]");
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "to_debug(m)").unwrap(), r#"#{"a": [#{"a": <shared #1>, "x": [42, <shared #3>]}], "x": <shared #3>}"#);
+
+    // Flattening
+    assert!(matches!(*x.flatten_deep().unwrap_err(), EvalAltResult::ErrorCyclicData(ref t, ..) if t == "array"));
+    assert!(matches!(*m.flatten_deep().unwrap_err(), EvalAltResult::ErrorCyclicData(ref t, ..) if t == "map"));
+
+    let z = Dynamic::from_array(vec![Dynamic::from(1 as INT).into_shared(), Dynamic::from_array(vec![Dynamic::from(2 as INT).into_shared()]).into_shared()]);
+    let z = z.flatten_deep().unwrap();
+    assert_eq!(format!("{z:?}"), "[1, [2]]");
+}
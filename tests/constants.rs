@@ -120,3 +120,82 @@ fn test_constant_mut() {
 
     assert!(matches!(*engine.run_with_scope(&mut scope, "MY_NUMBER.value = 42;").unwrap_err(), EvalAltResult::ErrorNonPureMethodCallOnConstant(..)));
 }
+
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_constant_global() {
+    let mut engine = Engine::new();
+
+    engine.set_global_constants([("ANSWER", 40 as INT), ("STEP", 2 as INT)]);
+
+    assert_eq!(engine.eval::<INT>("fn calc() { global::ANSWER + global::STEP } calc()").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("global::ANSWER").unwrap(), 40);
+    assert_eq!(engine.eval::<INT>("const ANSWER = 1; fn calc() { global::ANSWER + global::STEP } calc()").unwrap(), 3);
+
+    // Constants defined by a script do not leak into the next evaluation
+    assert_eq!(engine.eval::<INT>("const EXTRA = 1; fn calc() { global::EXTRA } calc()").unwrap(), 1);
+    assert!(matches!(
+        *engine.eval::<INT>("fn calc() { global::EXTRA } calc()").unwrap_err(),
+        EvalAltResult::ErrorInFunctionCall(.., err, _) if matches!(*err, EvalAltResult::ErrorVariableNotFound(..))
+    ));
+
+    let ast = engine
+        .compile(
+            "
+                const ANSWER = 42;
+                let x = 1;
+                const NAME = `x = ${x}`;
+                { const LOCAL = 0; }
+                fn calc() { global::ANSWER }
+            ",
+        )
+        .unwrap();
+
+    let constants = ast.iter_global_constants().map(|(name, value)| (name, value.map(|v| v.as_int().unwrap()))).collect::<Vec<_>>();
+
+    assert_eq!(constants, [("ANSWER", Some(42)), ("NAME", None)]);
+
+    engine.set_global_constants(Vec::<(&str, INT)>::new());
+
+    assert!(engine.eval::<INT>("global::STEP").is_err());
+}
+
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_constant_global_mut() {
+    #[derive(Debug, Clone)]
+    struct TestStruct(INT);
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<TestStruct>("TestStruct")
+        .register_fn("new_ts", || TestStruct(123))
+        .register_get("value", |obj: &mut TestStruct| obj.0)
+        .register_fn("update_value", |obj: &mut TestStruct, value: INT| obj.0 = value);
+
+    engine.set_global_constants([("SEEDED", rhai::Dynamic::from(TestStruct(1)))]);
+
+    assert_eq!(engine.eval::<INT>("fn calc() { global::SEEDED.value } calc()").unwrap(), 1);
+
+    for script in [
+        "global::SEEDED.update_value(42);",
+        "fn calc() { global::SEEDED.update_value(42); } calc();",
+        "const MY_NUMBER = new_ts(); fn calc() { global::MY_NUMBER.update_value(42); } calc();",
+        r#"fn calc() { global::SEEDED.call(Fn("update_value"), 42); } calc();"#,
+    ] {
+        let err = engine.run(script).unwrap_err();
+        let err = match *err {
+            EvalAltResult::ErrorInFunctionCall(.., err, _) => err,
+            _ => err,
+        };
+        assert!(matches!(*err, EvalAltResult::ErrorNonPureMethodCallOnConstant(ref f, ..) if f == "update_value"), "{}", err);
+    }
+
+    // Copies of global constants can be mutated
+    assert_eq!(engine.eval::<INT>("let x = global::SEEDED; x.update_value(42); x.value").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("fn calc() { global::SEEDED.value } calc()").unwrap(), 1);
+}
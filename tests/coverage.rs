@@ -0,0 +1,82 @@
+#![cfg(not(feature = "no_position"))]
+use rhai::coverage::CoverageCollector;
+use rhai::{Engine, INT};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_coverage() {
+    let mut engine = Engine::new();
+    let coverage = CoverageCollector::new();
+    coverage.attach(&mut engine);
+
+    let mut ast = engine
+        .compile(
+            "
+                let x = 40;
+                if x > 0 {
+                    x += 2;
+                } else {
+                    x = 0;
+                }
+                x
+            ",
+        )
+        .unwrap();
+    ast.set_source("test");
+
+    assert_eq!(coverage.uncovered_lines(&ast), [2, 3, 4, 6, 8]);
+
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+    assert_eq!(coverage.uncovered_lines(&ast), [6]);
+    assert_eq!(coverage.hits(Some("test"), 4), 1);
+    assert_eq!(coverage.hits(None, 4), 0);
+
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+    assert_eq!(coverage.covered_lines(Some("test")), [(2, 2), (3, 2), (4, 2), (8, 2)]);
+
+    coverage.clear();
+    assert_eq!(coverage.hits(Some("test"), 4), 0);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let ast = engine
+            .compile(
+                "
+                    fn foo(x) {
+                        if x { 1 } else { 2 }
+                    }
+                    fn bar() {
+                        42
+                    }
+                    foo(true)
+                ",
+            )
+            .unwrap();
+
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 1);
+        assert_eq!(coverage.uncovered_lines(&ast), [6]);
+    }
+}
+
+#[test]
+fn test_coverage_node_ids() {
+    let mut engine = Engine::new();
+    let stmts = Arc::new(Mutex::new(Vec::new()));
+    let log = stmts.clone();
+
+    engine.on_statement(move |source, pos, id| log.lock().unwrap().push((source.map(str::to_string), pos.line().unwrap(), id)));
+
+    engine.run("let x = 1;\nx += 1;").unwrap();
+    assert_eq!(*stmts.lock().unwrap(), [(None, 1, 0), (None, 2, 0)]);
+
+    stmts.lock().unwrap().clear();
+    engine.set_assign_node_ids(true);
+
+    let ast = engine.compile("let x = 1;\nx += 1;").unwrap();
+    engine.run_ast(&ast).unwrap();
+
+    let ids: Vec<_> = stmts.lock().unwrap().iter().map(|&(.., id)| id).collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.iter().all(|&id| id != 0));
+    assert_ne!(ids[0], ids[1]);
+}
@@ -1,6 +1,6 @@
 #![cfg(not(feature = "no_custom_syntax"))]
 
-use rhai::{Dynamic, Engine, EvalAltResult, ImmutableString, LexError, ParseErrorType, Position, Scope, INT};
+use rhai::{CustomSyntaxPosition, Dynamic, Engine, EvalAltResult, ImmutableString, LexError, ParseErrorType, Position, Scope, INT};
 
 #[test]
 fn test_custom_syntax() {
@@ -225,6 +225,105 @@ fn test_custom_syntax() {
     assert_eq!(scope.len(), 1);
 }
 
+#[test]
+fn test_custom_syntax_position() {
+    let mut engine = Engine::new();
+
+    engine
+        .register_custom_syntax_with_position(["retry", "$int$", "$block$"], false, CustomSyntaxPosition::Statement, |context, inputs| {
+            let count = inputs[0].get_literal_value::<INT>().unwrap();
+            let mut result = Dynamic::UNIT;
+            for _ in 0..count {
+                result = context.eval_expression_tree(&inputs[1])?;
+            }
+            Ok(result)
+        })
+        .unwrap()
+        .register_custom_syntax_with_position(["calc", "$expr$"], false, CustomSyntaxPosition::Expression, |context, inputs| Ok((context.eval_expression_tree(&inputs[0])?.as_int().unwrap() * 2).into()))
+        .unwrap();
+
+    assert_eq!(engine.eval::<INT>("let x = 0; retry 3 { x += 1; } x").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("let x = calc 21; x").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("40 + calc 1").unwrap(), 42);
+
+    let err = engine.compile("let x = 0; let y = retry 3 { x += 1; };").unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::CustomSyntaxStatementOnly("retry".to_string()));
+    assert_eq!(err.position(), Position::new(1, 20));
+
+    let err = engine.compile("let x = 0; foo(retry 3 { x += 1; });").unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::CustomSyntaxStatementOnly("retry".to_string()));
+
+    let err = engine.compile("let x = 0; calc x + 1; x").unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::CustomSyntaxExpressionOnly("calc".to_string()));
+    assert_eq!(err.position(), Position::new(1, 12));
+
+    // Ambiguous registrations are rejected
+    assert_eq!(
+        *engine.register_custom_syntax(["retry", "$expr$"], false, |_, _| Ok(Dynamic::UNIT)).unwrap_err().err_type(),
+        ParseErrorType::BadInput(LexError::ImproperSymbol("retry".to_string(), "Custom syntax starting with 'retry' conflicts with an existing custom syntax 'retry'".to_string()))
+    );
+
+    engine.register_custom_operator("mix", 160).unwrap();
+
+    assert_eq!(
+        *engine.register_custom_syntax(["mix", "$expr$"], false, |_, _| Ok(Dynamic::UNIT)).unwrap_err().err_type(),
+        ParseErrorType::BadInput(LexError::ImproperSymbol("mix".to_string(), "Custom syntax starting with 'mix' conflicts with a custom operator 'mix'".to_string()))
+    );
+
+    // Distinguishable registrations are accepted
+    engine.register_custom_syntax(["retry_all", "$block$"], false, |_, _| Ok(Dynamic::UNIT)).unwrap();
+}
+
+#[test]
+fn test_custom_syntax_repeat() {
+    let mut engine = Engine::new();
+
+    engine
+        .register_custom_syntax(["pipeline", "$expr$", "$rep$", "=>", "$expr$", "end"], false, |context, inputs| {
+            let mut stages = Vec::new();
+            for input in inputs {
+                stages.push(context.eval_expression_tree(input)?.to_string());
+            }
+            Ok(stages.join(" -> ").into())
+        })
+        .unwrap();
+
+    assert_eq!(engine.eval::<String>("pipeline 1 end").unwrap(), "1");
+    assert_eq!(engine.eval::<String>("let x = 2; pipeline 1 => x => x + 1 end").unwrap(), "1 -> 2 -> 3");
+
+    let script = format!("pipeline {} end", (1..=10).map(|i| i.to_string()).collect::<Vec<_>>().join(" => "));
+    assert_eq!(engine.eval::<String>(&script).unwrap(), (1..=10).map(|i| i.to_string()).collect::<Vec<_>>().join(" -> "));
+
+    let err = engine.compile("pipeline 1 => 2").unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::MissingToken("end".to_string(), "for 'pipeline' expression".to_string()));
+
+    let err = engine.compile("pipeline 1 => 2 ; 42").unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::MissingToken("end".to_string(), "for 'pipeline' expression".to_string()));
+    assert_eq!(err.position(), Position::new(1, 17));
+
+    // Repeated group starting with a marker
+    engine
+        .register_custom_syntax(["sum", "$rep$", "$expr$", ";"], false, |context, inputs| {
+            let mut total = 0;
+            for input in inputs {
+                total += context.eval_expression_tree(input)?.as_int().unwrap();
+            }
+            Ok(total.into())
+        })
+        .unwrap();
+
+    assert_eq!(engine.eval::<INT>("sum 1 2 3; sum 40 2;").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("sum ; 42").unwrap(), 42);
+
+    // Improper repetitions are rejected
+    for symbols in [&["bad", "$rep$", "end"][..], &["bad", "$rep$", "$expr$"], &["bad", "$rep$", "|", "$expr$", "|"], &["bad", "$rep$", "$expr$", "$expr$"], &["bad", "$rep$", "$expr$", "$rep$", "end"]] {
+        assert!(matches!(
+            engine.register_custom_syntax(symbols, false, |_, _| Ok(Dynamic::UNIT)).unwrap_err().err_type(),
+            ParseErrorType::BadInput(LexError::ImproperSymbol(..))
+        ));
+    }
+}
+
 #[test]
 fn test_custom_syntax_scope() {
     let mut engine = Engine::new();
@@ -377,6 +476,69 @@ fn test_custom_syntax_raw() {
     assert_eq!(*engine.compile("hello hey").unwrap_err().err_type(), ParseErrorType::BadInput(LexError::ImproperSymbol("hey".to_string(), String::new())));
 }
 
+#[test]
+fn test_custom_syntax_raw_new_vars() {
+    let mut engine = Engine::new();
+
+    engine.set_strict_variables(true);
+
+    // let2 $ident$, $ident$ = $expr$;
+    engine.register_custom_syntax_with_state_raw(
+        "let2",
+        |stream, _, _| match stream.len() {
+            1 | 3 => Ok(Some("$ident$".into())),
+            2 => Ok(Some(",".into())),
+            4 => Ok(Some(format!("$var${}", stream[1]).into())),
+            5 => Ok(Some(format!("$var${}", stream[3]).into())),
+            6 => Ok(Some("=".into())),
+            7 => Ok(Some("$expr$".into())),
+            _ => Ok(None),
+        },
+        false,
+        |context, inputs, _| {
+            let x = inputs[0].get_string_value().unwrap().to_string();
+            let y = inputs[1].get_string_value().unwrap().to_string();
+            let value = context.eval_expression_tree(&inputs[2])?.as_int().unwrap();
+            context.scope_mut().push(x, value).push(y, value * 2);
+            Ok(Dynamic::UNIT)
+        },
+    );
+
+    assert_eq!(engine.eval::<INT>("let2 x, y = 14; x + y").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("let z = 1; let2 x, y = 10; let w = 1; z + x + y + w").unwrap(), 32);
+    assert_eq!(engine.eval::<INT>("let x = 1; let y = 2; let2 x, y = x + y; x * 10 + y").unwrap(), 36);
+    assert_eq!(engine.eval::<INT>("let x = 40; { let2 x, y = 1; } x + 2").unwrap(), 42);
+
+    // Variables are not visible inside the custom syntax itself
+    assert_eq!(*engine.compile("let2 x, y = x;").unwrap_err().err_type(), ParseErrorType::VariableUndefined("x".to_string()));
+
+    // Variables are resolved without searching the scope
+    #[cfg(feature = "internals")]
+    {
+        let ast = engine.compile("let2 x, y = 1; y").unwrap();
+
+        match ast.statements().last().unwrap() {
+            rhai::Stmt::Expr(expr) => match &**expr {
+                rhai::Expr::Variable(x, ..) => assert_eq!(x.0.map(|i| i.get()), Some(1)),
+                expr => panic!("{:?}", expr),
+            },
+            stmt => panic!("{:?}", stmt),
+        }
+    }
+
+    engine.register_custom_syntax_with_state_raw(
+        "bad_var",
+        |stream, _, _| match stream.len() {
+            1 => Ok(Some("$var$123".into())),
+            _ => Ok(None),
+        },
+        false,
+        |_, _, _| Ok(Dynamic::UNIT),
+    );
+
+    assert_eq!(*engine.compile("bad_var").unwrap_err().err_type(), ParseErrorType::VariableExpected);
+}
+
 #[test]
 fn test_custom_syntax_raw2() {
     let mut engine = Engine::new();
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "unchecked"))]
-use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+use rhai::{Engine, EvalAltResult, LimitInfo, LimitKind, ParseErrorType, INT};
 
 #[cfg(not(feature = "no_index"))]
 use rhai::Array;
@@ -87,6 +87,14 @@ fn test_max_array_size() {
         EvalAltResult::ErrorDataTooLarge(..)
     ));
 
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(engine.eval::<Array>("[1,2,3,4,5,6,7,8].partition(|v| v > 4)").unwrap().len(), 2);
+        assert!(matches!(*engine.run("[1,2,3,4,5,6,7,8,9].partition(|v| v > 4)").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+        assert!(matches!(*engine.run("[1,2,3,4,5,6,7,8,9].chunk_by(|v| v % 2)").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    }
+
     #[cfg(not(feature = "no_closure"))]
     assert_eq!(
         engine
@@ -336,3 +344,40 @@ fn test_max_map_size() {
         4
     );
 }
+
+#[test]
+fn test_data_size_limit_info() {
+    let mut engine = Engine::new();
+    engine.set_max_string_size(10);
+
+    match *engine.run(r#"let x = "hello, "; let y = "world!"; x + y"#).unwrap_err() {
+        EvalAltResult::ErrorDataTooLarge(typ, info, ..) => {
+            assert_eq!(typ, "Length of string");
+            assert_eq!(info, LimitInfo::new(LimitKind::StringLength, 10, 13));
+        }
+        err => panic!("{}", err),
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        engine.set_max_array_size(5);
+
+        match *engine.run("let x = [1, 2, 3]; x + [4, 5, 6]").unwrap_err() {
+            EvalAltResult::ErrorDataTooLarge(_, info, ..) => assert_eq!(info, LimitInfo::new(LimitKind::ArraySize, 5, 6)),
+            err => panic!("{}", err),
+        }
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        engine.set_max_map_size(2);
+
+        let err = engine.run("let x = #{a: 1, b: 2}; x.c = 3;").unwrap_err();
+        assert_eq!(err.to_string(), "Size of object map too large (line 1, position 28)");
+
+        match *err {
+            EvalAltResult::ErrorDataTooLarge(_, info, ..) => assert_eq!(info, LimitInfo::new(LimitKind::MapSize, 2, 3)),
+            err => panic!("{}", err),
+        }
+    }
+}
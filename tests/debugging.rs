@@ -71,3 +71,48 @@ fn test_debugger_state() {
 
     engine.run("let x = 42;").unwrap();
 }
+
+#[test]
+fn test_debugger_modify_variables() {
+    let mut engine = Engine::new();
+
+    engine.register_fn("tick", |_: INT| ());
+
+    engine.register_debugger(
+        |_, mut debugger| {
+            debugger.break_points_mut().push(rhai::debugger::BreakPoint::AtFunctionName { name: "tick".into(), enabled: true });
+            debugger
+        },
+        |mut context, event, _, _, _| {
+            if let rhai::debugger::DebuggerEvent::BreakPoint(..) = event {
+                // Exit the loop early by setting the loop counter
+                if context.scope().get_value::<INT>("i") == Some(3) {
+                    context.scope_mut().set_value("i", 100 as INT);
+                }
+                // Patch `this` inside methods
+                if context.this_ptr().is_some() {
+                    context.set_this(41 as INT)?;
+                }
+            }
+            Ok(rhai::debugger::DebuggerCommand::Continue)
+        },
+    );
+
+    assert_eq!(engine.eval::<INT>("let i = 0; let n = 0; while i < 10 { tick(i); i += 1; n += 1; } n + i").unwrap(), 105);
+
+    // Push new variables while in the middle of the loop
+    assert_eq!(engine.eval::<INT>("let n = 0; let i = 0; while i < 10 { let x = 1; tick(i); i += x; n += 1; } n + i").unwrap(), 105);
+
+    #[cfg(not(feature = "no_closure"))]
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<INT>("let i = 0; let f = || i; while i < 10 { tick(i); i += 1; } f.call()").unwrap(), 101);
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn inc() { tick(this); this += 1; } let x = 1; x.inc(); x").unwrap(), 42);
+
+        assert!(engine.eval::<INT>("fn inc() { tick(this); this += 1; } const x = 1; x.inc(); x").is_err());
+    }
+}
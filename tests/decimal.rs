@@ -0,0 +1,46 @@
+#![cfg(feature = "decimal")]
+use rhai::{Engine, EvalAltResult};
+use rust_decimal::{Decimal, RoundingStrategy};
+
+#[test]
+fn test_decimal_scale() {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("1") / 8"#).unwrap().to_string(), "0.125");
+
+    engine.set_decimal_scale(Some(2));
+
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("1") / 8"#).unwrap().to_string(), "0.12");
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("3") / 8"#).unwrap().to_string(), "0.38");
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("-1") / 8"#).unwrap().to_string(), "-0.12");
+    assert_eq!(engine.eval::<Decimal>(r#"let x = parse_decimal("5"); x /= 8; x"#).unwrap().to_string(), "0.62");
+    assert_eq!(engine.eval::<Decimal>(r#"let x = parse_decimal("0.5"); x * parse_decimal("0.25")"#).unwrap().to_string(), "0.12");
+
+    engine.set_decimal_rounding(RoundingStrategy::MidpointAwayFromZero);
+
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("1") / 8"#).unwrap().to_string(), "0.13");
+    assert_eq!(engine.eval::<Decimal>(r#"let x = parse_decimal("5"); x /= 8; x"#).unwrap().to_string(), "0.63");
+
+    engine.set_decimal_scale(None);
+
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("1") / 8"#).unwrap().to_string(), "0.125");
+}
+
+#[test]
+fn test_decimal_rescale() {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("2.345").round_to(2)"#).unwrap().to_string(), "2.34");
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("2.355").round_to(2)"#).unwrap().to_string(), "2.36");
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("1.5").with_scale(3)"#).unwrap().to_string(), "1.500");
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("2.345").with_scale(2)"#).unwrap().to_string(), "2.34");
+    assert_eq!(engine.eval::<Decimal>(r#"let x = parse_decimal("2.345"); x.rescale(2); x"#).unwrap().to_string(), "2.34");
+    assert_eq!(engine.eval::<Decimal>(r#"let x = parse_decimal("7"); x.rescale(2); x"#).unwrap().to_string(), "7.00");
+
+    assert!(matches!(*engine.eval::<Decimal>(r#"parse_decimal("1.5").round_to(-1)"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<Decimal>(r#"parse_decimal("1.5").with_scale(29)"#).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    engine.set_decimal_rounding(RoundingStrategy::ToZero);
+
+    assert_eq!(engine.eval::<Decimal>(r#"parse_decimal("2.349").round_to(2)"#).unwrap().to_string(), "2.34");
+}
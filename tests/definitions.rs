@@ -0,0 +1,119 @@
+#![cfg(feature = "internals")]
+#![cfg(feature = "metadata")]
+#![cfg(not(feature = "no_module"))]
+use rhai::{Dynamic, Engine, Module, Scope, INT};
+
+#[test]
+fn test_definitions_ts() {
+    #[derive(Debug, Clone)]
+    struct TestStruct {
+        field: INT,
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<TestStruct>("TestStruct")
+        .register_get("field", |x: &mut TestStruct| x.field)
+        .register_fn("+", |x: TestStruct, y: TestStruct| TestStruct { field: x.field + y.field })
+        .register_fn_with_metadata("update", |x: &mut TestStruct, value: INT| x.field = value, &["obj", "value"], "", &["/// Update the field."])
+        .register_fn_with_metadata("new_ts", |field: INT| TestStruct { field }, &["field"], "", &["/// Create a new `TestStruct`.", "/// Hello!"]);
+
+    let mut module = Module::new();
+    module.set_var("ANSWER", 42 as INT);
+    let hash = module.set_native_fn("greet", |name: &str| Ok(format!("hello, {name}")));
+    module.update_fn_metadata_with_comments(hash, ["name: &str", "String"], ["/// Say hello."]);
+    engine.register_static_module("util", module.into());
+
+    let mut scope = Scope::new();
+    scope.push_constant("LIMIT", 10 as INT);
+
+    let definitions = engine.definitions_with_scope(&scope).include_standard_packages(false);
+
+    assert_eq!(
+        definitions.ts_format(),
+        r#"declare interface TestStruct {
+    readonly field: number;
+    "+"(arg1: TestStruct): TestStruct;
+    /**
+     * Update the field.
+     */
+    update(value: number): void;
+}
+
+/**
+ * Create a new `TestStruct`.
+ * Hello!
+ */
+declare function new_ts(field: number): TestStruct;
+/**
+ * Update the field.
+ */
+declare function update(obj: TestStruct, value: number): void;
+
+declare namespace util {
+    const ANSWER: number;
+    /**
+     * Say hello.
+     */
+    function greet(name: string): string;
+}
+
+/**
+ * Value: `10`
+ */
+declare const LIMIT: number;
+"#
+    );
+
+    let files = definitions.iter_ts_files().map(|(name, _)| name).collect::<Vec<_>>();
+    assert_eq!(files, ["__static__.d.ts", "__scope__.d.ts", "util.d.ts"]);
+}
+
+#[test]
+fn test_definitions_scope() {
+    #[derive(Debug, Clone)]
+    struct TestStruct;
+
+    let mut engine = Engine::new();
+    engine.register_type_with_name::<TestStruct>("TestStruct");
+
+    let mut scope = Scope::new();
+    scope.push_constant("LIMIT", 10 as INT);
+    scope.push_constant("GREETING", "hello");
+    scope.push("obj", TestStruct);
+    #[cfg(not(feature = "no_closure"))]
+    scope.push_dynamic("shared", Dynamic::from(42 as INT).into_shared());
+    #[cfg(feature = "no_closure")]
+    scope.push("shared", 42 as INT);
+
+    let definitions = engine.definitions_with_scope(&scope);
+
+    assert_eq!(
+        definitions.scope_items(),
+        r#"/// Value: `10`
+const LIMIT: int;
+
+/// Value: `"hello"`
+const GREETING: String;
+
+let obj: TestStruct;
+
+let shared: int;"#
+    );
+
+    assert_eq!(
+        definitions.iter_ts_files().find(|(name, _)| name == "__scope__.d.ts").unwrap().1,
+        r#"/**
+ * Value: `10`
+ */
+declare const LIMIT: number;
+/**
+ * Value: `"hello"`
+ */
+declare const GREETING: string;
+declare let obj: TestStruct;
+declare let shared: number;
+"#
+    );
+}
@@ -1,4 +1,4 @@
-use rhai::{Engine, LexError, ParseErrorType, Scope, INT};
+use rhai::{Engine, EvalAltResult, LexError, ParseErrorType, Scope, INT};
 
 #[test]
 fn test_eval() {
@@ -165,3 +165,50 @@ fn test_eval_disabled() {
         ParseErrorType::BadInput(LexError::ImproperSymbol(err, ..)) if err == "eval"
     ));
 }
+
+#[test]
+fn test_eval_disabled_function() {
+    let mut engine = Engine::new();
+
+    engine.disable_function("eval").disable_function("abs");
+
+    assert!(engine.compile(r#"eval("40 + 2")"#).is_ok());
+
+    let err = engine.run(r#"eval("40 + 2")"#).unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(ref f, ..) if f == "eval (disabled by host)"));
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.to_string(), "Function not found: eval (disabled by host) (line 1, position 1)");
+
+    // Function pointers and method-call style are also blocked
+    assert!(matches!(*engine.run(r#"call(Fn("abs"), -1)"#).unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(*engine.run("let x = -1; x.abs()").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    // Other functions work
+    assert_eq!(engine.eval::<INT>(r#"let x = "hello"; len(x) + max(37, 4)"#).unwrap(), 42);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        engine.disable_function("foo");
+        assert!(matches!(*engine.run("fn foo() { 42 } foo()").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+        assert_eq!(engine.eval::<INT>("fn bar() { 42 } bar()").unwrap(), 42);
+    }
+
+    #[cfg(not(feature = "no_module"))]
+    {
+        let mut module = rhai::Module::new();
+        module.set_native_fn("calc", |x: INT| Ok(x * 2));
+        engine.register_static_module("test", module.into());
+
+        engine.disable_function("test::calc");
+        assert!(matches!(*engine.run("test::calc(21)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(ref f, ..) if f == "test::calc (disabled by host)"));
+
+        engine.enable_function("test::calc");
+        assert_eq!(engine.eval::<INT>("test::calc(21)").unwrap(), 42);
+    }
+
+    engine.enable_function("eval");
+    assert!(!engine.is_function_disabled("eval"));
+    assert!(engine.is_function_disabled("abs"));
+    assert_eq!(engine.eval::<INT>(r#"eval("40 + 2")"#).unwrap(), 42);
+}
@@ -0,0 +1,79 @@
+#![cfg(not(feature = "no_std"))]
+use rhai::{Engine, EvalAltResult, INT};
+
+#[test]
+fn test_files_missing() {
+    let engine = Engine::new();
+    let path = std::env::temp_dir().join(format!("rhai-test-missing-{}.rhai", std::process::id()));
+
+    match *engine.eval_file::<INT>(path.clone()).unwrap_err() {
+        EvalAltResult::ErrorSystem(msg, err) => {
+            assert!(msg.contains(&*path.to_string_lossy()), "{}", msg);
+            assert_eq!(err.downcast_ref::<std::io::Error>().unwrap().kind(), std::io::ErrorKind::NotFound);
+        }
+        err => panic!("wrong error: {}", err),
+    }
+
+    assert!(matches!(*engine.run_file(path.clone()).unwrap_err(), EvalAltResult::ErrorSystem(..)));
+    assert!(matches!(*engine.compile_file(path).unwrap_err(), EvalAltResult::ErrorSystem(..)));
+}
+
+#[test]
+fn test_files_reader() {
+    let engine = Engine::new();
+
+    let ast = engine.compile_reader("#!/usr/bin/env rhai\nlet x = 40;\nx + 2".as_bytes(), "reader").unwrap();
+    assert_eq!(ast.source(), Some("reader"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    let err = engine.compile_reader(&b"40 + \xff"[..], "invalid").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorSystem(ref msg, ..) if msg.contains("'invalid'")));
+
+    assert!(matches!(*engine.compile_reader("40 +".as_bytes(), "broken").unwrap_err(), EvalAltResult::ErrorParsing(..)));
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_files_max_size() {
+    use std::io::Read;
+
+    let dir = std::env::temp_dir().join(format!("rhai-test-files-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("small.rhai"), "40 + 2").unwrap();
+    std::fs::write(dir.join("large.rhai"), format!("{}42", " ".repeat(1000))).unwrap();
+
+    let mut engine = Engine::new();
+    engine.set_max_script_file_size(100);
+    assert_eq!(engine.max_script_file_size(), 100);
+
+    assert_eq!(engine.eval_file::<INT>(dir.join("small.rhai")).unwrap(), 42);
+
+    match *engine.eval_file::<INT>(dir.join("large.rhai")).unwrap_err() {
+        EvalAltResult::ErrorSystem(msg, err) => {
+            assert!(msg.contains("large.rhai"), "{}", msg);
+            assert_eq!(err.to_string(), "larger than the maximum size of 100 bytes");
+        }
+        err => panic!("wrong error: {}", err),
+    }
+
+    // Readers are not read beyond the limit
+    let reader = std::io::repeat(b' ').take(98).chain("42".as_bytes());
+    assert_eq!(engine.eval_ast::<INT>(&engine.compile_reader(reader, "exact").unwrap()).unwrap(), 42);
+    assert!(matches!(*engine.compile_reader(std::io::repeat(b' '), "endless").unwrap_err(), EvalAltResult::ErrorSystem(..)));
+
+    #[cfg(not(feature = "no_module"))]
+    {
+        use rhai::module_resolvers::FileModuleResolver;
+
+        engine.set_module_resolver(FileModuleResolver::new_with_path(&dir));
+
+        assert_eq!(engine.eval::<INT>(r#"import "small" as m; 42"#).unwrap(), 42);
+        assert!(matches!(*engine.run(r#"import "large" as m;"#).unwrap_err(), EvalAltResult::ErrorInModule(ref name, ref err, ..) if name == "large" && matches!(**err, EvalAltResult::ErrorSystem(..))));
+        assert!(matches!(*engine.run(r#"import "missing" as m;"#).unwrap_err(), EvalAltResult::ErrorModuleNotFound(..)));
+    }
+
+    engine.set_max_script_file_size(0);
+    assert_eq!(engine.eval_file::<INT>(dir.join("large.rhai")).unwrap(), 42);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
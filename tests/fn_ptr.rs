@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, FnPtr, INT};
+use rhai::{Dynamic, Engine, EvalAltResult, FnPtr, Scope, INT};
 
 #[test]
 fn test_fn_ptr() {
@@ -173,3 +173,128 @@ fn test_fn_ptr_make_closure() {
     // 'f' captures: the Engine, the AST, and the closure
     assert_eq!(f(42).unwrap(), "hello42");
 }
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_fn_ptr_collect() {
+    let engine = Engine::new();
+    let mut scope = rhai::Scope::new();
+    scope.push("base", 100 as INT);
+
+    let ast = engine
+        .compile(
+            r#"
+                fn on_init() { helper(1) }
+                fn on_init(x) { helper(x) }
+                fn helper(x) { x * 3 }
+                private fn on_private() { 0 }
+
+                let on_tick = |n| helper(n) + 1;
+                let on_tick = |n| helper(n) + 2;    // shadowed
+                let on_count = 42;                  // not a function pointer
+                let tick_count = 0;
+                let on_fn = Fn("on_init");
+            "#,
+        )
+        .unwrap();
+
+    let handlers = engine.collect_fn_ptrs(&mut scope, &ast, "on_").unwrap();
+
+    drop(ast);
+
+    assert_eq!(handlers.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), ["on_tick", "on_fn", "on_init", "on_private"]);
+
+    assert_eq!(handlers[0].1.call_with_environ::<INT>(&engine, (10 as INT,)).unwrap(), 32);
+    assert_eq!(handlers[1].1.call_with_environ::<INT>(&engine, ()).unwrap(), 3);
+    assert_eq!(handlers[1].1.call_with_environ::<INT>(&engine, (5 as INT,)).unwrap(), 15);
+    assert_eq!(handlers[2].1.call_with_environ::<INT>(&engine, ()).unwrap(), 3);
+    assert_eq!(handlers[2].1.call_with_environ::<INT>(&engine, (7 as INT,)).unwrap(), 21);
+    assert_eq!(handlers[3].1.call_with_environ::<INT>(&engine, ()).unwrap(), 0);
+
+    // Variables defined by the script are left in the scope
+    assert_eq!(scope.get_value::<INT>("on_count").unwrap(), 42);
+
+    // Global constants and imported modules
+    #[cfg(not(feature = "no_module"))]
+    {
+        let mut engine = Engine::new();
+        let mut resolver = rhai::module_resolvers::StaticModuleResolver::new();
+        let mut module = rhai::Module::new();
+        module.set_var("OFFSET", 2 as INT);
+        resolver.insert("offsets", module);
+        engine.set_module_resolver(resolver);
+
+        let ast = engine.compile(r#"import "offsets" as o; const FACTOR = 5; fn on_calc(x) { x * global::FACTOR + o::OFFSET }"#).unwrap();
+        let handlers = engine.collect_fn_ptrs(&mut rhai::Scope::new(), &ast, "on_").unwrap();
+
+        assert_eq!(handlers[0].1.call_with_environ::<INT>(&engine, (10 as INT,)).unwrap(), 52);
+    }
+
+    // Closures capturing variables
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let ast = engine.compile("let count = 0; let on_tick = || { count += 1; count };").unwrap();
+        let handlers = engine.collect_fn_ptrs(&mut rhai::Scope::new(), &ast, "on_").unwrap();
+
+        assert_eq!(handlers.len(), 1);
+        assert_eq!(handlers[0].1.call_with_environ::<INT>(&engine, ()).unwrap(), 1);
+        assert_eq!(handlers[0].1.call_with_environ::<INT>(&engine, ()).unwrap(), 2);
+    }
+
+    // Binding an environment manually
+    let ast = engine.compile("fn foo(x) { bar(x) + 1 } fn bar(x) { x * 2 }").unwrap();
+
+    let mut fn_ptr = FnPtr::new("foo").unwrap();
+    assert!(matches!(*fn_ptr.call_with_environ::<INT>(&engine, (20 as INT,)).unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    fn_ptr.bind_environ(&ast);
+    drop(ast);
+
+    assert_eq!(fn_ptr.call_with_environ::<INT>(&engine, (20 as INT,)).unwrap(), 41);
+}
+
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_fn_ptr_native_closure() {
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let offset = 100 as INT;
+
+    let add = FnPtr::from_fn("add", move |_, args| {
+        let sum = args.iter().map(|v| v.as_int().unwrap()).sum::<INT>();
+        Ok((sum + offset).into())
+    })
+    .unwrap();
+    assert!(add.is_native());
+    assert_eq!(add.to_string(), "Fn(add)");
+
+    scope.push("add", add.clone());
+
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "add.call(1, 2)").unwrap(), 103);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "call(add, 1, 2, 3)").unwrap(), 106);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "let f = add.curry(10); f.call(1)").unwrap(), 111);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "let f = curry(add, 10, 20); call(f, 1)").unwrap(), 131);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "let x = 42; x.call(add, 1)").unwrap(), 143);
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "add.curry(1).to_string()").unwrap(), "Fn(add)");
+
+    // The object is passed as the first argument
+    let count = FnPtr::from_fn("count", |_, args| Ok(format!("{}:{}", args.len(), args[0].type_name()).into())).unwrap();
+    scope.push("count", count);
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "let m = #{ count: count.curry(1) }; m.count(2)").unwrap(), "3:map");
+
+    // The closure is not registered by name
+    assert!(matches!(*engine.eval_with_scope::<INT>(&mut scope, "add(1, 2)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+    assert!(matches!(*engine.eval::<INT>("call(Fn(\"add\"), 1, 2)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    // Call from Rust
+    let ast = engine.compile("").unwrap();
+    assert_eq!(add.call::<INT>(&engine, &ast, (1 as INT, 2 as INT)).unwrap(), 103);
+
+    // Errors from the closure are propagated
+    let fail = FnPtr::from_fn("fail", |_, _| Err("boo!".into())).unwrap();
+    scope.push("fail", fail);
+    assert!(matches!(*engine.eval_with_scope::<INT>(&mut scope, "fail.call()").unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+
+    assert!(FnPtr::from_fn("+", |_, _| Ok(Dynamic::UNIT)).is_err());
+}
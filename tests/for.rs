@@ -1,4 +1,4 @@
-use rhai::{Engine, Module, INT};
+use rhai::{Engine, EvalAltResult, Module, Scope, INT};
 
 #[cfg(not(feature = "no_float"))]
 use rhai::FLOAT;
@@ -316,6 +316,55 @@ fn test_for_overflow() {
     assert_eq!(engine.eval::<INT>(script).unwrap(), 0);
 }
 
+#[test]
+fn test_for_step_by() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in (0..10).step_by(3) { sum = sum * 10 + x; } sum").unwrap(), 369);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in (1..=7).step_by(3) { sum = sum * 10 + x; } sum").unwrap(), 147);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in (10..0).step_by(-3) { sum = sum * 100 + x; } sum").unwrap(), 10070401);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in (9..=1).step_by(-4) { sum = sum * 10 + x; } sum").unwrap(), 951);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in range(9, 0, -4) { sum = sum * 10 + x; } sum").unwrap(), 951);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in (5..5).step_by(-1) { sum += 1; } sum").unwrap(), 0);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in (5..=5).step_by(-1) { sum += x; } sum").unwrap(), 5);
+    assert_eq!(engine.eval::<String>("type_of((0..10).step_by(2))").unwrap(), "range");
+
+    assert!(matches!(*engine.run("(0..10).step_by(-1)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.run("(10..=0).step_by(2)").unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<FLOAT>("let sum = 0.0; for x in (0.0..1.0).step_by(0.25) { sum += x; } sum").unwrap(), 1.5);
+        assert_eq!(engine.eval::<FLOAT>("let sum = 0.0; for x in (0.0..=1.0).step_by(0.25) { sum += x; } sum").unwrap(), 2.5);
+        assert_eq!(engine.eval::<FLOAT>("let sum = 0.0; for x in (1.0..=0.0).step_by(-0.5) { sum += x; } sum").unwrap(), 1.5);
+    }
+}
+
+#[test]
+fn test_range_contains() {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>("3 in 1..5").unwrap());
+    assert!(!engine.eval::<bool>("5 in 1..5").unwrap());
+    assert!(engine.eval::<bool>("5 in 1..=5").unwrap());
+    assert_eq!(engine.eval::<String>("type_of(1..5)").unwrap(), "range");
+    assert_eq!(engine.eval::<String>("type_of(1..=5)").unwrap(), "range=");
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert!(engine.eval::<bool>("1.5 in 1.0..2.0").unwrap());
+        assert!(!engine.eval::<bool>("2.0 in 1.0..2.0").unwrap());
+        assert!(engine.eval::<bool>("2.0 in 1.0..=2.0").unwrap());
+        assert!(engine.eval::<bool>("2 in 1.0..=2.0").unwrap());
+        assert!(!engine.eval::<bool>("0.5 in 1..=2.0").unwrap());
+        assert!(engine.eval::<bool>("(2.0..1.0).is_empty").unwrap());
+        assert_eq!(engine.eval::<FLOAT>("(1.0..=2.5).end").unwrap(), 2.5);
+        assert_eq!(engine.eval::<String>("type_of(1.0..2.0)").unwrap(), "range");
+        assert_eq!(engine.eval::<String>("type_of(1.0..=2.0)").unwrap(), "range=");
+        assert_eq!(engine.eval::<String>("`${1.0..=2.5}`").unwrap(), "1.0..=2.5");
+    }
+}
+
 #[test]
 fn test_for_string() {
     let engine = Engine::new();
@@ -381,6 +430,82 @@ fn test_for_object() {
     assert_eq!(engine.eval::<INT>(script).unwrap(), 9);
 }
 
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_for_object_entries() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"let s = ""; for (k, v) in #{a: 1, b: 2, c: 3} { s += `${k}=${v};`; } s"#).unwrap(), "a=1;b=2;c=3;");
+    assert_eq!(engine.eval::<String>(r#"let s = ""; let m = #{a: 1, b: 2}; for (k, v, i) in m { s += `${i}:${k}=${v};`; } s"#).unwrap(), "0:a=1;1:b=2;");
+    assert_eq!(engine.eval::<String>(r#"let s = ""; for x in #{a: 1, b: 2} { s += `${x};`; } s"#).unwrap(), r#"["a", 1];["b", 2];"#);
+    assert_eq!(engine.eval::<INT>("let n = 0; for (k, v) in #{} { n += 1; } n").unwrap(), 0);
+
+    assert_eq!(engine.eval::<rhai::Array>("let m = #{a: 1, bbb: 2, cc: 3}; m.keys_sorted_by(|x, y| y.len - x.len)").unwrap().into_iter().map(|v| v.into_string().unwrap()).collect::<Vec<_>>(), ["bbb", "cc", "a"]);
+    assert_eq!(engine.eval::<rhai::Array>("let m = #{a: 1, bbb: 2, cc: 3}; m.values_sorted_by_key(|x, y| y.len - x.len)").unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>(), [2, 3, 1]);
+    assert!(engine.eval::<rhai::Array>(r#"#{a: 1, b: 2}.keys_sorted_by(|x, y| "oops")"#).is_err());
+
+    let script = r#"
+        let m = #{alice: 3, bob: 1, carol: 2};
+        let w = m;
+        let s = "";
+        for (k, v) in m.keys_sorted_by(|x, y| w[x] - w[y]).map(|k| [k, w[k]]) {
+            s += `${k}=${v};`;
+        }
+        s
+    "#;
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(engine.eval::<String>(script).unwrap(), "bob=1;carol=2;alice=3;");
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_for_destructure() {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let sum = 0; for (a, b) in [[1, 2], [3, 4]] { sum += a * b; } sum").unwrap(), 14);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for (a, b, i) in [[1, 2], [3, 4]] { sum += (a + b) * i; } sum").unwrap(), 7);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for (a, b, c) in [[1, 2, 3], [4, 5, 6]] { sum += a * b * c; } sum").unwrap(), 126);
+
+    // Items that are not two-element arrays are paired with a counter
+    assert_eq!(engine.eval::<INT>("let sum = 0; for (x, i) in [10, 20, 30] { sum += x * i; } sum").unwrap(), 80);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for (x, i) in [[1, 2, 3], [4]] { sum += x[0] * i; } sum").unwrap(), 4);
+
+    // Arity mismatches
+    let err = engine.run("for (a, b, c) in [1, 2] { print(a); }").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorMismatchDataType(ref e, ..) if e == "an array of 2 or 3 elements"));
+    let err = engine.run("for (a, b, c) in [[1, 2, 3, 4]] { print(a); }").unwrap_err();
+    assert!(err.to_string().starts_with("Data type incorrect: an array of 4 elements (expecting an array of 2 or 3 elements)"), "{}", err);
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.position(), rhai::Position::new(1, 6));
+
+    assert!(matches!(engine.compile("for (a, b, c, d) in [] { }").unwrap_err().err_type(), rhai::ParseErrorType::MissingToken(t, ..) if t == ")"));
+    assert!(matches!(engine.compile("for (a, b, a) in [] { }").unwrap_err().err_type(), rhai::ParseErrorType::DuplicatedVariable(v) if v == "a"));
+
+    // Custom iterators yielding pairs
+    engine.register_iterator::<MyPairs>();
+    engine.register_fn("pairs", |n: INT| MyPairs(n));
+
+    assert_eq!(engine.eval::<INT>("let sum = 0; for (a, b) in pairs(3) { sum += a * b; } sum").unwrap(), 2 + 6 + 12);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for (a, b, i) in pairs(3) { sum += b * i; } sum").unwrap(), 3 + 4 * 2);
+    assert_eq!(engine.eval::<INT>("let sum = 0; for p in pairs(3) { sum += p[0]; } sum").unwrap(), 6);
+}
+
+#[cfg(not(feature = "no_index"))]
+#[derive(Debug, Clone)]
+struct MyPairs(INT);
+
+#[cfg(not(feature = "no_index"))]
+impl IntoIterator for MyPairs {
+    type Item = rhai::Array;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        (1..=self.0).map(|x| vec![x.into(), (x + 1).into()]).collect::<Vec<_>>().into_iter()
+    }
+}
+
 #[derive(Debug, Clone)]
 struct MyIterableType(String);
 
@@ -395,6 +520,54 @@ impl IntoIterator for MyIterableType {
     }
 }
 
+#[derive(Debug, Clone)]
+struct MyFallibleIterableType(INT);
+
+impl IntoIterator for MyFallibleIterableType {
+    type Item = Result<INT, Box<EvalAltResult>>;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    #[inline]
+    #[must_use]
+    fn into_iter(self) -> Self::IntoIter {
+        (1..=self.0).map(|x| if x < 3 { Ok(x) } else { Err(format!("cannot read item {x}").into()) }).collect::<Vec<_>>().into_iter()
+    }
+}
+
+#[test]
+fn test_for_fallible_iterator() {
+    let mut engine = Engine::new();
+
+    engine.register_iterator_result::<MyFallibleIterableType, INT>();
+    engine.register_fn("new_items", MyFallibleIterableType);
+
+    let mut scope = Scope::new();
+    scope.push("sum", 0 as INT);
+
+    let err = engine
+        .run_with_scope(
+            &mut scope,
+            "
+                let items = new_items(5);
+                for x in items {
+                    sum += x;
+                }
+            ",
+        )
+        .unwrap_err();
+
+    // The loop is aborted at the third item
+    assert_eq!(scope.get_value::<INT>("sum").unwrap(), 3);
+    assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.clone().into_string().unwrap() == "cannot read item 3"));
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.position(), rhai::Position::new(3, 17));
+
+    assert_eq!(engine.eval::<INT>("let sum = 0; for x in new_items(2) { sum += x; } sum").unwrap(), 3);
+
+    // Errors are not ignored even if the loop body is empty
+    assert!(engine.run("for x in new_items(3) { }").is_err());
+}
+
 #[cfg(not(feature = "no_module"))]
 #[test]
 fn test_for_module_iterator() {
@@ -0,0 +1,132 @@
+use rhai::{Engine, FormatOptions};
+
+/// Format a script, then check that formatting is stable and that the formatted script compiles
+/// to the same [`AST`][rhai::AST] as the original.
+fn check_format(engine: &Engine, script: &str, options: FormatOptions) -> String {
+    let formatted = engine.format_script(script, options).unwrap_or_else(|err| panic!("cannot format:\n{}\n{}", script, err));
+    let formatted2 = engine.format_script(&formatted, options).unwrap_or_else(|err| panic!("cannot format again:\n{}\n{}", formatted, err));
+
+    assert_eq!(formatted2, formatted, "unstable formatting for:\n{script}");
+    assert_eq!(engine.compact_script(&formatted).unwrap(), engine.compact_script(script).unwrap(), "different tokens for:\n{formatted}");
+
+    #[cfg(feature = "internals")]
+    assert_eq!(engine.compile(&formatted).unwrap().to_source(), engine.compile(script).unwrap().to_source(), "different AST for:\n{formatted}");
+
+    formatted
+}
+
+#[cfg(not(any(feature = "no_position", feature = "no_object", feature = "no_index")))]
+#[test]
+fn test_format_script() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        check_format(&engine, "let x=0xff_ff;// answer\n\n\n\nif x>42{print(x)}else{x+=1;/* add */}", FormatOptions::new()),
+        "let x = 0xff_ff; // answer\n\nif x > 42 {\n    print(x)\n} else {\n    x += 1; /* add */\n}\n"
+    );
+
+    assert_eq!(
+        check_format(
+            &engine,
+            "
+                // leading comment
+                let  x  =  [ 1 , 2 , -3 ] ;   // trailing comment
+                x . len ( )
+            ",
+            FormatOptions::new().with_indent_width(2).preserve_comments(false)
+        ),
+        "let x = [1, 2, -3];\nx.len()\n"
+    );
+
+    assert_eq!(
+        check_format(&engine, "let a=[1,2,3];let b=[1000,2000,3000,// large\n4000];a.len()", FormatOptions::new().with_max_line_len(16)),
+        "let a = [\n    1,\n    2,\n    3\n];\nlet b = [\n    1000,\n    2000,\n    3000, // large\n    4000\n];\na.len()\n"
+    );
+
+    assert_eq!(check_format(&engine, "let s=`x = ${ 40+2 }!`;s", FormatOptions::new()), "let s = `x = ${40 + 2}!`;\ns\n");
+    assert_eq!(check_format(&engine, "", FormatOptions::new()), "");
+
+    assert!(engine.format_script("let x = ;", FormatOptions::new()).is_err());
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(feature = "metadata")]
+#[test]
+fn test_format_script_doc_comments() {
+    let engine = Engine::new();
+
+    assert_eq!(
+        check_format(&engine, "//! Module.\n// Not a doc-comment.\n/// Add one.\nfn inc(x){x+1}", FormatOptions::new().preserve_comments(false)),
+        "//! Module.\n/// Add one.\nfn inc(x) {\n    x + 1\n}\n"
+    );
+}
+
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "only_i32")))]
+const SCRIPTS: &[&str] = &[
+    "let x=- -1;let y=-x;let z=!!true;let w=x- -1;let r=0..-1;let s=-(-x);[x,y,z,w,r,s,- - -x,-1_000]",
+    "let x = 10; x -= 3; x *= 2; x /= 7; x %= 5; x <<= 4; x >>= 1; x |= 1; x &= 13; x ^= 6; x **= 2; x",
+    "let x=0xff_ff+0b101+0o17+1_000;let f=1.5e3+1e-7+0.5*2.;x+f",
+    "let x=0;do{x+=1;}while x<10;do{x+=2}until x>=20;loop{break;}while false{}x",
+    "let sum=0;for(x,i)in[10,20,30]{sum+=x*i;}for x in 0..10{sum+=x;}for x in 0..=10{sum+=x;}sum",
+    r#"let r="";try{throw "oops";}catch(err){r=err;}try{throw 1}catch{r+="!"}r"#,
+    "let x='c';switch x{'a'|'b'=>1,'c' if true=>{let y=2;y},_=>3}",
+    "let x=42;switch x{1=>1,2 if x>0=>2,0..10=>3,40..=50=>4,_=>5}",
+    "let m=#{a:#{b:[1,2,3]}};let n=();[m?.a?.b?[1]+m.a.b[0],n?.x,n?[0]]",
+    "let a=[];let b=#{};let c=();[a.len()+b.len(),c,2 in[1,2],3 !in[1],()??1]",
+    "let s=`a ${if true {1} else {2}} b ${#{a:1}.a} c ${`inner ${1+1}`}`;s",
+    "let x=if true{1}else{2}+3;let y={let z=x;{z*2}};x+y",
+    "fn add(x,y=1,z=x*y){x+y+z}private fn sub(x,y){return x-y;}add(1)+add(1,2)+sub(5,3)",
+    "fn log(fmt,...args){fmt+args.len()}log(\"a\")+log(\"b\",1,2)",
+    "fn area(width,height){width*height}area(height:2,width:3)+area(4,height:5)",
+    "fn inc(){this+=1;}let x=41;x.inc();x",
+    "fn foo(){x}let x=42;foo!()+foo!(  )",
+    "let f=|x,y|x+y;let g=||42;let h=|x|{let y=x*2;y};f.call(1,2)+g.call()+h.call(3)",
+    "let a=[1,2,3,4,5];a.map(|x|x*2).filter(|x|x>4).reduce(|sum,x|sum+x,0)",
+    "let obj=#{value:1,action:||this.value+=1,get:|x|{this.value+x}};obj.action();obj.get(1)",
+    "fn f(a,b,c){a+b+c}f(1111111111,2222222222,3333333333)+f(f(1111111111,2222222222,3333333333),f(4444444444,5555555555,6666666666),7)",
+    "let x=[1,// one\n2,/* two */3,\n// before four\n4,];x.len()",
+    "let x = 1; // c1\n// c2\n\n\nlet y = 2; /* c3 */ let z = 3;\nx + y + z // c4\n\n// at the end\n",
+    "fn f(x){\n\n  // leading\n  x // trailing\n\n  // closing\n}\nf(1)",
+];
+
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "only_i32")))]
+#[test]
+fn test_format_script_round_trip() {
+    let engine = Engine::new();
+
+    for options in [FormatOptions::new(), FormatOptions::new().with_indent_width(2).with_max_line_len(20), FormatOptions::new().preserve_comments(false)] {
+        for script in SCRIPTS {
+            let formatted = check_format(&engine, script, options);
+
+            let result = engine.eval::<rhai::Dynamic>(script).unwrap();
+            let result2 = engine.eval::<rhai::Dynamic>(&formatted).unwrap();
+            assert_eq!(format!("{result:?}"), format!("{result2:?}"), "different results for:\n{formatted}");
+        }
+    }
+}
+
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "no_module", feature = "only_i32")))]
+#[test]
+fn test_format_script_examples() {
+    let mut engine = Engine::new();
+    #[cfg(not(feature = "unchecked"))]
+    engine.set_max_expr_depths(0, 0);
+
+    let mut count = 0;
+
+    for entry in std::fs::read_dir("scripts").unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.to_string_lossy();
+
+        // Skip definition files
+        if !name.ends_with(".rhai") || name.ends_with(".d.rhai") {
+            continue;
+        }
+
+        let script = std::fs::read_to_string(&path).unwrap();
+        check_format(&engine, &script, FormatOptions::new());
+        count += 1;
+    }
+
+    assert!(count > 0);
+}
@@ -238,3 +238,131 @@ fn test_functions_bang() {
         123
     );
 }
+
+#[test]
+fn test_functions_dispatch() {
+    let mut engine = Engine::new();
+
+    engine.register_fn("handle_native", |x: INT| x * 2);
+    engine.register_fn("secret", |x: INT| x);
+
+    let script = r#"
+        fn handle_script(x, y) { x + y }
+        fn hidden(x) { x }
+    "#;
+
+    // Without an allowlist, `dispatch` is an ordinary function call
+    assert!(matches!(*engine.run(&format!(r#"{script} dispatch("handle_", "script", 1, 2)"#)).unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    engine.set_dynamic_dispatch_allowlist(["handle_*", "exact"]);
+
+    assert_eq!(engine.eval::<INT>(&format!(r#"{script} dispatch("handle_", "script", 40, 2)"#)).unwrap(), 42);
+    assert_eq!(engine.eval::<INT>(&format!(r#"{script} let e = "native"; dispatch("handle_", e, 21)"#)).unwrap(), 42);
+    assert_eq!(engine.eval::<INT>(r#"let sum = 0; for x in 0..4 { sum += dispatch("handle_", "native", x) } sum"#).unwrap(), 12);
+
+    // Names outside the allowlist are rejected even if the function exists
+    for (prefix, suffix) in [("sec", "ret"), ("hid", "den"), ("exa", "ctly"), ("handle", "")] {
+        match *engine.run(&format!(r#"{script} dispatch("{prefix}", "{suffix}", 1)"#)).unwrap_err() {
+            EvalAltResult::ErrorDispatchNotAllowed(name, ..) => assert_eq!(name, format!("{prefix}{suffix}")),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    // Allowed names that do not exist are not found
+    assert!(matches!(
+        *engine.run(r#"dispatch("handle_", "missing", 1)"#).unwrap_err(),
+        EvalAltResult::ErrorFunctionNotFound(ref sig, ..) if sig.starts_with("handle_missing")
+    ));
+    assert!(matches!(*engine.run(r#"dispatch("handle_", 42, 1)"#).unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+
+    engine.clear_dynamic_dispatch_allowlist();
+    assert!(!engine.is_dynamic_dispatch_enabled());
+}
+
+#[test]
+fn test_functions_trace() {
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, RwLock};
+
+    const SCRIPT: &str = "
+        fn fib(n) {
+            if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+        }
+        let x = fib(7);
+        print(x);
+        x
+    ";
+
+    let mut engine = Engine::new();
+    engine.on_print(|_| ());
+
+    // Histogram of function name => (number of calls, is native, maximum call level)
+    let histogram = Arc::new(RwLock::new(BTreeMap::<String, (usize, bool, usize)>::new()));
+    let hist = histogram.clone();
+
+    engine.on_fn_call(move |info| {
+        assert!(info.elapsed().is_none());
+
+        let mut hist = hist.write().unwrap();
+        let entry = hist.entry(info.name().to_string()).or_insert((0, info.is_native(), 0));
+        entry.0 += 1;
+        entry.2 = entry.2.max(info.level());
+    });
+
+    assert_eq!(engine.eval::<INT>(SCRIPT).unwrap(), 13);
+
+    let hist = histogram.read().unwrap();
+    assert_eq!(hist["fib"], (41, false, 7));
+    assert_eq!(hist["print"], (1, true, 1));
+
+    #[cfg(not(feature = "no_time"))]
+    {
+        let counts = Arc::new(RwLock::new((0, 0)));
+        let c = counts.clone();
+
+        engine.on_fn_call_timed(move |info| {
+            if info.name() != "fib" {
+                return;
+            }
+            let mut counts = c.write().unwrap();
+            match info.elapsed() {
+                None => counts.0 += 1,
+                Some(_) => counts.1 += 1,
+            }
+        });
+
+        assert_eq!(engine.eval::<INT>(SCRIPT).unwrap(), 13);
+        assert_eq!(*counts.read().unwrap(), (41, 41));
+    }
+}
+
+#[cfg(feature = "metadata")]
+#[cfg(not(feature = "only_i32"))]
+#[test]
+fn test_functions_native_metadata() {
+    let mut engine = Engine::new();
+
+    engine.register_fn("plain", |x: INT, y: bool| if y { x } else { 0 });
+    engine.register_fn_with_metadata("documented", |x: INT, y: bool| if y { x } else { 0 }, &["value", "flag: Flag"], "Value", &["/// Hello world!"]);
+    engine.register_fn_with_metadata("partial", |x: INT, _: INT| x, &["x"], "", &[]);
+
+    let json: serde_json::Value = serde_json::from_str(&engine.gen_fn_metadata_to_json(false).unwrap()).unwrap();
+    let functions = json["functions"].as_array().unwrap();
+    let find = |name: &str| functions.iter().find(|f| f["name"] == name).unwrap();
+
+    let plain = find("plain");
+    assert_eq!(plain["params"], serde_json::json!([{ "type": "i64" }, { "type": "bool" }]));
+    assert_eq!(plain["returnType"], "i64");
+    assert!(plain.get("docComments").is_none());
+
+    let documented = find("documented");
+    assert_eq!(documented["params"], serde_json::json!([{ "name": "value", "type": "i64" }, { "name": "flag", "type": "Flag" }]));
+    assert_eq!(documented["returnType"], "Value");
+    assert_eq!(documented["docComments"], serde_json::json!(["/// Hello world!"]));
+    assert_eq!(documented["signature"], "documented(value: i64, flag: Flag) -> Value");
+
+    let partial = find("partial");
+    assert_eq!(partial["params"], serde_json::json!([{ "name": "x", "type": "i64" }, { "type": "i64" }]));
+
+    assert_eq!(engine.eval::<INT>("documented(42, true) + partial(1, 2)").unwrap(), 43);
+}
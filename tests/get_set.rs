@@ -319,6 +319,52 @@ fn test_get_set_indexer() {
     );
 }
 
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_get_set_indexer_op_assignment() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone)]
+    struct Values(Vec<INT>);
+
+    #[derive(Debug, Clone)]
+    struct Wrapper(Values);
+
+    let mut engine = Engine::new();
+    let counter = Arc::new(AtomicUsize::new(0));
+    let c = counter.clone();
+
+    // Index function with side effects
+    engine.register_fn("index", move |i: INT| {
+        c.fetch_add(1, Ordering::Relaxed);
+        i
+    });
+
+    // Only get/set indexers, no access by reference
+    engine
+        .register_type_with_name::<Values>("Values")
+        .register_fn("new_values", || Values(vec![1, 2, 3]))
+        .register_indexer_get(|v: &mut Values, i: INT| v.0[i as usize])
+        .register_indexer_set(|v: &mut Values, i: INT, x: INT| v.0[i as usize] = x)
+        .register_type_with_name::<Wrapper>("Wrapper")
+        .register_fn("new_wrapper", || Wrapper(Values(vec![1, 2, 3])))
+        .register_get_set("values", |w: &mut Wrapper| w.0.clone(), |w: &mut Wrapper, v: Values| w.0 = v);
+
+    for (script, expected, count) in [
+        ("let a = [1, 2, 3]; a[index(1)] += 10; a[1]", 12, 1),
+        ("let v = new_values(); v[index(1)] += 10; v[1]", 12, 1),
+        ("let v = new_values(); v[index(2)] *= 10; v[2]", 30, 1),
+        ("let v = [new_values()]; v[0][index(1)] -= 10; v[0][1]", -8, 1),
+        ("let w = new_wrapper(); w.values[index(1)] += 10; w.values[1]", 12, 1),
+        ("let a = [[1, 2], [3, 4]]; a[index(1)][index(0)] += 10; a[1][0]", 13, 2),
+    ] {
+        counter.store(0, Ordering::Relaxed);
+        assert_eq!(engine.eval::<INT>(script).unwrap(), expected, "{}", script);
+        assert_eq!(counter.load(Ordering::Relaxed), count, "{}", script);
+    }
+}
+
 #[test]
 fn test_get_set_elvis() {
     let engine = Engine::new();
@@ -328,3 +374,26 @@ fn test_get_set_elvis() {
     engine.eval::<()>("let x = #{a:()}; x.a?.foo.bar.baz").unwrap();
     assert_eq!(engine.eval::<String>("let x = 'x'; x?.type_of()").unwrap(), "char");
 }
+
+#[test]
+fn test_get_set_optional() {
+    #[derive(Clone)]
+    struct TestStruct {
+        x: Option<INT>,
+    }
+
+    let mut engine = Engine::new();
+
+    engine.register_type::<TestStruct>();
+    engine.register_get_opt("x", |value: &mut TestStruct| value.x);
+    engine.register_fn("new_ts", |x: INT| TestStruct { x: if x > 0 { Some(x) } else { None } });
+
+    assert_eq!(engine.eval::<INT>("let a = new_ts(42); a.x").unwrap(), 42);
+    assert!(engine.eval::<bool>("let a = new_ts(0); a.x == ()").unwrap());
+    assert_eq!(engine.eval::<INT>("let a = new_ts(0); a.x ?? 123").unwrap(), 123);
+
+    engine.set_fail_on_invalid_map_property(true);
+
+    assert_eq!(engine.eval::<INT>("let a = new_ts(42); a.x").unwrap(), 42);
+    assert!(matches!(*engine.run("let a = new_ts(0); a.x").unwrap_err(), EvalAltResult::ErrorPropertyNotFound(p, ..) if p == "x"));
+}
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "no_function"))]
-use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+use rhai::{Engine, EvalAltResult, LanguageVersion, ParseErrorType, INT};
 
 #[test]
 fn test_internal_fn() {
@@ -128,6 +128,223 @@ fn test_internal_fn_params() {
         ParseErrorType::FnDuplicatedParam(a, b) if a == "hello" && b == "x"));
 }
 
+#[test]
+fn test_internal_fn_defaults() {
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn add_me(a, b = 2, c = a * 10) { a + b + c }
+                    add_me(1) + add_me(1, 3) * 100 + add_me(1, 3, 5) * 10000
+                "
+            )
+            .unwrap(),
+        13 + 1400 + 90000
+    );
+
+    assert!(engine
+        .eval::<bool>(
+            r#"
+                fn foo(x, y = 1) { x + y }
+                is_def_fn("foo", 1) && is_def_fn("foo", 2) && !is_def_fn("foo", 0)
+            "#
+        )
+        .unwrap());
+
+    assert_eq!(
+        *engine
+            .compile(
+                "
+                    fn abc(x, y = 1) { x + y }
+                    fn abc(x) { x - 42 }
+                "
+            )
+            .unwrap_err()
+            .err_type(),
+        ParseErrorType::FnDuplicatedDefinition("abc".to_string(), 1)
+    );
+    assert_eq!(
+        *engine
+            .compile(
+                "
+                    fn abc(x, y, z) { x + y + z }
+                    fn abc(x, y = 1, z = 2) { x - 42 }
+                "
+            )
+            .unwrap_err()
+            .err_type(),
+        ParseErrorType::FnDuplicatedDefinition("abc".to_string(), 3)
+    );
+    assert!(engine
+        .compile(
+            "
+                fn abc(x, y = 1) { x + y }
+                fn abc(x, y, z) { x - 42 }
+            "
+        )
+        .is_ok());
+
+    assert!(matches!(engine.compile("fn abc(x = 1, y) { x + y }").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+
+    assert!(matches!(*engine.run("fn abc(x, y = x.len()) { x + y } abc(42)").unwrap_err(), EvalAltResult::ErrorInFunctionCall(..)));
+
+    engine.set_language_version(LanguageVersion::new(1, 16));
+    assert!(engine.compile("fn abc(x, y = 1) { x + y }").is_err());
+    assert!(engine.compile("fn abc(x, y) { x + y }").is_ok());
+}
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_internal_fn_variadic() {
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<String>(
+                r#"
+                    fn log(fmt, ...args) { `${fmt}: ${args.len()} ${args}` }
+                    [log("a"), log("b", 1), log("c", 1, 2, 3, 4, 5)].reduce(|acc, s| acc + "|" + s, "")
+                "#
+            )
+            .unwrap(),
+        "|a: 0 []|b: 1 [1]|c: 5 [1, 2, 3, 4, 5]"
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn sum(...values) { values.reduce(|acc, v| acc + v, 0) }
+                    let f = Fn(\"sum\").curry(1).curry(2);
+                    sum() + f.call() * 10 + f.call(3, 4) * 100
+                "
+            )
+            .unwrap(),
+        3 * 10 + 10 * 100
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn add(x, y = 10, ...rest) { x + y + rest.len() }
+                    add(1) + add(1, 2) * 100 + add(1, 2, 3, 4) * 10000
+                "
+            )
+            .unwrap(),
+        11 + 300 + 50000
+    );
+
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn push_all(...items) { for item in items { this.push(item); } }
+                    let a = [];
+                    a.push_all();
+                    a.push_all(1, 2, 3);
+                    a.len()
+                "
+            )
+            .unwrap(),
+        3
+    );
+
+    assert!(engine
+        .eval::<bool>(
+            r#"
+                fn foo(x, ...y) { x }
+                is_def_fn("foo", 1) && is_def_fn("foo", 2) && is_def_fn("foo", 10) && !is_def_fn("foo", 0)
+            "#
+        )
+        .unwrap());
+
+    assert!(matches!(*engine.run("fn foo(x, y, ...z) { x } foo(1)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    assert_eq!(*engine.compile("fn foo(x, ...y) { x } fn foo(...z) { 0 }").unwrap_err().err_type(), ParseErrorType::FnDuplicatedDefinition("foo".to_string(), 0));
+    assert_eq!(*engine.compile("fn foo(x, ...y) { x } fn foo(x) { 0 }").unwrap_err().err_type(), ParseErrorType::FnDuplicatedDefinition("foo".to_string(), 1));
+    assert!(engine.compile("fn foo(x, ...y) { x } fn foo(x, y) { 0 }").is_ok());
+
+    assert!(matches!(engine.compile("fn foo(...x, y) { x }").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+
+    engine.set_language_version(LanguageVersion::new(1, 16));
+    assert!(engine.compile("fn foo(x, ...y) { x }").is_err());
+}
+
+#[test]
+fn test_internal_fn_named_args() {
+    let mut engine = Engine::new();
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn make_widget(width, height) { width * 100 + height }
+                    make_widget(width: 1, height: 2) + make_widget(height: 2, width: 1) * 1000
+                "
+            )
+            .unwrap(),
+        102 + 102000
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn foo(a, b, c) { a * 100 + b * 10 + c }
+                    foo(1, c: 3, b: 2) + foo(1, 2, c: 3,)
+                "
+            )
+            .unwrap(),
+        246
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn foo(x, y = 2, z = x + y) { x * 100 + y * 10 + z }
+                    foo(y: 5, x: 1) + foo(z: 0, x: 1, y: 1) * 1000
+                "
+            )
+            .unwrap(),
+        156 + 110000
+    );
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<INT>("fn foo(x, y, ...rest) { x - y + rest.len() } foo(y: 1, x: 10)").unwrap(), 9);
+
+    assert_eq!(engine.eval::<INT>("fn foo(x, y) { x - y } let x = 42; foo(y: x, x: 50)").unwrap(), 8);
+
+    let err = engine.run("fn foo(x, y) { x - y } foo(1, z: 2)").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(ref f, ..) if f.contains("'z'")));
+    assert_eq!(err.position(), rhai::Position::new(1, 31));
+
+    let err = engine.run("fn foo(x, y) { x - y } foo(1, x: 2)").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(ref f, ..) if f.contains("'x'")));
+    assert_eq!(err.position(), rhai::Position::new(1, 31));
+
+    assert!(matches!(*engine.run("fn foo(x, y = 1, z = 2) { x } foo(1, z: 2)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(ref f, ..) if f.contains("'y'")));
+    assert!(matches!(*engine.run("fn foo(x, y, z) { x } foo(1, z: 2)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+    assert!(matches!(*engine.run("fn foo(x, y) { x } foo(1, 2, y: 3)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+    assert!(matches!(*engine.run("abs(x: 1)").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    let err = engine.compile("fn foo(x, y) { x } foo(x: 1, x: 2)").unwrap_err();
+    assert!(matches!(err.err_type(), ParseErrorType::FnDuplicatedParam(a, b) if a == "foo" && b == "x"));
+    assert_eq!(err.position(), rhai::Position::new(1, 30));
+
+    assert!(matches!(engine.compile("fn foo(x, y) { x } foo(y: 1, 2)").unwrap_err().err_type(), ParseErrorType::MissingToken(..)));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(engine.compile("fn foo(x) { x } let a = 1; a.foo(x: 1)").is_err());
+
+    engine.set_language_version(LanguageVersion::new(1, 16));
+    assert!(engine.compile("fn foo(x, y) { x } foo(x: 1, y: 2)").is_err());
+}
+
 #[test]
 fn test_function_pointers() {
     let engine = Engine::new();
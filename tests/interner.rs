@@ -0,0 +1,52 @@
+use rhai::{Engine, Scope, INT};
+
+#[test]
+fn test_interner_bounded() {
+    let mut engine = Engine::new();
+
+    engine.set_max_strings_interned(100);
+    assert_eq!(engine.max_strings_interned(), 100);
+
+    for i in 0..1000 {
+        let script = (0..100)
+            .map(|j| format!("let x{i}_{j} = {j}; x{i}_{j};"))
+            .collect::<String>();
+
+        engine.compile(script).unwrap();
+
+        let stats = engine.interner_stats();
+        assert!(stats.count <= 100, "{:?}", stats);
+    }
+
+    let stats = engine.interner_stats();
+    assert!(stats.count > 0);
+    assert!(stats.bytes >= stats.count);
+    assert_eq!(stats.max, 100);
+
+    engine.set_max_strings_interned(10);
+    assert!(engine.interner_stats().count <= 10);
+
+    engine.set_max_strings_interned(0);
+    assert_eq!(engine.max_strings_interned(), 0);
+    assert_eq!(engine.interner_stats().count, 0);
+    assert_eq!(engine.eval::<INT>("let foo = 42; foo").unwrap(), 42);
+}
+
+#[test]
+fn test_interner_clear() {
+    let mut engine = Engine::new();
+
+    let ast = engine
+        .compile("let hello = 40; let world = 2; hello + world")
+        .unwrap();
+
+    engine.clear_string_interner();
+    assert_eq!(engine.interner_stats().count, 0);
+
+    // Strings already handed out are not affected
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    let mut scope = Scope::new();
+    engine.run_ast_with_scope(&mut scope, &ast).unwrap();
+    assert_eq!(scope.get_value::<INT>("hello").unwrap(), 40);
+}
@@ -228,6 +228,119 @@ fn test_map_json() {
     assert!(matches!(*engine.parse_json("{a:`hello${world}`}", true).unwrap_err(), EvalAltResult::ErrorParsing(..)));
 }
 
+#[test]
+fn test_map_json_full() {
+    let mut engine = Engine::new();
+
+    let value = engine
+        .parse_json_full(r#" { "a": null, "c": {}, "d": "\"\\\/\b\f\n\r\t\u0041\ud83d\ude00", "e": -0 } "#)
+        .unwrap();
+    let map = value.cast::<Map>();
+    assert!(map["a"].is_unit());
+    assert_eq!(map["d"].clone().into_string().unwrap(), "\"\\/\u{8}\u{c}\n\r\tA\u{1f600}");
+    assert_eq!(map["e"].as_int().unwrap(), 0);
+
+    // Top-level values
+    assert!(engine.parse_json_full("null").unwrap().is_unit());
+    assert!(engine.parse_json_full(" true ").unwrap().as_bool().unwrap());
+    assert_eq!(engine.parse_json_full("-42").unwrap().as_int().unwrap(), -42);
+    assert_eq!(engine.parse_json_full(r#""hello""#).unwrap().into_string().unwrap(), "hello");
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.parse_json_full("[[1, 2], [], [[3]]]").unwrap().into_typed_array::<rhai::Array>().unwrap().len(), 3);
+    #[cfg(feature = "no_index")]
+    assert!(matches!(*engine.parse_json_full("[1]").unwrap_err(), EvalAltResult::ErrorParsing(..)));
+
+    // Numbers
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.parse_json_full("1.5e3").unwrap().as_float().unwrap(), 1500.0);
+        assert_eq!(engine.parse_json_full("-2E-2").unwrap().as_float().unwrap(), -0.02);
+        assert_eq!(engine.parse_json_full("123456789012345678901234567890").unwrap().as_float().unwrap(), 123456789012345678901234567890.0);
+    }
+    #[cfg(feature = "no_float")]
+    {
+        assert!(matches!(*engine.parse_json_full("1.5").unwrap_err(), EvalAltResult::ErrorParsing(..)));
+        assert!(matches!(*engine.parse_json_full("123456789012345678901234567890").unwrap_err(), EvalAltResult::ErrorParsing(..)));
+    }
+
+    // Syntax errors
+    for json in ["", "{", "[1,]", "{\"a\" 1}", "{\"a\":1,}", "{a:1}", "01", "1.", ".5", "1e", "+1", "nul", "'x'", "\"\\x\"", "\"\\ud800\"", "\"a\nb\"", "\"abc", "1 2", "()"] {
+        assert!(matches!(*engine.parse_json_full(json).unwrap_err(), EvalAltResult::ErrorParsing(..)), "{}", json);
+    }
+    #[cfg(not(feature = "no_position"))]
+    assert!(matches!(
+        *engine.parse_json_full("{\n  \"a\": 1,\n  \"b\": x\n}").unwrap_err(),
+        EvalAltResult::ErrorParsing(ParseErrorType::BadInput(..), pos) if pos.line() == Some(3) && pos.position() == Some(8)
+    ));
+
+    // Limits are enforced while parsing
+    #[cfg(not(feature = "unchecked"))]
+    {
+        engine.set_max_expr_depths(4, 4);
+        assert!(engine.parse_json_full(r#"{"a": {"b": {"c": {}}}}"#).is_ok());
+        assert!(matches!(*engine.parse_json_full(r#"{"a": {"b": {"c": {"d": {}}}}}"#).unwrap_err(), EvalAltResult::ErrorParsing(ParseErrorType::ExprTooDeep, ..)));
+        #[cfg(not(feature = "no_index"))]
+        assert!(matches!(*engine.parse_json_full("[[[[[1]]]]]").unwrap_err(), EvalAltResult::ErrorParsing(ParseErrorType::ExprTooDeep, ..)));
+        engine.set_max_expr_depths(0, 0);
+
+        engine.set_max_map_size(2);
+        assert!(engine.parse_json_full(r#"{"a": 1, "b": 2}"#).is_ok());
+        assert!(matches!(*engine.parse_json_full(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap_err(), EvalAltResult::ErrorParsing(ParseErrorType::LiteralTooLarge(..), ..)));
+
+        #[cfg(not(feature = "no_index"))]
+        {
+            engine.set_max_array_size(3);
+            assert!(engine.parse_json_full("[1, 2, 3]").is_ok());
+            assert!(matches!(*engine.parse_json_full("[1, 2, 3, 4]").unwrap_err(), EvalAltResult::ErrorParsing(ParseErrorType::LiteralTooLarge(..), ..)));
+        }
+
+        engine.set_max_string_size(5);
+        assert!(engine.parse_json_full(r#""hello""#).is_ok());
+        assert!(matches!(*engine.parse_json_full(r#"{"a": "hello!"}"#).unwrap_err(), EvalAltResult::ErrorParsing(ParseErrorType::LiteralTooLarge(..), ..)));
+    }
+}
+
+#[test]
+fn test_map_to_json_string() {
+    #[derive(Clone)]
+    struct Point {
+        x: INT,
+        y: INT,
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Point>("Point")
+        .register_fn("new_point", |x: INT, y: INT| Point { x, y })
+        .register_fn("to_string", |p: &mut Point| format!("({}, {})", p.x, p.y));
+
+    let value = engine.eval::<rhai::Dynamic>(r#"#{ a: (), b: true, c: 'x', d: "say \"hi\"\n", e: new_point(1, 2), f: #{} }"#).unwrap();
+
+    assert_eq!(engine.to_json_string(&value, false), r#"{"a":null,"b":true,"c":"x","d":"say \"hi\"\n","e":"(1, 2)","f":{}}"#);
+
+    let json = engine.to_json_string(&value, true);
+    assert_eq!(json, "{\n  \"a\": null,\n  \"b\": true,\n  \"c\": \"x\",\n  \"d\": \"say \\\"hi\\\"\\n\",\n  \"e\": \"(1, 2)\",\n  \"f\": {}\n}");
+
+    let value2 = engine.parse_json_full(&json).unwrap();
+    assert_eq!(engine.to_json_string(&value2, false), engine.to_json_string(&value, false));
+
+    assert_eq!(engine.to_json_string(&().into(), true), "null");
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let value = engine.eval::<rhai::Dynamic>("let b = blob(2, 7); [1, [], [b]]").unwrap();
+        assert_eq!(engine.to_json_string(&value, false), "[1,[],[[7,7]]]");
+        assert_eq!(engine.to_json_string(&value, true), "[\n  1,\n  [],\n  [\n    [\n      7,\n      7\n    ]\n  ]\n]");
+    }
+
+    #[cfg(not(feature = "no_float"))]
+    assert_eq!(engine.to_json_string(&engine.eval::<rhai::Dynamic>("#{ x: 0.0 / 0.0, y: 1.5 }").unwrap(), false), r#"{"x":null,"y":1.5}"#);
+
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(engine.to_json_string(&engine.eval::<rhai::Dynamic>("let x = #{ a: 1 }; let f = || x; f.call()").unwrap(), false), r#"{"a":1}"#);
+}
+
 #[test]
 #[cfg(not(feature = "no_function"))]
 fn test_map_oop() {
@@ -249,3 +362,109 @@ fn test_map_oop() {
         42
     );
 }
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_map_entries() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let m = #{a:1, b:2, c:3}; let e = m.entries(); e.len()").unwrap(), 3);
+    assert_eq!(engine.eval::<String>(r#"let m = #{a:1, b:2}; m.entries()[1][0]"#).unwrap(), "b");
+    assert_eq!(engine.eval::<INT>("let m = #{a:1, b:2, c:3}; let n = from_entries(m.entries()); n.b + n.c").unwrap(), 5);
+    assert_eq!(engine.eval::<INT>(r#"let m = from_entries([["a", 1], ['b', 2], ["a", 40]]); m.a + m.b"#).unwrap(), 42);
+    assert!(engine.eval::<Map>("from_entries([])").unwrap().is_empty());
+
+    let err = engine.run(r#"let m = from_entries([["a", 1], 42]);"#).unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref msg, ..) if msg.to_string().contains("index 1")));
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.position().line(), Some(1));
+
+    let err = engine.run(r#"let m = from_entries([["a", 1, 2]]);"#).unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref msg, ..) if msg.to_string().contains("3 items")));
+
+    let err = engine.run("let m = from_entries([[1, 2]]);").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref msg, ..) if msg.to_string().contains("string name")));
+}
+
+#[test]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_closure"))]
+fn test_map_deep_merge() {
+    let engine = Engine::new();
+
+    let result = engine
+        .eval::<Map>(
+            "
+                let m = #{a:1, b:#{x:1, y:2, z:#{p:1}}, d:true};
+                let n = #{a:41, b:#{y:40, z:#{q:2}, w:3}, c:0, d:#{e:1}};
+                let conflicts = [];
+                let r = m.merge(n, |key, v1, v2| {
+                    conflicts.push(key);
+                    if type_of(v1) == type_of(v2) { v1 + v2 } else { v2 }
+                });
+                r.conflicts = conflicts;
+                r
+            ",
+        )
+        .unwrap();
+
+    assert_eq!(result["a"].as_int().unwrap(), 42);
+    assert_eq!(result["c"].as_int().unwrap(), 0);
+    assert!(result["d"].is_map());
+    let b = result["b"].clone().cast::<Map>();
+    assert_eq!(b["x"].as_int().unwrap(), 1);
+    assert_eq!(b["y"].as_int().unwrap(), 42);
+    assert_eq!(b["w"].as_int().unwrap(), 3);
+    let z = b["z"].clone().cast::<Map>();
+    assert_eq!(z.len(), 2);
+
+    assert_eq!(result["conflicts"].clone().into_array().unwrap().len(), 3);
+
+    // The original map is unchanged
+    assert_eq!(engine.eval::<INT>("let m = #{a:1}; let r = m.merge(#{a:2}, |k, a, b| a + b); m.a * 10 + r.a").unwrap(), 13);
+
+    // Errors in the resolver are propagated
+    assert!(engine.run(r#"#{a:1}.merge(#{a:2}, |k, a, b| throw "conflict on " + k)"#).is_err());
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let mut engine = Engine::new();
+        engine.set_max_map_size(3);
+
+        assert_eq!(engine.eval::<Map>("#{a:1, b:2}.merge(#{b:3, c:4}, |k, a, b| a)").unwrap().len(), 3);
+        assert!(matches!(*engine.run("#{a:1, b:2}.merge(#{c:3, d:4}, |k, a, b| a)").unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+    }
+}
+
+#[test]
+#[cfg(feature = "map_insertion_order")]
+fn test_map_insertion_order() {
+    let engine = Engine::new();
+
+    let map = engine.eval::<Map>(r#"let m = #{z: 1, a: 2, "m": 3}; m.b = 4; m.z = 5; m.remove("a"); m"#).unwrap();
+
+    let mut entries = Vec::new();
+
+    for (k, v) in map.clone() {
+        entries.push((k.to_string(), v.as_int().unwrap()));
+    }
+
+    assert_eq!(entries, [("z".to_string(), 5), ("m".to_string(), 3), ("b".to_string(), 4)]);
+
+    assert_eq!(rhai::format_map_as_json(&map), r#"{"z":5,"m":3,"b":4}"#);
+    assert_eq!(engine.eval::<String>("let m = #{y: 1, x: #{q: 2, p: 3}}; m.w = (); m.to_json()").unwrap(), r#"{"y":1,"x":{"q":2,"p":3},"w":null}"#);
+
+    let json = engine.parse_json(r#"{"c": 1, "b": 2, "a": 3}"#, true).unwrap();
+    assert_eq!(json.keys().map(|k| k.as_str()).collect::<Vec<_>>(), ["c", "b", "a"]);
+
+    #[cfg(not(feature = "no_index"))]
+    assert_eq!(engine.eval::<String>(r#"let m = #{c: 1, b: 2}; m.a = 3; let s = ""; for (k, i) in m.keys() { s += `${i}${k}`; } s"#).unwrap(), "0c1b2a");
+
+    // Equality does not depend on order
+    let map1: rhai::OrderedMap<_, _> = [("a", 1), ("b", 2), ("c", 3)].into();
+    let map2: rhai::OrderedMap<_, _> = [("c", 3), ("a", 1), ("b", 2)].into();
+    assert_eq!(map1, map2);
+    assert_ne!(map1, [("a", 1), ("b", 2)].into());
+    assert!(engine.eval::<bool>("#{a: 1, b: 2} == #{b: 2, a: 1}").unwrap());
+    assert!(engine.eval::<bool>("let m = #{a: 1}; m.b = 2; m == #{b: 2, a: 1}").unwrap());
+}
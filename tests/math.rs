@@ -57,3 +57,61 @@ fn test_math_parse() {
     assert_eq!(engine.eval::<INT>(r#"parse_int("42", 16)"#).unwrap(), 0x42);
     assert_eq!(engine.eval::<INT>(r#"parse_int("abcdef", 16)"#).unwrap(), 0xabcdef);
 }
+
+#[test]
+fn test_math_strict_arithmetic() {
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "only_i32"))]
+    let script = "let x = 9223372036854775807; x + 1";
+    #[cfg(feature = "only_i32")]
+    let script = "let x = 2147483647; x + 1";
+
+    // Without `unchecked`, integer arithmetic is always checked
+    #[cfg(not(feature = "unchecked"))]
+    assert!(matches!(*engine.eval::<INT>(script).expect_err("expects overflow"), EvalAltResult::ErrorArithmetic(..)));
+
+    // With `unchecked`, integer arithmetic wraps around (release builds only; debug builds panic)
+    #[cfg(feature = "unchecked")]
+    #[cfg(not(debug_assertions))]
+    assert_eq!(engine.eval::<INT>(script).unwrap(), INT::MIN);
+
+    engine.set_strict_arithmetic(true);
+
+    assert!(matches!(*engine.eval::<INT>(script).expect_err("expects overflow"), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("let x = 42; x / 0").expect_err("expects division by zero"), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("let x = 42; x % 0").expect_err("expects division by zero"), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("let x = 42; x /= 0; x").expect_err("expects division by zero"), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>("let x = 2; x ** 100").expect_err("expects overflow"), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>(&format!("{script}; x += 1; x")).expect_err("expects overflow"), EvalAltResult::ErrorArithmetic(..)));
+    assert_eq!(engine.eval::<INT>("let x = 40; x += 1; x + 1").unwrap(), 42);
+
+    // Also without fast operators
+    engine.set_fast_operators(false);
+
+    assert!(matches!(*engine.eval::<INT>(script).expect_err("expects overflow"), EvalAltResult::ErrorArithmetic(..)));
+    assert!(matches!(*engine.eval::<INT>(&format!("{script}; x += 1; x")).expect_err("expects overflow"), EvalAltResult::ErrorArithmetic(..)));
+    assert_eq!(engine.eval::<INT>("let x = 40; x += 1; x + 1").unwrap(), 42);
+}
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_math_strict_arithmetic_operations() {
+    fn count_operations(engine: &mut Engine) -> u64 {
+        let count = std::rc::Rc::new(std::cell::Cell::new(0));
+        let counter = count.clone();
+        engine.on_progress(move |n| {
+            counter.set(n);
+            None
+        });
+        engine.run("let x = 0; for i in 0..1000 { x += i * 2 - 1; }").unwrap();
+        count.get()
+    }
+
+    let mut engine = Engine::new();
+    let normal = count_operations(&mut engine);
+
+    engine.set_strict_arithmetic(true);
+
+    assert_eq!(count_operations(&mut engine), normal);
+}
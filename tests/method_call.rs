@@ -33,6 +33,36 @@ fn test_method_call_style() {
     assert_eq!(engine.eval::<INT>("let x = -123; x.abs(); x").unwrap(), -123);
 }
 
+#[test]
+fn test_method_call_primitives() {
+    let mut engine = Engine::new();
+
+    // Methods on primitives resolve regardless of whether the first parameter is taken by value or by `&mut`
+    engine.register_fn("clamp_by_val", |x: INT, lo: INT, hi: INT| x.max(lo).min(hi));
+    engine.register_fn("clamp_by_ref", |x: &mut INT, lo: INT, hi: INT| (*x).max(lo).min(hi));
+    engine.register_fn("pad_by_val", |s: rhai::ImmutableString, len: INT, c: char| format!("{}{}", s, c.to_string().repeat(len as usize - s.len())));
+    engine.register_fn("pad_by_ref", |s: &mut rhai::ImmutableString, len: INT, c: char| format!("{}{}", s, c.to_string().repeat(len as usize - s.len())));
+
+    assert_eq!(engine.eval::<INT>("(3).max(5)").unwrap(), 5);
+    assert_eq!(engine.eval::<INT>("3.min(5)").unwrap(), 3);
+    assert_eq!(engine.eval::<String>(r#"let s = "abc"; s.pad(5, '*'); s"#).unwrap(), "abc**");
+
+    for name in ["clamp_by_val", "clamp_by_ref"] {
+        assert_eq!(engine.eval::<INT>(&format!("42.{}(0, 10)", name)).unwrap(), 10);
+        assert_eq!(engine.eval::<INT>(&format!("let x = 42; x.{}(0, 10)", name)).unwrap(), 10);
+        assert_eq!(engine.eval::<INT>(&format!("const X = -1; X.{}(0, 10)", name)).unwrap(), 0);
+        assert_eq!(engine.eval::<INT>(&format!("let m = #{{ x: 5 }}; m.x.{}(0, 10)", name)).unwrap(), 5);
+    }
+
+    for name in ["pad_by_val", "pad_by_ref"] {
+        assert_eq!(engine.eval::<String>(&format!(r#""abc".{}(5, '*')"#, name)).unwrap(), "abc**");
+        assert_eq!(engine.eval::<String>(&format!(r#"const S = "abc"; S.{}(5, '*')"#, name)).unwrap(), "abc**");
+    }
+
+    // Methods that modify the object in place still cannot be called on constants
+    assert!(matches!(*engine.eval::<()>(r#"const S = "abc"; S.pad(5, '*')"#).unwrap_err(), EvalAltResult::ErrorNonPureMethodCallOnConstant(..)));
+}
+
 #[cfg(not(feature = "no_optimize"))]
 #[test]
 fn test_method_call_with_full_optimization() {
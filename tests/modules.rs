@@ -1,7 +1,7 @@
 #![cfg(not(feature = "no_module"))]
 use rhai::{
     module_resolvers::{DummyModuleResolver, StaticModuleResolver},
-    Dynamic, Engine, EvalAltResult, FnNamespace, ImmutableString, Module, ParseError, ParseErrorType, Scope, INT,
+    Dynamic, Engine, EvalAltResult, FnNamespace, ImmutableString, Module, ParseError, ParseErrorType, Position, Scope, INT,
 };
 //
 #[cfg(all(not(feature = "no_function"), feature = "internals"))]
@@ -82,6 +82,74 @@ fn test_module_sub_module() {
     assert_eq!(engine.eval::<INT>("super_inc(question::life::universe::answer)").unwrap(), 42);
 }
 
+#[test]
+fn test_module_lazy_static() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let mut engine = Engine::new();
+
+    let c = counter.clone();
+    engine.register_lazy_static_module("tables", move || {
+        c.fetch_add(1, Ordering::SeqCst);
+        let mut module = Module::new();
+        module.set_var("SIZE", 42 as INT);
+        module.set_native_fn("calc", |x: INT| Ok(x + 1));
+        module.into()
+    });
+
+    // Not built when there is no namespaced access
+    engine.set_strict_variables(true);
+    let ast = engine.compile("let x = 40; if x > 100 { tables::calc(x) } else { x }").unwrap();
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 40);
+    assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+    // Built exactly once upon first namespaced access
+    assert_eq!(engine.eval::<INT>("tables::calc(41)").unwrap(), 42);
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+    assert_eq!(engine.eval::<INT>("tables::calc(tables::SIZE)").unwrap(), 43);
+    assert_eq!(engine.eval_ast::<INT>(&engine.compile("let x = 101; if x > 100 { tables::calc(x) } else { x }").unwrap()).unwrap(), 102);
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    // Registering a static module with the same name replaces the lazy module
+    engine.register_static_module("tables", Module::new().into());
+    assert!(engine.eval::<INT>("tables::calc(41)").is_err());
+    assert_eq!(counter.load(Ordering::SeqCst), 1);
+
+    #[cfg(feature = "sync")]
+    {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let mut engine = Engine::new();
+
+        let c = counter.clone();
+        engine.register_lazy_static_module("tables", move || {
+            c.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            let mut module = Module::new();
+            module.set_var("SIZE", 42 as INT);
+            module.into()
+        });
+
+        let engine = Arc::new(engine);
+
+        let handles = (0..8)
+            .map(|_| {
+                let engine = engine.clone();
+                std::thread::spawn(move || engine.eval::<INT>("tables::SIZE").unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), 42);
+        }
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+}
+
 #[test]
 fn test_module_resolver() {
     let mut resolver = StaticModuleResolver::new();
@@ -378,6 +446,147 @@ fn test_module_from_ast() {
     ));
 }
 
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_module_from_ast_with_options() {
+    use rhai::{AstToModuleOptions, ExportFilter};
+
+    let mut engine = Engine::new();
+
+    let mut resolver1 = StaticModuleResolver::new();
+    let mut sub_module = Module::new();
+    sub_module.set_var("foo", true);
+    resolver1.insert("another module", sub_module);
+    engine.set_module_resolver(resolver1);
+
+    let ast = engine
+        .compile(
+            r#"
+            import "another module" as extra;
+
+            fn area(w, h) { w * h }
+            fn perimeter(w, h) { double(w + h) }
+            fn double(x) { x * 2 }
+            fn triple(x) { x * 3 }
+            fn square(x) { area(x, x) }
+
+            let width = 2;
+            let height = 3;
+            let depth = 4;
+            let color = "red";
+            let weight = 5;
+
+            export width;
+            export height;
+            export depth;
+            export color;
+            export weight;
+        "#,
+        )
+        .unwrap();
+
+    let options = AstToModuleOptions::new()
+        .export_variables(ExportFilter::only(["width"]))
+        .export_functions(ExportFilter::only(["area", "perimeter"]))
+        .include_submodules(false);
+
+    let module = Module::eval_ast_as_new_with_options(Scope::new(), &ast, &engine, &options).unwrap();
+
+    let mut runner = Engine::new();
+    let mut resolver2 = StaticModuleResolver::new();
+    resolver2.insert("testing", module);
+    runner.set_module_resolver(resolver2);
+
+    assert_eq!(runner.eval::<INT>(r#"import "testing" as t; t::width"#).unwrap(), 2);
+    assert_eq!(runner.eval::<INT>(r#"import "testing" as t; t::area(t::width, 7)"#).unwrap(), 14);
+    assert_eq!(runner.eval::<INT>(r#"import "testing" as t; t::perimeter(2, 3)"#).unwrap(), 10);
+
+    for var in ["height", "depth", "color", "weight"] {
+        let script = format!(r#"import "testing" as t; t::{}"#, var);
+        assert!(matches!(*runner.run(&script).unwrap_err(), EvalAltResult::ErrorVariableNotFound(..)));
+    }
+    for func in ["double(1)", "triple(1)", "square(1)"] {
+        let script = format!(r#"import "testing" as t; t::{}"#, func);
+        assert!(matches!(*runner.run(&script).unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+    }
+    assert!(matches!(
+        *runner.run(r#"import "testing" as t; t::extra::foo"#).unwrap_err(),
+        EvalAltResult::ErrorVariableNotFound(..) | EvalAltResult::ErrorModuleNotFound(..)
+    ));
+
+    let options = AstToModuleOptions::new().export_functions(ExportFilter::only(["area", "volume"]));
+
+    match *Module::eval_ast_as_new_with_options(Scope::new(), &ast, &engine, &options).unwrap_err() {
+        EvalAltResult::ErrorFunctionNotFound(msg, ..) => {
+            assert_eq!(msg, "volume (non-private functions: `area`, `double`, `perimeter`, `square`, `triple`)")
+        }
+        err => panic!("wrong error: {}", err),
+    }
+
+    let options = AstToModuleOptions::new().export_variables(ExportFilter::only(["size"]));
+
+    match *Module::eval_ast_as_new_with_options(Scope::new(), &ast, &engine, &options).unwrap_err() {
+        EvalAltResult::ErrorVariableNotFound(msg, ..) => assert!(msg.starts_with("size (exported variables: "), "{}", msg),
+        err => panic!("wrong error: {}", err),
+    }
+
+    let options = AstToModuleOptions::new().export_variables(ExportFilter::None);
+    let module = Module::eval_ast_as_new_with_options(Scope::new(), &ast, &engine, &options).unwrap();
+
+    assert!(!module.contains_var("width"));
+    assert!(module.contains_sub_module("extra"));
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_module_re_export() {
+    let mut engine = Engine::new();
+
+    let ast = engine.compile("fn double(x) { x * 2 } export const LEAF = 1;").unwrap();
+    let leaf = Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap();
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("leaf", leaf);
+    engine.set_module_resolver(resolver);
+
+    let ast = engine
+        .compile(
+            r#"
+                import "leaf" as leaf;
+                import "leaf" as hidden;
+
+                fn quadruple(x) { leaf::double(leaf::double(x)) }
+
+                export leaf as util;
+            "#,
+        )
+        .unwrap();
+    let inner = Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap();
+
+    assert!(inner.contains_sub_module("util"));
+    assert!(!inner.contains_sub_module("leaf"));
+    assert!(!inner.contains_sub_module("hidden"));
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("inner", inner);
+    engine.set_module_resolver(resolver);
+
+    let ast = engine.compile(r#"import "inner" as inner; export inner;"#).unwrap();
+    let outer = Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap();
+
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("outer", outer);
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(engine.eval::<INT>(r#"import "outer" as outer; outer::inner::util::double(21)"#).unwrap(), 42);
+    assert_eq!(engine.eval::<INT>(r#"import "outer" as outer; outer::inner::quadruple(10)"#).unwrap(), 40);
+    assert_eq!(engine.eval::<INT>(r#"import "outer" as outer; outer::inner::util::LEAF"#).unwrap(), 1);
+    assert!(engine.run(r#"import "outer" as outer; outer::inner::hidden::double(21)"#).is_err());
+
+    let ast = engine.compile("export unknown;").unwrap();
+    assert!(matches!(*Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap_err(), EvalAltResult::ErrorVariableNotFound(..)));
+}
+
 #[test]
 fn test_module_export() {
     let engine = Engine::new();
@@ -520,6 +729,357 @@ fn test_module_file() {
     Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap();
 }
 
+#[test]
+fn test_module_resolvers_collection() {
+    use rhai::module_resolvers::{FileModuleResolver, ModuleResolversCollection};
+
+    let dir = std::env::temp_dir().join(format!("rhai-test-resolvers-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("disk.rhai"), "export const X = 1;").unwrap();
+    std::fs::write(dir.join("corrupt.rhai"), "export const X = ;").unwrap();
+
+    let mut embedded = StaticModuleResolver::new();
+    let mut module = Module::new();
+    module.set_var("X", 42 as INT);
+    embedded.insert("embedded", module.clone());
+    embedded.insert("corrupt", module);
+
+    let mut collection = ModuleResolversCollection::new();
+    collection.push(FileModuleResolver::new_with_path(&dir));
+    collection.push(embedded);
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(collection);
+
+    // Found in the first resolver
+    assert_eq!(engine.eval::<INT>(r#"import "disk" as m; m::X"#).unwrap(), 1);
+
+    // Only in the second resolver
+    assert_eq!(engine.eval::<INT>(r#"import "embedded" as m; m::X"#).unwrap(), 42);
+
+    // Not found anywhere
+    assert!(matches!(
+        *engine.eval::<INT>(r#"import "missing" as m; m::X"#).unwrap_err(),
+        EvalAltResult::ErrorModuleNotFound(ref p, ..) if p == "missing"
+    ));
+
+    // A corrupt module in the first resolver aborts the search
+    assert!(matches!(*engine.eval::<INT>(r#"import "corrupt" as m; m::X"#).unwrap_err(), EvalAltResult::ErrorParsing(..)));
+
+    let resolver = engine.module_resolver();
+
+    assert!(matches!(resolver.resolve_ast(&engine, None, "disk", Position::NONE), Some(Ok(..))));
+    assert!(resolver.resolve_ast(&engine, None, "embedded", Position::NONE).is_none());
+    assert!(matches!(resolver.resolve_ast(&engine, None, "corrupt", Position::NONE), Some(Err(err)) if matches!(*err, EvalAltResult::ErrorParsing(..))));
+
+    assert_eq!(resolver.resolve_source_path(&engine, None, "disk"), Some(dir.join("disk.rhai").to_string_lossy().into_owned()));
+    assert_eq!(resolver.resolve_source_path(&engine, None, "embedded").as_deref(), Some("embedded"));
+    assert_eq!(resolver.resolve_source_path(&engine, None, "missing"), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_module_resolver_relative_paths() {
+    use rhai::module_resolvers::FileModuleResolver;
+
+    let dir = std::env::temp_dir().join(format!("rhai-test-relative-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("app/lib")).unwrap();
+    std::fs::write(dir.join("app/main.rhai"), r#"import "./lib/a" as a; a::X"#).unwrap();
+    std::fs::write(dir.join("app/mixed.rhai"), r#"import "lib/a" as a; a::X"#).unwrap();
+    std::fs::write(dir.join("app/lib/a.rhai"), r#"import "./b" as b; import "../util" as u; export const X = b::Y + u::Z;"#).unwrap();
+    std::fs::write(dir.join("app/lib/b.rhai"), "export const Y = 40;").unwrap();
+    std::fs::write(dir.join("app/util.rhai"), "export const Z = 2;").unwrap();
+
+    // Relative paths resolve against the importing script, regardless of the base path
+    let mut engine = Engine::new();
+    engine.set_module_resolver(FileModuleResolver::new_with_path(dir.join("elsewhere")));
+
+    assert_eq!(engine.eval_file::<INT>(dir.join("app/main.rhai")).unwrap(), 42);
+
+    // The whole library can be relocated
+    std::fs::rename(dir.join("app"), dir.join("moved")).unwrap();
+
+    assert_eq!(engine.eval_file::<INT>(dir.join("moved/main.rhai")).unwrap(), 42);
+
+    // Modules imported via the base path resolve their own relative imports against their location
+    engine.set_module_resolver(FileModuleResolver::new_with_path(dir.join("moved")));
+
+    assert_eq!(engine.eval_file::<INT>(dir.join("moved/mixed.rhai")).unwrap(), 42);
+
+    // Scripts without a source fall back to the base path
+    assert_eq!(engine.eval::<INT>(r#"import "./lib/b" as b; b::Y"#).unwrap(), 40);
+    assert!(matches!(*engine.run(r#"import "../util" as u;"#).unwrap_err(), EvalAltResult::ErrorModuleNotFound(..)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_module_resolver_check_timestamps() {
+    use rhai::module_resolvers::FileModuleResolver;
+
+    let dir = std::env::temp_dir().join(format!("rhai-test-timestamps-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("live.rhai"), "export const X = 1;").unwrap();
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(FileModuleResolver::new_with_path(&dir));
+
+    assert_eq!(engine.eval::<INT>(r#"import "live" as m; m::X"#).unwrap(), 1);
+
+    // Stale contents are returned from the cache
+    std::fs::write(dir.join("live.rhai"), "export const X = 42;").unwrap();
+
+    assert_eq!(engine.eval::<INT>(r#"import "live" as m; m::X"#).unwrap(), 1);
+
+    // Changed files are reloaded when timestamps are checked
+    let mut resolver = FileModuleResolver::new_with_path(&dir);
+    resolver.set_check_timestamps(true);
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(engine.eval::<INT>(r#"import "live" as m; m::X"#).unwrap(), 42);
+
+    std::fs::write(dir.join("live.rhai"), "export const X = 123;").unwrap();
+
+    assert_eq!(engine.eval::<INT>(r#"import "live" as m; m::X"#).unwrap(), 123);
+    assert_eq!(engine.eval::<INT>(r#"import "live" as m; m::X"#).unwrap(), 123);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_module_resolver_async() {
+    use rhai::module_resolvers::AsyncModuleResolver;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    // A future that is pending once before completing, like a real async source
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                Poll::Ready(())
+            } else {
+                self.0 = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+
+        loop {
+            if let Poll::Ready(value) = future.as_mut().poll(&mut cx) {
+                return value;
+            }
+        }
+    }
+
+    let loads = Arc::new(AtomicUsize::new(0));
+    let loads2 = loads.clone();
+
+    let resolver = AsyncModuleResolver::new(
+        move |path| {
+            let path = path.to_string();
+            let loads = loads2.clone();
+
+            Box::pin(async move {
+                YieldOnce(false).await;
+                loads.fetch_add(1, Ordering::SeqCst);
+
+                match path.as_str() {
+                    "db" => {
+                        let mut module = Module::new();
+                        module.set_var("answer", 42 as INT);
+                        Ok(module.into())
+                    }
+                    _ => Err(EvalAltResult::ErrorModuleNotFound(path, Position::NONE).into()),
+                }
+            })
+        },
+        block_on,
+    );
+
+    assert!(!resolver.is_cached("db"));
+
+    let mut engine = Engine::new();
+    engine.set_module_resolver(resolver);
+
+    assert_eq!(engine.eval::<INT>(r#"import "db" as m; m::answer"#).unwrap(), 42);
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    // Cache hit - the future does not run again
+    assert_eq!(engine.eval::<INT>(r#"import "db" as m; import "db" as n; m::answer + n::answer"#).unwrap(), 84);
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    // Errors are not cached
+    assert!(matches!(*engine.run(r#"import "missing" as m;"#).unwrap_err(), EvalAltResult::ErrorModuleNotFound(..)));
+    assert!(matches!(*engine.run(r#"import "missing" as m;"#).unwrap_err(), EvalAltResult::ErrorModuleNotFound(..)));
+    assert_eq!(loads.load(Ordering::SeqCst), 3);
+}
+
+#[cfg(feature = "metadata")]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_module_fn_signatures_grouped() {
+    use rhai::{FnSignatureInfo, FnSignatureKind, FnSignatureOptions, FnSignatureSource};
+
+    let engine = Engine::new();
+    let ast = engine
+        .compile(
+            "
+                /// Add one.
+                fn calc(x) { x + 1 }
+                private fn hidden() { 0 }
+            ",
+        )
+        .unwrap();
+
+    let mut module = AsRef::<Module>::as_ref(&ast).clone();
+    module.set_getter_fn("value", |x: &mut INT| Ok(*x));
+    module.set_native_fn("+", |x: INT, y: bool| Ok(x + if y { 1 } else { 0 }));
+
+    let mut sub = Module::new();
+    sub.set_native_fn("inner", |x: INT| Ok(x));
+    let mut sub2 = Module::new();
+    sub2.set_native_fn("deep", || Ok(true));
+    sub.set_sub_module("deeper", sub2);
+    module.set_sub_module("sub", sub);
+
+    let snapshot = |signatures: &[FnSignatureInfo]| {
+        signatures
+            .iter()
+            .map(|f| format!("{}{:?} {} = {}", f.namespace.iter().map(|ns| format!("{}::", ns)).collect::<String>(), f.kind, f.name, f.signature))
+            .collect::<Vec<_>>()
+    };
+
+    let signatures = module.fn_signatures_grouped(FnSignatureOptions::new());
+
+    assert_eq!(
+        snapshot(&signatures),
+        ["Operator + = +(_, _)", "Function calc = calc(x)", "Getter value = get$value(_)", "sub::Function inner = inner(_)", "sub::deeper::Function deep = deep()",]
+    );
+    assert!(signatures.iter().all(|f| f.source == FnSignatureSource::Module));
+
+    let calc = &signatures[1];
+    assert!(calc.is_script);
+    assert_eq!(calc.params, [("x".into(), None)]);
+    assert_eq!(calc.return_type, None);
+    assert_eq!(calc.comments, ["/// Add one."]);
+
+    let options = FnSignatureOptions::new()
+        .include_private(true)
+        .include_operators(false)
+        .include_properties(false)
+        .include_sub_modules(false);
+
+    assert_eq!(snapshot(&module.fn_signatures_grouped(options)), ["Function calc = calc(x)", "Function hidden = hidden()"]);
+
+    // Engine-level signatures record where each function comes from
+    let mut engine = Engine::new_raw();
+    engine.register_fn("global_fn", |x: INT| x);
+    engine.register_static_module("sub", module.into());
+
+    let signatures = engine.gen_fn_signatures_grouped(false);
+
+    assert_eq!(signatures[0].name, "global_fn");
+    assert_eq!(signatures[0].kind, FnSignatureKind::Function);
+    assert_eq!(signatures[0].source, FnSignatureSource::Global);
+    assert_eq!(signatures[0].params, [("_".into(), Some(std::any::type_name::<INT>().into()))]);
+    assert_eq!(signatures[0].return_type.as_deref(), Some(std::any::type_name::<INT>()));
+    assert!(signatures[1..].iter().all(|f| f.source == FnSignatureSource::StaticModule("sub".into()) && f.namespace[0] == "sub"));
+    assert_eq!(signatures.last().unwrap().namespace, ["sub", "sub", "deeper"]);
+    assert_eq!(format!("{:?}", signatures), format!("{:?}", engine.gen_fn_signatures_grouped(false)));
+}
+
+#[cfg(feature = "metadata")]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_module_fn_signatures_readable() {
+    use rhai::{FnSignatureKind, FnSignatureSource};
+
+    #[derive(Debug, Clone)]
+    struct Foo {
+        name: String,
+        items: Vec<bool>,
+    }
+
+    let mut engine = Engine::new_raw();
+
+    engine
+        .register_type_with_name::<Foo>("Foo")
+        .register_get_set("name", |x: &mut Foo| x.name.clone(), |x: &mut Foo, v: String| x.name = v)
+        .register_indexer_get(|x: &mut Foo, i: INT| x.items[i as usize])
+        .register_indexer_set(|x: &mut Foo, i: INT, v: bool| x.items[i as usize] = v)
+        .register_fn("==", |_: &mut Foo, _: Foo| true)
+        .register_fn("new_foo", || Foo { name: String::new(), items: Vec::new() });
+
+    let mut module = Module::new();
+    let hash = module.set_native_fn("double", |x: INT| Ok(x * 2));
+    module.update_fn_metadata(hash, ["x: INT", "INT"]);
+    let mut sub = Module::new();
+    sub.set_native_fn("ping", || Ok(true));
+    module.set_sub_module("net", sub);
+    engine.register_static_module("calc", module.into());
+
+    let int = std::any::type_name::<INT>();
+
+    let expected = [
+        "==(_: &mut Foo, _: Foo) -> bool".to_string(),
+        format!("Foo[{int}] -> bool"),
+        format!("Foo[{int}] = bool"),
+        "Foo.name: String".to_string(),
+        "Foo.name = String".to_string(),
+        "new_foo() -> Foo".to_string(),
+        format!("calc::double(x: {int}) -> {int}"),
+        "calc::net::ping()".to_string(),
+    ];
+
+    assert_eq!(engine.gen_fn_signatures(false), expected);
+    assert_eq!(engine.gen_fn_signatures(false), engine.gen_fn_signatures(false));
+
+    let ast = engine.compile("fn triple(x) { x * 3 } private fn hidden() {}").unwrap();
+    let signatures = engine.gen_fn_signatures_with_ast(&ast, false);
+    assert_eq!(signatures[0], "triple(x)");
+    assert_eq!(signatures[1..], expected);
+
+    let signatures = engine.gen_fn_signatures_grouped_with_ast(&ast, false);
+    assert_eq!(signatures[0].source, FnSignatureSource::Script);
+    assert_eq!(signatures[0].to_string(), "triple(x)");
+
+    let setter = signatures.iter().find(|f| f.kind == FnSignatureKind::Setter).unwrap();
+    assert_eq!(setter.name, "name");
+    assert!(setter.namespace.is_empty());
+    assert_eq!(setter.params.len(), 2);
+    assert!(setter.params[0].1.as_deref().unwrap().ends_with("Foo"));
+
+    let double = signatures.iter().find(|f| f.name == "double").unwrap();
+    assert_eq!(double.kind, FnSignatureKind::Function);
+    assert_eq!(double.namespace, ["calc"]);
+    assert_eq!(double.params, [("x".into(), Some(int.into()))]);
+    assert_eq!(double.return_type.as_deref(), Some(int));
+    assert_eq!(double.to_string(), format!("calc::double(x: {int}) -> {int}"));
+}
+
 #[cfg(not(feature = "no_function"))]
 #[test]
 fn test_module_environ() {
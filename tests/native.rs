@@ -53,3 +53,246 @@ fn test_native_overload() {
     assert_eq!(engine.eval::<String>(r#"let x = "hello"; let y = "world"; x + y"#).unwrap(), "hello***world");
     assert_eq!(engine.eval::<String>(r#"let x = "hello"; let y = (); x + y"#).unwrap(), "hello Foo!");
 }
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_native_call_native_fn() {
+    let mut engine = Engine::new();
+
+    engine
+        .register_fn("describe", |context: NativeCallContext, x: INT| -> Result<String, Box<EvalAltResult>> {
+            let s = context.call_native_fn::<ImmutableString>("to_string", (x,))?;
+            Ok(format!("<{s}>"))
+        })
+        .register_fn("describe_raw", |context: NativeCallContext, mut x: Dynamic| context.call_native_fn_raw("to_string", true, &mut [&mut x]))
+        .register_fn("describe_any", |context: NativeCallContext, x: INT| context.call_fn::<ImmutableString>("to_string", (x,)));
+
+    // The script function forwards to the native version without recursing into itself
+    assert_eq!(engine.eval::<String>(r#"fn to_string(x) { "script:" + describe(x) } to_string(42)"#).unwrap(), "script:<42>");
+    assert_eq!(engine.eval::<String>(r#"fn to_string(x) { "script:" + describe_raw(x) } to_string(42)"#).unwrap(), "script:42");
+
+    // A normal call resolves to the script function
+    assert_eq!(engine.eval::<String>(r#"fn to_string(x) { "script" } describe_any(42)"#).unwrap(), "script");
+}
+
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_native_borrowed_args() {
+    use rhai::{Array, Map};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CLONES: AtomicUsize = AtomicUsize::new(0);
+
+    struct Counted;
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            CLONES.fetch_add(1, Ordering::SeqCst);
+            Self
+        }
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Counted>("Counted")
+        .register_fn("counted", || Counted)
+        .register_fn("clones", || CLONES.load(Ordering::SeqCst) as INT)
+        .register_fn("total", |a: &mut Array, b: &Array| (a.len() + b.len()) as INT)
+        .register_fn("total", |a: &mut Map, b: &Map| (a.len() + b.len()) as INT)
+        .register_fn("total", |a: INT, b: &Array, c: &Array| a + (b.len() + c.len()) as INT)
+        .register_fn("total_by_value", |a: &mut Array, b: Array| (a.len() + b.len()) as INT)
+        .register_fn("check", |a: INT, b: &Array| -> Result<INT, Box<EvalAltResult>> { if a > 0 { Ok(a + b.len() as INT) } else { Err("negative".into()) } });
+
+    // Borrowed arguments are not cloned
+    assert_eq!(engine.eval::<Array>("let a = [counted(), counted()]; let b = [counted(), counted(), counted()]; let n = clones(); let t = total(a, b); [t, clones() - n, a.len(), b.len()]").unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>(), [5, 0, 2, 3]);
+    assert_eq!(engine.eval::<Array>("let a = #{x: counted()}; let b = #{y: counted(), z: counted()}; let n = clones(); let t = total(a, b); [t, clones() - n, a.len(), b.len()]").unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>(), [3, 0, 1, 2]);
+    assert_eq!(engine.eval::<Array>("let a = [counted()]; let b = [counted(), counted()]; let n = clones(); let t = total(40, a, b); [t, clones() - n, a.len(), b.len()]").unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>(), [43, 0, 1, 2]);
+
+    // Arguments passed by value are cloned, but the variables are kept intact
+    assert_eq!(engine.eval::<Array>("let a = [counted(), counted()]; let b = [counted(), counted(), counted()]; let n = clones(); let t = total_by_value(a, b); [t, clones() - n, a.len(), b.len()]").unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>(), [5, 3, 2, 3]);
+    assert_eq!(engine.eval::<Array>("fn total(a, b) { a + b.len() } let a = 1; let b = [counted(), counted(), counted()]; let n = clones(); let t = total(a, b); [t, clones() - n, b.len()]").unwrap().into_iter().map(|v| v.as_int().unwrap()).collect::<Vec<_>>(), [4, 3, 3]);
+
+    // The same variable used twice is not borrowed
+    assert_eq!(engine.eval::<INT>("let b = [counted(), counted(), counted()]; total(b, b)").unwrap(), 6);
+    assert_eq!(engine.eval::<INT>("let b = [counted(), counted(), counted()]; total(1, b, b)").unwrap(), 7);
+
+    // Borrowed arguments are restored even when the function fails
+    assert_eq!(engine.eval::<INT>("let b = [1, 2, 3]; try { check(-1, b) } catch { 0 }; b.len()").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("let b = [1, 2, 3]; check(39, b)").unwrap(), 42);
+}
+
+#[test]
+fn test_native_clone_registrations() {
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone)]
+    struct Foo(INT);
+
+    let mut engine = Engine::new();
+
+    engine.register_type_with_name::<Foo>("Foo").register_fn("new_foo", |x: INT| Foo(x)).register_fn("value", |x: &mut Foo| x.0);
+    engine.register_fn("add", |x: INT, y: INT| x + y);
+    engine.disable_symbol("while");
+    #[cfg(not(feature = "unchecked"))]
+    engine.set_max_operations(500);
+
+    #[cfg(not(feature = "no_module"))]
+    let loads = {
+        let mut module = rhai::Module::new();
+        module.set_native_fn("triple", |x: INT| Ok(x * 3));
+        engine.register_static_module("calc", module.into());
+
+        let loads = Arc::new(Mutex::new(0));
+        let counter = loads.clone();
+        engine.register_lazy_static_module("lazy", move || {
+            *counter.lock().unwrap() += 1;
+            let mut module = rhai::Module::new();
+            module.set_var("ANSWER", 42 as INT);
+            module.into()
+        });
+        loads
+    };
+
+    #[cfg(not(feature = "no_custom_syntax"))]
+    engine.register_custom_syntax(["twice", "$expr$"], false, |context, inputs| Ok((context.eval_expression_tree(&inputs[0])?.as_int().unwrap() * 2).into())).unwrap();
+
+    let log1 = Arc::new(Mutex::new(Vec::<String>::new()));
+    let log = log1.clone();
+    engine.on_print(move |s| log.lock().unwrap().push(s.to_string()));
+
+    let mut engine2 = engine.clone_registrations();
+
+    // Registrations are shared
+    assert_eq!(engine2.eval::<INT>("add(40, 2)").unwrap(), 42);
+    assert_eq!(engine2.eval::<INT>("value(new_foo(42))").unwrap(), 42);
+    assert_eq!(engine2.eval::<String>("type_of(new_foo(1))").unwrap(), "Foo");
+    assert!(engine2.compile("while true {}").is_err());
+    #[cfg(not(feature = "unchecked"))]
+    assert_eq!(engine2.max_operations(), 500);
+    #[cfg(not(feature = "no_custom_syntax"))]
+    assert_eq!(engine2.eval::<INT>("twice 21").unwrap(), 42);
+
+    #[cfg(not(feature = "no_module"))]
+    {
+        assert_eq!(engine2.eval::<INT>("calc::triple(14)").unwrap(), 42);
+        assert_eq!(engine2.eval::<INT>("lazy::ANSWER").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("lazy::ANSWER").unwrap(), 42);
+        assert_eq!(*loads.lock().unwrap(), 1);
+    }
+
+    // Callbacks are independent
+    engine2.run(r#"print("hello")"#).unwrap();
+    assert!(log1.lock().unwrap().is_empty());
+
+    let log2 = Arc::new(Mutex::new(Vec::<String>::new()));
+    let log = log2.clone();
+    engine2.on_print(move |s| log.lock().unwrap().push(s.to_string()));
+
+    engine.run(r#"print("one")"#).unwrap();
+    engine2.run(r#"print("two")"#).unwrap();
+    assert_eq!(*log1.lock().unwrap(), ["one"]);
+    assert_eq!(*log2.lock().unwrap(), ["two"]);
+
+    // New registrations are not shared
+    engine2.register_fn("only2", || 42 as INT);
+    assert_eq!(engine2.eval::<INT>("only2()").unwrap(), 42);
+    assert!(matches!(*engine.eval::<INT>("only2()").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    #[cfg(feature = "sync")]
+    {
+        let engine = Arc::new(engine);
+
+        let handles = (0..4)
+            .map(|i| {
+                let engine = engine.clone();
+                std::thread::spawn(move || engine.clone_registrations().eval::<INT>(&format!("add({i}, value(new_foo(40)))")).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(handles.into_iter().map(|h| h.join().unwrap()).collect::<Vec<_>>(), [40, 41, 42, 43]);
+    }
+}
+
+#[test]
+fn test_native_memoization() {
+    use rhai::Module;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone)]
+    struct Foo(INT);
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut module = Module::new();
+
+    let counter = calls.clone();
+    let hash = module.set_native_fn("slow_add", move |x: INT, y: INT| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Ok(x + y)
+    });
+    module.update_fn_memoization(hash, 2);
+
+    let counter = calls.clone();
+    let hash = module.set_native_fn("slow_value", move |foo: Foo| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        Ok(foo.0)
+    });
+    module.update_fn_memoization(hash, 10);
+
+    let mut engine = Engine::new();
+    engine.register_global_module(module.into());
+    engine.register_fn("new_foo", Foo);
+
+    assert_eq!(engine.eval::<INT>("let s = 0; for i in 0..10 { s += slow_add(40, 2); } s").unwrap(), 420);
+    assert_eq!(calls.swap(0, Ordering::Relaxed), 1);
+
+    // Memoized results do not survive across evaluations
+    assert_eq!(engine.eval::<INT>("slow_add(40, 2) + slow_add(40, 2) + slow_add(1, 2)").unwrap(), 87);
+    assert_eq!(calls.swap(0, Ordering::Relaxed), 2);
+
+    // Memoized results are discarded when the capacity is reached
+    assert_eq!(engine.eval::<INT>("slow_add(1, 1) + slow_add(1, 2) + slow_add(1, 3) + slow_add(1, 1)").unwrap(), 11);
+    assert_eq!(calls.swap(0, Ordering::Relaxed), 4);
+
+    // Arguments that cannot be hashed are never memoized
+    assert_eq!(engine.eval::<INT>("let s = 0; for i in 0..10 { s += slow_value(new_foo(42)); } s").unwrap(), 420);
+    assert_eq!(calls.swap(0, Ordering::Relaxed), 10);
+}
+
+#[cfg(not(feature = "no_module"))]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_native_context_global_constants() {
+    let mut engine = Engine::new();
+
+    engine.set_default_tag(42 as INT);
+
+    engine.register_fn("get_config", |context: NativeCallContext, key: &str| -> Result<Dynamic, Box<EvalAltResult>> {
+        let config = context.global_constant("CONFIG").ok_or_else(|| format!("CONFIG not found in {}", context.fn_name()))?;
+        Ok(config.cast::<rhai::Map>().get(key).cloned().unwrap_or(Dynamic::UNIT))
+    });
+    engine.register_fn("num_constants", |context: NativeCallContext| context.iter_global_constants().count() as INT);
+    engine.register_fn("get_tag", |context: NativeCallContext| context.tag().cloned().unwrap_or(Dynamic::UNIT));
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                r#"
+                    const CONFIG = #{ answer: 42, name: "hello" };
+                    const LIMIT = 10;
+
+                    fn foo() { get_config("answer") }
+
+                    foo() + num_constants()
+                "#
+            )
+            .unwrap(),
+        44
+    );
+    assert_eq!(engine.eval::<String>(r#"const CONFIG = #{ name: "hello" }; fn foo() { get_config("name") } foo()"#).unwrap(), "hello");
+    assert!(engine.eval::<Dynamic>(r#"fn foo() { get_config("name") } foo()"#).is_err());
+    assert_eq!(engine.eval::<INT>("get_tag()").unwrap(), 42);
+}
@@ -1,5 +1,5 @@
 #![cfg(not(feature = "unchecked"))]
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, LimitKind, INT};
 
 #[test]
 fn test_max_operations() {
@@ -140,3 +140,105 @@ fn test_max_operations_progress() {
         *engine.run("for x in 0..500 {}").unwrap_err(),
         EvalAltResult::ErrorTerminated(x, ..) if x.as_int().unwrap() == 42));
 }
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+fn test_max_operations_call_budget() {
+    let mut engine = Engine::new();
+    #[cfg(not(feature = "no_optimize"))]
+    engine.set_optimization_level(rhai::OptimizationLevel::None);
+
+    let progress = std::rc::Rc::new(std::cell::Cell::new(0_u64));
+    let progress2 = progress.clone();
+
+    engine.on_progress(move |count| {
+        progress2.set(progress2.get().max(count));
+        None
+    });
+
+    engine.register_fn("try_call", |context: rhai::NativeCallContext, name: &str| -> Result<INT, _> {
+        match context.call_fn_with_budget::<INT>(name, (), 200) {
+            Err(err) if matches!(*err, EvalAltResult::ErrorTooManyOperations(..)) => Ok(-1),
+            result => result,
+        }
+    });
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    fn quick() { 42 }
+                    fn spin() { loop {} }
+
+                    let x = try_call(\"spin\");
+                    let y = try_call(\"quick\");
+                    x + y
+                "
+            )
+            .unwrap(),
+        41
+    );
+    assert!(progress.get() > 200);
+
+    engine.register_fn("must_call", |context: rhai::NativeCallContext, name: &str| context.call_fn_with_budget::<INT>(name, (), 200));
+
+    let err = engine.run("fn spin() { loop {} }\nlet x = 1;\nx + must_call(\"spin\")").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorTooManyOperations(_, pos) if pos.line() == Some(3)));
+}
+
+#[test]
+fn test_max_operations_limit_info() {
+    let mut engine = Engine::new();
+    engine.set_max_operations(500);
+
+    let err = engine.run("let x = 0; loop { x += 1; }").unwrap_err();
+    assert_eq!(err.to_string(), "Too many operations (line 1, position 19)");
+
+    match *err {
+        EvalAltResult::ErrorTooManyOperations(info, ..) => {
+            assert_eq!(info.kind, LimitKind::Operations);
+            assert_eq!(info.limit, 500);
+            assert_eq!(info.actual, 501);
+        }
+        err => panic!("{}", err),
+    }
+}
+
+#[test]
+fn test_sleep_terminated_by_progress() {
+    use std::time::{Duration, Instant};
+
+    let mut engine = Engine::new();
+    engine.set_sleep_granularity(Duration::from_millis(10));
+
+    let start = Instant::now();
+
+    engine.on_progress(move |_| if start.elapsed() < Duration::from_millis(50) { None } else { Some((42 as INT).into()) });
+
+    assert!(matches!(*engine.run("sleep(10)").unwrap_err(), EvalAltResult::ErrorTerminated(x, ..) if x.as_int().unwrap() == 42));
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[test]
+fn test_yield_now() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    let mut engine = Engine::new();
+    let count = Arc::new(AtomicUsize::new(0));
+    let counter = count.clone();
+
+    engine.register_fn("nop", || ());
+    engine.on_progress(move |_| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        None
+    });
+
+    engine.run("nop()").unwrap();
+    let base = count.swap(0, Ordering::Relaxed);
+
+    engine.run("yield_now()").unwrap();
+    assert_eq!(count.load(Ordering::Relaxed), base + 1);
+}
@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, Scope, INT};
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, INT};
 
 #[test]
 fn test_ops() {
@@ -71,3 +71,101 @@ fn test_ops_custom_types() {
     assert!(!engine.eval::<bool>("let x = new_ts1(); x == ()").unwrap());
     assert!(engine.eval::<bool>("let x = new_ts1(); x != ()").unwrap());
 }
+
+#[test]
+fn test_ops_in_custom_types() {
+    #[derive(Debug, Clone)]
+    struct MySet(Vec<INT>);
+
+    #[derive(Debug, Clone)]
+    struct MyList(Vec<INT>);
+
+    impl IntoIterator for MyList {
+        type Item = INT;
+        type IntoIter = std::vec::IntoIter<INT>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            self.0.into_iter()
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct MyThing;
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<MySet>("MySet")
+        .register_type_with_name::<MyList>("MyList")
+        .register_type_with_name::<MyThing>("MyThing")
+        .register_fn("new_set", || MySet(vec![1, 2, 3]))
+        .register_fn("new_list", || MyList(vec![1, 2, 3]))
+        .register_fn("new_thing", || MyThing)
+        .register_fn("contains", |s: &mut MySet, x: Dynamic| x.as_int().map_or(false, |x| s.0.contains(&x)))
+        .register_iterator::<MyList>();
+
+    // Type with `contains` taking `Dynamic`
+    assert!(engine.eval::<bool>("2 in new_set()").unwrap());
+    assert!(!engine.eval::<bool>("42 in new_set()").unwrap());
+    assert!(!engine.eval::<bool>(r#""x" in new_set()"#).unwrap());
+    assert!(engine.eval::<bool>("42 !in new_set()").unwrap());
+
+    // Type with only an iterator
+    assert!(engine.eval::<bool>("2 in new_list()").unwrap());
+    assert!(!engine.eval::<bool>("42 in new_list()").unwrap());
+    assert!(!engine.eval::<bool>(r#""x" in new_list()"#).unwrap());
+    assert!(engine.eval::<bool>("42 !in new_list()").unwrap());
+    assert!(engine.eval::<bool>("let x = new_list(); contains(x, 3)").unwrap());
+
+    // Type without `contains` nor an iterator
+    let err = engine.eval::<bool>("42 in new_thing()").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(..)));
+    assert!(err.to_string().contains("type 'MyThing' does not support the 'in' operator (no 'contains' function for"));
+}
+
+#[test]
+fn test_ops_unary_custom_types() {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Vec2 {
+        x: INT,
+        y: INT,
+    }
+
+    for fast_ops in [false, true] {
+        let mut engine = Engine::new();
+
+        engine.set_fast_operators(fast_ops);
+
+        engine
+            .register_type_with_name::<Vec2>("Vec2")
+            .register_fn("vec2", |x: INT, y: INT| Vec2 { x, y })
+            .register_fn("-", |v: Vec2| Vec2 { x: -v.x, y: -v.y })
+            .register_fn("+", |v: Vec2| v)
+            .register_fn("!", |v: Vec2| Vec2 { x: v.y, y: v.x })
+            .register_fn("*", |a: Vec2, b: Vec2| a.x * b.x + a.y * b.y)
+            .register_fn("*", |a: Vec2, k: INT| Vec2 { x: a.x * k, y: a.y * k })
+            .register_get("x", |v: &mut Vec2| v.x)
+            .register_indexer_get(|v: &mut Vec2, i: Vec2| v.x * i.x + v.y * i.y);
+
+        assert_eq!(engine.eval::<Vec2>("let a = vec2(1, 2); -a").unwrap(), Vec2 { x: -1, y: -2 });
+        assert_eq!(engine.eval::<Vec2>("let a = vec2(1, 2); +a").unwrap(), Vec2 { x: 1, y: 2 });
+        assert_eq!(engine.eval::<Vec2>("let a = vec2(1, 2); !a").unwrap(), Vec2 { x: 2, y: 1 });
+        assert_eq!(engine.eval::<Vec2>("-vec2(1, 2)").unwrap(), Vec2 { x: -1, y: -2 });
+        assert_eq!(engine.eval::<Vec2>("let a = vec2(1, 2); -(-a)").unwrap(), Vec2 { x: 1, y: 2 });
+
+        // Inside larger expressions
+        assert_eq!(engine.eval::<INT>("let a = vec2(1, 2); let b = vec2(3, 4); -a * b").unwrap(), -11);
+        assert_eq!(engine.eval::<Vec2>("let a = vec2(1, 2); -a * 3").unwrap(), Vec2 { x: -3, y: -6 });
+        assert_eq!(engine.eval::<INT>("let a = vec2(1, 2); (-a).x + 10").unwrap(), 9);
+        assert_eq!(engine.eval::<INT>("let a = vec2(1, 2); let b = vec2(3, 4); b[-a]").unwrap(), -11);
+        assert_eq!(engine.eval::<INT>("let a = vec2(1, 2); let b = vec2(3, 4); b[!a] - (-b)[a]").unwrap(), 21);
+        assert_eq!(engine.eval::<Vec2>("let a = [vec2(1, 2)]; -a[0]").unwrap(), Vec2 { x: -1, y: -2 });
+
+        // Built-in types are not affected
+        assert_eq!(engine.eval::<INT>("let x = 42; -x").unwrap(), -42);
+        assert_eq!(engine.eval::<INT>("let x = 42; +x").unwrap(), 42);
+        assert!(engine.eval::<bool>("let x = false; !x").unwrap());
+        #[cfg(not(feature = "unchecked"))]
+        assert!(matches!(*engine.eval::<INT>(&format!("let x = -{} - 1; -x", INT::MAX)).unwrap_err(), EvalAltResult::ErrorArithmetic(..)));
+    }
+}
@@ -193,3 +193,401 @@ fn test_optimizer_full() {
 
     assert_eq!(scope.get_value::<TestStruct>("FOO").unwrap().0, 42);
 }
+
+#[test]
+fn test_optimizer_free_variables() {
+    let engine = Engine::new();
+
+    let ast = engine
+        .compile(
+            "
+                let x = A;
+                for (i, n) in range(0, B) { x += i * n + C; }
+                let y = { let z = D; z };
+                try { throw E; } catch (err) { x += err; }
+                x + y + this_is_free
+            ",
+        )
+        .unwrap();
+
+    let vars = ast.collect_free_variables();
+
+    assert_eq!(vars.iter().map(|v| v.as_str()).collect::<Vec<_>>(), ["A", "B", "C", "D", "E", "this_is_free"]);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let ast = engine.compile("fn foo(x) { let y = x; y + FOO } fn bar() { BAR } foo(BAZ)").unwrap();
+        let vars = ast.collect_free_variables();
+        assert_eq!(vars.iter().map(|v| v.as_str()).collect::<Vec<_>>(), ["BAR", "BAZ", "FOO"]);
+    }
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_optimizer_incremental() {
+    const SCRIPT: &str = "
+        fn foo() { if DEBUG { FOO * 2 } else { FOO } }
+        fn bar(x) { let y = x + 1; y * BAR }
+        fn baz() { 42 }
+        foo() + bar(1) + baz() + if DEBUG { 1 } else { 0 }
+    ";
+
+    let engine = Engine::new();
+    let mut scope = Scope::new();
+    scope.push_constant("FOO", 1 as INT);
+    scope.push_constant("BAR", 10 as INT);
+
+    let ast = engine.compile_with_scope(&scope, SCRIPT).unwrap();
+
+    scope.push_constant("DEBUG", true);
+
+    let full = engine.optimize_ast(&scope, ast.clone(), OptimizationLevel::Simple);
+    let incremental = engine.optimize_ast_incremental(&scope, ast.clone(), OptimizationLevel::Simple, &["DEBUG"]);
+
+    assert_eq!(format!("{incremental:?}"), format!("{full:?}"));
+    assert_eq!(engine.eval_ast::<INT>(&incremental).unwrap(), 65);
+    assert_eq!(engine.eval_ast::<INT>(&full).unwrap(), 65);
+    assert!(incremental.collect_free_variables().is_empty());
+
+    #[cfg(feature = "internals")]
+    {
+        let find = |ast: &rhai::AST, name: &str| ast.iter_fn_def().find(|f| f.name == name).unwrap().clone();
+
+        for name in ["foo", "bar", "baz"] {
+            assert_eq!(format!("{:?}", find(&incremental, name).body), format!("{:?}", find(&full, name).body));
+        }
+
+        // Untouched functions are not re-optimized
+        assert!(!std::ptr::eq(&find(&ast, "foo").body, &find(&incremental, "foo").body));
+        assert!(std::ptr::eq(&find(&ast, "bar").body, &find(&incremental, "bar").body));
+        assert!(std::ptr::eq(&find(&ast, "baz").body, &find(&incremental, "baz").body));
+    }
+
+    // Nothing changed
+    let ast2 = engine.optimize_ast_incremental(&scope, incremental.clone(), OptimizationLevel::Simple, &["NOTHING"]);
+    assert_eq!(format!("{ast2:?}"), format!("{incremental:?}"));
+}
+
+#[test]
+fn test_optimizer_foldable() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let squares = Arc::new(AtomicUsize::new(0));
+    let rands = Arc::new(AtomicUsize::new(0));
+
+    let mut module = Module::new();
+
+    let count = squares.clone();
+    let hash = module.set_native_fn("square", move |x: INT| {
+        count.fetch_add(1, Ordering::SeqCst);
+        Ok(x * x)
+    });
+    module.update_fn_foldable(hash, true);
+
+    let hash = module.set_native_fn("checked_half", |x: INT| if x % 2 == 0 { Ok(x / 2) } else { Err("odd number".into()) });
+    module.update_fn_foldable(hash, true);
+
+    let count = rands.clone();
+    module.set_native_fn("rand", move || {
+        count.fetch_add(1, Ordering::SeqCst);
+        Ok(4 as INT)
+    });
+
+    let module = rhai::Shared::new(module);
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Simple);
+    engine.register_global_module(module.clone());
+    #[cfg(not(feature = "no_module"))]
+    engine.register_static_module("math", module);
+
+    let ast = engine.compile("square(7) + rand()").unwrap();
+    assert_eq!(squares.load(Ordering::SeqCst), 1);
+    assert_eq!(rands.load(Ordering::SeqCst), 0);
+
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 53);
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 53);
+    assert_eq!(squares.load(Ordering::SeqCst), 1);
+    assert_eq!(rands.load(Ordering::SeqCst), 2);
+
+    #[cfg(not(feature = "no_module"))]
+    {
+        let ast = engine.compile("math::square(3) + math::rand()").unwrap();
+        assert_eq!(squares.load(Ordering::SeqCst), 2);
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 13);
+        assert_eq!(squares.load(Ordering::SeqCst), 2);
+        assert_eq!(rands.load(Ordering::SeqCst), 3);
+    }
+
+    // Errors during folding leave the call in place
+    let ast = engine.compile("checked_half(42) + checked_half(3)").unwrap();
+    assert!(engine.eval_ast::<INT>(&ast).unwrap_err().to_string().contains("odd number"));
+
+    // Script-defined functions override foldable functions
+    let count = squares.load(Ordering::SeqCst);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn square(x) { x } square(5)").unwrap(), 5);
+        assert_eq!(squares.load(Ordering::SeqCst), count);
+    }
+
+    engine.set_optimization_level(OptimizationLevel::None);
+    engine.compile("square(7)").unwrap();
+    assert_eq!(squares.load(Ordering::SeqCst), count);
+}
+
+#[test]
+fn test_optimizer_unroll_loops() {
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Simple);
+
+    assert_eq!(engine.loop_unroll_threshold(), 8);
+
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..4 { x += i * 2; } x").unwrap(), 12);
+    assert_eq!(engine.eval::<INT>("let x = 0; for (i, n) in 10..=13 { x += i * n; } x").unwrap(), 74);
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..4 { let y = i + 1; x += y; } x").unwrap(), 10);
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..4 { if i == 2 { continue; } x += i; } x").unwrap(), 4);
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..4 { if i == 2 { break; } x += i; } x").unwrap(), 1);
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..4 { for j in 0..9 { if j > i { break; } x += j; } } x").unwrap(), 10);
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..4 { i += 1; x += i; } x").unwrap(), 10);
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..4 { for i in 0..2 { x += i; } } x").unwrap(), 4);
+    assert_eq!(engine.eval::<INT>("let x = 0; for i in 0..20 { x += i; } x").unwrap(), 190);
+    assert_eq!(engine.eval::<()>("for i in 0..4 { i }").unwrap(), ());
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::Arc;
+
+        let ops = Arc::new(AtomicU64::new(0));
+        let count = ops.clone();
+
+        engine.on_progress(move |n| {
+            count.store(n, Ordering::SeqCst);
+            None
+        });
+
+        let script = "let x = 0; for i in 0..4 { x += i * 2; } x";
+
+        assert_eq!(engine.eval::<INT>(script).unwrap(), 12);
+        let unrolled = ops.load(Ordering::SeqCst);
+
+        engine.set_loop_unroll_threshold(0);
+        assert_eq!(engine.eval::<INT>(script).unwrap(), 12);
+        let looped = ops.load(Ordering::SeqCst);
+
+        assert!(unrolled < looped, "{unrolled} >= {looped}");
+    }
+}
+
+#[cfg(not(feature = "no_module"))]
+#[test]
+fn test_optimizer_fold_module_constants() {
+    use rhai::module_resolvers::StaticModuleResolver;
+
+    let mut module = Module::new();
+    module.set_var("MAX", 100 as INT);
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Simple);
+    engine.register_static_module("c", module.clone().into());
+
+    let script = "let x = 0; for i in 0..50 { x += c::MAX; } x";
+
+    // Not folded by default
+    assert!(!engine.fold_module_constants());
+    let ast = engine.compile(script).unwrap();
+    assert!(format!("{ast:?}").contains("c::MAX"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 5000);
+
+    engine.set_fold_module_constants(true);
+
+    let ast = engine.compile(script).unwrap();
+    let debug = format!("{ast:?}");
+    assert!(!debug.contains("c::MAX"), "{}", debug);
+    #[cfg(not(feature = "no_position"))]
+    assert!(debug.contains("100 @ 1:34"), "{}", debug);
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 5000);
+
+    // Imported modules shadow static modules
+    let ast = engine.compile(r#"import "c" as c; c::MAX"#).unwrap();
+    assert!(format!("{ast:?}").contains("c::MAX"));
+
+    // Modules embedded in the AST
+    let mut resolver = StaticModuleResolver::new();
+    resolver.insert("consts", module);
+    engine.set_module_resolver(resolver);
+
+    let ast = engine.compile_into_self_contained(&Scope::new(), r#"import "consts" as k; let x = 0; for i in 0..50 { x += k::MAX; } x"#).unwrap();
+    assert!(!format!("{ast:?}").contains("k::MAX"));
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 5000);
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let ast = engine.compile("fn foo(x) { x + c::MAX } foo(1)").unwrap();
+        assert!(!format!("{ast:?}").contains("c::MAX"));
+        assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 101);
+    }
+}
+
+#[cfg(feature = "internals")]
+#[cfg(not(feature = "no_position"))]
+#[test]
+fn test_optimizer_node_ids() {
+    use rhai::{ASTNode, Expr, Stmt, AST};
+
+    fn find_id(ast: &AST, f: impl Fn(&ASTNode) -> bool) -> u32 {
+        let mut id = 0;
+        ast.walk(&mut |path| {
+            let node = path.last().unwrap();
+            if f(node) {
+                id = node.id(ast);
+            }
+            id == 0
+        });
+        id
+    }
+
+    let is_40 = |node: &ASTNode| matches!(node, ASTNode::Expr(Expr::IntegerConstant(40, ..)));
+    let is_0 = |node: &ASTNode| matches!(node, ASTNode::Expr(Expr::IntegerConstant(0, ..)));
+    let is_debug = |node: &ASTNode| matches!(node, ASTNode::Expr(Expr::Variable(..)));
+    let is_let = |node: &ASTNode| matches!(node, ASTNode::Stmt(Stmt::Var(..)));
+    let is_add = |node: &ASTNode| matches!(node, ASTNode::Stmt(Stmt::Expr(..) | Stmt::FnCall(..)));
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::None);
+
+    let ast = engine.compile("let x = 40; x + 2").unwrap();
+    assert_eq!(find_id(&ast, is_40), 0);
+
+    engine.set_assign_node_ids(true);
+
+    let ast = engine.compile("let x = 40; if DEBUG { x + 2 } else { 0 }").unwrap();
+
+    let mut ids = Vec::new();
+    ast.walk(&mut |path| {
+        let node = path.last().unwrap();
+        let id = node.id(&ast);
+        assert_eq!(ast.node_by_id(id).unwrap(), *node);
+        ids.push(id);
+        true
+    });
+    assert_eq!(ids, (1..=ids.len() as u32).collect::<Vec<_>>());
+    assert!(ast.node_by_id(0).is_none());
+
+    let id_40 = find_id(&ast, is_40);
+    let id_0 = find_id(&ast, is_0);
+    let id_debug = find_id(&ast, is_debug);
+    let id_let = find_id(&ast, is_let);
+    let id_add = find_id(&ast, is_add);
+
+    let mut scope = Scope::new();
+    scope.push_constant("DEBUG", true);
+
+    let ast = engine.optimize_ast(&scope, ast, OptimizationLevel::Simple);
+
+    // Retained nodes keep their ID's
+    assert_eq!(find_id(&ast, is_40), id_40);
+    assert_eq!(find_id(&ast, is_let), id_let);
+    assert_eq!(find_id(&ast, is_add), id_add);
+    assert!(is_40(&ast.node_by_id(id_40).unwrap()));
+    assert!(is_add(&ast.node_by_id(id_add).unwrap()));
+
+    // Nodes optimized away are gone
+    assert!(ast.node_by_id(id_0).is_none());
+    assert!(ast.node_by_id(id_debug).is_none());
+}
+
+#[test]
+fn test_optimizer_compile_options() {
+    use rhai::CompileOptions;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let reads = Arc::new(AtomicUsize::new(0));
+
+    // A getter with side effects, but marked as foldable
+    let mut module = Module::new();
+    let count = reads.clone();
+    let hash = module.set_native_fn("reading", move || Ok(count.fetch_add(1, Ordering::SeqCst) as INT));
+    module.update_fn_foldable(hash, true);
+
+    let mut engine = Engine::new();
+    engine.set_optimization_level(OptimizationLevel::Simple);
+    engine.register_global_module(module.into());
+
+    let reset = || reads.swap(0, Ordering::SeqCst);
+
+    // Folded under the default optimization level
+    let ast = engine.compile("reading(); reading()").unwrap();
+    assert_eq!(reset(), 2);
+    engine.run_ast(&ast).unwrap();
+    engine.run_ast(&ast).unwrap();
+    assert_eq!(reset(), 0);
+
+    // Preserved when optimization is turned off for this compilation
+    let options = CompileOptions::new().with_optimization_level(OptimizationLevel::None);
+    let ast = engine.compile_with_options("reading(); reading()", options).unwrap();
+    assert_eq!(reset(), 0);
+    engine.run_ast(&ast).unwrap();
+    engine.run_ast(&ast).unwrap();
+    assert_eq!(reset(), 4);
+    assert_eq!(engine.optimization_level(), OptimizationLevel::Simple);
+
+    // Preserved when optimization is turned off via a marker comment before any code
+    let ast = engine.compile("// reads the sensor\n//! rhai:optimize=off\nreading(); reading()").unwrap();
+    assert_eq!(reset(), 0);
+    engine.run_ast(&ast).unwrap();
+    assert_eq!(reset(), 2);
+
+    // A marker comment after the first statement does not apply to the script
+    engine.compile("reading();\n//! rhai:optimize=off\nreading()").unwrap();
+    assert_eq!(reset(), 2);
+
+    // Strict Variables mode is turned on or off for this compilation
+    let strict = CompileOptions::new().with_strict_variables(true);
+    assert!(engine.compile_with_options("let x = 1; x + y", strict).is_err());
+    assert!(engine.compile("let x = 1; x + y").is_ok());
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        assert!(engine.compile_with_options("fn foo() { y }", strict).is_err());
+
+        engine.set_strict_variables(true);
+        let lax = CompileOptions::new().with_strict_variables(false);
+        assert!(engine.compile("fn foo() { y }").is_err());
+        assert!(engine.compile_with_options("fn foo() { y }", lax).is_ok());
+        engine.set_strict_variables(false);
+
+        // Only the function after the marker comment is excluded from optimization
+        let script = "
+            fn folded() { reading() }
+
+            //! rhai:optimize=off
+            fn preserved() { reading() }
+
+            folded() + preserved()
+        ";
+
+        let ast = engine.compile(script).unwrap();
+        assert_eq!(reset(), 1);
+        engine.run_ast(&ast).unwrap();
+        assert_eq!(reset(), 1);
+
+        // Re-optimizing the AST keeps the function excluded
+        let ast = engine.optimize_ast(&Scope::new(), ast, OptimizationLevel::Full);
+        assert_eq!(reset(), 0);
+        engine.run_ast(&ast).unwrap();
+        assert_eq!(reset(), 1);
+
+        // Marker comments may precede doc-comments and are not part of the documentation
+        #[cfg(feature = "metadata")]
+        {
+            let ast = engine.compile("//! Sensor\n//! rhai:optimize=off\n\n//! rhai:optimize=off\n/// Read it\nfn preserved() { reading() }").unwrap();
+            assert_eq!(ast.doc(), "//! Sensor");
+            assert_eq!(reset(), 0);
+        }
+    }
+}
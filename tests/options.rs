@@ -1,4 +1,4 @@
-use rhai::{Engine, Scope, INT};
+use rhai::{Dynamic, Engine, LanguageVersion, ParseErrorType, ParseWarningKind, Scope, ShadowingPolicy, INT};
 
 #[test]
 fn test_options_allow() {
@@ -48,6 +48,88 @@ fn test_options_allow() {
     assert!(engine.run_with_scope(&mut scope, "let x = 42;").is_err());
 }
 
+#[test]
+fn test_options_shadowing_policy() {
+    let mut engine = Engine::new();
+
+    let script = "let x = 1; { let x = x + 1; print(x); } let x = x * 2; x";
+
+    assert_eq!(engine.shadowing_policy(), ShadowingPolicy::Allow);
+    assert!(engine.compile_with_warnings(script).unwrap().1.is_empty());
+
+    engine.set_shadowing_policy(ShadowingPolicy::Warn);
+    assert!(engine.allow_shadowing());
+
+    let (ast, warnings) = engine.compile_with_warnings(script).unwrap();
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 2);
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings.iter().all(|w| w.kind == ParseWarningKind::ShadowedVariable && w.message == "Variable 'x' shadows an existing variable"));
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(warnings.iter().map(|w| w.position.position().unwrap()).collect::<Vec<_>>(), [18, 45]);
+
+    engine.compile(script).unwrap();
+
+    engine.set_shadowing_policy(ShadowingPolicy::Deny);
+    assert!(!engine.allow_shadowing());
+    assert!(matches!(engine.compile(script).unwrap_err().err_type(), ParseErrorType::VariableExists(s) if s == "x"));
+    engine.compile("let x = 1; { let y = x + 1; } let y = 2;").unwrap();
+
+    // Variables in the input scope
+    let mut scope = Scope::new();
+    scope.push("x", 42 as INT);
+
+    assert!(matches!(engine.compile_with_scope(&scope, "let x = 1;").unwrap_err().err_type(), ParseErrorType::VariableExists(s) if s == "x"));
+    #[cfg(not(feature = "no_function"))]
+    engine.compile_with_scope(&scope, "fn foo() { let x = 1; x }").unwrap();
+
+    engine.set_allow_shadowing(true);
+    assert_eq!(engine.shadowing_policy(), ShadowingPolicy::Allow);
+    engine.compile_with_scope(&scope, "let x = 1;").unwrap();
+
+    // Variable definition filters see the same shadowing flag
+    #[allow(deprecated)] // not deprecated but unstable
+    engine.on_def_var(|_, info, _| Ok(!info.will_shadow_other_variables()));
+
+    assert!(matches!(engine.compile_with_scope(&scope, "let x = 1;").unwrap_err().err_type(), ParseErrorType::ForbiddenVariable(s) if s == "x"));
+    engine.compile_with_scope(&scope, "let y = 1;").unwrap();
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_options_param_shadowing_policy() {
+    let mut engine = Engine::new();
+
+    let script = "let x = 1; fn foo(x, y) { x + y } foo(x, 2)";
+
+    // Independent of the policy on variables shadowing
+    engine.set_shadowing_policy(ShadowingPolicy::Deny);
+    assert_eq!(engine.param_shadowing_policy(), ShadowingPolicy::Allow);
+    assert!(engine.compile_with_warnings(script).unwrap().1.is_empty());
+
+    engine.set_shadowing_policy(ShadowingPolicy::Allow);
+    engine.set_param_shadowing_policy(ShadowingPolicy::Warn);
+
+    let (ast, warnings) = engine.compile_with_warnings(script).unwrap();
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 3);
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].kind, ParseWarningKind::ShadowedParameter);
+    assert_eq!(warnings[0].message, "Parameter 'x' of function 'foo' shadows a global variable");
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(warnings[0].position.position(), Some(19));
+
+    assert!(engine.compile_with_warnings("fn foo(x) { x } let x = 1; foo(x)").unwrap().1.is_empty());
+
+    engine.set_param_shadowing_policy(ShadowingPolicy::Deny);
+
+    assert!(matches!(engine.compile(script).unwrap_err().err_type(), ParseErrorType::VariableExists(s) if s == "x"));
+    engine.compile("fn foo(x) { x } let x = 1; foo(x)").unwrap();
+
+    let mut scope = Scope::new();
+    scope.push("y", 42 as INT);
+
+    assert!(matches!(engine.compile_with_scope(&scope, "fn foo(y) { y }").unwrap_err().err_type(), ParseErrorType::VariableExists(s) if s == "y"));
+}
+
 #[test]
 fn test_options_strict_var() {
     let mut engine = Engine::new();
@@ -113,3 +195,353 @@ fn test_options_strict_var() {
         assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "fn foo(z) { y + z } foo(x)").unwrap(), 42);
     }
 }
+
+#[test]
+fn test_options_strict_fn() {
+    let mut engine = Engine::new();
+
+    engine.register_fn("add_one", |x: INT| x + 1);
+    engine.register_fn("show", |_: Dynamic| ());
+
+    engine.compile(r#"prnt("x");"#).unwrap();
+
+    engine.set_strict_functions(true);
+
+    let err = engine.compile(r#"let x = 42; prnt("x");"#).unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::FnUndefined("prnt".into(), 1, Some("print".into())));
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.position(), rhai::Position::new(1, 13));
+    assert_eq!(err.err_type().to_string(), "Undefined function: prnt with 1 argument (did you mean print?)");
+
+    // Wrong number of arguments
+    assert!(matches!(*engine.compile("add_one(1, 2)").unwrap_err().err_type(), ParseErrorType::FnUndefined(ref name, 2, None) if name == "add_one"));
+    assert!(matches!(*engine.compile_expression("xyzzy()").unwrap_err().err_type(), ParseErrorType::FnUndefined(ref name, 0, None) if name == "xyzzy"));
+
+    engine.compile(r#"let x = add_one(-1); show(x); show("hello"); print(type_of(x)); debug(x == 0 && x != 1)"#).unwrap();
+    engine.compile_expression("add_one(40) + 1").unwrap();
+
+    #[cfg(not(feature = "no_object"))]
+    engine.compile("let x = 42; x.foo(); x.bar(1, 2, 3);").unwrap();
+
+    #[cfg(not(feature = "no_index"))]
+    engine.compile("let x = [1, 2, 3]; len(x) + x[0]").unwrap();
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        engine.compile("fn foo(x, y = 1) { bar(x) } fn bar(x) { foo(x, 2) + foo(x) }").unwrap();
+        assert!(engine.compile("fn foo(x, y = 1) { x } foo()").is_err());
+        assert!(engine.compile("fn foo(x) { baz(x) }").is_err());
+        #[cfg(not(feature = "no_index"))]
+        engine.compile("fn foo(x, ...y) { x } foo(1); foo(1, 2, 3);").unwrap();
+        engine.compile(r#"let f = Fn("add_one"); call(f, 2); let g = curry(f, 3); call(g);"#).unwrap();
+        engine.compile(r#"eval("prnt(42)")"#).unwrap();
+        #[cfg(not(feature = "no_object"))]
+        engine.compile("let f = |x| x + 1; f.call(1)").unwrap();
+    }
+
+    #[cfg(not(feature = "no_module"))]
+    {
+        let mut module = rhai::Module::new();
+        module.set_native_fn("calc", |x: INT| Ok(x * 2));
+        engine.register_static_module("m", module.into());
+
+        engine.compile("m::calc(21)").unwrap();
+        let err = engine.compile("m::calx(21)").unwrap_err();
+        assert_eq!(*err.err_type(), ParseErrorType::FnUndefined("m::calx".into(), 1, Some("calc".into())));
+        assert!(engine.compile("n::calc(21)").is_err());
+
+        // Functions in imported modules cannot be checked
+        engine.compile(r#"import "hello" as h; h::foo(); bar();"#).unwrap();
+    }
+}
+
+#[test]
+fn test_options_language_version() {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.language_version(), LanguageVersion::LATEST);
+
+    let script = r#"let x = (); let y = x ?? 40; if "x" !in "abc" { y + 2 } else { 0 }"#;
+
+    let ast = engine.compile(script).unwrap();
+    assert_eq!(ast.language_version(), LanguageVersion::LATEST);
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 42);
+
+    // Features introduced in later versions are rejected
+    engine.set_language_version(LanguageVersion::new(1, 11));
+
+    let err = engine.compile(script).unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::UnsupportedLanguageFeature("'!in' operator".into(), "1.12".into()));
+    assert_eq!(err.err_type().to_string(), "'!in' operator requires language version 1.12 or above");
+
+    engine.set_language_version(LanguageVersion::new(1, 7));
+
+    let err = engine.compile(script).unwrap_err();
+    assert_eq!(*err.err_type(), ParseErrorType::UnsupportedLanguageFeature("Null-coalescing operator '??'".into(), "1.8".into()));
+
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        engine.compile("let x = #{a: 1}; x?.a").unwrap_err().err_type(),
+        ParseErrorType::UnsupportedLanguageFeature(f, v) if f == "Elvis operator '?.'" && v == "1.8"
+    ));
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    assert!(matches!(
+        engine.compile("fn int.foo() { this }").unwrap_err().err_type(),
+        ParseErrorType::UnsupportedLanguageFeature(f, v) if f == "Typed method" && v == "1.14"
+    ));
+
+    // Old scripts parse identically under older and newer versions
+    let script = "let x = 40; x + 2";
+
+    let old_ast = engine.compile(script).unwrap();
+    assert_eq!(old_ast.language_version(), LanguageVersion::new(1, 7));
+    engine.set_language_version(LanguageVersion::LATEST);
+    let new_ast = engine.compile(script).unwrap();
+    assert_eq!(format!("{:?}", old_ast), format!("{:?}", new_ast));
+    assert_eq!(engine.eval_ast::<INT>(&old_ast).unwrap(), 42);
+
+    // Merged ASTs have the latest version among them
+    assert_eq!(old_ast.merge(&new_ast).language_version(), LanguageVersion::LATEST);
+
+    // `is_shared` is only a keyword in newer versions
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let script = "let is_shared = 42; is_shared";
+
+        assert!(engine.compile(script).is_err());
+        engine.set_language_version(LanguageVersion::new(1, 13));
+        assert_eq!(engine.eval::<INT>(script).unwrap(), 42);
+    }
+
+    // Individual features can still be turned off under the latest version
+    engine.set_language_version(LanguageVersion::LATEST);
+    engine.disable_symbol("??");
+    assert!(engine.compile("let x = (); x ?? 42").is_err());
+}
+
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_position"))]
+#[test]
+fn test_options_track_provenance() {
+    use rhai::{EvalAltResult, Position};
+
+    let mut engine = Engine::new();
+
+    let script = "
+fn pass(v) { v }
+let a = 42;
+let b = a;
+let c = b;
+let d = pass(c);
+";
+
+    assert_eq!(engine.eval::<Dynamic>(&format!("{script} d")).unwrap().provenance(), None);
+
+    engine.set_track_provenance(true);
+
+    let value = engine.eval::<Dynamic>(&format!("{script} d")).unwrap();
+    assert_eq!(value.as_int().unwrap(), 42);
+    assert_eq!(value.provenance(), Some(Position::new(3, 9)));
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        let map = engine.eval::<rhai::Map>(&format!("{script} provenance(d)")).unwrap();
+        assert_eq!(map["line"].as_int().unwrap(), 3);
+        assert_eq!(map["position"].as_int().unwrap(), 9);
+    }
+
+    // Arithmetic replaces the provenance with the position of the operator
+    let value = engine.eval::<Dynamic>(&format!("{script} let e = d + 1; e")).unwrap();
+    assert_eq!(value.provenance(), Some(Position::new(7, 10)));
+
+    let value = engine.eval::<Dynamic>(&format!("{script} d *= 2; d")).unwrap();
+    assert_eq!(value.provenance(), Some(Position::new(7, 4)));
+
+    // Runtime errors include the origins of the offending operands
+    let err = engine.run(&format!(r#"{script} let s = "x"; d - s"#)).unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(ref sig, ..) if sig.ends_with("[#1 from line 3, position 9; #2 from line 7, position 10]")), "{}", err);
+}
+
+#[cfg(not(feature = "no_position"))]
+#[test]
+fn test_options_error_spans() {
+    use rhai::{EvalAltResult, Position, Span};
+
+    let mut engine = Engine::new();
+
+    let script = "let a = (); let b = 2; let c = 3;\na + b * c";
+
+    let err = engine.run(script).unwrap_err();
+    assert_eq!(err.span(), None);
+    assert!(matches!(*err, EvalAltResult::ErrorFunctionNotFound(..)));
+
+    engine.set_error_spans(true);
+
+    // The span covers the whole binary expression
+    let err = engine.run(script).unwrap_err();
+    assert_eq!(err.position(), Position::new(2, 1));
+    assert_eq!(err.span(), Some(Span::new(Position::new(2, 1), Position::new(2, 9))));
+    assert!(matches!(err.unwrap_inner(), EvalAltResult::ErrorFunctionNotFound(f, ..) if f.starts_with("+ (")));
+    assert_eq!(err.to_string(), engine.set_error_spans(false).run(script).unwrap_err().to_string());
+    engine.set_error_spans(true);
+
+    // The span is that of the innermost expression
+    let err = engine.run("let a = 1; let b = (); let c = 3;\nlet x = a + b * c;").unwrap_err();
+    assert_eq!(err.span(), Some(Span::new(Position::new(2, 13), Position::new(2, 17))));
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let err = engine.run("let x = [1, 2, 3];\nx[1 + 1] + x[10]").unwrap_err();
+        assert_eq!(err.span(), Some(Span::new(Position::new(2, 12), Position::new(2, 16))));
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        let err = engine.run("fn foo(x, y) { x + y }\nlet z = foo(1, () );").unwrap_err();
+        assert_eq!(err.span(), Some(Span::new(Position::new(2, 9), Position::new(2, 19))));
+        assert!(matches!(*err, EvalAltResult::ErrorWithSpan(ref e, ..) if matches!(**e, EvalAltResult::ErrorInFunctionCall(..))));
+    }
+
+    // Errors caught by `try` are not affected
+    #[cfg(not(feature = "no_object"))]
+    assert_eq!(engine.eval::<String>("let r = (); try { let a = (); a + 1; } catch (e) { r = e.error } r").unwrap(), "ErrorFunctionNotFound");
+}
+
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_options_settings() {
+    use rhai::{Array, Map, SettingsError};
+
+    let mut engine = Engine::new();
+
+    engine.set_strict_variables(true).set_fast_operators(false).disable_symbol("while").disable_symbol("+=");
+    engine.disable_function("eval");
+    engine.set_language_version(LanguageVersion::new(1, 8));
+    #[cfg(not(feature = "unchecked"))]
+    engine.set_max_operations(1000).set_max_string_size(42).set_max_tokens(5000);
+    #[cfg(not(feature = "no_optimize"))]
+    engine.set_optimization_level(rhai::OptimizationLevel::Full);
+
+    let settings = engine.settings();
+
+    assert_eq!(settings["version"].as_int().unwrap(), rhai::SETTINGS_VERSION);
+    assert_eq!(settings["disabled_symbols"].clone().into_typed_array::<String>().unwrap(), ["+=", "while"]);
+    assert!(settings["callbacks"].read_lock::<Map>().unwrap()["print"].as_bool().unwrap());
+
+    let mut engine2 = Engine::new();
+    engine2.apply_settings(&settings).unwrap();
+
+    assert!(engine2.strict_variables());
+    assert!(!engine2.fast_operators());
+    assert!(engine2.is_symbol_disabled("while"));
+    assert!(engine2.is_function_disabled("eval"));
+    assert_eq!(engine2.language_version(), LanguageVersion::new(1, 8));
+    #[cfg(not(feature = "unchecked"))]
+    assert_eq!(engine2.max_operations(), 1000);
+    #[cfg(not(feature = "unchecked"))]
+    assert_eq!(engine2.max_tokens(), 5000);
+    assert_eq!(format!("{:?}", engine2.settings()), format!("{:?}", settings));
+
+    // Partial settings
+    let mut partial = Map::new();
+    let mut options = Map::new();
+    options.insert("strict_variables".into(), false.into());
+    partial.insert("options".into(), options.into());
+    partial.insert("disabled_symbols".into(), Array::new().into());
+    engine2.apply_settings(&partial).unwrap();
+    assert!(!engine2.strict_variables());
+    assert!(!engine2.fast_operators());
+    assert!(!engine2.is_symbol_disabled("while"));
+
+    // Invalid settings are rejected with the key named, without changing anything
+    let mut bad = settings.clone();
+    bad.insert("foo".into(), Dynamic::UNIT);
+    assert_eq!(engine2.apply_settings(&bad).unwrap_err(), SettingsError::UnknownKey("foo".into()));
+    assert!(!engine2.is_symbol_disabled("while"));
+
+    let mut bad = settings.clone();
+    bad.insert("version".into(), (999 as INT).into());
+    assert_eq!(engine2.apply_settings(&bad).unwrap_err().key(), "version");
+
+    let mut bad = Map::new();
+    let mut options = Map::new();
+    options.insert("strict_variables".into(), (1 as INT).into());
+    bad.insert("options".into(), options.into());
+    let err = engine2.apply_settings(&bad).unwrap_err();
+    assert!(matches!(err, SettingsError::InvalidValue(ref key, ..) if key == "options.strict_variables"), "{}", err);
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let mut bad = Map::new();
+        let mut limits = Map::new();
+        limits.insert("max_operations".into(), (-1 as INT).into());
+        bad.insert("limits".into(), limits.into());
+        assert_eq!(engine2.apply_settings(&bad).unwrap_err().key(), "limits.max_operations");
+    }
+
+    let mut bad = Map::new();
+    bad.insert("language_version".into(), "latest".into());
+    assert_eq!(engine2.apply_settings(&bad).unwrap_err().key(), "language_version");
+
+    #[cfg(not(feature = "no_optimize"))]
+    {
+        let mut bad = Map::new();
+        bad.insert("optimization_level".into(), "fastest".into());
+        assert_eq!(engine2.apply_settings(&bad).unwrap_err().key(), "optimization_level");
+    }
+}
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_options_capture_stack_on_error() {
+    use rhai::Position;
+
+    let mut engine = Engine::new();
+
+    let script = "
+        fn c(x) {
+            throw x;
+        }
+        fn b(x) { c(x + 1) }
+        fn a(x) { b(x + 1) }
+        a(1)
+    ";
+
+    let err = engine.run(script).unwrap_err();
+    assert!(err.call_stack().is_none());
+
+    engine.set_capture_stack_on_error(true);
+
+    let err = engine.run(script).unwrap_err();
+    let stack = err.call_stack().expect("call stack");
+    let frames = stack.iter().map(|(name, pos)| (name.as_str(), *pos)).collect::<Vec<_>>();
+
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(frames, [("a", Position::new(7, 9)), ("b", Position::new(6, 19)), ("c", Position::new(5, 19))]);
+    #[cfg(feature = "no_position")]
+    assert_eq!(frames, [("a", Position::NONE), ("b", Position::NONE), ("c", Position::NONE)]);
+
+    // The error itself is unchanged
+    assert_eq!(err.error_value().unwrap().as_int().unwrap(), 3);
+    assert_eq!(err.to_string(), engine.set_capture_stack_on_error(false).run(script).unwrap_err().to_string());
+    engine.set_capture_stack_on_error(true);
+
+    // Errors caught inside functions do not leave stale frames behind
+    let err = engine.run("fn f() { throw 1; } fn g() { try { f() } catch { } throw 2; } g()").unwrap_err();
+    assert_eq!(err.call_stack().unwrap().iter().map(|(name, ..)| name.as_str()).collect::<Vec<_>>(), ["g"]);
+
+    // Errors outside of functions have no call stack
+    assert!(engine.run("throw 42;").unwrap_err().call_stack().is_none());
+
+    // System errors are not wrapped
+    #[cfg(not(feature = "unchecked"))]
+    {
+        engine.set_max_call_levels(10);
+        let err = engine.run("fn f(x) { f(x + 1) } f(0)").unwrap_err();
+        assert!(matches!(*err, rhai::EvalAltResult::ErrorStackOverflow(..)));
+        assert!(err.call_stack().is_none());
+    }
+}
@@ -51,3 +51,59 @@ fn test_packages_with_script() {
     assert_eq!(engine.eval::<INT>("foo(41)").unwrap(), 42);
     assert_eq!(engine.eval::<INT>("bar(40)").unwrap(), 42);
 }
+
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_packages_fn_metadata() {
+    use rhai::{Array, Dynamic};
+    use std::any::TypeId;
+
+    let package = SSS::new();
+    let module = package.as_shared_module();
+
+    let push = module.iter_fn_metadata().find(|f| f.name == "push" && f.param_types == [TypeId::of::<Array>(), TypeId::of::<Dynamic>()]).expect("push(Array, Dynamic) should exist");
+
+    assert_eq!(push.num_params, 2);
+    assert!(push.is_plugin_fn);
+    assert!(push.is_method);
+    assert!(!push.is_pure);
+    assert!(!push.is_script);
+
+    #[cfg(feature = "metadata")]
+    {
+        assert_eq!(push.param_type_names, ["&mut Array", "Dynamic"]);
+        assert_eq!(push.return_type, "");
+    }
+
+    let len = module.iter_fn_metadata().find(|f| f.name == "len" && f.param_types == [TypeId::of::<Array>()]).unwrap();
+    assert!(len.is_pure);
+
+    let mut module = Module::new();
+    let hash = module.set_native_fn("calc", |x: INT, y: &str| Ok(x + y.len() as INT));
+
+    let calc = module.iter_fn_metadata().find(|f| f.name == "calc").unwrap();
+    assert_eq!(calc.param_types, [TypeId::of::<INT>(), TypeId::of::<rhai::ImmutableString>()]);
+    assert!(!calc.is_plugin_fn);
+    assert!(!calc.is_method);
+    assert!(!calc.is_foldable);
+
+    module.update_fn_foldable(hash, true);
+    assert!(module.iter_fn_metadata().find(|f| f.name == "calc").unwrap().is_foldable);
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_module"))]
+    {
+        let engine = Engine::new();
+        let ast = engine.compile("fn foo(x, y) { x + y }").unwrap();
+        let module = Module::eval_ast_as_new(Scope::new(), &ast, &engine).unwrap();
+
+        let foo = module.iter_fn_metadata().find(|f| f.name == "foo").unwrap();
+        assert_eq!(foo.num_params, 2);
+        assert_eq!(foo.access, rhai::FnAccess::Public);
+        assert!(foo.param_types.is_empty());
+        assert!(foo.is_script);
+
+        #[cfg(feature = "metadata")]
+        assert_eq!(foo.param_type_names, ["Dynamic", "Dynamic"]);
+    }
+}
@@ -1,5 +1,9 @@
-use rhai::{Engine, Scope, INT};
+use rhai::{DebugFormat, Engine, Scope, INT};
 use std::sync::{Arc, RwLock};
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_closure"))]
+use rhai::{Array, Dynamic};
 
 #[cfg(not(feature = "only_i32"))]
 #[cfg(not(feature = "only_i64"))]
@@ -95,3 +99,56 @@ fn test_print_custom_type() {
         .unwrap()
         .contains(r#""e": hello: 42"#));
 }
+
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_print_debug_format() {
+    let mut engine = Engine::new();
+    let script = r#"let x = [1, #{a: "hello", b: [true, []], c: #{}}]; x"#;
+
+    assert_eq!(engine.eval::<String>(&format!("to_debug({{ {script} }})")).unwrap(), r#"[1, #{"a": "hello", "b": [true, []], "c": #{}}]"#);
+
+    engine.set_debug_format(DebugFormat::Pretty { indent: 4 });
+
+    assert_eq!(
+        engine.eval::<String>(&format!("to_debug({{ {script} }})")).unwrap(),
+        "[\n    1,\n    #{\n        \"a\": \"hello\",\n        \"b\": [\n            true,\n            [],\n        ],\n        \"c\": #{},\n    },\n]"
+    );
+
+    // print is not affected
+    assert_eq!(engine.eval::<String>(&format!("to_string({{ {script} }})")).unwrap(), r#"[1, #{"a": "hello", "b": [true, []], "c": #{}}]"#);
+
+    let logbook = Arc::new(RwLock::new(Vec::<String>::new()));
+    let log1 = logbook.clone();
+    let log2 = logbook.clone();
+
+    engine.on_print(move |s| log1.write().unwrap().push(s.to_string())).on_debug(move |s, _, _| log2.write().unwrap().push(s.to_string()));
+    engine.run(&format!("print({{ {script} }}); debug(#{{x: [1]}});")).unwrap();
+
+    assert_eq!(*logbook.read().unwrap(), [r#"[1, #{"a": "hello", "b": [true, []], "c": #{}}]"#, "#{\n    \"x\": [\n        1,\n    ],\n}"]);
+}
+
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_closure"))]
+#[test]
+fn test_print_debug_format_cycles() {
+    let mut engine = Engine::new();
+
+    // A shared array that contains itself
+    let mut x = Dynamic::from_array(vec![Dynamic::from(42 as INT)]).into_shared();
+    let x2 = x.clone();
+    x.write_lock::<Array>().unwrap().push(x2);
+
+    let mut scope = Scope::new();
+    scope.push("x", x);
+
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "x.to_debug()").unwrap(), "[42, <shared #1>]");
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "to_debug(x)").unwrap(), "[42, [42, <shared #1>]]");
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "x.to_string()").unwrap(), "[42, <shared #1>]");
+
+    engine.set_debug_format(DebugFormat::Pretty { indent: 2 });
+
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "x.to_debug()").unwrap(), "[\n  42,\n  <shared #1>,\n]");
+}
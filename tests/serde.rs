@@ -599,6 +599,85 @@ fn test_serde_de_untagged_enum() {
     assert_eq!(MyEnum::VariantStruct2 { b: 123 }, from_dynamic(&map.into()).unwrap());
 }
 
+#[test]
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+fn test_serde_enum_round_trip() {
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum External {
+        Unit,
+        Newtype(INT),
+        Tuple(INT, INT),
+        Struct { a: INT, b: String },
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "type")]
+    enum Internal {
+        Unit,
+        Struct { a: INT, b: String },
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(tag = "t", content = "c")]
+    enum Adjacent {
+        Unit,
+        Newtype(INT),
+        Tuple(INT, INT),
+        Struct { a: INT, b: String },
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Untagged {
+        Struct { a: INT, b: String },
+        Tuple(INT, INT),
+        Newtype(bool),
+    }
+
+    fn round_trip<T: Serialize + serde::de::DeserializeOwned + PartialEq + std::fmt::Debug>(values: &[T]) {
+        for value in values {
+            let d = to_dynamic(value).unwrap();
+            assert_eq!(&from_dynamic::<T>(&d).unwrap(), value);
+        }
+        let d = to_dynamic(values).unwrap();
+        assert_eq!(from_dynamic::<Vec<T>>(&d).unwrap(), values);
+    }
+
+    round_trip(&[External::Unit, External::Newtype(1), External::Tuple(1, 2), External::Struct { a: 1, b: "x".into() }]);
+    round_trip(&[Internal::Unit, Internal::Struct { a: 1, b: "x".into() }]);
+    round_trip(&[Adjacent::Unit, Adjacent::Newtype(1), Adjacent::Tuple(1, 2), Adjacent::Struct { a: 1, b: "x".into() }]);
+    round_trip(&[Untagged::Struct { a: 1, b: "x".into() }, Untagged::Tuple(1, 2), Untagged::Newtype(true)]);
+
+    // Maps produced by scripts
+    let engine = Engine::new();
+
+    let d = engine.eval::<Dynamic>(r#"[#{ type: "Unit" }, #{ type: "Struct", a: 42, b: "hello" }]"#).unwrap();
+    assert_eq!(from_dynamic::<Vec<Internal>>(&d).unwrap(), [Internal::Unit, Internal::Struct { a: 42, b: "hello".into() }]);
+
+    let d = engine.eval::<Dynamic>(r#"#{ t: "Tuple", c: [1, 2] }"#).unwrap();
+    assert_eq!(from_dynamic::<Adjacent>(&d).unwrap(), Adjacent::Tuple(1, 2));
+
+    let d = engine.eval::<Dynamic>(r#"[#{ a: 1, b: "x" }, [1, 2], false]"#).unwrap();
+    assert_eq!(from_dynamic::<Vec<Untagged>>(&d).unwrap(), [Untagged::Struct { a: 1, b: "x".into() }, Untagged::Tuple(1, 2), Untagged::Newtype(false)]);
+
+    // Errors include the available keys
+    let d = engine.eval::<Dynamic>(r#"#{ kind: "Struct", a: 42 }"#).unwrap();
+    assert_eq!(from_dynamic::<Internal>(&d).unwrap_err().to_string(), "Syntax error: missing field `type` (available keys: `a`, `kind`)");
+
+    let d = engine.eval::<Dynamic>(r#"#{ type: "Other" }"#).unwrap();
+    assert!(from_dynamic::<Internal>(&d).unwrap_err().to_string().ends_with("(available keys: `type`)"));
+
+    let d = engine.eval::<Dynamic>(r#"#{ x: #{ type: "Struct", a: 42 } }"#).unwrap();
+    assert_eq!(from_dynamic::<std::collections::BTreeMap<String, Internal>>(&d).unwrap_err().to_string(), "Syntax error: missing field `b` (available keys: `a`, `type`)");
+
+    let d = engine.eval::<Dynamic>("#{ c: 1 }").unwrap();
+    assert!(from_dynamic::<Untagged>(&d).unwrap_err().to_string().ends_with("(available keys: `c`)"));
+
+    let d = engine.eval::<Dynamic>("#{ Newtype: 1, Unit: () }").unwrap();
+    assert!(from_dynamic::<External>(&d).unwrap_err().to_string().ends_with("but found 2 keys (`Newtype`, `Unit`)"));
+}
+
 #[test]
 #[cfg(feature = "metadata")]
 #[cfg(not(feature = "no_object"))]
@@ -756,3 +835,113 @@ fn test_serde_scope() {
     assert!(scope.get_value::<bool>("y").unwrap());
     assert_eq!(scope.get_value::<String>("z").unwrap(), "serde::test_serde_scope::TestStruct");
 }
+
+#[test]
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+fn test_serde_to_from_scope() {
+    use rhai::serde::{from_scope, to_scope, to_scope_constants};
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    enum Mode {
+        Fast,
+        Limited(INT),
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Limits {
+        depth: INT,
+        names: Vec<String>,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+    struct Config {
+        name: String,
+        retries: Option<INT>,
+        timeout: Option<INT>,
+        mode: Mode,
+        limits: Limits,
+    }
+
+    let config = Config {
+        name: "test".into(),
+        retries: Some(3),
+        timeout: None,
+        mode: Mode::Limited(10),
+        limits: Limits { depth: 5, names: vec!["a".into(), "b".into()] },
+    };
+
+    let engine = Engine::new();
+
+    let mut scope = to_scope(&config).unwrap();
+
+    assert_eq!(scope.len(), 5);
+    assert_eq!(scope.is_constant("name"), Some(false));
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "retries + limits.depth + mode.Limited").unwrap(), 18);
+    assert!(engine.eval_with_scope::<bool>(&mut scope, "timeout == ()").unwrap());
+    assert_eq!(engine.eval_with_scope::<String>(&mut scope, "limits.names[1]").unwrap(), "b");
+
+    engine
+        .run_with_scope(&mut scope, r#"name += "-2"; retries = (); timeout = 30; mode = "Fast"; limits.names.push("c");"#)
+        .unwrap();
+
+    let config: Config = from_scope(&scope).unwrap();
+
+    assert_eq!(
+        config,
+        Config {
+            name: "test-2".into(),
+            retries: None,
+            timeout: Some(30),
+            mode: Mode::Fast,
+            limits: Limits {
+                depth: 5,
+                names: vec!["a".into(), "b".into(), "c".into()]
+            },
+        }
+    );
+
+    // Shadowed variables take the latest value
+    engine.run_with_scope(&mut scope, "let retries = 7;").unwrap();
+    assert_eq!(from_scope::<Config>(&scope).unwrap().retries, Some(7));
+
+    let mut scope = to_scope_constants(&config).unwrap();
+
+    assert_eq!(scope.is_constant("limits"), Some(true));
+    assert!(engine.run_with_scope(&mut scope, "timeout = 1;").is_err());
+
+    assert!(to_scope(42 as INT).is_err());
+    assert!(from_scope::<Config>(&Scope::new()).is_err());
+}
+
+#[test]
+fn test_serde_tag() {
+    let mut value = Dynamic::from(42 as INT);
+    value.set_tag(123);
+
+    assert_eq!(to_dynamic(&value).unwrap().tag(), 123);
+    assert_eq!(from_dynamic::<Dynamic>(&value).unwrap().tag(), 123);
+    assert_eq!(to_dynamic(from_dynamic::<Dynamic>(&value).unwrap()).unwrap().tag(), 123);
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let array: Array = vec![value.clone(), Dynamic::from(1 as INT)];
+        let result = to_dynamic(&array).unwrap().into_array().unwrap();
+        assert_eq!(result[0].tag(), 123);
+        assert_eq!(result[1].tag(), Dynamic::DEFAULT_TAG);
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        let mut map = Map::new();
+        map.insert("a".into(), value.clone());
+        let result = to_dynamic(&map).unwrap().cast::<Map>();
+        assert_eq!(result["a"].tag(), 123);
+    }
+
+    #[cfg(not(feature = "no_closure"))]
+    assert_eq!(to_dynamic(value.clone().into_shared()).unwrap().tag(), 123);
+
+    // Serializing into other formats is not affected by the tag
+    assert_eq!(serde_json::to_string(&value).unwrap(), "42");
+}
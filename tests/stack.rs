@@ -1,5 +1,5 @@
 #![cfg(not(feature = "unchecked"))]
-use rhai::{Engine, EvalAltResult, ParseErrorType, INT};
+use rhai::{Engine, EvalAltResult, LimitInfo, LimitKind, ParseErrorType, INT};
 
 #[test]
 #[cfg(not(feature = "no_function"))]
@@ -33,6 +33,50 @@ fn test_stack_overflow_fn_calls() {
             .unwrap_err(),
         EvalAltResult::ErrorStackOverflow(..)
     ));
+
+    match *engine.run("fn foo(n) { if n == 0 { 0 } else { n + foo(n-1) } } foo(1000)").unwrap_err() {
+        EvalAltResult::ErrorStackOverflow(info, ..) => assert_eq!(info, LimitInfo::new(LimitKind::CallLevels, max as u64, max as u64 + 1)),
+        err => panic!("{}", err),
+    }
+}
+
+#[test]
+#[cfg(not(feature = "no_function"))]
+#[cfg(not(feature = "no_index"))]
+#[cfg(not(feature = "no_closure"))]
+fn test_stack_overflow_closure_calls() {
+    const CHAIN: &str = "
+        let f = |x| x;
+        for i in 0..200 { let g = f; f = |x| [x].map(g)[0] + 1; }
+        f.call(0)
+    ";
+
+    // Deeply-nested calls need a larger stack in debug builds
+    std::thread::Builder::new()
+        .stack_size(256 * 1024 * 1024)
+        .spawn(|| {
+            let mut engine = Engine::new();
+            engine.set_max_call_levels(250);
+
+            // By default, calls through function pointers count towards the normal limit
+            assert_eq!(engine.max_closure_call_levels(), 0);
+            assert!(matches!(*engine.eval::<INT>(CHAIN).unwrap_err(), EvalAltResult::ErrorStackOverflow(..)));
+
+            engine.set_max_closure_call_levels(300);
+            assert_eq!(engine.eval::<INT>(CHAIN).unwrap(), 200);
+
+            // Real recursion is still limited
+            assert!(matches!(
+                *engine.run("fn foo(n) { if n == 0 { 0 } else { n + foo(n-1) } } foo(260)").unwrap_err(),
+                EvalAltResult::ErrorStackOverflow(..)
+            ));
+
+            engine.set_max_closure_call_levels(100);
+            assert!(matches!(*engine.eval::<INT>(CHAIN).unwrap_err(), EvalAltResult::ErrorStackOverflow(..)));
+        })
+        .unwrap()
+        .join()
+        .unwrap();
 }
 
 #[test]
@@ -167,6 +167,134 @@ fn test_string_split() {
     assert_eq!(engine.eval::<INT>(r#"let x = "\u2764\u2764\u2764 hello! \u2764\u2764\u2764"; x.split("hello").len"#).unwrap(), 2);
 }
 
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_casefold() {
+    let mut engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#""Straße".casefold()"#).unwrap(), "strasse");
+    assert!(engine.eval::<bool>(r#""STRASSE".eq_ignore_case("straße")"#).unwrap());
+    assert!(engine.eval::<bool>(r#"eq_ignore_case("ΣΊΣΥΦΟΣ", "σίσυφος")"#).unwrap());
+    assert!(!engine.eval::<bool>(r#""STRASSE".eq_ignore_case("strasze")"#).unwrap());
+
+    // Turkic case mappings do not apply
+    assert_eq!(engine.eval::<String>(r#""I".casefold()"#).unwrap(), "i");
+    assert_eq!(engine.eval::<String>(r#""\u0130".casefold()"#).unwrap(), "i\u{307}");
+    assert_eq!(engine.eval::<String>(r#""\u0131".casefold()"#).unwrap(), "\u{131}");
+    assert!(!engine.eval::<bool>(r#""\u0131".eq_ignore_case("I")"#).unwrap());
+
+    #[cfg(feature = "normalize")]
+    {
+        assert!(engine
+            .eval::<bool>(
+                r#"
+                    let a = "\u00e9";
+                    let b = "e\u0301";
+                    a != b && a.normalize("NFC") == b.normalize("NFC") && a.normalize("NFD") == b.normalize("NFD")
+                "#
+            )
+            .unwrap());
+        assert!(matches!(*engine.eval::<String>(r#""x".normalize("NFX")"#).unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let x = engine.eval::<rhai::Array>(r#"let x = ["b", "\u00e9", "B", "a", "e"]; x.sort(); x"#).unwrap();
+        assert_eq!(x.into_iter().map(|v| v.into_string().unwrap()).collect::<Vec<_>>(), ["B", "a", "b", "e", "\u{e9}"]);
+
+        #[cfg(not(feature = "no_function"))]
+        {
+            let x = engine
+                .eval::<rhai::Array>(
+                    r#"
+                        let x = ["b", "A", "\u00df", "B", "ss", "a"];
+                        x.sort(|p, q| {
+                            let p = p.casefold();
+                            let q = q.casefold();
+                            if p < q { -1 } else if p > q { 1 } else { 0 }
+                        });
+                        x
+                    "#,
+                )
+                .unwrap();
+            assert_eq!(x.into_iter().map(|v| v.into_string().unwrap()).collect::<Vec<_>>(), ["A", "a", "b", "B", "\u{df}", "ss"]);
+        }
+    }
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        engine.set_max_string_size(5);
+        assert!(matches!(*engine.eval::<String>(r#""\u0390".casefold()"#).unwrap_err(), EvalAltResult::ErrorDataTooLarge(..)));
+        engine.set_max_string_size(0);
+
+        engine.set_max_operations(500);
+        assert!(matches!(*engine.eval::<String>(r#"let s = ""; s.pad(1000, 'A'); s.casefold()"#).unwrap_err(), EvalAltResult::ErrorTooManyOperations(..)));
+        assert_eq!(engine.eval::<String>(r#"let s = ""; s.pad(100, 'A'); s.casefold()"#).unwrap().len(), 100);
+    }
+}
+
+#[test]
+fn test_string_reverse() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"let x = "hello, world!"; reverse(x); x"#).unwrap(), "!dlrow ,olleh");
+    assert_eq!(engine.eval::<String>(r#"let x = ""; reverse(x); x"#).unwrap(), "");
+    assert_eq!(engine.eval::<String>(r#"let x = "héllo"; reverse(x); x"#).unwrap(), "olléh");
+
+    #[cfg(not(feature = "unicode-segmentation"))]
+    assert_eq!(engine.eval::<String>(r#"let x = "ae\u0301"; reverse(x); x"#).unwrap(), "\u{301}ea");
+}
+
+#[cfg(feature = "unicode-segmentation")]
+#[test]
+fn test_string_graphemes() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"let x = "ae\u0301👨‍👩‍👧‍👦z"; reverse(x); x"#).unwrap(), "z👨‍👩‍👧‍👦e\u{301}a");
+
+    assert_eq!(engine.eval::<INT>(r#"grapheme_len("")"#).unwrap(), 0);
+    assert_eq!(engine.eval::<INT>(r#"grapheme_len("e\u0301")"#).unwrap(), 1);
+    assert_eq!(engine.eval::<INT>(r#"grapheme_len("👨‍👩‍👧‍👦")"#).unwrap(), 1);
+    assert_eq!(engine.eval::<INT>(r#"grapheme_len("👨‍👩‍👧‍👦 e\u0301!")"#).unwrap(), 4);
+
+    assert_eq!(engine.eval::<String>(r#"let s = ""; for g in graphemes("a👨‍👩‍👧‍👦e\u0301") { s += "[" + g + "]"; } s"#).unwrap(), "[a][👨‍👩‍👧‍👦][e\u{301}]");
+
+    let script = r#"let x = "a👨‍👩‍👧‍👦e\u0301z"; "#;
+    assert_eq!(engine.eval::<String>(&format!("{}sub_graphemes(x, 1, 2)", script)).unwrap(), "👨‍👩‍👧‍👦e\u{301}");
+    assert_eq!(engine.eval::<String>(&format!("{}sub_graphemes(x, -2, 1)", script)).unwrap(), "e\u{301}");
+    assert_eq!(engine.eval::<String>(&format!("{}sub_graphemes(x, -10, 2)", script)).unwrap(), "a👨‍👩‍👧‍👦");
+    assert_eq!(engine.eval::<String>(&format!("{}sub_graphemes(x, 2, 100)", script)).unwrap(), "e\u{301}z");
+    assert_eq!(engine.eval::<String>(&format!("{}sub_graphemes(x, 4, 1)", script)).unwrap(), "");
+    assert_eq!(engine.eval::<String>(&format!("{}sub_graphemes(x, 1, 0)", script)).unwrap(), "");
+    assert_eq!(engine.eval::<String>(&format!("{}sub_graphemes(x, 1, -1)", script)).unwrap(), "");
+}
+
+#[cfg(feature = "regex")]
+#[cfg(not(feature = "no_object"))]
+#[test]
+fn test_string_regex() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"let x = "2023-12-25"; x.replace_re("(\\d+)-(\\d+)-(\\d+)", "$3/$2/$1")"#).unwrap(), "25/12/2023");
+    assert_eq!(engine.eval::<String>(r#"let x = "hello world"; x.replace_re("(?P<w>\\w+)", "<${w}>")"#).unwrap(), "<hello> <world>");
+    assert_eq!(engine.eval::<String>(r#"let x = "a1b2"; x.replace_re("\\d", "$$")"#).unwrap(), "a$b$");
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(engine.eval::<String>(r#"let x = "a1 b22 c333"; x.matches("\\d+").reduce(|s, v| s + v + ",", "")"#).unwrap(), "1,22,333,");
+        assert_eq!(engine.eval::<INT>(r#"let x = "hello"; x.matches("\\d+").len()"#).unwrap(), 0);
+        assert_eq!(engine.eval::<String>(r#"let x = "a, b;c ,d"; x.split_re("\\s*[,;]\\s*").reduce(|s, v| s + v, "")"#).unwrap(), "abcd");
+    }
+
+    let err = engine.eval::<String>(r#"let x = "hello"; x.replace_re("(abc", "x")"#).unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref msg, ..) if msg.is_string()), "{}", err);
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(err.position(), rhai::Position::new(1, 20));
+
+    #[cfg(not(feature = "no_index"))]
+    assert!(matches!(*engine.eval::<INT>(r#"let x = "hello"; x.split_re("[a-").len()"#).unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+}
+
 #[test]
 fn test_string_interpolated() {
     // Make sure strings interpolation works even under raw
@@ -307,3 +435,31 @@ fn test_immutable_string() {
     let _ssx: Box<str> = x.clone().into();
     let _ssx: Box<str> = (&x).into();
 }
+
+#[test]
+fn test_string_append_in_place() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<String>(r#"let s = "x"; s += 42; s += 'y'; s += "z"; s"#).unwrap(), "x42yz");
+    assert_eq!(engine.eval::<String>(r#"let s = "x"; let t = s; s += 42; t"#).unwrap(), "x");
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                r#"
+                    let s = "";
+                    for i in 0..10000 { s += "ab"; s += 'c'; s += i % 10; }
+                    s.len
+                "#
+            )
+            .unwrap(),
+        40000
+    );
+
+    let mut s: ImmutableString = "hello".into();
+    let s2 = s.clone();
+    s.make_mut().push('!');
+    assert_eq!(s, "hello!");
+    assert_eq!(s2, "hello");
+    assert_eq!(s.strong_count(), 1);
+}
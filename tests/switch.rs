@@ -304,3 +304,66 @@ fn test_switch_ranges() {
         'z'
     );
 }
+
+#[test]
+fn test_switch_custom_type() {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    impl Color {
+        fn name(self) -> &'static str {
+            match self {
+                Self::Red => "red",
+                Self::Green => "green",
+                Self::Blue => "blue",
+            }
+        }
+    }
+
+    let mut engine = Engine::new();
+
+    engine
+        .register_type_with_name::<Color>("Color")
+        .register_fn("red", || Color::Red)
+        .register_fn("green", || Color::Green)
+        .register_fn("blue", || Color::Blue)
+        .register_fn("==", |c: &mut Color, name: &str| c.name() == name)
+        .register_fn("==", |c: &mut Color, value: INT| *c as INT == value);
+
+    let script = "
+        switch x {
+            \"red\" => 1,
+            \"green\" if y > 0 => 2,
+            2 => 3,
+            0..10 => 4,
+            _ => 0
+        }
+    ";
+
+    let mut scope = Scope::new();
+    scope.push("x", Color::Red).push("y", 0 as INT);
+
+    // Not allowed by default - falls through to the default case
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script).unwrap(), 0);
+
+    engine.set_allow_switch_on_custom_types(true);
+
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script).unwrap(), 1);
+    scope.set_value("x", Color::Green);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script).unwrap(), 0);
+    scope.set_value("y", 1 as INT);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script).unwrap(), 2);
+    scope.set_value("x", Color::Blue);
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, script).unwrap(), 3);
+
+    // Literals are still matched by hash
+    assert_eq!(engine.eval::<INT>("switch 2 { \"red\" => 1, 2 => 3, _ => 0 }").unwrap(), 3);
+    assert_eq!(engine.eval::<INT>("switch 5 { \"red\" => 1, 2 => 3, 0..10 => 4, _ => 0 }").unwrap(), 4);
+
+    // No `==` registered for this type
+    assert_eq!(engine.eval::<INT>("switch blue() { 'b' => 1, _ => 0 }").unwrap(), 0);
+}
@@ -1,4 +1,4 @@
-use rhai::{Engine, EvalAltResult, INT};
+use rhai::{Engine, EvalAltResult, LanguageVersion, Position, INT};
 
 #[test]
 fn test_throw() {
@@ -95,3 +95,109 @@ fn test_try_catch() {
     #[cfg(not(feature = "unchecked"))]
     assert!(matches!(*engine.run("try { 42/0; } catch { throw; }").expect_err("expects error"), EvalAltResult::ErrorArithmetic(..)));
 }
+
+#[test]
+fn test_throw_error_value() {
+    #[derive(Debug, Clone)]
+    struct MyError {
+        code: INT,
+    }
+
+    let mut engine = Engine::new();
+
+    engine.register_type_with_name::<MyError>("MyError").register_fn("my_error", |code: INT| MyError { code });
+
+    let mut err = engine.run("throw my_error(42);").unwrap_err();
+    assert_eq!(err.error_value().unwrap().type_name(), std::any::type_name::<MyError>());
+    assert!(err.try_take_error_value::<INT>().is_none());
+    assert_eq!(err.try_take_error_value::<MyError>().unwrap().code, 42);
+
+    assert!(engine.run("x").unwrap_err().error_value().is_none());
+
+    #[cfg(not(feature = "no_function"))]
+    {
+        use rhai::{FnPtr, NativeCallContext};
+
+        // Thrown values are preserved through native functions calling script callbacks
+        engine.register_fn("call_it", |ctx: NativeCallContext, f: FnPtr| -> Result<INT, Box<EvalAltResult>> { f.call_within_context(&ctx, ()) });
+
+        let mut err = engine.run("fn foo() { throw my_error(123); } fn bar() { call_it(foo) } bar()").unwrap_err();
+        assert!(matches!(*err, EvalAltResult::ErrorInFunctionCall(ref f, ..) if f == "bar"));
+        assert_eq!(err.try_take_error_value::<MyError>().unwrap().code, 123);
+    }
+}
+
+#[test]
+fn test_try_catch_guard() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("try { throw 42; } catch (x if x > 40) { return x; }").unwrap(), 42);
+    assert_eq!(
+        engine
+            .eval::<INT>(r#"let r = 0; try { throw "hello"; } catch (x if type_of(x) == "string") { r = x.len(); } r"#)
+            .unwrap(),
+        5
+    );
+
+    // Non-matching guard re-throws the original error with its original position
+    let err = engine
+        .run(
+            "
+                let x = 0;
+                try {
+                    x += 1;     throw 42;
+                } catch (err if type_of(err) == \"string\") {
+                    x = 999;
+                }
+            ",
+        )
+        .unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref v, ..) if v.as_int().unwrap() == 42));
+    assert_eq!(err.position(), Position::new(4, 33));
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        let script = "let a = [1, 2, 3];\ntry { a[10] } catch (err if false) { }";
+        let err = engine.run(script).unwrap_err();
+        assert!(matches!(*err, EvalAltResult::ErrorArrayBounds(3, 10, ..)));
+        assert_eq!(err.position(), engine.run("let a = [1, 2, 3];\n{     a[10] }").unwrap_err().position());
+    }
+
+    // The error variable shadows constants of the same name
+    assert_eq!(engine.eval::<INT>("const x = 1; try { throw 42; } catch (x if x == 42) { return x; }").unwrap(), 42);
+
+    // Nested guards
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                "
+                    try {
+                        try { throw 42; } catch (x if x < 0) { return -1; }
+                    } catch (x if x > 0) {
+                        return x + 1;
+                    }
+                "
+            )
+            .unwrap(),
+        43
+    );
+
+    // Guard that itself throws
+    #[cfg(not(feature = "no_function"))]
+    {
+        let err = engine.run(r#"fn check(x) { throw "oops"; } try { throw 42; } catch (x if check(x)) { }"#).unwrap_err();
+        assert!(matches!(*err, EvalAltResult::ErrorInFunctionCall(ref f, ..) if f == "check"));
+        assert_eq!(err.error_value().unwrap().clone().into_string().unwrap(), "oops");
+    }
+
+    // Guard must be boolean
+    assert!(matches!(*engine.run("try { throw 42; } catch (x if x) { }").unwrap_err(), EvalAltResult::ErrorMismatchDataType(..)));
+
+    // Guard requires a catch variable
+    assert!(engine.compile("try { throw 42; } catch (if true) { }").is_err());
+
+    let mut engine = Engine::new();
+    engine.set_language_version(LanguageVersion::new(1, 16));
+    assert!(engine.compile("try { throw 42; } catch (x if x > 0) { }").is_err());
+    assert!(engine.compile("try { throw 42; } catch (x) { }").is_ok());
+}
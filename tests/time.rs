@@ -119,3 +119,51 @@ fn test_timestamp_op() {
         42
     );
 }
+
+#[test]
+fn test_timestamp_functions() {
+    let engine = Engine::new();
+
+    assert!(engine.eval::<bool>("let t = timestamp(); 5 + t == t + 5").unwrap());
+    assert_eq!(engine.eval::<String>("duration_to_string(0)").unwrap(), "0s");
+
+    #[cfg(not(feature = "no_float"))]
+    {
+        assert_eq!(engine.eval::<String>("duration_to_string(83)").unwrap(), "1m 23s");
+        assert_eq!(engine.eval::<String>("duration_to_string(90061)").unwrap(), "1d 1h 1m 1s");
+        assert_eq!(engine.eval::<String>("duration_to_string(-3600)").unwrap(), "-1h");
+        assert_eq!(engine.eval::<String>("duration_to_string(83.5)").unwrap(), "1m 23.5s");
+        assert_eq!(engine.eval::<String>("duration_to_string(-0.25)").unwrap(), "-0.25s");
+        assert_eq!(engine.eval::<String>("duration_to_string(3600.0001)").unwrap(), "1h");
+
+        assert_eq!(engine.eval::<FLOAT>("let t = timestamp(); timestamp_diff(t + 90, t)").unwrap(), 90.0);
+        assert_eq!(engine.eval::<FLOAT>("let t = timestamp(); timestamp_diff(t, t + 90)").unwrap(), -90.0);
+        assert_eq!(engine.eval::<String>("let t = timestamp(); duration_to_string(timestamp_diff(t + 3660, t))").unwrap(), "1h 1m");
+        assert!((engine.eval::<FLOAT>("let t = timestamp(); timestamp_diff(t - 1.5, t)").unwrap() + 1.5).abs() < 0.001);
+
+        #[cfg(not(feature = "unchecked"))]
+        assert!(matches!(*engine.run("duration_to_string(1e300)").unwrap_err(), rhai::EvalAltResult::ErrorArithmetic(..)));
+    }
+
+    #[cfg(feature = "no_float")]
+    {
+        assert_eq!(engine.eval::<String>("duration_to_string(83000)").unwrap(), "1m 23s");
+        assert_eq!(engine.eval::<String>("duration_to_string(83500)").unwrap(), "1m 23.5s");
+        assert_eq!(engine.eval::<String>("duration_to_string(90061000)").unwrap(), "1d 1h 1m 1s");
+        assert_eq!(engine.eval::<String>("duration_to_string(-250)").unwrap(), "-0.25s");
+
+        assert_eq!(engine.eval::<INT>("let t = timestamp(); timestamp_diff(t + 90, t)").unwrap(), 90_000);
+        assert_eq!(engine.eval::<INT>("let t = timestamp(); timestamp_diff(t, t + 90)").unwrap(), -90_000);
+        assert_eq!(engine.eval::<String>("let t = timestamp(); duration_to_string(timestamp_diff(t + 3660, t))").unwrap(), "1h 1m");
+    }
+
+    #[cfg(not(feature = "unchecked"))]
+    #[cfg(not(feature = "only_i32"))]
+    {
+        let err = engine.run("let t = timestamp(); t + 9223372036854775807").unwrap_err();
+        assert!(matches!(*err, rhai::EvalAltResult::ErrorArithmetic(..)));
+
+        let err = engine.run("let t = timestamp(); let x = -9223372036854775807 - 1; t + x + x").unwrap_err();
+        assert!(matches!(*err, rhai::EvalAltResult::ErrorArithmetic(..)));
+    }
+}
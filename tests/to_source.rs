@@ -0,0 +1,150 @@
+#![cfg(feature = "internals")]
+
+use rhai::{Dynamic, Engine, AST};
+
+/// Print the [`AST`] back into script text, re-compile it, then check that both evaluate to the same result
+/// and that printing is stable.
+fn check_round_trip(engine: &Engine, script: &str) {
+    let ast = engine.compile(script).unwrap();
+    let source = ast.to_source();
+    let ast2: AST = engine.compile(&source).unwrap_or_else(|err| panic!("cannot compile:\n{}\n{}", source, err));
+
+    let result = engine.eval_ast::<Dynamic>(&ast).map_err(|err| err.to_string());
+    let result2 = engine.eval_ast::<Dynamic>(&ast2).map_err(|err| err.to_string());
+
+    assert_eq!(format!("{result:?}"), format!("{result2:?}"), "different results for:\n{source}");
+    assert_eq!(ast2.to_source(), source);
+}
+
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "only_i32")))]
+const SCRIPTS: &[&str] = &[
+    "let x = (1 + 2) * 3 - -4; let y = 2 ** 3 ** 2; let z = (2 ** 3) ** 2; x * 1000000 + y * 1000 + z",
+    "let x = 10; x -= 3; x *= 2; x /= 7; x %= 5; x <<= 4; x >>= 1; x |= 1; x &= 13; x ^= 6; x",
+    "let x = 42; x > 40 && !(x == 50) || x < 0",
+    "let x = 5; -(x + 1) * -x",
+    "const X = -9223372036854775807 - 1; [X, 0xff, 0b101, 0o17]",
+    r#""hello\n\t\"world\" \\ ${x}" + 'c' + '\'' + '\\' + "\u0001""#,
+    "let x = 42; let y = `multi\nline ${x + 1} with ``ticks`` and ${if x > 10 { `inner ${x}` } else { \"small\" }}`; y",
+    "let s = `$${1}{}`; s",
+    "let x = 0; if x > 0 { 1 } else if x < 0 { -1 } else { 0 }",
+    "let x = 0; while x < 10 { x += 1; if x == 5 { break; } } x",
+    "let x = 0; loop { x += 1; if x % 2 == 0 { continue; } if x > 10 { break x * 2; } }",
+    "let x = 0; do { x += 1; } while x < 10; do { x += 2; } until x >= 20; x",
+    "let sum = 0; for (x, i) in [10, 20, 30] { sum += x * i; } for x in 0..10 { sum += x; } for x in 0..=10 { sum += x; } sum",
+    "let x = 'c'; switch x { 'a' | 'b' => 1, 'c' if true => { let y = 2; y }, _ => 3 }",
+    r#"let x = "hello"; switch x { "hello" => 1, "world" => 2 }"#,
+    "let x = 42; switch x { 1 => 1, 2 if x > 0 => 2, 0..10 => 3, 40..=50 => 4, _ => 5 }",
+    r#"let r = ""; try { throw "oops"; } catch (err) { r = err; } try { throw 1; } catch { r += "!"; } r"#,
+    "let x = 1; let y = { let z = x + 1; z * 2 }; let u = {}; [x, y, u]",
+    "let x = (); let y = x ?? 42; y",
+    "let a = [1, [2, 3], #{x: 4}]; a[1][0] = 10; a[2].x += 1; a",
+    r#"let m = #{a: 1, "b c": [2, 3], d: #{e: 'x'}}; m.d.e = 'y'; m["b c"][1] += 10; m.a.to_string() + m["b c"][1]"#,
+    "let x = (); [x?.foo, x?[1], x?.bar?.baz]",
+    "let x = 2 in [1, 2, 3]; let y = 4 !in [1, 2, 3]; let z = \"ell\" in \"hello\"; [x, y, z]",
+    "let r = 1..10; let s = 1..=10; [r.start, r.end, s.end, (-5).abs(), (2.0).sqrt(), \"hello\"[1]]",
+    "let x = 1.0e-7 + 1e20 + -0.5 + 1.5; x",
+    "let x = 4; is_def_var(\"x\") && type_of(x) == type_of(1) || true",
+    "fn add(x, y) { x + y } private fn sub(x, y) { return x - y; } add(1, 2) * sub(5, 3)",
+    "fn fib(n) { if n < 2 { n } else { fib(n - 1) + fib(n - 2) } } fib(5)",
+    "fn add(x, y = 1, z = x * y) { x + y + z } add(1) + add(1, 2) + add(1, 2, 3)",
+    "fn log(fmt, ...args) { fmt + args.len() } log(\"a\") + log(\"b\", 1, 2)",
+    "fn area(width, height) { width * height } area(height: 2, width: 3) + area(4, height: 5)",
+    "fn inc() { this += 1; } let x = 41; x.inc(); x",
+    "fn foo() { x } let x = 42; foo!()",
+    "fn check(x) { if x < 0 { throw \"negative\"; } x } let r = check(1); try { check(-1) } catch (err) { r = err; } r",
+    "let f = |x, y| x * y; let g = |x| { let y = x + 1; y * 2 }; f.call(2, 3) + g.call(4)",
+    "fn foo(x) { x * 2 } let f = Fn(\"foo\"); let g = f.curry(21); f.call(1) + g.call()",
+    "let a = [1, 2, 3, 4, 5]; a.map(|x| x * 2).filter(|x| x > 4).reduce(|sum, x| sum + x, 0)",
+    "let x = 40; let y = \"hi\"; let f = |a| a + x + y.len; x = 2; f.call(1)",
+    "let x = 1; let g = |a, b| { let k = a; |c| c + k + b + x }; g.call(2, 3).call(4)",
+    "let obj = #{ value: 1, action: || this.value += 1 }; obj.action(); obj.value",
+    "let x = #{a: 1}; x.a = x.a + 1; x.b = x.a * 2; x",
+];
+
+#[test]
+fn test_to_source() {
+    let engine = Engine::new();
+
+    let ast = engine.compile("let x=(y+2)*3;if x>5{print(`x = ${x}`)}else{x=-x;}x").unwrap();
+
+    assert_eq!(ast.to_source(), "let x = (y + 2) * 3;\nif x > 5 {\n    print(`x = ${x}`)\n} else {\n    x = -x\n}\nx\n");
+
+    let ast = engine.compile("(a + b) * c - (d - e) + f ** (g ** h) + (i ** j) ** k").unwrap();
+
+    assert_eq!(ast.to_source(), "(a + b) * c - (d - e) + f ** g ** h + (i ** j) ** k\n");
+}
+
+#[cfg(not(any(feature = "no_function", feature = "no_closure", feature = "no_object", feature = "no_index", feature = "no_float", feature = "only_i32")))]
+#[test]
+fn test_to_source_round_trip() {
+    let engine = Engine::new();
+
+    for script in SCRIPTS {
+        check_round_trip(&engine, script);
+    }
+
+    #[cfg(not(feature = "no_optimize"))]
+    for level in [rhai::OptimizationLevel::None, rhai::OptimizationLevel::Full] {
+        let mut engine = Engine::new();
+        engine.set_optimization_level(level);
+
+        for script in SCRIPTS {
+            check_round_trip(&engine, script);
+        }
+    }
+}
+
+#[cfg(feature = "metadata")]
+#[test]
+fn test_to_source_doc_comments() {
+    let engine = Engine::new();
+
+    let ast = engine
+        .compile(
+            "
+                //! Module documentation.
+
+                /// Add two numbers.
+                fn add(x, y) { x + y }
+
+                /** Block
+                    comment */
+                fn foo() { 42 }
+
+                add(1, 2)
+            ",
+        )
+        .unwrap();
+
+    assert_eq!(ast.to_source(), "//! Module documentation.\n\n/// Add two numbers.\nfn add(x, y) {\n    x + y\n}\n\n/** Block\n    comment */\nfn foo() {\n    42\n}\n\nadd(1, 2)\n");
+}
+
+#[cfg(not(feature = "no_custom_syntax"))]
+#[test]
+fn test_to_source_custom_syntax() {
+    let mut engine = Engine::new();
+
+    engine
+        .register_custom_syntax(["exec", "[", "$ident$", "$symbol$", "$int$", "]", "->", "$block$"], true, |context, inputs| {
+            let var_name = inputs[0].get_string_value().unwrap();
+            let op = inputs[1].get_literal_value::<rhai::ImmutableString>().unwrap();
+            let max = inputs[2].get_literal_value::<rhai::INT>().unwrap();
+            let mut value: rhai::INT = 0;
+
+            while (op == "<" && value < max) || (op == "<=" && value <= max) {
+                context.scope_mut().push(var_name.to_string(), value);
+                let _ = context.eval_expression_tree(&inputs[3])?;
+                value += 1;
+            }
+
+            Ok(value.into())
+        })
+        .unwrap();
+    engine.register_custom_operator("#", 160).unwrap().register_fn("#", |x: rhai::INT, y: rhai::INT| x * 10 + y);
+
+    let ast = engine.compile("let sum = 0; exec [i <= 3] -> { sum += i; } sum + 1 # 2").unwrap();
+
+    assert_eq!(ast.to_source(), "let sum = 0;\nexec [ i <= 3 ] -> {\n    sum += i\n}\nsum + (1 # 2)\n");
+
+    check_round_trip(&engine, "let sum = 0; exec [i <= 3] -> { sum += i; } sum + 1 # 2 * 3");
+}
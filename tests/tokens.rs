@@ -1,4 +1,4 @@
-use rhai::{Engine, ParseErrorType, INT};
+use rhai::{Engine, ParseErrorType, Token, INT};
 
 #[test]
 fn test_tokens_disabled() {
@@ -94,3 +94,227 @@ fn test_tokens_unicode_xid_ident() {
     );
     assert!(result.is_err());
 }
+
+#[cfg(not(feature = "unchecked"))]
+#[test]
+fn test_tokens_limits() {
+    use rhai::LexError;
+    use std::time::{Duration, Instant};
+
+    let mut engine = Engine::new();
+    engine
+        .set_max_identifier_len(64)
+        .set_max_string_literal_len(1000)
+        .set_max_interpolation_depth(4)
+        .set_max_tokens(1000)
+        .set_max_line_len(1000);
+
+    let check = |engine: &Engine, script: String, expected: LexError| {
+        let start = Instant::now();
+        let err = engine.compile(script).unwrap_err();
+        assert_eq!(*err.err_type(), ParseErrorType::BadInput(expected));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    };
+
+    check(&engine, format!("let {} = 1;", "x".repeat(1_000_000)), LexError::IdentifierTooLong(64));
+    check(&engine, format!(r#"let x = "{}";"#, "a".repeat(1_000_000)), LexError::StringLiteralTooLong(1000));
+    check(&engine, format!("let x = `{}`;", "a".repeat(1_000_000)), LexError::StringLiteralTooLong(1000));
+    check(&engine, format!("let x = {};", "`${".repeat(10_000)), LexError::InterpolationTooDeep(4));
+    check(&engine, "x;\n".repeat(1_000_000), LexError::TooManyTokens(1000));
+    #[cfg(not(feature = "no_position"))]
+    {
+        engine.set_max_tokens(0);
+        check(&engine, "x;".repeat(10_000), LexError::LineTooLong(1000));
+        engine.set_max_tokens(1000);
+    }
+
+    // Within limits
+    engine.compile(format!("let {} =\n`{}${{`${{`${{42}}`}}`}}`;", "x".repeat(64), "a".repeat(900))).unwrap();
+
+    // The string literal limit is separate from the maximum string size at runtime
+    engine.set_max_string_literal_len(0).set_max_string_size(10);
+    assert!(matches!(engine.compile(format!(r#""{}""#, "a".repeat(100))).unwrap_err().err_type(), ParseErrorType::LiteralTooLarge(..)));
+}
+
+#[test]
+fn test_tokens_compile_never_panics() {
+    const FRAGMENTS: &[&str] = &[
+        "`",
+        "${",
+        "}",
+        "{",
+        "\"",
+        "'",
+        "\\",
+        "(",
+        ")",
+        "[",
+        "]",
+        "#{",
+        ",",
+        ";",
+        ".",
+        "?.",
+        "?[",
+        "??",
+        "!in",
+        "::",
+        "=>",
+        "|",
+        "||",
+        "=",
+        "+=",
+        "-",
+        "0x",
+        "1e",
+        "1.",
+        "_",
+        "//",
+        "/*",
+        "*/",
+        "///",
+        "\n",
+        " ",
+        "let",
+        "const",
+        "fn",
+        "private",
+        "import",
+        "export",
+        "as",
+        "switch",
+        "if",
+        "else",
+        "while",
+        "loop",
+        "for",
+        "in",
+        "do",
+        "return",
+        "throw",
+        "try",
+        "catch",
+        "this",
+        "global",
+        "Fn",
+        "call",
+        "is_shared",
+        "x",
+        "foo",
+        "42",
+        "π",
+        "\u{0}",
+    ];
+
+    let mut engine = Engine::new();
+    #[cfg(not(feature = "unchecked"))]
+    engine
+        .set_max_identifier_len(64)
+        .set_max_string_literal_len(1000)
+        .set_max_interpolation_depth(8)
+        .set_max_tokens(10_000)
+        .set_max_line_len(1000);
+
+    // Simple deterministic pseudo-random number generator (xorshift)
+    let mut seed = 0x2545_F491_4F6C_DD1D_u64;
+    let mut next = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        seed
+    };
+
+    for _ in 0..2000 {
+        // Arbitrary bytes
+        let len = (next() % 200) as usize;
+        let bytes = (0..len).map(|_| next() as u8).collect::<Vec<_>>();
+        let _ = engine.compile(String::from_utf8_lossy(&bytes));
+
+        // Arbitrary sequences of syntax fragments
+        let len = (next() % 100) as usize;
+        let script = (0..len).map(|_| FRAGMENTS[(next() % FRAGMENTS.len() as u64) as usize]).collect::<String>();
+        let _ = engine.compile(script);
+    }
+}
+
+#[test]
+fn test_tokens_tokenize() {
+    let mut engine = Engine::new();
+
+    #[cfg(not(feature = "no_custom_syntax"))]
+    engine.register_custom_operator("foo", 160).unwrap();
+
+    engine.disable_symbol("while");
+
+    let script = r#"
+        let x = "hello"; // comment
+        let y = `x = ${x + `${1}`}, map = ${#{a: 1}.a}!`;
+        x foo 2; while /* block
+        comment */
+    "#;
+
+    let tokens = engine.tokenize_with_comments(script).collect::<Vec<_>>();
+
+    assert_eq!(
+        tokens.iter().map(|(token, ..)| token.to_string()).collect::<Vec<_>>(),
+        [
+            "let",
+            "x",
+            "=",
+            "\"hello\"",
+            ";",
+            "// comment",
+            "let",
+            "y",
+            "=",
+            "string",
+            "{",
+            "x",
+            "+",
+            "string",
+            "{",
+            "1",
+            "}",
+            "\"\"",
+            "}",
+            "string",
+            "{",
+            "#{",
+            "a",
+            ":",
+            "1",
+            "}",
+            ".",
+            "a",
+            "}",
+            "\"!\"",
+            ";",
+            "x",
+            "foo",
+            "2",
+            ";",
+            "while",
+            "/* block\n        comment */"
+        ]
+    );
+
+    #[cfg(not(feature = "no_position"))]
+    {
+        let spans = tokens.iter().map(|(_, pos, span)| format!("{:?} {:?}", pos, span)).collect::<Vec<_>>();
+
+        assert_eq!(spans[3], "2:17 2:17-23");
+        assert_eq!(spans[5], "2:26 2:26-35");
+        assert_eq!(spans[9], "3:17 3:17-22");
+        assert_eq!(spans[17], "3:33 3:33-33");
+        assert_eq!(spans[21], "3:45 3:45-46");
+        assert_eq!(spans[29], "3:55 3:55-56");
+        assert_eq!(spans[32], "4:11 4:11-13");
+        assert_eq!(spans[35], "4:18 4:18-22");
+        assert_eq!(spans[36], "4:24 4:24-5:18");
+    }
+
+    assert!(matches!(tokens[35].0, Token::Reserved(ref s) if &**s == "while"));
+
+    // Comments are skipped by default
+    assert_eq!(engine.tokenize(script).count(), tokens.len() - 2);
+}
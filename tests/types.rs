@@ -1,4 +1,4 @@
-use rhai::{Engine, INT};
+use rhai::{Dynamic, Engine, INT};
 
 #[test]
 fn test_type_of() {
@@ -47,3 +47,58 @@ fn test_type_of() {
     #[cfg(feature = "only_i32")]
     assert_eq!(engine.eval::<String>("let x = 123; type_of(x)").unwrap(), "i32");
 }
+
+#[test]
+fn test_type_tag() {
+    let engine = Engine::new();
+
+    assert_eq!(engine.eval::<INT>("let x = 1; tag(x)").unwrap(), Dynamic::DEFAULT_TAG as INT);
+    assert_eq!(engine.eval::<INT>("let x = 1; set_tag(x, -7); tag(x)").unwrap(), -7);
+    assert_eq!(engine.eval::<INT>(&format!("let x = 1; set_tag(x, {}); tag(x)", Dynamic::MAX_TAG)).unwrap(), Dynamic::MAX_TAG as INT);
+    assert_eq!(engine.eval::<INT>(&format!("let x = 1; set_tag(x, {}); tag(x)", Dynamic::MIN_TAG)).unwrap(), Dynamic::MIN_TAG as INT);
+    #[cfg(not(feature = "only_i32"))]
+    {
+        assert!(matches!(*engine.run(&format!("let x = 1; set_tag(x, {});", Dynamic::MAX_TAG as INT + 1)).unwrap_err(), rhai::EvalAltResult::ErrorArithmetic(..)));
+        assert!(matches!(*engine.run(&format!("let x = 1; set_tag(x, {});", Dynamic::MIN_TAG as INT - 1)).unwrap_err(), rhai::EvalAltResult::ErrorArithmetic(..)));
+    }
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(engine.eval::<INT>("let x = 1; set_tag(x, 42); let a = [x]; tag(a[0])").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("let x = 1; set_tag(x, 42); let a = [0]; insert(a, 0, x); push(a, x); tag(a[0]) + tag(a[2])").unwrap(), 84);
+        assert_eq!(engine.eval::<INT>(r#"let x = "hello"; set_tag(x, 42); let a = [x]; let b = a; tag(b[0])"#).unwrap(), 42);
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("let x = 1; x.tag = 42; let m = #{a: x}; m.a.tag").unwrap(), 42);
+        assert_eq!(engine.eval::<INT>("let x = 1; x.tag = 42; let m = #{}; m.b = x; m.b.tag").unwrap(), 42);
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("let x = 1; x.tag = 42; let f = |v| v; f.call(x).tag").unwrap(), 42);
+
+        #[cfg(not(feature = "no_closure"))]
+        {
+            assert_eq!(engine.eval::<INT>("let x = 1; x.tag = 42; let f = || x; f.call().tag").unwrap(), 42);
+            assert_eq!(engine.eval::<INT>("let x = 1; let f = || x; x.tag = 42; f.call().tag").unwrap(), 42);
+            assert_eq!(engine.eval::<INT>("let x = 1; let f = || x.tag = 42; f.call(); x.tag").unwrap(), 42);
+        }
+    }
+
+    #[cfg(not(feature = "no_closure"))]
+    {
+        let mut value = Dynamic::from(1 as INT);
+        value.set_tag(42);
+
+        let mut shared = value.into_shared();
+        assert_eq!(shared.tag(), 42);
+        assert_eq!(shared.clone().flatten().tag(), 42);
+
+        shared.set_tag(-1);
+        assert_eq!(shared.tag(), -1);
+        assert_eq!(shared.flatten().tag(), -1);
+    }
+}
@@ -298,6 +298,59 @@ fn test_var_is_def() {
         .unwrap());
 }
 
+#[test]
+#[cfg(not(feature = "no_index"))]
+fn test_var_scope_introspection() {
+    let mut engine = Engine::new();
+
+    assert!(matches!(*engine.run("let x = 42; scope_var_names()").unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+    assert!(matches!(*engine.run(r#"let x = 42; scope_var("x")"#).unwrap_err(), EvalAltResult::ErrorFunctionNotFound(..)));
+
+    engine.set_scope_introspection(true);
+
+    let mut scope = Scope::new();
+    scope.push("a", 1 as INT);
+
+    assert_eq!(
+        engine
+            .eval_with_scope::<rhai::Array>(
+                &mut scope,
+                r#"
+                    let x = 42;
+                    const y = "hello";
+                    let z = [1, 2, 3];
+                    scope_var_names()
+                "#
+            )
+            .unwrap()
+            .into_iter()
+            .map(|v| v.into_string().unwrap())
+            .collect::<Vec<_>>(),
+        ["a", "x", "y", "z"]
+    );
+
+    assert_eq!(
+        engine
+            .eval::<INT>(
+                r#"
+                    let x = 1;
+                    x += 41;
+                    scope_var("x")
+                "#
+            )
+            .unwrap(),
+        42
+    );
+    assert_eq!(engine.eval::<String>(r#"let x = "hello"; scope_var_type("x")"#).unwrap(), "string");
+    assert!(matches!(*engine.run(r#"let x = 42; scope_var("y")"#).unwrap_err(), EvalAltResult::ErrorVariableNotFound(..)));
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert!(matches!(*engine.run(r#"let x = [42]; scope_var("x").push(1);"#).unwrap_err(), EvalAltResult::ErrorNonPureMethodCallOnConstant(..)));
+        assert_eq!(engine.eval::<INT>(r#"let x = [42]; let y = scope_var("x"); y.push(1); x.len()"#).unwrap(), 1);
+    }
+}
+
 #[test]
 fn test_scope_eval() {
     let engine = Engine::new();
@@ -376,6 +429,75 @@ fn test_var_resolver() {
         EvalAltResult::ErrorVariableNotFound(n, ..) if n == "DO_NOT_USE"));
 }
 
+#[cfg(not(feature = "no_object"))]
+#[cfg(not(feature = "no_index"))]
+#[test]
+fn test_var_resolver_chain() {
+    use rhai::{Map, VarChainSegment};
+    use std::sync::{Arc, Mutex};
+
+    let mut engine = Engine::new();
+
+    let log = Arc::new(Mutex::new(Vec::<String>::new()));
+    let log2 = log.clone();
+
+    #[allow(deprecated)] // not deprecated but unstable
+    engine.on_var_chain(move |name, _, chain, _| {
+        let path = chain
+            .iter()
+            .map(|segment| match segment {
+                VarChainSegment::Property(prop) => format!(".{}", prop),
+                VarChainSegment::Index(index) => format!("[{}]", index),
+                _ => unreachable!(),
+            })
+            .collect::<String>();
+        log2.lock().unwrap().push(format!("{}{}", name, path));
+
+        if name != "user" {
+            return Ok(None);
+        }
+
+        match chain {
+            // Serve the leaf value directly
+            [VarChainSegment::Property("address"), VarChainSegment::Property("city"), ..] => Ok(Some(("Paris".into(), 2))),
+            // Serve only the array and leave indexing to the engine
+            [VarChainSegment::Property("tags"), ..] => Ok(Some((Dynamic::from_array(vec!["a".into(), "b".into()]), 1))),
+            // Plain variable access
+            [] => {
+                let mut map = Map::new();
+                map.insert("name".into(), "Bob".into());
+                Ok(Some((map.into(), 0)))
+            }
+            _ => Err(EvalAltResult::ErrorRuntime("intermediate value requested".into(), Position::NONE).into()),
+        }
+    });
+
+    let take_log = || std::mem::take(&mut *log.lock().unwrap());
+
+    assert_eq!(engine.eval::<String>("user.address.city").unwrap(), "Paris");
+    assert_eq!(take_log(), ["user.address.city"]);
+
+    assert_eq!(engine.eval::<INT>("user.address.city.len()").unwrap(), 5);
+    assert_eq!(take_log(), ["user.address.city"]);
+
+    assert_eq!(engine.eval::<String>("user.tags[0]").unwrap(), "a");
+    assert_eq!(take_log(), ["user.tags[0]"]);
+
+    assert_eq!(engine.eval::<String>("let i = 1; user.tags[i]").unwrap(), "b");
+    assert_eq!(take_log(), ["i", "user.tags"]);
+
+    assert_eq!(engine.eval::<String>("let u = user; u.name").unwrap(), "Bob");
+    assert_eq!(take_log(), ["user", "u.name"]);
+
+    assert!(matches!(*engine.run("user.address.zip").unwrap_err(), EvalAltResult::ErrorRuntime(..)));
+    take_log();
+
+    // Unchanged behavior for normal variables
+    assert_eq!(engine.eval::<INT>("let x = 40; x + 2").unwrap(), 42);
+    assert_eq!(engine.eval::<INT>("let m = #{a: 1}; m.a = 2; m.a").unwrap(), 2);
+    assert_eq!(engine.eval::<INT>("let a = [1, [2, 3]]; a[1][0] += 40; a[1][0]").unwrap(), 42);
+}
+
 #[test]
 fn test_var_def_filter() {
     let mut engine = Engine::new();
@@ -397,3 +519,112 @@ fn test_var_def_filter() {
     assert!(engine.run("let y = 42; { let x = y + 1; }").is_err());
     engine.run("let y = 42; { let z = y + 1; { let x = z + 1; } }").unwrap();
 }
+
+#[test]
+fn test_var_write_filter() {
+    use rhai::VarWrite;
+    use std::sync::{Arc, Mutex};
+
+    let mut engine = Engine::new();
+
+    let log = Arc::new(Mutex::new(Vec::<String>::new()));
+    let log2 = log.clone();
+
+    #[allow(deprecated)] // not deprecated but unstable
+    engine.on_var_write(move |name, write, pos, _| {
+        if name.starts_with("_ro_") {
+            return Err(EvalAltResult::ErrorRuntime(format!("{} is read-only", name).into(), pos).into());
+        }
+
+        log2.lock().unwrap().push(match write {
+            VarWrite::Assign(value) => format!("{} = {}", name, value),
+            VarWrite::OpAssign(op, value) => format!("{} {} {}", name, op, value),
+            VarWrite::Member(op, value) => format!("{}.. {} {}", name, op, value),
+            _ => unreachable!(),
+        });
+
+        Ok(())
+    });
+
+    let take_log = || std::mem::take(&mut *log.lock().unwrap());
+
+    assert_eq!(engine.eval::<INT>("let x = 40; x = 1; x += 41; x").unwrap(), 42);
+    assert_eq!(take_log(), ["x = 1", "x += 41"]);
+
+    let err = engine.run("let _ro_x = 1; let y = 2; y = 3; _ro_x = 2;").unwrap_err();
+    assert!(matches!(*err, EvalAltResult::ErrorRuntime(ref msg, ..) if msg.to_string() == "_ro_x is read-only"));
+    assert_eq!(take_log(), ["y = 3"]);
+
+    // The variable is not modified
+    let mut scope = Scope::new();
+    scope.push("_ro_x", 1 as INT);
+    assert!(engine.run_with_scope(&mut scope, "_ro_x -= 1").is_err());
+    assert_eq!(scope.get_value::<INT>("_ro_x").unwrap(), 1);
+    assert!(take_log().is_empty());
+
+    #[cfg(not(feature = "no_index"))]
+    {
+        assert_eq!(engine.eval::<INT>("let a = [1, [2, 3]]; a[1][0] += 40; a[1][0]").unwrap(), 42);
+        assert_eq!(take_log(), ["a.. += 40"]);
+
+        assert!(engine.run("let _ro_a = [1, 2]; _ro_a[0] = 0;").is_err());
+        assert!(take_log().is_empty());
+    }
+
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("let m = #{a: #{b: 1}}; m.a.b = 42; m.a.b").unwrap(), 42);
+        assert_eq!(take_log(), ["m.. = 42"]);
+
+        assert!(engine.run("let _ro_m = #{a: 1}; _ro_m.a = 2;").is_err());
+        assert!(take_log().is_empty());
+
+        // Property reads are not intercepted
+        assert_eq!(engine.eval::<INT>("let _ro_m = #{a: 1}; _ro_m.a + 1").unwrap(), 2);
+    }
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    {
+        assert_eq!(engine.eval::<INT>("fn inc(n) { this += n; } let x = 1; x.inc(41); x").unwrap(), 42);
+        assert_eq!(take_log(), ["this += 41"]);
+    }
+}
+
+#[cfg(not(feature = "no_closure"))]
+#[test]
+fn test_var_scope_shared() {
+    let mut engine = Engine::new();
+    let mut scope = Scope::new();
+
+    let x = scope.push_shared("x", 1 as INT);
+    assert!(scope.get("x").unwrap().is_shared());
+
+    engine.run_with_scope(&mut scope, "x *= 42;").unwrap();
+    assert_eq!(*Dynamic::from(x.clone()).read_lock::<INT>().unwrap(), 42);
+
+    *Dynamic::from(x.clone()).write_lock::<INT>().unwrap() = 123;
+    assert_eq!(engine.eval_with_scope::<INT>(&mut scope, "x").unwrap(), 123);
+
+    // Shadowing creates a new, unshared variable
+    engine.run_with_scope(&mut scope, "let x = 0;").unwrap();
+    assert_eq!(*Dynamic::from(x.clone()).read_lock::<INT>().unwrap(), 123);
+
+    #[cfg(not(feature = "unchecked"))]
+    {
+        let mut scope = Scope::new();
+        let stop = scope.push_shared("stop", false);
+
+        engine.on_progress(move |ops| {
+            if ops == 100 {
+                *Dynamic::from(stop.clone()).write_lock::<bool>().unwrap() = true;
+            }
+            None
+        });
+
+        // The loop only ends when the host changes `stop`
+        let n = engine.eval_with_scope::<INT>(&mut scope, "let n = 0; while !stop { n += 1; } n").unwrap();
+        assert!(n > 0 && n < 100, "{}", n);
+        assert!(scope.get_value::<bool>("stop").unwrap());
+    }
+}
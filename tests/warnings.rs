@@ -0,0 +1,145 @@
+use rhai::{Engine, ParseWarningKind, INT};
+
+#[cfg(not(feature = "no_function"))]
+#[test]
+fn test_warnings() {
+    let mut engine = Engine::new();
+
+    let script = "
+        fn foo(x, y) {
+            return x;
+            print(x);
+        }
+        let a = 1;
+        let b = 2;
+        if true { print(b); }
+        while b < 10 {
+            b += 1;
+            break;
+            b += 2;
+        }
+        foo(1, 2)
+    ";
+
+    let (ast, warnings) = engine.compile_with_warnings(script).unwrap();
+
+    assert_eq!(engine.eval_ast::<INT>(&ast).unwrap(), 1);
+
+    let kinds = warnings.iter().map(|w| w.kind).collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        [ParseWarningKind::UnusedParameter, ParseWarningKind::UnreachableCode, ParseWarningKind::UnusedVariable, ParseWarningKind::ConstantCondition, ParseWarningKind::UnreachableCode]
+    );
+
+    assert_eq!(warnings[0].message, "Parameter 'y' of function 'foo' is never used");
+    assert_eq!(warnings[1].message, "Unreachable code after 'return'");
+    assert_eq!(warnings[2].message, "Variable 'a' is never used");
+    assert_eq!(warnings[3].message, "Condition is always true");
+    assert_eq!(warnings[4].message, "Unreachable code after 'break'");
+
+    #[cfg(not(feature = "no_position"))]
+    {
+        let positions = warnings.iter().map(|w| (w.position.line().unwrap(), w.position.position().unwrap())).collect::<Vec<_>>();
+
+        assert_eq!(positions, [(2, 19), (4, 13), (6, 13), (8, 12), (12, 13)]);
+    }
+
+    engine.set_warn_unused_params(false);
+
+    let (_, warnings) = engine.compile_with_warnings(script).unwrap();
+
+    assert_eq!(warnings.len(), 4);
+    assert!(warnings.iter().all(|w| w.kind != ParseWarningKind::UnusedParameter));
+
+    // Warnings are found before optimization
+    let (_, warnings) = engine.compile_with_warnings("let x = 1; while 1 < 2 { x += 1; if x > 5 { break; } } do { x -= 1; } until false;").unwrap();
+
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings.iter().all(|w| w.kind == ParseWarningKind::ConstantCondition));
+    assert_eq!(warnings[0].message, "Condition is a constant expression");
+    assert_eq!(warnings[1].message, "Condition is always false");
+}
+
+#[test]
+fn test_warnings_unused_variables() {
+    let engine = Engine::new();
+
+    let (_, warnings) = engine.compile_with_warnings("let x = 1; x = 2; let y = 0; y += 1; let _z = 42; { let w = 1; } for (v, i) in [1, 2] { print(v); }").unwrap();
+
+    let messages = warnings.iter().map(|w| w.message.as_str()).collect::<Vec<_>>();
+
+    assert_eq!(messages, ["Variable 'x' is never used", "Variable 'w' is never used", "Variable 'i' is never used"]);
+
+    // Shadowed variables
+    let (_, warnings) = engine.compile_with_warnings("let x = 1; let x = x + 1; { let x = 2; } x").unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    #[cfg(not(feature = "no_position"))]
+    assert_eq!(warnings[0].position.position(), Some(33));
+
+    // Variables may be accessed via `eval`
+    let (_, warnings) = engine.compile_with_warnings(r#"let x = 1; eval("x")"#).unwrap();
+
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_warnings_clean() {
+    let engine = Engine::new();
+
+    let (_, warnings) = engine
+        .compile_with_warnings(
+            "
+                let x = 0;
+                let sum = 0;
+
+                while x < 10 {
+                    x += 1;
+                    if x % 2 == 0 { continue; }
+                    sum += x;
+                }
+
+                try {
+                    throw sum;
+                } catch (err) {
+                    print(err);
+                }
+
+                sum
+            ",
+        )
+        .unwrap();
+
+    assert!(warnings.is_empty());
+
+    #[cfg(not(feature = "no_function"))]
+    #[cfg(not(feature = "no_object"))]
+    #[cfg(not(feature = "no_index"))]
+    #[cfg(not(feature = "no_module"))]
+    {
+        let (_, warnings) = engine
+            .compile_with_warnings(
+                "
+                    const SCALE = 10;
+
+                    fn scale(x) { x * global::SCALE }
+
+                    fn inc() { this += 1; }
+
+                    let list = [1, 2, 3];
+                    let factor = 2;
+                    let result = list.map(|v| scale(v) * factor);
+                    result[0].inc();
+
+                    switch result.len() {
+                        0 => throw \"empty\",
+                        _ => result
+                    }
+                ",
+            )
+            .unwrap();
+
+        assert!(warnings.is_empty());
+    }
+}